@@ -0,0 +1,194 @@
+//! A few cubes floating on an animated ocean planet.
+//!
+//! `cargo run --example floating_cubes --features examples`
+//!
+//! Space switches between the plugin's adaptive animation and rebuilding the whole mesh every
+//! frame, the average frame time of both is logged. Scroll to move the camera away and watch
+//! the adaptive updates thin out.
+
+use bevy::prelude::*;
+use ocean::*;
+
+const SEA_LEVEL: f32 = 10.0;
+
+/// How often the average frame time is logged (seconds)
+const REPORT_INTERVAL: f32 = 2.0;
+
+/// How the waves are moved, switched with Space
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum AnimationMode {
+    /// `OceanPlugin` updates the mesh in place, as seldom as the view allows
+    #[default]
+    Adaptive,
+    /// A new mesh is built every frame
+    Rebuild,
+}
+
+/// Frame times since the last report
+#[derive(Resource, Default)]
+struct FrameTimes {
+    total: f32,
+    frames: u32,
+}
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            OceanPlugin {
+                animation: OceanAnimation {
+                    // Nothing is under this ocean, the back is hidden by the front
+                    occluder_radius: Some(SEA_LEVEL),
+                    ..default()
+                },
+            },
+        ))
+        .insert_resource(OceanSurface {
+            config: ocean_config(),
+            ..default()
+        })
+        .init_resource::<AnimationMode>()
+        .init_resource::<FrameTimes>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                switch_mode,
+                zoom_camera,
+                rebuild_ocean.before(animate_ocean).before(float_on_ocean),
+                report_frame_time,
+            ),
+        )
+        .run();
+}
+
+fn ocean_config() -> OceanConfig {
+    OceanConfig {
+        sea_level: SEA_LEVEL,
+        grid_size: 128,
+        wave_amplitude: 0.15,
+        wave_frequency: 0.4,
+        ..default()
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let ocean = OceanMeshBuilder::new(ocean_config()).build().expect("valid ocean config");
+    let ocean_entity = commands
+        .spawn((
+            Mesh3d(meshes.add(ocean.mesh)),
+            MeshMaterial3d(materials.add(ocean.material)),
+            Transform::default(),
+            AnimatedOcean,
+        ))
+        .id();
+
+    // Floating transforms are relative to the ocean center, so the cubes are its children
+    let cube_mesh = meshes.add(Cuboid::new(0.6, 0.6, 0.6));
+    let colors = [Color::srgb(0.9, 0.3, 0.2), Color::srgb(0.9, 0.8, 0.2), Color::srgb(0.3, 0.8, 0.3)];
+    for (i, color) in colors.into_iter().enumerate() {
+        let direction = Vec3::new(0.3 * i as f32 - 0.3, 0.2, 1.0).normalize();
+        let cube = commands
+            .spawn((
+                Mesh3d(cube_mesh.clone()),
+                MeshMaterial3d(materials.add(color)),
+                // Dropped from above the water, they settle on the waves
+                Transform::from_translation(direction * (SEA_LEVEL + 2.0)),
+                Floating {
+                    radius: 0.3,
+                    bob_damping: 3.0 + i as f32 * 3.0,
+                    ..default()
+                },
+            ))
+            .id();
+        commands.entity(ocean_entity).add_child(cube);
+    }
+
+    commands.spawn((
+        DirectionalLight {
+            illuminance: 8000.0,
+            ..default()
+        },
+        Transform::from_xyz(5.0, 10.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 4.0, SEA_LEVEL + 5.0).looking_at(Vec3::Z * SEA_LEVEL, Vec3::Y),
+    ));
+}
+
+fn switch_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut mode: ResMut<AnimationMode>,
+    mut animation: ResMut<OceanAnimation>,
+    mut frame_times: ResMut<FrameTimes>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    *mode = match *mode {
+        AnimationMode::Adaptive => AnimationMode::Rebuild,
+        AnimationMode::Rebuild => AnimationMode::Adaptive,
+    };
+    // The plugin stays out of the way while the example rebuilds the mesh itself
+    animation.paused = *mode == AnimationMode::Rebuild;
+    *frame_times = FrameTimes::default();
+    info!("Animation mode: {:?}", *mode);
+}
+
+fn zoom_camera(
+    mut scroll: MessageReader<bevy::input::mouse::MouseWheel>,
+    mut camera: Query<&mut Transform, With<Camera3d>>,
+) {
+    let delta: f32 = scroll.read().map(|wheel| wheel.y).sum();
+    if delta == 0.0 {
+        return;
+    }
+    for mut transform in camera.iter_mut() {
+        let distance = (transform.translation.length() * (1.0 - delta * 0.1)).clamp(SEA_LEVEL + 1.0, SEA_LEVEL * 60.0);
+        transform.translation = transform.translation.normalize() * distance;
+    }
+}
+
+/// Rebuild the ocean at the current time, the way to animate it without the plugin
+fn rebuild_ocean(
+    time: Res<Time>,
+    mode: Res<AnimationMode>,
+    mut surface: ResMut<OceanSurface>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut ocean: Query<&mut Mesh3d, With<AnimatedOcean>>,
+) {
+    if *mode != AnimationMode::Rebuild {
+        return;
+    }
+    surface.time += time.delta_secs();
+    for mut mesh in ocean.iter_mut() {
+        // Validated by the first build in `setup`
+        let ocean = OceanMeshBuilder::new(surface.config).with_time(surface.time).build_unchecked();
+        mesh.0 = meshes.add(ocean.mesh);
+    }
+}
+
+fn report_frame_time(
+    time: Res<Time>,
+    mode: Res<AnimationMode>,
+    animation: Res<OceanAnimation>,
+    mut frame_times: ResMut<FrameTimes>,
+) {
+    frame_times.total += time.delta_secs();
+    frame_times.frames += 1;
+    if frame_times.total < REPORT_INTERVAL {
+        return;
+    }
+    info!(
+        "{:?}: {:.2} ms per frame, {} attribute writes so far",
+        *mode,
+        frame_times.total * 1000.0 / frame_times.frames as f32,
+        animation.attribute_writes
+    );
+    *frame_times = FrameTimes::default();
+}
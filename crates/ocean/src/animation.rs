@@ -0,0 +1,210 @@
+//! Moves the waves of the rendered ocean, as seldom as the view allows
+
+use crate::OceanMeshBuilder;
+use crate::plugin::OceanSurface;
+use bevy::prelude::*;
+
+/// Marks the ocean mesh `animate_ocean` keeps moving. It has to be a mesh built by
+/// `OceanMeshBuilder` with the config and the latitude range of `OceanSurface`.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct AnimatedOcean;
+
+/// When the ocean mesh is updated. Every skipped update is a mesh that isn't sent to the GPU
+/// again, so the knobs trade smooth waves for frame time.
+#[derive(Resource, Debug, Clone)]
+pub struct OceanAnimation {
+    /// Waves and floating objects stand still
+    pub paused: bool,
+    /// At or below this `wave_amplitude` the sea counts as flat and the mesh isn't touched
+    pub calm_amplitude: f32,
+    /// Camera distance from the ocean center, in sea levels, up to which the waves move
+    /// every frame. Twice as far they move every second frame, and so on.
+    pub full_rate_distance: f32,
+    /// Most frames between two updates, however far the camera
+    pub max_frame_interval: u32,
+    /// Radius of the planet under the ocean. When set, only the vertices the camera can see
+    /// past it are updated, the back of the ocean keeps its last waves.
+    pub occluder_radius: Option<f32>,
+    /// Vertex attributes written so far, for profiling and tests
+    pub attribute_writes: u64,
+}
+
+impl Default for OceanAnimation {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            calm_amplitude: 1e-4,
+            full_rate_distance: 3.0,
+            max_frame_interval: 8,
+            occluder_radius: None,
+            attribute_writes: 0,
+        }
+    }
+}
+
+impl OceanAnimation {
+    /// Frames between two updates with the camera `camera_distance` from an ocean of
+    /// `sea_level`
+    pub fn frame_interval(&self, camera_distance: f32, sea_level: f32) -> u32 {
+        let full_rate = self.full_rate_distance * sea_level;
+        if full_rate <= 0.0 || camera_distance <= full_rate {
+            return 1;
+        }
+        let interval = (camera_distance / full_rate).ceil() as u32;
+        interval.clamp(1, self.max_frame_interval.max(1))
+    }
+}
+
+/// Margin of the visible cap, so the waves at the horizon are updated too
+const HORIZON_MARGIN: f32 = 0.05;
+
+/// Whether the ocean vertex in the direction `up` can be seen from `camera`, both relative to
+/// the ocean center, past a planet of `occluder_radius`
+fn faces_camera(up: Vec3, camera: Vec3, occluder_radius: f32) -> bool {
+    let distance = camera.length();
+    if distance <= occluder_radius {
+        return true;
+    }
+    up.dot(camera / distance) > occluder_radius / distance - HORIZON_MARGIN
+}
+
+/// Moves the `AnimatedOcean` vertices to the waves at the current `OceanSurface` time with
+/// `OceanMeshBuilder::update_vertices`, and advances that time unless paused
+pub fn animate_ocean(
+    time: Res<Time>,
+    mut animation: ResMut<OceanAnimation>,
+    mut surface: ResMut<OceanSurface>,
+    meshes: Option<ResMut<Assets<Mesh>>>,
+    oceans: Query<(&Mesh3d, &GlobalTransform), With<AnimatedOcean>>,
+    cameras: Query<&GlobalTransform, With<Camera>>,
+    mut frame: Local<u64>,
+) {
+    if oceans.is_empty() || animation.paused {
+        return;
+    }
+    surface.time += time.delta_secs();
+    *frame += 1;
+
+    let (Some(mut meshes), Ok(camera)) = (meshes, cameras.single()) else {
+        return;
+    };
+    if surface.config.wave_amplitude <= animation.calm_amplitude {
+        return;
+    }
+
+    let (min_latitude, max_latitude) = surface.latitude_range;
+    let mut builder = OceanMeshBuilder::new(surface.config).with_latitude_range(min_latitude, max_latitude);
+    if let Some(wind) = &surface.wind {
+        builder = builder.with_wind_sampler(Box::new(wind));
+    }
+
+    for (mesh, ocean_transform) in oceans.iter() {
+        let camera = ocean_transform
+            .affine()
+            .inverse()
+            .transform_point3(camera.translation());
+        let interval = animation.frame_interval(camera.length(), surface.config.sea_level);
+        if !frame.is_multiple_of(interval as u64) {
+            continue;
+        }
+        let Some(mesh) = meshes.get_mut(&mesh.0) else {
+            continue;
+        };
+
+        let visible = |up: Vec3| match animation.occluder_radius {
+            Some(radius) => faces_camera(up, camera, radius),
+            None => true,
+        };
+        // A mesh of another grid isn't this ocean's and is left alone. The sea isn't calm here,
+        // so an update writes the positions and the normals.
+        if builder.update_vertices(mesh, surface.time, visible).is_ok() {
+            animation.attribute_writes += 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OceanConfig, OceanMeshBuilder, OceanPlugin};
+    use std::time::Duration;
+
+    /// An app with an animated ocean of `config` and a camera at `camera`
+    fn app_with_ocean(config: OceanConfig, camera: Vec3) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), TransformPlugin, OceanPlugin::default()))
+            .init_asset::<Mesh>()
+            .insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)));
+        app.world_mut().resource_mut::<OceanSurface>().config = config;
+
+        let mesh = OceanMeshBuilder::new(config).build_unchecked().mesh;
+        let mesh = app.world_mut().resource_mut::<Assets<Mesh>>().add(mesh);
+        app.world_mut().spawn((AnimatedOcean, Mesh3d(mesh), Transform::default()));
+        app.world_mut().spawn((Camera::default(), Transform::from_translation(camera)));
+        app
+    }
+
+    fn attribute_writes(app: &mut App, frames: usize) -> u64 {
+        for _ in 0..frames {
+            app.update();
+        }
+        app.world().resource::<OceanAnimation>().attribute_writes
+    }
+
+    #[test]
+    fn test_a_moving_ocean_writes_its_attributes() {
+        let mut app = app_with_ocean(OceanConfig::default(), Vec3::Z * 60.0);
+        assert!(attribute_writes(&mut app, 10) > 0);
+    }
+
+    #[test]
+    fn test_a_paused_ocean_writes_nothing() {
+        let mut app = app_with_ocean(OceanConfig::default(), Vec3::Z * 60.0);
+        app.world_mut().resource_mut::<OceanAnimation>().paused = true;
+
+        assert_eq!(attribute_writes(&mut app, 10), 0);
+        assert_eq!(app.world().resource::<OceanSurface>().time, 0.0);
+    }
+
+    #[test]
+    fn test_a_flat_ocean_writes_nothing() {
+        let config = OceanConfig {
+            wave_amplitude: 0.0,
+            ..default()
+        };
+        let mut app = app_with_ocean(config, Vec3::Z * 60.0);
+
+        assert_eq!(attribute_writes(&mut app, 10), 0);
+    }
+
+    #[test]
+    fn test_a_far_camera_updates_less_often() {
+        let sea_level = OceanConfig::default().sea_level;
+        let mut near = app_with_ocean(OceanConfig::default(), Vec3::Z * sea_level * 2.0);
+        let mut far = app_with_ocean(OceanConfig::default(), Vec3::Z * sea_level * 20.0);
+
+        assert!(attribute_writes(&mut far, 16) < attribute_writes(&mut near, 16));
+    }
+
+    #[test]
+    fn test_frame_interval_grows_with_distance() {
+        let animation = OceanAnimation::default();
+
+        for (distance, expected) in [(50.0, 1), (150.0, 1), (151.0, 2), (400.0, 3), (10_000.0, 8)] {
+            assert_eq!(animation.frame_interval(distance, 50.0), expected, "{distance}");
+        }
+    }
+
+    #[test]
+    fn test_only_the_near_side_faces_the_camera() {
+        let camera = Vec3::Z * 200.0;
+
+        assert!(faces_camera(Vec3::Z, camera, 50.0));
+        // The camera sees down to where its line of sight touches the planet
+        let horizon = Vec3::new((1.0 - 0.25_f32.powi(2)).sqrt(), 0.0, 0.25);
+        assert!(faces_camera(horizon, camera, 50.0), "the horizon stays visible");
+        assert!(!faces_camera(Vec3::X, camera, 50.0));
+        assert!(!faces_camera(-Vec3::Z, camera, 50.0));
+        assert!(faces_camera(-Vec3::Z, Vec3::Z * 10.0, 50.0), "inside the planet");
+    }
+}
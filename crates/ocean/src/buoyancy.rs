@@ -0,0 +1,87 @@
+//! Where the water is around a floating object, from the same height function as the mesh
+
+use crate::{OceanConfig, sample_ocean_height, sample_ocean_height_with_wind, sample_ocean_normal, sample_ocean_normal_with_wind};
+use bevy::math::Vec3;
+
+/// The water around an object, see `sample_water`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaterSample {
+    /// Radius of the water surface above the object (distance from the ocean center)
+    pub surface_height: f32,
+    /// Surface normal of the water above the object
+    pub normal: Vec3,
+    /// Share of the object below the surface, 0 when it's above the water, 1 when it's under
+    pub submerged_fraction: f32,
+}
+
+/// The water around a sphere of `radius` centered at `position` (relative to the ocean center),
+/// with the same waves everywhere. Matches a mesh built without a wind sampler.
+pub fn sample_water(config: &OceanConfig, position: Vec3, radius: f32, time: f32) -> WaterSample {
+    let surface_height = sample_ocean_height(config, position, time);
+    WaterSample {
+        surface_height,
+        normal: sample_ocean_normal(config, position, time),
+        submerged_fraction: submerged_fraction(position.length() - surface_height, radius),
+    }
+}
+
+/// The water around a sphere of `radius` centered at `position` (relative to the ocean center),
+/// with waves shaped by the wind. Matches a mesh built with the same wind sampler.
+pub fn sample_water_with_wind(
+    config: &OceanConfig,
+    position: Vec3,
+    radius: f32,
+    time: f32,
+    wind: impl Fn(Vec3) -> Vec3,
+) -> WaterSample {
+    let surface_height = sample_ocean_height_with_wind(config, position, time, &wind);
+    WaterSample {
+        surface_height,
+        normal: sample_ocean_normal_with_wind(config, position, time, &wind),
+        submerged_fraction: submerged_fraction(position.length() - surface_height, radius),
+    }
+}
+
+/// Volume share of a sphere of `radius` below a flat surface `height` under its center:
+/// the spherical cap of the immersed depth over the whole sphere
+fn submerged_fraction(height: f32, radius: f32) -> f32 {
+    if radius <= 0.0 {
+        return if height < 0.0 { 1.0 } else { 0.0 };
+    }
+    let depth = (radius - height).clamp(0.0, 2.0 * radius);
+    depth * depth * (3.0 * radius - depth) / (4.0 * radius * radius * radius)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submerged_fraction_goes_from_dry_to_sunk() {
+        assert_eq!(submerged_fraction(2.0, 1.0), 0.0);
+        assert_eq!(submerged_fraction(1.0, 1.0), 0.0);
+        assert!((submerged_fraction(0.0, 1.0) - 0.5).abs() < 1e-6);
+        assert_eq!(submerged_fraction(-1.0, 1.0), 1.0);
+        assert_eq!(submerged_fraction(-5.0, 1.0), 1.0);
+        assert!(submerged_fraction(0.5, 1.0) < submerged_fraction(-0.5, 1.0));
+    }
+
+    #[test]
+    fn test_floating_object_sits_on_the_rendered_surface() {
+        let config = OceanConfig::default();
+        let wind = |p: Vec3| Vec3::Y.cross(p).normalize_or_zero() * 8.0;
+        for direction in [Vec3::X, Vec3::new(0.3, 0.8, -0.5).normalize()] {
+            let calm = sample_water(&config, direction * 60.0, 0.5, 1.2);
+            assert_eq!(calm.surface_height, sample_ocean_height(&config, direction, 1.2));
+            assert!(calm.normal.angle_between(sample_ocean_normal(&config, direction, 1.2)) < 1e-5);
+            assert_eq!(calm.submerged_fraction, 0.0);
+
+            let windy = sample_water_with_wind(&config, direction * 40.0, 0.5, 1.2, wind);
+            assert_eq!(windy.surface_height, sample_ocean_height_with_wind(&config, direction, 1.2, wind));
+            assert_eq!(windy.submerged_fraction, 1.0);
+
+            let afloat = sample_water(&config, direction * calm.surface_height, 0.5, 1.2);
+            assert!((afloat.submerged_fraction - 0.5).abs() < 1e-3);
+        }
+    }
+}
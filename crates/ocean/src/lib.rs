@@ -15,15 +15,15 @@
 //!     let config = OceanConfig {
 //!         sea_level: 50.0, // planet_radius + continent_threshold
 //!         grid_size: 64,
-//!         wave_amplitude: 0.1,
-//!         wave_frequency: 2.0,
-//!         wave_speed: 1.0,
-//!         ocean_color: Color::srgb(0.0, 0.4, 0.7),
+//!         ..default()
 //!     };
 //!
+//!     // Optional: tint the water by depth, the sampler returns the terrain radius
 //!     let ocean = OceanMeshBuilder::new(config)
 //!         .with_time(0.0)
-//!         .build();
+//!         .with_height_sampler(Box::new(|position: Vec3| 49.0 + position.x.sin()))
+//!         .build()
+//!         .expect("valid ocean config");
 //!
 //!     commands.spawn((
 //!         Mesh3d(meshes.add(ocean.mesh)),
@@ -31,39 +31,419 @@
 //!     ));
 //! }
 //! ```
+//!
+//! ## Floating objects
+//! `sample_water` tells where the water surface is around an object. Add `OceanPlugin`, keep
+//! `OceanSurface` in sync with the rendered ocean and give entities a `Floating` component to
+//! have them ride the waves, see `examples/floating_cubes.rs`.
+//!
+//! ## Animation
+//! Give the ocean mesh entity `AnimatedOcean` and the plugin moves its waves, updating the
+//! vertices in place. `OceanAnimation` decides how often: not at all for a calm or paused sea,
+//! less often the farther the camera is, and only on the side facing the camera.
+//!
+//! To move or retune the waves of a mesh yourself, keep the builder: `set_config` changes it,
+//! `update_mesh` rewrites the vertices of the mesh it built at a given time and
+//! `needs_rebuild` tells when only `rebuild` can follow the new config.
+
+mod animation;
+mod buoyancy;
+mod plugin;
+
+pub use animation::{AnimatedOcean, OceanAnimation, animate_ocean};
+pub use buoyancy::{WaterSample, sample_water, sample_water_with_wind};
+pub use plugin::{Floating, OceanPlugin, OceanSurface, float_on_ocean};
 
 use bevy::asset::RenderAssetUsages;
-use bevy::color::Color;
+use bevy::color::{Color, ColorToComponents};
 use bevy::math::Vec3;
-use bevy::mesh::{Indices, Mesh, PrimitiveTopology};
+use bevy::mesh::{Indices, Mesh, PrimitiveTopology, VertexAttributeValues};
 use bevy::pbr::StandardMaterial;
 use bevy::prelude::AlphaMode;
+use std::f32::consts::FRAC_PI_2;
 
 /// Configuration for ocean generation - your game provides this
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct OceanConfig {
     /// Sea level - the radius at which the ocean sphere is rendered
     /// Typically: planet_radius + continent_threshold (relative to planet center)
     pub sea_level: f32,
+    /// How much lower the sea level is at the poles than at the equator, in world units.
+    /// Falls off with sin² of the latitude like an oblate planet; typically
+    /// `planet_radius * oblateness` (0 = sphere)
+    pub polar_flattening: f32,
     /// Number of grid subdivisions (higher = more detailed)
     pub grid_size: u32,
-    /// Base ocean color
+    /// Base ocean color, used when no height sampler is provided
     pub ocean_color: Color,
+    /// Water color at the coast, used for depth tinting
+    pub shallow_color: Color,
+    /// Water color over deep terrain, used for depth tinting
+    pub deep_color: Color,
+    /// Depth below sea level at which the water reaches `deep_color`
+    pub max_depth_for_tint: f32,
+    /// Color of the foam band along the coast, blended over the depth tint
+    pub foam_color: Color,
+    /// Width of the foam band as a depth below sea level in world units (0 = no foam)
+    pub foam_width: f32,
+    /// Wave height above/below sea level in calm conditions (0 = flat ocean)
+    pub wave_amplitude: f32,
+    /// Number of wave crests per world unit
+    pub wave_frequency: f32,
+    /// Phase speed of the waves in radians per second
+    pub wave_speed: f32,
+    /// Wind speed at which waves reach `wave_amplitude`, stronger wind makes them higher
+    pub full_wave_wind_speed: f32,
+    /// See-through water over shallow terrain, needs a height sampler (None = opaque water)
+    pub depth_alpha: Option<DepthAlpha>,
+}
+
+/// How see-through the water is by its depth below sea level. Between `near_depth` and
+/// `far_depth` the opacity goes from `min_alpha` to `max_alpha`.
+///
+/// The alpha goes into the vertex colors next to the depth tint and the material switches to
+/// `AlphaMode::Blend`, which is sorted with the other transparent meshes of the scene.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthAlpha {
+    /// Depth up to which the water has `min_alpha`
+    pub near_depth: f32,
+    /// Depth from which the water has `max_alpha`
+    pub far_depth: f32,
+    /// Opacity of the shallowest water
+    pub min_alpha: f32,
+    /// Opacity of deep water
+    pub max_alpha: f32,
+}
+
+impl Default for DepthAlpha {
+    fn default() -> Self {
+        Self {
+            near_depth: 0.0,
+            far_depth: 1.0,
+            min_alpha: 0.3,
+            max_alpha: 0.95,
+        }
+    }
+}
+
+impl DepthAlpha {
+    /// Opacity of water `depth` below sea level
+    pub fn alpha(&self, depth: f32) -> f32 {
+        let span = self.far_depth - self.near_depth;
+        let t = if span > 0.0 {
+            ((depth - self.near_depth) / span).clamp(0.0, 1.0)
+        } else if depth < self.far_depth {
+            0.0
+        } else {
+            1.0
+        };
+        self.min_alpha * (1.0 - t) + self.max_alpha * t
+    }
+}
+
+/// Coarsest grid that still makes a closed sphere, fewer subdivisions collapse it into a line
+pub const MIN_GRID_SIZE: u32 = 3;
+/// Finest grid whose vertex indices fit into `u32`
+pub const MAX_GRID_SIZE: u32 = 65_534;
+
+/// Why an `OceanConfig` can't be built into a mesh
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OceanError {
+    /// `grid_size` outside `MIN_GRID_SIZE..=MAX_GRID_SIZE`
+    GridSize(u32),
+    /// `sea_level` not positive or not finite
+    SeaLevel(f32),
+    /// `wave_amplitude` negative or not finite
+    WaveAmplitude(f32),
+    /// `wave_frequency` not positive or not finite, the wave slopes would be NaN
+    WaveFrequency(f32),
+    /// A color with a NaN or infinite component, named by its field
+    Color(&'static str),
+    /// `with_latitude_range` band that is empty or reaches past a pole
+    LatitudeRange(f32, f32),
+    /// `update_mesh` given a mesh with this many vertices, which isn't the grid of the config
+    MeshTopology(usize),
+}
+
+impl std::fmt::Display for OceanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OceanError::GridSize(size) => {
+                write!(f, "grid_size {size} is outside {MIN_GRID_SIZE}..={MAX_GRID_SIZE}")
+            }
+            OceanError::SeaLevel(level) => write!(f, "sea_level {level} must be positive"),
+            OceanError::WaveAmplitude(amplitude) => {
+                write!(f, "wave_amplitude {amplitude} must not be negative")
+            }
+            OceanError::WaveFrequency(frequency) => {
+                write!(f, "wave_frequency {frequency} must be positive")
+            }
+            OceanError::Color(field) => write!(f, "{field} has a component that isn't finite"),
+            OceanError::LatitudeRange(min, max) => {
+                write!(f, "latitude range {min}..{max} must be a band between -π/2 and π/2")
+            }
+            OceanError::MeshTopology(vertices) => {
+                write!(f, "a mesh with {vertices} vertices isn't the ocean grid of this config")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OceanError {}
+
+impl OceanConfig {
+    /// Check that a mesh can be built from this config, see `OceanError` for what's rejected
+    pub fn validate(&self) -> Result<(), OceanError> {
+        if !(MIN_GRID_SIZE..=MAX_GRID_SIZE).contains(&self.grid_size) {
+            return Err(OceanError::GridSize(self.grid_size));
+        }
+        if !(self.sea_level.is_finite() && self.sea_level > 0.0) {
+            return Err(OceanError::SeaLevel(self.sea_level));
+        }
+        if !(self.wave_amplitude.is_finite() && self.wave_amplitude >= 0.0) {
+            return Err(OceanError::WaveAmplitude(self.wave_amplitude));
+        }
+        if !(self.wave_frequency.is_finite() && self.wave_frequency > 0.0) {
+            return Err(OceanError::WaveFrequency(self.wave_frequency));
+        }
+        for (field, color) in [
+            ("ocean_color", self.ocean_color),
+            ("shallow_color", self.shallow_color),
+            ("deep_color", self.deep_color),
+            ("foam_color", self.foam_color),
+        ] {
+            if !color.to_linear().to_f32_array().iter().all(|component| component.is_finite()) {
+                return Err(OceanError::Color(field));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a mesh of `self` can be turned into one of `other` in place: both only differ in
+    /// how the waves move. Everything else changes the grid, the colors or the material, and
+    /// switching the waves on or off changes which attributes an update writes.
+    pub fn differs_only_in_waves(&self, other: &OceanConfig) -> bool {
+        let without_waves = |config: &OceanConfig| OceanConfig {
+            wave_amplitude: 0.0,
+            wave_frequency: 1.0,
+            wave_speed: 0.0,
+            full_wave_wind_speed: 0.0,
+            ..*config
+        };
+        (self.wave_amplitude > 0.0) == (other.wave_amplitude > 0.0) && without_waves(self) == without_waves(other)
+    }
 }
 
 impl Default for OceanConfig {
     fn default() -> Self {
         Self {
             sea_level: 50.0,
+            polar_flattening: 0.0,
             grid_size: 64,
             ocean_color: Color::srgb(0.0, 0.4, 0.7),
+            shallow_color: Color::srgb(0.1, 0.55, 0.65),
+            deep_color: Color::srgb(0.02, 0.15, 0.35),
+            max_depth_for_tint: 1.0,
+            foam_color: Color::srgb(0.9, 0.95, 1.0),
+            foam_width: 0.0,
+            wave_amplitude: 0.05,
+            wave_frequency: 0.5,
+            wave_speed: 1.0,
+            full_wave_wind_speed: 5.0,
+            depth_alpha: None,
         }
     }
 }
 
 /// Optional terrain height sampler - your game can provide this
-/// Returns height at a given world position
-pub type HeightSampler = Box<dyn Fn(Vec3) -> f32 + Send + Sync>;
+/// Returns the terrain radius (distance from the planet center, same units as `sea_level`)
+/// below a given ocean position
+pub type HeightSampler<'a> = Box<dyn Fn(Vec3) -> f32 + Send + Sync + 'a>;
+
+/// Optional wind sampler - your game can provide this
+/// Returns the wind velocity (tangent to the surface) at a given position
+pub type WindSampler<'a> = Box<dyn Fn(Vec3) -> Vec3 + Send + Sync + 'a>;
+
+/// Waves never grow beyond this multiple of `wave_amplitude`, however strong the wind
+const MAX_WIND_WAVE_FACTOR: f32 = 2.5;
+
+/// Fixed wave travel directions; the local wind picks which of them dominate.
+/// Opposite directions are separate entries because the waves move.
+const WAVE_DIRECTIONS: [Vec3; 18] = {
+    const D: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    [
+        Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z,
+        Vec3::new(D, D, 0.0), Vec3::new(-D, -D, 0.0), Vec3::new(D, -D, 0.0), Vec3::new(-D, D, 0.0),
+        Vec3::new(D, 0.0, D), Vec3::new(-D, 0.0, -D), Vec3::new(D, 0.0, -D), Vec3::new(-D, 0.0, D),
+        Vec3::new(0.0, D, D), Vec3::new(0.0, -D, -D), Vec3::new(0.0, D, -D), Vec3::new(0.0, -D, D),
+    ]
+};
+
+/// Radius of the water surface above `position` at `time`, with the same waves everywhere.
+/// Matches the vertices of a mesh built without a wind sampler.
+pub fn sample_ocean_height(config: &OceanConfig, position: Vec3, time: f32) -> f32 {
+    config.sea_level + water_field(config, position.normalize_or_zero(), time, None).height
+}
+
+/// Radius of the water surface above `position` at `time`, with waves shaped by the wind.
+/// Matches the vertices of a mesh built with the same wind sampler.
+pub fn sample_ocean_height_with_wind(
+    config: &OceanConfig,
+    position: Vec3,
+    time: f32,
+    wind: impl Fn(Vec3) -> Vec3,
+) -> f32 {
+    let normal = position.normalize_or_zero();
+    let wind = wind(normal * sea_radius(config, normal));
+    config.sea_level + water_field(config, normal, time, Some(wind)).height
+}
+
+/// Surface normal of the water above `position` at `time`, with the same waves everywhere.
+/// Matches the normals of a mesh built without a wind sampler.
+pub fn sample_ocean_normal(config: &OceanConfig, position: Vec3, time: f32) -> Vec3 {
+    let up = position.normalize_or_zero();
+    surface_normal(config, up, water_field(config, up, time, None))
+}
+
+/// Surface normal of the water above `position` at `time`, with waves shaped by the wind.
+/// Matches the normals of a mesh built with the same wind sampler.
+pub fn sample_ocean_normal_with_wind(
+    config: &OceanConfig,
+    position: Vec3,
+    time: f32,
+    wind: impl Fn(Vec3) -> Vec3,
+) -> Vec3 {
+    let up = position.normalize_or_zero();
+    let field = water_field(config, up, time, Some(wind(up * sea_radius(config, up))));
+    surface_normal(config, up, field)
+}
+
+/// Radius of the calm water surface in the direction `up`, lowered towards the poles
+/// by `polar_flattening`
+pub fn sea_radius(config: &OceanConfig, up: Vec3) -> f32 {
+    config.sea_level + flattening_field(config, up).height
+}
+
+/// Height of the water above `sea_level` and its slope: the waves on top of the flattening
+fn water_field(config: &OceanConfig, up: Vec3, time: f32, wind: Option<Vec3>) -> WaveSample {
+    let waves = wave_field(config, up, time, wind);
+    let flattening = flattening_field(config, up);
+    WaveSample {
+        height: waves.height + flattening.height,
+        gradient: waves.gradient + flattening.gradient,
+    }
+}
+
+/// The drop of the calm surface towards the poles, `-polar_flattening * sin²(latitude)`.
+/// A step on the sea level sphere turns `up` by the step length over `sea_level`, hence the division.
+fn flattening_field(config: &OceanConfig, up: Vec3) -> WaveSample {
+    if config.polar_flattening == 0.0 || config.sea_level <= 0.0 {
+        return WaveSample::default();
+    }
+    let sin_latitude = up.y;
+    let towards_pole = Vec3::Y - up * sin_latitude;
+    WaveSample {
+        height: -config.polar_flattening * sin_latitude * sin_latitude,
+        gradient: towards_pole * (-2.0 * config.polar_flattening * sin_latitude / config.sea_level),
+    }
+}
+
+/// Wave height and its slope at one point of the water surface
+#[derive(Debug, Clone, Copy, Default)]
+struct WaveSample {
+    /// Height above sea level
+    height: f32,
+    /// Change of the height per world unit along the sea level sphere (tangent to it)
+    gradient: Vec3,
+}
+
+/// Wave height and slope at the surface point with the given `normal`.
+///
+/// The surface is a sum of plane waves along `WAVE_DIRECTIONS`. Without wind all of them
+/// contribute equally; with wind the ones travelling along the wind dominate and the amplitude
+/// scales with the wind speed, so storm belts get high, aligned waves and calm areas stay flat.
+///
+/// Every wave is a closed-form sine of the position, so the slope is its exact derivative.
+/// Wind weights and amplitude change over thousands of wavelengths and count as constant there.
+fn wave_field(config: &OceanConfig, normal: Vec3, time: f32, wind: Option<Vec3>) -> WaveSample {
+    if config.wave_amplitude <= 0.0 || normal == Vec3::ZERO {
+        return WaveSample::default();
+    }
+
+    let (amplitude, wind_direction) = match wind {
+        Some(wind) => {
+            let tangent = wind - normal * wind.dot(normal);
+            let factor = if config.full_wave_wind_speed > 0.0 {
+                (tangent.length() / config.full_wave_wind_speed).min(MAX_WIND_WAVE_FACTOR)
+            } else {
+                1.0
+            };
+            (config.wave_amplitude * factor, tangent.try_normalize())
+        }
+        None => (config.wave_amplitude, None),
+    };
+    if amplitude <= 0.0 {
+        return WaveSample::default();
+    }
+
+    let wavenumber = config.wave_frequency * std::f32::consts::TAU;
+    let surface = normal * config.sea_level;
+    let mut height = 0.0;
+    let mut gradient = Vec3::ZERO;
+    let mut total_weight = 0.0;
+    for direction in WAVE_DIRECTIONS {
+        // Tangential part of the direction: how far along it a step on the surface moves
+        let along_surface = direction - normal * direction.dot(normal);
+        let weight = match wind_direction {
+            // Favour waves that run with the wind
+            Some(wind_direction) => along_surface.dot(wind_direction).max(0.0).powi(4),
+            None => 1.0,
+        };
+        if weight <= 0.0 {
+            continue;
+        }
+        let phase = surface.dot(direction) * wavenumber - time * config.wave_speed;
+        height += weight * phase.sin();
+        gradient += along_surface * (weight * wavenumber * phase.cos());
+        total_weight += weight;
+    }
+
+    if total_weight > 0.0 {
+        let scale = amplitude / total_weight;
+        WaveSample {
+            height: height * scale,
+            gradient: gradient * scale,
+        }
+    } else {
+        WaveSample::default()
+    }
+}
+
+/// Normal of the surface `up * (sea_level + height)` from the wave slope.
+/// A step on the displaced surface is `(sea_level + height) / sea_level` times longer than on
+/// the sea level sphere, which flattens the slope by the same factor.
+fn surface_normal(config: &OceanConfig, up: Vec3, wave: WaveSample) -> Vec3 {
+    if up == Vec3::ZERO {
+        return Vec3::ZERO;
+    }
+    let radius = config.sea_level + wave.height;
+    if radius <= 0.0 {
+        return up;
+    }
+    (up - wave.gradient * (config.sea_level / radius)).normalize()
+}
+
+/// Position and normal of the ocean vertex in the direction `up` at `time`
+fn displaced_vertex(
+    config: &OceanConfig,
+    up: Vec3,
+    time: f32,
+    wind: Option<&(dyn Fn(Vec3) -> Vec3 + Send + Sync)>,
+) -> (Vec3, Vec3) {
+    let wind = wind.map(|sampler| sampler(up * sea_radius(config, up)));
+    let water = water_field(config, up, time, wind);
+    (up * (config.sea_level + water.height), surface_normal(config, up, water))
+}
 
 /// The output of ocean generation - ready to render
 pub struct OceanOutput {
@@ -72,19 +452,27 @@ pub struct OceanOutput {
 }
 
 /// Builder for creating ocean meshes from external state
-pub struct OceanMeshBuilder {
+pub struct OceanMeshBuilder<'a> {
     config: OceanConfig,
     time: f32,
-    height_sampler: Option<HeightSampler>,
+    height_sampler: Option<HeightSampler<'a>>,
+    wind_sampler: Option<WindSampler<'a>>,
+    /// Southern and northern latitude of the band the mesh covers, in radians
+    latitude_range: (f32, f32),
+    /// `set_config` changed more than the waves since the last build
+    needs_rebuild: bool,
 }
 
-impl OceanMeshBuilder {
+impl<'a> OceanMeshBuilder<'a> {
     /// Create a new ocean mesh builder with the given configuration
     pub fn new(config: OceanConfig) -> Self {
         Self {
             config,
             time: 0.0,
             height_sampler: None,
+            wind_sampler: None,
+            latitude_range: (-FRAC_PI_2, FRAC_PI_2),
+            needs_rebuild: false,
         }
     }
 
@@ -94,51 +482,184 @@ impl OceanMeshBuilder {
         self
     }
 
-    /// Set a terrain height sampler for wave interaction with terrain
-    pub fn with_height_sampler(mut self, sampler: HeightSampler) -> Self {
+    /// Set a terrain height sampler. The water is then tinted per vertex from
+    /// `shallow_color` at the coast to `deep_color` over deep terrain, with a
+    /// `foam_color` band along the coast if `foam_width` is positive. With `depth_alpha`
+    /// the shallow water also lets the terrain below show through.
+    pub fn with_height_sampler(mut self, sampler: HeightSampler<'a>) -> Self {
         self.height_sampler = Some(sampler);
         self
     }
 
-    /// Build the ocean mesh and material
-    pub fn build(self) -> OceanOutput {
+    /// Set a wind sampler. Wave height then follows the local wind speed and the waves
+    /// run along the wind, see `sample_ocean_height_with_wind`.
+    pub fn with_wind_sampler(mut self, sampler: WindSampler<'a>) -> Self {
+        self.wind_sampler = Some(sampler);
+        self
+    }
+
+    /// Only build the band of the sphere between two latitudes in radians, -π/2 being the south
+    /// pole. The mesh ends in an open ring at each cut, e.g. where an ice cap covers the water.
+    /// Only the mesh is cut, `sample_ocean_height` still works for any direction.
+    pub fn with_latitude_range(mut self, min_latitude: f32, max_latitude: f32) -> Self {
+        self.latitude_range = (min_latitude, max_latitude);
+        self
+    }
+
+    /// Replace the config. A mesh built before can follow changes to the waves with
+    /// `update_mesh`, anything else sets `needs_rebuild`.
+    pub fn set_config(&mut self, config: OceanConfig) {
+        if !self.config.differs_only_in_waves(&config) {
+            self.needs_rebuild = true;
+        }
+        self.config = config;
+    }
+
+    /// Whether the config changed in a way `update_mesh` can't bring a mesh built before up
+    /// to, that mesh has to be replaced with one from `rebuild`
+    pub fn needs_rebuild(&self) -> bool {
+        self.needs_rebuild
+    }
+
+    /// Build the ocean mesh and material, or tell why the config can't make one
+    pub fn build(mut self) -> Result<OceanOutput, OceanError> {
+        self.rebuild()
+    }
+
+    /// Build the ocean mesh and material and keep the builder for updating them later.
+    /// Clears `needs_rebuild`.
+    pub fn rebuild(&mut self) -> Result<OceanOutput, OceanError> {
+        self.validate()?;
+        self.needs_rebuild = false;
+        Ok(OceanOutput {
+            mesh: self.generate_mesh(),
+            material: self.generate_material(),
+        })
+    }
+
+    /// Move the vertices of `mesh`, built by this builder, to the waves at `time`. Only the
+    /// positions are rewritten, and the normals when there are waves; the indices, UVs and
+    /// colors stay. The result is the mesh a build at `time` makes, as long as
+    /// `needs_rebuild` is false.
+    ///
+    /// Fails when the config is invalid or `mesh` isn't the grid of the config.
+    pub fn update_mesh(&self, mesh: &mut Mesh, time: f32) -> Result<(), OceanError> {
+        self.update_vertices(mesh, time, |_| true)
+    }
+
+    /// `update_mesh` for the vertices in the directions `update` accepts, the others keep the
+    /// waves they have
+    pub fn update_vertices(
+        &self,
+        mesh: &mut Mesh,
+        time: f32,
+        update: impl Fn(Vec3) -> bool,
+    ) -> Result<(), OceanError> {
+        self.validate()?;
+        let size = self.config.grid_size;
+        let vertices = mesh.count_vertices();
+        let row = size as usize + 1;
+        let index_count = mesh.indices().map_or(0, Indices::len);
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList
+            || vertices != row * row
+            || index_count != 6 * (row - 1) * (row - 1)
+        {
+            return Err(OceanError::MeshTopology(vertices));
+        }
+
+        let displaced: Vec<Option<(Vec3, Vec3)>> = (0..=size)
+            .flat_map(|y| (0..=size).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (up, _) = self.grid_vertex(x, y);
+                update(up).then(|| displaced_vertex(&self.config, up, time, self.wind_sampler.as_deref()))
+            })
+            .collect();
+
+        if let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION) {
+            for (position, vertex) in positions.iter_mut().zip(&displaced) {
+                if let Some((displaced, _)) = vertex {
+                    *position = displaced.to_array();
+                }
+            }
+        }
+        // Without waves the normals don't change with time
+        if self.config.wave_amplitude > 0.0
+            && let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+        {
+            for (normal, vertex) in normals.iter_mut().zip(&displaced) {
+                if let Some((_, displaced)) = vertex {
+                    *normal = displaced.to_array();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), OceanError> {
+        self.config.validate()?;
+        let (min_latitude, max_latitude) = self.latitude_range;
+        if !(-FRAC_PI_2 <= min_latitude && min_latitude < max_latitude && max_latitude <= FRAC_PI_2) {
+            return Err(OceanError::LatitudeRange(min_latitude, max_latitude));
+        }
+        Ok(())
+    }
+
+    /// Build the ocean mesh and material from a config that already passed
+    /// `OceanConfig::validate`. Panics or makes a broken mesh otherwise.
+    pub fn build_unchecked(self) -> OceanOutput {
         let mesh = self.generate_mesh();
         let material = self.generate_material();
 
         OceanOutput { mesh, material }
     }
 
+    /// Direction of the grid vertex in column `x` and row `y` from the center, and its UV
+    fn grid_vertex(&self, x: u32, y: u32) -> (Vec3, [f32; 2]) {
+        let size = self.config.grid_size;
+        // Rows run from the northern to the southern edge of the band, the full range keeps
+        // them at 0 to π exactly
+        let (min_latitude, max_latitude) = self.latitude_range;
+        let first_phi = FRAC_PI_2 - max_latitude;
+        let phi_span = max_latitude - min_latitude;
+
+        let u = x as f32 / size as f32;
+        let v = y as f32 / size as f32;
+
+        // Spherical coordinates
+        let theta = u * std::f32::consts::TAU; // longitude (0 to 2π)
+        let phi = first_phi + v * phi_span;    // colatitude (0 to π)
+
+        let sin_phi = phi.sin();
+        let cos_phi = phi.cos();
+        let sin_theta = theta.sin();
+        let cos_theta = theta.cos();
+
+        (Vec3::new(sin_phi * cos_theta, cos_phi, sin_phi * sin_theta), [u, v])
+    }
+
     fn generate_mesh(&self) -> Mesh {
         let size = self.config.grid_size;
-        let radius = self.config.sea_level;
 
         let mut positions = Vec::new();
         let mut normals = Vec::new();
         let mut uvs = Vec::new();
+        let mut colors = Vec::new();
         let mut indices = Vec::new();
 
         // Generate UV sphere - avoid seams by not duplicating vertices at poles/edges
         for y in 0..=size {
             for x in 0..=size {
-                let u = x as f32 / size as f32;
-                let v = y as f32 / size as f32;
-
-                // Spherical coordinates
-                let theta = u * std::f32::consts::TAU; // longitude (0 to 2π)
-                let phi = v * std::f32::consts::PI;    // latitude (0 to π)
-
-                let sin_phi = phi.sin();
-                let cos_phi = phi.cos();
-                let sin_theta = theta.sin();
-                let cos_theta = theta.cos();
-
-                let x_pos = radius * sin_phi * cos_theta;
-                let y_pos = radius * cos_phi;
-                let z_pos = radius * sin_phi * sin_theta;
+                let (up, uv) = self.grid_vertex(x, y);
+                let (position, normal) = displaced_vertex(&self.config, up, self.time, self.wind_sampler.as_deref());
+                positions.push(position.to_array());
+                normals.push(normal.to_array());
+                uvs.push(uv);
 
-                positions.push([x_pos, y_pos, z_pos]);
-                normals.push([sin_phi * cos_theta, cos_phi, sin_phi * sin_theta]);
-                uvs.push([u, v]);
+                if let Some(sampler) = &self.height_sampler {
+                    let sea_radius = sea_radius(&self.config, up);
+                    let depth = sea_radius - sampler(up * sea_radius);
+                    colors.push(self.depth_tint(depth));
+                }
             }
         }
 
@@ -165,23 +686,77 @@ impl OceanMeshBuilder {
         mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
         mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
         mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        if !colors.is_empty() {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
         mesh.insert_indices(Indices::U32(indices));
 
         mesh
     }
+
+    /// Linear RGBA vertex color for water of the given depth below sea level. The tint and the
+    /// foam go into RGB; with `depth_alpha` its opacity goes into A, where foam stays opaque.
+    fn depth_tint(&self, depth: f32) -> [f32; 4] {
+        let mut color = self.depth_color(depth);
+        if let Some(depth_alpha) = &self.config.depth_alpha {
+            color[3] = depth_alpha.alpha(depth).max(self.foam(depth)).clamp(0.0, 1.0);
+        }
+        color
+    }
+
+    /// Full foam at the waterline fading out towards the inner edge of the band, 0 without foam
+    fn foam(&self, depth: f32) -> f32 {
+        if self.config.foam_width <= 0.0 {
+            return 0.0;
+        }
+        1.0 - (depth / self.config.foam_width).clamp(0.0, 1.0)
+    }
+
+    fn depth_color(&self, depth: f32) -> [f32; 4] {
+        let t = if self.config.max_depth_for_tint > 0.0 {
+            (depth / self.config.max_depth_for_tint).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let shallow = self.config.shallow_color.to_linear().to_f32_array();
+        let deep = self.config.deep_color.to_linear().to_f32_array();
+        let tint = lerp_color(shallow, deep, t);
+
+        if self.config.foam_width <= 0.0 {
+            return tint;
+        }
+        lerp_color(tint, self.config.foam_color.to_linear().to_f32_array(), self.foam(depth))
+    }
+}
+
+fn lerp_color(from: [f32; 4], to: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| from[i] * (1.0 - t) + to[i] * t)
 }
 
-impl OceanMeshBuilder {
+impl OceanMeshBuilder<'_> {
     fn generate_material(&self) -> StandardMaterial {
+        // Vertex colors are multiplied with the base color, keep it white so the tint shows as is
+        let base_color = if self.height_sampler.is_some() {
+            Color::WHITE
+        } else {
+            self.config.ocean_color
+        };
+        // Vertex alpha only shows with blending, and only exists with a height sampler
+        let depth_alpha = self.height_sampler.is_some() && self.config.depth_alpha.is_some();
+        let alpha_mode = if depth_alpha {
+            AlphaMode::Blend
+        } else {
+            // AlphaMode::Blend causes flickering with Hanabi particles due to Bevy's
+            // transparent-pass sorting (order-dependent transparency).
+            AlphaMode::Opaque
+        };
+
         StandardMaterial {
-            base_color: self.config.ocean_color,
+            base_color,
             metallic: 0.0,
             perceptual_roughness: 0.1,
             reflectance: 0.8,
-            // AlphaMode::Blend causes flickering with Hanabi particles due to Bevy's
-            // transparent-pass sorting (order-dependent transparency).
-            // alpha_mode: AlphaMode::Blend,
-            alpha_mode: AlphaMode::Opaque,
+            alpha_mode,
             unlit: false,
             double_sided: false,
             cull_mode: None,
@@ -189,3 +764,603 @@ impl OceanMeshBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::mesh::VertexAttributeValues;
+
+    fn tinted_colors(terrain_radius: f32) -> Vec<[f32; 4]> {
+        tinted_colors_with(
+            OceanConfig {
+                sea_level: 50.0,
+                grid_size: 8,
+                ..Default::default()
+            },
+            terrain_radius,
+        )
+    }
+
+    fn tinted_colors_with(config: OceanConfig, terrain_radius: f32) -> Vec<[f32; 4]> {
+        let ocean = OceanMeshBuilder::new(config)
+            .with_height_sampler(Box::new(move |_| terrain_radius))
+            .build()
+            .unwrap();
+
+        match ocean.mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => colors.clone(),
+            _ => panic!("ocean mesh has no vertex colors"),
+        }
+    }
+
+    #[test]
+    fn test_very_deep_water_is_deep_color() {
+        let expected = OceanConfig::default().deep_color.to_linear().to_f32_array();
+
+        assert!(tinted_colors(10.0).iter().all(|color| *color == expected));
+    }
+
+    #[test]
+    fn test_water_at_the_coast_is_shallow_color() {
+        let expected = OceanConfig::default().shallow_color.to_linear().to_f32_array();
+
+        assert!(tinted_colors(50.0).iter().all(|color| *color == expected));
+    }
+
+    #[test]
+    fn test_foam_covers_the_coast_and_stops_at_the_band_edge() {
+        let config = OceanConfig {
+            sea_level: 50.0,
+            grid_size: 8,
+            foam_width: 0.1,
+            ..Default::default()
+        };
+        let foam = config.foam_color.to_linear().to_f32_array();
+        let shallow = config.shallow_color.to_linear().to_f32_array();
+
+        assert!(tinted_colors_with(config, 50.0).iter().all(|color| *color == foam));
+
+        let beyond_band = tinted_colors_with(config, 49.8);
+        assert!(beyond_band.iter().all(|color| *color != foam && *color != shallow));
+        let deep = config.deep_color.to_linear().to_f32_array();
+        assert!(tinted_colors_with(config, 10.0).iter().all(|color| *color == deep));
+    }
+
+    #[test]
+    fn test_depth_alpha_fades_from_shallow_to_deep_and_keeps_the_tint() {
+        let depth_alpha = DepthAlpha {
+            near_depth: 0.5,
+            far_depth: 2.5,
+            min_alpha: 0.2,
+            max_alpha: 0.9,
+        };
+        let config = OceanConfig {
+            sea_level: 50.0,
+            grid_size: 8,
+            depth_alpha: Some(depth_alpha),
+            ..Default::default()
+        };
+        let alphas = |terrain_radius: f32| -> Vec<f32> {
+            tinted_colors_with(config, terrain_radius).iter().map(|color| color[3]).collect()
+        };
+
+        assert!(alphas(49.8).iter().all(|&alpha| alpha == 0.2));
+        assert!(alphas(48.5).iter().all(|&alpha| (alpha - 0.55).abs() < 1e-5));
+        assert!(alphas(40.0).iter().all(|&alpha| alpha == 0.9));
+
+        // The RGB stays the depth tint
+        let deep = config.deep_color.to_linear().to_f32_array();
+        assert!(tinted_colors_with(config, 40.0).iter().all(|color| color[..3] == deep[..3]));
+    }
+
+    #[test]
+    fn test_foam_stays_opaque_over_see_through_water() {
+        let config = OceanConfig {
+            sea_level: 50.0,
+            grid_size: 8,
+            foam_width: 0.1,
+            depth_alpha: Some(DepthAlpha::default()),
+            ..Default::default()
+        };
+
+        assert!(tinted_colors_with(config, 50.0).iter().all(|color| color[3] == 1.0));
+    }
+
+    #[test]
+    fn test_only_depth_alpha_with_a_height_sampler_blends() {
+        let config = OceanConfig {
+            depth_alpha: Some(DepthAlpha::default()),
+            ..Default::default()
+        };
+        let blended = OceanMeshBuilder::new(config)
+            .with_height_sampler(Box::new(|_| 0.0))
+            .build()
+            .unwrap();
+        let no_sampler = OceanMeshBuilder::new(config).build().unwrap();
+        let no_depth_alpha = OceanMeshBuilder::new(OceanConfig::default())
+            .with_height_sampler(Box::new(|_| 0.0))
+            .build()
+            .unwrap();
+
+        assert_eq!(blended.material.alpha_mode, AlphaMode::Blend);
+        assert_eq!(no_sampler.material.alpha_mode, AlphaMode::Opaque);
+        assert_eq!(no_depth_alpha.material.alpha_mode, AlphaMode::Opaque);
+    }
+
+    fn wave_heights(config: &OceanConfig, wind: Vec3, positions: &[Vec3]) -> Vec<f32> {
+        positions
+            .iter()
+            .map(|&p| sample_ocean_height_with_wind(config, p, 0.0, |_| wind) - config.sea_level)
+            .collect()
+    }
+
+    #[test]
+    fn test_strong_wind_makes_choppier_water_than_calm() {
+        let config = OceanConfig::default();
+        // A patch of the surface around +X, wind blowing along +Z there
+        let patch: Vec<Vec3> = (0..200)
+            .map(|i| Vec3::new(1.0, (i % 20) as f32 * 0.01, (i / 20) as f32 * 0.01).normalize())
+            .collect();
+
+        let spread = |heights: Vec<f32>| heights.iter().fold(0.0f32, |max, h| max.max(h.abs()));
+        let storm = spread(wave_heights(&config, Vec3::Z * 10.0, &patch));
+        let doldrums = spread(wave_heights(&config, Vec3::Z * 0.5, &patch));
+
+        assert!(storm > doldrums * 5.0, "storm {storm}, doldrums {doldrums}");
+        assert!(storm <= config.wave_amplitude * MAX_WIND_WAVE_FACTOR + 1e-5);
+    }
+
+    #[test]
+    fn test_waves_run_along_the_wind() {
+        let config = OceanConfig::default();
+        let origin = Vec3::X * config.sea_level;
+        let wind = Vec3::Z * 5.0;
+        // Sample along the wind and across it over one wavelength
+        let line = |dir: Vec3| -> Vec<Vec3> {
+            (0..40).map(|i| origin + dir * (i as f32 * 0.05 / config.wave_frequency)).collect()
+        };
+        let variation = |heights: Vec<f32>| {
+            heights.windows(2).map(|pair| (pair[1] - pair[0]).abs()).sum::<f32>()
+        };
+
+        let along = variation(wave_heights(&config, wind, &line(Vec3::Z)));
+        let across = variation(wave_heights(&config, wind, &line(Vec3::Y)));
+
+        assert!(along > across * 3.0, "along {along}, across {across}");
+    }
+
+    #[test]
+    fn test_mesh_vertices_match_sampled_ocean_height() {
+        let config = OceanConfig {
+            grid_size: 16,
+            ..Default::default()
+        };
+        let wind = |p: Vec3| Vec3::Y.cross(p).normalize_or_zero() * 8.0;
+        let ocean = OceanMeshBuilder::new(config)
+            .with_time(1.5)
+            .with_wind_sampler(Box::new(wind))
+            .build()
+            .unwrap();
+
+        let Some(VertexAttributeValues::Float32x3(positions)) = ocean.mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("ocean mesh has no positions");
+        };
+        for &position in positions {
+            let position = Vec3::from(position);
+            let expected = sample_ocean_height_with_wind(&config, position, 1.5, wind);
+            assert!((position.length() - expected).abs() < 1e-3);
+        }
+    }
+
+    /// Normal from the actual water surface: central differences of `surface_at` around `up`,
+    /// with a step of 2% of a wavelength: small enough for the curvature of the waves, large
+    /// enough that f32 rounding of the wave phase doesn't swamp the difference
+    fn surface_normal_by_differences(config: &OceanConfig, up: Vec3, surface_at: impl Fn(Vec3) -> Vec3) -> Vec3 {
+        let east = up.any_orthonormal_vector();
+        let north = up.cross(east);
+        let angle = 0.02 / config.wave_frequency / config.sea_level;
+        let along_east = surface_at(up + east * angle) - surface_at(up - east * angle);
+        let along_north = surface_at(up + north * angle) - surface_at(up - north * angle);
+        along_east.cross(along_north).normalize()
+    }
+
+    fn test_directions() -> Vec<Vec3> {
+        vec![
+            Vec3::new(1.0, 0.3, -0.2).normalize(),
+            Vec3::new(-0.4, -0.8, 0.5).normalize(),
+            Vec3::new(0.1, 0.2, 1.0).normalize(),
+            Vec3::new(0.7, -0.1, 0.7).normalize(),
+        ]
+    }
+
+    #[test]
+    fn test_analytic_normal_matches_the_surface() {
+        let radii_and_frequencies = [10.0, 50.0, 200.0]
+            .into_iter()
+            .flat_map(|sea_level| [0.5, 5.0].map(|wave_frequency| (sea_level, wave_frequency)));
+        for (sea_level, wave_frequency) in radii_and_frequencies {
+            let config = OceanConfig {
+                sea_level,
+                wave_frequency,
+                ..Default::default()
+            };
+            for up in test_directions() {
+                let surface_at = |dir: Vec3| dir.normalize() * sample_ocean_height(&config, dir, 0.7);
+                let expected = surface_normal_by_differences(&config, up, surface_at);
+                let normal = sample_ocean_normal(&config, up * 3.0, 0.7);
+
+                assert!((normal.length() - 1.0).abs() < 1e-4);
+                assert!(
+                    normal.angle_between(expected) < 0.01,
+                    "{normal} vs {expected} at {up}, radius {sea_level}, frequency {wave_frequency}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_analytic_normal_with_wind_matches_the_surface() {
+        let config = OceanConfig::default();
+        // Uniform wind, so only the waves change from point to point
+        let wind = |_: Vec3| Vec3::new(3.0, 0.0, 6.0);
+        for up in test_directions() {
+            let surface_at = |dir: Vec3| {
+                dir.normalize() * sample_ocean_height_with_wind(&config, dir, 0.0, wind)
+            };
+            let expected = surface_normal_by_differences(&config, up, surface_at);
+            let normal = sample_ocean_normal_with_wind(&config, up, 0.0, wind);
+
+            // The wave weights follow the tangent plane, which the analytic slope ignores
+            assert!(normal.angle_between(expected) < 0.02, "{normal} vs {expected} at {up}");
+        }
+    }
+
+    #[test]
+    fn test_flattened_sea_is_lower_at_the_poles_with_matching_normals() {
+        let config = OceanConfig {
+            polar_flattening: 5.0,
+            ..Default::default()
+        };
+        let calm = OceanConfig {
+            wave_amplitude: 0.0,
+            ..config
+        };
+        assert!((sample_ocean_height(&calm, Vec3::Y, 0.0) - 45.0).abs() < 1e-4);
+        assert!((sample_ocean_height(&calm, Vec3::NEG_Y, 0.0) - 45.0).abs() < 1e-4);
+        assert!((sample_ocean_height(&calm, Vec3::X, 0.0) - 50.0).abs() < 1e-4);
+
+        for up in test_directions() {
+            assert!((sample_ocean_height(&calm, up, 0.0) - sea_radius(&calm, up)).abs() < 1e-4);
+
+            let surface_at = |dir: Vec3| dir.normalize() * sample_ocean_height(&config, dir, 0.7);
+            let expected = surface_normal_by_differences(&config, up, surface_at);
+            let normal = sample_ocean_normal(&config, up, 0.7);
+            assert!(normal.angle_between(expected) < 0.01, "{normal} vs {expected} at {up}");
+        }
+    }
+
+    #[test]
+    fn test_normal_is_well_defined_at_the_poles() {
+        let config = OceanConfig {
+            wave_frequency: 5.0,
+            ..Default::default()
+        };
+        for pole in [Vec3::Y, Vec3::NEG_Y] {
+            let normal = sample_ocean_normal(&config, pole * config.sea_level, 0.3);
+            assert!(normal.is_finite() && (normal.length() - 1.0).abs() < 1e-4);
+
+            // Approaching the pole from any longitude ends at the same normal
+            for longitude in [0.0f32, 1.3, 2.9, 4.4] {
+                let near = (pole + Vec3::new(longitude.cos(), 0.0, longitude.sin()) * 1e-6).normalize();
+                let nearby = sample_ocean_normal(&config, near, 0.3);
+                assert!(normal.angle_between(nearby) < 1e-3);
+            }
+        }
+
+        assert_eq!(sample_ocean_normal(&config, Vec3::ZERO, 0.0), Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_mesh_normals_match_sampled_ocean_normal() {
+        let config = OceanConfig {
+            grid_size: 16,
+            ..Default::default()
+        };
+        let ocean = OceanMeshBuilder::new(config).with_time(2.0).build().unwrap();
+
+        let Some(VertexAttributeValues::Float32x3(positions)) = ocean.mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("ocean mesh has no positions");
+        };
+        let Some(VertexAttributeValues::Float32x3(normals)) = ocean.mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("ocean mesh has no normals");
+        };
+        for (&position, &normal) in positions.iter().zip(normals) {
+            let expected = sample_ocean_normal(&config, Vec3::from(position), 2.0);
+            assert!(Vec3::from(normal).distance(expected) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_without_sampler_the_ocean_uses_a_single_color() {
+        let ocean = OceanMeshBuilder::new(OceanConfig::default()).build().unwrap();
+
+        assert!(ocean.mesh.attribute(Mesh::ATTRIBUTE_COLOR).is_none());
+        assert_eq!(ocean.material.base_color, OceanConfig::default().ocean_color);
+    }
+
+    fn build_error(config: OceanConfig) -> OceanError {
+        match OceanMeshBuilder::new(config).build() {
+            Ok(_) => panic!("built an ocean from an invalid config"),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert_eq!(OceanConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_grid_too_coarse_or_too_fine_is_rejected() {
+        for grid_size in [0, 1, MIN_GRID_SIZE - 1, MAX_GRID_SIZE + 1] {
+            let config = OceanConfig {
+                grid_size,
+                ..Default::default()
+            };
+            assert_eq!(build_error(config), OceanError::GridSize(grid_size));
+        }
+
+        let coarsest = OceanConfig {
+            grid_size: MIN_GRID_SIZE,
+            ..Default::default()
+        };
+        let ocean = OceanMeshBuilder::new(coarsest).build().unwrap();
+        assert!(ocean.mesh.indices().is_some_and(|indices| !indices.is_empty()));
+    }
+
+    #[test]
+    fn test_sea_level_must_be_positive() {
+        for sea_level in [0.0, -10.0, f32::NAN, f32::INFINITY] {
+            let config = OceanConfig {
+                sea_level,
+                ..Default::default()
+            };
+            assert!(matches!(build_error(config), OceanError::SeaLevel(_)));
+        }
+    }
+
+    #[test]
+    fn test_negative_wave_amplitude_is_rejected() {
+        for wave_amplitude in [-0.1, f32::NAN] {
+            let config = OceanConfig {
+                wave_amplitude,
+                ..Default::default()
+            };
+            assert!(matches!(build_error(config), OceanError::WaveAmplitude(_)));
+        }
+
+        // A flat ocean is fine
+        let flat = OceanConfig {
+            wave_amplitude: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(flat.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_wave_frequency_must_be_positive() {
+        for wave_frequency in [0.0, -0.5, f32::NAN] {
+            let config = OceanConfig {
+                wave_frequency,
+                ..Default::default()
+            };
+            assert!(matches!(build_error(config), OceanError::WaveFrequency(_)));
+        }
+    }
+
+    #[test]
+    fn test_colors_must_be_finite() {
+        let broken = Color::srgb(f32::NAN, 0.5, 0.5);
+        let default = OceanConfig::default();
+        let cases = [
+            ("ocean_color", OceanConfig { ocean_color: broken, ..default }),
+            ("shallow_color", OceanConfig { shallow_color: broken, ..default }),
+            ("deep_color", OceanConfig { deep_color: broken, ..default }),
+            ("foam_color", OceanConfig { foam_color: Color::srgb(0.9, f32::INFINITY, 1.0), ..default }),
+        ];
+        for (field, config) in cases {
+            assert_eq!(build_error(config), OceanError::Color(field));
+        }
+    }
+
+    /// Every vertex attribute and the indices of an ocean mesh, for comparing two meshes
+    #[allow(clippy::type_complexity)]
+    fn mesh_layout(
+        mesh: &Mesh,
+    ) -> (Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<[f32; 2]>, Vec<[f32; 4]>, Vec<usize>) {
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("ocean mesh has no positions");
+        };
+        let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("ocean mesh has no normals");
+        };
+        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0) else {
+            panic!("ocean mesh has no uvs");
+        };
+        let colors = match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => colors.clone(),
+            _ => Vec::new(),
+        };
+        let indices = mesh.indices().expect("ocean mesh has indices").iter().collect();
+        (positions.clone(), normals.clone(), uvs.clone(), colors, indices)
+    }
+
+    #[test]
+    fn test_full_latitude_range_is_the_whole_sphere() {
+        let config = OceanConfig {
+            sea_level: 50.0,
+            grid_size: 8,
+            ..Default::default()
+        };
+        let build = |builder: OceanMeshBuilder| {
+            builder
+                .with_time(1.5)
+                .with_height_sampler(Box::new(|position: Vec3| 49.5 + position.x * 0.02))
+                .build()
+                .unwrap()
+        };
+        let whole = build(OceanMeshBuilder::new(config));
+        let full_range = build(OceanMeshBuilder::new(config).with_latitude_range(-FRAC_PI_2, FRAC_PI_2));
+
+        assert_eq!(mesh_layout(&full_range.mesh), mesh_layout(&whole.mesh));
+    }
+
+    #[test]
+    fn test_latitude_band_stops_at_its_edges() {
+        let config = OceanConfig {
+            sea_level: 50.0,
+            grid_size: 16,
+            ..Default::default()
+        };
+        let edge = 60f32.to_radians();
+        let band = OceanMeshBuilder::new(config)
+            .with_latitude_range(-edge, edge)
+            .with_height_sampler(Box::new(|_: Vec3| 49.0))
+            .build()
+            .unwrap();
+        let (positions, _, uvs, colors, indices) = mesh_layout(&band.mesh);
+
+        let latitudes: Vec<f32> = positions
+            .iter()
+            .map(|&position| Vec3::from(position).normalize().y.asin())
+            .collect();
+        for &latitude in &latitudes {
+            assert!(latitude.abs() <= edge + 1e-4, "vertex at {} degrees", latitude.to_degrees());
+        }
+        // The first and last rows are the rings at the cut lines
+        let row = config.grid_size as usize + 1;
+        for &latitude in latitudes[..row].iter() {
+            assert!((latitude - edge).abs() < 1e-4);
+        }
+        for &latitude in latitudes[latitudes.len() - row..].iter() {
+            assert!((latitude + edge).abs() < 1e-4);
+        }
+
+        // Same grid as the whole sphere, so tinting and texturing see the usual layout
+        assert_eq!(positions.len(), row * row);
+        assert_eq!(colors.len(), positions.len());
+        assert_eq!(uvs.first(), Some(&[0.0, 0.0]));
+        assert_eq!(uvs.last(), Some(&[1.0, 1.0]));
+        assert_eq!(indices.len(), 6 * (row - 1) * (row - 1));
+    }
+
+    /// Raw bytes of every vertex attribute and the indices, meshes that match here are the same
+    fn mesh_bytes(mesh: &Mesh) -> Vec<Vec<u8>> {
+        let attributes = [
+            Mesh::ATTRIBUTE_POSITION,
+            Mesh::ATTRIBUTE_NORMAL,
+            Mesh::ATTRIBUTE_UV_0,
+            Mesh::ATTRIBUTE_COLOR,
+        ];
+        let mut bytes: Vec<Vec<u8>> = attributes
+            .into_iter()
+            .map(|attribute| mesh.attribute(attribute).map_or(Vec::new(), |values| values.get_bytes().to_vec()))
+            .collect();
+        let indices = mesh.indices().expect("ocean mesh has indices");
+        bytes.push(indices.iter().flat_map(|index| (index as u32).to_le_bytes()).collect());
+        bytes
+    }
+
+    /// A builder with every sampler and a band, so the update has to get all of them right
+    fn sampled_builder(config: OceanConfig) -> OceanMeshBuilder<'static> {
+        OceanMeshBuilder::new(config)
+            .with_latitude_range(-1.2, 1.4)
+            .with_height_sampler(Box::new(|position: Vec3| 49.5 + position.x * 0.02))
+            .with_wind_sampler(Box::new(|position: Vec3| Vec3::Y.cross(position).normalize_or_zero() * 8.0))
+    }
+
+    #[test]
+    fn test_updating_the_time_in_place_matches_a_fresh_build() {
+        let config = OceanConfig {
+            grid_size: 16,
+            polar_flattening: 2.0,
+            ..Default::default()
+        };
+        let mut builder = sampled_builder(config).with_time(0.5);
+        let mut mesh = builder.rebuild().unwrap().mesh;
+
+        builder.update_mesh(&mut mesh, 3.25).unwrap();
+
+        let fresh = sampled_builder(config).with_time(3.25).build().unwrap().mesh;
+        assert_eq!(mesh_bytes(&mesh), mesh_bytes(&fresh));
+        assert!(!builder.needs_rebuild());
+    }
+
+    #[test]
+    fn test_only_a_change_of_the_waves_is_updated_in_place() {
+        let config = OceanConfig {
+            grid_size: 16,
+            ..Default::default()
+        };
+        let mut builder = sampled_builder(config);
+        let mut mesh = builder.rebuild().unwrap().mesh;
+
+        let choppier = OceanConfig {
+            wave_amplitude: 0.2,
+            wave_speed: 3.0,
+            ..config
+        };
+        builder.set_config(choppier);
+        assert!(!builder.needs_rebuild());
+        builder.update_mesh(&mut mesh, 1.0).unwrap();
+        let fresh = sampled_builder(choppier).with_time(1.0).build().unwrap().mesh;
+        assert_eq!(mesh_bytes(&mesh), mesh_bytes(&fresh));
+
+        // The foam is in the vertex colors, the calm sea writes no normals
+        for changed in [
+            OceanConfig { foam_width: 0.3, ..choppier },
+            OceanConfig { sea_level: 60.0, ..choppier },
+            OceanConfig { wave_amplitude: 0.0, ..choppier },
+        ] {
+            let mut builder = sampled_builder(choppier);
+            builder.set_config(changed);
+            assert!(builder.needs_rebuild(), "{changed:?}");
+            builder.rebuild().unwrap();
+            assert!(!builder.needs_rebuild());
+        }
+    }
+
+    #[test]
+    fn test_update_needs_a_mesh_of_the_same_grid() {
+        let coarse = OceanConfig {
+            grid_size: 8,
+            ..Default::default()
+        };
+        let mut mesh = OceanMeshBuilder::new(coarse).build().unwrap().mesh;
+        let before = mesh_bytes(&mesh);
+
+        let mut builder = OceanMeshBuilder::new(coarse);
+        builder.set_config(OceanConfig { grid_size: 16, ..coarse });
+
+        assert!(builder.needs_rebuild());
+        assert_eq!(builder.update_mesh(&mut mesh, 1.0), Err(OceanError::MeshTopology(81)));
+        assert_eq!(mesh_bytes(&mesh), before, "a rejected update left the mesh alone");
+    }
+
+    #[test]
+    fn test_latitude_range_must_be_a_band() {
+        let edge = 60f32.to_radians();
+        for (min, max) in [(edge, -edge), (0.5, 0.5), (-2.0, 1.0), (-1.0, f32::NAN)] {
+            let result = OceanMeshBuilder::new(OceanConfig::default())
+                .with_latitude_range(min, max)
+                .build();
+            assert!(matches!(result, Err(OceanError::LatitudeRange(..))), "{min}..{max}");
+        }
+    }
+
+}
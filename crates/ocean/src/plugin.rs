@@ -0,0 +1,159 @@
+//! Optional plugin that animates the ocean and keeps `Floating` entities on the water surface
+
+use crate::animation::{OceanAnimation, animate_ocean};
+use crate::buoyancy::{WaterSample, sample_water, sample_water_with_wind};
+use crate::{OceanConfig, WindSampler};
+use bevy::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+/// Adds `OceanSurface`, `OceanAnimation`, the system that moves the `AnimatedOcean` waves and
+/// the one that moves `Floating` entities with them
+#[derive(Default)]
+pub struct OceanPlugin {
+    /// Initial animation policy, the `OceanAnimation` resource can be changed later
+    pub animation: OceanAnimation,
+}
+
+impl Plugin for OceanPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OceanSurface>()
+            .insert_resource(self.animation.clone())
+            .add_systems(Update, (animate_ocean, float_on_ocean).chain());
+    }
+}
+
+/// The water `Floating` entities follow. Keep it in sync with the rendered ocean: the same
+/// config, time and wind sampler the mesh was built with.
+#[derive(Resource)]
+pub struct OceanSurface {
+    pub config: OceanConfig,
+    pub time: f32,
+    pub wind: Option<WindSampler<'static>>,
+    /// Southern and northern latitude of the animated mesh, see
+    /// `OceanMeshBuilder::with_latitude_range`
+    pub latitude_range: (f32, f32),
+}
+
+impl Default for OceanSurface {
+    fn default() -> Self {
+        Self {
+            config: OceanConfig::default(),
+            time: 0.0,
+            wind: None,
+            latitude_range: (-FRAC_PI_2, FRAC_PI_2),
+        }
+    }
+}
+
+impl OceanSurface {
+    /// The water around a sphere of `radius` at `position`, see `sample_water`
+    pub fn sample(&self, position: Vec3, radius: f32) -> WaterSample {
+        match &self.wind {
+            Some(wind) => sample_water_with_wind(&self.config, position, radius, self.time, wind),
+            None => sample_water(&self.config, position, radius, self.time),
+        }
+    }
+}
+
+/// Keeps an entity on the water surface. Its `Transform` is relative to the ocean center, so
+/// spawn it as a child of the ocean (or of whatever the ocean is attached to).
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Floating {
+    /// Approximate size of the object, the center floats this much above the surface
+    /// scaled by `draft`
+    pub radius: f32,
+    /// How deep the object sits in the water: 0 rides on top, 1 is half under
+    pub draft: f32,
+    /// How quickly the object follows the surface up and down, per second. Lower values bob
+    /// lazily behind the waves, very high values stick to the surface.
+    pub bob_damping: f32,
+    /// How quickly the object tilts to the wave normal, per second
+    pub align_smoothing: f32,
+}
+
+impl Default for Floating {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            draft: 0.5,
+            bob_damping: 8.0,
+            align_smoothing: 4.0,
+        }
+    }
+}
+
+impl Floating {
+    /// Distance of the object center from the ocean center when it rests on `water`
+    pub fn resting_height(&self, water: &WaterSample) -> f32 {
+        water.surface_height + self.radius * (1.0 - self.draft.clamp(0.0, 1.0))
+    }
+}
+
+/// Share of the way to the target covered this frame, frame rate independent
+fn smoothing_factor(rate: f32, dt: f32) -> f32 {
+    if rate <= 0.0 {
+        return 1.0;
+    }
+    1.0 - (-rate * dt).exp()
+}
+
+pub fn float_on_ocean(
+    time: Res<Time>,
+    surface: Res<OceanSurface>,
+    mut floating: Query<(&Floating, &mut Transform)>,
+) {
+    let dt = time.delta_secs();
+    for (floating, mut transform) in floating.iter_mut() {
+        let Some(up) = transform.translation.try_normalize() else {
+            continue;
+        };
+        let water = surface.sample(transform.translation, floating.radius);
+
+        let height = transform.translation.length();
+        let target = floating.resting_height(&water);
+        let height = height + (target - height) * smoothing_factor(floating.bob_damping, dt);
+        transform.translation = up * height;
+
+        let current_up = transform.rotation * Vec3::Y;
+        let alignment = Quat::from_rotation_arc(current_up, water.normal);
+        let aligned = (alignment * transform.rotation).normalize();
+        transform.rotation = transform
+            .rotation
+            .slerp(aligned, smoothing_factor(floating.align_smoothing, dt));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_floating_entity_settles_on_the_water() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, OceanPlugin::default()))
+            .insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)));
+        let cube = app
+            .world_mut()
+            .spawn((
+                Floating {
+                    draft: 1.0,
+                    bob_damping: 20.0,
+                    ..default()
+                },
+                Transform::from_xyz(53.0, 0.0, 0.0),
+            ))
+            .id();
+
+        for _ in 0..200 {
+            app.update();
+        }
+
+        let transform = app.world().get::<Transform>(cube).unwrap();
+        let surface = app.world().resource::<OceanSurface>();
+        let water = surface.sample(transform.translation, 0.5);
+        // Half under at full draft
+        assert!((water.submerged_fraction - 0.5).abs() < 0.01);
+        assert!((transform.rotation * Vec3::Y).angle_between(water.normal) < 0.01);
+    }
+}
@@ -0,0 +1,440 @@
+//! Benchmarks for the expensive parts of planet generation.
+//!
+//! Run with `cargo bench -p planetgen` (or `cargo bench` inside crates/planetgen).
+//! Every benchmark builds its input from `generator()` with a fixed seed, so numbers are
+//! comparable between runs. With `--features trace` the time of every span is printed and
+//! checked as well, see `bench_span_coverage`.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+// Pipeline stages outside the prelude, see the crate docs
+use planetgen::continents::ContinentNoiseConfig;
+use planetgen::cube_grid::StitchedGrid;
+use planetgen::plate_blend::PlateBlend;
+use planetgen::prelude::*;
+use planetgen::config::{GenerationConfig, PlanetGenConfig, PrecipitationConfig};
+use planetgen::{get_config, set_config};
+use std::collections::HashMap;
+use std::hint::black_box;
+
+const BENCH_SEED: u64 = 42;
+
+/// Share of the generation the stage spans have to account for
+#[cfg(feature = "trace")]
+const MIN_STAGE_COVERAGE: f64 = 0.9;
+
+/// The config file lives in the repository root, next to the game
+fn load_config() -> PlanetGenConfig {
+    std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../.."))
+        .expect("repository root must exist");
+    get_config()
+}
+
+fn generator(radius: f32) -> PlanetGenerator {
+    let mut generator = PlanetGenerator::new(radius);
+    generator.seed = BENCH_SEED;
+    generator
+}
+
+fn bench_assign_plates(c: &mut Criterion) {
+    load_config();
+    let mut group = c.benchmark_group("assign_plates");
+    group.sample_size(10);
+
+    // Every cell pays for 3 warp samples plus 3 flow samples per advection step, and then
+    // scans all plates, so this grows with radius² × (flow steps + plate count).
+    for radius in [20.0, 50.0] {
+        let generator = generator(radius);
+        let plates = generator.generate_plates();
+        let size = generator.face_grid_size();
+
+        group.bench_with_input(BenchmarkId::from_parameter(radius), &plates, |b, plates| {
+            b.iter(|| generator.assign_plates(black_box(size), black_box(plates)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_generate_faces(c: &mut Criterion) {
+    let config = load_config();
+    let generator = generator(config.generation.radius);
+    let size = generator.face_grid_size();
+    let plates = generator.generate_plates();
+    let plate_map = generator.assign_plates(size, &plates);
+    let continent_noise = ContinentNoiseConfig::from_config(BENCH_SEED as u32, &config.continents);
+    let plate_blend = PlateBlend::calculate(size, &plate_map, config.boundaries.height_blend_width);
+    let plate_base_heights = vec![0.0; plates.len()];
+
+    let mut group = c.benchmark_group("generate_faces");
+    group.sample_size(10);
+    group.bench_function("default_radius", |b| {
+        b.iter(|| {
+            generator.generate_faces(
+                black_box(size),
+                &continent_noise,
+                &plate_map,
+                &plate_blend,
+                &plate_base_heights,
+            )
+        })
+    });
+    group.finish();
+}
+
+/// The whole default pipeline, with the time of each stage of one run printed first
+fn bench_generate(c: &mut Criterion) {
+    let config = load_config();
+    let generator = generator(config.generation.radius);
+    generator.generate_with(&GenerationPipeline::default(), |stage, elapsed| {
+        eprintln!("{stage:>22}: {elapsed:?}");
+    });
+
+    let mut group = c.benchmark_group("generate");
+    group.sample_size(10);
+    group.bench_function("default_radius", |b| b.iter(|| black_box(generator.generate())));
+    group.finish();
+}
+
+fn bench_temperature(c: &mut Criterion) {
+    let config = load_config().temperature;
+    let mut group = c.benchmark_group("temperature_cubemap");
+
+    for resolution in [64, 256] {
+        group.bench_with_input(BenchmarkId::from_parameter(resolution), &resolution, |b, &res| {
+            b.iter(|| {
+                TemperatureCubeMap::build(
+                    black_box(res),
+                    config.equator_temp,
+                    config.pole_temp,
+                    config.min_temp,
+                    config.max_temp,
+                    Palette::Default,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn planet() -> PlanetData {
+    let config = load_config();
+    generator(config.generation.radius).generate()
+}
+
+fn bench_wind(c: &mut Criterion) {
+    let config = load_config();
+    let planet = planet();
+
+    let mut group = c.benchmark_group("wind_cubemap");
+    group.sample_size(10);
+    group.bench_function("build_with_terrain", |b| {
+        b.iter(|| {
+            WindCubeMap::build_with_terrain(
+                black_box(config.temperature.cubemap_resolution),
+                config.wind.zonal_speed,
+                &planet,
+                &config.wind_deflection,
+            )
+        })
+    });
+    group.finish();
+}
+
+fn bench_precipitation(c: &mut Criterion) {
+    let config = load_config();
+    let planet = planet();
+    let resolution = config.precipitation.cubemap_resolution;
+    let (wind, _) = WindCubeMap::build_with_terrain(
+        resolution,
+        config.wind.zonal_speed,
+        &planet,
+        &config.wind_deflection,
+    );
+    let vertical_air = VerticalAirCubeMap::build_from_wind(&wind);
+    let temperature = TemperatureCubeMap::build(
+        resolution,
+        config.temperature.equator_temp,
+        config.temperature.pole_temp,
+        config.temperature.min_temp,
+        config.temperature.max_temp,
+        Palette::Default,
+    );
+
+    let mut group = c.benchmark_group("precipitation_cubemap");
+    for (name, blur_passes) in [("without_blur", 0), ("with_blur", config.precipitation.blur_passes)] {
+        set_config(PlanetGenConfig {
+            precipitation: PrecipitationConfig { blur_passes, ..config.precipitation.clone() },
+            ..config.clone()
+        });
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                PrecipitationCubeMap::build(
+                    &vertical_air,
+                    Some(&temperature),
+                    Some(&planet),
+                    config.precipitation.temperature_weight,
+                    config.precipitation.ocean_weight,
+                    config.temperature.equator_temp,
+                    config.temperature.pole_temp,
+                )
+            })
+        });
+    }
+    set_config(config);
+    group.finish();
+}
+
+fn bench_mesh(c: &mut Criterion) {
+    let config = load_config();
+    // The same planet with its vertex positions worked out in f64
+    set_config(PlanetGenConfig {
+        generation: GenerationConfig { double_precision_vertices: true, ..config.generation.clone() },
+        ..config.clone()
+    });
+    let precise_planet = planet();
+    set_config(config.clone());
+    let planet = planet();
+
+    let mut group = c.benchmark_group("mesh_data");
+    group.sample_size(10);
+    for (name, planet) in [("from_planet", &planet), ("from_planet_double_precision", &precise_planet)] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                MeshData::from_planet(
+                    black_box(planet),
+                    ViewMode::Continents,
+                    SnowLine::height(config.mountains.snow_threshold),
+                    (&config.hillshade, &ColorVariation { strength: config.color_variation.strength, seed: BENCH_SEED }),
+                    Palette::Default,
+                    MeshUvs::None,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Point of a cube face at `u, v` in -1..=1, same as the generator's
+fn cube_face_point(face: usize, u: f32, v: f32) -> [f32; 3] {
+    match face {
+        0 => [1.0, v, -u],
+        1 => [-1.0, v, u],
+        2 => [u, 1.0, -v],
+        3 => [u, -1.0, v],
+        4 => [u, v, 1.0],
+        _ => [-u, v, -1.0],
+    }
+}
+
+/// The vertex deduplication `MeshData` used before `StitchedGrid`: every grid point hashed by
+/// its direction quantized to the grid size
+fn dir_map_vertex_indices(size: usize) -> Vec<u32> {
+    let cells = (size - 1) as f32;
+    let mut dir_map: HashMap<(i32, i32, i32), u32> = HashMap::new();
+    let mut indices = Vec::with_capacity(6 * size * size);
+    for face in 0..6 {
+        for y in 0..size {
+            for x in 0..size {
+                let [nx, ny, nz] = cube_face_point(face, x as f32 / cells * 2.0 - 1.0, y as f32 / cells * 2.0 - 1.0);
+                let length = (nx * nx + ny * ny + nz * nz).sqrt();
+                let key = (
+                    (nx / length * cells).round() as i32,
+                    (ny / length * cells).round() as i32,
+                    (nz / length * cells).round() as i32,
+                );
+                let next = dir_map.len() as u32;
+                indices.push(*dir_map.entry(key).or_insert(next));
+            }
+        }
+    }
+    indices
+}
+
+/// Numbering the vertices of a full resolution planet mesh, the old hash map against the
+/// explicit edge stitching
+fn bench_vertex_stitching(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vertex_stitching");
+    for size in [65, 201] {
+        group.bench_with_input(BenchmarkId::new("dir_map", size), &size, |b, &size| {
+            b.iter(|| dir_map_vertex_indices(black_box(size)))
+        });
+        group.bench_with_input(BenchmarkId::new("stitched_grid", size), &size, |b, &size| {
+            b.iter(|| StitchedGrid::new(black_box(size)))
+        });
+    }
+    group.finish();
+}
+
+/// Adds up the time spent inside every span by name, generation stages are told apart by
+/// their `stage` field
+#[cfg(feature = "trace")]
+mod span_times {
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    #[derive(Default)]
+    struct Spans {
+        next_id: AtomicU64,
+        /// Name and enter time of every span not exited yet
+        open: Mutex<HashMap<u64, (String, Option<Instant>)>>,
+        totals: Mutex<HashMap<String, Duration>>,
+    }
+
+    /// Subscriber timing the spans, clones share the totals
+    #[derive(Clone, Default)]
+    pub struct SpanTimes(Arc<Spans>);
+
+    impl SpanTimes {
+        pub fn totals(&self) -> HashMap<String, Duration> {
+            self.0.totals.lock().unwrap().clone()
+        }
+    }
+
+    struct StageField(Option<String>);
+
+    impl Visit for StageField {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "stage" {
+                self.0 = Some(value.to_owned());
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn Debug) {}
+    }
+
+    impl Subscriber for SpanTimes {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+            let mut stage = StageField(None);
+            attributes.record(&mut stage);
+            let name = match stage.0 {
+                Some(stage) => format!("stage {stage}"),
+                None => attributes.metadata().name().to_owned(),
+            };
+            let id = self.0.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+            self.0.open.lock().unwrap().insert(id, (name, None));
+            Id::from_u64(id)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, span: &Id) {
+            if let Some((_, entered)) = self.0.open.lock().unwrap().get_mut(&span.into_u64()) {
+                *entered = Some(Instant::now());
+            }
+        }
+
+        fn exit(&self, span: &Id) {
+            let exited = self.0.open.lock().unwrap().remove(&span.into_u64());
+            if let Some((name, Some(entered))) = exited {
+                *self.0.totals.lock().unwrap().entry(name).or_default() += entered.elapsed();
+            }
+        }
+    }
+}
+
+/// A planet with its climate maps and mesh under the span timer, the time of every span printed
+/// first. Fails when a span is missing or the stages leave a noticeable part of the generation
+/// untimed, a slowdown there couldn't be pinned on any stage.
+#[cfg(feature = "trace")]
+fn bench_span_coverage(_c: &mut Criterion) {
+    let config = load_config();
+    let times = span_times::SpanTimes::default();
+    tracing::subscriber::with_default(times.clone(), || {
+        let planet = planet();
+        let resolution = config.precipitation.cubemap_resolution;
+        let (wind, _) = WindCubeMap::build_with_terrain(
+            resolution,
+            config.wind.zonal_speed,
+            &planet,
+            &config.wind_deflection,
+        );
+        let vertical_air = VerticalAirCubeMap::build_from_wind(&wind);
+        let temperature = TemperatureCubeMap::build(
+            resolution,
+            config.temperature.equator_temp,
+            config.temperature.pole_temp,
+            config.temperature.min_temp,
+            config.temperature.max_temp,
+            Palette::Default,
+        );
+        PrecipitationCubeMap::build(
+            &vertical_air,
+            Some(&temperature),
+            Some(&planet),
+            config.precipitation.temperature_weight,
+            config.precipitation.ocean_weight,
+            config.temperature.equator_temp,
+            config.temperature.pole_temp,
+        );
+        MeshData::from_planet(
+            &planet,
+            ViewMode::Continents,
+            SnowLine::height(config.mountains.snow_threshold),
+            (&config.hillshade, &ColorVariation { strength: config.color_variation.strength, seed: BENCH_SEED }),
+            Palette::Default,
+            MeshUvs::None,
+        );
+    });
+
+    let totals = times.totals();
+    let mut spans: Vec<_> = totals.iter().collect();
+    spans.sort_by_key(|&(_, time)| std::cmp::Reverse(*time));
+    for (name, time) in spans {
+        eprintln!("{name:>32}: {time:?}");
+    }
+
+    for name in [
+        "generate",
+        "wind_cubemap_with_terrain",
+        "wind_cubemap",
+        "mountain_influence_map",
+        "vertical_air_cubemap",
+        "temperature_cubemap",
+        "precipitation_cubemap",
+        "mesh_data",
+    ] {
+        assert!(totals.contains_key(name), "no {name} span");
+    }
+    let generate = totals["generate"];
+    let stages: std::time::Duration = totals
+        .iter()
+        .filter(|(name, _)| name.starts_with("stage "))
+        .map(|(_, time)| *time)
+        .sum();
+    assert!(
+        stages.as_secs_f64() >= generate.as_secs_f64() * MIN_STAGE_COVERAGE,
+        "the stages account for {stages:?} of the {generate:?} generation"
+    );
+}
+
+#[cfg(not(feature = "trace"))]
+fn bench_span_coverage(_c: &mut Criterion) {
+    eprintln!("Span timing needs --features trace");
+}
+
+criterion_group!(
+    benches,
+    bench_assign_plates,
+    bench_generate_faces,
+    bench_generate,
+    bench_temperature,
+    bench_wind,
+    bench_precipitation,
+    bench_mesh,
+    bench_vertex_stitching,
+    bench_span_coverage,
+);
+criterion_main!(benches);
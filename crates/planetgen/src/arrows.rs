@@ -1,11 +1,12 @@
 use crate::generator::cube_face_point;
 use crate::planet::PlanetData;
+use crate::tools::sphere::tangent_basis;
 use glam::{Quat, Vec3};
 
 /// Data needed to render an arrow representing a tectonic plate's movement
 #[derive(Debug, Clone)]
 pub struct PlateArrowData {
-    /// Position of the arrow on the planet surface
+    /// Position of the arrow on the ground, the terrain or the sea above it
     pub position: Vec3,
     /// Rotation to align the arrow with the plate's movement direction
     pub rotation: Quat,
@@ -42,13 +43,11 @@ fn calculate_single_plate_arrow(
     // Get the movement direction of the plate
     let direction = Vec3::new(plate.direction.x, plate.direction.y, plate.direction.z).normalize();
 
-    // Get the surface normal at this position (pointing outward from center)
-    let surface_normal = center.normalize();
-
     // Project the plate direction onto the tangent plane at this surface point
-    // This removes the component of the direction that points toward/away from the center
-    let tangent_direction =
-        (direction - surface_normal * direction.dot(surface_normal)).normalize();
+    // This removes the component of the direction that points toward/away from the center,
+    // a direction straight up or down has nothing left and the arrow points north
+    let (east, north) = tangent_basis(center);
+    let tangent_direction = (east * direction.dot(east) + north * direction.dot(north)).normalize_or(north);
 
     // Calculate rotation to point in the tangent direction
     let default_direction = Vec3::Z;
@@ -64,7 +63,7 @@ fn calculate_single_plate_arrow(
 /// Calculate the center position of a tectonic plate
 ///
 /// Returns the average position of all cells belonging to the plate,
-/// projected onto the planet surface with a small offset above the terrain.
+/// projected onto the terrain there, or the sea surface if the terrain is under water.
 fn calculate_plate_center(planet: &PlanetData, plate_idx: usize) -> Option<Vec3> {
     let mut center = Vec3::ZERO;
     let mut count = 0;
@@ -80,7 +79,7 @@ fn calculate_plate_center(planet: &PlanetData, plate_idx: usize) -> Option<Vec3>
                     let (nx, ny, nz) = cube_face_point(face_idx, u, v);
                     let dir = Vec3::new(nx, ny, nz).normalize();
                     let height = face.heightmap[y][x];
-                    let pos = dir * (planet.radius + height);
+                    let pos = dir * (planet.surface_radius(dir) + height);
 
                     center += pos;
                     count += 1;
@@ -91,8 +90,9 @@ fn calculate_plate_center(planet: &PlanetData, plate_idx: usize) -> Option<Vec3>
 
     if count > 0 {
         center /= count as f32;
-        // Normalize to the planet radius and add a small offset above surface
-        center = center.normalize() * (planet.radius + 1.0);
+        // Put it down on the ground, how far above it to draw is up to the renderer
+        let dir = center.normalize();
+        center = dir * (planet.surface_radius(dir) + planet.sample_height(dir).max(planet.sea_level()));
         Some(center)
     } else {
         None
@@ -127,4 +127,19 @@ mod tests {
         let expected_scale = radius * 0.2;
         assert_eq!(expected_scale, 2.0);
     }
+
+    #[test]
+    fn test_arrows_sit_on_the_ground() {
+        let mut generator = crate::generator::PlanetGenerator::new(5.0);
+        generator.seed = 42;
+        let planet = generator.generate();
+
+        let arrows = calculate_plate_arrows(&planet);
+        assert!(!arrows.is_empty());
+        for arrow in arrows {
+            let dir = arrow.position.normalize();
+            let ground = planet.surface_radius(dir) + planet.sample_height(dir).max(planet.sea_level());
+            assert!((arrow.position.length() - ground).abs() < 1e-3, "{} vs {ground}", arrow.position.length());
+        }
+    }
 }
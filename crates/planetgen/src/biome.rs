@@ -1,7 +1,9 @@
+use crate::snow::SNOW_COLOR;
+
 /// Biome classification and coloring based on temperature and precipitation.
 ///
 /// Produces smooth color gradients between biome zones rather than hard boundaries.
-/// Mountain snow (based on height threshold) and ocean floor coloring are preserved.
+/// Snow (from the snow line) and ocean floor coloring are preserved.
 
 /// Configurable biome zone thresholds.
 #[derive(Clone, Debug)]
@@ -59,13 +61,70 @@ impl Default for BiomeColors {
     }
 }
 
-/// Compute the biome-based RGBA color for a vertex.
+/// The biomes the colors blend between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Biome {
+    Ice,
+    Tundra,
+    Desert,
+    Savanna,
+    Temperate,
+    Jungle,
+}
+
+impl Biome {
+    /// In the order of the weights of `biome_weights`
+    const ALL: [Biome; 6] = [
+        Biome::Ice,
+        Biome::Tundra,
+        Biome::Desert,
+        Biome::Savanna,
+        Biome::Temperate,
+        Biome::Jungle,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Biome::Ice => "Ice",
+            Biome::Tundra => "Tundra",
+            Biome::Desert => "Desert",
+            Biome::Savanna => "Savanna",
+            Biome::Temperate => "Temperate",
+            Biome::Jungle => "Jungle",
+        }
+    }
+}
+
+/// The biome with the most weight in the color of land at `temperature` (°C) and
+/// `precipitation` (0.0-1.0)
+pub fn dominant_biome(temperature: f32, precipitation: f32, thresholds: &BiomeThresholds) -> Biome {
+    Biome::ALL
+        .into_iter()
+        .zip(biome_weights(temperature, precipitation, thresholds))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(Biome::Temperate, |(biome, _)| biome)
+}
+
+/// Share of savanna, temperate forest and jungle in the color of land at `temperature` (°C) and
+/// `precipitation` (0.0-1.0), from 0.0 to 1.0
+pub(crate) fn vegetation_share(temperature: f32, precipitation: f32, thresholds: &BiomeThresholds) -> f32 {
+    let weights = biome_weights(temperature, precipitation, thresholds);
+    let total: f32 = weights.iter().sum();
+    if total < 1e-10 {
+        // Colored temperate then, see biome_base_color
+        return 1.0;
+    }
+    (weights[3] + weights[4] + weights[5]) / total
+}
+
+/// Compute the biome-based RGBA color for a vertex. `snow_cover` blends land towards snow, see
+/// [`SnowLine::cover`](crate::snow::SnowLine::cover).
 pub fn biome_color(
     height_above_ocean: f32,
     temperature: f32,
     precipitation: f32,
     height: f32,
-    snow_threshold: f32,
+    snow_cover: f32,
     continent_threshold: f32,
     colors: &BiomeColors,
     thresholds: &BiomeThresholds,
@@ -82,19 +141,6 @@ pub fn biome_color(
         ];
     }
 
-    // Mountain snow: pure white above threshold
-    if height > snow_threshold {
-        return [0.95, 0.95, 1.0, 1.0];
-    }
-
-    // Smooth transition zone near snow line (top 15% of mountain range)
-    let snow_transition_start = snow_threshold - (snow_threshold - continent_threshold) * 0.15;
-    let snow_blend = if height > snow_transition_start {
-        ((height - snow_transition_start) / (snow_threshold - snow_transition_start)).clamp(0.0, 1.0)
-    } else {
-        0.0
-    };
-
     // Narrow sandy strip at coast
     let shore_width = continent_threshold * 0.05;
     let shore_blend = if height_above_ocean < shore_width {
@@ -108,13 +154,10 @@ pub fn biome_color(
     let base_color = biome_base_color(temperature, precipitation, colors, thresholds);
 
     // Blend with shore
-    let mut color = lerp_color(base_color, shore_color, shore_blend);
+    let color = lerp_color(base_color, shore_color, shore_blend);
 
-    // Blend with snow near mountain tops
-    let snow_color: [f32; 4] = [0.95, 0.95, 1.0, 1.0];
-    color = lerp_color(color, snow_color, snow_blend);
-
-    color
+    // Blend with snow where it's cold enough
+    lerp_color(color, SNOW_COLOR, snow_cover)
 }
 
 fn rgb3_to_rgba(c: [f32; 3]) -> [f32; 4] {
@@ -207,7 +250,7 @@ fn biome_base_color(temperature: f32, precipitation: f32, colors: &BiomeColors,
 }
 
 /// Linear interpolation between two RGBA colors.
-fn lerp_color(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+pub(crate) fn lerp_color(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
     [
         a[0] + (b[0] - a[0]) * t,
         a[1] + (b[1] - a[1]) * t,
@@ -215,3 +258,27 @@ fn lerp_color(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
         a[3] + (b[3] - a[3]) * t,
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dominant_biome_follows_the_climate() {
+        let thresholds = BiomeThresholds::default();
+
+        for (temperature, precipitation, expected) in [
+            (-25.0, 0.3, Biome::Ice),
+            (2.0, 0.3, Biome::Tundra),
+            (28.0, 0.02, Biome::Desert),
+            (12.0, 0.3, Biome::Temperate),
+            (28.0, 0.7, Biome::Jungle),
+        ] {
+            assert_eq!(
+                dominant_biome(temperature, precipitation, &thresholds),
+                expected,
+                "{temperature} °C, {precipitation}"
+            );
+        }
+    }
+}
@@ -3,9 +3,14 @@
 /// This module calculates the interaction type at plate boundaries by analyzing
 /// the relative velocity of adjacent plates.
 
+use crate::palette::Palette;
 use crate::plate::TectonicPlate;
 use crate::planet::PlateMap;
 use glam::Vec3;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const NEIGHBORS_8: [(i32, i32); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
 
 /// Type of plate boundary interaction
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -26,18 +31,31 @@ impl BoundaryType {
 }
 
 /// Boundary information for the entire planet
+#[derive(Clone)]
 pub struct BoundaryData {
     /// Boundary type for each cell (None = interior, Some = boundary)
     pub boundaries: [Vec<Vec<Option<BoundaryType>>>; 6],
-    /// Distance from boundary (0.0 = exact boundary, 1.0+ = far from any boundary)
-    /// Used for fade-out effect
+    /// Distance from the nearest boundary along the unit sphere (radians, so a fraction of the
+    /// planet radius): 0.0 = exact boundary, INFINITY = outside every band
     pub boundary_distances: [Vec<Vec<f32>>; 6],
+    /// Width of the band on each side of a boundary, same units as the distances
+    pub band_width: f32,
 }
 
 impl BoundaryData {
+    /// A planet without plate boundaries
+    pub fn empty(face_grid_size: usize, band_width: f32) -> Self {
+        Self {
+            boundaries: std::array::from_fn(|_| vec![vec![None; face_grid_size]; face_grid_size]),
+            boundary_distances: std::array::from_fn(|_| vec![vec![f32::INFINITY; face_grid_size]; face_grid_size]),
+            band_width,
+        }
+    }
+
     /// Calculate boundary types for all plate boundaries
     ///
-    /// Creates wider boundaries (5% of planet size) with fade-out effect.
+    /// Creates bands `band_width` wide (fraction of the planet radius) on each side of a
+    /// boundary, so they look the same at any grid resolution.
     ///
     /// # Algorithm
     /// 1. Find exact boundary cells (adjacent to different plates)
@@ -48,6 +66,7 @@ impl BoundaryData {
         face_grid_size: usize,
         plate_map: &PlateMap,
         plates: &[TectonicPlate],
+        band_width: f32,
     ) -> Self {
         // Create plate ID to plate lookup
         let plate_lookup: std::collections::HashMap<usize, &TectonicPlate> =
@@ -70,14 +89,15 @@ impl BoundaryData {
             &mut boundary_distances,
         );
 
-        // Step 2: Calculate distance field using flood fill
+        // Step 2: Calculate distance field out to the band width
         Self::propagate_boundary_distances(
             face_grid_size,
+            band_width,
             &mut boundaries,
             &mut boundary_distances,
         );
 
-        Self { boundaries, boundary_distances }
+        Self { boundaries, boundary_distances, band_width }
     }
 
     /// Find exact boundary cells and classify their interaction type
@@ -122,7 +142,10 @@ impl BoundaryData {
                                         let pos_neigh = Vec3::from(crate::generator::cube_face_point(face_idx, u_neigh, v_neigh)).normalize();
                                         let boundary_position = ((pos_curr + pos_neigh) * 0.5).normalize();
 
-                                        let boundary_type = classify_boundary(boundary_position, plate_a, plate_b);
+                                        // Direction across the boundary, from this cell's plate into the neighbour's
+                                        let across = pos_neigh - pos_curr;
+
+                                        let boundary_type = classify_boundary(boundary_position, across, plate_a, plate_b);
 
                                         // Mark BOTH sides with the same boundary type
                                         boundaries[face_idx][y][x] = Some(boundary_type);
@@ -139,95 +162,119 @@ impl BoundaryData {
         }
     }
 
-    /// Propagate boundary distances using flood fill
+    /// Propagate boundary distances with Dijkstra over the 8 neighbours of each cell
     ///
-    /// Creates a distance field around boundaries for fade-out effect.
-    /// Boundary width: 5% of grid size (roughly 5% of planet radius)
+    /// Steps are the actual angles between the cell centers, so diagonals count √2 and cells
+    /// near face corners, which are smaller, count less. Bands come out round instead of
+    /// diamond-shaped around isolated kinks. Each cell takes the type of its nearest boundary.
     fn propagate_boundary_distances(
         face_grid_size: usize,
+        band_width: f32,
         boundaries: &mut [Vec<Vec<Option<BoundaryType>>>; 6],
         boundary_distances: &mut [Vec<Vec<f32>>; 6],
     ) {
-        let boundary_width = (face_grid_size as f32 * 0.05).max(3.0) as usize;
-
-        // Simple distance propagation
-        for dist in 1..=boundary_width {
-            for face_idx in 0..6 {
-                for y in 0..face_grid_size {
-                    for x in 0..face_grid_size {
-                        if boundary_distances[face_idx][y][x] == (dist - 1) as f32 {
-                            // Propagate to neighbors
-                            for (dx, dy) in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
-                                let nx = x as i32 + dx;
-                                let ny = y as i32 + dy;
-                                if nx >= 0 && ny >= 0 && (nx as usize) < face_grid_size && (ny as usize) < face_grid_size {
-                                    let nx = nx as usize;
-                                    let ny = ny as usize;
-
-                                    if boundary_distances[face_idx][ny][nx] == f32::INFINITY {
-                                        boundary_distances[face_idx][ny][nx] = dist as f32;
-
-                                        // Inherit boundary type from parent
-                                        if boundaries[face_idx][y][x].is_some() && boundaries[face_idx][ny][nx].is_none() {
-                                            boundaries[face_idx][ny][nx] = boundaries[face_idx][y][x];
-                                        }
-                                    }
-                                }
-                            }
-                        }
+        let inv = 1.0 / (face_grid_size - 1) as f32;
+        for face_idx in 0..6 {
+            let directions: Vec<Vec<Vec3>> = (0..face_grid_size)
+                .map(|y| {
+                    let v = y as f32 * inv * 2.0 - 1.0;
+                    (0..face_grid_size)
+                        .map(|x| {
+                            let u = x as f32 * inv * 2.0 - 1.0;
+                            Vec3::from(crate::generator::cube_face_point(face_idx, u, v)).normalize()
+                        })
+                        .collect()
+                })
+                .collect();
+
+            // Distances are never negative, so their bit patterns sort like the values
+            let mut queue = BinaryHeap::new();
+            for (y, row) in boundary_distances[face_idx].iter().enumerate() {
+                for (x, &distance) in row.iter().enumerate() {
+                    if distance == 0.0 {
+                        queue.push((Reverse(0.0f32.to_bits()), x, y));
                     }
                 }
             }
-        }
-    }
 
-    /// Get the boundary type at a specific cell
-    pub fn get_boundary(&self, face: usize, x: usize, y: usize) -> Option<BoundaryType> {
-        self.boundaries[face][y][x]
+            while let Some((Reverse(bits), x, y)) = queue.pop() {
+                let dist = f32::from_bits(bits);
+                if dist > boundary_distances[face_idx][y][x] {
+                    continue;
+                }
+                for (dx, dy) in NEIGHBORS_8 {
+                    let nx = x as i32 + dx;
+                    let ny = y as i32 + dy;
+                    if nx < 0 || ny < 0 || nx as usize >= face_grid_size || ny as usize >= face_grid_size {
+                        continue;
+                    }
+                    let (nx, ny) = (nx as usize, ny as usize);
+
+                    let next = dist + directions[y][x].angle_between(directions[ny][nx]);
+                    if next <= band_width && next < boundary_distances[face_idx][ny][nx] {
+                        boundary_distances[face_idx][ny][nx] = next;
+                        // Inherit boundary type from the nearest boundary
+                        boundaries[face_idx][ny][nx] = boundaries[face_idx][y][x];
+                        queue.push((Reverse(next.to_bits()), nx, ny));
+                    }
+                }
+            }
+        }
     }
 
-    /// Get boundary color with distance-based opacity
-    /// Returns (color, opacity) where opacity is 1.0 at boundary, 0.0 far away
-    pub fn get_boundary_color(&self, face: usize, x: usize, y: usize) -> Option<([f32; 3], f32)> {
+    /// Get boundary color in `palette` with distance-based opacity
+    /// Returns (color, opacity) where opacity is 1.0 at boundary, 0.0 at the edge of the band
+    pub fn get_boundary_color(
+        &self,
+        face: usize,
+        x: usize,
+        y: usize,
+        palette: Palette,
+    ) -> Option<([f32; 3], f32)> {
         if let Some(boundary_type) = self.boundaries[face][y][x] {
             let distance = self.boundary_distances[face][y][x];
 
-            // Calculate opacity: 1.0 at boundary (distance 0), fade to 0.0 at max distance
+            // Calculate opacity: 1.0 at boundary (distance 0), fade to 0.0 at the band width
             // Use smooth falloff curve
-            let max_dist = 10.0; // Approximately 5% of grid size
-            let normalized_dist = (distance / max_dist).min(1.0);
+            let normalized_dist = (distance / self.band_width.max(f32::EPSILON)).min(1.0);
             let opacity = 1.0 - normalized_dist * normalized_dist; // Quadratic falloff for smooth fade
 
-            Some((boundary_type.color(), opacity))
+            Some((palette.boundary_color(boundary_type), opacity))
         } else {
             None
         }
     }
+
+    /// Distance to the nearest boundary in cells of average size, for terrain features sized
+    /// in cells
+    pub(crate) fn distance_in_cells(&self, face: usize, x: usize, y: usize, face_grid_size: usize) -> f32 {
+        self.boundary_distances[face][y][x] / mean_cell_angle(face_grid_size)
+    }
+}
+
+/// Average angle between neighbouring cells, a face spans 90° of arc
+pub(crate) fn mean_cell_angle(face_grid_size: usize) -> f32 {
+    std::f32::consts::FRAC_PI_2 / (face_grid_size - 1) as f32
 }
 
 /// Classify the type of boundary interaction between two plates
 ///
-/// Uses the plate centers to determine a consistent classification across the entire boundary.
+/// Uses the local direction across the boundary rather than the plate centers: microplates are
+/// seeded right next to a boundary and merged plates extend far beyond their original seed, so
+/// a center-to-center line says little about which way the boundary faces at a given cell.
 ///
 /// # Algorithm
-/// 1. Find the boundary midpoint between the two plate centers
-/// 2. Calculate tangent velocities of both plates at this reference point
-/// 3. Compute relative velocity component perpendicular to the line connecting centers
+/// 1. Project `across` (pointing from plate A into plate B) into the tangent plane at the boundary
+/// 2. Calculate tangent velocities of both plates at the boundary position
+/// 3. Take the component of the relative velocity along the across-boundary normal
 /// 4. Classify based on whether plates are converging, diverging, or sliding
 fn classify_boundary(
     position: Vec3,
+    across: Vec3,
     plate_a: &TectonicPlate,
     plate_b: &TectonicPlate,
 ) -> BoundaryType {
-    // The boundary lies on the great circle perpendicular to the line connecting centers.
-    // We care about the component of relative velocity *across* that boundary within the local tangent plane.
-    let center_line = (plate_a.center - plate_b.center).normalize_or_zero();
-    if center_line.length_squared() < 1e-6 {
-        return BoundaryType::Transform;
-    };
-
-    // Project center_line into the tangent plane at the boundary position to get the across-boundary normal.
-    let tangent_normal = (center_line - position * center_line.dot(position)).normalize_or_zero();
+    let tangent_normal = (across - position * across.dot(position)).normalize_or_zero();
     if tangent_normal.length_squared() < 1e-8 {
         return BoundaryType::Transform;
     };
@@ -256,3 +303,182 @@ fn classify_boundary(
         BoundaryType::Transform
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid2D;
+    use crate::planet::{PlateSizeClass, PlateType};
+
+    const GRID: usize = 9;
+    const MID: usize = GRID / 2;
+    const BAND_WIDTH: f32 = 0.5;
+
+    fn plate(id: usize, angular_velocity: Vec3, center: Vec3, size_class: PlateSizeClass) -> TectonicPlate {
+        TectonicPlate {
+            id,
+            direction: Vec3::ZERO,
+            angular_velocity,
+            center,
+            size_class,
+            plate_type: PlateType::default(),
+            debug_color: [0.0; 4],
+        }
+    }
+
+    /// Every cell belongs to plate 0, except cells on face 0 (+X) picked by `is_other`
+    fn plate_map_with(other_id: usize, is_other: impl Fn(usize, usize) -> bool) -> PlateMap {
+        let mut plate_map = vec![Grid2D::new(GRID, GRID, 0); 6];
+        for y in 0..GRID {
+            for x in 0..GRID {
+                if is_other(x, y) {
+                    plate_map[0][y][x] = other_id;
+                }
+            }
+        }
+        plate_map
+    }
+
+    /// Face 0 split in two: plate 0 on the u < 0 side (+Z), plate 1 on the u >= 0 side (-Z)
+    fn split_face_map() -> PlateMap {
+        plate_map_with(1, |x, _| x >= MID)
+    }
+
+    #[test]
+    fn test_converging_plates_are_convergent() {
+        // Around +X, rotation about +Y moves the surface towards -Z, i.e. from plate 0 into plate 1
+        let plates = [
+            plate(0, Vec3::Y, Vec3::Z, PlateSizeClass::Regular),
+            plate(1, -Vec3::Y, -Vec3::Z, PlateSizeClass::Regular),
+        ];
+        let data = BoundaryData::calculate(GRID, &split_face_map(), &plates, BAND_WIDTH);
+
+        assert_eq!(data.boundaries[0][MID][MID - 1], Some(BoundaryType::Convergent));
+        assert_eq!(data.boundaries[0][MID][MID], Some(BoundaryType::Convergent));
+    }
+
+    #[test]
+    fn test_diverging_plates_are_divergent() {
+        let plates = [
+            plate(0, -Vec3::Y, Vec3::Z, PlateSizeClass::Regular),
+            plate(1, Vec3::Y, -Vec3::Z, PlateSizeClass::Regular),
+        ];
+        let data = BoundaryData::calculate(GRID, &split_face_map(), &plates, BAND_WIDTH);
+
+        assert_eq!(data.boundaries[0][MID][MID - 1], Some(BoundaryType::Divergent));
+        assert_eq!(data.boundaries[0][MID][MID], Some(BoundaryType::Divergent));
+    }
+
+    #[test]
+    fn test_shearing_plates_are_transform() {
+        // Rotation about Z moves the surface around +X along Y, parallel to the boundary
+        let plates = [
+            plate(0, Vec3::Z, Vec3::Z, PlateSizeClass::Regular),
+            plate(1, -Vec3::Z, -Vec3::Z, PlateSizeClass::Regular),
+        ];
+        let data = BoundaryData::calculate(GRID, &split_face_map(), &plates, BAND_WIDTH);
+
+        assert_eq!(data.boundaries[0][MID][MID - 1], Some(BoundaryType::Transform));
+    }
+
+    #[test]
+    fn test_microplate_edges_depend_on_its_motion() {
+        // 3x3 microplate in the middle of face 0, drifting towards -Z (+x in grid space)
+        // through a resting major plate. Its center sits right next to the boundary.
+        let plate_map = plate_map_with(1, |x, y| x.abs_diff(MID) <= 1 && y.abs_diff(MID) <= 1);
+        let plates = [
+            plate(0, Vec3::ZERO, Vec3::Z, PlateSizeClass::Regular),
+            plate(1, Vec3::Y, Vec3::X, PlateSizeClass::Micro),
+        ];
+        let data = BoundaryData::calculate(GRID, &plate_map, &plates, BAND_WIDTH);
+
+        // Leading edge pushes into the major plate
+        assert_eq!(data.boundaries[0][MID][MID + 2], Some(BoundaryType::Convergent));
+        // Trailing edge opens up behind it
+        assert_eq!(data.boundaries[0][MID][MID - 2], Some(BoundaryType::Divergent));
+        // Sides slide past the major plate
+        assert_eq!(data.boundaries[0][MID - 2][MID], Some(BoundaryType::Transform));
+        assert_eq!(data.boundaries[0][MID + 2][MID], Some(BoundaryType::Transform));
+        // Interior of the microplate is not a boundary cell
+        assert!(data.boundary_distances[0][MID][MID] > 0.0);
+    }
+
+    #[test]
+    fn test_merged_plate_uses_primary_motion() {
+        // Plate 2 was absorbed into plate 1: it is still in the plate list but no longer in the map.
+        // Plate 1's seed center is on the far side of the planet, so the merged region extends
+        // well past anything its center could describe.
+        let plates = [
+            plate(0, Vec3::ZERO, Vec3::Z, PlateSizeClass::Regular),
+            plate(1, Vec3::Y, -Vec3::X, PlateSizeClass::Regular),
+            plate(2, -Vec3::Y * 5.0, -Vec3::Z, PlateSizeClass::Regular),
+        ];
+        let data = BoundaryData::calculate(GRID, &split_face_map(), &plates, BAND_WIDTH);
+
+        // Plate 1 moves towards -Z, away from plate 0
+        assert_eq!(data.boundaries[0][MID][MID - 1], Some(BoundaryType::Divergent));
+        assert_eq!(data.boundaries[0][MID][MID], Some(BoundaryType::Divergent));
+    }
+
+    fn direction(n: usize, x: usize, y: usize) -> Vec3 {
+        let to_uv = |i: usize| i as f32 / (n - 1) as f32 * 2.0 - 1.0;
+        Vec3::from(crate::generator::cube_face_point(0, to_uv(x), to_uv(y))).normalize()
+    }
+
+    #[test]
+    fn test_band_width_is_the_same_at_any_resolution() {
+        let plates = [
+            plate(0, Vec3::Y, Vec3::Z, PlateSizeClass::Regular),
+            plate(1, -Vec3::Y, -Vec3::Z, PlateSizeClass::Regular),
+        ];
+
+        // Arc covered by the band across the middle row of a face split in two
+        let band_span = |n: usize| {
+            let mut plate_map = vec![Grid2D::new(n, n, 0); 6];
+            for row in plate_map[0].rows_mut() {
+                row[n / 2..].fill(1);
+            }
+            let data = BoundaryData::calculate(n, &plate_map, &plates, 0.3);
+            let banded: Vec<usize> = (0..n).filter(|&x| data.boundaries[0][n / 2][x].is_some()).collect();
+            direction(n, banded[0], n / 2).angle_between(direction(n, *banded.last().unwrap(), n / 2))
+        };
+
+        let (coarse, fine) = (band_span(17), band_span(65));
+        assert!((coarse - fine).abs() < mean_cell_angle(17), "{coarse} vs {fine}");
+    }
+
+    #[test]
+    fn test_band_around_a_single_cell_is_round() {
+        let n = 33;
+        let mid = n / 2;
+        let mut plate_map = vec![Grid2D::new(n, n, 0); 6];
+        plate_map[0][mid][mid] = 1;
+        let plates = [
+            plate(0, Vec3::ZERO, Vec3::Z, PlateSizeClass::Regular),
+            plate(1, Vec3::Y, Vec3::X, PlateSizeClass::Micro),
+        ];
+        let data = BoundaryData::calculate(n, &plate_map, &plates, 0.5);
+
+        let sources: Vec<Vec3> = (0..n)
+            .flat_map(|y| (0..n).map(move |x| (x, y)))
+            .filter(|&(x, y)| data.boundary_distances[0][y][x] == 0.0)
+            .map(|(x, y)| direction(n, x, y))
+            .collect();
+        for y in 0..n {
+            for x in 0..n {
+                let distance = data.boundary_distances[0][y][x];
+                if !distance.is_finite() {
+                    continue;
+                }
+                // A 4-neighbour flood fill would count diagonals twice
+                let exact = sources
+                    .iter()
+                    .map(|&source| source.angle_between(direction(n, x, y)))
+                    .fold(f32::INFINITY, f32::min);
+                assert!(distance >= exact - 1e-4 && distance <= exact * 1.1 + 1e-4, "({x}, {y}): {distance} vs {exact}");
+            }
+        }
+        // The diagonal reaches as far out as the axes
+        assert!(data.boundaries[0][mid + 5][mid + 5].is_some());
+    }
+}
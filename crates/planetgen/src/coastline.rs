@@ -0,0 +1,210 @@
+//! Coastlines as closed loops on the sea surface
+//!
+//! Marching squares over the grid cells of all six faces at the sea level. Crossings are keyed
+//! by the two grid points of the edge they lie on, numbered by `StitchedGrid`, so a crossing on
+//! a cube edge is the same one from both faces and a coast running over several faces closes
+//! into a single loop.
+
+use crate::cube_grid::StitchedGrid;
+use crate::generator::cell_direction;
+use crate::planet::PlanetData;
+use glam::Vec3;
+use std::collections::HashMap;
+
+/// A closed line on the planet, the last point connects back to the first
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Vec3>,
+}
+
+impl Polyline {
+    /// Length of the loop, the closing segment included
+    pub fn length(&self) -> f32 {
+        let closing = self.points.last().zip(self.points.first());
+        self.points
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .chain(closing.map(|(&last, &first)| (last, first)))
+            .map(|(a, b)| a.distance(b))
+            .sum()
+    }
+}
+
+/// Coastline loops of a planet with their total length
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Coastline {
+    /// Seen from outside, every loop has the land on its left: islands run counter-clockwise,
+    /// lakes of ocean inside a continent clockwise
+    pub loops: Vec<Polyline>,
+    /// Summed length of the loops, in the units of the planet radius
+    pub length: f32,
+}
+
+/// Edge between two vertices of the stitched grid, lowest vertex first
+type EdgeKey = (u32, u32);
+
+/// Where the heightmap crosses `sea_level`, as loops on the sea surface at that level. Cells
+/// higher than `sea_level` are land. A planet without both land and ocean has no loops.
+pub fn extract_coastline(planet: &PlanetData, sea_level: f32) -> Coastline {
+    let n = planet.face_grid_size;
+    let grid = StitchedGrid::new(n);
+    // The height of the owner of a shared point, so both faces agree on it
+    let height = |vertex: u32| {
+        let (face, x, y) = grid.vertices()[vertex as usize];
+        planet.faces[face].heightmap[y][x]
+    };
+    let is_land = |vertex: u32| height(vertex) > sea_level;
+
+    let mut crossings: HashMap<EdgeKey, Vec3> = HashMap::new();
+    let mut crossing = |a: u32, b: u32| -> EdgeKey {
+        let key = (a.min(b), a.max(b));
+        crossings.entry(key).or_insert_with(|| {
+            let (ha, hb) = (height(a), height(b));
+            let t = ((sea_level - ha) / (hb - ha)).clamp(0.0, 1.0);
+            let direction = |vertex: u32| {
+                let (face, x, y) = grid.vertices()[vertex as usize];
+                cell_direction(n, face, x, y)
+            };
+            direction(a).lerp(direction(b), t).normalize()
+        });
+        key
+    };
+
+    // Each crossing starts one segment and ends another, land on the left of both
+    let mut next: HashMap<EdgeKey, EdgeKey> = HashMap::new();
+    for face in 0..6 {
+        for y in 0..n - 1 {
+            for x in 0..n - 1 {
+                // Counter-clockwise seen from outside, like the mesh triangles
+                let corners = [
+                    grid.index(face, x, y),
+                    grid.index(face, x + 1, y),
+                    grid.index(face, x + 1, y + 1),
+                    grid.index(face, x, y + 1),
+                ];
+                let land = corners.map(is_land);
+                // Crossings in order around the cell, flagged true where the land begins
+                let mut around = Vec::with_capacity(4);
+                for i in 0..4 {
+                    let j = (i + 1) % 4;
+                    if land[i] != land[j] {
+                        around.push((crossing(corners[i], corners[j]), land[j]));
+                    }
+                }
+                // A saddle, land on two opposite corners, is joined through the middle when the
+                // middle is above the sea
+                let saddle_joins_land =
+                    around.len() == 4 && corners.iter().map(|&corner| height(corner)).sum::<f32>() > 4.0 * sea_level;
+                // The land between a crossing into it and the next one out is closed by a
+                // segment from the way out back to the way in
+                for (index, &(way_in, entering)) in around.iter().enumerate() {
+                    if !entering {
+                        continue;
+                    }
+                    let count = around.len();
+                    let way_out = if saddle_joins_land {
+                        // The land runs on to the next piece, its way out is three crossings on
+                        around[(index + 3) % count].0
+                    } else {
+                        around[(index + 1) % count].0
+                    };
+                    next.insert(way_out, way_in);
+                }
+            }
+        }
+    }
+
+    let mut loops = Vec::new();
+    let mut starts: Vec<EdgeKey> = next.keys().copied().collect();
+    starts.sort_unstable();
+    for start in starts {
+        let Some(mut current) = next.remove(&start) else {
+            continue;
+        };
+        let mut keys = vec![start];
+        while current != start {
+            keys.push(current);
+            let Some(following) = next.remove(&current) else {
+                break;
+            };
+            current = following;
+        }
+        let points = keys
+            .into_iter()
+            .map(|key| {
+                let direction = crossings[&key];
+                direction * (planet.surface_radius(direction) + sea_level)
+            })
+            .collect();
+        loops.push(Polyline { points });
+    }
+
+    let length = loops.iter().map(Polyline::length).sum();
+    Coastline { loops, length }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const N: usize = 65;
+    const RADIUS: f32 = 10.0;
+
+    /// Land within `angle` radians of `center`, sloping down from it
+    fn island(center: Vec3, angle: f32) -> PlanetData {
+        let mut planet = PlanetData::half_land(N, RADIUS);
+        let center = center.normalize();
+        for (face, cube_face) in planet.faces.iter_mut().enumerate() {
+            for y in 0..N {
+                for x in 0..N {
+                    cube_face.heightmap[y][x] = cell_direction(N, face, x, y).dot(center) - angle.cos();
+                }
+            }
+        }
+        planet
+    }
+
+    #[test]
+    fn test_an_island_over_a_cube_corner_is_one_loop() {
+        let angle = 0.5;
+        let planet = island(Vec3::new(1.0, 1.0, 0.8), angle);
+        let coastline = extract_coastline(&planet, 0.0);
+
+        assert_eq!(coastline.loops.len(), 1);
+        let expected = TAU * RADIUS * angle.sin();
+        assert!(
+            (coastline.length - expected).abs() < expected * 0.02,
+            "{} instead of {expected}",
+            coastline.length
+        );
+        for point in &coastline.loops[0].points {
+            assert!((point.length() - RADIUS).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_the_land_is_left_of_the_coast() {
+        let center = Vec3::new(-0.3, 0.2, 1.0).normalize();
+        let planet = island(center, 0.4);
+        let coastline = extract_coastline(&planet, 0.0);
+
+        let points = &coastline.loops[0].points;
+        for (index, &point) in points.iter().enumerate() {
+            let ahead = points[(index + 1) % points.len()] - point;
+            let left = point.normalize().cross(ahead);
+            assert!(left.dot(center) > 0.0, "land on the right at point {index}");
+        }
+    }
+
+    #[test]
+    fn test_planets_without_a_coast_have_no_loops() {
+        // Heights of 1 and -1, all land below the lowest and all ocean above the highest
+        let planet = PlanetData::half_land(N, RADIUS);
+        for level in [-2.0, 2.0] {
+            let coastline = extract_coastline(&planet, level);
+            assert!(coastline.loops.is_empty(), "sea level {level}");
+            assert_eq!(coastline.length, 0.0);
+        }
+    }
+}
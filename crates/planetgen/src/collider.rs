@@ -0,0 +1,379 @@
+//! Ray picking against the displaced terrain
+//!
+//! The collider holds the same surface as the full resolution terrain mesh: a vertex per grid
+//! point at its terrain height, or at the water surface on lakes, over the oblate zero-height
+//! surface, and two triangles per cell. Every face is split into square tiles with a bounding box
+//! around their vertices, so a ray only tests the triangles of the few tiles it passes through.
+//! The ocean surface is not part of it, rays over the sea hit the sea floor.
+
+use crate::generator::cell_direction;
+use crate::planet::PlanetData;
+use crate::trace::trace_span;
+use glam::{Quat, Vec3};
+
+/// Cells along each side of a tile
+const TILE_CELLS: usize = 8;
+
+/// Slack on the barycentric coordinates, so rays through an edge or vertex shared by two
+/// triangles don't slip between them
+const BARYCENTRIC_EPSILON: f32 = 1e-5;
+
+/// Where a ray hit the terrain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceHit {
+    /// World position of the hit
+    pub position: Vec3,
+    /// Unit direction of the hit from the planet center in the planet's own frame, the
+    /// direction the cube maps are sampled with
+    pub direction: Vec3,
+    /// Cube face of the hit cell
+    pub face: usize,
+    /// `(x, y)` of the grid cell whose triangles were hit, the grid point at its lower corner
+    pub cell: (usize, usize),
+}
+
+/// Axis aligned box around a set of vertices
+#[derive(Clone, Copy)]
+struct Bounds {
+    min: Vec3,
+    max: Vec3,
+}
+
+impl Bounds {
+    const EMPTY: Bounds = Bounds {
+        min: Vec3::INFINITY,
+        max: Vec3::NEG_INFINITY,
+    };
+
+    fn grow(&mut self, point: Vec3) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
+
+    fn merge(&mut self, other: &Bounds) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Distance along the ray where it enters the box, 0 if it starts inside, None if it
+    /// passes by
+    fn entry(&self, origin: Vec3, dir: Vec3) -> Option<f32> {
+        let mut near = 0.0_f32;
+        let mut far = f32::INFINITY;
+        for axis in 0..3 {
+            if dir[axis] == 0.0 {
+                // Parallel to this pair of sides, the ray is between them or never gets there
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (self.min[axis] - origin[axis]) / dir[axis];
+            let t2 = (self.max[axis] - origin[axis]) / dir[axis];
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+        }
+        (near <= far).then_some(near)
+    }
+}
+
+/// A block of up to `TILE_CELLS` × `TILE_CELLS` cells of one face
+struct Tile {
+    face: usize,
+    /// First cell of the tile along x and y
+    x0: usize,
+    y0: usize,
+    /// One past the last cell along x and y
+    x1: usize,
+    y1: usize,
+    bounds: Bounds,
+}
+
+/// Picking collider of a generated planet, see the module docs.
+///
+/// It is a snapshot, build a new one when the terrain changes.
+pub struct SurfaceCollider {
+    face_grid_size: usize,
+    /// Vertex positions of each face at `y * face_grid_size + x`, in the planet's own frame
+    positions: [Vec<Vec3>; 6],
+    /// Tiles of each face
+    tiles: [Vec<Tile>; 6],
+    /// Box around all tiles of each face
+    face_bounds: [Bounds; 6],
+}
+
+impl SurfaceCollider {
+    pub fn build(planet: &PlanetData) -> Self {
+        let n = planet.face_grid_size;
+        trace_span!("surface_collider", face_grid_size = n);
+        let positions: [Vec<Vec3>; 6] = std::array::from_fn(|face| {
+            (0..n)
+                .flat_map(|y| (0..n).map(move |x| (x, y)))
+                .map(|(x, y)| {
+                    let dir = cell_direction(n, face, x, y);
+                    // Same height as the terrain mesh, lakes are flat water at their surface
+                    let height = planet
+                        .lakes
+                        .surface_at(face, x, y)
+                        .unwrap_or(planet.faces[face].heightmap[y][x]);
+                    dir * (planet.surface_radius(dir) + height)
+                })
+                .collect()
+        });
+
+        let cells = n.saturating_sub(1);
+        let tiles: [Vec<Tile>; 6] = std::array::from_fn(|face| {
+            let starts = (0..cells).step_by(TILE_CELLS);
+            starts
+                .clone()
+                .flat_map(|y0| starts.clone().map(move |x0| (x0, y0)))
+                .map(|(x0, y0)| {
+                    let (x1, y1) = ((x0 + TILE_CELLS).min(cells), (y0 + TILE_CELLS).min(cells));
+                    let mut bounds = Bounds::EMPTY;
+                    // The cells of the tile reach up to the grid points at x1, y1
+                    for y in y0..=y1 {
+                        for x in x0..=x1 {
+                            bounds.grow(positions[face][y * n + x]);
+                        }
+                    }
+                    Tile {
+                        face,
+                        x0,
+                        y0,
+                        x1,
+                        y1,
+                        bounds,
+                    }
+                })
+                .collect()
+        });
+
+        let face_bounds = std::array::from_fn(|face| {
+            let mut bounds = Bounds::EMPTY;
+            for tile in &tiles[face] {
+                bounds.merge(&tile.bounds);
+            }
+            bounds
+        });
+
+        Self {
+            face_grid_size: n,
+            positions,
+            tiles,
+            face_bounds,
+        }
+    }
+
+    /// The first point where the ray from `origin` along `dir` hits the terrain, None if it
+    /// misses the planet. The planet sits at the world origin turned by `planet_rotation`,
+    /// `origin` and `dir` are in world space.
+    pub fn raycast(&self, origin: Vec3, dir: Vec3, planet_rotation: Quat) -> Option<SurfaceHit> {
+        // Work in the planet's own frame, where the vertices are
+        let to_planet = planet_rotation.inverse();
+        let origin_local = to_planet * origin;
+        let dir_local = (to_planet * dir).try_normalize()?;
+
+        // Tiles the ray passes through, nearest first
+        let mut candidates: Vec<(f32, &Tile)> = (0..6)
+            .filter(|&face| {
+                self.face_bounds[face]
+                    .entry(origin_local, dir_local)
+                    .is_some()
+            })
+            .flat_map(|face| &self.tiles[face])
+            .filter_map(|tile| Some((tile.bounds.entry(origin_local, dir_local)?, tile)))
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut nearest: Option<(f32, usize, usize, usize)> = None;
+        for (entry, tile) in candidates {
+            // Every tile left starts behind the hit
+            if nearest.is_some_and(|(distance, ..)| distance < entry) {
+                break;
+            }
+            for y in tile.y0..tile.y1 {
+                for x in tile.x0..tile.x1 {
+                    let Some(distance) =
+                        self.intersect_cell(tile.face, x, y, origin_local, dir_local)
+                    else {
+                        continue;
+                    };
+                    if nearest.is_none_or(|(nearest_distance, ..)| distance < nearest_distance) {
+                        nearest = Some((distance, tile.face, x, y));
+                    }
+                }
+            }
+        }
+
+        nearest.map(|(distance, face, x, y)| {
+            let local = origin_local + dir_local * distance;
+            SurfaceHit {
+                position: planet_rotation * local,
+                direction: local.normalize(),
+                face,
+                cell: (x, y),
+            }
+        })
+    }
+
+    /// Distance along the ray to the nearer of the two triangles of a cell, in the same split
+    /// as the terrain mesh
+    fn intersect_cell(
+        &self,
+        face: usize,
+        x: usize,
+        y: usize,
+        origin: Vec3,
+        dir: Vec3,
+    ) -> Option<f32> {
+        let n = self.face_grid_size;
+        let vertex = |x: usize, y: usize| self.positions[face][y * n + x];
+        let (p0, p1, p2, p3) = (
+            vertex(x, y),
+            vertex(x + 1, y),
+            vertex(x, y + 1),
+            vertex(x + 1, y + 1),
+        );
+        match (
+            intersect_triangle(origin, dir, p0, p1, p2),
+            intersect_triangle(origin, dir, p1, p3, p2),
+        ) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Möller–Trumbore ray/triangle intersection from either side, the distance along `dir` to the
+/// hit in front of `origin`
+fn intersect_triangle(origin: Vec3, dir: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = dir.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < f32::EPSILON * edge1.length_squared().max(edge2.length_squared()) {
+        // The ray runs along the triangle's plane
+        return None;
+    }
+    let inverse = 1.0 / determinant;
+    let to_origin = origin - a;
+    let u = to_origin.dot(p) * inverse;
+    if !(-BARYCENTRIC_EPSILON..=1.0 + BARYCENTRIC_EPSILON).contains(&u) {
+        return None;
+    }
+    let q = to_origin.cross(edge1);
+    let v = dir.dot(q) * inverse;
+    if v < -BARYCENTRIC_EPSILON || u + v > 1.0 + BARYCENTRIC_EPSILON {
+        return None;
+    }
+    let distance = edge2.dot(q) * inverse;
+    (distance >= 0.0).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    const N: usize = 17;
+    const RADIUS: f32 = 10.0;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            actual.abs_diff_eq(expected, 1e-3),
+            "{actual} instead of {expected}"
+        );
+    }
+
+    #[test]
+    fn test_ray_down_the_x_axis_hits_the_x_face_at_its_height() {
+        // Land at height 1 on the first 3 faces, so +X
+        let collider = SurfaceCollider::build(&PlanetData::half_land(N, RADIUS));
+
+        let hit = collider
+            .raycast(Vec3::new(50.0, 0.0, 0.0), Vec3::NEG_X, Quat::IDENTITY)
+            .unwrap();
+
+        assert_eq!(hit.face, 0);
+        assert_near(hit.position, Vec3::new(RADIUS + 1.0, 0.0, 0.0));
+        assert_near(hit.direction, Vec3::X);
+    }
+
+    #[test]
+    fn test_ray_hits_the_near_side_of_the_planet() {
+        // Ocean floor at height -1 on -Y, the land on +Y is behind it
+        let collider = SurfaceCollider::build(&PlanetData::half_land(N, RADIUS));
+
+        let hit = collider
+            .raycast(Vec3::new(0.0, -50.0, 0.0), Vec3::Y, Quat::IDENTITY)
+            .unwrap();
+
+        assert_eq!(hit.face, 3);
+        assert_near(hit.position, Vec3::new(0.0, -(RADIUS - 1.0), 0.0));
+    }
+
+    #[test]
+    fn test_ray_past_the_planet_misses() {
+        let collider = SurfaceCollider::build(&PlanetData::half_land(N, RADIUS));
+
+        let hit = collider.raycast(Vec3::new(50.0, 20.0, 0.0), Vec3::NEG_X, Quat::IDENTITY);
+
+        assert_eq!(hit, None);
+    }
+
+    #[test]
+    fn test_peak_catches_a_ray_above_the_rest_of_the_surface() {
+        let mut planet = PlanetData::half_land(N, RADIUS);
+        let center = N / 2;
+        // The middle of +X rises to radius 15, the rest of the face stays at 11
+        planet.faces[0].heightmap[center][center] = 5.0;
+        let collider = SurfaceCollider::build(&planet);
+
+        // Grazes the planet at x = 13, through the side of the peak
+        let hit = collider
+            .raycast(Vec3::new(13.0, 0.0, -50.0), Vec3::Z, Quat::IDENTITY)
+            .unwrap();
+
+        assert_eq!(hit.face, 0);
+        assert!(
+            hit.position.z < 0.0,
+            "hit the far side of the peak at {}",
+            hit.position
+        );
+        assert!((hit.position.x - 13.0).abs() < 1e-3);
+        assert!(
+            [center - 1, center].contains(&hit.cell.0)
+                && [center - 1, center].contains(&hit.cell.1)
+        );
+    }
+
+    #[test]
+    fn test_ray_is_turned_with_the_planet() {
+        let collider = SurfaceCollider::build(&PlanetData::half_land(N, RADIUS));
+        // Turns the planet's +X face towards world +Z
+        let rotation = Quat::from_rotation_y(-FRAC_PI_2);
+
+        let hit = collider
+            .raycast(Vec3::new(0.0, 0.0, 50.0), Vec3::NEG_Z, rotation)
+            .unwrap();
+
+        assert_eq!(hit.face, 0);
+        assert_near(hit.position, Vec3::new(0.0, 0.0, RADIUS + 1.0));
+        assert_near(hit.direction, Vec3::X);
+    }
+
+    #[test]
+    fn test_ray_at_the_pole_hits_the_flattened_surface() {
+        let mut planet = PlanetData::half_land(N, RADIUS);
+        planet.oblateness = 0.1;
+        let collider = SurfaceCollider::build(&planet);
+
+        let hit = collider
+            .raycast(Vec3::new(0.0, 50.0, 0.0), Vec3::NEG_Y, Quat::IDENTITY)
+            .unwrap();
+
+        // +Y is land at height 1 over a polar radius of 9
+        assert_eq!(hit.face, 2);
+        assert_near(hit.position, Vec3::new(0.0, RADIUS * 0.9 + 1.0, 0.0));
+    }
+}
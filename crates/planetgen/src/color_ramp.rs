@@ -0,0 +1,87 @@
+//! The color scales of the overlays behind one interface, so legends can draw any of them
+
+use crate::crust_age::crust_age_to_color;
+use crate::palette::Palette;
+use crate::precipitations::precipitation_to_color;
+use crate::temperature::TemperatureField;
+use crate::wind::influence::influence_to_color;
+use crate::wind::vertical::divergence_to_color;
+use glam::Vec3;
+
+/// Color scale of one overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorRamp {
+    Temperature,
+    Precipitation,
+    VerticalAir,
+    WindInfluence,
+    CrustAge,
+}
+
+impl ColorRamp {
+    /// Color at `t` from 0 (low end of the scale) to 1 (high end), the same color the overlay
+    /// draws for the value at that point of its range with `palette`
+    pub fn ramp(self, t: f32, palette: Palette) -> Vec3 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            ColorRamp::Temperature => TemperatureField::temperature_to_color(t, 0.0, 1.0, palette),
+            ColorRamp::Precipitation => precipitation_to_color(t, palette),
+            // Divergence runs from -1 (rising) to 1 (sinking)
+            ColorRamp::VerticalAir => divergence_to_color(t * 2.0 - 1.0, palette),
+            ColorRamp::WindInfluence => influence_to_color(t, palette),
+            ColorRamp::CrustAge => crust_age_to_color(Some(t), palette),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramps_end_on_the_overlay_colors() {
+        let palette = Palette::Default;
+        assert_eq!(
+            ColorRamp::Temperature.ramp(1.0, palette),
+            TemperatureField::temperature_to_color(50.0, -50.0, 50.0, palette)
+        );
+        assert_eq!(ColorRamp::Precipitation.ramp(0.0, palette), precipitation_to_color(0.0, palette));
+        assert_eq!(ColorRamp::VerticalAir.ramp(0.5, palette), divergence_to_color(0.0, palette));
+        assert_eq!(ColorRamp::CrustAge.ramp(2.0, palette), crust_age_to_color(Some(1.0), palette));
+    }
+
+    /// A few fixed points of every ramp in every palette, so an edit can't shift a scale
+    /// without a test noticing
+    #[test]
+    fn test_ramp_golden_values() {
+        let cases = [
+            (ColorRamp::Temperature, Palette::Default, 0.5, [0.6, 0.9, 0.25]),
+            (ColorRamp::Precipitation, Palette::Default, 0.25, [0.75, 0.9, 0.6]),
+            (ColorRamp::VerticalAir, Palette::Default, 0.25, [0.5, 0.5, 1.0]),
+            (ColorRamp::WindInfluence, Palette::Default, 0.75, [0.8, 0.3, 0.05]),
+            (ColorRamp::CrustAge, Palette::Default, 0.5, [0.625, 0.75, 0.275]),
+            (ColorRamp::Temperature, Palette::Viridis, 0.25, [0.231, 0.322, 0.545]),
+            (ColorRamp::VerticalAir, Palette::Viridis, 0.5, [0.129, 0.569, 0.549]),
+            (ColorRamp::CrustAge, Palette::Viridis, 0.0, [0.267, 0.005, 0.329]),
+            (ColorRamp::Precipitation, Palette::Cividis, 0.75, [0.651, 0.616, 0.459]),
+            (ColorRamp::WindInfluence, Palette::Cividis, 0.125, [0.071, 0.208, 0.439]),
+            (ColorRamp::Temperature, Palette::HighContrast, 0.5, [0.45, 0.535, 0.35]),
+            (ColorRamp::VerticalAir, Palette::HighContrast, 0.25, [0.5, 0.65, 0.8]),
+            (ColorRamp::CrustAge, Palette::HighContrast, 1.0, [1.0, 1.0, 0.75]),
+        ];
+        for (ramp, palette, t, expected) in cases {
+            let color = ramp.ramp(t, palette);
+            assert!(
+                color.abs_diff_eq(Vec3::from(expected), 1e-5),
+                "{ramp:?} with {palette:?} at {t}: {color} instead of {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_continental_crust_stays_gray_in_every_palette() {
+        for palette in Palette::ALL {
+            assert_eq!(crust_age_to_color(None, palette), Vec3::splat(0.55));
+        }
+    }
+}
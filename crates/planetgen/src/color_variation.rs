@@ -0,0 +1,145 @@
+//! Color variation: a faint, seeded value noise over the sphere that shifts the brightness and
+//! saturation of the terrain colors by a few percent, so land of one height and one climate
+//! reads as textured instead of flat. The noise is sampled by direction, so it runs on across
+//! the cube face edges.
+
+use glam::Vec3;
+use noise::{NoiseFn, Value};
+
+/// Noise cells along the radius of the unit sphere for the coarse octave, a few vertices each
+/// at the usual grid sizes
+const FREQUENCY: f64 = 24.0;
+/// How much more forest and grassland vary than the rest, their colors read as patches of trees
+const VEGETATION_BOOST: f32 = 0.75;
+
+/// Strength of the variation and the seed of its noise
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorVariation {
+    /// Largest brightness and saturation change as a share of the color, 0.0 leaves the colors
+    /// as they are
+    pub strength: f32,
+    pub seed: u64,
+}
+
+impl ColorVariation {
+    pub const NONE: ColorVariation = ColorVariation { strength: 0.0, seed: 0 };
+}
+
+/// The noise of a [`ColorVariation`], built once per mesh
+pub(crate) struct ColorNoise {
+    strength: f32,
+    brightness: Value,
+    saturation: Value,
+}
+
+impl ColorNoise {
+    pub(crate) fn new(variation: &ColorVariation) -> Self {
+        // The noise takes a 32 bit seed, fold the planet seed into it
+        let seed = (variation.seed ^ (variation.seed >> 32)) as u32;
+        Self {
+            strength: variation.strength,
+            brightness: Value::new(seed),
+            saturation: Value::new(seed.wrapping_add(1)),
+        }
+    }
+
+    /// `color` varied at `dir`. `vegetation` is the share of forest and grassland there, from 0.0
+    /// to 1.0, they vary more. With strength 0.0 the color comes back untouched.
+    pub(crate) fn apply(&self, color: [f32; 4], dir: Vec3, vegetation: f32) -> [f32; 4] {
+        if self.strength <= 0.0 {
+            return color;
+        }
+        let amount = self.strength * (1.0 + VEGETATION_BOOST * vegetation.clamp(0.0, 1.0));
+        let brightness = 1.0 + amount * sample(&self.brightness, dir);
+        let saturation = 1.0 + amount * sample(&self.saturation, dir);
+
+        let luminance = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+        let vary = |channel: f32| ((luminance + (channel - luminance) * saturation) * brightness).clamp(0.0, 1.0);
+        [vary(color[0]), vary(color[1]), vary(color[2]), color[3]]
+    }
+}
+
+/// Two octaves of `noise` at `dir`, within -1.0 to 1.0
+fn sample(noise: &Value, dir: Vec3) -> f32 {
+    let p = dir.normalize().as_dvec3() * FREQUENCY;
+    let coarse = noise.get([p.x, p.y, p.z]);
+    let fine = noise.get([p.x * 2.0 + 17.0, p.y * 2.0 + 17.0, p.z * 2.0 + 17.0]);
+    ((coarse * 2.0 + fine) / 3.0).clamp(-1.0, 1.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(strength: f32) -> ColorNoise {
+        ColorNoise::new(&ColorVariation { strength, seed: 42 })
+    }
+
+    fn directions() -> impl Iterator<Item = Vec3> {
+        (0..500).map(|i| {
+            let i = i as f32;
+            Vec3::new((i * 0.37).sin(), (i * 0.71).cos(), (i * 0.13).sin() + 0.1).normalize()
+        })
+    }
+
+    #[test]
+    fn test_zero_strength_keeps_the_color() {
+        let noise = noise(0.0);
+        for dir in directions() {
+            let color = [0.3, 0.5, 0.2, 1.0];
+            assert_eq!(noise.apply(color, dir, 1.0), color);
+        }
+    }
+
+    #[test]
+    fn test_variation_stays_within_a_few_percent() {
+        let noise = noise(0.05);
+        let color = [0.3, 0.5, 0.2, 1.0];
+        let mut varied = false;
+        for dir in directions() {
+            let result = noise.apply(color, dir, 0.0);
+            for channel in 0..3 {
+                assert!((result[channel] - color[channel]).abs() < 0.05, "{result:?}");
+            }
+            assert_eq!(result[3], 1.0);
+            varied |= result != color;
+        }
+        assert!(varied, "the noise left every color as it was");
+    }
+
+    #[test]
+    fn test_vegetation_varies_more() {
+        let noise = noise(0.05);
+        let color = [0.15, 0.4, 0.1, 1.0];
+        let spread = |vegetation: f32| -> f32 {
+            directions()
+                .map(|dir| (noise.apply(color, dir, vegetation)[1] - color[1]).abs())
+                .sum()
+        };
+        assert!(spread(1.0) > spread(0.0) * 1.5);
+    }
+
+    #[test]
+    fn test_noise_is_continuous_over_the_sphere() {
+        let noise = noise(0.05);
+        let color = [0.3, 0.5, 0.2, 1.0];
+        // Along a great circle through a cube edge and corner, steps far below a noise cell
+        let steps = 20_000;
+        let mut previous = noise.apply(color, Vec3::X, 0.0);
+        for i in 1..=steps {
+            let angle = i as f32 / steps as f32 * std::f32::consts::TAU;
+            let dir = Vec3::new(angle.cos(), angle.sin() * 0.7, angle.sin() * 0.7);
+            let current = noise.apply(color, dir, 0.0);
+            assert!((current[1] - previous[1]).abs() < 0.01, "jump at {dir}");
+            previous = current;
+        }
+    }
+
+    #[test]
+    fn test_seed_changes_the_pattern() {
+        let a = ColorNoise::new(&ColorVariation { strength: 0.05, seed: 1 });
+        let b = ColorNoise::new(&ColorVariation { strength: 0.05, seed: 2 });
+        let color = [0.3, 0.5, 0.2, 1.0];
+        assert!(directions().any(|dir| a.apply(color, dir, 0.0) != b.apply(color, dir, 0.0)));
+    }
+}
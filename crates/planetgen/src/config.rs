@@ -20,6 +20,13 @@ pub fn reload_config() {
     reload_config_from_file("planetgen_config.toml").unwrap();
 }
 
+/// Replace the current configuration, e.g. with the one a planet was generated with
+pub fn set_config(config: PlanetGenConfig) {
+    // Set before the file was ever loaded, the file is never needed
+    let config_mutex = CONFIG.get_or_init(|| Mutex::new(config.clone()));
+    *config_mutex.lock().unwrap() = config;
+}
+
 #[derive(Debug, Clone)]
 pub struct NoiseConfig {
     perlin: Perlin,
@@ -54,22 +61,39 @@ pub struct PlanetGenConfig {
     pub continents: ContinentConfig,
     pub merging: MergingConfig,
     pub mountains: MountainConfig,
+    pub lakes: LakeConfig,
+    pub crust_age: CrustAgeConfig,
+    pub tectonics: TectonicsConfig,
+    pub craters: CraterConfig,
+    pub moon: MoonConfig,
     pub ocean: OceanConfig,
     pub wind: WindConfig,
     pub wind_deflection: WindDeflectionConfig,
     pub temperature: TemperatureConfig,
     pub precipitation: PrecipitationConfig,
     pub biome: BiomeConfig,
+    pub hillshade: HillshadeConfig,
+    pub color_variation: ColorVariationConfig,
+    pub validation: ValidationConfig,
+    pub randomization: RandomizationProfile,
+    pub pipeline: PipelineConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationConfig {
     pub cells_per_unit: f32,
+    pub preview_cells_per_unit: f32,
+    pub max_face_grid_size: usize,
+    pub scale_heights_with_radius: bool,
+    /// Work out the mesh vertex positions in f64 and round them to f32 once, see
+    /// `PlanetData::double_precision_vertices`
+    pub double_precision_vertices: bool,
     pub continental_freq: f32,
     pub continental_amp: f32,
     pub oceanic_freq: f32,
     pub oceanic_amp: f32,
     pub radius: f32,
+    pub oblateness: f32,
     pub default_num_plates: usize,
     pub default_num_micro_plates: usize,
 }
@@ -78,6 +102,11 @@ pub struct GenerationConfig {
 pub struct PlateConfig {
     pub min_separation_chord_distance: f32,
     pub micro_plate_weight_factor: f32,
+    /// Base height added to continental plates before the continent noise, blended across
+    /// plate boundaries
+    pub continental_elevation: f32,
+    /// Same for oceanic plates, usually negative
+    pub oceanic_elevation: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,6 +114,9 @@ pub struct BoundaryConfig {
     pub distortion_frequency: f32,
     pub distortion_amplitude: f32,
     pub warp_multiplier: f32,
+    pub height_blend_width: f32,
+    /// Width of the boundary bands on each side of a plate boundary, fraction of the radius
+    pub band_width: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,6 +145,10 @@ pub struct ContinentConfig {
     pub detail_amplitude: f32,
     pub continent_threshold: f32,
     pub ocean_depth_amplitude: f32,
+    pub ocean_detail_frequency: f32,
+    pub ocean_detail_amplitude: f32,
+    /// Width of the band below the coastline where the ocean floor fades into the land profile
+    pub ocean_detail_blend_width: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -130,6 +166,114 @@ pub struct MountainConfig {
     pub mountain_underwater_threshold: f32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LakeConfig {
+    /// Smallest lake that is kept, as a fraction of the planet surface
+    pub min_lake_area: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrustAgeConfig {
+    /// How much deeper the oldest ocean floor sits than the floor at a ridge, 0 turns it off
+    pub subsidence: f32,
+}
+
+/// Plates drifting after generation, see `PlanetData::step_tectonics`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TectonicsConfig {
+    /// Height of the new ocean floor that opens up behind a moving plate
+    pub ridge_height: f32,
+    /// Uplift per step where plates collide
+    pub collision_uplift: f32,
+}
+
+/// Impact craters, the whole terrain of barren worlds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CraterConfig {
+    /// Craters per steradian, the whole sphere is 4π steradians
+    pub density: f32,
+    /// Rim radius of the smallest and largest craters, in degrees of arc
+    pub min_radius_deg: f32,
+    pub max_radius_deg: f32,
+    /// Power law of the sizes, higher means more small craters
+    pub size_exponent: f32,
+    /// Bowl depth as a fraction of the crater radius
+    pub depth_ratio: f32,
+    /// Rim height above the surroundings as a fraction of the bowl depth
+    pub rim_height: f32,
+    /// 0 = soft, worn craters, 1 = flat floors with steep walls and sharp rims
+    pub rim_sharpness: f32,
+    /// Where the ejecta apron ends, in crater radii from the center
+    pub ejecta_extent: f32,
+    /// Height of a barren surface above the continent threshold before any crater
+    pub base_height: f32,
+    /// Low noise on the barren surface between the craters
+    pub roughness_frequency: f32,
+    pub roughness_amplitude: f32,
+    /// Also crater terran planets, on top of the continent noise
+    pub craters_on_terran: bool,
+}
+
+/// Moons generated next to the planet, their craters are shaped by `CraterConfig`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoonConfig {
+    /// Vertices along one cube face edge, moons are small on screen and stay coarse
+    pub face_grid_size: usize,
+    /// Craters per steradian, replaces the planet's crater density
+    pub crater_density: f32,
+    /// Low noise between the craters, the amplitude as a fraction of the moon radius
+    pub roughness_frequency: f32,
+    pub roughness_amplitude: f32,
+}
+
+/// Limits of the sanity checks run on every generated planet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationConfig {
+    /// Less land than this (fraction of the surface) is reported as a degenerate planet
+    pub min_land_fraction: f32,
+    /// More land than this is reported as well
+    pub max_land_fraction: f32,
+    /// Plates covering less than this fraction of the surface are reported
+    pub min_plate_fraction: f32,
+}
+
+/// Which generation stages run, see `GenerationPipeline`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineConfig {
+    /// Names of stages to skip
+    pub disabled_stages: Vec<String>,
+}
+
+/// Ranges the "Surprise me" button picks settings from, `[min, max]` each. Narrower than the
+/// sliders so every pick gives a reasonable planet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RandomizationProfile {
+    /// Sea level, the low end leaves about 45% land and the high end about 20%
+    pub continent_threshold: [f32; 2],
+    pub distortion_frequency: [f32; 2],
+    pub distortion_amplitude: [f32; 2],
+    pub detail_frequency: [f32; 2],
+    pub detail_amplitude: [f32; 2],
+    pub mountain_height: [f32; 2],
+    pub mountain_width: [f32; 2],
+    pub oblateness: [f32; 2],
+    pub axial_tilt: [f32; 2],
+    pub num_plates: [usize; 2],
+    pub num_micro_plates: [usize; 2],
+    pub flow_warp_freq: [f32; 2],
+    pub flow_warp_steps: [usize; 2],
+    pub flow_warp_step_angle: [f32; 2],
+    pub equator_temp: [f32; 2],
+    /// Picked below the equator temperature
+    pub pole_temp: [f32; 2],
+    pub latitude_falloff: [f32; 2],
+    pub land_temperature_bonus: [f32; 2],
+    /// How far the color scale reaches past the coldest and hottest generated temperatures
+    pub color_scale_margin: [f32; 2],
+    pub precipitation_temperature_weight: [f32; 2],
+    pub precipitation_ocean_weight: [f32; 2],
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OceanConfig {
     pub wave_amplitude: f32,
@@ -164,6 +308,7 @@ pub struct TemperatureConfig {
     pub min_temp: f32,        // Minimum temperature for color scale
     pub land_temperature_bonus: f32, // Extra warmth for land above sea level
     pub latitude_falloff: f32, // Exponent controlling equator-to-pole temperature curve
+    pub lapse_rate: f32,      // Cooling per unit of height above sea level, places the snow line
     pub cubemap_resolution: usize, // Resolution of temperature cubemap
 }
 
@@ -177,6 +322,14 @@ pub struct PrecipitationConfig {
     pub ocean_weight: f32,
     /// Resolution of precipitation cubemap (pixels per face side)
     pub cubemap_resolution: usize,
+    /// Annual precipitation of the wettest point in mm, what a map value of 1.0 stands for
+    pub max_annual_mm: f32,
+    /// Exponent of the curve from map values to mm, above 1.0 the middle of the map gets drier
+    pub mm_gamma: f32,
+    /// Degrees the thermal equator, and the rain bands with it, swing north and south over a year
+    pub seasonal_shift_degrees: f32,
+    /// Smoothing passes over the precipitation map, 0 leaves the raw values
+    pub blur_passes: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -192,6 +345,24 @@ pub struct BiomeConfig {
     pub temperate_precip: f32,
 }
 
+/// Relief shading baked into the terrain vertex colors, lit like a GIS hillshade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HillshadeConfig {
+    /// Direction the light comes from, degrees clockwise from north
+    pub azimuth: f32,
+    /// Height of the light above the horizon in degrees
+    pub elevation: f32,
+    /// 0.0 keeps the plain colors, 1.0 applies the full shading
+    pub strength: f32,
+}
+
+/// Brightness and saturation noise baked into the terrain vertex colors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorVariationConfig {
+    /// Largest change as a share of the color, 0.0 keeps the plain colors
+    pub strength: f32,
+}
+
 impl PlanetGenConfig {
     pub fn load_from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let content = std::fs::read_to_string(path)?;
@@ -212,16 +383,6 @@ impl PlanetGenConfig {
 }
 
 fn reload_config_from_file(path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let new_config = PlanetGenConfig::load_from_file(path)?;
-
-    // Get the config mutex, creating it with the loaded config if it doesn't exist yet
-    let config_mutex = CONFIG.get_or_init(|| {
-        // This should rarely happen since get_config() is usually called first
-        // But if reload is called before get_config, we'll initialize with the loaded config
-        Mutex::new(new_config.clone())
-    });
-
-    // Update the existing config with the newly loaded one
-    *config_mutex.lock().unwrap() = new_config;
+    set_config(PlanetGenConfig::load_from_file(path)?);
     Ok(())
 }
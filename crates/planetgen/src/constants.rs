@@ -1,10 +1,16 @@
-pub const DEBUG_COLORS: &[[f32; 4]] = &[
-    [0.0, 1.0, 0.0, 1.0], // green
-    [0.0, 0.0, 1.0, 1.0], // blue
-    [1.0, 0.0, 1.0, 1.0], // magenta
-    [0.0, 1.0, 1.0, 1.0], // cyan
-    [1.0, 0.5, 0.0, 1.0], // orange
-    [0.5, 0.0, 1.0, 1.0], // violet
-    [0.0, 0.5, 1.0, 1.0], // sky blue
-    [0.5, 1.0, 0.0, 1.0], // lime
+/// Plate colors in the crust type view, picked by plate id so neighbouring plates of the
+/// same type still differ a little
+pub(crate) const CONTINENTAL_CRUST_COLORS: &[[f32; 4]] = &[
+    [0.62, 0.45, 0.28, 1.0], // ochre
+    [0.52, 0.36, 0.22, 1.0], // umber
+    [0.70, 0.55, 0.35, 1.0], // sand
+    [0.58, 0.40, 0.30, 1.0], // terracotta
 ];
+pub(crate) const OCEANIC_CRUST_COLORS: &[[f32; 4]] = &[
+    [0.14, 0.30, 0.55, 1.0], // deep blue
+    [0.18, 0.40, 0.62, 1.0], // steel blue
+    [0.12, 0.36, 0.48, 1.0], // teal
+    [0.22, 0.32, 0.66, 1.0], // indigo
+];
+/// How far microplates are lightened towards white in the crust type view
+pub(crate) const MICROPLATE_BRIGHTEN: f32 = 0.3;
@@ -1,11 +1,9 @@
 //! # Continent Generation System
 //!
-//! This module provides two systems for generating planetary continents:
-//!
-//! ## Simple System (`ContinentNoiseConfig`)
-//! - Two-layer noise: base continent shape + surface detail
-//! - Fast and straightforward
-//! - Good for testing and simple planets
+//! Continents come from a single noise-based generator (`ContinentNoiseConfig`):
+//! - Domain-warped base continent shape + surface detail
+//! - Land and ocean floor get their own profiles, blended just below the coastline
+//! - There is no terraced shelf step, the ocean floor ramps down smoothly from the coast
 
 use crate::config::NoiseConfig;
 use glam::Vec3;
@@ -22,6 +20,10 @@ pub struct ContinentNoiseConfig {
     pub continent_threshold: f32,
     /// Maximum depth variation for oceans (positive value, applied negatively)
     pub ocean_depth_amplitude: f32,
+    /// Detail for the ocean floor (lower frequency and amplitude than the land detail)
+    pub ocean_detail_scale: NoiseConfig,
+    /// Width of the band below the coastline where land and ocean floor profiles are blended
+    pub ocean_detail_blend_width: f32,
 }
 
 impl ContinentNoiseConfig {
@@ -44,6 +46,12 @@ impl ContinentNoiseConfig {
             ),
             continent_threshold: cfg.continent_threshold,
             ocean_depth_amplitude: cfg.ocean_depth_amplitude,
+            ocean_detail_scale: NoiseConfig::new(
+                seed_base.wrapping_add(3),
+                cfg.ocean_detail_frequency,
+                cfg.ocean_detail_amplitude,
+            ),
+            ocean_detail_blend_width: cfg.ocean_detail_blend_width,
         }
     }
 
@@ -56,7 +64,44 @@ impl ContinentNoiseConfig {
     /// 1. Base continent shape (large blobs)
     /// 2. Mid-scale distortion (domain warping to break up round shapes)
     /// 3. Fine detail (coastline roughness)
+    ///
+    /// Land and ocean floor get separate profiles: land uses the fine detail layer, the ocean
+    /// floor a calmer one of its own. Just below the coastline the land profile fades into the
+    /// ocean profile so shelves don't end in a cliff.
     pub fn sample_height(&self, position: Vec3) -> f32 {
+        let (coast_distance, continent_value, detail_value) = self.sample_coast_distance(position);
+        let land_height = self.land_height(coast_distance, detail_value);
+        if coast_distance > 0.0 {
+            return land_height;
+        }
+
+        let ocean_height = self.ocean_floor_height(continent_value, position);
+        if self.ocean_detail_blend_width <= 0.0 {
+            return ocean_height;
+        }
+
+        let t = (-coast_distance / self.ocean_detail_blend_width).clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+        land_height + (ocean_height - land_height) * t
+    }
+
+    /// How far above (positive, land) or below (negative, ocean) the coastline threshold the
+    /// continent noise is at `position`, together with the raw continent value and the detail
+    /// value that roughened the threshold
+    fn sample_coast_distance(&self, position: Vec3) -> (f32, f32, f32) {
+        let continent_value = self.sample_warped_continent(position);
+
+        // Sample detail at original position (fine coastline roughness)
+        let detail_value = self.detail_scale.sample(position);
+
+        // Add detail to threshold for rough coastlines
+        let adjusted_threshold = self.continent_threshold + (detail_value * 0.3);
+
+        (continent_value - adjusted_threshold, continent_value, detail_value)
+    }
+
+    /// Continent noise sampled at a domain-warped position
+    fn sample_warped_continent(&self, position: Vec3) -> f32 {
         // Sample distortion noise for domain warping
         let distortion_value = self.distortion_scale.sample(position);
 
@@ -77,35 +122,27 @@ impl ContinentNoiseConfig {
         let warped_position = (position + warp_offset).normalize();
 
         // Sample continent at warped position (creates torn, irregular shapes)
-        let continent_value = self.continent_scale.sample(warped_position);
-
-        // Sample detail at original position (fine coastline roughness)
-        let detail_value = self.detail_scale.sample(position);
-
-        // Add detail to threshold for rough coastlines
-        let adjusted_threshold = self.continent_threshold + (detail_value * 0.3);
-
-        // Determine if this is continent or ocean based on threshold
-        if continent_value > adjusted_threshold {
-            // CONTINENT: Take the noise value above threshold and scale it
-            // The noise naturally varies, creating elevation changes
-            let height_above_threshold = continent_value - adjusted_threshold;
+        self.continent_scale.sample(warped_position)
+    }
 
-            // Scale by continent amplitude and add fine detail
-            let base_height = height_above_threshold * self.continent_scale.amplitude;
-            let detailed_height = base_height + (detail_value * self.detail_scale.amplitude);
+    /// Land profile: height above the threshold scaled by the continent amplitude, plus fine detail
+    fn land_height(&self, height_above_threshold: f32, detail_value: f32) -> f32 {
+        let base_height = height_above_threshold * self.continent_scale.amplitude;
+        let detailed_height = base_height + (detail_value * self.detail_scale.amplitude);
 
-            detailed_height.max(0.0) // Ensure non-negative for land
-        } else {
-            // OCEAN: Take the noise value below threshold and scale it negatively
-            let depth_below_threshold = adjusted_threshold - continent_value;
+        detailed_height.max(0.0) // Ensure non-negative for land
+    }
 
-            // Scale by ocean depth amplitude and add subtle detail
-            let base_depth = depth_below_threshold * self.ocean_depth_amplitude;
-            let detailed_depth = base_depth + (detail_value * self.detail_scale.amplitude * 0.3);
+    /// Ocean profile: depth below the threshold scaled into trenches, plus the ocean floor detail
+    ///
+    /// Measured from the plain threshold; the rough coastline threshold would carry the land
+    /// detail out into the ocean.
+    fn ocean_floor_height(&self, continent_value: f32, position: Vec3) -> f32 {
+        let depth_below_threshold = self.continent_threshold - continent_value;
+        let base_depth = depth_below_threshold * self.ocean_depth_amplitude;
+        let detailed_depth = base_depth + self.ocean_detail_scale.sample(position);
 
-            -detailed_depth.max(0.0) // Make it negative for ocean depth
-        }
+        -detailed_depth.max(0.0) // Make it negative for ocean depth
     }
 
     /// Get just the continent mask (0.0 = ocean, 1.0 = continent)
@@ -131,3 +168,105 @@ impl ContinentNoiseConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ContinentConfig;
+
+    const SEED: u32 = 7;
+
+    /// Values from planetgen_config.toml
+    fn continent_config() -> ContinentConfig {
+        ContinentConfig {
+            continent_frequency: 1.5,
+            continent_amplitude: 1.0,
+            distortion_frequency: 3.6,
+            distortion_amplitude: 0.22,
+            detail_frequency: 20.0,
+            detail_amplitude: 0.385,
+            continent_threshold: 0.1,
+            ocean_depth_amplitude: 0.8,
+            ocean_detail_frequency: 6.0,
+            ocean_detail_amplitude: 0.03,
+            ocean_detail_blend_width: 0.05,
+        }
+    }
+
+    /// Ocean floor detail with the frequency and strength of the land detail
+    fn land_like_ocean_config() -> ContinentConfig {
+        let cfg = continent_config();
+        ContinentConfig {
+            ocean_detail_frequency: cfg.detail_frequency,
+            ocean_detail_amplitude: cfg.detail_amplitude * cfg.detail_amplitude * 0.3,
+            ..cfg
+        }
+    }
+
+    /// Fibonacci sphere
+    fn sample_directions() -> Vec<Vec3> {
+        let count = 20_000;
+        let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+        (0..count)
+            .map(|i| {
+                let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+                let r = (1.0 - y * y).sqrt();
+                let theta = golden_angle * i as f32;
+                Vec3::new(r * theta.cos(), y, r * theta.sin())
+            })
+            .collect()
+    }
+
+    /// Local height variance of land and open ocean cells (the coastal blend band is skipped)
+    ///
+    /// Uses the second difference over three nearby samples, so the large-scale slope of
+    /// continents and trenches cancels out and only the small-scale noise is left.
+    fn local_variance(noise: &ContinentNoiseConfig) -> (f32, f32) {
+        let (mut land, mut land_count, mut ocean, mut ocean_count) = (0.0, 0, 0.0, 0);
+        for dir in sample_directions() {
+            let offset = dir.any_orthonormal_vector() * 0.01;
+            let points = [(dir - offset).normalize(), dir, (dir + offset).normalize()];
+            let [a, b, c] = points.map(|p| noise.sample_height(p));
+            let diff = (a + c - 2.0 * b).powi(2);
+            let coast_distances = points.map(|p| noise.sample_coast_distance(p).0);
+            if coast_distances.iter().all(|&d| d > 0.0) {
+                land += diff;
+                land_count += 1;
+            } else if coast_distances.iter().all(|&d| d < -noise.ocean_detail_blend_width) {
+                ocean += diff;
+                ocean_count += 1;
+            }
+        }
+        (land / land_count as f32, ocean / ocean_count as f32)
+    }
+
+    #[test]
+    fn test_calm_ocean_floor_keeps_land_unchanged() {
+        let calm = ContinentNoiseConfig::from_config(SEED, &continent_config());
+        let land_like = ContinentNoiseConfig::from_config(SEED, &land_like_ocean_config());
+
+        let (calm_land, calm_ocean) = local_variance(&calm);
+        let (land_like_land, land_like_ocean) = local_variance(&land_like);
+
+        assert_eq!(calm_land, land_like_land);
+        assert!(
+            calm_ocean < land_like_ocean * 0.5,
+            "ocean variance {calm_ocean} vs {land_like_ocean} with the land profile"
+        );
+    }
+
+    #[test]
+    fn test_no_cliff_at_the_coastline() {
+        let noise = ContinentNoiseConfig::from_config(SEED, &continent_config());
+
+        // Walk the equator; every step is tiny so heights may only change a little
+        let steps = 20_000;
+        let mut previous = noise.sample_height(Vec3::X);
+        for i in 1..=steps {
+            let angle = std::f32::consts::TAU * i as f32 / steps as f32;
+            let height = noise.sample_height(Vec3::new(angle.cos(), 0.0, angle.sin()));
+            assert!((height - previous).abs() < 0.05, "jump from {previous} to {height}");
+            previous = height;
+        }
+    }
+}
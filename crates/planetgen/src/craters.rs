@@ -0,0 +1,199 @@
+//! Impact craters for airless, dead worlds
+//!
+//! Craters are placed at random directions with a power-law size distribution: many small ones,
+//! few big ones. Each one is stamped onto the heightmap by angular distance from its center, so
+//! a crater crossing a cube face edge continues seamlessly on the next face.
+
+use crate::config::CraterConfig;
+use crate::generator::cube_face_point;
+use crate::planet::CubeFace;
+use glam::Vec3;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// What the surface of a planet is made of
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorldType {
+    /// Continents and oceans from the continent noise
+    #[default]
+    Terran,
+    /// Airless rock covered in impact craters, no oceans
+    Barren,
+}
+
+/// One impact, sizes as angles so they don't depend on the planet radius
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crater {
+    pub center: Vec3,
+    /// Angular radius of the rim in radians
+    pub radius: f32,
+}
+
+/// Number of craters on the whole sphere for a density in craters per steradian
+pub fn crater_count(config: &CraterConfig) -> usize {
+    (config.density.max(0.0) * 4.0 * std::f32::consts::PI).round() as usize
+}
+
+/// Random craters with radii between the configured bounds. The number of craters larger than
+/// `r` falls off as `r^-size_exponent`, sampled by inverting that distribution.
+pub fn generate_craters(rng: &mut impl Rng, config: &CraterConfig) -> Vec<Crater> {
+    let min = config.min_radius_deg.max(0.01).to_radians();
+    let max = config.max_radius_deg.to_radians().max(min);
+    let k = config.size_exponent.max(0.01);
+
+    (0..crater_count(config))
+        .map(|_| {
+            let center = loop {
+                let candidate = Vec3::new(
+                    rng.random_range(-1.0..1.0),
+                    rng.random_range(-1.0..1.0),
+                    rng.random_range(-1.0..1.0),
+                );
+                // Rejection sampling inside the unit ball gives uniform directions
+                let length_squared = candidate.length_squared();
+                if length_squared > 1e-6 && length_squared <= 1.0 {
+                    break candidate.normalize();
+                }
+            };
+            let u: f32 = rng.random_range(0.0..1.0);
+            let radius = (min.powf(-k) - u * (min.powf(-k) - max.powf(-k))).powf(-1.0 / k);
+            Crater { center, radius }
+        })
+        .collect()
+}
+
+/// Height of the crater profile at `r`, the distance from the center in rim radii, relative to
+/// the bowl depth: -1 at the floor, `rim_height` on the rim, fading to 0 at `ejecta_extent`.
+/// `rim_sharpness` (0 to 1) flattens the floor, steepens the walls and shortens the apron.
+pub fn crater_profile(r: f32, config: &CraterConfig) -> f32 {
+    let sharpness = config.rim_sharpness.clamp(0.0, 1.0);
+    let exponent = 2.0 + 4.0 * sharpness;
+    let rim = config.rim_height;
+
+    if r <= 1.0 {
+        // Bowl rising from the floor to the rim crest
+        -1.0 + (1.0 + rim) * r.max(0.0).powf(exponent)
+    } else {
+        // Ejecta apron falling off from the rim crest
+        let extent = config.ejecta_extent.max(1.0 + f32::EPSILON);
+        let t = ((r - 1.0) / (extent - 1.0)).clamp(0.0, 1.0);
+        rim * (1.0 - t).powf(exponent)
+    }
+}
+
+/// Add every crater to the heightmap. `radius` is the planet radius, the bowl depth is
+/// `depth_ratio` times the crater radius in world units.
+pub fn apply_craters(
+    faces: &mut [CubeFace; 6],
+    face_grid_size: usize,
+    craters: &[Crater],
+    radius: f32,
+    config: &CraterConfig,
+) {
+    let extent = config.ejecta_extent.max(1.0);
+    // Angular reach of each crater, the dot product lets most cells skip the acos
+    let reach: Vec<f32> = craters
+        .iter()
+        .map(|crater| (crater.radius * extent).min(std::f32::consts::PI).cos())
+        .collect();
+
+    for (face_idx, face) in faces.iter_mut().enumerate() {
+        for y in 0..face_grid_size {
+            let v = y as f32 / (face_grid_size - 1) as f32 * 2.0 - 1.0;
+            for x in 0..face_grid_size {
+                let u = x as f32 / (face_grid_size - 1) as f32 * 2.0 - 1.0;
+                let dir = Vec3::from(cube_face_point(face_idx, u, v)).normalize();
+
+                let mut height = 0.0;
+                for (crater, &min_dot) in craters.iter().zip(&reach) {
+                    let dot = dir.dot(crater.center);
+                    if dot < min_dot {
+                        continue;
+                    }
+                    let angle = dot.clamp(-1.0, 1.0).acos();
+                    let depth = crater.radius * radius * config.depth_ratio;
+                    height += crater_profile(angle / crater.radius, config) * depth;
+                }
+                face.heightmap[y][x] += height;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid2D;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn config() -> CraterConfig {
+        CraterConfig {
+            density: 10.0,
+            min_radius_deg: 1.0,
+            max_radius_deg: 20.0,
+            size_exponent: 2.0,
+            depth_ratio: 0.1,
+            rim_height: 0.3,
+            rim_sharpness: 0.5,
+            ejecta_extent: 2.0,
+            base_height: 0.3,
+            roughness_frequency: 4.0,
+            roughness_amplitude: 0.1,
+            craters_on_terran: false,
+        }
+    }
+
+    #[test]
+    fn test_profile_has_a_floor_a_rim_and_fades_out() {
+        let config = config();
+
+        assert_eq!(crater_profile(0.0, &config), -1.0);
+        assert!((crater_profile(1.0, &config) - config.rim_height).abs() < 1e-5);
+        assert!((crater_profile(1.0 + 1e-4, &config) - config.rim_height).abs() < 1e-3);
+        assert_eq!(crater_profile(config.ejecta_extent, &config), 0.0);
+        assert_eq!(crater_profile(10.0, &config), 0.0);
+    }
+
+    #[test]
+    fn test_sizes_stay_in_range_and_small_craters_dominate() {
+        let config = config();
+        let mut rng = StdRng::seed_from_u64(3);
+        let craters = generate_craters(&mut rng, &config);
+
+        assert_eq!(craters.len(), crater_count(&config));
+        let (min, max) = (config.min_radius_deg.to_radians(), config.max_radius_deg.to_radians());
+        assert!(craters.iter().all(|c| c.radius >= min - 1e-6 && c.radius <= max + 1e-6));
+        let small = craters.iter().filter(|c| c.radius < min * 2.0).count();
+        assert!(small * 2 > craters.len(), "only {small} of {} are small", craters.len());
+    }
+
+    #[test]
+    fn test_crater_on_a_face_edge_is_continuous_across_it() {
+        let config = config();
+        let n = 65;
+        let blank = CubeFace {
+            heightmap: Grid2D::new(n, n, 0.0),
+        };
+        let mut faces = std::array::from_fn(|_| blank.clone());
+
+        // Centered on a corner where three faces meet
+        let crater = Crater {
+            center: Vec3::ONE.normalize(),
+            radius: 10f32.to_radians(),
+        };
+        apply_craters(&mut faces, n, &[crater], 50.0, &config);
+
+        // Every face sees the same bowl at the same angular distance, so the floor is equally
+        // deep on all faces touching the corner
+        let deepest: Vec<f32> = faces
+            .iter()
+            .map(|face| face.heightmap.iter().copied().fold(0.0, f32::min))
+            .filter(|&depth| depth < 0.0)
+            .collect();
+        assert_eq!(deepest.len(), 3);
+        for depth in &deepest {
+            assert!((depth - deepest[0]).abs() < 1e-3, "{deepest:?}");
+        }
+    }
+}
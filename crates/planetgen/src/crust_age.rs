@@ -0,0 +1,185 @@
+//! Age of the oceanic crust
+//!
+//! New ocean floor forms at divergent boundaries (mid-ocean ridges) and moves away from them,
+//! so the distance from the nearest ridge stands in for how old the floor is. Old crust has
+//! cooled down and sits deeper, which the generator can add as extra subsidence.
+
+use crate::boundaries::{BoundaryData, BoundaryType};
+use crate::cubemap_utils::{all_cells, nearest_cell, neighbors};
+use crate::palette::{Palette, lerp_stops};
+use crate::planet::{PlateMap, PlateType};
+use crate::plate::TectonicPlate;
+use glam::Vec3;
+use std::collections::VecDeque;
+
+/// Crust age of every cell on the planet
+#[derive(Clone)]
+pub struct CrustAgeMap {
+    /// 0 on a ridge up to 1 for the oldest floor, None on continental plates
+    pub age: [Vec<Vec<Option<f32>>>; 6],
+    face_grid_size: usize,
+}
+
+impl CrustAgeMap {
+    /// A planet without oceanic crust
+    pub fn empty(face_grid_size: usize) -> Self {
+        Self {
+            age: std::array::from_fn(|_| vec![vec![None; face_grid_size]; face_grid_size]),
+            face_grid_size,
+        }
+    }
+
+    /// Breadth-first distance from every divergent boundary cell of an oceanic plate, normalized
+    /// by the largest distance on the planet. The distance grows inside the plate the ridge
+    /// belongs to and stops at the next plate boundary. Oceanic plates without a ridge get the
+    /// oldest age.
+    pub fn calculate(
+        face_grid_size: usize,
+        plate_map: &PlateMap,
+        plates: &[TectonicPlate],
+        boundary_data: &BoundaryData,
+    ) -> Self {
+        let n = face_grid_size;
+        let oceanic = |plate_id: usize| plates.get(plate_id).is_some_and(|plate| plate.plate_type == PlateType::Oceanic);
+
+        let mut steps = std::array::from_fn::<_, 6, _>(|_| vec![vec![None; n]; n]);
+        let mut queue = VecDeque::new();
+        for (face, x, y) in all_cells(n) {
+            let on_ridge = boundary_data.boundary_distances[face][y][x] == 0.0
+                && boundary_data.boundaries[face][y][x] == Some(BoundaryType::Divergent);
+            if on_ridge && oceanic(plate_map[face][y][x]) {
+                steps[face][y][x] = Some(0usize);
+                queue.push_back((face, x, y));
+            }
+        }
+
+        while let Some(cell) = queue.pop_front() {
+            let (face, x, y) = cell;
+            let plate = plate_map[face][y][x];
+            let step = steps[face][y][x].unwrap_or(0);
+            for (nf, nx, ny) in neighbors(cell, n) {
+                if plate_map[nf][ny][nx] != plate || steps[nf][ny][nx].is_some() {
+                    continue;
+                }
+                steps[nf][ny][nx] = Some(step + 1);
+                queue.push_back((nf, nx, ny));
+            }
+        }
+
+        let oldest = steps.iter().flatten().flatten().flatten().copied().max().unwrap_or(0).max(1) as f32;
+        let age = std::array::from_fn(|face| {
+            (0..n)
+                .map(|y| {
+                    (0..n)
+                        .map(|x| {
+                            oceanic(plate_map[face][y][x])
+                                .then(|| steps[face][y][x].map_or(1.0, |step| step as f32 / oldest))
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+
+        Self { age, face_grid_size: n }
+    }
+
+    pub fn age_at(&self, face: usize, x: usize, y: usize) -> Option<f32> {
+        self.age[face][y][x]
+    }
+
+    /// Crust age of the cell closest to `dir`
+    pub fn age_in_direction(&self, dir: Vec3) -> Option<f32> {
+        let (face, x, y) = nearest_cell(dir, self.face_grid_size);
+        self.age_at(face, x, y)
+    }
+}
+
+/// Overlay color of a crust age: red at the ridges through yellow and green to blue for the
+/// oldest floor, gray for continental crust. Other palettes run from new to old floor.
+pub fn crust_age_to_color(age: Option<f32>, palette: Palette) -> Vec3 {
+    let Some(age) = age else {
+        return Vec3::splat(0.55);
+    };
+    if let Some(color) = palette.sequential(age) {
+        return color;
+    }
+    let stops = [
+        Vec3::new(0.85, 0.15, 0.10),
+        Vec3::new(0.95, 0.80, 0.20),
+        Vec3::new(0.30, 0.70, 0.35),
+        Vec3::new(0.10, 0.25, 0.65),
+    ];
+    lerp_stops(&stops, age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid2D;
+    use crate::planet::PlateSizeClass;
+
+    const N: usize = 17;
+
+    fn plate(id: usize, plate_type: PlateType) -> TectonicPlate {
+        TectonicPlate {
+            id,
+            direction: Vec3::X,
+            angular_velocity: Vec3::ZERO,
+            center: Vec3::X,
+            size_class: PlateSizeClass::Regular,
+            plate_type,
+            debug_color: [0.0; 4],
+        }
+    }
+
+    /// Plate 1 is the +Z face, plate 0 everything else, with a ridge along x = 0 of the +Z face
+    fn ridge_on_plus_z() -> (PlateMap, BoundaryData) {
+        let plate_map = (0..6).map(|face| Grid2D::new(N, N, usize::from(face == 4))).collect();
+        let mut boundary_data = BoundaryData {
+            boundaries: std::array::from_fn(|_| vec![vec![None; N]; N]),
+            boundary_distances: std::array::from_fn(|_| vec![vec![f32::INFINITY; N]; N]),
+            band_width: 0.1,
+        };
+        for y in 0..N {
+            boundary_data.boundaries[4][y][0] = Some(BoundaryType::Divergent);
+            boundary_data.boundary_distances[4][y][0] = 0.0;
+        }
+        (plate_map, boundary_data)
+    }
+
+    #[test]
+    fn test_age_grows_away_from_the_ridge_up_to_the_next_boundary() {
+        let (plate_map, boundary_data) = ridge_on_plus_z();
+        let plates = [plate(0, PlateType::Continental), plate(1, PlateType::Oceanic)];
+
+        let ages = CrustAgeMap::calculate(N, &plate_map, &plates, &boundary_data);
+
+        let row: Vec<f32> = (0..N).map(|x| ages.age_at(4, x, 8).unwrap()).collect();
+        assert_eq!(row[0], 0.0);
+        assert!(row.windows(2).all(|pair| pair[0] < pair[1]), "{row:?}");
+        assert_eq!(row[N - 1], 1.0);
+    }
+
+    #[test]
+    fn test_continental_plates_have_no_age() {
+        let (plate_map, boundary_data) = ridge_on_plus_z();
+        let plates = [plate(0, PlateType::Continental), plate(1, PlateType::Oceanic)];
+
+        let ages = CrustAgeMap::calculate(N, &plate_map, &plates, &boundary_data);
+
+        assert_eq!(ages.age_at(0, 8, 8), None);
+        assert_eq!(ages.age_in_direction(Vec3::NEG_Z), None);
+        assert!(ages.age_in_direction(Vec3::Z).is_some());
+    }
+
+    #[test]
+    fn test_oceanic_plate_without_a_ridge_is_oldest() {
+        let (plate_map, boundary_data) = ridge_on_plus_z();
+        let plates = [plate(0, PlateType::Oceanic), plate(1, PlateType::Oceanic)];
+
+        let ages = CrustAgeMap::calculate(N, &plate_map, &plates, &boundary_data);
+
+        // Plate 0 doesn't own the ridge cells, so none of its crust is young
+        assert_eq!(ages.age_at(1, 8, 8), Some(1.0));
+    }
+}
@@ -0,0 +1,203 @@
+//! Vertex numbering of a cube sphere mesh
+//!
+//! Every face has `size × size` grid points, and the points on the 12 cube edges and 8 corners
+//! are shared by the faces meeting there. Each shared point belongs to the lowest numbered face
+//! it lies on and gets its vertex index there; the other faces look the index up. No directions
+//! are compared, so two distinct points can never be merged however coarse the grid is.
+//!
+//! A split grid gives every face its own copy of its edge points instead, for attributes like
+//! texture coordinates that differ between the faces meeting at an edge.
+
+/// Vertex indices of every grid point of the six faces, edge and corner points shared
+pub struct StitchedGrid {
+    size: usize,
+    /// Vertex index of `(face, x, y)` at `(face * size + y) * size + x`
+    indices: Vec<u32>,
+    /// The grid point each vertex was created from, in vertex index order
+    vertices: Vec<(usize, usize, usize)>,
+}
+
+/// Number of distinct vertices of a cube sphere grid with `size` points along each face edge:
+/// `6·(n−2)²` face interiors, `12·(n−2)` edge points and the 8 corners
+pub fn stitched_vertex_count(size: usize) -> usize {
+    let inner = size.saturating_sub(2);
+    6 * inner * inner + 12 * inner + 8
+}
+
+impl StitchedGrid {
+    /// Number the grid points of a cube sphere with `size` (at least 2) points along each face edge
+    pub fn new(size: usize) -> Self {
+        assert!(size >= 2, "a cube face needs at least its corners");
+        let mut indices = vec![0u32; 6 * size * size];
+        let mut vertices = Vec::with_capacity(stitched_vertex_count(size));
+
+        for face in 0..6 {
+            for y in 0..size {
+                for x in 0..size {
+                    let owner = owning_point(face, x, y, size);
+                    let index = if owner == (face, x, y) {
+                        vertices.push(owner);
+                        (vertices.len() - 1) as u32
+                    } else {
+                        // The owner is a lower face, numbered already
+                        let (owner_face, owner_x, owner_y) = owner;
+                        indices[(owner_face * size + owner_y) * size + owner_x]
+                    };
+                    indices[(face * size + y) * size + x] = index;
+                }
+            }
+        }
+
+        Self {
+            size,
+            indices,
+            vertices,
+        }
+    }
+
+    /// Number the grid points of the six faces without sharing any, `6 · size²` vertices in face
+    /// and row order
+    pub fn split(size: usize) -> Self {
+        assert!(size >= 2, "a cube face needs at least its corners");
+        let vertices: Vec<(usize, usize, usize)> = (0..6)
+            .flat_map(|face| (0..size).flat_map(move |y| (0..size).map(move |x| (face, x, y))))
+            .collect();
+
+        Self {
+            size,
+            indices: (0..vertices.len() as u32).collect(),
+            vertices,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Vertex index of the grid point `x, y` of `face`
+    pub fn index(&self, face: usize, x: usize, y: usize) -> u32 {
+        self.indices[(face * self.size + y) * self.size + x]
+    }
+
+    /// The grid point each vertex belongs to, as `(face, x, y)` in vertex index order
+    pub fn vertices(&self) -> &[(usize, usize, usize)] {
+        &self.vertices
+    }
+
+    /// The same point as `x, y` of `face` on the lowest numbered face it lies on. Copies of a
+    /// split grid read their attributes there, so the faces still meet without a crack.
+    pub fn owner(&self, face: usize, x: usize, y: usize) -> (usize, usize, usize) {
+        owning_point(face, x, y, self.size)
+    }
+
+    /// Two triangles per grid cell of every face
+    pub fn triangle_indices(&self) -> Vec<u32> {
+        let n = self.size;
+        let mut indices = Vec::with_capacity(6 * (n - 1) * (n - 1) * 6);
+        for face in 0..6 {
+            for y in 0..n - 1 {
+                for x in 0..n - 1 {
+                    let i0 = self.index(face, x, y);
+                    let i1 = self.index(face, x + 1, y);
+                    let i2 = self.index(face, x, y + 1);
+                    let i3 = self.index(face, x + 1, y + 1);
+                    indices.extend_from_slice(&[i0, i1, i2, i1, i3, i2]);
+                }
+            }
+        }
+        indices
+    }
+}
+
+/// The grid point that owns the point `x, y` of `face`: the same point on the lowest numbered
+/// face it lies on. Works on the integer cube corner coordinates `0..=size-1` of
+/// `cube_face_point`, so points on shared edges match exactly.
+fn owning_point(face: usize, x: usize, y: usize, size: usize) -> (usize, usize, usize) {
+    let last = size - 1;
+    // Integer version of `cube_face_point`, where u = x / last * 2 - 1 turns into x and -u into last - x
+    let (cx, cy, cz) = match face {
+        0 => (last, y, last - x),
+        1 => (0, y, x),
+        2 => (x, last, last - y),
+        3 => (x, 0, y),
+        4 => (x, y, last),
+        _ => (last - x, y, 0),
+    };
+
+    if cx == last {
+        (0, last - cz, cy)
+    } else if cx == 0 {
+        (1, cz, cy)
+    } else if cy == last {
+        (2, cx, last - cz)
+    } else if cy == 0 {
+        (3, cx, cz)
+    } else if cz == last {
+        (4, cx, cy)
+    } else {
+        (5, last - cx, cy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::cell_direction;
+
+    #[test]
+    fn test_every_edge_and_corner_point_is_one_vertex() {
+        for size in [2, 3, 4, 7, 33] {
+            let grid = StitchedGrid::new(size);
+
+            assert_eq!(grid.vertices().len(), stitched_vertex_count(size), "size {size}");
+            // Grid points with the same direction share a vertex, different ones never do
+            for face in 0..6 {
+                for y in 0..size {
+                    for x in 0..size {
+                        let (owner_face, owner_x, owner_y) = grid.vertices()[grid.index(face, x, y) as usize];
+                        let distance = cell_direction(size, face, x, y)
+                            .distance(cell_direction(size, owner_face, owner_x, owner_y));
+                        assert!(distance < 1e-6, "size {size}: {face} {x} {y} merged with a distinct point");
+                    }
+                }
+            }
+            let mut directions: Vec<[i64; 3]> = grid
+                .vertices()
+                .iter()
+                .map(|&(face, x, y)| {
+                    let dir = cell_direction(size, face, x, y);
+                    [dir.x, dir.y, dir.z].map(|c| (c * 1e6).round() as i64)
+                })
+                .collect();
+            directions.sort_unstable();
+            directions.dedup();
+            assert_eq!(directions.len(), grid.vertices().len(), "size {size} has duplicate vertices");
+        }
+    }
+
+    #[test]
+    fn test_split_grid_gives_every_face_its_own_edges() {
+        let grid = StitchedGrid::split(4);
+
+        assert_eq!(grid.vertices().len(), 6 * 4 * 4);
+        for (index, &(face, x, y)) in grid.vertices().iter().enumerate() {
+            assert_eq!(grid.index(face, x, y), index as u32);
+        }
+        // Both copies of a shared corner point at the same owner
+        assert_eq!(grid.owner(1, 3, 0), grid.owner(4, 0, 0));
+        assert_ne!(grid.index(1, 3, 0), grid.index(4, 0, 0));
+    }
+
+    #[test]
+    fn test_owned_points_come_first_in_face_order() {
+        let grid = StitchedGrid::new(5);
+
+        // Face 0 owns all of its points, so its vertices are numbered row by row
+        for y in 0..5 {
+            for x in 0..5 {
+                assert_eq!(grid.index(0, x, y), (y * 5 + x) as u32);
+            }
+        }
+        assert!(grid.vertices().windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    }
+}
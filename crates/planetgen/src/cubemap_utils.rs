@@ -1,24 +1,39 @@
-/// Shared utilities for cube map operations, including cross-face blurring.
+/// Shared utilities for cube map operations: cross-face blurring and bilinear sampling.
+///
+/// All cube maps in this crate store texels at u, v = x / (resolution - 1) * 2 - 1, so the
+/// outermost texel rows lie exactly on the cube edges and are stored once per adjacent face.
 
+use crate::generator::cell_direction;
+use crate::grid::Grid2D;
 use crate::wind::velocity::{cube_face_point, direction_to_cube_uv};
+use glam::Vec3;
+use std::collections::HashMap;
+use std::ops::{Add, Mul};
 
-/// Read a pixel from a cubemap face, even if x/y are outside the face bounds.
+/// Read a texel from a cubemap face, even if x/y are outside the face bounds.
 ///
 /// If x/y are within the face, just return the value directly.
 /// If x/y are outside (e.g. x = -1), figure out which neighboring face
-/// that pixel belongs to and read from there instead.
-/// This is needed for blurring: edge pixels need to average with their
-/// neighbors, which may be on a different face of the cube.
-fn sample_cross_face(
-    faces: &[Vec<Vec<f32>>; 6],
+/// that texel belongs to and read from there instead.
+/// This is needed for blurring and sampling near edges: edge texels need to
+/// combine with their neighbors, which may be on a different face of the cube.
+fn fetch_cross_face<T>(
+    fetch: &impl Fn(usize, usize, usize) -> T,
     face_idx: usize,
     x: i32,
     y: i32,
     resolution: usize,
-) -> f32 {
+) -> T {
+    let (face, x, y) = cross_face_texel(face_idx, x, y, resolution);
+    fetch(face, x, y)
+}
+
+/// The texel at x/y of a face, where x/y may lie outside the face by a few texels.
+/// Returns `(face, x, y)` of the texel that actually covers that spot on the sphere.
+pub(crate) fn cross_face_texel(face_idx: usize, x: i32, y: i32, resolution: usize) -> (usize, usize, usize) {
     let res = resolution as i32;
     if x >= 0 && x < res && y >= 0 && y < res {
-        return faces[face_idx][y as usize][x as usize];
+        return (face_idx, x as usize, y as usize);
     }
 
     // Out of bounds: convert to UV, then to 3D, then back to the correct face
@@ -34,21 +49,129 @@ fn sample_cross_face(
     let nx = nx.min(resolution - 1);
     let ny = ny.min(resolution - 1);
 
-    faces[neighbor_face][ny][nx]
+    (neighbor_face, nx, ny)
+}
+
+/// Every cell of every face as `(face, x, y)`
+pub(crate) fn all_cells(n: usize) -> impl Iterator<Item = (usize, usize, usize)> {
+    (0..6).flat_map(move |face| (0..n).flat_map(move |y| (0..n).map(move |x| (face, x, y))))
+}
+
+/// The cell closest to the direction `dir`, as `(face, x, y)`
+pub(crate) fn nearest_cell(dir: Vec3, n: usize) -> (usize, usize, usize) {
+    let (face, u, v) = direction_to_cube_uv(dir.normalize());
+    let last = (n - 1) as f32;
+    let x = (((u + 1.0) * 0.5) * last).round().clamp(0.0, last) as usize;
+    let y = (((v + 1.0) * 0.5) * last).round().clamp(0.0, last) as usize;
+    (face, x, y)
+}
+
+/// The 4 neighbours of a cell, continuing onto the adjacent face at face edges
+pub(crate) fn neighbors(cell: (usize, usize, usize), n: usize) -> impl Iterator<Item = (usize, usize, usize)> {
+    let (face, x, y) = cell;
+    [(1, 0), (0, 1), (-1, 0), (0, -1)]
+        .into_iter()
+        .map(move |(dx, dy)| cross_face_texel(face, x as i32 + dx, y as i32 + dy, n))
+}
+
+fn sample_cross_face(faces: &[Grid2D<f32>; 6], face_idx: usize, x: i32, y: i32, resolution: usize) -> f32 {
+    fetch_cross_face(&|f, x, y| faces[f][(x, y)], face_idx, x, y, resolution)
+}
+
+/// Bilinearly sample a cube map in the direction `dir`.
+///
+/// `fetch(face, x, y)` reads a single texel. When the 2×2 footprint crosses a face edge the
+/// out-of-face texels are read from the neighbouring face, so sampled values are continuous
+/// over edges and corners instead of clamping to the edge of one face.
+pub(crate) fn sample_bilinear<T>(resolution: usize, dir: Vec3, fetch: impl Fn(usize, usize, usize) -> T) -> T
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    let (face_idx, u, v) = direction_to_cube_uv(dir.normalize());
+
+    // Convert u,v from [-1, 1] to grid coordinates [0, resolution-1]
+    let last = (resolution - 1) as f32;
+    let fx = (((u + 1.0) * 0.5) * last).clamp(0.0, last);
+    let fy = (((v + 1.0) * 0.5) * last).clamp(0.0, last);
+
+    let x0 = fx.floor() as i32;
+    let y0 = fy.floor() as i32;
+    let tx = fx - x0 as f32;
+    let ty = fy - y0 as f32;
+
+    let texel = |x: i32, y: i32| fetch_cross_face(&fetch, face_idx, x, y, resolution);
+    let v00 = texel(x0, y0);
+    let v10 = texel(x0 + 1, y0);
+    let v01 = texel(x0, y0 + 1);
+    let v11 = texel(x0 + 1, y0 + 1);
+
+    let v0 = v00 * (1.0 - tx) + v10 * tx;
+    let v1 = v01 * (1.0 - tx) + v11 * tx;
+    v0 * (1.0 - ty) + v1 * ty
+}
+
+/// Faces of a cube map at `new_resolution`, every texel bilinearly sampled from the map at
+/// `resolution` that `fetch(face, x, y)` reads. Works for both directions, so a map can follow
+/// a resolution change without being built again. Edge texels land on the same point of the
+/// sphere on every face that stores them, so they stay welded.
+pub(crate) fn resample_faces<T>(
+    resolution: usize,
+    new_resolution: usize,
+    fetch: impl Fn(usize, usize, usize) -> T,
+) -> [Grid2D<T>; 6]
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    std::array::from_fn(|face_idx| {
+        Grid2D::from_fn(new_resolution, new_resolution, |x, y| {
+            sample_bilinear(resolution, cell_direction(new_resolution, face_idx, x, y), &fetch)
+        })
+    })
+}
+
+/// Make every texel that lies on a cube edge hold the same value on all faces that store it.
+///
+/// Edge texels are computed separately per face (e.g. by blurring with a different neighbourhood),
+/// so their copies can drift apart and show up as a seam. Averaging the copies removes it.
+pub(crate) fn weld_cube_edges(faces: &mut [Grid2D<f32>; 6], resolution: usize) {
+    let last = resolution - 1;
+    let is_edge = |x: usize, y: usize| x == 0 || y == 0 || x == last || y == last;
+    let key = |face_idx: usize, x: usize, y: usize| {
+        let u = (x as f32 / last as f32) * 2.0 - 1.0;
+        let v = (y as f32 / last as f32) * 2.0 - 1.0;
+        let p = (cube_face_point(face_idx, u, v) + Vec3::ONE) * 0.5 * last as f32;
+        (p.x.round() as i32, p.y.round() as i32, p.z.round() as i32)
+    };
+
+    let mut sums: HashMap<(i32, i32, i32), (f32, u32)> = HashMap::new();
+    for (face_idx, face) in faces.iter().enumerate() {
+        for (y, row) in face.rows().enumerate() {
+            for (x, value) in row.iter().enumerate() {
+                if is_edge(x, y) {
+                    let entry = sums.entry(key(face_idx, x, y)).or_insert((0.0, 0));
+                    entry.0 += value;
+                    entry.1 += 1;
+                }
+            }
+        }
+    }
+
+    for (face_idx, face) in faces.iter_mut().enumerate() {
+        for (y, row) in face.rows_mut().enumerate() {
+            for (x, value) in row.iter_mut().enumerate() {
+                if is_edge(x, y) {
+                    let (sum, count) = sums[&key(face_idx, x, y)];
+                    *value = sum / count as f32;
+                }
+            }
+        }
+    }
 }
 
 /// Apply a single box blur pass across all 6 cube faces with cross-face sampling.
 /// Edge and corner pixels correctly sample from neighboring faces.
-pub fn blur_cube_faces(faces: &[Vec<Vec<f32>>; 6], resolution: usize) -> [Vec<Vec<f32>>; 6] {
-    let blank = vec![vec![0.0f32; resolution]; resolution];
-    let mut out = [
-        blank.clone(),
-        blank.clone(),
-        blank.clone(),
-        blank.clone(),
-        blank.clone(),
-        blank,
-    ];
+pub(crate) fn blur_cube_faces(faces: &[Grid2D<f32>; 6], resolution: usize) -> [Grid2D<f32>; 6] {
+    let mut out: [Grid2D<f32>; 6] = std::array::from_fn(|_| Grid2D::new(resolution, resolution, 0.0));
 
     for face_idx in 0..6 {
         for y in 0..resolution {
@@ -65,10 +188,123 @@ pub fn blur_cube_faces(faces: &[Vec<Vec<f32>>; 6], resolution: usize) -> [Vec<Ve
                         );
                     }
                 }
-                out[face_idx][y][x] = sum / 9.0;
+                out[face_idx][(x, y)] = sum / 9.0;
             }
         }
     }
 
+    weld_cube_edges(&mut out, resolution);
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOLUTION: usize = 17;
+
+    /// A smooth field with a different value at every point of the sphere
+    fn smooth_field(dir: Vec3) -> f32 {
+        dir.x * 0.7 + dir.y * 1.3 - dir.z * 0.4 + dir.x * dir.y
+    }
+
+    fn build_faces(field: impl Fn(Vec3) -> f32) -> [Grid2D<f32>; 6] {
+        std::array::from_fn(|face_idx| {
+            Grid2D::from_fn(RESOLUTION, RESOLUTION, |x, y| {
+                let u = (x as f32 / (RESOLUTION - 1) as f32) * 2.0 - 1.0;
+                let v = (y as f32 / (RESOLUTION - 1) as f32) * 2.0 - 1.0;
+                field(cube_face_point(face_idx, u, v).normalize())
+            })
+        })
+    }
+
+    fn sample(faces: &[Grid2D<f32>; 6], dir: Vec3) -> f32 {
+        sample_bilinear(RESOLUTION, dir, |f, x, y| faces[f][(x, y)])
+    }
+
+    /// Walk a great circle and return the largest jump between consecutive samples
+    fn max_jump_along(faces: &[Grid2D<f32>; 6], axis: Vec3, start: Vec3) -> f32 {
+        let steps = 4000;
+        let mut previous = sample(faces, start);
+        let mut max_jump = 0.0f32;
+        for i in 1..=steps {
+            let angle = std::f32::consts::TAU * i as f32 / steps as f32;
+            let dir = glam::Quat::from_axis_angle(axis, angle) * start;
+            let value = sample(faces, dir);
+            max_jump = max_jump.max((value - previous).abs());
+            previous = value;
+        }
+        max_jump
+    }
+
+    #[test]
+    fn test_sampling_is_continuous_over_edges_and_poles() {
+        let faces = build_faces(smooth_field);
+
+        // Through both poles (crosses ±Y face centers and four face edges)
+        let meridian = max_jump_along(&faces, Vec3::Z, Vec3::X);
+        // Diagonal circle crossing cube corners
+        let diagonal = max_jump_along(&faces, Vec3::new(1.0, -1.0, 0.0).normalize(), Vec3::new(1.0, 1.0, 1.0).normalize());
+
+        // A step of TAU / 4000 on a field with gradient < 3 changes it by less than 0.005
+        assert!(meridian < 0.01, "jump of {meridian} along the meridian");
+        assert!(diagonal < 0.01, "jump of {diagonal} along the diagonal");
+    }
+
+    #[test]
+    fn test_sampling_matches_field_near_corners() {
+        let faces = build_faces(smooth_field);
+        let corner = Vec3::new(1.0, 1.0, 1.0).normalize();
+
+        for offset in [Vec3::new(0.01, 0.0, 0.0), Vec3::new(0.0, -0.01, 0.01), Vec3::ZERO] {
+            let dir = (corner + offset).normalize();
+            assert!((sample(&faces, dir) - smooth_field(dir)).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_resampling_to_the_same_resolution_keeps_the_values() {
+        let faces = build_faces(smooth_field);
+        let resampled = resample_faces(RESOLUTION, RESOLUTION, |f, x, y| faces[f][(x, y)]);
+
+        for (face, resampled_face) in faces.iter().zip(&resampled) {
+            for (value, resampled_value) in face.iter().zip(resampled_face.iter()) {
+                assert!((value - resampled_value).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_downsample_then_upsample_stays_close_to_a_smooth_field() {
+        let faces = build_faces(smooth_field);
+        let coarse = resample_faces(RESOLUTION, 9, |f, x, y| faces[f][(x, y)]);
+        let restored = resample_faces(9, RESOLUTION, |f, x, y| coarse[f][(x, y)]);
+
+        // A texel of the coarse map spans twice the angle, bilinear interpolation of a field with
+        // a gradient below 3 and little curvature is off by a few hundredths at most
+        let max_error = faces
+            .iter()
+            .zip(&restored)
+            .flat_map(|(face, restored_face)| face.iter().zip(restored_face.iter()))
+            .map(|(value, restored_value)| (value - restored_value).abs())
+            .fold(0.0f32, f32::max);
+        assert!(max_error < 0.05, "round trip is off by {max_error}");
+    }
+
+    #[test]
+    fn test_blur_keeps_edge_copies_identical() {
+        let mut faces = build_faces(smooth_field);
+        for _ in 0..3 {
+            faces = blur_cube_faces(&faces, RESOLUTION);
+        }
+
+        let last = RESOLUTION - 1;
+        // +X face right edge (u = 1) is the -Z face left edge (u = -1)
+        for y in 0..RESOLUTION {
+            assert_eq!(faces[0][y][last], faces[5][y][0]);
+        }
+
+        let meridian = max_jump_along(&faces, Vec3::Z, Vec3::X);
+        assert!(meridian < 0.01, "jump of {meridian} after blurring");
+    }
+}
@@ -0,0 +1,356 @@
+//! Landmasses, ocean basins and mountain ranges of a planet, with names
+//!
+//! Features are regions of connected cells, connected across face edges too. Each gets a
+//! direction to put its label at, a cell of the region itself so a bent continent doesn't get
+//! its label in the sea.
+
+use crate::cubemap_utils::{all_cells, neighbors};
+use crate::generator::cell_direction;
+use crate::names::NameGenerator;
+use crate::planet::PlanetData;
+use crate::tools::sphere::solid_angle_of_cell;
+use glam::Vec3;
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+/// Mixed into the planet seed so the names don't follow the terrain noise
+const NAME_SEED_SALT: u64 = 0x6e61_6d65_7321;
+
+pub(crate) type Cell = (usize, usize, usize);
+
+/// Cells connected to each other through their 4 neighbours
+pub(crate) struct Region {
+    pub cells: Vec<Cell>,
+    /// Solid angle the cells cover
+    pub area: f32,
+    /// Sum of the cell directions weighted by their solid angle
+    pub weighted_direction: Vec3,
+}
+
+impl Region {
+    /// Area weighted mean direction, or the first cell for a region wrapped all the way around
+    pub fn center(&self, n: usize) -> Vec3 {
+        let (face, x, y) = self.cells[0];
+        self.weighted_direction.try_normalize().unwrap_or(cell_direction(n, face, x, y))
+    }
+
+    /// The cell of the region closest to its center
+    fn central_cell(&self, n: usize) -> Vec3 {
+        let center = self.center(n);
+        self.cells
+            .iter()
+            .map(|&(face, x, y)| cell_direction(n, face, x, y))
+            .max_by(|a, b| a.dot(center).total_cmp(&b.dot(center)))
+            .unwrap_or(center)
+    }
+}
+
+/// Regions of the cells `include` accepts on a cube of `n`×`n` faces, in the order of their
+/// first cell
+pub(crate) fn connected_regions(n: usize, include: impl Fn(Cell) -> bool) -> Vec<Region> {
+    let mut visited = vec![vec![vec![false; n]; n]; 6];
+    let mut regions = Vec::new();
+    for start in all_cells(n) {
+        let (face, x, y) = start;
+        if visited[face][y][x] || !include(start) {
+            continue;
+        }
+        visited[face][y][x] = true;
+
+        let mut region = Region {
+            cells: Vec::new(),
+            area: 0.0,
+            weighted_direction: Vec3::ZERO,
+        };
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            let (face, x, y) = cell;
+            let cell_area = solid_angle_of_cell(face, x, y, n);
+            region.cells.push(cell);
+            region.area += cell_area;
+            region.weighted_direction += cell_direction(n, face, x, y) * cell_area;
+            for next in neighbors(cell, n) {
+                let (nf, nx, ny) = next;
+                if !visited[nf][ny][nx] && include(next) {
+                    visited[nf][ny][nx] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        regions.push(region);
+    }
+    regions
+}
+
+/// What kind of feature a name belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureKind {
+    Continent,
+    Island,
+    Ocean,
+    Sea,
+    MountainRange,
+}
+
+/// A named feature of the planet
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedFeature {
+    pub kind: FeatureKind,
+    /// The full name, like "Tharos Ocean"
+    pub name: String,
+    /// Unit direction of the label, on the feature
+    pub direction: Vec3,
+    /// Share of the planet surface the feature covers, 0 to 1
+    pub area: f32,
+}
+
+/// Which features get a name
+#[derive(Debug, Clone)]
+pub struct FeatureNaming {
+    /// Smallest landmass named, share of the planet surface
+    pub min_island_area: f32,
+    /// Landmasses from this share of the surface up are continents
+    pub continent_area: f32,
+    /// Water at least this many cells from the coast, as a share of the face grid size, is
+    /// open water. Basins are regions of open water, so straits between continents split them.
+    pub open_water_distance: f32,
+    /// Smallest ocean basin named, share of the planet surface
+    pub min_basin_area: f32,
+    /// Basins from this share of the surface up are oceans, smaller ones seas
+    pub ocean_area: f32,
+    /// Land this high up among all land is mountains, 0.97 is the highest 3 %
+    pub mountain_percentile: f32,
+    /// Fewer cells of mountains in a cluster are a lone peak, not a range
+    pub min_mountain_cells: usize,
+    /// The ranges with the highest peaks are named, this many of them
+    pub mountain_ranges: usize,
+}
+
+impl Default for FeatureNaming {
+    fn default() -> Self {
+        Self {
+            min_island_area: 0.0005,
+            continent_area: 0.02,
+            open_water_distance: 0.06,
+            min_basin_area: 0.005,
+            ocean_area: 0.05,
+            mountain_percentile: 0.97,
+            min_mountain_cells: 3,
+            mountain_ranges: 5,
+        }
+    }
+}
+
+impl PlanetData {
+    /// Landmasses, ocean basins and the highest mountain ranges with names made up from
+    /// `seed`. Largest first within each kind, the same planet and seed give the same names.
+    pub fn named_features(&self, seed: u64, naming: &FeatureNaming) -> Vec<NamedFeature> {
+        let n = self.face_grid_size;
+        let height = |(face, x, y): Cell| self.faces[face].heightmap[y][x];
+        let is_land = |cell: Cell| height(cell) > self.sea_level;
+        let share = |area: f32| area / (4.0 * PI);
+        let mut names = NameGenerator::new(seed ^ NAME_SEED_SALT);
+        let mut features = Vec::new();
+
+        let mut landmasses = connected_regions(n, is_land);
+        landmasses.retain(|region| share(region.area) >= naming.min_island_area);
+        landmasses.sort_by(|a, b| b.area.total_cmp(&a.area));
+        for region in &landmasses {
+            let kind = if share(region.area) >= naming.continent_area {
+                FeatureKind::Continent
+            } else {
+                FeatureKind::Island
+            };
+            features.push(NamedFeature {
+                kind,
+                name: feature_name(kind, &names.next_name()),
+                direction: region.central_cell(n),
+                area: share(region.area),
+            });
+        }
+
+        let coast_distance = distance_from_land(n, is_land);
+        let distance = |(face, x, y): Cell| coast_distance[face][y][x];
+        let open_water = (naming.open_water_distance * n as f32).ceil().max(1.0) as u32;
+        let mut basins = connected_regions(n, |cell| !is_land(cell) && distance(cell) >= open_water);
+        basins.retain(|region| share(region.area) >= naming.min_basin_area);
+        basins.sort_by(|a, b| b.area.total_cmp(&a.area));
+        for region in &basins {
+            let kind = if share(region.area) >= naming.ocean_area {
+                FeatureKind::Ocean
+            } else {
+                FeatureKind::Sea
+            };
+            // Farthest from any coast, in the middle of the water
+            let (face, x, y) = *region.cells.iter().max_by_key(|&&cell| distance(cell)).unwrap_or(&region.cells[0]);
+            features.push(NamedFeature {
+                kind,
+                name: feature_name(kind, &names.next_name()),
+                direction: cell_direction(n, face, x, y),
+                area: share(region.area),
+            });
+        }
+
+        let mut land_heights: Vec<f32> = all_cells(n).filter(|&cell| is_land(cell)).map(height).collect();
+        land_heights.sort_by(f32::total_cmp);
+        let percentile_index = (land_heights.len() as f32 * naming.mountain_percentile) as usize;
+        if let Some(&mountain_height) = land_heights.get(percentile_index.min(land_heights.len().saturating_sub(1))) {
+            let mut ranges = connected_regions(n, |cell| is_land(cell) && height(cell) >= mountain_height);
+            ranges.retain(|region| region.cells.len() >= naming.min_mountain_cells);
+            let peak = |region: &Region| region.cells.iter().map(|&cell| height(cell)).fold(f32::MIN, f32::max);
+            ranges.sort_by(|a, b| peak(b).total_cmp(&peak(a)));
+            // On the middle of the range, a flat top has no single peak
+            for region in ranges.iter().take(naming.mountain_ranges) {
+                features.push(NamedFeature {
+                    kind: FeatureKind::MountainRange,
+                    name: feature_name(FeatureKind::MountainRange, &names.next_name()),
+                    direction: region.central_cell(n),
+                    area: share(region.area),
+                });
+            }
+        }
+        features
+    }
+}
+
+/// The full name of a feature of `kind` called `name`
+fn feature_name(kind: FeatureKind, name: &str) -> String {
+    match kind {
+        FeatureKind::Continent => name.to_string(),
+        FeatureKind::Island => format!("{name} Isle"),
+        FeatureKind::Ocean => format!("{name} Ocean"),
+        FeatureKind::Sea => format!("Sea of {name}"),
+        FeatureKind::MountainRange => format!("{name} Mountains"),
+    }
+}
+
+/// Steps from every cell to the nearest land cell, 0 on land. `u32::MAX` everywhere without
+/// land.
+fn distance_from_land(n: usize, is_land: impl Fn(Cell) -> bool) -> Vec<Vec<Vec<u32>>> {
+    let mut distance = vec![vec![vec![u32::MAX; n]; n]; 6];
+    let mut queue = VecDeque::new();
+    for cell in all_cells(n).filter(|&cell| is_land(cell)) {
+        let (face, x, y) = cell;
+        distance[face][y][x] = 0;
+        queue.push_back(cell);
+    }
+    while let Some(cell) = queue.pop_front() {
+        let (face, x, y) = cell;
+        let next_distance = distance[face][y][x] + 1;
+        for (nf, nx, ny) in neighbors(cell, n) {
+            if distance[nf][ny][nx] > next_distance {
+                distance[nf][ny][nx] = next_distance;
+                queue.push_back((nf, nx, ny));
+            }
+        }
+    }
+    distance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid2D;
+
+    const N: usize = 33;
+
+    /// Ocean everywhere, with land of `height` in the given cells
+    fn planet_with(land: impl IntoIterator<Item = (Cell, f32)>) -> PlanetData {
+        let mut planet = PlanetData::half_land(N, 10.0);
+        for face in &mut planet.faces {
+            face.heightmap = Grid2D::new(N, N, -1.0);
+        }
+        for ((face, x, y), height) in land {
+            planet.faces[face].heightmap[y][x] = height;
+        }
+        planet
+    }
+
+    /// A square of land on `face`, `size` cells wide around the face center
+    fn square(face: usize, size: usize, height: f32) -> impl Iterator<Item = (Cell, f32)> {
+        let start = (N - size) / 2;
+        (start..start + size).flat_map(move |y| (start..start + size).map(move |x| ((face, x, y), height)))
+    }
+
+    fn of_kind(features: &[NamedFeature], kind: FeatureKind) -> Vec<&NamedFeature> {
+        features.iter().filter(|feature| feature.kind == kind).collect()
+    }
+
+    #[test]
+    fn test_land_across_a_face_edge_is_one_region() {
+        let land = |(face, x, _): Cell| (face == 4 && x >= N - 3) || (face == 0 && x <= 2);
+
+        let regions = connected_regions(N, land);
+
+        assert_eq!(regions.len(), 1);
+        // Every cell of every face covers the sphere once
+        let sphere: f32 = connected_regions(N, |_| true).iter().map(|region| region.area).sum();
+        assert!((sphere - 4.0 * PI).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_landmasses_are_continents_or_islands_by_area() {
+        let land = square(4, 13, 0.5).chain(square(1, 3, 0.5)).chain(std::iter::once(((2, 16, 16), 0.5)));
+        let planet = planet_with(land);
+
+        let features = planet.named_features(1, &FeatureNaming::default());
+
+        let continents = of_kind(&features, FeatureKind::Continent);
+        let islands = of_kind(&features, FeatureKind::Island);
+        assert_eq!(continents.len(), 1);
+        assert!(continents[0].direction.distance(Vec3::Z) < 0.05);
+        // The single cell is too small to be named
+        assert_eq!(islands.len(), 1);
+        assert!(islands[0].name.ends_with(" Isle"));
+    }
+
+    #[test]
+    fn test_basin_label_is_far_from_the_coast() {
+        let planet = planet_with(square(4, 13, 0.5));
+
+        let features = planet.named_features(1, &FeatureNaming::default());
+
+        let oceans = of_kind(&features, FeatureKind::Ocean);
+        assert_eq!(oceans.len(), 1);
+        // Opposite the only continent
+        assert!(oceans[0].direction.distance(Vec3::NEG_Z) < 0.05, "{}", oceans[0].direction);
+    }
+
+    #[test]
+    fn test_highest_ranges_are_named() {
+        let naming = FeatureNaming {
+            mountain_ranges: 1,
+            ..FeatureNaming::default()
+        };
+        let mut land: Vec<(Cell, f32)> = square(4, 15, 0.5).chain(square(0, 15, 0.5)).collect();
+        for (cells, height) in [(square(4, 3, 2.0), 2.0), (square(0, 3, 3.0), 3.0)] {
+            for (cell, _) in cells {
+                land.retain(|&(other, _)| other != cell);
+                land.push((cell, height));
+            }
+        }
+        let planet = planet_with(land);
+
+        let features = planet.named_features(1, &naming);
+
+        let ranges = of_kind(&features, FeatureKind::MountainRange);
+        assert_eq!(ranges.len(), 1);
+        assert!(ranges[0].direction.distance(Vec3::X) < 0.05, "{}", ranges[0].direction);
+        assert!(ranges[0].name.ends_with(" Mountains"));
+    }
+
+    #[test]
+    fn test_names_follow_the_seed() {
+        let planet = planet_with(square(4, 13, 0.5).chain(square(1, 11, 0.5)));
+        let names = |seed| {
+            planet
+                .named_features(seed, &FeatureNaming::default())
+                .into_iter()
+                .map(|feature| feature.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(5), names(5));
+        assert_ne!(names(5), names(6));
+    }
+}
@@ -0,0 +1,225 @@
+//! Summary numbers of a generated planet, to compare generator tweaks across many generations
+
+use crate::boundaries::{BoundaryData, BoundaryType};
+use crate::generator::cube_face_point;
+use crate::planet::PlanetData;
+use crate::tools::sphere::solid_angle_of_cell;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Plate boundaries, junctions, plate shapes and land of one planet. Lengths are angles on the
+/// unit sphere, so they don't depend on the radius or the grid size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    /// Length of every plate boundary, in radians
+    pub boundary_length: f32,
+    pub convergent_length: f32,
+    pub divergent_length: f32,
+    pub transform_length: f32,
+    /// Points where three or more plates meet
+    pub triple_junctions: usize,
+    /// Mean of area / perimeter² over the plates with a boundary, in steradians over radians².
+    /// A small disc gives 1/(4π) ≈ 0.08, a hemisphere 1/(2π) ≈ 0.16, 0.0 without boundaries.
+    pub mean_compactness: f32,
+    /// Share of the surface above sea level
+    pub land_fraction: f32,
+}
+
+impl GenerationStats {
+    /// Measure `planet` with the boundary types in `boundaries`.
+    ///
+    /// Boundaries run between neighbouring cells of different plates, along the edge the two
+    /// cells share. Each edge is measured on its face only, an edge along a cube edge is half on
+    /// each of the two faces that store its cells. A boundary takes the type its cells are
+    /// marked with, boundaries with unmarked cells only count towards the total.
+    pub fn compute(planet: &PlanetData, boundaries: &BoundaryData) -> Self {
+        let n = planet.face_grid_size;
+        let step = 2.0 / (n - 1) as f32;
+        let to_uv = |i: f32| (i * step - 1.0).clamp(-1.0, 1.0);
+        let point = |face: usize, x: f32, y: f32| Vec3::from(cube_face_point(face, to_uv(x), to_uv(y))).normalize();
+
+        let mut stats = Self::default();
+        let mut perimeters: HashMap<usize, f32> = HashMap::new();
+        let mut areas: HashMap<usize, f32> = HashMap::new();
+        let mut land_area = 0.0;
+
+        for face in 0..6 {
+            let plates = &planet.plate_map[face];
+            for y in 0..n {
+                for x in 0..n {
+                    let plate = plates[y][x];
+                    let area = solid_angle_of_cell(face, x, y, n);
+                    *areas.entry(plate).or_default() += area;
+                    if planet.faces[face].heightmap[y][x] > planet.sea_level {
+                        land_area += area;
+                    }
+
+                    // The edge to the right and the one below, each one is visited once
+                    let (fx, fy) = (x as f32, y as f32);
+                    let edges = [
+                        (x + 1 < n).then(|| ((x + 1, y), point(face, fx + 0.5, fy - 0.5), point(face, fx + 0.5, fy + 0.5))),
+                        (y + 1 < n).then(|| ((x, y + 1), point(face, fx - 0.5, fy + 0.5), point(face, fx + 0.5, fy + 0.5))),
+                    ];
+                    for ((nx, ny), a, b) in edges.into_iter().flatten() {
+                        let other = plates[ny][nx];
+                        if other == plate {
+                            continue;
+                        }
+                        let length = a.angle_between(b);
+                        stats.boundary_length += length;
+                        *perimeters.entry(plate).or_default() += length;
+                        *perimeters.entry(other).or_default() += length;
+                        let boundary_type = boundaries.boundaries[face][y][x].or(boundaries.boundaries[face][ny][nx]);
+                        match boundary_type {
+                            Some(BoundaryType::Convergent) => stats.convergent_length += length,
+                            Some(BoundaryType::Divergent) => stats.divergent_length += length,
+                            Some(BoundaryType::Transform) => stats.transform_length += length,
+                            None => {}
+                        }
+                    }
+
+                    // The grid corner between this cell and the ones right and below it
+                    if x + 1 < n && y + 1 < n {
+                        let mut around = [plate, plates[y][x + 1], plates[y + 1][x], plates[y + 1][x + 1]];
+                        around.sort_unstable();
+                        let distinct = 1 + around.windows(2).filter(|pair| pair[0] != pair[1]).count();
+                        if distinct >= 3 {
+                            stats.triple_junctions += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        let compactness: Vec<f32> = perimeters
+            .iter()
+            .filter(|&(_, &perimeter)| perimeter > 0.0)
+            .map(|(plate, perimeter)| areas[plate] / (perimeter * perimeter))
+            .collect();
+        if !compactness.is_empty() {
+            stats.mean_compactness = compactness.iter().sum::<f32>() / compactness.len() as f32;
+        }
+        stats.land_fraction = land_area / (4.0 * std::f32::consts::PI);
+        stats
+    }
+}
+
+impl std::fmt::Display for GenerationStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "boundaries {:.3} rad (convergent {:.3}, divergent {:.3}, transform {:.3}), \
+             {} triple junctions, mean compactness {:.4}, land {:.1}%",
+            self.boundary_length,
+            self.convergent_length,
+            self.divergent_length,
+            self.transform_length,
+            self.triple_junctions,
+            self.mean_compactness,
+            self.land_fraction * 100.0,
+        )
+    }
+}
+
+impl PlanetData {
+    /// [`GenerationStats`] of this planet with its own boundaries
+    pub fn generation_stats(&self) -> GenerationStats {
+        GenerationStats::compute(self, &self.boundary_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubemap_utils::all_cells;
+    use crate::generator::cell_direction;
+    use crate::grid::Grid2D;
+    use std::f32::consts::PI;
+
+    /// Even, so the middle lines of the faces run between cells
+    const N: usize = 16;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3 * expected.max(1.0), "{actual} != {expected}");
+    }
+
+    /// A planet with the plate and the land of every cell picked by its direction
+    fn planet(plate_at: impl Fn(Vec3) -> usize, land_at: impl Fn(Vec3) -> bool) -> PlanetData {
+        let mut planet = PlanetData::half_land(N, 1.0);
+        for (face, x, y) in all_cells(N) {
+            let dir = cell_direction(N, face, x, y);
+            planet.plate_map[face][y][x] = plate_at(dir);
+            planet.faces[face].heightmap[y][x] = if land_at(dir) { 1.0 } else { -1.0 };
+        }
+        planet
+    }
+
+    fn hemispheres(dir: Vec3) -> usize {
+        if dir.y > 0.0 { 0 } else { 1 }
+    }
+
+    #[test]
+    fn test_two_hemispheres_meet_along_the_equator() {
+        let planet = planet(hemispheres, |dir| dir.y > 0.0);
+        let stats = planet.generation_stats();
+
+        assert_close(stats.boundary_length, 2.0 * PI);
+        assert_eq!(stats.triple_junctions, 0);
+        // Each hemisphere: 2π / (2π)²
+        assert_close(stats.mean_compactness, 1.0 / (2.0 * PI));
+        assert_close(stats.land_fraction, 0.5);
+        // No cell is marked with a boundary type
+        assert_eq!(stats.convergent_length + stats.divergent_length + stats.transform_length, 0.0);
+    }
+
+    #[test]
+    fn test_boundary_length_splits_by_type() {
+        let mut planet = planet(hemispheres, |_| false);
+        let mut boundaries = BoundaryData::empty(N, 0.0);
+        for (face, x, y) in all_cells(N) {
+            let dir = cell_direction(N, face, x, y);
+            boundaries.boundaries[face][y][x] =
+                Some(if dir.x > 0.0 { BoundaryType::Convergent } else { BoundaryType::Divergent });
+        }
+        planet.boundary_data = boundaries;
+        let stats = planet.generation_stats();
+
+        assert_close(stats.convergent_length, PI);
+        assert_close(stats.divergent_length, PI);
+        assert_eq!(stats.transform_length, 0.0);
+        assert_eq!(stats.land_fraction, 0.0);
+    }
+
+    #[test]
+    fn test_three_plates_meet_twice() {
+        // The southern hemisphere split in half along the meridian through ±Z
+        let planet = planet(
+            |dir| match (dir.y > 0.0, dir.x > 0.0) {
+                (true, _) => 0,
+                (false, true) => 1,
+                (false, false) => 2,
+            },
+            |_| true,
+        );
+        let stats = planet.generation_stats();
+
+        // The equator and half a great circle
+        assert_close(stats.boundary_length, 3.0 * PI);
+        assert_eq!(stats.triple_junctions, 2);
+        // 2π / (2π)² for the north, π / (2π)² for each quarter
+        assert_close(stats.mean_compactness, 1.0 / (3.0 * PI));
+        assert_close(stats.land_fraction, 1.0);
+    }
+
+    #[test]
+    fn test_single_plate_has_no_boundaries() {
+        let mut planet = PlanetData::half_land(N, 1.0);
+        planet.plate_map = (0..6).map(|_| Grid2D::new(N, N, 0)).collect();
+        let stats = planet.generation_stats();
+
+        assert_eq!(stats.boundary_length, 0.0);
+        assert_eq!(stats.triple_junctions, 0);
+        assert_eq!(stats.mean_compactness, 0.0);
+    }
+}
@@ -1,26 +1,45 @@
 use crate::config::{NoiseConfig, PlanetGenConfig};
 use crate::boundaries::BoundaryType;
-use crate::constants::*;
+use crate::craters::WorldType;
+use crate::grid::Grid2D;
+use crate::pipeline::{GenerationContext, GenerationPipeline, PLATE_LAYOUT_STAGES};
 use crate::planet::*;
 use crate::plate::TectonicPlate;
+use crate::plate_colors::plate_color;
+use crate::plate_layout::PlateLayout;
+use crate::tools::sphere::{angular_distance, bearing, offset_by};
 use crate::tools::splitmix64;
-use glam::Vec3;
+use crate::trace::trace_span;
+use glam::{DVec3, Quat, Vec2, Vec3};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use std::collections::{HashMap, HashSet};
+use std::f32::consts::PI;
+use std::time::Duration;
+
+/// Cells sampled along a face edge to decide the plate types
+const PLATE_TYPE_SAMPLES_PER_EDGE: usize = 64;
 
 pub struct PlanetGenerator {
     pub radius: f32,
+    pub oblateness: f32,
     pub cells_per_unit: f32,
+    /// Upper bound on the vertices along one face edge, see [`Self::face_grid_size`]
+    pub max_face_grid_size: usize,
     pub num_plates: usize,
     pub num_micro_plates: usize,
     pub seed: u64,
+    /// Seeds the plates and the plate map instead of `seed`, re-rolls the plates on their own
+    pub plate_seed: Option<u64>,
+    /// Seeds the terrain noise, mountains and craters instead of `seed`
+    pub terrain_seed: Option<u64>,
     pub flow_warp_freq: f32,
     pub flow_warp_amp: f32,
     pub flow_warp_steps: usize,
     pub flow_warp_step_angle: f32,
     pub mountain_height: f32,
     pub mountain_width: f32,
+    pub world_type: WorldType,
     config: PlanetGenConfig,
 }
 
@@ -29,21 +48,42 @@ impl PlanetGenerator {
         let config = crate::get_config();
         Self {
             radius,
+            oblateness: config.generation.oblateness,
             cells_per_unit: config.generation.cells_per_unit,
+            max_face_grid_size: config.generation.max_face_grid_size,
             // default values, will be replaced by planet settings
             num_plates: config.generation.default_num_plates,
             num_micro_plates: config.generation.default_num_micro_plates,
             seed: 0,
+            plate_seed: None,
+            terrain_seed: None,
             flow_warp_freq: config.flow_warp.default_freq,
             flow_warp_amp: config.flow_warp.default_amp,
             flow_warp_steps: config.flow_warp.default_steps,
             flow_warp_step_angle: config.flow_warp.default_step_angle,
             mountain_height: config.mountains.height,
             mountain_width: config.mountains.width,
+            world_type: WorldType::default(),
             config,
         }
     }
 
+    /// Vertices along one face edge the radius and cells_per_unit ask for, before the budget
+    pub fn requested_face_grid_size(&self) -> usize {
+        // Each cube face represents a square section of the unit sphere, scaled the planet's radius.
+        // cells_per_unit = how many grid cells per 1 unit of world space
+        // radius * cells_per_unit = number of cells from edge to edge on one face
+        // +1 = adds 1 to include both start and end of the grid (for vertices, not just quads)
+        (self.radius * self.cells_per_unit).ceil() as usize + 1
+    }
+
+    /// Vertices along one face edge of the generated planet. The requested size grows linearly
+    /// with the radius and the vertex count with its square, so it is clamped to
+    /// `max_face_grid_size`; large planets get coarser cells instead of more of them.
+    pub fn face_grid_size(&self) -> usize {
+        self.requested_face_grid_size().min(self.max_face_grid_size.max(2))
+    }
+
     pub fn with_continent_config(&mut self, continent_config: crate::config::ContinentConfig) {
         self.config.continents = continent_config;
     }
@@ -63,9 +103,19 @@ impl PlanetGenerator {
         h
     }
 
-    fn seed32_for(&self, domain: &str) -> [u8; 32] {
-        // Mix master seed with domain label via FNV1a64, then expand with SplitMix64
-        let s = Self::fnv1a64(self.seed, domain.as_bytes());
+    /// Seed of everything that shapes the plate layout
+    fn plate_stream(&self) -> u64 {
+        self.plate_seed.unwrap_or(self.seed)
+    }
+
+    /// Seed of everything that shapes the terrain on top of the plates
+    fn terrain_stream(&self) -> u64 {
+        self.terrain_seed.unwrap_or(self.seed)
+    }
+
+    fn seed32_for(seed: u64, domain: &str) -> [u8; 32] {
+        // Mix the stream seed with domain label via FNV1a64, then expand with SplitMix64
+        let s = Self::fnv1a64(seed, domain.as_bytes());
         let mut out = [0u8; 32];
         for i in 0..4 {
             let v = splitmix64(s ^ (i as u64));
@@ -74,71 +124,158 @@ impl PlanetGenerator {
         out
     }
 
-    fn seed_u32_for(&self, domain: &str) -> u32 {
+    fn seed_u32_for(seed: u64, domain: &str) -> u32 {
         // Take lower 32 bits of SplitMix64 expansion for quick u32 seeds
-        let v = splitmix64(Self::fnv1a64(self.seed, domain.as_bytes()));
+        let v = splitmix64(Self::fnv1a64(seed, domain.as_bytes()));
         (v & 0xFFFF_FFFF) as u32
     }
 
-    fn rng_for_indexed(&self, domain: &str, idx: u64) -> StdRng {
+    fn rng_for_indexed(seed: u64, domain: &str, idx: u64) -> StdRng {
         let key = format!("{domain}/{idx}");
-        StdRng::from_seed(self.seed32_for(&key))
+        StdRng::from_seed(Self::seed32_for(seed, &key))
     }
 
-    pub fn generate(&self) -> PlanetData {
-        // Each cube face represents a square section of the unit sphere, scaled the planet's radius.
-        // cells_per_unit = how many grid cells per 1 unit of world space
-        // radius * cells_per_unit = number of cells from edge to edge on one face
-        // +1 = adds 1 to include both start and end of the grid (for vertices, not just quads)
-        let face_grid_size = (self.radius * self.cells_per_unit).ceil() as usize + 1;
+    pub(crate) fn config(&self) -> &PlanetGenConfig {
+        &self.config
+    }
 
-        let mut plates = self.generate_plates();
-        let mut plate_map = self.assign_plates(face_grid_size, &plates);
+    /// Generate the planet with the default pipeline
+    pub fn generate(&self) -> PlanetData {
+        self.generate_with(&GenerationPipeline::default(), |_, _| {})
+    }
 
-        let micros = self.generate_microplates(face_grid_size, &plates, &plate_map);
-        plates.extend(micros);
+    /// Generate the planet with the stages of `pipeline`, except the ones the config disables.
+    /// `on_stage` gets the name and run time of every stage that ran.
+    pub fn generate_with(
+        &self,
+        pipeline: &GenerationPipeline,
+        on_stage: impl FnMut(&'static str, Duration),
+    ) -> PlanetData {
+        trace_span!("generate", face_grid_size = self.face_grid_size(), seed = self.seed);
+        let mut context = GenerationContext::new(self);
+        pipeline.run(&mut context, &self.config.pipeline.disabled_stages, on_stage);
+        self.finish(context)
+    }
 
-        plate_map = self.assign_plates(face_grid_size, &plates);
+    /// Generate new terrain on the plates of `layout` with the default pipeline. Only the
+    /// stages after the plate layout run, so the plate seed and plate settings are ignored.
+    /// A layout of a different grid size is resampled to this generator's.
+    pub fn generate_with_plate_layout(&self, layout: &PlateLayout) -> PlanetData {
+        trace_span!("generate_with_plate_layout", face_grid_size = self.face_grid_size(), seed = self.seed);
+        let mut context = GenerationContext::new(self);
+        context.plates = layout.plates();
+        context.plate_map = layout.plate_map(context.face_grid_size);
+        let mut disabled = self.config.pipeline.disabled_stages.clone();
+        disabled.extend(PLATE_LAYOUT_STAGES.map(String::from));
+        GenerationPipeline::default().run(&mut context, &disabled, |_, _| {});
+        self.finish(context)
+    }
 
-        // Apply plate merging (always enabled with probabilistic selection)
-        self.merge_plates(face_grid_size, &mut plate_map);
+    fn finish(&self, context: GenerationContext) -> PlanetData {
+        let plate_drift = vec![Quat::IDENTITY; context.plates.len()];
+        let mut planet = PlanetData {
+            faces: context.faces,
+            face_grid_size: context.face_grid_size,
+            radius: self.radius,
+            oblateness: self.oblateness,
+            sea_level: context.sea_level,
+            plate_map: context.plate_map,
+            plates: context.plates,
+            boundary_data: context.boundary_data,
+            lakes: context.lakes,
+            crust_age: context.crust_age,
+            plate_drift,
+            double_precision_vertices: self.config.generation.double_precision_vertices,
+            report: crate::report::GenerationReport::default(),
+        };
 
-        majority_smooth(face_grid_size, &mut plate_map);
+        // Flag degenerate results so the user gets an explanation instead of just a weird planet
+        planet.report = crate::report::GenerationReport::inspect(&planet, &self.config.validation);
+        if self.world_type == WorldType::Barren {
+            // All land is the point of a barren world
+            planet
+                .report
+                .warnings
+                .retain(|warning| !matches!(warning, crate::report::GenerationWarning::MostlyLand { .. }));
+        }
+        planet
+    }
 
+    /// Continent noise plus the per-plate base heights. Plates are classified by the continent
+    /// noise alone, then continental plates are raised and oceanic ones lowered by their offset.
+    pub(crate) fn generate_terran_faces(
+        &self,
+        face_grid_size: usize,
+        plates: &mut [TectonicPlate],
+        plate_map: &PlateMap,
+    ) -> [CubeFace; 6] {
         // Create continent noise configuration using custom config (independent of plates)
-        let continent_seed = self.seed_u32_for("continents");
+        let continent_seed = Self::seed_u32_for(self.terrain_stream(), "continents");
         let continent_noise = crate::continents::ContinentNoiseConfig::from_config(
             continent_seed,
             &self.config.continents,
         );
 
-        let mut faces = self.generate_faces(face_grid_size, &continent_noise);
+        let sea_level = self.config.continents.continent_threshold;
+        classify_plate_types(face_grid_size, plate_map, plates, sea_level, |face, x, y| {
+            continent_noise.sample_height(cell_direction(face_grid_size, face, x, y))
+        });
 
-        // Calculate plate boundary interactions
-        let boundary_data = crate::boundaries::BoundaryData::calculate(
+        // Blended across boundaries by generate_faces, so there is no cliff at the plate edge
+        let plate_base_heights: Vec<f32> = plates
+            .iter()
+            .map(|plate| match plate.plate_type {
+                PlateType::Continental => self.config.plates.continental_elevation,
+                PlateType::Oceanic => self.config.plates.oceanic_elevation,
+            })
+            .collect();
+        let plate_blend = crate::plate_blend::PlateBlend::calculate(
             face_grid_size,
-            &plate_map,
-            &plates,
+            plate_map,
+            self.config.boundaries.height_blend_width,
         );
 
-        // Apply tectonic uplift for convergent boundaries (mountain ranges)
-        self.apply_convergent_mountains(face_grid_size, &boundary_data, &mut faces);
-
-        PlanetData {
-            faces,
+        self.generate_faces(
             face_grid_size,
-            radius: self.radius,
+            &continent_noise,
             plate_map,
-            plates,
-            continent_noise,
-            boundary_data,
-        }
+            &plate_blend,
+            &plate_base_heights,
+        )
+    }
+
+    /// A gently rolling surface above sea level for the craters to dig into
+    pub(crate) fn generate_barren_faces(&self, face_grid_size: usize) -> [CubeFace; 6] {
+        let craters = &self.config.craters;
+        let base = self.config.continents.continent_threshold + craters.base_height;
+        let roughness = NoiseConfig::new(
+            Self::seed_u32_for(self.terrain_stream(), "barren"),
+            craters.roughness_frequency,
+            craters.roughness_amplitude,
+        );
+
+        std::array::from_fn(|face_idx| {
+            let heightmap = Grid2D::from_fn(face_grid_size, face_grid_size, |x, y| {
+                let u = x as f32 / (face_grid_size - 1) as f32 * 2.0 - 1.0;
+                let v = y as f32 / (face_grid_size - 1) as f32 * 2.0 - 1.0;
+                let dir = Vec3::from(cube_face_point(face_idx, u, v)).normalize();
+                base + roughness.sample(dir)
+            });
+            CubeFace { heightmap }
+        })
+    }
+
+    pub(crate) fn apply_craters(&self, face_grid_size: usize, faces: &mut [CubeFace; 6]) {
+        let mut rng = StdRng::from_seed(Self::seed32_for(self.terrain_stream(), "craters"));
+        let craters = crate::craters::generate_craters(&mut rng, &self.config.craters);
+        crate::craters::apply_craters(faces, face_grid_size, &craters, self.radius, &self.config.craters);
     }
 
     fn make_plate(&self, id: usize, direction: Vec3, center: Vec3, size_class: PlateSizeClass) -> TectonicPlate {
-        let color = DEBUG_COLORS[id % DEBUG_COLORS.len()];
+        // Plates trade colors with each other once the plate map is final, see PlateColors
+        let color = plate_color(id);
         // Derive a stable angular velocity axis per-plate, tangent to the sphere at the center.
-        let mut rng = self.rng_for_indexed("plates/angular", id as u64);
+        let mut rng = Self::rng_for_indexed(self.plate_stream(), "plates/angular", id as u64);
         let mut axis_raw = Vec3::new(
             rng.random_range(-1.0..1.0),
             rng.random_range(-1.0..1.0),
@@ -164,6 +301,8 @@ impl PlanetGenerator {
             angular_velocity: axis * speed,
             center: center.normalize(),
             size_class,
+            // Known once the continent noise is sampled, see classify_plate_types
+            plate_type: PlateType::default(),
             debug_color: color,
         }
     }
@@ -173,11 +312,11 @@ impl PlanetGenerator {
     /// Creates random plates for tectonic simulation.
     /// Each plate gets a random seed direction on the unit sphere.
     /// Note: Height/elevation comes from continent noise, not plate noise.
-    fn generate_plates(&self) -> Vec<TectonicPlate> {
+    pub fn generate_plates(&self) -> Vec<TectonicPlate> {
         // Derive a separate RNG per-plate for directions
         let mut directions: Vec<Vec3> = (0..self.num_plates)
             .map(|i| {
-                let mut rng = self.rng_for_indexed("plates/direction", i as u64);
+                let mut rng = Self::rng_for_indexed(self.plate_stream(), "plates/direction", i as u64);
                 Vec3::new(
                     rng.random_range(-1.0..1.0),
                     rng.random_range(-1.0..1.0),
@@ -205,53 +344,48 @@ impl PlanetGenerator {
     /// `O(P² · I)`, where `P` is the number of plates and `I` is the number of iterations (<= max_iterations).
     ///
     /// # Notes
-    /// - Inputs should be unit vectors; the function re-normalises after each relaxation step.
-    /// - Uses chord distance on unit sphere scaled by radius for intuitive distance calculations.
-    fn enforce_minimum_plate_distance(&self, directions: &mut Vec<Vec3>) {
+    /// - The configured minimum is a chord on the unit sphere, it's turned into the angle it spans.
+    /// - Plates too close move apart along the great circle through both of them, each by half
+    ///   the missing angle. Plates at the same spot still get pushed apart, in opposite directions.
+    fn enforce_minimum_plate_distance(&self, directions: &mut [Vec3]) {
         let max_iterations = 50;
-        let eps = 1e-6_f32;
+        let min_chord = self.config.plates.min_separation_chord_distance.clamp(0.0, 2.0);
+        let min_angle = 2.0 * (min_chord * 0.5).asin();
 
         for _ in 0..max_iterations {
             let mut any_moved = false;
-            let mut adjustments = vec![Vec3::ZERO; directions.len()];
+            // Per plate: the sum of the bearings it is pushed along, weighted by how far
+            let mut pushes = vec![Vec2::ZERO; directions.len()];
 
             // Calculate position adjustments between all pairs of plates
             for i in 0..directions.len() {
                 for j in (i + 1)..directions.len() {
-                    let dir_i = directions[i];
-                    let dir_j = directions[j];
-
-                    // Calculate chord distance on unit sphere surface
-                    let dot = dir_i.dot(dir_j).clamp(-1.0, 1.0);
-                    let chord_distance = (2.0 * (1.0 - dot)).sqrt();
-
-                    // If too close, calculate position adjustments
-                    if chord_distance < self.config.plates.min_separation_chord_distance {
-                        any_moved = true;
-
-                        // Calculate the vector between the two points
-                        let diff = dir_j - dir_i;
-                        let diff_length = diff.length();
-
-                        if diff_length > eps {
-                            let distance_deficit =
-                                self.config.plates.min_separation_chord_distance - chord_distance;
-                            // Each plate moves half the distance needed to meet the criteria
-                            let adjustment_magnitude = distance_deficit * 0.5;
-                            let diff_normalized = diff / diff_length;
-
-                            // Apply adjustments to both plates (equal and opposite)
-                            adjustments[i] -= diff_normalized * adjustment_magnitude;
-                            adjustments[j] += diff_normalized * adjustment_magnitude;
-                        }
+                    let distance = angular_distance(directions[i], directions[j]);
+                    if distance >= min_angle {
+                        continue;
                     }
+                    any_moved = true;
+
+                    // Each plate moves half the missing angle, straight away from the other one
+                    let push = (min_angle - distance) * 0.5;
+                    let away_from_j = bearing(directions[i], directions[j]) + PI;
+                    // Two plates at the same spot have no bearing to each other, so the second
+                    // one goes the opposite way of the first
+                    let away_from_i = if distance > 0.0 {
+                        bearing(directions[j], directions[i]) + PI
+                    } else {
+                        away_from_j + PI
+                    };
+                    pushes[i] += Vec2::from_angle(away_from_j) * push;
+                    pushes[j] += Vec2::from_angle(away_from_i) * push;
                 }
             }
 
-            // Apply position adjustments and re-normalize to sphere surface
-            for i in 0..directions.len() {
-                if adjustments[i].length_squared() > eps * eps {
-                    directions[i] = (directions[i] + adjustments[i]).normalize();
+            // Walk each plate along the sum of its pushes
+            for (direction, push) in directions.iter_mut().zip(&pushes) {
+                let angle = push.length();
+                if angle > 1e-6 {
+                    *direction = offset_by(*direction, push.to_angle(), angle);
                 }
             }
 
@@ -284,7 +418,7 @@ impl PlanetGenerator {
     ///
     /// Microplates are placed at locations where different major plates meet,
     /// creating more detailed terrain features along plate boundaries.
-    fn generate_microplates(
+    pub(crate) fn generate_microplates(
         &self,
         face_grid_size: usize,
         plates: &[TectonicPlate],
@@ -295,7 +429,7 @@ impl PlanetGenerator {
                 let id = plates.len() + i;
 
                 // RNG dedicated for placement sampling, stable per microplate index
-                let mut rng_pick = self.rng_for_indexed("microplates/pick", i as u64);
+                let mut rng_pick = Self::rng_for_indexed(self.plate_stream(), "microplates/pick", i as u64);
 
                 let (f, x, y) = loop {
                     let f: usize = rng_pick.random_range(0..6);
@@ -315,7 +449,7 @@ impl PlanetGenerator {
                 );
                 let base_dir = Vec3::new(dx, dy, dz).normalize();
                 // tiny jitter so seed stays close to boundary; independent RNG for jitter per microplate
-                let mut rng_jitter = self.rng_for_indexed("microplates/jitter", i as u64);
+                let mut rng_jitter = Self::rng_for_indexed(self.plate_stream(), "microplates/jitter", i as u64);
                 let jitter = Vec3::new(
                     rng_jitter.random_range(self.config.microplate_jitter_range()),
                     rng_jitter.random_range(self.config.microplate_jitter_range()),
@@ -341,8 +475,8 @@ impl PlanetGenerator {
     ///   distortion is applied to the grid cell direction to make plate boundaries less square);
     /// - the plate whose direction is closest (smallest angular distance) "wins" that grid cell
     /// - store the winner: Put that winning plate's ID into map[face][y][x]
-    fn assign_plates(&self, face_grid_size: usize, plates: &[TectonicPlate]) -> PlateMap {
-        let mut map = vec![vec![vec![0; face_grid_size]; face_grid_size]; 6];
+    pub fn assign_plates(&self, face_grid_size: usize, plates: &[TectonicPlate]) -> PlateMap {
+        let mut map: PlateMap = vec![Grid2D::new(face_grid_size, face_grid_size, 0); 6];
 
         // Precompute plate vectors
         let pre: Vec<(Vec3, f32, usize)> = plates
@@ -358,32 +492,32 @@ impl PlanetGenerator {
 
         // Deterministic warp and flow noise seeds per axis
         let warp_x = NoiseConfig::new(
-            self.seed_u32_for("assign_plates/warp/x"),
+            Self::seed_u32_for(self.plate_stream(), "assign_plates/warp/x"),
             self.config.boundaries.distortion_frequency,
             self.config.boundaries.distortion_amplitude,
         );
         let warp_y = NoiseConfig::new(
-            self.seed_u32_for("assign_plates/warp/y"),
+            Self::seed_u32_for(self.plate_stream(), "assign_plates/warp/y"),
             self.config.boundaries.distortion_frequency,
             self.config.boundaries.distortion_amplitude,
         );
         let warp_z = NoiseConfig::new(
-            self.seed_u32_for("assign_plates/warp/z"),
+            Self::seed_u32_for(self.plate_stream(), "assign_plates/warp/z"),
             self.config.boundaries.distortion_frequency,
             self.config.boundaries.distortion_amplitude,
         );
         let flow_x = NoiseConfig::new(
-            self.seed_u32_for("assign_plates/flow/x"),
+            Self::seed_u32_for(self.plate_stream(), "assign_plates/flow/x"),
             self.flow_warp_freq,
             self.flow_warp_amp,
         );
         let flow_y = NoiseConfig::new(
-            self.seed_u32_for("assign_plates/flow/y"),
+            Self::seed_u32_for(self.plate_stream(), "assign_plates/flow/y"),
             self.flow_warp_freq,
             self.flow_warp_amp,
         );
         let flow_z = NoiseConfig::new(
-            self.seed_u32_for("assign_plates/flow/z"),
+            Self::seed_u32_for(self.plate_stream(), "assign_plates/flow/z"),
             self.flow_warp_freq,
             self.flow_warp_amp,
         );
@@ -410,7 +544,7 @@ impl PlanetGenerator {
                             best_id = *pid;
                         }
                     }
-                    map[f][y][x] = best_id;
+                    map[f][(x, y)] = best_id;
                 }
             }
         }
@@ -420,19 +554,21 @@ impl PlanetGenerator {
     /// Generates heightmaps for all six cube faces of the planet
     ///
     /// Uses multi-octave continent noise to create realistic terrain with continents and oceans.
-    /// Plates are used for tectonic simulation only, not for elevation.
     /// Generate the 6 cube faces with heightmaps using continent noise
     ///
-    /// This generates elevation purely from continent noise,
-    /// independent of tectonic plates. Mountains will be added later via
-    /// plate simulation.
-    fn generate_faces(
+    /// Elevation comes from continent noise plus a per-plate base height (indexed by plate id).
+    /// The per-plate part is blended with the neighbouring plate near boundaries so there are no
+    /// cliffs at plate edges. Mountains are added afterwards and are not blended.
+    pub fn generate_faces(
         &self,
         face_grid_size: usize,
         continent_noise: &crate::continents::ContinentNoiseConfig,
+        plate_map: &PlateMap,
+        plate_blend: &crate::plate_blend::PlateBlend,
+        plate_base_heights: &[f32],
     ) -> [CubeFace; 6] {
         let blank = CubeFace {
-            heightmap: vec![vec![0.0; face_grid_size]; face_grid_size],
+            heightmap: Grid2D::new(face_grid_size, face_grid_size, 0.0),
         };
         let mut faces = [
             blank.clone(),
@@ -450,7 +586,15 @@ impl PlanetGenerator {
                     let dir = Vec3::from(cube_face_point(face_idx, u, v)).normalize();
 
                     // Generate height using continent noise
-                    let height = continent_noise.sample_height(dir);
+                    let plate_id = plate_map[face_idx][y][x];
+                    let plate_height = plate_blend.blend(
+                        face_idx,
+                        x,
+                        y,
+                        plate_base_heights[plate_id],
+                        |other| plate_base_heights[other],
+                    );
+                    let height = continent_noise.sample_height(dir) + plate_height;
 
                     faces[face_idx].heightmap[y][x] = height;
                 }
@@ -464,7 +608,7 @@ impl PlanetGenerator {
     /// Uses deterministic probabilities based on the master seed:
     /// - 10% chance for each plate to be selected as a primary for merging
     /// - 30% chance to select 2 neighbors, otherwise 1 neighbor
-    fn merge_plates(&self, face_grid_size: usize, plate_map: &mut PlateMap) {
+    pub(crate) fn merge_plates(&self, face_grid_size: usize, plate_map: &mut PlateMap) {
         // Build adjacency map and count plate areas
         let adjacency = self.build_plate_adjacency(face_grid_size, plate_map);
         let plate_areas = self.count_plate_areas(face_grid_size, plate_map);
@@ -567,7 +711,7 @@ impl PlanetGenerator {
         candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
 
         // Use master seed-based RNG for plate selection
-        let mut selection_rng = StdRng::from_seed(self.seed32_for("merge/selection"));
+        let mut selection_rng = StdRng::from_seed(Self::seed32_for(self.plate_stream(), "merge/selection"));
 
         for (candidate_plate, _) in candidates {
             // Skip if this plate is already involved in a merge
@@ -580,12 +724,14 @@ impl PlanetGenerator {
                 continue;
             }
 
-            // Get available neighbors (not already used)
-            let available_neighbors: Vec<usize> = adjacency[&candidate_plate]
+            // Get available neighbors (not already used), sorted so the shuffle below doesn't
+            // depend on the hash set's iteration order
+            let mut available_neighbors: Vec<usize> = adjacency[&candidate_plate]
                 .iter()
                 .filter(|neighbor_id| !used_plates.contains(neighbor_id))
                 .copied()
                 .collect();
+            available_neighbors.sort_unstable();
 
             if available_neighbors.is_empty() {
                 continue;
@@ -644,7 +790,7 @@ impl PlanetGenerator {
     /// Adds mountain height near convergent plate boundaries using noisy ridges with a smooth falloff.
     /// Mountains can form slightly below sea level based on mountain_underwater_threshold config.
     /// Uses layering to create varied mountain shapes with secondary ridges on one or both sides.
-    fn apply_convergent_mountains(
+    pub(crate) fn apply_convergent_mountains(
         &self,
         face_grid_size: usize,
         boundary_data: &crate::boundaries::BoundaryData,
@@ -654,11 +800,11 @@ impl PlanetGenerator {
         let mountain_height = self.mountain_height;
 
         // Fine-grained noise for multiple peaks along the ridge
-        let mountain_noise = NoiseConfig::new(self.seed_u32_for("mountains"), self.config.mountains.noise_frequency, 1.0);
+        let mountain_noise = NoiseConfig::new(Self::seed_u32_for(self.terrain_stream(), "mountains"), self.config.mountains.noise_frequency, 1.0);
         // Width variation noise - makes some areas wider, some narrower
-        let width_noise = NoiseConfig::new(self.seed_u32_for("mountains/width"), 6.0, 1.0);
+        let width_noise = NoiseConfig::new(Self::seed_u32_for(self.terrain_stream(), "mountains/width"), 6.0, 1.0);
         // Layering noise - determines where to add secondary mountain layers (1-2 extra ridges)
-        let layer_noise = NoiseConfig::new(self.seed_u32_for("mountains/layers"), 3.0, 1.0);
+        let layer_noise = NoiseConfig::new(Self::seed_u32_for(self.terrain_stream(), "mountains/layers"), 3.0, 1.0);
 
         // Minimum elevation threshold for mountain formation
         // Mountains can form slightly below sea level (down to continent_threshold - mountain_underwater_threshold)
@@ -677,7 +823,7 @@ impl PlanetGenerator {
                             continue;
                         }
 
-                        let dist = boundary_data.boundary_distances[face_idx][y][x];
+                        let dist = boundary_data.distance_in_cells(face_idx, x, y, face_grid_size);
 
                         // Calculate position for noise sampling
                         let u = x as f32 * inv * 2.0 - 1.0;
@@ -715,7 +861,7 @@ impl PlanetGenerator {
                                 for offset_x in [-1, 1] {
                                     let offset_pos_x = (x as i32 + offset_x * (layer_offset as i32).max(1)) as usize;
                                     if offset_pos_x < face_grid_size {
-                                        let offset_dist = boundary_data.boundary_distances[face_idx][y][offset_pos_x];
+                                        let offset_dist = boundary_data.distance_in_cells(face_idx, offset_pos_x, y, face_grid_size);
                                         if offset_dist.is_finite() && offset_dist <= local_width * 0.7 {
                                             let falloff = ((local_width * 0.7 - offset_dist) / (local_width * 0.7)).max(0.0);
                                             let falloff = falloff * falloff * falloff;
@@ -736,7 +882,7 @@ impl PlanetGenerator {
                                 for offset_y in [-1, 1] {
                                     let offset_pos_y = (y as i32 + offset_y * (layer_offset as i32).max(1)) as usize;
                                     if offset_pos_y < face_grid_size {
-                                        let offset_dist = boundary_data.boundary_distances[face_idx][offset_pos_y][x];
+                                        let offset_dist = boundary_data.distance_in_cells(face_idx, x, offset_pos_y, face_grid_size);
                                         if offset_dist.is_finite() && offset_dist <= local_width * 0.6 {
                                             let falloff = ((local_width * 0.6 - offset_dist) / (local_width * 0.6)).max(0.0);
                                             let falloff = falloff * falloff * falloff;
@@ -761,11 +907,91 @@ impl PlanetGenerator {
     }
 }
 
+/// Plates with most of their cells above `sea_level` are continental, the rest oceanic.
+/// Plates absorbed by merging own no cells and stay oceanic. Only every few cells are sampled,
+/// the majority of a plate doesn't need the full grid.
+pub(crate) fn classify_plate_types(
+    face_grid_size: usize,
+    plate_map: &PlateMap,
+    plates: &mut [TectonicPlate],
+    sea_level: f32,
+    height_at: impl Fn(usize, usize, usize) -> f32,
+) {
+    let stride = (face_grid_size / PLATE_TYPE_SAMPLES_PER_EDGE).max(1);
+    let mut land = vec![0usize; plates.len()];
+    let mut total = vec![0usize; plates.len()];
+    for (face, grid) in plate_map.iter().enumerate() {
+        for y in (0..face_grid_size).step_by(stride) {
+            for x in (0..face_grid_size).step_by(stride) {
+                let plate_id = grid[y][x];
+                total[plate_id] += 1;
+                if height_at(face, x, y) > sea_level {
+                    land[plate_id] += 1;
+                }
+            }
+        }
+    }
+
+    for (plate, (land, total)) in plates.iter_mut().zip(land.into_iter().zip(total)) {
+        plate.plate_type = if land * 2 > total {
+            PlateType::Continental
+        } else {
+            PlateType::Oceanic
+        };
+    }
+}
+
+/// Unit direction of a grid cell
+pub(crate) fn cell_direction(face_grid_size: usize, face: usize, x: usize, y: usize) -> Vec3 {
+    let to_uv = |i: usize| i as f32 / (face_grid_size - 1) as f32 * 2.0 - 1.0;
+    Vec3::from(cube_face_point(face, to_uv(x), to_uv(y))).normalize()
+}
+
+/// Lowers the ocean floor by `subsidence` times its crust age. Cells above sea level are left
+/// alone so the coastlines don't move.
+pub(crate) fn apply_crust_subsidence(
+    faces: &mut [CubeFace; 6],
+    crust_age: &crate::crust_age::CrustAgeMap,
+    sea_level: f32,
+    subsidence: f32,
+) {
+    if subsidence <= 0.0 {
+        return;
+    }
+    for (face_idx, face) in faces.iter_mut().enumerate() {
+        for (y, row) in face.heightmap.rows_mut().enumerate() {
+            for (x, height) in row.iter_mut().enumerate() {
+                if let Some(age) = crust_age.age_at(face_idx, x, y)
+                    && *height < sea_level
+                {
+                    *height -= subsidence * age;
+                }
+            }
+        }
+    }
+}
+
+/// Shifts the whole terrain up so its lowest cell sits just above `level`, so overlapping
+/// craters can't dig below sea level on a planet without water
+pub(crate) fn raise_above(faces: &mut [CubeFace; 6], level: f32) {
+    let lowest = faces
+        .iter()
+        .flat_map(|face| face.heightmap.iter())
+        .copied()
+        .fold(f32::INFINITY, f32::min);
+    let shift = level + f32::EPSILON - lowest;
+    if shift > 0.0 {
+        for height in faces.iter_mut().flat_map(|face| face.heightmap.iter_mut()) {
+            *height += shift;
+        }
+    }
+}
+
 /// Converts 2D cube face coordinates to 3D world coordinates
 ///
 /// Maps normalized coordinates (u, v) in range [-1, 1] on a specific cube face
 /// to 3D coordinates on the unit cube surface.
-pub fn cube_face_point(face_idx: usize, u: f32, v: f32) -> (f32, f32, f32) {
+pub(crate) fn cube_face_point(face_idx: usize, u: f32, v: f32) -> (f32, f32, f32) {
     match face_idx {
         0 => (1.0, v, -u),
         1 => (-1.0, v, u),
@@ -777,6 +1003,20 @@ pub fn cube_face_point(face_idx: usize, u: f32, v: f32) -> (f32, f32, f32) {
     }
 }
 
+/// `cube_face_point` in f64
+pub(crate) fn cube_face_point_f64(face_idx: usize, u: f64, v: f64) -> DVec3 {
+    let (x, y, z) = match face_idx {
+        0 => (1.0, v, -u),
+        1 => (-1.0, v, u),
+        2 => (u, 1.0, -v),
+        3 => (u, -1.0, v),
+        4 => (u, v, 1.0),
+        5 => (-u, v, -1.0),
+        _ => (0.0, 0.0, 0.0),
+    };
+    DVec3::new(x, y, z)
+}
+
 /// Smooths thin, noisy seams in the plate map using a single-pass majority vote.
 /// For each cell, counts its 8 neighbours plus itself (self counts double) and
 /// assigns the most frequent plate ID to the cell.
@@ -793,14 +1033,21 @@ pub fn cube_face_point(face_idx: usize, u: f32, v: f32) -> (f32, f32, f32) {
 ///
 /// # Notes
 /// If over-smoothing occurs, reduce self weight or drop the diagonal neighbours.
-fn majority_smooth(face_n: usize, map: &mut PlateMap) {
+pub(crate) fn majority_smooth(face_n: usize, map: &mut PlateMap) {
     for f in 0..6 {
         let mut out = map[f].clone();
         for y in 0..face_n {
             for x in 0..face_n {
-                let mut hist: HashMap<usize, u32> = HashMap::new();
+                // Counts in the order the plates are first seen, so ties go to the earliest
+                let mut hist: Vec<(usize, u32)> = Vec::with_capacity(9);
+                let mut count = |plate: usize, weight: u32| {
+                    match hist.iter_mut().find(|(id, _)| *id == plate) {
+                        Some((_, votes)) => *votes += weight,
+                        None => hist.push((plate, weight)),
+                    }
+                };
                 let pid = map[f][y][x];
-                *hist.entry(pid).or_insert(0) += 2;
+                count(pid, 2);
                 for (dx, dy) in [
                     (-1i32, 0i32),
                     (1, 0),
@@ -814,8 +1061,7 @@ fn majority_smooth(face_n: usize, map: &mut PlateMap) {
                     let nx = x as i32 + dx;
                     let ny = y as i32 + dy;
                     if nx >= 0 && ny >= 0 && (nx as usize) < face_n && (ny as usize) < face_n {
-                        let q = map[f][ny as usize][nx as usize];
-                        *hist.entry(q).or_insert(0) += 1;
+                        count(map[f][ny as usize][nx as usize], 1);
                     }
                 }
                 let mut best = pid;
@@ -832,3 +1078,153 @@ fn majority_smooth(face_n: usize, map: &mut PlateMap) {
         map[f] = out;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::continents::ContinentNoiseConfig;
+
+    fn generator() -> PlanetGenerator {
+        let mut generator = PlanetGenerator::new(5.0);
+        generator.seed = 42;
+        generator
+    }
+
+    #[test]
+    fn test_plates_too_close_are_pushed_apart() {
+        let generator = generator();
+        let min_chord = generator.config.plates.min_separation_chord_distance;
+        // Two plates on the same spot, one close to them and one right at the north pole
+        let mut directions = vec![Vec3::Z, Vec3::Z, Vec3::new(0.05, 0.0, 1.0).normalize(), Vec3::Y];
+
+        generator.enforce_minimum_plate_distance(&mut directions);
+
+        for (i, a) in directions.iter().enumerate() {
+            assert!((a.length() - 1.0).abs() < 1e-5);
+            for b in &directions[i + 1..] {
+                assert!(a.distance(*b) > min_chord * 0.9, "{a} and {b} are still too close");
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_plate_elevations_leave_the_continent_noise_alone() {
+        let mut generator = generator();
+        generator.config.plates.continental_elevation = 0.0;
+        generator.config.plates.oceanic_elevation = 0.0;
+        let n = generator.face_grid_size();
+        let mut plates = generator.generate_plates();
+        let plate_map = generator.assign_plates(n, &plates);
+
+        let faces = generator.generate_terran_faces(n, &mut plates, &plate_map);
+
+        let continent_seed = PlanetGenerator::seed_u32_for(generator.terrain_stream(), "continents");
+        let noise = ContinentNoiseConfig::from_config(continent_seed, &generator.config.continents);
+        for (face_idx, face) in faces.iter().enumerate() {
+            for y in 0..n {
+                for x in 0..n {
+                    let expected = noise.sample_height(cell_direction(n, face_idx, x, y));
+                    assert_eq!(face.heightmap[y][x], expected, "face {face_idx} ({x}, {y})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_continental_plates_hold_more_land_than_oceanic_ones() {
+        let mut generator = generator();
+        generator.config.plates.continental_elevation = 0.2;
+        generator.config.plates.oceanic_elevation = -0.2;
+        let planet = generator.generate();
+        let sea_level = planet.sea_level();
+
+        // (land cells, all cells) of each plate type
+        let mut continental = (0, 0);
+        let mut oceanic = (0, 0);
+        for (face, grid) in planet.faces.iter().zip(&planet.plate_map) {
+            for (&height, &plate_id) in face.heightmap.iter().zip(grid.iter()) {
+                let counts = match planet.plates[plate_id].plate_type {
+                    PlateType::Continental => &mut continental,
+                    PlateType::Oceanic => &mut oceanic,
+                };
+                counts.0 += usize::from(height > sea_level);
+                counts.1 += 1;
+            }
+        }
+
+        assert!(continental.1 > 0 && oceanic.1 > 0, "seed without both plate types");
+        let share = |(land, total): (usize, usize)| land as f32 / total as f32;
+        assert!(share(continental) > 0.5, "continental plates {continental:?}");
+        assert!(share(continental) > share(oceanic), "{continental:?} vs {oceanic:?}");
+    }
+
+    #[test]
+    fn test_planet_remembers_the_threshold_it_was_generated_with() {
+        let mut generator = generator();
+        generator.config.continents.continent_threshold = 0.17;
+        let planet = generator.generate();
+
+        // Changing the config afterwards doesn't move the water of a generated planet
+        generator.config.continents.continent_threshold = -0.4;
+        assert_eq!(planet.sea_level(), 0.17);
+        let radius = planet.surface_radius(Vec3::X);
+        assert_eq!(planet.sea_surface_radius(Vec3::X), radius + 0.17);
+    }
+
+    fn heights(planet: &PlanetData) -> Vec<f32> {
+        planet.faces.iter().flat_map(|face| face.heightmap.iter().copied()).collect()
+    }
+
+    #[test]
+    fn test_sub_seeds_default_to_the_seed() {
+        let mut with_sub_seeds = generator();
+        with_sub_seeds.plate_seed = Some(42);
+        with_sub_seeds.terrain_seed = Some(42);
+
+        let planet = generator().generate();
+        let same = with_sub_seeds.generate();
+        assert_eq!(same.plate_map, planet.plate_map);
+        assert_eq!(heights(&same), heights(&planet));
+    }
+
+    #[test]
+    fn test_terrain_rerolled_on_a_kept_plate_layout() {
+        let planet = generator().generate();
+        let layout = planet.extract_plate_layout();
+
+        // Same seeds, same planet
+        let again = generator().generate_with_plate_layout(&layout);
+        assert_eq!(again.plate_map, planet.plate_map);
+        assert_eq!(heights(&again), heights(&planet));
+
+        // Another terrain seed and other plate settings, the plates stay
+        let mut rerolled = generator();
+        rerolled.terrain_seed = Some(7);
+        rerolled.num_plates += 3;
+        let rerolled = rerolled.generate_with_plate_layout(&layout);
+        assert_eq!(rerolled.plate_map, planet.plate_map);
+        assert_eq!(rerolled.plates.len(), planet.plates.len());
+        assert_ne!(heights(&rerolled), heights(&planet));
+    }
+
+    #[test]
+    fn test_plates_rerolled_with_the_same_terrain_noise() {
+        let mut generator = generator();
+        // Without plate elevations the terrain is the continent noise alone
+        generator.config.plates.continental_elevation = 0.0;
+        generator.config.plates.oceanic_elevation = 0.0;
+        let n = generator.face_grid_size();
+        let terrain = |generator: &PlanetGenerator| {
+            let mut plates = generator.generate_plates();
+            let plate_map = generator.assign_plates(n, &plates);
+            let faces = generator.generate_terran_faces(n, &mut plates, &plate_map);
+            (plate_map, faces.map(|face| face.heightmap))
+        };
+        let (plate_map, heightmaps) = terrain(&generator);
+
+        generator.plate_seed = Some(7);
+        let (rerolled_map, rerolled_heightmaps) = terrain(&generator);
+        assert_ne!(rerolled_map, plate_map);
+        assert_eq!(rerolled_heightmaps, heightmaps);
+    }
+}
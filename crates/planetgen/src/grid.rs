@@ -0,0 +1,153 @@
+//! Flat storage of the per-face grids
+//!
+//! Heightmaps, plate maps and the climate cube map faces keep their cells row by row in one
+//! buffer. A cell is `grid[(x, y)]`; `grid[y]` is row `y` as a slice, so `grid[y][x]` reaches
+//! the same cell and loops written against nested rows read the same.
+
+use std::ops::{Index, IndexMut};
+
+/// `width × height` cells stored row by row in one contiguous buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid2D<T> {
+    /// Every cell set to `value`
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid2D<T> {
+    /// Cells from `cell(x, y)`, filled row by row
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| cell(x, y)).collect();
+        Self { width, height, cells }
+    }
+
+    /// The grid of equally long `rows`, the first one is `y = 0`
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|row| row.len() == width), "rows of different lengths");
+        Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// All cells row by row
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Rows from `y = 0` up
+    pub fn rows(&self) -> std::slice::Chunks<'_, T> {
+        // A zero width has no cells, any chunk size yields no rows
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn rows_mut(&mut self) -> std::slice::ChunksMut<'_, T> {
+        self.cells.chunks_mut(self.width.max(1))
+    }
+
+    /// A grid of the same size with `f` applied to every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid2D<U> {
+        Grid2D {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        debug_assert!(x < self.width, "x {x} outside a grid {} wide", self.width);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid2D<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        debug_assert!(x < self.width, "x {x} outside a grid {} wide", self.width);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Row `y`
+impl<T> Index<usize> for Grid2D<T> {
+    type Output = [T];
+
+    fn index(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+}
+
+impl<T> IndexMut<usize> for Grid2D<T> {
+    fn index_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cells_and_rows_are_the_same_storage() {
+        let mut grid = Grid2D::from_fn(3, 2, |x, y| 10 * y + x);
+
+        assert_eq!(grid.as_slice(), &[0, 1, 2, 10, 11, 12]);
+        assert_eq!(grid[(2, 1)], 12);
+        assert_eq!(grid[1][2], 12);
+        assert_eq!(grid.get(3, 0), None);
+
+        grid[(0, 1)] = 7;
+        grid[0][1] = 8;
+        let rows: Vec<&[usize]> = grid.rows().collect();
+        assert_eq!(rows, [&[0, 8, 2][..], &[7, 11, 12][..]]);
+        assert_eq!(grid, Grid2D::from_rows(vec![vec![0, 8, 2], vec![7, 11, 12]]));
+    }
+
+    #[test]
+    fn test_empty_grid_has_no_rows() {
+        let grid: Grid2D<f32> = Grid2D::new(0, 0, 0.0);
+        assert_eq!(grid.rows().count(), 0);
+        assert!(grid.as_slice().is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+//! Hillshade: brightness from the slope of the terrain towards a light, the classic GIS
+//! relief shading done in the local tangent frame of the sphere.
+
+use crate::config::HillshadeConfig;
+use crate::planet::PlanetData;
+use glam::Vec3;
+
+/// Brightness multiplier for the terrain in direction `dir`. Flat ground stays at 1.0, slopes
+/// facing the light get brighter and slopes facing away darker.
+///
+/// Heights are sampled bilinearly across cube faces, so there is no seam at the face edges.
+pub fn hillshade_factor(planet: &PlanetData, dir: Vec3, config: &HillshadeConfig) -> f32 {
+    if config.strength <= 0.0 {
+        return 1.0;
+    }
+
+    let up = dir.normalize();
+    let (east, north) = tangent_frame(up);
+
+    // One full resolution cell in cube face coordinates, so every LOD gets the same shading
+    let eps = 2.0 / (planet.face_grid_size - 1).max(1) as f32;
+    let height = |offset: Vec3| planet.sample_height((up + offset * eps).normalize());
+    // Distance between the samples on the surface
+    let distance = 2.0 * eps * planet.radius;
+    let slope_east = (height(east) - height(-east)) / distance;
+    let slope_north = (height(north) - height(-north)) / distance;
+    let normal = (up - east * slope_east - north * slope_north).normalize();
+
+    let azimuth = config.azimuth.to_radians();
+    let elevation = config.elevation.clamp(1.0, 90.0).to_radians();
+    let light = (east * azimuth.sin() + north * azimuth.cos()) * elevation.cos() + up * elevation.sin();
+
+    // Relative to flat ground, which is lit at sin(elevation)
+    let shade = normal.dot(light).max(0.0) / elevation.sin();
+    1.0 + config.strength * (shade - 1.0)
+}
+
+/// Multiply the color channels by a hillshade factor, alpha stays
+pub(crate) fn shade_color(color: [f32; 4], factor: f32) -> [f32; 4] {
+    [
+        (color[0] * factor).clamp(0.0, 1.0),
+        (color[1] * factor).clamp(0.0, 1.0),
+        (color[2] * factor).clamp(0.0, 1.0),
+        color[3],
+    ]
+}
+
+/// East and north on the surface at `up`, north points to the +Y pole
+fn tangent_frame(up: Vec3) -> (Vec3, Vec3) {
+    let east = Vec3::Y.cross(up);
+    // At the poles any east will do
+    let east = if east.length_squared() < 1e-12 {
+        Vec3::X.cross(up).normalize()
+    } else {
+        east.normalize()
+    };
+    (east, up.cross(east))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::cube_face_point;
+
+    fn light() -> HillshadeConfig {
+        HillshadeConfig {
+            azimuth: 315.0,
+            elevation: 45.0,
+            strength: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_flat_terrain_is_not_shaded() {
+        let planet = PlanetData::half_land(13, 10.0);
+        let config = light();
+
+        // Away from the land/ocean border every face is flat
+        for dir in [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Z] {
+            let factor = hillshade_factor(&planet, dir, &config);
+            assert!((factor - 1.0).abs() < 1e-4, "{dir}: {factor}");
+        }
+    }
+
+    #[test]
+    fn test_no_seam_across_cube_edges() {
+        // Terrain rising towards +X, sloped across the edge between the +X and +Z faces
+        let mut planet = PlanetData::half_land(13, 10.0);
+        let n = planet.face_grid_size;
+        for (face_idx, face) in planet.faces.iter_mut().enumerate() {
+            for y in 0..n {
+                for x in 0..n {
+                    let u = x as f32 / (n - 1) as f32 * 2.0 - 1.0;
+                    let v = y as f32 / (n - 1) as f32 * 2.0 - 1.0;
+                    let dir = Vec3::from(cube_face_point(face_idx, u, v)).normalize();
+                    face.heightmap[y][x] = 2.0 * dir.x;
+                }
+            }
+        }
+        let config = light();
+
+        for v in [-0.6, 0.0, 0.3] {
+            let on_x_face = hillshade_factor(&planet, Vec3::new(1.0, v, 0.999), &config);
+            let on_z_face = hillshade_factor(&planet, Vec3::new(0.999, v, 1.0), &config);
+            assert!((on_x_face - on_z_face).abs() < 0.01, "{on_x_face} vs {on_z_face}");
+            assert!((on_x_face - 1.0).abs() > 0.01, "the slope should be shaded");
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Lakes in closed basins above sea level
+//!
+//! Water that ends up in a depression on land has nowhere to drain, so it fills the depression
+//! up to the lowest point of its rim (the spill level) and overflows from there. Without this
+//! pass such basins render as dry pits.
+//!
+//! Rivers don't exist yet; once they do they should end in the lakes found here.
+
+use crate::cubemap_utils::{all_cells, nearest_cell, neighbors};
+use crate::planet::CubeFace;
+use glam::Vec3;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Water surface of every lake cell on the planet
+#[derive(Clone)]
+pub struct LakeMap {
+    /// Lake surface height (relative to the planet radius, like the heightmap), None = no lake
+    pub surface: [Vec<Vec<Option<f32>>>; 6],
+    /// Number of separate lakes
+    pub lake_count: usize,
+    face_grid_size: usize,
+}
+
+impl LakeMap {
+    /// A planet without lakes
+    pub fn empty(face_grid_size: usize) -> Self {
+        Self {
+            surface: std::array::from_fn(|_| vec![vec![None; face_grid_size]; face_grid_size]),
+            lake_count: 0,
+            face_grid_size,
+        }
+    }
+
+    /// Find lakes in the heightmap
+    ///
+    /// `sea_level` is the height of the ocean surface in heightmap units. Lakes covering less
+    /// than `min_lake_area` (a fraction of the planet surface) are dropped.
+    ///
+    /// # Algorithm
+    /// 1. Priority flood (Planchon-Darboux / Barnes): starting from all ocean cells, always
+    ///    expand the lowest known cell; a neighbour gets the higher of its own height and the
+    ///    water level it was reached from. Cells that end up above their terrain are in a basin,
+    ///    and the level they got is the basin's spill level.
+    /// 2. Group basin cells into lakes and drop the small ones.
+    pub fn calculate(faces: &[CubeFace; 6], face_grid_size: usize, sea_level: f32, min_lake_area: f32) -> Self {
+        let n = face_grid_size;
+        let height = |cell: (usize, usize, usize)| faces[cell.0].heightmap[cell.2][cell.1];
+
+        // Step 1: priority flood
+        let mut level = std::array::from_fn::<_, 6, _>(|_| vec![vec![f32::NAN; n]; n]);
+        let mut queue = BinaryHeap::new();
+        for (face, x, y) in all_cells(n) {
+            let h = height((face, x, y));
+            if h <= sea_level {
+                level[face][y][x] = h;
+                queue.push(FloodCell { level: h, cell: (face, x, y) });
+            }
+        }
+
+        // A planet without ocean drains through its lowest point
+        if queue.is_empty() {
+            let lowest = all_cells(n)
+                .min_by(|&a, &b| height(a).total_cmp(&height(b)))
+                .expect("cube faces are not empty");
+            level[lowest.0][lowest.2][lowest.1] = height(lowest);
+            queue.push(FloodCell { level: height(lowest), cell: lowest });
+        }
+
+        while let Some(FloodCell { level: water, cell }) = queue.pop() {
+            for neighbor in neighbors(cell, n) {
+                let (face, x, y) = neighbor;
+                if level[face][y][x].is_nan() {
+                    let neighbor_level = height(neighbor).max(water);
+                    level[face][y][x] = neighbor_level;
+                    queue.push(FloodCell { level: neighbor_level, cell: neighbor });
+                }
+            }
+        }
+
+        // Step 2: connected basins, filtered by area
+        let is_basin = |cell: (usize, usize, usize)| {
+            let (face, x, y) = cell;
+            height(cell) > sea_level && level[face][y][x] > height(cell) + LAKE_EPSILON
+        };
+
+        let mut lakes = Self::empty(n);
+        let mut visited = std::array::from_fn::<_, 6, _>(|_| vec![vec![false; n]; n]);
+        for (face, x, y) in all_cells(n) {
+            if visited[face][y][x] || !is_basin((face, x, y)) {
+                continue;
+            }
+
+            let mut lake = Vec::new();
+            let mut area = 0.0;
+            let mut pending = VecDeque::from([(face, x, y)]);
+            visited[face][y][x] = true;
+            while let Some(cell) = pending.pop_front() {
+                lake.push(cell);
+                area += cell_area(cell, n);
+                for neighbor in neighbors(cell, n) {
+                    let (nf, nx, ny) = neighbor;
+                    if !visited[nf][ny][nx] && is_basin(neighbor) {
+                        visited[nf][ny][nx] = true;
+                        pending.push_back(neighbor);
+                    }
+                }
+            }
+
+            if area >= min_lake_area {
+                for (lf, lx, ly) in lake {
+                    lakes.surface[lf][ly][lx] = Some(level[lf][ly][lx]);
+                }
+                lakes.lake_count += 1;
+            }
+        }
+
+        lakes
+    }
+
+    /// Lake surface at a cell, None if the cell is dry land or ocean
+    pub fn surface_at(&self, face: usize, x: usize, y: usize) -> Option<f32> {
+        self.surface[face][y][x]
+    }
+
+    /// Lake surface of the cell closest to `dir`
+    pub fn surface_in_direction(&self, dir: Vec3) -> Option<f32> {
+        let (face, x, y) = nearest_cell(dir, self.face_grid_size);
+        self.surface_at(face, x, y)
+    }
+}
+
+/// How far the water has to stand above the terrain before a cell counts as flooded
+const LAKE_EPSILON: f32 = 1e-5;
+
+/// Min-heap entry of the priority flood
+struct FloodCell {
+    level: f32,
+    cell: (usize, usize, usize),
+}
+
+impl PartialEq for FloodCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FloodCell {}
+
+impl PartialOrd for FloodCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FloodCell {
+    // Reversed so BinaryHeap pops the lowest level first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.level.total_cmp(&self.level)
+    }
+}
+
+/// Solid angle of a cell, as a fraction of the whole sphere
+fn cell_area(cell: (usize, usize, usize), n: usize) -> f32 {
+    let (_, x, y) = cell;
+    let step = 2.0 / (n - 1) as f32;
+    let u = x as f32 * step - 1.0;
+    let v = y as f32 * step - 1.0;
+    // Texels of a cube map shrink towards the face corners when projected onto the sphere
+    let solid_angle = step * step / (1.0 + u * u + v * v).powf(1.5);
+    solid_angle / (4.0 * std::f32::consts::PI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid2D;
+
+    const N: usize = 17;
+    const SEA_LEVEL: f32 = 0.0;
+
+    /// Flat land at height 1 with an ocean covering the -Y face
+    fn land_with_ocean() -> [CubeFace; 6] {
+        std::array::from_fn(|face| CubeFace {
+            heightmap: Grid2D::new(N, N, if face == 3 { -1.0 } else { 1.0 }),
+        })
+    }
+
+    fn dig(faces: &mut [CubeFace; 6], face: usize, xs: std::ops::Range<usize>, ys: std::ops::Range<usize>, depth: f32) {
+        for y in ys {
+            for x in xs.clone() {
+                faces[face].heightmap[y][x] = depth;
+            }
+        }
+    }
+
+    #[test]
+    fn test_closed_pit_fills_to_its_rim() {
+        let mut faces = land_with_ocean();
+        dig(&mut faces, 4, 6..11, 6..11, 0.5);
+
+        let lakes = LakeMap::calculate(&faces, N, SEA_LEVEL, 0.0);
+
+        assert_eq!(lakes.lake_count, 1);
+        assert_eq!(lakes.surface_at(4, 8, 8), Some(1.0));
+        assert_eq!(lakes.surface_at(4, 2, 2), None);
+        assert!(lakes.surface_in_direction(Vec3::Z).is_some());
+    }
+
+    #[test]
+    fn test_pit_open_to_the_ocean_stays_dry() {
+        let mut faces = land_with_ocean();
+        dig(&mut faces, 4, 6..11, 6..11, 0.5);
+        // A channel from the pit down to the -Y face edge of the +Z face (v = -1 is y = 0)
+        dig(&mut faces, 4, 8..9, 0..7, 0.2);
+
+        let lakes = LakeMap::calculate(&faces, N, SEA_LEVEL, 0.0);
+
+        assert_eq!(lakes.lake_count, 0);
+    }
+
+    #[test]
+    fn test_small_lakes_are_dropped() {
+        let mut faces = land_with_ocean();
+        dig(&mut faces, 4, 8..9, 8..9, 0.5);
+        dig(&mut faces, 2, 4..12, 4..12, 0.5);
+
+        let single_cell = cell_area((4, 8, 8), N);
+        let lakes = LakeMap::calculate(&faces, N, SEA_LEVEL, single_cell * 2.0);
+
+        assert_eq!(lakes.lake_count, 1);
+        assert_eq!(lakes.surface_at(4, 8, 8), None);
+        assert_eq!(lakes.surface_at(2, 8, 8), Some(1.0));
+    }
+
+    #[test]
+    fn test_basin_across_a_face_edge_is_one_lake() {
+        let mut faces = land_with_ocean();
+        // Both sides of the edge between +X (u = 1) and -Z (u = -1)
+        dig(&mut faces, 0, N - 4..N, 6..11, 0.5);
+        dig(&mut faces, 5, 0..4, 6..11, 0.5);
+
+        let lakes = LakeMap::calculate(&faces, N, SEA_LEVEL, 0.0);
+
+        assert_eq!(lakes.lake_count, 1);
+        assert_eq!(lakes.surface_at(0, N - 1, 8), Some(1.0));
+        assert_eq!(lakes.surface_at(5, 1, 8), Some(1.0));
+    }
+
+    #[test]
+    fn test_cell_areas_cover_the_sphere() {
+        // Edge texels are stored on two faces and half of each lies outside the face
+        let total: f32 = all_cells(N)
+            .map(|(face, x, y)| {
+                let edges = [x == 0 || x == N - 1, y == 0 || y == N - 1].iter().filter(|&&e| e).count();
+                cell_area((face, x, y), N) / (1 << edges) as f32
+            })
+            .sum();
+
+        assert!((total - 1.0).abs() < 0.01, "total area {total}");
+    }
+}
@@ -1,18 +1,64 @@
-pub mod arrows;
-pub mod biome;
-pub mod boundaries;
+//! Procedural planet generation: terrain, tectonic plates, climate maps and mesh data,
+//! with no dependency on a game engine.
+//!
+//! # Public API
+//! [`prelude`] and [`config`] are the supported surface. Everything a consumer needs is
+//! re-exported from the prelude, and removing or changing anything there is a breaking change.
+//! `get_config` and `reload_config` are also available at the crate root.
+//!
+//! The `continents`, `cube_grid`, `plate_blend` and `precipitations` modules are public only so
+//! the benchmarks can time individual pipeline stages. They are hidden from the docs and can
+//! change at any time.
+//!
+//! # Features
+//! `trace` wraps generation stages, cubemap builds and mesh building in `tracing` spans. It
+//! costs nothing when it's off.
+
+mod arrows;
+mod biome;
+mod boundaries;
+mod coastline;
+mod collider;
+mod color_ramp;
+mod color_variation;
 pub mod config;
-pub mod constants;
+mod constants;
+mod craters;
+mod crust_age;
+mod features;
+#[doc(hidden)]
 pub mod continents;
-pub mod generator;
-pub mod mesh_data;
-pub mod planet;
-pub mod plate;
+#[doc(hidden)]
+pub mod cube_grid;
+mod cubemap_utils;
+mod generation_stats;
+mod generator;
+mod grid;
+mod hillshade;
+mod lakes;
+mod mesh_data;
+mod mesh_validation;
+mod moon;
+mod names;
+mod palette;
+mod pipeline;
+mod planet;
+#[doc(hidden)]
+pub mod plate_blend;
+mod plate;
+mod plate_colors;
+mod plate_layout;
+mod plate_stats;
 pub mod prelude;
-pub mod temperature;
-pub mod tools;
-pub mod wind;
+#[doc(hidden)]
 pub mod precipitations;
-pub mod cubemap_utils;
+mod profiles;
+mod report;
+mod snow;
+mod tectonics;
+mod temperature;
+mod tools;
+mod trace;
+mod wind;
 
-pub use config::{get_config, reload_config};
+pub use config::{get_config, reload_config, set_config};
@@ -1,101 +1,182 @@
 use crate::biome;
-use crate::generator::cube_face_point;
-use crate::planet::PlanetData;
-use glam::Vec3;
-use std::collections::HashMap;
+use crate::color_variation::{ColorNoise, ColorVariation};
+use crate::config::HillshadeConfig;
+use crate::constants::{CONTINENTAL_CRUST_COLORS, MICROPLATE_BRIGHTEN, OCEANIC_CRUST_COLORS};
+use crate::cube_grid::StitchedGrid;
+use crate::generator::{cube_face_point, cube_face_point_f64};
+use crate::hillshade::{hillshade_factor, shade_color};
+use crate::palette::Palette;
+use crate::planet::{PlanetData, PlateSizeClass, PlateType};
+use crate::snow::{SNOW_COLOR, SnowLine};
+use crate::trace::trace_span;
+use glam::{DVec3, Vec3};
+
+/// Lake water color in continent view and with biome coloring
+pub const LAKE_COLOR: [f32; 4] = [0.15, 0.35, 0.55, 1.0];
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ViewMode {
+    /// Plates in random debug colors
     Plates,
+    /// Plates in earth tones for continental and blue for oceanic crust, microplates lighter
+    PlateCrust,
     Continents,
 }
 
+/// Columns and rows of the face atlas, face `f` is the tile in column `f % 3` of row `f / 3`
+pub const FACE_ATLAS_TILES: (usize, usize) = (3, 2);
+
+/// Texture coordinates of a planet mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeshUvs {
+    /// None, the faces share their edge vertices
+    #[default]
+    None,
+    /// Each face maps onto its tile of the face atlas, see [`face_atlas_uv`]. The faces get their
+    /// own copies of the edge vertices, a vertex has one UV.
+    FaceAtlas,
+}
+
 /// Raw mesh data that can be used by any rendering engine
 #[derive(Debug, Clone)]
 pub struct MeshData {
     pub positions: Vec<[f32; 3]>,
     pub normals: Vec<[f32; 3]>,
     pub colors: Vec<[f32; 4]>,
+    /// One per vertex with `MeshUvs::FaceAtlas`
+    pub uvs: Option<Vec<[f32; 2]>>,
     pub indices: Vec<u32>,
 }
 
+/// Position of the vertex `height` above the surface in the direction of the cube point `point`,
+/// `dir * (surface_radius(dir) + height)` in f64 and rounded to f32 at the end
+fn precise_vertex_position(planet: &PlanetData, point: DVec3, height: f32) -> Vec3 {
+    let dir = point.normalize();
+    let oblateness = f64::from(planet.oblateness);
+    let surface_radius = f64::from(planet.radius) * (1.0 - oblateness * dir.y * dir.y);
+    (dir * (surface_radius + f64::from(height))).as_vec3()
+}
+
+/// Where the grid point `x, y` of `face` lies in the face atlas, for a face grid of `size`
+/// points along each edge. The atlas holds the faces like the cubemaps do, `size × size`
+/// texels a face and 3 × 2 faces, and a point lands on the center of its texel. Fractional
+/// points lie between the texels, so the UVs never leave the tile of their face and nothing
+/// bleeds over from the tile next to it.
+pub fn face_atlas_uv(face: usize, x: f32, y: f32, size: usize) -> [f32; 2] {
+    let (columns, rows) = FACE_ATLAS_TILES;
+    let (column, row) = (face % columns, face / columns);
+    let size = size as f32;
+    [
+        (column as f32 * size + x + 0.5) / (columns as f32 * size),
+        (row as f32 * size + y + 0.5) / (rows as f32 * size),
+    ]
+}
+
 impl MeshData {
-    /// Generate mesh data from planet data
+    /// Generate mesh data from planet data at full resolution
     ///
     /// # Arguments
     /// * `planet` - The planet data to generate mesh from
     /// * `view_mode` - Whether to show plates or continents
-    /// * `snow_threshold` - Height threshold above which snow appears (in continent view)
-    /// * `continent_threshold` - Sea level threshold (dynamic from UI settings)
-    pub fn from_planet(planet: &PlanetData, view_mode: ViewMode, snow_threshold: f32, continent_threshold: f32) -> Self {
-        let size = planet.face_grid_size;
-        let mut positions = Vec::new();
-        let mut colors = Vec::new();
-        let mut indices = Vec::new();
-        let mut dir_map: HashMap<(i32, i32, i32), u32> = HashMap::new();
-        let mut vertex_indices = vec![vec![vec![0u32; size]; size]; 6];
-        let mut next_index = 0u32;
-
-        let quant_scale = (size - 1) as f32;
-
-        // Generate vertices for all faces
-        for (face_idx, face) in planet.faces.iter().enumerate() {
-            for y in 0..size {
-                let v = (y as f32 / (size - 1) as f32) * 2.0 - 1.0;
-                for x in 0..size {
-                    let u = (x as f32 / (size - 1) as f32) * 2.0 - 1.0;
-                    let (nx, ny, nz) = cube_face_point(face_idx, u, v);
-                    let dir = Vec3::new(nx, ny, nz).normalize();
-
-                    let key = (
-                        (dir.x * quant_scale).round() as i32,
-                        (dir.y * quant_scale).round() as i32,
-                        (dir.z * quant_scale).round() as i32,
-                    );
+    /// * `snow` - Where the continent view is white, from the temperatures when there are any
+    /// * `shading` - Relief shading and brightness and saturation noise of the continent view,
+    ///   strength 0 in either leaves the colors as they are
+    /// * `palette` - Plate and boundary colors of the plate view
+    /// * `uvs` - Whether the vertices get face atlas coordinates
+    pub fn from_planet(
+        planet: &PlanetData,
+        view_mode: ViewMode,
+        snow: SnowLine,
+        shading: (&HillshadeConfig, &ColorVariation),
+        palette: Palette,
+        uvs: MeshUvs,
+    ) -> Self {
+        Self::from_planet_lod(planet, view_mode, snow, shading, palette, 1, uvs)
+    }
 
-                    let idx = *dir_map.entry(key).or_insert_with(|| {
-                        let height = face.heightmap[y][x];
-                        // Always render geometry at radius + height (including negative heights for ocean floor)
-                        let radius = planet.radius + height;
-                        let pos = dir * radius;
-                        positions.push([pos.x, pos.y, pos.z]);
-
-                        let color = calculate_vertex_color(
-                            planet,
-                            view_mode,
-                            face_idx,
-                            x,
-                            y,
-                            height,
-                            dir,
-                            snow_threshold,
-                            continent_threshold,
-                        );
-                        colors.push(color);
-
-                        let i = next_index;
-                        next_index += 1;
-                        i
-                    });
-
-                    vertex_indices[face_idx][y][x] = idx;
-                }
+    /// Same as `from_planet` with only every `step`-th grid cell along each axis, so step 2
+    /// has a quarter of the triangles. Vertices are spread evenly over the face and take the
+    /// nearest cell's height, which keeps the face edges identical from both sides. The UVs
+    /// address the full resolution atlas whatever the step.
+    pub fn from_planet_lod(
+        planet: &PlanetData,
+        view_mode: ViewMode,
+        snow: SnowLine,
+        (hillshade, variation): (&HillshadeConfig, &ColorVariation),
+        palette: Palette,
+        step: usize,
+        uvs: MeshUvs,
+    ) -> Self {
+        trace_span!("mesh_data", face_grid_size = planet.face_grid_size, step);
+        let cells = planet.face_grid_size - 1;
+        // Vertices per face edge minus one
+        let lod_cells = cells.div_ceil(step.max(1)).max(1);
+        let grid = match uvs {
+            MeshUvs::None => StitchedGrid::new(lod_cells + 1),
+            MeshUvs::FaceAtlas => StitchedGrid::split(lod_cells + 1),
+        };
+        let mut positions = Vec::with_capacity(grid.vertices().len());
+        let mut colors = Vec::with_capacity(grid.vertices().len());
+        let mut atlas_uvs = Vec::new();
+        let color_noise = ColorNoise::new(variation);
+        let coloring = VertexColoring { view_mode, snow, palette };
+
+        // Full-resolution grid coordinate of a LOD vertex, and its nearest cell
+        let grid_coordinate = |i: usize| i as f32 * cells as f32 / lod_cells as f32;
+        let grid_cell = |i: usize| grid_coordinate(i).round() as usize;
+
+        // One vertex per distinct grid point, edge and corner points only on the face owning them.
+        // Copies on the other faces of a split grid are built from the owner too.
+        for &(vertex_face, vertex_x, vertex_y) in grid.vertices() {
+            if uvs == MeshUvs::FaceAtlas {
+                atlas_uvs.push(face_atlas_uv(
+                    vertex_face,
+                    grid_coordinate(vertex_x),
+                    grid_coordinate(vertex_y),
+                    planet.face_grid_size,
+                ));
             }
-        }
+            let (face_idx, x, y) = grid.owner(vertex_face, vertex_x, vertex_y);
+            let face = &planet.faces[face_idx];
+            let u = (x as f32 / lod_cells as f32) * 2.0 - 1.0;
+            let v = (y as f32 / lod_cells as f32) * 2.0 - 1.0;
+            let (cell_x, cell_y) = (grid_cell(x), grid_cell(y));
+            let (nx, ny, nz) = cube_face_point(face_idx, u, v);
+            let dir = Vec3::new(nx, ny, nz).normalize();
 
-        // Generate indices for all faces
-        for face_idx in 0..6 {
-            for y in 0..(size - 1) {
-                for x in 0..(size - 1) {
-                    let i0 = vertex_indices[face_idx][y][x];
-                    let i1 = vertex_indices[face_idx][y][x + 1];
-                    let i2 = vertex_indices[face_idx][y + 1][x];
-                    let i3 = vertex_indices[face_idx][y + 1][x + 1];
-                    indices.extend_from_slice(&[i0, i1, i2, i1, i3, i2]);
+            let lake_surface = planet.lakes.surface_at(face_idx, cell_x, cell_y);
+            // Lakes are flat water at their surface level instead of the basin floor
+            let height = lake_surface.unwrap_or(face.heightmap[cell_y][cell_x]);
+            // Always render geometry at radius + height (including negative heights for ocean floor)
+            let pos = if planet.double_precision_vertices {
+                let grid_uv = |i: usize| i as f64 / lod_cells as f64 * 2.0 - 1.0;
+                precise_vertex_position(planet, cube_face_point_f64(face_idx, grid_uv(x), grid_uv(y)), height)
+            } else {
+                dir * (planet.surface_radius(dir) + height)
+            };
+            positions.push([pos.x, pos.y, pos.z]);
+
+            let color = if lake_surface.is_some() && view_mode == ViewMode::Continents {
+                LAKE_COLOR
+            } else {
+                let color = calculate_vertex_color(planet, &coloring, face_idx, cell_x, cell_y, height, dir);
+                if view_mode == ViewMode::Continents {
+                    // No climate yet to tell the forests apart, everything varies alike
+                    let color = color_noise.apply(color, dir, 0.0);
+                    if hillshade.strength > 0.0 {
+                        shade_color(color, hillshade_factor(planet, dir, hillshade))
+                    } else {
+                        color
+                    }
+                } else {
+                    color
                 }
-            }
+            };
+            colors.push(color);
         }
 
+        let indices = grid.triangle_indices();
+
         // Calculate normals
         let normals: Vec<[f32; 3]> = positions
             .iter()
@@ -106,42 +187,62 @@ impl MeshData {
             positions,
             normals,
             colors,
+            uvs: (uvs == MeshUvs::FaceAtlas).then_some(atlas_uvs),
             indices,
         }
     }
 }
 
-/// Calculate the color for a vertex based on view mode and planet properties
+/// How the vertices of a mesh are colored, the same for all of them
+struct VertexColoring<'a> {
+    view_mode: ViewMode,
+    /// Where the continent view is white
+    snow: SnowLine<'a>,
+    /// Plate and boundary colors of the plate view
+    palette: Palette,
+}
+
+/// Calculate the color for a vertex based on view mode and planet properties, the continent
+/// view has its ocean level at the planet's sea level
 fn calculate_vertex_color(
     planet: &PlanetData,
-    view_mode: ViewMode,
+    coloring: &VertexColoring,
     face_idx: usize,
     x: usize,
     y: usize,
     height: f32,
     dir: Vec3,
-    snow_threshold: f32,
-    continent_threshold: f32,
 ) -> [f32; 4] {
-    match view_mode {
-        ViewMode::Plates => calculate_plate_view_color(planet, face_idx, x, y),
-        ViewMode::Continents => calculate_continent_view_color(height, dir, snow_threshold, continent_threshold),
+    match coloring.view_mode {
+        ViewMode::Plates | ViewMode::PlateCrust => {
+            calculate_plate_view_color(planet, coloring.view_mode, face_idx, x, y, coloring.palette)
+        }
+        ViewMode::Continents => {
+            calculate_continent_view_color(height, dir, &coloring.snow, planet.sea_level)
+        }
     }
 }
 
 /// Calculate color for plate view mode
 fn calculate_plate_view_color(
     planet: &PlanetData,
+    view_mode: ViewMode,
     face_idx: usize,
     x: usize,
     y: usize,
+    palette: Palette,
 ) -> [f32; 4] {
     let plate_id = planet.plate_map[face_idx][y][x];
     let plate = &planet.plates[plate_id];
-    let mut base_color = plate.debug_color;
+    let mut base_color = if view_mode == ViewMode::PlateCrust {
+        crust_color(plate_id, plate.plate_type, &plate.size_class)
+    } else {
+        palette.plate_color(plate_id, plate.debug_color)
+    };
 
     // Blend in boundary color if this is a boundary cell, with distance-based fade
-    if let Some((boundary_color, opacity)) = planet.boundary_data.get_boundary_color(face_idx, x, y)
+    if let Some((boundary_color, opacity)) =
+        planet.boundary_data.get_boundary_color(face_idx, x, y, palette)
     {
         // Blend based on opacity: full boundary color at edges, fade to plate color
         base_color[0] = base_color[0] * (1.0 - opacity) + boundary_color[0] * opacity;
@@ -152,23 +253,36 @@ fn calculate_plate_view_color(
     base_color
 }
 
+/// Color of a plate in the crust type view
+fn crust_color(plate_id: usize, plate_type: PlateType, size_class: &PlateSizeClass) -> [f32; 4] {
+    let palette = match plate_type {
+        PlateType::Continental => CONTINENTAL_CRUST_COLORS,
+        PlateType::Oceanic => OCEANIC_CRUST_COLORS,
+    };
+    let mut color = palette[plate_id % palette.len()];
+    if matches!(size_class, PlateSizeClass::Micro) {
+        for channel in &mut color[..3] {
+            *channel += (1.0 - *channel) * MICROPLATE_BRIGHTEN;
+        }
+    }
+    color
+}
+
 /// Calculate color for continent view mode
 fn calculate_continent_view_color(
     height: f32,
-    _dir: Vec3,
-    snow_threshold: f32,
+    dir: Vec3,
+    snow: &SnowLine,
     continent_threshold: f32,
 ) -> [f32; 4] {
+    let snow_threshold = snow.threshold;
     // Calculate height relative to ocean level (which is at continent_threshold)
     let height_above_ocean = height - continent_threshold;
     
     if height_above_ocean > 0.0 {
         // Land (above ocean level)
 
-        if height > snow_threshold {
-            // Pure white snow above threshold
-            [0.95, 0.95, 1.0, 1.0]
-        } else if height > continent_threshold + (continent_threshold * 0.5) {
+        let land = if height > continent_threshold + (continent_threshold * 0.5) {
             // High elevation: Green mountains transitioning to snow
             let mountain_factor = ((height - (continent_threshold + continent_threshold * 0.5)) / (snow_threshold - (continent_threshold + continent_threshold * 0.5))).clamp(0.0, 1.0);
             // Interpolate from much darker green at mid-elevation to lighter green near snow
@@ -198,7 +312,8 @@ fn calculate_continent_view_color(
                 0.45 - shore_factor * 0.3,        // Blue: warm sandy tone
                 1.0,
             ]
-        }
+        };
+        biome::lerp_color(land, SNOW_COLOR, snow.cover(dir, height, continent_threshold))
     } else {
         // Ocean floor (below ocean level): sandy/light color visible through transparent ocean
         let depth = -height;
@@ -213,52 +328,449 @@ fn calculate_continent_view_color(
     }
 }
 
+/// Everything the biome colors of a vertex depend on besides its position, see
+/// [`calculate_biome_colors`]
+pub struct BiomeColorInputs<'a> {
+    /// Radius of the zero-height surface in a direction, see [`PlanetData::surface_radius`]
+    pub surface_radius: &'a dyn Fn(Vec3) -> f32,
+    /// Height of the ocean surface above the zero-height surface, see [`PlanetData::sea_level`]
+    pub sea_level: f32,
+    /// Where the land is white
+    pub snow: SnowLine<'a>,
+    /// Extra warmth of land vertices over the sampled temperature
+    pub land_temperature_bonus: f32,
+    pub colors: &'a biome::BiomeColors,
+    pub thresholds: &'a biome::BiomeThresholds,
+    /// Color noise, stronger on forest and grassland
+    pub variation: &'a ColorVariation,
+    pub temperature: &'a dyn Fn(Vec3) -> f32,
+    pub precipitation: &'a dyn Fn(Vec3) -> f32,
+    pub is_lake: &'a dyn Fn(Vec3) -> bool,
+    /// Relief shading factor in a direction, see [`hillshade_factor`]
+    pub hillshade: &'a dyn Fn(Vec3) -> f32,
+}
+
 /// Calculate biome-based vertex colors for a planet mesh.
 ///
 /// Called after temperature and precipitation cubemaps are ready,
 /// to replace initial height-based colors with biome-aware colors.
-pub fn calculate_biome_colors(
-    positions: &[[f32; 3]],
-    planet_radius: f32,
-    continent_threshold: f32,
-    snow_threshold: f32,
-    land_temperature_bonus: f32,
-    biome_colors: &biome::BiomeColors,
-    biome_thresholds: &biome::BiomeThresholds,
-    sample_temperature: impl Fn(Vec3) -> f32,
-    sample_precipitation: impl Fn(Vec3) -> f32,
-) -> Vec<[f32; 4]> {
-    let ocean_level = planet_radius + continent_threshold;
-
+pub fn calculate_biome_colors(positions: &[[f32; 3]], inputs: &BiomeColorInputs) -> Vec<[f32; 4]> {
+    trace_span!("biome_colors", vertices = positions.len());
+    let color_noise = ColorNoise::new(inputs.variation);
     positions
         .iter()
         .map(|&[x, y, z]| {
             let position = Vec3::new(x, y, z);
             let direction = position.normalize();
-            let vertex_radius = position.length();
 
-            let height = vertex_radius - planet_radius;
-            let height_above_ocean = height - continent_threshold;
-            let is_land = vertex_radius > ocean_level;
+            if (inputs.is_lake)(direction) {
+                return LAKE_COLOR;
+            }
 
-            let base_temperature = sample_temperature(direction);
+            let height = position.length() - (inputs.surface_radius)(direction);
+            let height_above_ocean = height - inputs.sea_level;
+            let is_land = height > inputs.sea_level;
+
+            let base_temperature = (inputs.temperature)(direction);
             let temperature = if is_land {
-                base_temperature + land_temperature_bonus
+                base_temperature + inputs.land_temperature_bonus
             } else {
                 base_temperature
             };
-            let precipitation = sample_precipitation(direction);
+            let precipitation = (inputs.precipitation)(direction);
 
-            biome::biome_color(
+            let color = biome::biome_color(
                 height_above_ocean,
                 temperature,
                 precipitation,
                 height,
-                snow_threshold,
-                continent_threshold,
-                biome_colors,
-                biome_thresholds,
-            )
+                if is_land { inputs.snow.cover(direction, height, inputs.sea_level) } else { 0.0 },
+                inputs.sea_level,
+                inputs.colors,
+                inputs.thresholds,
+            );
+            let vegetation = if is_land {
+                biome::vegetation_share(temperature, precipitation, inputs.thresholds)
+            } else {
+                0.0
+            };
+            shade_color(color_noise.apply(color, direction, vegetation), (inputs.hillshade)(direction))
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube_grid::stitched_vertex_count;
+    use crate::mesh_validation::validate_mesh;
+use crate::temperature::TemperatureCubeMap;
+    use std::collections::{HashMap, HashSet};
+
+    fn no_hillshade() -> HillshadeConfig {
+        HillshadeConfig {
+            azimuth: 315.0,
+            elevation: 45.0,
+            strength: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_zero_hillshade_keeps_the_height_colors() {
+        let mut planet = PlanetData::half_land(13, 10.0);
+        // Steep relief between snow and deep ocean floor, both have a single color
+        let heights = planet.faces.iter_mut().flat_map(|face| face.heightmap.iter_mut());
+        for (i, height) in heights.enumerate() {
+            *height = if i % 3 == 0 { 3.0 } else { -2.0 };
+        }
+        planet.sea_level = 0.2;
+
+        let mesh = MeshData::from_planet(
+            &planet,
+            ViewMode::Continents,
+            SnowLine::height(0.5),
+            (&no_hillshade(), &ColorVariation::NONE),
+            Palette::Default,
+            MeshUvs::None,
+        );
+        for (position, color) in mesh.positions.iter().zip(&mesh.colors) {
+            let position = Vec3::from(*position);
+            let height = position.length() - planet.radius;
+            let expected = calculate_continent_view_color(height, position.normalize(), &SnowLine::height(0.5), 0.2);
+            assert_eq!(*color, expected);
+        }
+
+        let shaded = HillshadeConfig {
+            strength: 1.0,
+            ..no_hillshade()
+        };
+        let shaded_mesh = MeshData::from_planet(
+            &planet,
+            ViewMode::Continents,
+            SnowLine::height(0.5),
+            (&shaded, &ColorVariation::NONE),
+            Palette::Default,
+            MeshUvs::None,
+        );
+        assert_ne!(shaded_mesh.colors, mesh.colors);
+    }
+
+    #[test]
+    fn test_zero_color_variation_keeps_the_colors_of_any_seed() {
+        let mut planet = PlanetData::half_land(33, 10.0);
+        for height in planet.faces.iter_mut().flat_map(|face| face.heightmap.iter_mut()) {
+            *height = 0.5;
+        }
+        planet.sea_level = 0.2;
+        let mesh = |variation: ColorVariation| {
+            MeshData::from_planet(
+                &planet,
+                ViewMode::Continents,
+                SnowLine::height(3.0),
+                (&no_hillshade(), &variation),
+                Palette::Default,
+                MeshUvs::None,
+            )
+            .colors
+        };
+
+        let plain = mesh(ColorVariation::NONE);
+        assert_eq!(mesh(ColorVariation { strength: 0.0, seed: 7 }), plain);
+
+        // The same height is one color without the variation, a few percent around it with it
+        let varied = mesh(ColorVariation { strength: 0.05, seed: 7 });
+        assert!(plain.windows(2).all(|pair| pair[0] == pair[1]));
+        assert!(varied.windows(2).any(|pair| pair[0] != pair[1]));
+        for (varied, plain) in varied.iter().zip(&plain) {
+            assert!((0..3).all(|channel| (varied[channel] - plain[channel]).abs() < 0.05));
+        }
+    }
+
+    #[test]
+    fn test_cold_lowlands_are_white_with_temperatures() {
+        let mut planet = PlanetData::half_land(13, 10.0);
+        for height in planet.faces.iter_mut().flat_map(|face| face.heightmap.iter_mut()) {
+            *height = 0.5;
+        }
+        planet.sea_level = 0.2;
+        let temperatures = TemperatureCubeMap::build(32, 35.0, -30.0, -50.0, 50.0, Palette::Default);
+        // Far below the global threshold, only the temperatures can make snow here
+        let snow = SnowLine::height(3.0).with_temperature(&temperatures, 12.0, 5.0);
+
+        let mesh =
+            MeshData::from_planet(&planet, ViewMode::Continents, snow, (&no_hillshade(), &ColorVariation::NONE), Palette::Default, MeshUvs::None);
+        let color_towards = |target: Vec3| {
+            let closest = mesh.positions.iter().zip(&mesh.colors).max_by(|(a, _), (b, _)| {
+                Vec3::from(**a).normalize().dot(target).total_cmp(&Vec3::from(**b).normalize().dot(target))
+            });
+            *closest.unwrap().1
+        };
+        let is_snow = |color: [f32; 4]| color.iter().zip(SNOW_COLOR).all(|(a, b)| (a - b).abs() < 1e-5);
+        assert!(is_snow(color_towards(Vec3::Y)));
+        assert!(is_snow(color_towards(Vec3::NEG_Y)));
+        assert!(!is_snow(color_towards(Vec3::X)));
+    }
+
+    #[test]
+    fn test_crust_colors_tell_plate_types_and_microplates_apart() {
+        for plate_id in 0..8 {
+            let [r, _, b, _] = crust_color(plate_id, PlateType::Continental, &PlateSizeClass::Regular);
+            assert!(r > b, "continental plate {plate_id} isn't warm");
+            let [r, _, b, _] = crust_color(plate_id, PlateType::Oceanic, &PlateSizeClass::Regular);
+            assert!(b > r, "oceanic plate {plate_id} isn't blue");
+
+            let regular = crust_color(plate_id, PlateType::Oceanic, &PlateSizeClass::Regular);
+            let micro = crust_color(plate_id, PlateType::Oceanic, &PlateSizeClass::Micro);
+            assert!((0..3).all(|channel| micro[channel] > regular[channel]));
+        }
+    }
+
+    #[test]
+    fn test_oblate_planet_is_flattened_at_the_poles() {
+        let mut planet = PlanetData::half_land(13, 10.0);
+        planet.oblateness = 0.1;
+        for height in planet.faces.iter_mut().flat_map(|face| face.heightmap.iter_mut()) {
+            *height = 0.0;
+        }
+
+        let mesh = MeshData::from_planet(
+            &planet,
+            ViewMode::Continents,
+            SnowLine::height(0.5),
+            (&no_hillshade(), &ColorVariation::NONE),
+            Palette::Default,
+            MeshUvs::None,
+        );
+        let radius_towards = |target: Vec3| {
+            let closest = mesh.positions.iter().map(|&p| Vec3::from(p)).max_by(|a, b| {
+                a.normalize().dot(target).total_cmp(&b.normalize().dot(target))
+            });
+            closest.unwrap().length()
+        };
+        assert!((radius_towards(Vec3::Y) - 9.0).abs() < 1e-4);
+        assert!((radius_towards(Vec3::NEG_Y) - 9.0).abs() < 1e-4);
+        assert!((radius_towards(Vec3::X) - 10.0).abs() < 1e-4);
+
+        for position in &mesh.positions {
+            let position = Vec3::from(*position);
+            assert!((position.length() - planet.surface_radius(position)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_every_lod_is_a_closed_surface() {
+        let planet = PlanetData::half_land(13, 10.0);
+
+        // 5 doesn't divide the 12 cells per face edge
+        for (step, lod_cells) in [(1, 12), (2, 6), (4, 3), (5, 3)] {
+            let mesh = MeshData::from_planet_lod(
+                &planet,
+                ViewMode::Continents,
+                SnowLine::height(2.0),
+                (&no_hillshade(), &ColorVariation::NONE),
+                Palette::Default,
+                step,
+                MeshUvs::None,
+            );
+
+            // A cube sphere with m cells per face edge, without duplicate vertices on the edges
+            assert_eq!(mesh.positions.len(), 6 * lod_cells * lod_cells + 2, "step {step}");
+            assert_eq!(mesh.indices.len() / 3, 12 * lod_cells * lod_cells, "step {step}");
+
+            // Without cracks every edge is shared by exactly two triangles
+            let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+            for triangle in mesh.indices.chunks(3) {
+                for (a, b) in [(triangle[0], triangle[1]), (triangle[1], triangle[2]), (triangle[2], triangle[0])] {
+                    *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+                }
+            }
+            assert!(edges.values().all(|&count| count == 2), "step {step} has open edges");
+            let report = validate_mesh(&mesh);
+            assert!(report.is_clean(), "step {step}: {report}");
+        }
+    }
+
+    fn atlas_mesh(planet: &PlanetData, step: usize) -> MeshData {
+        MeshData::from_planet_lod(
+            planet,
+            ViewMode::Continents,
+            SnowLine::height(2.0),
+            (&no_hillshade(), &ColorVariation::NONE),
+            Palette::Default,
+            step,
+            MeshUvs::FaceAtlas,
+        )
+    }
+
+    #[test]
+    fn test_face_atlas_uvs_stay_in_the_tile_of_their_face() {
+        let planet = PlanetData::half_land(13, 10.0);
+        let (columns, rows) = FACE_ATLAS_TILES;
+
+        for (step, lod_cells) in [(1, 12), (5, 3)] {
+            let mesh = atlas_mesh(&planet, step);
+            let uvs = mesh.uvs.expect("atlas UVs");
+            let points_per_face = (lod_cells + 1) * (lod_cells + 1);
+            assert_eq!(uvs.len(), 6 * points_per_face, "step {step}");
+
+            // The vertices of a split grid come face by face
+            for (index, [u, v]) in uvs.into_iter().enumerate() {
+                let face = index / points_per_face;
+                let (column, row) = ((face % columns) as f32, (face / columns) as f32);
+                assert!(
+                    u > column / columns as f32 && u < (column + 1.0) / columns as f32,
+                    "step {step}: u {u} of face {face} outside its tile"
+                );
+                assert!(
+                    v > row / rows as f32 && v < (row + 1.0) / rows as f32,
+                    "step {step}: v {v} of face {face} outside its tile"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_face_atlas_uvs_sample_the_face_of_every_triangle() {
+        let planet = PlanetData::half_land(13, 10.0);
+        let size = planet.face_grid_size;
+        let (columns, rows) = FACE_ATLAS_TILES;
+        // Every texel holds the face of its tile, read with nearest filtering
+        let (width, height) = (columns * size, rows * size);
+        let texture: Vec<usize> = (0..width * height)
+            .map(|texel| {
+                let (x, y) = (texel % width, texel / width);
+                y / size * columns + x / size
+            })
+            .collect();
+        let sample = |[u, v]: [f32; 2]| {
+            let x = ((u * width as f32) as usize).min(width - 1);
+            let y = ((v * height as f32) as usize).min(height - 1);
+            texture[y * width + x]
+        };
+
+        for (step, lod_cells) in [(1, 12), (4, 3)] {
+            let mesh = atlas_mesh(&planet, step);
+            let uvs = mesh.uvs.expect("atlas UVs");
+            // Two triangles a cell, face by face
+            let triangles_per_face = 2 * lod_cells * lod_cells;
+            for (triangle, corners) in mesh.indices.chunks_exact(3).enumerate() {
+                let face = triangle / triangles_per_face;
+                let corner_uvs = [0, 1, 2].map(|corner| uvs[corners[corner] as usize]);
+                for uv in corner_uvs {
+                    assert_eq!(sample(uv), face, "step {step}: corner of triangle {triangle}");
+                }
+                let center = [0, 1].map(|axis| corner_uvs.iter().map(|uv| uv[axis]).sum::<f32>() / 3.0);
+                assert_eq!(sample(center), face, "step {step}: center of triangle {triangle}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_face_atlas_copies_keep_the_surface_closed() {
+        let planet = PlanetData::half_land(13, 10.0);
+        let stitched = MeshData::from_planet(
+            &planet,
+            ViewMode::Continents,
+            SnowLine::height(2.0),
+            (&no_hillshade(), &ColorVariation::NONE),
+            Palette::Default,
+            MeshUvs::None,
+        );
+        let split = atlas_mesh(&planet, 1);
+
+        assert!(stitched.uvs.is_none());
+        // Every copy of an edge point sits exactly where the shared vertex does
+        let key = |position: &[f32; 3]| position.map(f32::to_bits);
+        let shared: HashSet<[u32; 3]> = stitched.positions.iter().map(key).collect();
+        let copies: HashSet<[u32; 3]> = split.positions.iter().map(key).collect();
+        assert_eq!(copies, shared);
+        assert_eq!(split.indices.len(), stitched.indices.len());
+        let report = validate_mesh(&split);
+        assert!(report.is_clean(), "{report}");
+    }
+
+    #[test]
+    fn test_largest_planets_keep_the_vertex_spacing() {
+        // The largest radius the app offers, at the full vertex budget of the config
+        let (radius, face_grid_size) = (100.0, crate::get_config().generation.max_face_grid_size);
+
+        // The same vertex worked out in f64: +X face, land at height 1, no flattening
+        let cells = (face_grid_size - 1) as f64;
+        let reference = |x: usize, y: usize| {
+            let u = x as f64 / cells * 2.0 - 1.0;
+            let v = y as f64 / cells * 2.0 - 1.0;
+            let (px, py, pz) = (1.0, v, -u);
+            let scale = (radius as f64 + 1.0) / (px * px + py * py + pz * pz).sqrt();
+            [px * scale, py * scale, pz * scale]
+        };
+        let distance = |a: [f64; 3], b: [f64; 3]| {
+            ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+        };
+
+        let grid = StitchedGrid::new(face_grid_size);
+        for double_precision in [false, true] {
+            let mut planet = PlanetData::half_land(face_grid_size, radius);
+            planet.double_precision_vertices = double_precision;
+            let mesh = MeshData::from_planet(
+                &planet,
+                ViewMode::Continents,
+                SnowLine::height(2.0),
+                (&no_hillshade(), &ColorVariation::NONE),
+                Palette::Default,
+                MeshUvs::None,
+            );
+
+            // Along a row through the face center, where the cells are largest, and one next to
+            // the corner, where they are smallest
+            for y in [face_grid_size / 2, 1] {
+                let mut row: Vec<(usize, usize)> = grid
+                    .vertices()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (face, _, vertex_y))| *face == 0 && *vertex_y == y)
+                    .map(|(index, &(_, x, _))| (x, index))
+                    .collect();
+                row.sort_unstable();
+
+                for pair in row.windows(2) {
+                    let ((x0, i0), (x1, i1)) = (pair[0], pair[1]);
+                    let spacing = distance(mesh.positions[i0].map(f64::from), mesh.positions[i1].map(f64::from));
+                    let expected = distance(reference(x0, y), reference(x1, y));
+                    assert!(
+                        (spacing - expected).abs() < expected * 1e-4,
+                        "row {y}, x {x0}: {spacing} instead of {expected}"
+                    );
+                    if double_precision {
+                        // Rounded once, from the exact position
+                        assert_eq!(mesh.positions[i0], reference(x0, y).map(|c| c as f32), "row {y}, x {x0}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_coarse_grids_keep_every_distinct_vertex() {
+        for face_grid_size in [2, 3, 4, 6] {
+            let planet = PlanetData::half_land(face_grid_size, 10.0);
+            let mesh = MeshData::from_planet(
+                &planet,
+                ViewMode::Continents,
+                SnowLine::height(2.0),
+                (&no_hillshade(), &ColorVariation::NONE),
+                Palette::Default,
+                MeshUvs::None,
+            );
+
+            assert_eq!(mesh.positions.len(), stitched_vertex_count(face_grid_size));
+            let mut directions: Vec<[i64; 3]> = mesh
+                .positions
+                .iter()
+                .map(|&p| Vec3::from(p).normalize().to_array().map(|c| (c * 1e5).round() as i64))
+                .collect();
+            directions.sort_unstable();
+            directions.dedup();
+            assert_eq!(directions.len(), mesh.positions.len(), "size {face_grid_size} merged vertices");
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Topology checks on a built mesh
+//!
+//! A closed planet surface has every triangle with some area, wound counter-clockwise seen
+//! from outside, each edge shared by exactly two triangles and every vertex used. The report
+//! lists what breaks that, so a stray flipped or collapsed triangle near the cube corners shows
+//! up in the log instead of only on screen.
+
+use crate::mesh_data::MeshData;
+use glam::Vec3;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Offending triangles, edges or vertices listed in the report text of each defect
+pub const SHOWN_PER_DEFECT: usize = 5;
+
+/// Relative to its longest edge squared, a triangle with a smaller cross product has no area
+const DEGENERATE_AREA_RATIO: f32 = 1e-6;
+
+/// Defects of a mesh. Triangles are numbered in index buffer order, the first one is made of
+/// `indices[0..3]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshReport {
+    pub triangles: usize,
+    /// Triangles with an index past the last vertex, left out of every other check
+    pub out_of_range: Vec<usize>,
+    /// Triangles without area: a repeated vertex or three points on a line
+    pub degenerate: Vec<usize>,
+    /// Triangles over the same three vertices as an earlier one, in either winding
+    pub duplicate: Vec<usize>,
+    /// Edges, as vertex pairs lowest first, shared by more than two triangles
+    pub non_manifold_edges: Vec<[u32; 2]>,
+    /// Triangles wound clockwise seen from outside, facing into the planet
+    pub flipped: Vec<usize>,
+    /// Vertices no triangle uses
+    pub unreferenced_vertices: Vec<u32>,
+}
+
+impl MeshReport {
+    /// Nothing wrong with the mesh
+    pub fn is_clean(&self) -> bool {
+        self.out_of_range.is_empty()
+            && self.degenerate.is_empty()
+            && self.duplicate.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.flipped.is_empty()
+            && self.unreferenced_vertices.is_empty()
+    }
+}
+
+impl fmt::Display for MeshReport {
+    /// One line per defect, its count and the first few offenders
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "{} triangles, no defects", self.triangles);
+        }
+        write!(f, "{} triangles", self.triangles)?;
+        let mut defect = |name: &str, items: Vec<String>| -> fmt::Result {
+            if items.is_empty() {
+                return Ok(());
+            }
+            let shown: Vec<&str> = items.iter().take(SHOWN_PER_DEFECT).map(String::as_str).collect();
+            let more = if items.len() > SHOWN_PER_DEFECT { ", ..." } else { "" };
+            write!(f, "\n{} {name}: {}{more}", items.len(), shown.join(", "))
+        };
+        let numbers = |items: &[usize]| items.iter().map(usize::to_string).collect();
+        defect("triangles with indices out of range", numbers(&self.out_of_range))?;
+        defect("degenerate triangles", numbers(&self.degenerate))?;
+        defect("duplicate triangles", numbers(&self.duplicate))?;
+        defect(
+            "non-manifold edges",
+            self.non_manifold_edges.iter().map(|[a, b]| format!("{a}-{b}")).collect(),
+        )?;
+        defect("flipped triangles", numbers(&self.flipped))?;
+        defect(
+            "unreferenced vertices",
+            self.unreferenced_vertices.iter().map(u32::to_string).collect(),
+        )
+    }
+}
+
+/// Check the topology of a mesh around the origin. Outward is the vertex normals of a triangle
+/// added up, or the direction of its center when the mesh has no normals.
+pub fn validate_mesh(mesh: &MeshData) -> MeshReport {
+    let vertex_count = mesh.positions.len();
+    let has_normals = mesh.normals.len() == vertex_count;
+    let mut report = MeshReport {
+        triangles: mesh.indices.len() / 3,
+        ..MeshReport::default()
+    };
+    let mut referenced = vec![false; vertex_count];
+    let mut seen_triangles = HashSet::new();
+    let mut edge_uses: HashMap<[u32; 2], usize> = HashMap::new();
+
+    for (triangle, corners) in mesh.indices.chunks_exact(3).enumerate() {
+        let [a, b, c] = [corners[0], corners[1], corners[2]];
+        if [a, b, c].iter().any(|&index| index as usize >= vertex_count) {
+            report.out_of_range.push(triangle);
+            continue;
+        }
+        for index in [a, b, c] {
+            referenced[index as usize] = true;
+        }
+
+        let mut key = [a, b, c];
+        key.sort_unstable();
+        if !seen_triangles.insert(key) {
+            report.duplicate.push(triangle);
+        }
+        for edge in [[a, b], [b, c], [c, a]] {
+            if edge[0] != edge[1] {
+                *edge_uses.entry([edge[0].min(edge[1]), edge[0].max(edge[1])]).or_insert(0) += 1;
+            }
+        }
+
+        let [pa, pb, pc] = [a, b, c].map(|index| Vec3::from(mesh.positions[index as usize]));
+        let cross = (pb - pa).cross(pc - pa);
+        let longest_edge_sq = (pb - pa).length_squared().max((pc - pb).length_squared()).max((pa - pc).length_squared());
+        if a == b || b == c || c == a || cross.length() <= DEGENERATE_AREA_RATIO * longest_edge_sq {
+            report.degenerate.push(triangle);
+            continue;
+        }
+        let outward = if has_normals {
+            [a, b, c].iter().map(|&index| Vec3::from(mesh.normals[index as usize])).sum()
+        } else {
+            pa + pb + pc
+        };
+        if cross.dot(outward) < 0.0 {
+            report.flipped.push(triangle);
+        }
+    }
+
+    report.non_manifold_edges = edge_uses.into_iter().filter(|&(_, uses)| uses > 2).map(|(edge, _)| edge).collect();
+    report.non_manifold_edges.sort_unstable();
+    report.unreferenced_vertices = (0..vertex_count as u32).filter(|&index| !referenced[index as usize]).collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit octahedron, every face wound counter-clockwise seen from outside
+    fn octahedron() -> MeshData {
+        let positions = vec![
+            [1.0, 0.0, 0.0],
+            [-1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, -1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, -1.0],
+        ];
+        let indices = vec![
+            0, 2, 4, 4, 2, 1, 1, 2, 5, 5, 2, 0, //
+            4, 3, 0, 1, 3, 4, 5, 3, 1, 0, 3, 5,
+        ];
+        mesh(positions, indices)
+    }
+
+    fn mesh(positions: Vec<[f32; 3]>, indices: Vec<u32>) -> MeshData {
+        MeshData {
+            normals: positions.iter().map(|&p| Vec3::from(p).normalize_or_zero().to_array()).collect(),
+            colors: vec![[1.0; 4]; positions.len()],
+            uvs: None,
+            positions,
+            indices,
+        }
+    }
+
+    #[test]
+    fn test_closed_surface_is_clean() {
+        let report = validate_mesh(&octahedron());
+
+        assert!(report.is_clean(), "{report}");
+        assert_eq!(report.triangles, 8);
+    }
+
+    #[test]
+    fn test_degenerate_triangles() {
+        let mut mesh = octahedron();
+        // A repeated vertex and three points on a line through the middle of an edge
+        mesh.positions.push([0.5, 0.5, 0.0]);
+        mesh.normals.push([0.5, 0.5, 0.0]);
+        mesh.indices.extend_from_slice(&[0, 0, 2, 0, 6, 2]);
+
+        let report = validate_mesh(&mesh);
+
+        assert_eq!(report.degenerate, [8, 9]);
+        assert!(report.flipped.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_triangles_in_either_winding() {
+        let mut mesh = octahedron();
+        mesh.indices.extend_from_slice(&[0, 2, 4, 4, 2, 0]);
+
+        let report = validate_mesh(&mesh);
+
+        assert_eq!(report.duplicate, [8, 9]);
+    }
+
+    #[test]
+    fn test_edge_of_three_triangles_is_non_manifold() {
+        let mut mesh = octahedron();
+        // A fin from the 0-2 edge to the origin
+        mesh.positions.push([0.0, 0.0, 0.0]);
+        mesh.normals.push([0.0, 0.0, 1.0]);
+        mesh.indices.extend_from_slice(&[0, 2, 6]);
+
+        let report = validate_mesh(&mesh);
+
+        assert_eq!(report.non_manifold_edges, [[0, 2]]);
+        assert!(report.duplicate.is_empty());
+    }
+
+    #[test]
+    fn test_flipped_triangle() {
+        let mut mesh = octahedron();
+        mesh.indices.swap(4, 5);
+
+        let report = validate_mesh(&mesh);
+
+        assert_eq!(report.flipped, [1]);
+        assert!(report.non_manifold_edges.is_empty());
+    }
+
+    #[test]
+    fn test_flipped_without_normals_uses_the_triangle_center() {
+        let mut mesh = octahedron();
+        mesh.normals.clear();
+        mesh.indices.swap(4, 5);
+
+        assert_eq!(validate_mesh(&mesh).flipped, [1]);
+    }
+
+    #[test]
+    fn test_unreferenced_vertices() {
+        let mut mesh = octahedron();
+        mesh.positions.push([2.0, 2.0, 2.0]);
+        mesh.normals.push([1.0, 0.0, 0.0]);
+
+        let report = validate_mesh(&mesh);
+
+        assert_eq!(report.unreferenced_vertices, [6]);
+    }
+
+    #[test]
+    fn test_out_of_range_triangles_are_skipped() {
+        let mut mesh = octahedron();
+        mesh.indices.extend_from_slice(&[0, 2, 9]);
+
+        let report = validate_mesh(&mesh);
+
+        assert_eq!(report.out_of_range, [8]);
+        assert!(report.non_manifold_edges.is_empty());
+    }
+
+    #[test]
+    fn test_report_lists_the_first_offenders() {
+        let mut mesh = octahedron();
+        for _ in 0..7 {
+            mesh.indices.extend_from_slice(&[0, 2, 4]);
+        }
+
+        let text = validate_mesh(&mesh).to_string();
+
+        assert!(text.contains("7 duplicate triangles: 8, 9, 10, 11, 12, ..."), "{text}");
+        assert!(text.contains("3 non-manifold edges: 0-2, 0-4, 2-4"), "{text}");
+        assert_eq!(validate_mesh(&octahedron()).to_string(), "8 triangles, no defects");
+    }
+}
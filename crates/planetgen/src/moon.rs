@@ -0,0 +1,207 @@
+//! Small cratered moons to put in the sky around a planet
+//!
+//! A moon is a coarse cube sphere with gentle noise and the same impact craters as a barren
+//! world. Sizes and heights are in planet radii, scale the mesh by the planet radius to place
+//! it next to a planet of any size.
+
+use crate::config::{CraterConfig, MoonConfig, NoiseConfig};
+use crate::craters::{apply_craters, generate_craters};
+use crate::cube_grid::StitchedGrid;
+use crate::generator::cell_direction;
+use crate::grid::Grid2D;
+use crate::mesh_data::MeshData;
+use crate::planet::CubeFace;
+use glam::Vec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Surface color of a moon at its mean height
+const MOON_COLOR: [f32; 3] = [0.62, 0.6, 0.58];
+/// How much lighter the highest and darker the lowest terrain gets than `MOON_COLOR`
+const MOON_SHADE_RANGE: f32 = 0.35;
+
+/// Heightmap of a generated moon
+pub struct MoonData {
+    /// Radius of the zero-height surface in planet radii
+    pub radius: f32,
+    pub face_grid_size: usize,
+    faces: [CubeFace; 6],
+}
+
+/// Cratered moon with a radius of `radius_fraction` planet radii. The same seed gives the same
+/// moon, the resolution and the craters come from the `[moon]` and `[craters]` config.
+pub fn generate_moon(seed: u64, radius_fraction: f32) -> MoonData {
+    let config = crate::get_config();
+    generate_moon_with(seed, radius_fraction, &config.moon, &config.craters)
+}
+
+fn generate_moon_with(
+    seed: u64,
+    radius_fraction: f32,
+    config: &MoonConfig,
+    craters: &CraterConfig,
+) -> MoonData {
+    let n = config.face_grid_size.max(2);
+    let radius = radius_fraction.max(0.0);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let roughness = NoiseConfig::new(
+        rng.random(),
+        config.roughness_frequency,
+        config.roughness_amplitude * radius,
+    );
+    let mut faces: [CubeFace; 6] = std::array::from_fn(|face| CubeFace {
+        heightmap: Grid2D::from_fn(n, n, |x, y| roughness.sample(cell_direction(n, face, x, y))),
+    });
+
+    let crater_config = CraterConfig {
+        density: config.crater_density,
+        ..craters.clone()
+    };
+    let impacts = generate_craters(&mut rng, &crater_config);
+    apply_craters(&mut faces, n, &impacts, radius, &crater_config);
+
+    MoonData {
+        radius,
+        face_grid_size: n,
+        faces,
+    }
+}
+
+impl MoonData {
+    /// Terrain height relative to `radius` in the direction `dir`, bilinearly interpolated
+    pub fn sample_height(&self, dir: Vec3) -> f32 {
+        crate::cubemap_utils::sample_bilinear(self.face_grid_size, dir, |face, x, y| {
+            self.faces[face].heightmap[y][x]
+        })
+    }
+
+    /// Mesh of the moon around its center, grey with lighter highlands and darker crater floors.
+    /// Normals follow the terrain so the craters show under a light.
+    pub fn mesh_data(&self) -> MeshData {
+        let n = self.face_grid_size;
+        let (min, max) = self
+            .faces
+            .iter()
+            .flat_map(|face| face.heightmap.iter())
+            .fold((f32::MAX, f32::MIN), |(min, max), &h| (min.min(h), max.max(h)));
+        let span = (max - min).max(f32::EPSILON);
+
+        let grid = StitchedGrid::new(n);
+        let mut positions = Vec::with_capacity(grid.vertices().len());
+        let mut colors = Vec::with_capacity(grid.vertices().len());
+        for &(face, x, y) in grid.vertices() {
+            let height = self.faces[face].heightmap[y][x];
+            let dir = cell_direction(n, face, x, y);
+            positions.push((dir * (self.radius + height)).to_array());
+            let shade = 1.0 + ((height - min) / span * 2.0 - 1.0) * MOON_SHADE_RANGE;
+            colors.push([MOON_COLOR[0] * shade, MOON_COLOR[1] * shade, MOON_COLOR[2] * shade, 1.0]);
+        }
+        let indices = grid.triangle_indices();
+
+        let normals = terrain_normals(&positions, &indices);
+        MeshData {
+            positions,
+            normals,
+            colors,
+            uvs: None,
+            indices,
+        }
+    }
+}
+
+/// Vertex normals as the area weighted sum of the normals of the triangles around each vertex,
+/// turned outwards from the center
+fn terrain_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut sums = vec![Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i] as usize]));
+        // The cross product is twice the triangle area long, larger triangles count more
+        let normal = (b - a).cross(c - a);
+        for &index in triangle {
+            sums[index as usize] += normal;
+        }
+    }
+    sums.iter()
+        .zip(positions)
+        .map(|(sum, position)| {
+            let outward = Vec3::from(*position).normalize_or_zero();
+            let normal = sum.try_normalize().unwrap_or(outward);
+            let normal = if normal.dot(outward) < 0.0 { -normal } else { normal };
+            normal.to_array()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> (MoonConfig, CraterConfig) {
+        let moon = MoonConfig {
+            face_grid_size: 17,
+            crater_density: 20.0,
+            roughness_frequency: 3.0,
+            roughness_amplitude: 0.01,
+        };
+        let craters = CraterConfig {
+            density: 0.0,
+            min_radius_deg: 3.0,
+            max_radius_deg: 25.0,
+            size_exponent: 1.8,
+            depth_ratio: 0.1,
+            rim_height: 0.25,
+            rim_sharpness: 0.5,
+            ejecta_extent: 2.0,
+            base_height: 1.0,
+            roughness_frequency: 4.0,
+            roughness_amplitude: 0.15,
+            craters_on_terran: false,
+        };
+        (moon, craters)
+    }
+
+    fn moon(seed: u64) -> MoonData {
+        let (moon, craters) = config();
+        generate_moon_with(seed, 0.25, &moon, &craters)
+    }
+
+    #[test]
+    fn test_same_seed_gives_the_same_moon() {
+        let dir = Vec3::new(0.3, -0.5, 0.8).normalize();
+
+        assert_eq!(moon(7).sample_height(dir), moon(7).sample_height(dir));
+        let heights = |moon: &MoonData| moon.faces[4].heightmap.clone();
+        assert_ne!(heights(&moon(7)), heights(&moon(8)));
+    }
+
+    #[test]
+    fn test_craters_dig_in_and_heights_stay_small_against_the_radius() {
+        let moon = moon(3);
+        let heights: Vec<f32> = moon.faces.iter().flat_map(|face| face.heightmap.iter().copied()).collect();
+
+        assert!(heights.iter().all(|height| height.abs() < moon.radius * 0.2));
+        let lowest = heights.iter().copied().fold(f32::MAX, f32::min);
+        // Deeper than the noise alone can go
+        assert!(lowest < -moon.radius * 0.01, "lowest {lowest}");
+    }
+
+    #[test]
+    fn test_mesh_is_closed_with_outward_normals() {
+        let moon = moon(5);
+        let mesh = moon.mesh_data();
+        let n = moon.face_grid_size;
+
+        assert_eq!(mesh.positions.len(), crate::cube_grid::stitched_vertex_count(n));
+        assert_eq!(mesh.normals.len(), mesh.positions.len());
+        assert_eq!(mesh.colors.len(), mesh.positions.len());
+        assert_eq!(mesh.indices.len(), 6 * (n - 1) * (n - 1) * 6);
+        for (normal, position) in mesh.normals.iter().zip(&mesh.positions) {
+            let (normal, position) = (Vec3::from(*normal), Vec3::from(*position));
+            assert!((normal.length() - 1.0).abs() < 1e-4);
+            assert!(normal.dot(position.normalize()) > 0.0);
+        }
+        let report = crate::mesh_validation::validate_mesh(&mesh);
+        assert!(report.is_clean(), "{report}");
+    }
+}
@@ -0,0 +1,162 @@
+//! Made-up names for the features of a planet
+//!
+//! Names are strung together from syllables, each a consonant onset, a vowel and sometimes a
+//! closing consonant. Combinations that are hard to say are thrown away and rolled again, so
+//! every name passes [`is_pronounceable`].
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+
+const ONSETS: &[&str] = &[
+    "b", "d", "g", "k", "l", "m", "n", "r", "s", "t", "v", "z", "th", "br", "dr", "kr", "tr", "st", "sh", "",
+];
+const VOWELS: &[&str] = &["a", "e", "i", "o", "u", "a", "e", "o", "ae", "ia", "ou"];
+const CODAS: &[&str] = &["", "", "", "", "n", "r", "l", "s", "th", "m"];
+
+/// Syllables of a name, fewer are more likely
+const SYLLABLES: [usize; 4] = [2, 2, 3, 3];
+
+/// Letters of the shortest and of the longest name
+const MIN_LETTERS: usize = 3;
+const MAX_LETTERS: usize = 12;
+
+/// Most consonants, and most vowels, in a row
+const MAX_CONSONANT_RUN: usize = 2;
+const MAX_VOWEL_RUN: usize = 2;
+
+/// Rolls before giving up on the syllable table and building a name that can't fail
+const MAX_ATTEMPTS: usize = 64;
+
+/// Hands out names, the same seed gives the same names in the same order. A name is never
+/// handed out twice.
+pub struct NameGenerator {
+    rng: StdRng,
+    used: HashSet<String>,
+}
+
+impl NameGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            used: HashSet::new(),
+        }
+    }
+
+    /// A new capitalized name
+    pub fn next_name(&mut self) -> String {
+        let mut name = None;
+        for _ in 0..MAX_ATTEMPTS {
+            let candidate = self.roll();
+            if is_pronounceable(&candidate) && !self.used.contains(&candidate) {
+                name = Some(candidate);
+                break;
+            }
+        }
+        let name = name.unwrap_or_else(|| self.fallback());
+        self.used.insert(name.clone());
+        name
+    }
+
+    fn roll(&mut self) -> String {
+        let syllables = SYLLABLES[self.rng.random_range(0..SYLLABLES.len())];
+        let mut name = String::new();
+        for _ in 0..syllables {
+            name.push_str(self.pick(ONSETS));
+            name.push_str(self.pick(VOWELS));
+            name.push_str(self.pick(CODAS));
+        }
+        capitalize(&name)
+    }
+
+    /// Syllables of a single consonant and a single vowel are always pronounceable, more of
+    /// them are added until the name is new
+    fn fallback(&mut self) -> String {
+        let mut name = String::new();
+        loop {
+            let onset = ONSETS[self.rng.random_range(0..12)];
+            let vowel = VOWELS[self.rng.random_range(0..5)];
+            name.push_str(onset);
+            name.push_str(vowel);
+            let candidate = capitalize(&name);
+            if candidate.len() >= MIN_LETTERS && !self.used.contains(&candidate) {
+                return candidate;
+            }
+        }
+    }
+
+    fn pick(&mut self, table: &[&'static str]) -> &'static str {
+        table[self.rng.random_range(0..table.len())]
+    }
+}
+
+/// Whether `name` is easy to say: letters only, neither too short nor too long, with no more
+/// than two consonants or two vowels in a row and no letter three times in a row
+pub fn is_pronounceable(name: &str) -> bool {
+    let letters: Vec<char> = name.chars().map(|c| c.to_ascii_lowercase()).collect();
+    if !(MIN_LETTERS..=MAX_LETTERS).contains(&letters.len()) || !letters.iter().all(char::is_ascii_alphabetic) {
+        return false;
+    }
+    let is_vowel = |c: char| "aeiou".contains(c);
+    let longest_run = |vowels: bool| {
+        letters
+            .chunk_by(|&a, &b| is_vowel(a) == is_vowel(b))
+            .filter(|run| is_vowel(run[0]) == vowels)
+            .map(<[char]>::len)
+            .max()
+            .unwrap_or(0)
+    };
+    let tripled = letters.windows(3).any(|w| w[0] == w[1] && w[1] == w[2]);
+    longest_run(false) <= MAX_CONSONANT_RUN && longest_run(true) <= MAX_VOWEL_RUN && !tripled
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(seed: u64, count: usize) -> Vec<String> {
+        let mut generator = NameGenerator::new(seed);
+        (0..count).map(|_| generator.next_name()).collect()
+    }
+
+    #[test]
+    fn test_same_seed_gives_the_same_names() {
+        assert_eq!(names(42, 20), names(42, 20));
+        assert_ne!(names(42, 20), names(43, 20));
+    }
+
+    #[test]
+    fn test_names_are_pronounceable_and_unique() {
+        let names = names(7, 500);
+
+        for name in &names {
+            assert!(is_pronounceable(name), "{name}");
+            assert!(name.starts_with(|c: char| c.is_ascii_uppercase()), "{name}");
+        }
+        let unique: HashSet<&String> = names.iter().collect();
+        assert_eq!(unique.len(), names.len());
+    }
+
+    #[test]
+    fn test_hard_names_are_not_pronounceable() {
+        for (name, expected) in [
+            ("Tharos", true),
+            ("Ka", false),
+            ("Strkal", false),
+            ("Aeiou", false),
+            ("Baaan", false),
+            ("Kel-tor", false),
+            ("Abcdefghijklm", false),
+        ] {
+            assert_eq!(is_pronounceable(name), expected, "{name}");
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Color palettes of the overlays and the plate view
+//!
+//! `Default` keeps the colors every overlay had before palettes existed. The others replace the
+//! overlay scales with ones that stay readable with color vision deficiencies: sequential
+//! values go dark to light along a single ramp, and the plate view gets plate and boundary
+//! colors that don't rely on telling red from green.
+
+use crate::boundaries::BoundaryType;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Viridis sampled at nine evenly spaced points
+const VIRIDIS: [Vec3; 9] = [
+    Vec3::new(0.267, 0.005, 0.329),
+    Vec3::new(0.278, 0.173, 0.478),
+    Vec3::new(0.231, 0.322, 0.545),
+    Vec3::new(0.173, 0.447, 0.557),
+    Vec3::new(0.129, 0.569, 0.549),
+    Vec3::new(0.157, 0.682, 0.502),
+    Vec3::new(0.369, 0.788, 0.384),
+    Vec3::new(0.678, 0.863, 0.188),
+    Vec3::new(0.992, 0.906, 0.145),
+];
+
+/// Cividis sampled at nine evenly spaced points
+const CIVIDIS: [Vec3; 9] = [
+    Vec3::new(0.000, 0.133, 0.306),
+    Vec3::new(0.071, 0.208, 0.439),
+    Vec3::new(0.231, 0.286, 0.424),
+    Vec3::new(0.341, 0.365, 0.427),
+    Vec3::new(0.439, 0.443, 0.451),
+    Vec3::new(0.541, 0.529, 0.475),
+    Vec3::new(0.651, 0.616, 0.459),
+    Vec3::new(0.769, 0.710, 0.424),
+    Vec3::new(0.996, 0.910, 0.220),
+];
+
+/// Navy through blue and orange to pale yellow, the brightness rises the whole way
+const HIGH_CONTRAST: [Vec3; 4] = [
+    Vec3::new(0.05, 0.05, 0.35),
+    Vec3::new(0.0, 0.45, 0.70),
+    Vec3::new(0.90, 0.62, 0.0),
+    Vec3::new(1.0, 1.0, 0.75),
+];
+
+/// Blue through white to orange, for values on both sides of zero
+const HIGH_CONTRAST_DIVERGING: [Vec3; 3] = [
+    Vec3::new(0.0, 0.30, 0.60),
+    Vec3::new(1.0, 1.0, 1.0),
+    Vec3::new(0.80, 0.40, 0.0),
+];
+
+/// Plate colors of the color-blind palettes, Paul Tol's light scheme. They are pale enough for
+/// the saturated boundary colors to stand out on any of them.
+const PLATE_COLORS: [[f32; 4]; 9] = [
+    [0.467, 0.667, 0.867, 1.0],
+    [0.600, 0.867, 1.000, 1.0],
+    [0.267, 0.733, 0.667, 1.0],
+    [0.733, 0.800, 0.200, 1.0],
+    [0.667, 0.667, 0.000, 1.0],
+    [0.933, 0.867, 0.533, 1.0],
+    [0.933, 0.533, 0.400, 1.0],
+    [1.000, 0.667, 0.733, 1.0],
+    [0.867, 0.867, 0.867, 1.0],
+];
+
+/// Color set of every overlay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Palette {
+    /// The original overlay colors
+    #[default]
+    Default,
+    Viridis,
+    Cividis,
+    /// Strong brightness steps, for small screens and low vision as much as for color vision
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [
+        Palette::Default,
+        Palette::Viridis,
+        Palette::Cividis,
+        Palette::HighContrast,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Palette::Default => "Default",
+            Palette::Viridis => "Viridis",
+            Palette::Cividis => "Cividis",
+            Palette::HighContrast => "High contrast",
+        }
+    }
+
+    /// Color at `t` from 0 to 1 on the palette's sequential scale, `None` for `Default`, where
+    /// every overlay keeps its own scale
+    pub(crate) fn sequential(self, t: f32) -> Option<Vec3> {
+        let stops: &[Vec3] = match self {
+            Palette::Default => return None,
+            Palette::Viridis => &VIRIDIS,
+            Palette::Cividis => &CIVIDIS,
+            Palette::HighContrast => &HIGH_CONTRAST,
+        };
+        Some(lerp_stops(stops, t))
+    }
+
+    /// Color of a value from -1 to 1 centered on 0, `None` for `Default`. Viridis and cividis
+    /// have no neutral middle, they run over the whole range like any other value.
+    pub(crate) fn diverging(self, value: f32) -> Option<Vec3> {
+        let t = (value.clamp(-1.0, 1.0) + 1.0) * 0.5;
+        match self {
+            Palette::HighContrast => Some(lerp_stops(&HIGH_CONTRAST_DIVERGING, t)),
+            _ => self.sequential(t),
+        }
+    }
+
+    /// Color a plate boundary is drawn in. The color-blind palettes use vermillion, blue and
+    /// black, which differ in brightness as well as hue.
+    pub fn boundary_color(self, boundary: BoundaryType) -> [f32; 3] {
+        if self == Palette::Default {
+            return boundary.color();
+        }
+        match boundary {
+            BoundaryType::Convergent => [0.84, 0.37, 0.0],
+            BoundaryType::Divergent => [0.0, 0.45, 0.70],
+            BoundaryType::Transform => [0.0, 0.0, 0.0],
+        }
+    }
+
+    /// Color of a plate in the plate view, `debug_color` is its random color for `Default`
+    pub fn plate_color(self, plate_id: usize, debug_color: [f32; 4]) -> [f32; 4] {
+        if self == Palette::Default {
+            return debug_color;
+        }
+        PLATE_COLORS[plate_id % PLATE_COLORS.len()]
+    }
+}
+
+/// Linear interpolation between evenly spaced color stops, `t` from 0 to 1
+pub(crate) fn lerp_stops(stops: &[Vec3], t: f32) -> Vec3 {
+    let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let i = (t as usize).min(stops.len() - 2);
+    stops[i].lerp(stops[i + 1], t - i as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_color(actual: Vec3, expected: [f32; 3]) {
+        assert!(
+            actual.abs_diff_eq(Vec3::from(expected), 1e-4),
+            "{actual} instead of {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_default_has_no_scale_of_its_own() {
+        assert_eq!(Palette::Default.sequential(0.5), None);
+        assert_eq!(Palette::Default.diverging(0.5), None);
+        assert_eq!(
+            Palette::Default.plate_color(3, [0.1, 0.2, 0.3, 1.0]),
+            [0.1, 0.2, 0.3, 1.0]
+        );
+        assert_eq!(
+            Palette::Default.boundary_color(BoundaryType::Convergent),
+            BoundaryType::Convergent.color()
+        );
+    }
+
+    #[test]
+    fn test_viridis_golden_values() {
+        let viridis = |t| Palette::Viridis.sequential(t).unwrap();
+        assert_color(viridis(0.0), [0.267, 0.005, 0.329]);
+        assert_color(viridis(0.5), [0.129, 0.569, 0.549]);
+        assert_color(viridis(0.9375), [0.835, 0.8845, 0.1665]);
+        assert_color(viridis(1.0), [0.992, 0.906, 0.145]);
+        assert_color(
+            Palette::Viridis.diverging(0.0).unwrap(),
+            [0.129, 0.569, 0.549],
+        );
+    }
+
+    #[test]
+    fn test_cividis_golden_values() {
+        let cividis = |t| Palette::Cividis.sequential(t).unwrap();
+        assert_color(cividis(0.0), [0.0, 0.133, 0.306]);
+        assert_color(cividis(0.0625), [0.0355, 0.1705, 0.3725]);
+        assert_color(cividis(0.5), [0.439, 0.443, 0.451]);
+        assert_color(cividis(1.0), [0.996, 0.910, 0.220]);
+        assert_color(
+            Palette::Cividis.diverging(-1.0).unwrap(),
+            [0.0, 0.133, 0.306],
+        );
+    }
+
+    #[test]
+    fn test_high_contrast_golden_values() {
+        let high_contrast = |t| Palette::HighContrast.sequential(t).unwrap();
+        assert_color(high_contrast(0.0), [0.05, 0.05, 0.35]);
+        assert_color(high_contrast(0.5), [0.45, 0.535, 0.35]);
+        assert_color(high_contrast(1.0), [1.0, 1.0, 0.75]);
+        assert_color(
+            Palette::HighContrast.diverging(-1.0).unwrap(),
+            [0.0, 0.30, 0.60],
+        );
+        assert_color(
+            Palette::HighContrast.diverging(0.0).unwrap(),
+            [1.0, 1.0, 1.0],
+        );
+        assert_color(
+            Palette::HighContrast.diverging(0.5).unwrap(),
+            [0.9, 0.7, 0.5],
+        );
+    }
+
+    #[test]
+    fn test_color_blind_palettes_share_the_plate_view_colors() {
+        for palette in [Palette::Viridis, Palette::Cividis, Palette::HighContrast] {
+            assert_eq!(
+                palette.boundary_color(BoundaryType::Convergent),
+                [0.84, 0.37, 0.0]
+            );
+            assert_eq!(
+                palette.boundary_color(BoundaryType::Divergent),
+                [0.0, 0.45, 0.70]
+            );
+            assert_eq!(
+                palette.boundary_color(BoundaryType::Transform),
+                [0.0, 0.0, 0.0]
+            );
+            assert_eq!(palette.plate_color(2, [1.0; 4]), [0.267, 0.733, 0.667, 1.0]);
+            // Wraps around after the last color
+            assert_eq!(
+                palette.plate_color(11, [1.0; 4]),
+                palette.plate_color(2, [1.0; 4])
+            );
+        }
+    }
+
+    #[test]
+    fn test_sequential_scales_get_brighter() {
+        let luminance = |color: Vec3| color.dot(Vec3::new(0.299, 0.587, 0.114));
+        for palette in [Palette::Viridis, Palette::Cividis, Palette::HighContrast] {
+            let mut previous = f32::MIN;
+            for i in 0..=32 {
+                let brightness = luminance(palette.sequential(i as f32 / 32.0).unwrap());
+                assert!(brightness > previous, "{palette:?} gets darker at {i}/32");
+                previous = brightness;
+            }
+        }
+    }
+}
@@ -0,0 +1,536 @@
+//! Planet generation as a list of stages
+//!
+//! [`PlanetGenerator::generate`] runs the [`GenerationPipeline::default`] stages one after the
+//! other on a shared [`GenerationContext`]. A new generation step is a new stage inserted at
+//! the right spot, stages named in the config's `pipeline.disabled_stages` are skipped.
+
+use crate::boundaries::BoundaryData;
+use crate::craters::WorldType;
+use crate::crust_age::CrustAgeMap;
+use crate::generator::{
+    PlanetGenerator, apply_crust_subsidence, classify_plate_types, majority_smooth, raise_above,
+};
+use crate::grid::Grid2D;
+use crate::lakes::LakeMap;
+use crate::planet::{CubeFace, PlateMap};
+use crate::plate::TectonicPlate;
+use crate::plate_colors::assign_plate_colors;
+use crate::trace::trace_span;
+use std::time::{Duration, Instant};
+
+/// Everything the stages read and write. Starts out empty: no plates, every cell on plate 0,
+/// flat terrain at height 0 and no boundaries, oceanic crust or lakes.
+pub struct GenerationContext<'a> {
+    /// Settings, config and the seeded random number generators of the planet
+    pub generator: &'a PlanetGenerator,
+    pub face_grid_size: usize,
+    pub sea_level: f32,
+    pub plates: Vec<TectonicPlate>,
+    pub plate_map: PlateMap,
+    pub faces: [CubeFace; 6],
+    pub boundary_data: BoundaryData,
+    pub crust_age: CrustAgeMap,
+    pub lakes: LakeMap,
+}
+
+impl<'a> GenerationContext<'a> {
+    pub fn new(generator: &'a PlanetGenerator) -> Self {
+        let n = generator.face_grid_size();
+        let config = generator.config();
+        Self {
+            generator,
+            face_grid_size: n,
+            sea_level: config.continents.continent_threshold,
+            plates: Vec::new(),
+            plate_map: vec![Grid2D::new(n, n, 0); 6],
+            faces: std::array::from_fn(|_| CubeFace {
+                heightmap: Grid2D::new(n, n, 0.0),
+            }),
+            boundary_data: BoundaryData::empty(n, config.boundaries.band_width),
+            crust_age: CrustAgeMap::empty(n),
+            lakes: LakeMap::empty(n),
+        }
+    }
+}
+
+/// One step of planet generation
+pub trait GenerationStage: Send + Sync {
+    /// Unique within a pipeline, used to disable, find and time the stage
+    fn name(&self) -> &'static str;
+    fn run(&self, context: &mut GenerationContext);
+}
+
+/// Stages that make the plate layout, skipped when generating on a given one
+pub(crate) const PLATE_LAYOUT_STAGES: [&str; 7] = [
+    "plates",
+    "assign_plates",
+    "micro_plates",
+    "assign_micro_plates",
+    "merge_plates",
+    "smooth_plate_map",
+    "plate_colors",
+];
+
+/// The stages of a planet generation in the order they run
+pub struct GenerationPipeline {
+    stages: Vec<Box<dyn GenerationStage>>,
+}
+
+impl Default for GenerationPipeline {
+    /// Plates, plate map, terrain, then everything that is derived from the terrain
+    fn default() -> Self {
+        Self {
+            stages: vec![
+                Box::new(Plates),
+                Box::new(AssignPlates("assign_plates")),
+                Box::new(MicroPlates),
+                Box::new(AssignPlates("assign_micro_plates")),
+                Box::new(MergePlates),
+                Box::new(SmoothPlateMap),
+                Box::new(PlateColors),
+                Box::new(Terrain),
+                Box::new(Boundaries),
+                Box::new(CrustAge),
+                Box::new(CrustSubsidence),
+                Box::new(Mountains),
+                Box::new(Craters),
+                Box::new(RaiseBarrenSurface),
+                Box::new(Lakes),
+            ],
+        }
+    }
+}
+
+impl GenerationPipeline {
+    pub fn stage_names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    pub fn push(&mut self, stage: Box<dyn GenerationStage>) {
+        self.stages.push(stage);
+    }
+
+    /// Insert `stage` right before the stage called `name`, false if there is none
+    pub fn insert_before(&mut self, name: &str, stage: Box<dyn GenerationStage>) -> bool {
+        let Some(index) = self.position(name) else {
+            return false;
+        };
+        self.stages.insert(index, stage);
+        true
+    }
+
+    /// Insert `stage` right after the stage called `name`, false if there is none
+    pub fn insert_after(&mut self, name: &str, stage: Box<dyn GenerationStage>) -> bool {
+        let Some(index) = self.position(name) else {
+            return false;
+        };
+        self.stages.insert(index + 1, stage);
+        true
+    }
+
+    /// Take the stage called `name` out, insert it somewhere else to reorder
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn GenerationStage>> {
+        let index = self.position(name)?;
+        Some(self.stages.remove(index))
+    }
+
+    /// Run every stage not in `disabled`, `on_stage` gets the name and run time of each
+    pub fn run(
+        &self,
+        context: &mut GenerationContext,
+        disabled: &[String],
+        mut on_stage: impl FnMut(&'static str, Duration),
+    ) {
+        for stage in &self.stages {
+            if disabled.iter().any(|name| name == stage.name()) {
+                continue;
+            }
+            trace_span!("generation_stage", stage = stage.name());
+            let start = Instant::now();
+            stage.run(context);
+            on_stage(stage.name(), start.elapsed());
+        }
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.stages.iter().position(|stage| stage.name() == name)
+    }
+}
+
+/// Major plate seeds spread over the sphere
+struct Plates;
+
+impl GenerationStage for Plates {
+    fn name(&self) -> &'static str {
+        "plates"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        context.plates = context.generator.generate_plates();
+    }
+}
+
+/// Plate map from the current plates, runs once for the major plates and again with the
+/// micro plates added
+struct AssignPlates(&'static str);
+
+impl GenerationStage for AssignPlates {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        context.plate_map = context.generator.assign_plates(context.face_grid_size, &context.plates);
+    }
+}
+
+/// Micro plates along the boundaries of the major plates
+struct MicroPlates;
+
+impl GenerationStage for MicroPlates {
+    fn name(&self) -> &'static str {
+        "micro_plates"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        let micros = context
+            .generator
+            .generate_microplates(context.face_grid_size, &context.plates, &context.plate_map);
+        context.plates.extend(micros);
+    }
+}
+
+struct MergePlates;
+
+impl GenerationStage for MergePlates {
+    fn name(&self) -> &'static str {
+        "merge_plates"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        context.generator.merge_plates(context.face_grid_size, &mut context.plate_map);
+    }
+}
+
+struct SmoothPlateMap;
+
+impl GenerationStage for SmoothPlateMap {
+    fn name(&self) -> &'static str {
+        "smooth_plate_map"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        majority_smooth(context.face_grid_size, &mut context.plate_map);
+    }
+}
+
+/// Plate view colors, neighbouring plates on the final plate map get hues far apart
+struct PlateColors;
+
+impl GenerationStage for PlateColors {
+    fn name(&self) -> &'static str {
+        "plate_colors"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        assign_plate_colors(&mut context.plates, &context.plate_map);
+    }
+}
+
+/// Heightmaps of the world type, the plate types are decided from them
+struct Terrain;
+
+impl GenerationStage for Terrain {
+    fn name(&self) -> &'static str {
+        "terrain"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        let generator = context.generator;
+        let n = context.face_grid_size;
+        context.faces = match generator.world_type {
+            WorldType::Terran => generator.generate_terran_faces(n, &mut context.plates, &context.plate_map),
+            WorldType::Barren => {
+                let faces = generator.generate_barren_faces(n);
+                classify_plate_types(n, &context.plate_map, &mut context.plates, context.sea_level, |face, x, y| {
+                    faces[face].heightmap[y][x]
+                });
+                faces
+            }
+        };
+    }
+}
+
+/// Plate boundary interactions
+struct Boundaries;
+
+impl GenerationStage for Boundaries {
+    fn name(&self) -> &'static str {
+        "boundaries"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        context.boundary_data = BoundaryData::calculate(
+            context.face_grid_size,
+            &context.plate_map,
+            &context.plates,
+            context.generator.config().boundaries.band_width,
+        );
+    }
+}
+
+struct CrustAge;
+
+impl GenerationStage for CrustAge {
+    fn name(&self) -> &'static str {
+        "crust_age"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        context.crust_age = CrustAgeMap::calculate(
+            context.face_grid_size,
+            &context.plate_map,
+            &context.plates,
+            &context.boundary_data,
+        );
+    }
+}
+
+/// Older ocean floor sits deeper, terran worlds only
+struct CrustSubsidence;
+
+impl GenerationStage for CrustSubsidence {
+    fn name(&self) -> &'static str {
+        "crust_subsidence"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        if context.generator.world_type != WorldType::Terran {
+            return;
+        }
+        let subsidence = context.generator.config().crust_age.subsidence;
+        apply_crust_subsidence(&mut context.faces, &context.crust_age, context.sea_level, subsidence);
+    }
+}
+
+/// Tectonic uplift along convergent boundaries, terran worlds only. Dead worlds have no
+/// active tectonics.
+struct Mountains;
+
+impl GenerationStage for Mountains {
+    fn name(&self) -> &'static str {
+        "mountains"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        if context.generator.world_type != WorldType::Terran {
+            return;
+        }
+        context
+            .generator
+            .apply_convergent_mountains(context.face_grid_size, &context.boundary_data, &mut context.faces);
+    }
+}
+
+/// Impacts, all over barren worlds and on terran ones if the config asks for it
+struct Craters;
+
+impl GenerationStage for Craters {
+    fn name(&self) -> &'static str {
+        "craters"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        let generator = context.generator;
+        if generator.world_type == WorldType::Terran && !generator.config().craters.craters_on_terran {
+            return;
+        }
+        generator.apply_craters(context.face_grid_size, &mut context.faces);
+    }
+}
+
+/// Keeps overlapping craters from digging below sea level on a world without water
+struct RaiseBarrenSurface;
+
+impl GenerationStage for RaiseBarrenSurface {
+    fn name(&self) -> &'static str {
+        "raise_barren_surface"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        if context.generator.world_type == WorldType::Barren {
+            raise_above(&mut context.faces, context.sea_level);
+        }
+    }
+}
+
+/// Fill closed basins on land, terran worlds only
+struct Lakes;
+
+impl GenerationStage for Lakes {
+    fn name(&self) -> &'static str {
+        "lakes"
+    }
+
+    fn run(&self, context: &mut GenerationContext) {
+        if context.generator.world_type != WorldType::Terran {
+            return;
+        }
+        context.lakes = LakeMap::calculate(
+            &context.faces,
+            context.face_grid_size,
+            context.sea_level,
+            context.generator.config().lakes.min_lake_area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planet::PlanetData;
+
+    fn generator(world_type: WorldType) -> PlanetGenerator {
+        let mut generator = PlanetGenerator::new(5.0);
+        generator.seed = 42;
+        generator.world_type = world_type;
+        generator
+    }
+
+    /// The hard-coded sequence `generate` ran before it was split into stages
+    fn fixed_sequence(generator: &PlanetGenerator) -> GenerationContext<'_> {
+        let mut context = GenerationContext::new(generator);
+        let n = context.face_grid_size;
+        let sea_level = context.sea_level;
+        let config = generator.config();
+
+        let mut plates = generator.generate_plates();
+        let plate_map = generator.assign_plates(n, &plates);
+        plates.extend(generator.generate_microplates(n, &plates, &plate_map));
+        let mut plate_map = generator.assign_plates(n, &plates);
+        generator.merge_plates(n, &mut plate_map);
+        majority_smooth(n, &mut plate_map);
+
+        let mut faces = match generator.world_type {
+            WorldType::Terran => generator.generate_terran_faces(n, &mut plates, &plate_map),
+            WorldType::Barren => {
+                let faces = generator.generate_barren_faces(n);
+                classify_plate_types(n, &plate_map, &mut plates, sea_level, |face, x, y| {
+                    faces[face].heightmap[y][x]
+                });
+                faces
+            }
+        };
+        let boundary_data = BoundaryData::calculate(n, &plate_map, &plates, config.boundaries.band_width);
+        let crust_age = CrustAgeMap::calculate(n, &plate_map, &plates, &boundary_data);
+        match generator.world_type {
+            WorldType::Terran => {
+                apply_crust_subsidence(&mut faces, &crust_age, sea_level, config.crust_age.subsidence);
+                generator.apply_convergent_mountains(n, &boundary_data, &mut faces);
+                if config.craters.craters_on_terran {
+                    generator.apply_craters(n, &mut faces);
+                }
+                context.lakes = LakeMap::calculate(&faces, n, sea_level, config.lakes.min_lake_area);
+            }
+            WorldType::Barren => {
+                generator.apply_craters(n, &mut faces);
+                raise_above(&mut faces, sea_level);
+            }
+        }
+
+        context.plates = plates;
+        context.plate_map = plate_map;
+        context.faces = faces;
+        context.boundary_data = boundary_data;
+        context.crust_age = crust_age;
+        context
+    }
+
+    fn assert_same_planet(planet: &PlanetData, expected: &GenerationContext) {
+        assert_eq!(planet.plate_map, expected.plate_map);
+        let plate_types = |plates: &[TectonicPlate]| plates.iter().map(|plate| (plate.id, plate.plate_type)).collect::<Vec<_>>();
+        assert_eq!(plate_types(&planet.plates), plate_types(&expected.plates));
+        for (face, expected_face) in planet.faces.iter().zip(&expected.faces) {
+            assert_eq!(face.heightmap, expected_face.heightmap);
+        }
+        assert_eq!(planet.boundary_data.boundaries, expected.boundary_data.boundaries);
+        assert_eq!(planet.crust_age.age, expected.crust_age.age);
+        assert_eq!(planet.lakes.surface, expected.lakes.surface);
+    }
+
+    #[test]
+    fn test_default_pipeline_matches_the_fixed_sequence() {
+        for world_type in [WorldType::Terran, WorldType::Barren] {
+            let generator = generator(world_type);
+            let planet = generator.generate();
+            assert_same_planet(&planet, &fixed_sequence(&generator));
+        }
+    }
+
+    #[test]
+    fn test_every_stage_runs_once_in_order() {
+        let generator = generator(WorldType::Terran);
+        let pipeline = GenerationPipeline::default();
+        let mut ran = Vec::new();
+
+        generator.generate_with(&pipeline, |name, _| ran.push(name));
+
+        assert_eq!(ran, pipeline.stage_names());
+    }
+
+    #[test]
+    fn test_disabled_stages_are_skipped() {
+        let generator = generator(WorldType::Terran);
+        let pipeline = GenerationPipeline::default();
+        let disabled = ["mountains", "lakes"].map(String::from);
+        let mut context = GenerationContext::new(&generator);
+        let mut ran = Vec::new();
+
+        pipeline.run(&mut context, &disabled, |name, _| ran.push(name));
+
+        assert!(!ran.contains(&"mountains") && !ran.contains(&"lakes"));
+        assert_eq!(ran.len(), pipeline.stage_names().len() - 2);
+        assert_eq!(context.lakes.lake_count, 0);
+        // Mountains only ever raise the terrain
+        let planet = generator.generate();
+        let heights = |faces: &[CubeFace; 6]| faces.iter().flat_map(|face| face.heightmap.iter().copied()).collect::<Vec<_>>();
+        let with_mountains = heights(&planet.faces);
+        let without = heights(&context.faces);
+        assert!(with_mountains.iter().zip(&without).all(|(with, without)| with >= without));
+        assert!(with_mountains.iter().zip(&without).any(|(with, without)| with > without));
+    }
+
+    /// Stage that flattens the terrain, to see where it lands
+    struct Flatten;
+
+    impl GenerationStage for Flatten {
+        fn name(&self) -> &'static str {
+            "flatten"
+        }
+
+        fn run(&self, context: &mut GenerationContext) {
+            for height in context.faces.iter_mut().flat_map(|face| face.heightmap.iter_mut()) {
+                *height = 0.0;
+            }
+        }
+    }
+
+    #[test]
+    fn test_stages_can_be_inserted_and_moved() {
+        let mut pipeline = GenerationPipeline::default();
+        assert!(pipeline.insert_after("terrain", Box::new(Flatten)));
+        assert!(!pipeline.insert_before("erosion", Box::new(Flatten)));
+        let names = pipeline.stage_names();
+        let terrain = names.iter().position(|&name| name == "terrain").unwrap();
+        assert_eq!(names[terrain + 1], "flatten");
+
+        // Moved to the end, nothing is added on top of the flat terrain any more
+        let flatten = pipeline.remove("flatten").unwrap();
+        pipeline.push(flatten);
+        assert_eq!(pipeline.stage_names().last(), Some(&"flatten"));
+        let planet = generator(WorldType::Terran).generate_with(&pipeline, |_, _| {});
+        assert!(planet.faces.iter().all(|face| face.heightmap.iter().all(|&height| height == 0.0)));
+        assert!(pipeline.remove("flatten").is_some());
+        assert!(pipeline.remove("flatten").is_none());
+    }
+}
@@ -1,33 +1,293 @@
 use crate::plate::TectonicPlate;
-use crate::continents::ContinentNoiseConfig;
+use crate::plate_layout::PlateLayout;
 use crate::boundaries::BoundaryData;
+use crate::crust_age::CrustAgeMap;
+use crate::cubemap_utils::{all_cells, nearest_cell};
+use crate::features::connected_regions;
+use crate::generator::cell_direction;
+use crate::grid::Grid2D;
+use crate::lakes::LakeMap;
+use crate::report::GenerationReport;
+use glam::{Quat, Vec3};
 
-/// A single row on a cube face, containing plate IDs for each cell in that row
-pub type FaceRow = Vec<usize>;
-/// A complete grid for one cube face, containing multiple rows
-pub type FaceGrid = Vec<FaceRow>;
+/// Plate IDs of the cells of one cube face
+pub type FaceGrid = Grid2D<usize>;
 /// The complete plate map for all 6 cube faces of the planet
 pub type PlateMap = Vec<FaceGrid>;
 
 
+#[derive(Clone)]
 pub enum PlateSizeClass {
     Regular,
     Micro,
 }
 
+/// Crust a plate is made of, decided by how much of it ends up above sea level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlateType {
+    Continental,
+    #[default]
+    Oceanic,
+}
+
 #[derive(Clone)]
 pub struct CubeFace {
-    pub heightmap: Vec<Vec<f32>>,
+    pub heightmap: Grid2D<f32>,
 }
 
+/// A generated planet. Terrain, plates and lakes are internal to the generator, read them
+/// through the methods below.
+#[derive(Clone)]
 pub struct PlanetData {
-    pub faces: [CubeFace; 6],
+    pub(crate) faces: [CubeFace; 6],
     pub face_grid_size: usize,
+    /// Equatorial radius
     pub radius: f32,
-    pub plate_map: PlateMap,
-    pub plates: Vec<TectonicPlate>,
-    /// Continent noise configuration for generating continents independently of plates
-    pub continent_noise: ContinentNoiseConfig,
+    /// Flattening along Y, 0 is a sphere, see [`oblate_radius`]
+    pub oblateness: f32,
+    /// Height of the ocean surface in heightmap units, the continent threshold the planet was
+    /// generated with
+    pub(crate) sea_level: f32,
+    pub(crate) plate_map: PlateMap,
+    pub(crate) plates: Vec<TectonicPlate>,
     /// Plate boundary interaction classifications (convergent/divergent/transform)
-    pub boundary_data: BoundaryData,
+    pub(crate) boundary_data: BoundaryData,
+    /// Water surface of lakes filling closed basins on land
+    pub(crate) lakes: LakeMap,
+    /// Age of the ocean floor by distance from the mid-ocean ridges
+    pub(crate) crust_age: CrustAgeMap,
+    /// Rotation each plate has drifted since its cells last moved, see `step_tectonics`
+    pub(crate) plate_drift: Vec<Quat>,
+    /// Mesh vertex positions are worked out in f64 and rounded to f32 once, so the vertices of
+    /// very large, finely gridded planets don't snap to the coarse f32 steps of each
+    /// intermediate result. From `GenerationConfig::double_precision_vertices`.
+    pub(crate) double_precision_vertices: bool,
+    /// Warnings about anything degenerate in this planet, for the user
+    pub report: GenerationReport,
+}
+
+/// Distance from the center to the zero-height surface in the direction `dir`, for a planet
+/// flattened along Y: `radius * (1 - oblateness * sin²(latitude))`. Cube maps are still sampled
+/// by direction on the unit sphere, only the positions move.
+pub fn oblate_radius(radius: f32, oblateness: f32, dir: Vec3) -> f32 {
+    let sin_latitude = dir.normalize_or_zero().y;
+    radius * (1.0 - oblateness * sin_latitude * sin_latitude)
+}
+
+impl PlanetData {
+    /// Radius of the zero-height surface in the direction `dir`
+    pub fn surface_radius(&self, dir: Vec3) -> f32 {
+        oblate_radius(self.radius, self.oblateness, dir)
+    }
+
+    /// Height of the ocean surface relative to `radius`, cells below it are under water
+    pub fn sea_level(&self) -> f32 {
+        self.sea_level
+    }
+
+    /// Distance from the center to the ocean surface in the direction `dir`
+    pub fn sea_surface_radius(&self, dir: Vec3) -> f32 {
+        self.surface_radius(dir) + self.sea_level
+    }
+
+    /// Terrain height relative to `radius` in the direction `dir`, bilinearly interpolated
+    pub fn sample_height(&self, dir: Vec3) -> f32 {
+        crate::cubemap_utils::sample_bilinear(self.face_grid_size, dir, |face, x, y| {
+            self.faces[face].heightmap[y][x]
+        })
+    }
+
+    /// Whether the terrain in the direction `dir` lies under the ocean
+    pub fn is_ocean(&self, dir: Vec3) -> bool {
+        self.sample_height(dir) < self.sea_level
+    }
+
+    /// Whether the cell closest to `dir` is covered by a lake
+    pub fn has_lake(&self, dir: Vec3) -> bool {
+        self.lakes.surface_in_direction(dir).is_some()
+    }
+
+    /// Id of the plate of the cell closest to `dir`
+    pub fn plate_id(&self, dir: Vec3) -> usize {
+        let (face, x, y) = nearest_cell(dir, self.face_grid_size);
+        self.plate_map[face][y][x]
+    }
+
+    /// Age of the oceanic crust closest to `dir`, 0 at a ridge up to 1 for the oldest floor.
+    /// None on continental plates.
+    pub fn crust_age(&self, dir: Vec3) -> Option<f32> {
+        self.crust_age.age_in_direction(dir)
+    }
+
+    /// Direction of the highest terrain cell
+    pub fn highest_peak_direction(&self) -> Vec3 {
+        let n = self.face_grid_size;
+        let height = |&(face, x, y): &(usize, usize, usize)| self.faces[face].heightmap[y][x];
+        let (face, x, y) = all_cells(n)
+            .max_by(|a, b| height(a).total_cmp(&height(b)))
+            .unwrap_or((0, 0, 0));
+        cell_direction(n, face, x, y)
+    }
+
+    /// Direction of the center of the largest landmass above sea level, None without land.
+    ///
+    /// Landmasses are cells above sea level connected across face edges, measured by the
+    /// solid angle they cover. The center is their area weighted mean direction, which for a
+    /// landmass wrapped all the way around the planet falls back to its first cell.
+    pub fn largest_continent_direction(&self) -> Option<Vec3> {
+        let n = self.face_grid_size;
+        let is_land = |(face, x, y): (usize, usize, usize)| self.faces[face].heightmap[y][x] > self.sea_level;
+        connected_regions(n, is_land)
+            .into_iter()
+            .reduce(|largest, region| if region.area > largest.area { region } else { largest })
+            .map(|largest| largest.center(n))
+    }
+
+    /// The plates and plate map, to generate different terrain on the same plates
+    pub fn extract_plate_layout(&self) -> PlateLayout {
+        PlateLayout::new(self.face_grid_size, &self.plates, &self.plate_map)
+    }
+
+    /// Change the radius without regenerating. Heightmaps and the plate map stay; with
+    /// `scale_heights` terrain, lake heights and the sea level grow with the radius, otherwise
+    /// they keep their absolute height.
+    pub fn rescale(&mut self, new_radius: f32, scale_heights: bool) {
+        if scale_heights {
+            let scale = new_radius / self.radius;
+            for height in self.faces.iter_mut().flat_map(|face| face.heightmap.iter_mut()) {
+                *height *= scale;
+            }
+            for surface in self.lakes.surface.iter_mut().flatten().flatten().flatten() {
+                *surface *= scale;
+            }
+            self.sea_level *= scale;
+        }
+        self.radius = new_radius;
+    }
+}
+
+#[cfg(test)]
+impl PlanetData {
+    /// Flat planet for tests: land (height 1) on the first 3 faces, ocean (height -1) on the
+    /// rest, plate `face % 2` on every face and no plates, boundaries or lakes
+    pub(crate) fn half_land(face_grid_size: usize, radius: f32) -> Self {
+        let n = face_grid_size;
+        Self {
+            faces: std::array::from_fn(|face| CubeFace {
+                heightmap: Grid2D::new(n, n, if face < 3 { 1.0 } else { -1.0 }),
+            }),
+            face_grid_size: n,
+            radius,
+            oblateness: 0.0,
+            sea_level: 0.0,
+            plate_map: (0..6).map(|face| Grid2D::new(n, n, face % 2)).collect(),
+            plates: Vec::new(),
+            boundary_data: BoundaryData {
+                boundaries: std::array::from_fn(|_| vec![vec![None; n]; n]),
+                boundary_distances: std::array::from_fn(|_| vec![vec![f32::INFINITY; n]; n]),
+                band_width: 0.0,
+            },
+            lakes: LakeMap::empty(n),
+            crust_age: CrustAgeMap::empty(n),
+            plate_drift: Vec::new(),
+            double_precision_vertices: false,
+            report: GenerationReport::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: usize = 17;
+
+    /// A planet under water with `land` raised to the given heights
+    fn ocean_planet(land: &[((usize, usize, usize), f32)]) -> PlanetData {
+        let mut planet = PlanetData::half_land(N, 10.0);
+        for face in &mut planet.faces {
+            face.heightmap = Grid2D::new(N, N, -1.0);
+        }
+        for &((face, x, y), height) in land {
+            planet.faces[face].heightmap[y][x] = height;
+        }
+        planet
+    }
+
+    #[test]
+    fn test_highest_peak_is_the_highest_cell() {
+        let planet = ocean_planet(&[((2, 3, 11), 2.0), ((5, 8, 8), 3.5), ((0, 1, 1), 3.0)]);
+
+        let peak = planet.highest_peak_direction();
+
+        assert!(peak.distance(cell_direction(N, 5, 8, 8)) < 1e-6);
+    }
+
+    #[test]
+    fn test_largest_continent_wins_over_a_higher_island() {
+        // A tall island on +Y and a low 5x5 continent in the middle of +Z
+        let mut land = vec![((2, 8, 8), 5.0)];
+        for y in 6..=10 {
+            for x in 6..=10 {
+                land.push(((4, x, y), 0.5));
+            }
+        }
+        let planet = ocean_planet(&land);
+
+        let center = planet.largest_continent_direction().unwrap();
+
+        assert!(center.distance(Vec3::Z) < 1e-4, "{center}");
+    }
+
+    #[test]
+    fn test_continent_across_a_face_edge_counts_as_one() {
+        // Two strips on either side of the +X / +Z edge, each smaller than the island. Cells
+        // shrink towards the face edges and solid_angle_of_cell counts a cell on the edge as the
+        // half inside its face, the other half is the same cell of the next face. A strip one
+        // column wide along the edge would weigh too little, so both strips are two columns.
+        let mut land = vec![((2, 8, 8), 1.0), ((2, 9, 8), 1.0)];
+        for y in 7..=9 {
+            land.push(((4, N - 2, y), 1.0));
+            land.push(((4, N - 1, y), 1.0));
+            land.push(((0, 0, y), 1.0));
+            land.push(((0, 1, y), 1.0));
+        }
+        let planet = ocean_planet(&land);
+
+        let center = planet.largest_continent_direction().unwrap();
+
+        // On the edge between the faces, not on either face alone or the island
+        let edge = Vec3::new(1.0, 0.0, 1.0).normalize();
+        assert!(center.angle_between(edge) < 0.1, "{center}");
+    }
+
+    #[test]
+    fn test_no_land_has_no_continent() {
+        assert_eq!(ocean_planet(&[]).largest_continent_direction(), None);
+        assert!(PlanetData::half_land(N, 10.0).largest_continent_direction().is_some());
+    }
+
+    #[test]
+    fn test_plate_id_is_the_one_of_the_nearest_cell() {
+        let planet = PlanetData::half_land(N, 10.0);
+
+        for face in 0..6 {
+            assert_eq!(planet.plate_id(cell_direction(N, face, 3, 12)), face % 2);
+        }
+    }
+
+    #[test]
+    fn test_sea_level_follows_the_heights_on_rescale() {
+        let mut planet = PlanetData::half_land(N, 10.0);
+        planet.sea_level = 0.5;
+
+        planet.rescale(20.0, false);
+        assert_eq!(planet.sea_level(), 0.5);
+
+        planet.rescale(40.0, true);
+        assert_eq!(planet.sea_level(), 1.0);
+        assert_eq!(planet.sample_height(Vec3::X), 2.0);
+        assert!(!planet.is_ocean(Vec3::X));
+        assert!(planet.is_ocean(Vec3::NEG_Y));
+    }
 }
@@ -1,11 +1,13 @@
-use crate::planet::PlateSizeClass;
+use crate::planet::{PlateSizeClass, PlateType};
 use glam::Vec3;
 
+#[derive(Clone)]
 pub struct TectonicPlate {
     pub id: usize,
     pub direction: Vec3,
     pub angular_velocity: Vec3,
     pub center: Vec3,
     pub size_class: PlateSizeClass,
+    pub plate_type: PlateType,
     pub debug_color: [f32; 4],
 }
@@ -0,0 +1,183 @@
+//! Smooth blending of per-plate values across plate boundaries
+//!
+//! Anything a plate contributes to the terrain on its own (base elevation, noise, ...) would
+//! otherwise change abruptly from one cell to the next at a plate edge. This module measures,
+//! for every cell near a boundary, how far away the nearest foreign plate is and how much of
+//! that plate's value should be mixed in.
+
+use crate::cubemap_utils::{all_cells, neighbors};
+use crate::grid::Grid2D;
+use crate::planet::PlateMap;
+use std::collections::VecDeque;
+
+/// Blend weights around plate boundaries for the entire planet
+pub struct PlateBlend {
+    /// Nearest foreign plate within the blend band (None = plate interior)
+    pub neighbor_plate: [Grid2D<Option<usize>>; 6],
+    /// Weight of `neighbor_plate` in [0, 0.5]: 0.5 right at the boundary, 0.0 at the band edge
+    pub neighbor_weight: [Grid2D<f32>; 6],
+}
+
+impl PlateBlend {
+    /// Calculate blend weights for a blend band of `blend_width` on each side of every boundary
+    ///
+    /// `blend_width` is a fraction of the planet radius, so the band covers the same part of the
+    /// surface no matter how many cells the grid has. The search runs over the whole cube, so
+    /// boundaries on or across a face edge are blended like any other.
+    ///
+    /// # Algorithm
+    /// 1. Seed every cell adjacent to a different plate, half a cell away from the boundary
+    /// 2. Breadth-first search inwards, carrying the foreign plate id along
+    /// 3. Convert the distance to a weight with a smoothstep falloff
+    pub fn calculate(face_grid_size: usize, plate_map: &PlateMap, blend_width: f32) -> Self {
+        let n = face_grid_size;
+        let mut neighbor_plate = std::array::from_fn(|_| Grid2D::new(n, n, None));
+        let mut neighbor_weight = std::array::from_fn(|_| Grid2D::new(n, n, 0.0));
+
+        // A cube face spans 2 units of a unit-radius cube, so one radius is ~half a face
+        let width_cells = blend_width * (n - 1) as f32 * 0.5;
+        if width_cells <= 0.5 {
+            return Self { neighbor_plate, neighbor_weight };
+        }
+
+        let plate = |(face, x, y): (usize, usize, usize)| plate_map[face][(x, y)];
+        let mut distances: [Grid2D<f32>; 6] = std::array::from_fn(|_| Grid2D::new(n, n, f32::INFINITY));
+        let mut queue = VecDeque::new();
+
+        // Step 1: cells touching another plate, on their own face or the next one
+        for cell in all_cells(n) {
+            let own = plate(cell);
+            if let Some(foreign) = neighbors(cell, n).map(plate).find(|&other| other != own) {
+                let (face, x, y) = cell;
+                distances[face][(x, y)] = 0.5;
+                neighbor_plate[face][(x, y)] = Some(foreign);
+                queue.push_back(cell);
+            }
+        }
+
+        // Step 2: spread into each plate, staying on the same plate as the seed cell
+        while let Some(cell) = queue.pop_front() {
+            let (face, x, y) = cell;
+            let dist = distances[face][(x, y)] + 1.0;
+            if dist >= width_cells {
+                continue;
+            }
+            for next in neighbors(cell, n) {
+                let (nf, nx, ny) = next;
+                if plate(next) == plate(cell) && distances[nf][(nx, ny)] == f32::INFINITY {
+                    distances[nf][(nx, ny)] = dist;
+                    neighbor_plate[nf][(nx, ny)] = neighbor_plate[face][(x, y)];
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        // Step 3: distance to weight
+        for (face, x, y) in all_cells(n) {
+            if neighbor_plate[face][(x, y)].is_some() {
+                neighbor_weight[face][(x, y)] = blend_weight(distances[face][(x, y)], width_cells);
+            }
+        }
+
+        Self { neighbor_plate, neighbor_weight }
+    }
+
+    /// Mix a per-plate value at a cell with the value of the nearest foreign plate
+    pub fn blend(&self, face: usize, x: usize, y: usize, own: f32, plate_value: impl Fn(usize) -> f32) -> f32 {
+        match self.neighbor_plate[face][(x, y)] {
+            Some(other) => {
+                let w = self.neighbor_weight[face][(x, y)];
+                own * (1.0 - w) + plate_value(other) * w
+            }
+            None => own,
+        }
+    }
+}
+
+/// Both sides of a boundary get 0.5 at the boundary itself, so the blended value is continuous
+fn blend_weight(distance: f32, width: f32) -> f32 {
+    let t = (1.0 - distance / width).clamp(0.0, 1.0);
+    0.5 * t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubemap_utils::cross_face_texel;
+
+    /// Face 0 split in two halves, plate 0 left and plate 1 right; other faces are plate 0
+    fn split_map(face_grid_size: usize) -> PlateMap {
+        let mut plate_map = vec![Grid2D::new(face_grid_size, face_grid_size, 0); 6];
+        for row in plate_map[0].rows_mut() {
+            for cell in row.iter_mut().skip(face_grid_size / 2) {
+                *cell = 1;
+            }
+        }
+        plate_map
+    }
+
+    #[test]
+    fn test_values_meet_halfway_at_the_boundary() {
+        let n = 41;
+        let blend = PlateBlend::calculate(n, &split_map(n), 0.2);
+        let values = [0.0, 1.0];
+
+        let left = blend.blend(0, n / 2 - 1, n / 2, values[0], |p| values[p]);
+        let right = blend.blend(0, n / 2, n / 2, values[1], |p| values[p]);
+
+        assert!((left - right).abs() < 0.2, "jump across boundary: {left} vs {right}");
+        assert!(left > 0.0 && right < 1.0);
+    }
+
+    #[test]
+    fn test_interior_is_unchanged() {
+        let n = 41;
+        let blend = PlateBlend::calculate(n, &split_map(n), 0.2);
+
+        assert_eq!(blend.neighbor_plate[0][(0, n / 2)], None);
+        assert_eq!(blend.blend(0, 0, n / 2, 0.3, |_| 100.0), 0.3);
+        // The face opposite the split is too far from any boundary
+        assert!(blend.neighbor_plate[1].iter().all(|p| p.is_none()));
+    }
+
+    #[test]
+    fn test_band_width_does_not_depend_on_resolution() {
+        let band_fraction = |n: usize| {
+            let blend = PlateBlend::calculate(n, &split_map(n), 0.2);
+            let row = &blend.neighbor_plate[0][n / 2];
+            row.iter().filter(|p| p.is_some()).count() as f32 / n as f32
+        };
+
+        let coarse = band_fraction(41);
+        let fine = band_fraction(161);
+        assert!((coarse - fine).abs() < 0.05, "coarse {coarse}, fine {fine}");
+    }
+
+    #[test]
+    fn test_zero_width_disables_blending() {
+        let n = 41;
+        let blend = PlateBlend::calculate(n, &split_map(n), 0.0);
+
+        assert!(blend.neighbor_plate.iter().flat_map(Grid2D::iter).all(|p| p.is_none()));
+    }
+
+    #[test]
+    fn test_boundary_on_a_face_edge_is_blended_on_both_faces() {
+        // Face 0 is plate 1, everything around it plate 0, so the boundary runs along its edges
+        let n = 41;
+        let mut plate_map = vec![Grid2D::new(n, n, 0); 6];
+        plate_map[0] = Grid2D::new(n, n, 1);
+        let blend = PlateBlend::calculate(n, &plate_map, 0.2);
+        let values = [0.0, 1.0];
+
+        // The edge cell of face 0 and the next cell across the edge
+        let inside = blend.blend(0, n - 1, n / 2, values[1], |p| values[p]);
+        let (face, x, y) = cross_face_texel(0, n as i32, (n / 2) as i32, n);
+        let outside = blend.blend(face, x, y, values[0], |p| values[p]);
+
+        assert_ne!(face, 0);
+        assert_eq!(blend.neighbor_plate[face][(x, y)], Some(1));
+        assert!(inside < 1.0 && outside > 0.0, "not blended: {inside} and {outside}");
+        assert!((inside - outside).abs() < 0.2, "jump across the face edge: {inside} vs {outside}");
+    }
+}
@@ -0,0 +1,212 @@
+//! Random plate colors of the plate view
+//!
+//! Hues step around the OKLCH color wheel by the golden angle, so however many plates there are
+//! their colors stay spread out. Plates sharing a boundary then trade colors until no two of
+//! them are close in hue.
+
+use crate::planet::PlateMap;
+use crate::plate::TectonicPlate;
+use crate::plate_stats::plate_neighbors;
+use std::collections::HashMap;
+
+/// Share of a turn between consecutive hues, one over the golden ratio
+const GOLDEN_TURN: f64 = 0.618_033_988_749_895;
+/// Neighbouring plates closer in hue than this share of a turn trade colors
+const MIN_NEIGHBOR_HUE_DISTANCE: f32 = 1.0 / 12.0;
+/// OKLCH lightness the colors cycle through, so colors close in hue still differ in brightness
+const LIGHTNESS: [f32; 3] = [0.80, 0.64, 0.72];
+const CHROMA: f32 = 0.13;
+/// Rounds over every plate looking for trades, the last conflicts stay if none are left
+const MAX_TRADE_ROUNDS: usize = 8;
+
+/// Color of the `index`th plate color, linear RGB like the other vertex colors
+pub(crate) fn plate_color(index: usize) -> [f32; 4] {
+    let lightness = LIGHTNESS[index % LIGHTNESS.len()];
+    let [r, g, b] = oklch_to_linear_srgb(lightness, CHROMA, hue(index) * std::f32::consts::TAU);
+    [r, g, b, 1.0]
+}
+
+/// Give the plates colors of the golden angle palette with no two neighbours close in hue,
+/// where possible. The same plates on the same plate map always get the same colors.
+pub fn assign_plate_colors(plates: &mut [TectonicPlate], plate_map: &PlateMap) {
+    let position: HashMap<usize, usize> = plates.iter().enumerate().map(|(i, plate)| (plate.id, i)).collect();
+    let adjacency = plate_neighbors(plate_map);
+    let neighbors: Vec<Vec<usize>> = plates
+        .iter()
+        .map(|plate| {
+            adjacency
+                .get(&plate.id)
+                .into_iter()
+                .flatten()
+                .filter_map(|id| position.get(id).copied())
+                .collect()
+        })
+        .collect();
+
+    for (plate, slot) in plates.iter_mut().zip(color_slots(&neighbors)) {
+        plate.debug_color = plate_color(slot);
+    }
+}
+
+/// Palette index of each plate, `neighbors[i]` lists the plates next to plate `i` in
+/// ascending order. Starts with plate `i` on color `i` and trades colors between two plates
+/// whenever that leaves fewer neighbours too close in hue.
+fn color_slots(neighbors: &[Vec<usize>]) -> Vec<usize> {
+    let mut slots: Vec<usize> = (0..neighbors.len()).collect();
+    let conflicts = |slots: &[usize], plate: usize| {
+        neighbors[plate]
+            .iter()
+            .filter(|&&other| hue_distance(hue(slots[plate]), hue(slots[other])) < MIN_NEIGHBOR_HUE_DISTANCE)
+            .count()
+    };
+
+    for _ in 0..MAX_TRADE_ROUNDS {
+        let mut traded = false;
+        for plate in 0..slots.len() {
+            if conflicts(&slots, plate) == 0 {
+                continue;
+            }
+            for other in (0..slots.len()).filter(|&other| other != plate) {
+                let before = conflicts(&slots, plate) + conflicts(&slots, other);
+                slots.swap(plate, other);
+                if conflicts(&slots, plate) + conflicts(&slots, other) < before {
+                    traded = true;
+                    break;
+                }
+                slots.swap(plate, other);
+            }
+        }
+        if !traded {
+            break;
+        }
+    }
+    slots
+}
+
+/// Hue of the `index`th plate color, as a share of a turn
+fn hue(index: usize) -> f32 {
+    (index as f64 * GOLDEN_TURN).fract() as f32
+}
+
+/// Distance between two hues around the color wheel, 0 to 0.5 turns
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let distance = (a - b).abs();
+    distance.min(1.0 - distance)
+}
+
+/// OKLCH with the hue in radians to linear sRGB, clamped into the gamut
+fn oklch_to_linear_srgb(lightness: f32, chroma: f32, hue: f32) -> [f32; 3] {
+    let (a, b) = (chroma * hue.cos(), chroma * hue.sin());
+    let l = (lightness + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m = (lightness - 0.105_561_35 * a - 0.063_854_17 * b).powi(3);
+    let s = (lightness - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+    [
+        4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+        -1.268_438 * l + 2.609_757_4 * m - 0.341_319_4 * s,
+        -0.004_196_086 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+    ]
+    .map(|channel| channel.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid2D;
+    use crate::planet::{PlateSizeClass, PlateType};
+    use glam::Vec3;
+
+    fn plate(id: usize) -> TectonicPlate {
+        TectonicPlate {
+            id,
+            direction: Vec3::Z,
+            angular_velocity: Vec3::ZERO,
+            center: Vec3::Z,
+            size_class: PlateSizeClass::Regular,
+            plate_type: PlateType::default(),
+            debug_color: [0.0; 4],
+        }
+    }
+
+    /// Conflicting neighbour pairs in `slots`
+    fn close_neighbors(neighbors: &[Vec<usize>], slots: &[usize]) -> Vec<(usize, usize)> {
+        (0..neighbors.len())
+            .flat_map(|a| neighbors[a].iter().map(move |&b| (a, b)))
+            .filter(|&(a, b)| a < b && hue_distance(hue(slots[a]), hue(slots[b])) < MIN_NEIGHBOR_HUE_DISTANCE)
+            .collect()
+    }
+
+    /// Plates 0 and 8 share a color when picking from an 8 color list by id, plates 0 and 8 and
+    /// plates 5 and 13 are close in hue on the golden angle wheel. Here they are neighbours.
+    fn colliding_chain() -> Vec<Vec<usize>> {
+        let order = [0, 8, 5, 13, 2, 10, 7, 15, 4, 12, 1, 9, 6, 14, 3, 11];
+        let mut neighbors = vec![Vec::new(); order.len()];
+        for pair in order.windows(2) {
+            neighbors[pair[0]].push(pair[1]);
+            neighbors[pair[1]].push(pair[0]);
+        }
+        neighbors.iter_mut().for_each(|list| list.sort_unstable());
+        neighbors
+    }
+
+    #[test]
+    fn test_golden_angle_hues_spread_evenly() {
+        let colors: Vec<_> = (0..50).map(plate_color).collect();
+        for (i, a) in colors.iter().enumerate() {
+            assert!(a.iter().all(|channel| (0.0..=1.0).contains(channel)));
+            assert!(colors[i + 1..].iter().all(|b| a != b), "color {i} repeats");
+        }
+        // Any 12 consecutive hues leave no gap wider than a quarter turn
+        let mut hues: Vec<f32> = (7..19).map(hue).collect();
+        hues.sort_by(f32::total_cmp);
+        let wrap_gap = 1.0 - hues[hues.len() - 1] + hues[0];
+        assert!(hues.windows(2).map(|pair| pair[1] - pair[0]).all(|gap| gap < 0.25) && wrap_gap < 0.25);
+    }
+
+    #[test]
+    fn test_neighbours_close_in_hue_trade_colors() {
+        let neighbors = colliding_chain();
+        let naive: Vec<usize> = (0..neighbors.len()).collect();
+        assert!(!close_neighbors(&neighbors, &naive).is_empty());
+
+        let slots = color_slots(&neighbors);
+
+        assert_eq!(close_neighbors(&neighbors, &slots), vec![]);
+        // Still one color per plate
+        let mut used = slots.clone();
+        used.sort_unstable();
+        assert_eq!(used, naive);
+    }
+
+    #[test]
+    fn test_neighbouring_plates_on_the_map_get_distant_hues_deterministically() {
+        // Plate 8 is an island in plate 0, plates 1 to 7 were merged away
+        let n = 8;
+        let mut plate_map: PlateMap = vec![Grid2D::new(n, n, 0); 6];
+        for row in plate_map[0].rows_mut().skip(3).take(2) {
+            row[3..5].fill(8);
+        }
+        assert!(hue_distance(hue(0), hue(8)) < MIN_NEIGHBOR_HUE_DISTANCE);
+        let assign = || {
+            let mut plates: Vec<_> = (0..=8).map(plate).collect();
+            assign_plate_colors(&mut plates, &plate_map);
+            plates.iter().map(|plate| plate.debug_color).collect::<Vec<_>>()
+        };
+
+        let colors = assign();
+
+        assert_eq!(colors, assign());
+        let distance = hue_distance(oklab_hue(colors[0]), oklab_hue(colors[8]));
+        // A little slack for the channels clamped into the gamut
+        assert!(distance > MIN_NEIGHBOR_HUE_DISTANCE - 0.01, "{distance}");
+    }
+
+    /// Hue of a linear sRGB color in OKLab, as a share of a turn
+    fn oklab_hue([r, g, b, _]: [f32; 4]) -> f32 {
+        let l = (0.412_221_47 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        let a = 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s;
+        let b = 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s;
+        (b.atan2(a) / std::f32::consts::TAU).rem_euclid(1.0)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::grid::Grid2D;
+use crate::planet::{PlateMap, PlateSizeClass, PlateType};
+use crate::plate::TectonicPlate;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Plates and plate map of a planet without its terrain, see
+/// [`PlanetData::extract_plate_layout`](crate::planet::PlanetData::extract_plate_layout).
+/// New terrain is generated on it with
+/// [`PlanetGenerator::generate_with_plate_layout`](crate::generator::PlanetGenerator::generate_with_plate_layout).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlateLayout {
+    face_grid_size: usize,
+    plates: Vec<LayoutPlate>,
+    /// Plate id of every cell, face after face, row after row
+    plate_map: Vec<usize>,
+}
+
+/// What a plate is before there is terrain, its type is decided by the terrain on it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LayoutPlate {
+    id: usize,
+    direction: [f32; 3],
+    angular_velocity: [f32; 3],
+    center: [f32; 3],
+    micro: bool,
+    debug_color: [f32; 4],
+}
+
+impl PlateLayout {
+    pub(crate) fn new(face_grid_size: usize, plates: &[TectonicPlate], plate_map: &PlateMap) -> Self {
+        Self {
+            face_grid_size,
+            plates: plates
+                .iter()
+                .map(|plate| LayoutPlate {
+                    id: plate.id,
+                    direction: plate.direction.to_array(),
+                    angular_velocity: plate.angular_velocity.to_array(),
+                    center: plate.center.to_array(),
+                    micro: matches!(plate.size_class, PlateSizeClass::Micro),
+                    debug_color: plate.debug_color,
+                })
+                .collect(),
+            plate_map: plate_map.iter().flat_map(|face| face.iter().copied()).collect(),
+        }
+    }
+
+    /// Vertices along one face edge of the plate map
+    pub fn face_grid_size(&self) -> usize {
+        self.face_grid_size
+    }
+
+    pub fn plate_count(&self) -> usize {
+        self.plates.len()
+    }
+
+    pub(crate) fn plates(&self) -> Vec<TectonicPlate> {
+        self.plates
+            .iter()
+            .map(|plate| TectonicPlate {
+                id: plate.id,
+                direction: Vec3::from(plate.direction),
+                angular_velocity: Vec3::from(plate.angular_velocity),
+                center: Vec3::from(plate.center),
+                size_class: if plate.micro { PlateSizeClass::Micro } else { PlateSizeClass::Regular },
+                plate_type: PlateType::default(),
+                debug_color: plate.debug_color,
+            })
+            .collect()
+    }
+
+    /// The plate map on a `face_grid_size` grid, every cell takes the plate of the nearest cell
+    /// of the layout. Faces line up cell for cell, so the plates stay where they were.
+    pub(crate) fn plate_map(&self, face_grid_size: usize) -> PlateMap {
+        let n = self.face_grid_size;
+        let nearest = |i: usize| {
+            if face_grid_size < 2 {
+                0
+            } else {
+                (i as f32 * (n - 1) as f32 / (face_grid_size - 1) as f32).round() as usize
+            }
+        };
+        (0..6)
+            .map(|face| {
+                Grid2D::from_fn(face_grid_size, face_grid_size, |x, y| {
+                    self.plate_map[(face * n + nearest(y)) * n + nearest(x)]
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::PlanetGenerator;
+
+    fn layout() -> PlateLayout {
+        let mut generator = PlanetGenerator::new(5.0);
+        generator.seed = 42;
+        generator.generate().extract_plate_layout()
+    }
+
+    #[test]
+    fn test_layout_survives_a_round_trip_through_toml() {
+        let layout = layout();
+
+        let text = toml::to_string(&layout).unwrap();
+        assert_eq!(toml::from_str::<PlateLayout>(&text).unwrap(), layout);
+    }
+
+    #[test]
+    fn test_same_grid_keeps_every_cell() {
+        let layout = layout();
+        let n = layout.face_grid_size();
+
+        let map = layout.plate_map(n);
+        assert_eq!(PlateLayout::new(n, &layout.plates(), &map), layout);
+    }
+
+    #[test]
+    fn test_coarser_grid_keeps_the_corners() {
+        let layout = layout();
+        let n = layout.face_grid_size();
+
+        let coarse = layout.plate_map(n / 2);
+        for (face, grid) in coarse.iter().enumerate() {
+            let fine = &layout.plate_map(n)[face];
+            let last = n / 2 - 1;
+            assert_eq!(grid[0][0], fine[0][0]);
+            assert_eq!(grid[last][last], fine[n - 1][n - 1]);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Numbers about each plate of a generated planet, for the plates panel
+
+use crate::cubemap_utils::{all_cells, nearest_cell, neighbors};
+use crate::generator::cell_direction;
+use crate::planet::{PlanetData, PlateMap, PlateSizeClass, PlateType};
+use crate::tools::sphere::solid_angle_of_cell;
+use glam::Vec3;
+use std::collections::{BTreeSet, HashMap};
+
+/// One row of the plates panel
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlateStats {
+    pub id: usize,
+    /// The plate's color in the plate view with random colors
+    pub color: [f32; 4],
+    pub plate_type: PlateType,
+    /// Seeded as a microplate next to a boundary
+    pub micro: bool,
+    /// Grid cells, points on face edges count once for each face
+    pub cells: usize,
+    /// Share of the planet surface, 0 to 1
+    pub area_fraction: f32,
+    /// Mean terrain height of its cells, relative to the radius like the heightmap
+    pub mean_elevation: f32,
+    /// Length of the angular velocity, radians per unit of plate time
+    pub speed: f32,
+    /// Area weighted mean direction of its cells, the plate seed's direction if that's degenerate
+    pub center: Vec3,
+    /// Plates sharing a boundary with this one, ascending
+    pub neighbors: Vec<usize>,
+}
+
+/// Plates next to each plate, across face edges too. Plates without a neighbour (a single plate
+/// covering the planet) have no entry.
+pub(crate) fn plate_neighbors(plate_map: &PlateMap) -> HashMap<usize, BTreeSet<usize>> {
+    let n = plate_map[0].height();
+    let mut adjacency: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+    for cell in all_cells(n) {
+        let (face, x, y) = cell;
+        let plate = plate_map[face][y][x];
+        for (nf, nx, ny) in neighbors(cell, n) {
+            let other = plate_map[nf][ny][nx];
+            if other != plate {
+                adjacency.entry(plate).or_default().insert(other);
+            }
+        }
+    }
+    adjacency
+}
+
+impl PlanetData {
+    /// Size, type, speed and neighbours of every plate, in plate order
+    pub fn plate_stats(&self) -> Vec<PlateStats> {
+        let n = self.face_grid_size;
+        let adjacency = plate_neighbors(&self.plate_map);
+
+        // cells, solid angle, height times solid angle, direction times solid angle
+        let mut sums: HashMap<usize, (usize, f32, f32, Vec3)> = HashMap::new();
+        for (face, x, y) in all_cells(n) {
+            let area = solid_angle_of_cell(face, x, y, n);
+            let sum = sums.entry(self.plate_map[face][y][x]).or_default();
+            sum.0 += 1;
+            sum.1 += area;
+            sum.2 += self.faces[face].heightmap[y][x] * area;
+            sum.3 += cell_direction(n, face, x, y) * area;
+        }
+
+        let sphere = 4.0 * std::f32::consts::PI;
+        self.plates
+            .iter()
+            .map(|plate| {
+                let (cells, area, height, direction) =
+                    sums.get(&plate.id).copied().unwrap_or_default();
+                PlateStats {
+                    id: plate.id,
+                    color: plate.debug_color,
+                    plate_type: plate.plate_type,
+                    micro: matches!(plate.size_class, PlateSizeClass::Micro),
+                    cells,
+                    area_fraction: area / sphere,
+                    mean_elevation: if area > 0.0 { height / area } else { 0.0 },
+                    speed: plate.angular_velocity.length(),
+                    center: direction.try_normalize().unwrap_or(plate.center),
+                    neighbors: adjacency
+                        .get(&plate.id)
+                        .map(|neighbors| neighbors.iter().copied().collect())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+
+    /// Plate of the cell closest to `dir`
+    pub fn plate_at(&self, dir: Vec3) -> usize {
+        let (face, x, y) = nearest_cell(dir, self.face_grid_size);
+        self.plate_map[face][y][x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plate::TectonicPlate;
+
+    const N: usize = 9;
+
+    fn plate(id: usize, size_class: PlateSizeClass) -> TectonicPlate {
+        TectonicPlate {
+            id,
+            direction: Vec3::X,
+            angular_velocity: Vec3::new(0.0, 0.3, 0.4),
+            center: Vec3::X,
+            size_class,
+            plate_type: PlateType::default(),
+            debug_color: [id as f32, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Plate 0 on the +Y face (2) at height 1, plate 1 on the -Y face (3) at height -1 and
+    /// plate 2 around the middle at height 0. The caps include their face edges.
+    fn banded_planet() -> PlanetData {
+        let mut planet = PlanetData::half_land(N, 1.0);
+        for (face, x, y) in all_cells(N) {
+            let dir = cell_direction(N, face, x, y);
+            let side = dir.x.abs().max(dir.z.abs()) - 1e-6;
+            let (plate, height) = if dir.y >= side {
+                (0, 1.0)
+            } else if -dir.y >= side {
+                (1, -1.0)
+            } else {
+                (2, 0.0)
+            };
+            planet.plate_map[face][y][x] = plate;
+            planet.faces[face].heightmap[y][x] = height;
+        }
+        planet.plates = vec![
+            plate(0, PlateSizeClass::Regular),
+            plate(1, PlateSizeClass::Micro),
+            plate(2, PlateSizeClass::Regular),
+        ];
+        planet
+    }
+
+    #[test]
+    fn test_neighbors_meet_across_face_edges() {
+        let planet = banded_planet();
+
+        let adjacency = plate_neighbors(&planet.plate_map);
+
+        // The polar caps only touch the band, each on face edges
+        assert_eq!(adjacency[&0], BTreeSet::from([2]));
+        assert_eq!(adjacency[&1], BTreeSet::from([2]));
+        assert_eq!(adjacency[&2], BTreeSet::from([0, 1]));
+    }
+
+    #[test]
+    fn test_stats_cover_the_planet() {
+        let planet = banded_planet();
+
+        let stats = planet.plate_stats();
+
+        assert_eq!(
+            stats.iter().map(|plate| plate.id).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        let total: f32 = stats.iter().map(|plate| plate.area_fraction).sum();
+        assert!((total - 1.0).abs() < 1e-4, "areas add up to {total}");
+        // A cap is its face plus the half cells along the edges of the four faces next to it
+        assert!((stats[0].area_fraction - stats[1].area_fraction).abs() < 1e-5);
+        assert!(stats[0].area_fraction > 1.0 / 6.0 && stats[0].area_fraction < 0.25);
+        assert!(stats[0].center.distance(Vec3::Y) < 1e-4);
+        assert!(stats[1].center.distance(Vec3::NEG_Y) < 1e-4);
+        assert!(stats[1].micro && !stats[0].micro);
+        assert!((stats[0].speed - 0.5).abs() < 1e-6);
+        assert_eq!(stats[2].neighbors, [0, 1]);
+        assert!((stats[0].mean_elevation - 1.0).abs() < 1e-6);
+        assert!((stats[1].mean_elevation + 1.0).abs() < 1e-6);
+        assert_eq!(stats[2].mean_elevation, 0.0);
+    }
+
+    #[test]
+    fn test_plate_at_reads_the_nearest_cell() {
+        let planet = banded_planet();
+
+        assert_eq!(planet.plate_at(Vec3::Y), 0);
+        assert_eq!(planet.plate_at(Vec3::new(0.2, -1.0, 0.1)), 1);
+        assert_eq!(planet.plate_at(Vec3::X), 2);
+    }
+}
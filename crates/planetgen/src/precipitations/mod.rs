@@ -4,20 +4,62 @@
 // Uses the VerticalAirCubeMap to determine precipitation probability.
 // Negative values (rising air / convergence) lead to higher precipitation.
 // Positive values (sinking air / divergence) lead to lower precipitation.
-
+//
+// The map values are relative, 1.0 at the wettest point. `PrecipitationCalibration` turns them
+// into annual amounts in mm.
+
+use crate::config::PrecipitationConfig;
+use crate::cubemap_utils::all_cells;
+use crate::generator::cell_direction;
+use crate::grid::Grid2D;
+use crate::palette::Palette;
 use crate::planet::PlanetData;
 use crate::temperature::TemperatureCubeMap;
+use crate::tools::sphere::solid_angle_of_cell;
+use crate::trace::trace_span;
 use crate::wind::VerticalAirCubeMap;
-use glam::Vec3;
+use std::borrow::Cow;
+use glam::{Vec2, Vec3};
+use std::f32::consts::FRAC_PI_2;
+
+/// Buckets of `land_precipitation_histogram`
+pub const HISTOGRAM_BUCKETS: usize = 10;
+/// Annual precipitation each bucket of `land_precipitation_histogram` spans, mm. With 10 of
+/// them the first holds the deserts below 250 mm and the last everything from 2250 mm up.
+pub const HISTOGRAM_BUCKET_MM: f32 = 250.0;
+
+/// Curve from the 0–1 map values to annual precipitation, `max_annual_mm × value^gamma`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrecipitationCalibration {
+    /// Annual precipitation of the wettest point, mm
+    pub max_annual_mm: f32,
+    pub gamma: f32,
+}
+
+impl PrecipitationCalibration {
+    pub fn from_config(config: &PrecipitationConfig) -> Self {
+        Self {
+            max_annual_mm: config.max_annual_mm,
+            gamma: config.mm_gamma,
+        }
+    }
 
-/// Number of blur passes to create smooth precipitation zones
-const BLUR_PASSES: usize = 5;
+    /// Annual precipitation in mm of the map value `value`
+    pub fn to_mm(&self, value: f32) -> f32 {
+        self.max_annual_mm * value.clamp(0.0, 1.0).powf(self.gamma)
+    }
+
+    /// Map value with `mm` of annual precipitation, for thresholds given in mm
+    pub fn from_mm(&self, mm: f32) -> f32 {
+        (mm / self.max_annual_mm).clamp(0.0, 1.0).powf(1.0 / self.gamma)
+    }
+}
 
 /// A single cube face storing precipitation probability values
 #[derive(Clone)]
 pub struct PrecipitationCubeFace {
     /// Grid of precipitation probability values [y][x], range [0.0, 1.0]
-    pub values: Vec<Vec<f32>>,
+    pub values: Grid2D<f32>,
 }
 
 /// Pre-computed precipitation probability cube map for the entire planet.
@@ -26,6 +68,8 @@ pub struct PrecipitationCubeFace {
 pub struct PrecipitationCubeMap {
     pub faces: [PrecipitationCubeFace; 6],
     pub resolution: usize,
+    /// Amounts of the map values, from the config the map was built with
+    pub calibration: PrecipitationCalibration,
 }
 
 impl PrecipitationCubeMap {
@@ -37,6 +81,8 @@ impl PrecipitationCubeMap {
     /// - Temperature controls moisture capacity (warm = high, cold = low)
     /// - Water availability: oceans evaporate more, land evaporates less
     ///   - Evaporation also scales with temperature (warm ocean = high evaporation)
+    ///
+    /// The result is smoothed with the `blur_passes` of the precipitation config.
     pub fn build(
         vertical_air: &VerticalAirCubeMap,
         temperature: Option<&TemperatureCubeMap>,
@@ -45,11 +91,95 @@ impl PrecipitationCubeMap {
         ocean_weight: f32,
         equator_temp: f32,
         pole_temp: f32,
-        continent_threshold: f32,
+    ) -> Self {
+        // The temperatures have their own resolution setting, read them texel by texel on the
+        // grid of the vertical air
+        let temperature = temperature.map(|temp_map| {
+            if temp_map.resolution == vertical_air.resolution {
+                Cow::Borrowed(temp_map)
+            } else {
+                Cow::Owned(temp_map.resample(vertical_air.resolution))
+            }
+        });
+        let texel_temperature = |face: usize, x: usize, y: usize| {
+            temperature.as_ref().map(|temp_map| temp_map.faces[face].temperatures[y][x])
+        };
+        Self::build_from_texels(
+            vertical_air.resolution,
+            |face, x, y| {
+                let vertical = vertical_air.faces[face].values[y][x];
+                (vertical, normalized_temperature(texel_temperature(face, x, y), equator_temp, pole_temp))
+            },
+            planet,
+            temperature_weight,
+            ocean_weight,
+            crate::get_config().precipitation.blur_passes,
+        )
+    }
+
+    /// Precipitation at `phase` of the year, 0 to 1 from the northern spring equinox.
+    ///
+    /// The rising air and the temperatures are taken from where they would be without seasons,
+    /// shifted in latitude with the thermal equator, which swings `seasonal_shift_degrees`
+    /// north and south over the year. Land and ocean stay where they are, so a band of rain
+    /// moves on and off the coasts: a monsoon.
+    pub fn build_phase(
+        phase: f32,
+        vertical_air: &VerticalAirCubeMap,
+        temperature: Option<&TemperatureCubeMap>,
+        planet: Option<&PlanetData>,
+        temperature_weight: f32,
+        ocean_weight: f32,
+        equator_temp: f32,
+        pole_temp: f32,
     ) -> Self {
         let resolution = vertical_air.resolution;
+        let config = crate::get_config().precipitation;
+        let max_shift = config.seasonal_shift_degrees.to_radians();
+        let shift = max_shift * (phase * std::f32::consts::TAU).sin();
+        Self::build_from_texels(
+            resolution,
+            |face, x, y| {
+                let source = shift_latitude(cell_direction(resolution, face, x, y), -shift);
+                let temperature = temperature.map(|temp_map| temp_map.sample_temperature(source));
+                (vertical_air.sample(source), normalized_temperature(temperature, equator_temp, pole_temp))
+            },
+            planet,
+            temperature_weight,
+            ocean_weight,
+            config.blur_passes,
+        )
+    }
+
+    /// Blend of two maps of the same resolution, `self` at `t` = 0 and `other` at `t` = 1
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let faces = std::array::from_fn(|face| {
+            let mut values = self.faces[face].values.clone();
+            for (value, &to) in values.iter_mut().zip(other.faces[face].values.iter()) {
+                *value += (to - *value) * t;
+            }
+            PrecipitationCubeFace { values }
+        });
+        Self {
+            faces,
+            resolution: self.resolution,
+            calibration: self.calibration,
+        }
+    }
+
+    /// The map from the vertical air and the normalized temperature (0 at the pole temperature,
+    /// 1 at the equator one) of every texel, `texel(face, x, y)`
+    fn build_from_texels(
+        resolution: usize,
+        texel: impl Fn(usize, usize, usize) -> (f32, f32),
+        planet: Option<&PlanetData>,
+        temperature_weight: f32,
+        ocean_weight: f32,
+        blur_passes: usize,
+    ) -> Self {
+        trace_span!("precipitation_cubemap", resolution, blur_passes);
         let blank_face = PrecipitationCubeFace {
-            values: vec![vec![0.0; resolution]; resolution],
+            values: Grid2D::new(resolution, resolution, 0.0),
         };
 
         let mut faces = [
@@ -61,31 +191,15 @@ impl PrecipitationCubeMap {
             blank_face.clone(),
         ];
 
-        // Temperature range for normalization
-        let temp_range = equator_temp - pole_temp;
-
-        for face_idx in 0..6 {
+        for (face_idx, face) in faces.iter_mut().enumerate() {
             for y in 0..resolution {
                 for x in 0..resolution {
-                    let vertical = vertical_air.faces[face_idx].values[y][x];
+                    let (vertical, normalized_temp) = texel(face_idx, x, y);
 
                     // Uplift factor: rising air triggers precipitation
                     // -1 (rising) → 1.0, +1 (sinking) → 0.0
                     let uplift = (1.0 - vertical) / 2.0;
 
-                    // Moisture capacity from temperature
-                    // Warm = high capacity (1.0), Cold = low capacity (0.0)
-                    let normalized_temp = if let Some(temp_map) = temperature {
-                        if temp_range.abs() > 0.01 {
-                            let temp = temp_map.faces[face_idx].temperatures[y][x];
-                            ((temp - pole_temp) / temp_range).clamp(0.0, 1.0)
-                        } else {
-                            0.5
-                        }
-                    } else {
-                        0.5
-                    };
-
                     // Moisture capacity: temperature only reduces precipitation in cold
                     // regions (cold air holds less moisture), but does NOT boost hot regions
                     // above what uplift provides. This prevents hot sinking-air zones
@@ -96,14 +210,8 @@ impl PrecipitationCubeMap {
                     // Water availability (evaporation source strength)
                     // Ocean = high evaporation, Land = low evaporation
                     let water_availability = if let Some(planet) = planet {
-                        let u = (x as f32 / (resolution - 1) as f32) * 2.0 - 1.0;
-                        let v = (y as f32 / (resolution - 1) as f32) * 2.0 - 1.0;
-                        let height = sample_heightmap(planet, face_idx, u, v);
-
-                        let ocean_level = planet.radius + continent_threshold;
-                        let is_ocean = height < ocean_level;
-
-                        if is_ocean {
+                        // The same test the ocean is drawn by, so wet coasts end where the water does
+                        if planet.is_ocean(cell_direction(resolution, face_idx, x, y)) {
                             0.5 + 0.5 * normalized_temp
                         } else {
                             0.2 + 0.1 * normalized_temp
@@ -119,14 +227,14 @@ impl PrecipitationCubeMap {
                     // This ensures sinking air always means low precipitation regardless
                     // of temperature.
                     let precipitation = (uplift * effective_water).min(moisture_cap).clamp(0.0, 1.0);
-                    faces[face_idx].values[y][x] = precipitation;
+                    face.values[y][x] = precipitation;
                 }
             }
         }
 
         // Apply blur passes with cross-face sampling to avoid edge seams
-        let mut grids: [Vec<Vec<f32>>; 6] = std::array::from_fn(|i| faces[i].values.clone());
-        for _ in 0..BLUR_PASSES {
+        let mut grids: [Grid2D<f32>; 6] = std::array::from_fn(|i| faces[i].values.clone());
+        for _ in 0..blur_passes {
             grids = crate::cubemap_utils::blur_cube_faces(&grids, resolution);
         }
         for (i, grid) in grids.into_iter().enumerate() {
@@ -136,82 +244,82 @@ impl PrecipitationCubeMap {
         // Normalize precipitation so the wettest point reaches 1.0
         let mut max_val = 0.0f32;
         for face in &faces {
-            for row in &face.values {
-                for &v in row {
-                    max_val = max_val.max(v);
-                }
+            for &v in face.values.iter() {
+                max_val = max_val.max(v);
             }
         }
         if max_val > 0.0 {
             let scale = 1.0 / max_val;
             for face in &mut faces {
-                for row in &mut face.values {
-                    for v in row {
-                        *v *= scale;
-                    }
+                for v in face.values.iter_mut() {
+                    *v *= scale;
                 }
             }
         }
 
-        Self { faces, resolution }
+        let calibration = PrecipitationCalibration::from_config(&crate::get_config().precipitation);
+        Self {
+            faces,
+            resolution,
+            calibration,
+        }
     }
 
     /// Sample precipitation probability at a given position using bilinear interpolation.
     ///
     /// Returns a value in [0.0, 1.0]: 0 = dry, 1 = maximum precipitation.
     pub fn sample(&self, position: Vec3) -> f32 {
-        let dir = position.normalize();
-        let (face_idx, u, v) = crate::wind::velocity::direction_to_cube_uv(dir);
-
-        let fx = ((u + 1.0) * 0.5) * (self.resolution - 1) as f32;
-        let fy = ((v + 1.0) * 0.5) * (self.resolution - 1) as f32;
-
-        let x0 = fx.floor() as usize;
-        let y0 = fy.floor() as usize;
-        let x1 = (x0 + 1).min(self.resolution - 1);
-        let y1 = (y0 + 1).min(self.resolution - 1);
-
-        let tx = fx - x0 as f32;
-        let ty = fy - y0 as f32;
-
-        let face = &self.faces[face_idx];
-        let v00 = face.values[y0][x0];
-        let v10 = face.values[y0][x1];
-        let v01 = face.values[y1][x0];
-        let v11 = face.values[y1][x1];
+        crate::cubemap_utils::sample_bilinear(self.resolution, position, |face, x, y| {
+            self.faces[face].values[y][x]
+        })
+    }
 
-        let v0 = v00 + (v10 - v00) * tx;
-        let v1 = v01 + (v11 - v01) * tx;
-        v0 + (v1 - v0) * ty
+    /// Annual precipitation at a given position in mm, see `PrecipitationCalibration`
+    pub fn sample_mm(&self, position: Vec3) -> f32 {
+        self.calibration.to_mm(self.sample(position))
     }
 }
 
-/// Sample heightmap at given cube face coordinates using bilinear interpolation.
-fn sample_heightmap(planet: &PlanetData, face_idx: usize, u: f32, v: f32) -> f32 {
-    let grid_size = planet.face_grid_size;
-    let heightmap = &planet.faces[face_idx].heightmap;
-
-    // Convert u,v from [-1, 1] to grid coordinates [0, grid_size-1]
-    let fx = ((u + 1.0) * 0.5) * (grid_size - 1) as f32;
-    let fy = ((v + 1.0) * 0.5) * (grid_size - 1) as f32;
-
-    let x0 = (fx.floor() as usize).min(grid_size - 1);
-    let y0 = (fy.floor() as usize).min(grid_size - 1);
-    let x1 = (x0 + 1).min(grid_size - 1);
-    let y1 = (y0 + 1).min(grid_size - 1);
+/// Moisture capacity scale of a temperature, 0 at `pole_temp` and 1 at `equator_temp`.
+/// Without a temperature map, or with no range between the two, everywhere is halfway.
+fn normalized_temperature(temperature: Option<f32>, equator_temp: f32, pole_temp: f32) -> f32 {
+    let temp_range = equator_temp - pole_temp;
+    match temperature {
+        Some(temp) if temp_range.abs() > 0.01 => ((temp - pole_temp) / temp_range).clamp(0.0, 1.0),
+        _ => 0.5,
+    }
+}
 
-    let tx = fx - x0 as f32;
-    let ty = fy - y0 as f32;
+/// `dir` moved `shift` radians north along its meridian, stopping at the poles
+fn shift_latitude(dir: Vec3, shift: f32) -> Vec3 {
+    let latitude = (dir.y.clamp(-1.0, 1.0).asin() + shift).clamp(-FRAC_PI_2, FRAC_PI_2);
+    let meridian = Vec2::new(dir.x, dir.z).try_normalize().unwrap_or(Vec2::X);
+    Vec3::new(meridian.x * latitude.cos(), latitude.sin(), meridian.y * latitude.cos())
+}
 
-    // Bilinear interpolation
-    let h00 = heightmap[y0][x0];
-    let h10 = heightmap[y0][x1];
-    let h01 = heightmap[y1][x0];
-    let h11 = heightmap[y1][x1];
+/// Share of the land area in each `HISTOGRAM_BUCKET_MM` step of annual precipitation, driest
+/// first, the last bucket open-ended. Land is taken at the cells of the planet grid, each
+/// weighted by the area it covers. All zeros on a planet without land.
+pub fn land_precipitation_histogram(
+    planet: &PlanetData,
+    precipitation: &PrecipitationCubeMap,
+) -> [f32; HISTOGRAM_BUCKETS] {
+    let n = planet.face_grid_size;
+    let mut histogram = [0.0; HISTOGRAM_BUCKETS];
+    for (face, x, y) in all_cells(n) {
+        let dir = cell_direction(n, face, x, y);
+        if planet.is_ocean(dir) {
+            continue;
+        }
+        let bucket = (precipitation.sample_mm(dir) / HISTOGRAM_BUCKET_MM) as usize;
+        histogram[bucket.min(HISTOGRAM_BUCKETS - 1)] += solid_angle_of_cell(face, x, y, n);
+    }
 
-    let h0 = h00 + (h10 - h00) * tx;
-    let h1 = h01 + (h11 - h01) * tx;
-    h0 + (h1 - h0) * ty
+    let land: f32 = histogram.iter().sum();
+    if land > 0.0 {
+        histogram.iter_mut().for_each(|share| *share /= land);
+    }
+    histogram
 }
 
 /// Convert precipitation probability to RGB color.
@@ -219,8 +327,13 @@ fn sample_heightmap(planet: &PlanetData, face_idx: usize, u: f32, v: f32) -> f32
 /// * 0.0 (dry): yellow
 /// * 0.5 (moderate): light blue
 /// * 1.0 (wet): blue
-pub fn precipitation_to_color(value: f32) -> Vec3 {
+///
+/// Other palettes run from their low end (dry) to their high end (wet).
+pub fn precipitation_to_color(value: f32, palette: Palette) -> Vec3 {
     let t = value.clamp(0.0, 1.0);
+    if let Some(color) = palette.sequential(t) {
+        return color;
+    }
 
     if t < 0.5 {
         // Dry to moderate: yellow → light blue
@@ -244,17 +357,238 @@ pub fn precipitation_to_color(value: f32) -> Vec3 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::wind::vertical::VerticalAirCubeFace;
+
+    const N: usize = 8;
+
+    /// The map without blur or temperatures, every texel at the middle of the temperature range
+    fn unblurred(vertical_air: &VerticalAirCubeMap, planet: Option<&PlanetData>, ocean_weight: f32) -> PrecipitationCubeMap {
+        let texel = |face: usize, x: usize, y: usize| (vertical_air.faces[face].values[y][x], 0.5);
+        PrecipitationCubeMap::build_from_texels(vertical_air.resolution, texel, planet, 0.0, ocean_weight, 0)
+    }
+
+    /// Mean precipitation on the land faces and on the ocean faces, without blur
+    fn land_and_ocean(planet: Option<&PlanetData>) -> (f32, f32) {
+        let vertical_air = VerticalAirCubeMap {
+            faces: std::array::from_fn(|_| VerticalAirCubeFace {
+                values: Grid2D::new(N, N, -0.5),
+            }),
+            resolution: N,
+        };
+        let map = unblurred(&vertical_air, planet, 1.0);
+        let mean = |faces: &[PrecipitationCubeFace]| {
+            let values: Vec<f32> = faces.iter().flat_map(|face| face.values.iter().copied()).collect();
+            values.iter().sum::<f32>() / values.len() as f32
+        };
+        (mean(&map.faces[..3]), mean(&map.faces[3..]))
+    }
+
+    #[test]
+    fn test_terrain_makes_oceans_wetter_than_land() {
+        let (land, ocean) = land_and_ocean(None);
+        assert!((land - ocean).abs() < 1e-6, "without terrain everything is alike");
+
+        let planet = PlanetData::half_land(N, 50.0);
+        let (land, ocean) = land_and_ocean(Some(&planet));
+        assert!(ocean > land * 2.0, "ocean {ocean} vs land {land}");
+    }
+
+    #[test]
+    fn test_ocean_texels_are_exactly_the_flooded_terrain() {
+        let n = 13;
+        let mut planet = PlanetData::half_land(n, 50.0);
+        // A coastline running diagonally across the faces, above a sea level other than 0
+        for face in 0..6 {
+            for y in 0..n {
+                for x in 0..n {
+                    let dir = cell_direction(n, face, x, y);
+                    planet.faces[face].heightmap[y][x] = dir.x + 0.5 * dir.y - 0.3 * dir.z;
+                }
+            }
+        }
+        planet.sea_level = 0.25;
+
+        let vertical_air = VerticalAirCubeMap {
+            faces: std::array::from_fn(|_| VerticalAirCubeFace {
+                values: Grid2D::new(N, N, -0.5),
+            }),
+            resolution: N,
+        };
+        let map = unblurred(&vertical_air, Some(&planet), 1.0);
+
+        // Without blur or temperature ocean texels are 1 and land ones a third of that
+        let (mut ocean, mut land) = (0, 0);
+        for face in 0..6 {
+            for y in 0..N {
+                for x in 0..N {
+                    let is_wet = map.faces[face].values[y][x] > 0.5;
+                    let dir = cell_direction(N, face, x, y);
+                    assert_eq!(is_wet, planet.is_ocean(dir), "face {face} ({x}, {y})");
+                    if is_wet {
+                        ocean += 1;
+                    } else {
+                        land += 1;
+                    }
+                }
+            }
+        }
+        assert!(ocean > 0 && land > 0, "{ocean} ocean and {land} land texels");
+    }
+
+    #[test]
+    fn test_calibration_maps_values_to_mm_and_back() {
+        let calibration = PrecipitationCalibration {
+            max_annual_mm: 4000.0,
+            gamma: 2.0,
+        };
+
+        assert_eq!(calibration.to_mm(0.0), 0.0);
+        assert_eq!(calibration.to_mm(0.5), 1000.0);
+        assert_eq!(calibration.to_mm(1.5), 4000.0);
+        assert!((calibration.from_mm(1000.0) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_histogram_weighs_land_by_area() {
+        let planet = PlanetData::half_land(N, 50.0);
+        let vertical_air = VerticalAirCubeMap {
+            faces: std::array::from_fn(|_| VerticalAirCubeFace {
+                values: Grid2D::new(N, N, -0.5),
+            }),
+            resolution: N,
+        };
+        let mut map = unblurred(&vertical_air, None, 0.0);
+        map.calibration = PrecipitationCalibration {
+            max_annual_mm: 1000.0,
+            gamma: 1.0,
+        };
+        // A dry one of the three land faces and two wet ones, the ocean faces in between
+        for (face, map_face) in map.faces.iter_mut().enumerate() {
+            let value = match face {
+                0 => 0.0,
+                1 | 2 => 1.0,
+                _ => 0.5,
+            };
+            map_face.values.iter_mut().for_each(|v| *v = value);
+        }
+
+        let histogram = land_precipitation_histogram(&planet, &map);
+
+        assert!((histogram.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        // Blended at the face edges, the ocean's 500 mm left out
+        assert!((histogram[0] - 1.0 / 3.0).abs() < 0.1, "{histogram:?}");
+        assert!((histogram[4] - 2.0 / 3.0).abs() < 0.1, "{histogram:?}");
+    }
+
+    #[test]
+    fn test_default_planet_has_deserts_and_rainforests() {
+        let config = crate::get_config();
+        let mut generator = crate::generator::PlanetGenerator::new(20.0);
+        generator.seed = 42;
+        let planet = generator.generate();
+        let resolution = planet.face_grid_size.min(config.precipitation.cubemap_resolution);
+        let (wind, _) = crate::wind::WindCubeMap::build_with_terrain(
+            resolution,
+            config.wind.zonal_speed,
+            &planet,
+            &config.wind_deflection,
+        );
+        let temperature = TemperatureCubeMap::build_with_falloff(
+            resolution,
+            config.temperature.equator_temp,
+            config.temperature.pole_temp,
+            config.temperature.min_temp,
+            config.temperature.max_temp,
+            config.temperature.latitude_falloff,
+            Palette::Default,
+        );
+        let map = PrecipitationCubeMap::build(
+            &VerticalAirCubeMap::build_from_wind(&wind),
+            Some(&temperature),
+            Some(&planet),
+            config.precipitation.temperature_weight,
+            config.precipitation.ocean_weight,
+            config.temperature.equator_temp,
+            config.temperature.pole_temp,
+        );
+
+        let histogram = land_precipitation_histogram(&planet, &map);
+
+        assert!((histogram.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        // Some land gets less than 250 mm a year and some more than 2000 mm, like on Earth
+        assert!(histogram[0] > 0.0, "{histogram:?}");
+        assert!(histogram[8] + histogram[9] > 0.0, "{histogram:?}");
+    }
+
+    /// Rising air along the equator, sinking air at the poles
+    fn equatorial_uplift(n: usize) -> VerticalAirCubeMap {
+        VerticalAirCubeMap {
+            faces: std::array::from_fn(|face| VerticalAirCubeFace {
+                values: Grid2D::from_fn(n, n, |x, y| 2.0 * cell_direction(n, face, x, y).y.abs() - 1.0),
+            }),
+            resolution: n,
+        }
+    }
+
+    /// Latitude of the rain, as the precipitation-weighted mean of the texel heights
+    fn rain_latitude(map: &PrecipitationCubeMap) -> f32 {
+        let n = map.resolution;
+        let (mut weighted, mut total) = (0.0, 0.0);
+        for (face, x, y) in all_cells(n) {
+            let value = map.faces[face].values[y][x];
+            weighted += value * cell_direction(n, face, x, y).y;
+            total += value;
+        }
+        weighted / total
+    }
+
+    #[test]
+    fn test_rain_band_follows_the_thermal_equator() {
+        let n = 17;
+        let vertical_air = equatorial_uplift(n);
+        let phase = |phase| PrecipitationCubeMap::build_phase(phase, &vertical_air, None, None, 0.0, 0.0, 30.0, -20.0);
+
+        // At the equinox the band is where it is without seasons
+        let annual = PrecipitationCubeMap::build(&vertical_air, None, None, 0.0, 0.0, 30.0, -20.0);
+        let equinox = phase(0.0);
+        for (face, x, y) in all_cells(n) {
+            let difference = (equinox.faces[face].values[y][x] - annual.faces[face].values[y][x]).abs();
+            assert!(difference < 1e-3, "face {face} ({x}, {y}) off by {difference}");
+        }
+
+        let northern_summer = rain_latitude(&phase(0.25));
+        let southern_summer = rain_latitude(&phase(0.75));
+        assert!(rain_latitude(&equinox).abs() < 0.01);
+        assert!(northern_summer > 0.05, "{northern_summer}");
+        assert!((northern_summer + southern_summer).abs() < 0.01, "{northern_summer} vs {southern_summer}");
+    }
+
+    #[test]
+    fn test_lerp_blends_two_maps() {
+        let vertical_air = equatorial_uplift(N);
+        let from = PrecipitationCubeMap::build_phase(0.0, &vertical_air, None, None, 0.0, 0.0, 30.0, -20.0);
+        let to = PrecipitationCubeMap::build_phase(0.25, &vertical_air, None, None, 0.0, 0.0, 30.0, -20.0);
+
+        let quarter = from.lerp(&to, 0.25);
+
+        assert_eq!(from.lerp(&to, 0.0).faces[2].values, from.faces[2].values);
+        assert_eq!(from.lerp(&to, 1.0).faces[2].values, to.faces[2].values);
+        for (face, x, y) in all_cells(N) {
+            let (a, b) = (from.faces[face].values[y][x], to.faces[face].values[y][x]);
+            assert!((quarter.faces[face].values[y][x] - (0.75 * a + 0.25 * b)).abs() < 1e-6);
+        }
+    }
 
     #[test]
     fn test_precipitation_color_range() {
         // Dry should be yellow
-        let dry = precipitation_to_color(0.0);
+        let dry = precipitation_to_color(0.0, Palette::Default);
         assert!(dry.x > 0.9); // red high
         assert!(dry.y > 0.9); // green high
         assert!(dry.z < 0.3); // blue low
 
         // Wet should be blue
-        let wet = precipitation_to_color(1.0);
+        let wet = precipitation_to_color(1.0, Palette::Default);
         assert!(wet.x < 0.2); // red low
         assert!(wet.z > 0.9); // blue high
     }
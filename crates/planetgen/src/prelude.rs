@@ -1,2 +1,54 @@
+//! The supported public API of planetgen
+//!
+//! Anything not reachable from here or from [`crate::config`] is an implementation detail.
+
+// Generation
+pub use crate::craters::WorldType;
+pub use crate::generation_stats::GenerationStats;
 pub use crate::generator::PlanetGenerator;
-pub use crate::planet::PlanetData;
+pub use crate::pipeline::{GenerationContext, GenerationPipeline, GenerationStage};
+pub use crate::planet::{PlanetData, PlateType, oblate_radius};
+pub use crate::plate_colors::assign_plate_colors;
+pub use crate::plate_layout::PlateLayout;
+pub use crate::plate_stats::PlateStats;
+pub use crate::report::{GenerationReport, GenerationWarning};
+pub use crate::tools::{expand_seed64, generate_seed8};
+
+// Spherical geometry, directions from the planet center
+pub use crate::tools::sphere::{angular_distance, bearing, offset_by, solid_angle_of_cell, tangent_basis};
+
+// Meshes and overlays built from a generated planet
+pub use crate::arrows::{PlateArrowData, calculate_plate_arrows};
+pub use crate::biome::{Biome, BiomeColors, BiomeThresholds, dominant_biome};
+pub use crate::coastline::{Coastline, Polyline, extract_coastline};
+pub use crate::color_ramp::ColorRamp;
+pub use crate::color_variation::ColorVariation;
+pub use crate::crust_age::crust_age_to_color;
+pub use crate::features::{FeatureKind, FeatureNaming, NamedFeature};
+pub use crate::hillshade::hillshade_factor;
+pub use crate::mesh_data::{BiomeColorInputs, FACE_ATLAS_TILES, MeshData, MeshUvs, ViewMode, calculate_biome_colors, face_atlas_uv};
+pub use crate::mesh_validation::{MeshReport, validate_mesh};
+pub use crate::moon::{MoonData, generate_moon};
+pub use crate::names::{NameGenerator, is_pronounceable};
+pub use crate::palette::Palette;
+pub use crate::snow::SnowLine;
+
+// Picking on the displaced terrain
+pub use crate::collider::{SurfaceCollider, SurfaceHit};
+
+// Climate cube maps, built from the planet and sampled by direction. Their faces and the
+// terrain are stored in flat grids.
+pub use crate::grid::Grid2D;
+pub use crate::precipitations::{
+    PrecipitationCalibration, PrecipitationCubeMap, land_precipitation_histogram, precipitation_to_color,
+};
+pub use crate::profiles::{
+    MeridionalWind, ProfileSample, SphereField, ZonalWind, meridian_profile, zonal_mean,
+};
+pub use crate::temperature::{TemperatureCubeMap, TemperatureField};
+pub use crate::wind::influence::{MountainInfluenceMap, influence_to_color};
+pub use crate::wind::vertical::divergence_to_color;
+pub use crate::wind::{
+    DEFAULT_CUBEMAP_RESOLUTION, DEFAULT_GLYPH_SPACING, MIN_GLYPH_SPACING, TraceDirection, VerticalAirCubeMap,
+    WindCubeMap, WindGlyph, glyph_grid, sample_wind_glyphs, trace_streamline,
+};
@@ -0,0 +1,146 @@
+//! Latitude profiles of the climate maps: a value per latitude band, either averaged over all
+//! longitudes or read along a single meridian. Latitude comes from the Y axis, like everywhere
+//! else in the climate code.
+
+use crate::precipitations::PrecipitationCubeMap;
+use crate::temperature::TemperatureCubeMap;
+use glam::Vec3;
+
+/// Longitudes averaged for a zonal mean, one every 5°
+const ZONAL_MEAN_LONGITUDES: usize = 72;
+
+/// One point of a latitude profile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileSample {
+    /// Degrees, -90 at the south pole to 90 at the north pole
+    pub latitude: f32,
+    pub value: f32,
+}
+
+/// A scalar with a value in every direction on the sphere
+pub trait SphereField {
+    fn sample_at(&self, direction: Vec3) -> f32;
+}
+
+impl<F: Fn(Vec3) -> f32> SphereField for F {
+    fn sample_at(&self, direction: Vec3) -> f32 {
+        self(direction)
+    }
+}
+
+impl SphereField for TemperatureCubeMap {
+    fn sample_at(&self, direction: Vec3) -> f32 {
+        self.sample_temperature(direction)
+    }
+}
+
+impl SphereField for PrecipitationCubeMap {
+    fn sample_at(&self, direction: Vec3) -> f32 {
+        self.sample(direction)
+    }
+}
+
+/// East component of a wind field, positive towards the east
+pub struct ZonalWind<F>(pub F);
+
+impl<F: Fn(Vec3) -> Vec3> SphereField for ZonalWind<F> {
+    fn sample_at(&self, direction: Vec3) -> f32 {
+        (self.0)(direction).dot(eastward(direction))
+    }
+}
+
+/// North component of a wind field, positive towards the north pole
+pub struct MeridionalWind<F>(pub F);
+
+impl<F: Fn(Vec3) -> Vec3> SphereField for MeridionalWind<F> {
+    fn sample_at(&self, direction: Vec3) -> f32 {
+        let north = direction.cross(eastward(direction));
+        (self.0)(direction).dot(north)
+    }
+}
+
+/// Average of `field` around each of `n_lat` latitude bands, south to north.
+/// Samples sit at the band centers, so the poles themselves are never sampled and an odd
+/// `n_lat` puts one sample right on the equator.
+pub fn zonal_mean(field: &impl SphereField, n_lat: usize) -> Vec<ProfileSample> {
+    band_latitudes(n_lat)
+        .map(|latitude| {
+            let sum: f32 = (0..ZONAL_MEAN_LONGITUDES)
+                .map(|i| {
+                    let longitude = i as f32 * 360.0 / ZONAL_MEAN_LONGITUDES as f32;
+                    field.sample_at(direction_at(latitude, longitude))
+                })
+                .sum();
+            ProfileSample {
+                latitude,
+                value: sum / ZONAL_MEAN_LONGITUDES as f32,
+            }
+        })
+        .collect()
+}
+
+/// `field` along the meridian at `longitude` degrees, at the same latitudes as [`zonal_mean`]
+pub fn meridian_profile(field: &impl SphereField, longitude: f32, n_lat: usize) -> Vec<ProfileSample> {
+    band_latitudes(n_lat)
+        .map(|latitude| ProfileSample {
+            latitude,
+            value: field.sample_at(direction_at(latitude, longitude)),
+        })
+        .collect()
+}
+
+/// Unit direction at a latitude and longitude in degrees, longitude 0 on +X turning towards +Z
+fn direction_at(latitude: f32, longitude: f32) -> Vec3 {
+    let (lat_sin, lat_cos) = latitude.to_radians().sin_cos();
+    let (lon_sin, lon_cos) = longitude.to_radians().sin_cos();
+    Vec3::new(lat_cos * lon_cos, lat_sin, lat_cos * lon_sin)
+}
+
+fn band_latitudes(n_lat: usize) -> impl Iterator<Item = f32> {
+    let band = 180.0 / n_lat.max(1) as f32;
+    (0..n_lat).map(move |i| -90.0 + (i as f32 + 0.5) * band)
+}
+
+/// Same east as the wind model uses, the band centers never hit the poles
+fn eastward(direction: Vec3) -> Vec3 {
+    Vec3::Y.cross(direction).normalize_or_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::Palette;
+    use crate::wind::WindCubeMap;
+
+    #[test]
+    fn test_temperature_zonal_mean_at_the_equator_is_the_equator_temperature() {
+        let cubemap = TemperatureCubeMap::build(32, 30.0, -20.0, -40.0, 40.0, Palette::Default);
+        let profile = zonal_mean(&cubemap, 9);
+
+        let equator = profile[4];
+        assert_eq!(equator.latitude, 0.0);
+        assert!((equator.value - 30.0).abs() < 0.5, "equator at {}", equator.value);
+        assert!(profile[0].value < equator.value && profile[8].value < equator.value);
+    }
+
+    #[test]
+    fn test_profiles_run_from_south_to_north_inside_the_poles() {
+        let profile = meridian_profile(&|direction: Vec3| direction.y, 90.0, 6);
+
+        assert_eq!(profile.len(), 6);
+        assert!(profile.windows(2).all(|pair| pair[0].latitude < pair[1].latitude));
+        assert!(profile[0].latitude > -90.0 && profile[5].latitude < 90.0);
+        for sample in profile {
+            assert!((sample.value - sample.latitude.to_radians().sin()).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_zonal_wind_follows_the_trade_winds() {
+        let wind = WindCubeMap::build(32, 5.0);
+        let profile = zonal_mean(&ZonalWind(|direction: Vec3| wind.sample(direction)), 19);
+
+        // Trade winds blow towards the west near the equator
+        assert!(profile[9].value < 0.0, "equator zonal wind {}", profile[9].value);
+    }
+}
@@ -0,0 +1,230 @@
+//! Sanity checks on a finished planet
+//!
+//! Some seeds and slider combinations produce planets that are technically valid but look
+//! broken: almost no land, a plate squeezed down to a few cells, mountains along every plate
+//! edge. The report lists what looks off in plain words so the user knows why.
+
+use crate::boundaries::BoundaryType;
+use crate::config::ValidationConfig;
+use crate::grid::Grid2D;
+use crate::planet::{PlanetData, PlateSizeClass};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Something about a generated planet that is likely not what the user wanted
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerationWarning {
+    /// Land covers less than the configured minimum
+    MostlyOcean { land_fraction: f32 },
+    /// Land covers more than the configured maximum
+    MostlyLand { land_fraction: f32 },
+    /// A plate ended up with only a sliver of the surface
+    TinyPlate { plate_id: usize, cells: usize },
+    /// Only one plate is left, so there are no boundaries and no mountains
+    SinglePlate,
+    /// Every boundary cell is convergent, so mountains run along all plate edges
+    OnlyConvergentBoundaries,
+    /// Heights that are NaN or infinite, the mesh will have holes or spikes there
+    InvalidHeights { cells: usize },
+}
+
+impl fmt::Display for GenerationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationWarning::MostlyOcean { land_fraction } => write!(
+                f,
+                "The planet is {:.0}% ocean, try lowering the continent threshold",
+                (1.0 - land_fraction) * 100.0
+            ),
+            GenerationWarning::MostlyLand { land_fraction } => write!(
+                f,
+                "The planet is {:.0}% land, try raising the continent threshold",
+                land_fraction * 100.0
+            ),
+            GenerationWarning::TinyPlate { plate_id, cells } => {
+                write!(f, "Plate {plate_id} covers only {cells} cells")
+            }
+            GenerationWarning::SinglePlate => {
+                write!(f, "All plates merged into one, the planet has no plate boundaries")
+            }
+            GenerationWarning::OnlyConvergentBoundaries => {
+                write!(f, "Every plate boundary is convergent, mountains line all plate edges")
+            }
+            GenerationWarning::InvalidHeights { cells } => {
+                write!(f, "{cells} cells have no valid height (NaN or infinite)")
+            }
+        }
+    }
+}
+
+/// Result of checking a generated planet, empty when nothing looks off
+#[derive(Debug, Clone, Default)]
+pub struct GenerationReport {
+    pub warnings: Vec<GenerationWarning>,
+    /// Fraction of the surface above sea level
+    pub land_fraction: f32,
+}
+
+impl GenerationReport {
+    /// Check a planet, land is what lies above its sea level.
+    ///
+    pub fn inspect(planet: &PlanetData, config: &ValidationConfig) -> Self {
+        let mut warnings = Vec::new();
+        let n = planet.face_grid_size;
+        let total_cells = 6 * n * n;
+
+        // Land coverage and broken heights
+        let mut land_cells = 0;
+        let mut invalid_cells = 0;
+        for face in &planet.faces {
+            for &height in face.heightmap.iter() {
+                if !height.is_finite() {
+                    invalid_cells += 1;
+                } else if height > planet.sea_level {
+                    land_cells += 1;
+                }
+            }
+        }
+
+        let land_fraction = land_cells as f32 / total_cells as f32;
+        if land_fraction < config.min_land_fraction {
+            warnings.push(GenerationWarning::MostlyOcean { land_fraction });
+        } else if land_fraction > config.max_land_fraction {
+            warnings.push(GenerationWarning::MostlyLand { land_fraction });
+        }
+
+        if invalid_cells > 0 {
+            warnings.push(GenerationWarning::InvalidHeights { cells: invalid_cells });
+        }
+
+        // Plate sizes. Microplates are small on purpose, and plates absorbed by merging own no cells.
+        let mut plate_cells: HashMap<usize, usize> = HashMap::new();
+        for &plate_id in planet.plate_map.iter().flat_map(Grid2D::iter) {
+            *plate_cells.entry(plate_id).or_insert(0) += 1;
+        }
+
+        if plate_cells.len() < 2 {
+            warnings.push(GenerationWarning::SinglePlate);
+        }
+
+        let min_plate_cells = (config.min_plate_fraction * total_cells as f32).ceil() as usize;
+        for plate in &planet.plates {
+            let cells = plate_cells.get(&plate.id).copied().unwrap_or(0);
+            if matches!(plate.size_class, PlateSizeClass::Regular) && cells > 0 && cells < min_plate_cells {
+                warnings.push(GenerationWarning::TinyPlate { plate_id: plate.id, cells });
+            }
+        }
+
+        // Boundary types
+        let mut boundary_types = planet.boundary_data.boundaries.iter().flatten().flatten().flatten();
+        if let Some(first) = boundary_types.next()
+            && *first == BoundaryType::Convergent
+            && boundary_types.all(|boundary| *boundary == BoundaryType::Convergent)
+        {
+            warnings.push(GenerationWarning::OnlyConvergentBoundaries);
+        }
+
+        Self {
+            warnings,
+            land_fraction,
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planet::PlateType;
+    use crate::plate::TectonicPlate;
+    use glam::Vec3;
+
+    const N: usize = 9;
+
+    fn config() -> ValidationConfig {
+        ValidationConfig {
+            min_land_fraction: 0.05,
+            max_land_fraction: 0.95,
+            min_plate_fraction: 0.01,
+        }
+    }
+
+    fn plate(id: usize, size_class: PlateSizeClass) -> TectonicPlate {
+        TectonicPlate {
+            id,
+            direction: Vec3::X,
+            angular_velocity: Vec3::ZERO,
+            center: Vec3::X,
+            size_class,
+            plate_type: PlateType::default(),
+            debug_color: [0.0; 4],
+        }
+    }
+
+    /// Two plates on alternating faces, land on the first 3 faces
+    fn planet() -> PlanetData {
+        let mut planet = PlanetData::half_land(N, 1.0);
+        planet.plates = vec![plate(0, PlateSizeClass::Regular), plate(1, PlateSizeClass::Regular)];
+        planet
+    }
+
+    #[test]
+    fn test_reasonable_planet_is_clean() {
+        let report = GenerationReport::inspect(&planet(), &config());
+        assert!(report.is_clean(), "{:?}", report.warnings);
+        assert_eq!(report.land_fraction, 0.5);
+    }
+
+    #[test]
+    fn test_land_fraction_outside_limits() {
+        let mut ocean_world = planet();
+        for face in ocean_world.faces.iter_mut() {
+            face.heightmap = Grid2D::new(N, N, -1.0);
+        }
+        let report = GenerationReport::inspect(&ocean_world, &config());
+        assert_eq!(report.warnings, vec![GenerationWarning::MostlyOcean { land_fraction: 0.0 }]);
+
+        let mut drained = planet();
+        drained.sea_level = -2.0;
+        let report = GenerationReport::inspect(&drained, &config());
+        assert_eq!(report.warnings, vec![GenerationWarning::MostlyLand { land_fraction: 1.0 }]);
+    }
+
+    #[test]
+    fn test_tiny_and_missing_plates() {
+        let mut planet = planet();
+        planet.plates.push(plate(7, PlateSizeClass::Regular));
+        planet.plates.push(plate(8, PlateSizeClass::Micro));
+        planet.plate_map[0][0][0] = 7;
+        planet.plate_map[0][0][1] = 8;
+        let report = GenerationReport::inspect(&planet, &config());
+        assert_eq!(report.warnings, vec![GenerationWarning::TinyPlate { plate_id: 7, cells: 1 }]);
+
+        planet.plate_map = (0..6).map(|_| Grid2D::new(N, N, 0)).collect();
+        let report = GenerationReport::inspect(&planet, &config());
+        assert_eq!(report.warnings, vec![GenerationWarning::SinglePlate]);
+    }
+
+    #[test]
+    fn test_only_convergent_boundaries() {
+        let mut planet = planet();
+        planet.boundary_data.boundaries[0][4][4] = Some(BoundaryType::Convergent);
+        planet.boundary_data.boundaries[1][4][4] = Some(BoundaryType::Convergent);
+        let report = GenerationReport::inspect(&planet, &config());
+        assert_eq!(report.warnings, vec![GenerationWarning::OnlyConvergentBoundaries]);
+
+        planet.boundary_data.boundaries[2][4][4] = Some(BoundaryType::Divergent);
+        assert!(GenerationReport::inspect(&planet, &config()).is_clean());
+    }
+
+    #[test]
+    fn test_invalid_heights() {
+        let mut planet = planet();
+        planet.faces[0].heightmap[1][1] = f32::NAN;
+        let report = GenerationReport::inspect(&planet, &config());
+        assert_eq!(report.warnings, vec![GenerationWarning::InvalidHeights { cells: 1 }]);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::temperature::TemperatureCubeMap;
+use glam::Vec3;
+
+/// Color of fully snow-covered land
+pub(crate) const SNOW_COLOR: [f32; 4] = [0.95, 0.95, 1.0, 1.0];
+
+/// Width of the band around freezing the snow fades in over (°C)
+const SNOW_BAND: f32 = 4.0;
+
+/// How far the freezing line is moved back and forth between neighbouring vertices (°C), so
+/// the band edge frays instead of following the temperature contours
+const SNOW_DITHER: f32 = 1.5;
+
+/// Part of the range between the sea and the snow threshold the snow fades in over, without
+/// temperatures
+const THRESHOLD_TRANSITION: f32 = 0.15;
+
+/// Where the land is covered in snow
+///
+/// With a temperature map it's wherever the air is below freezing: the sea level temperature
+/// of the map plus the land bonus, cooler by `lapse_rate` for every unit of height above the
+/// sea. Polar lowlands are white while equatorial peaks only get snow far higher up. Without
+/// one it's everything above the global `threshold`.
+#[derive(Clone, Copy)]
+pub struct SnowLine<'a> {
+    /// Height snow lies above without a temperature map, also the top of the mountain colors
+    pub threshold: f32,
+    pub temperature: Option<&'a TemperatureCubeMap>,
+    /// °C colder per unit of height above sea level
+    pub lapse_rate: f32,
+    /// Extra warmth of land over the sea level temperature, see the biome colors
+    pub land_temperature_bonus: f32,
+}
+
+impl<'a> SnowLine<'a> {
+    /// Snow above `threshold`, the same everywhere
+    pub fn height(threshold: f32) -> Self {
+        Self {
+            threshold,
+            temperature: None,
+            lapse_rate: 0.0,
+            land_temperature_bonus: 0.0,
+        }
+    }
+
+    /// Snow where `temperature`, cooled with height, is below freezing
+    pub fn with_temperature(self, temperature: &'a TemperatureCubeMap, lapse_rate: f32, land_temperature_bonus: f32) -> Self {
+        Self {
+            temperature: Some(temperature),
+            lapse_rate,
+            land_temperature_bonus,
+            ..self
+        }
+    }
+
+    /// How much of the land at `direction` and `height` is snow, from 0 to 1
+    pub fn cover(&self, direction: Vec3, height: f32, sea_level: f32) -> f32 {
+        let Some(temperature) = self.temperature else {
+            let transition_start = self.threshold - (self.threshold - sea_level) * THRESHOLD_TRANSITION;
+            return smoothstep(transition_start, self.threshold, height);
+        };
+        let air = temperature.sample_temperature(direction) + self.land_temperature_bonus
+            - self.lapse_rate * (height - sea_level).max(0.0);
+        let dithered = air + (dither(direction) - 0.5) * 2.0 * SNOW_DITHER;
+        1.0 - smoothstep(-SNOW_BAND / 2.0, SNOW_BAND / 2.0, dithered)
+    }
+}
+
+/// 0 at `edge0` and below, 1 at `edge1` and above, eased in between
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge1 <= edge0 {
+        return if x >= edge1 { 1.0 } else { 0.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Pseudo random value in [0, 1) that jumps around between nearby directions
+fn dither(direction: Vec3) -> f32 {
+    let hash = (direction.dot(Vec3::new(12.9898, 78.233, 37.719)) * 1000.0).sin() * 43758.547;
+    hash - hash.floor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::Palette;
+
+    const LAPSE_RATE: f32 = 12.0;
+    const LAND_BONUS: f32 = 5.0;
+
+    /// 35 °C at the equator, -30 °C at the poles
+    fn temperatures() -> TemperatureCubeMap {
+        TemperatureCubeMap::build(32, 35.0, -30.0, -50.0, 50.0, Palette::Default)
+    }
+
+    #[test]
+    fn test_snow_follows_the_temperature() {
+        let map = temperatures();
+        let snow = SnowLine::height(1.4).with_temperature(&map, LAPSE_RATE, LAND_BONUS);
+
+        for (name, direction, height, expected) in [
+            ("polar lowland", Vec3::Y, 0.1, 1.0),
+            ("equatorial coast", Vec3::X, 0.1, 0.0),
+            ("equatorial foothills", Vec3::X, 2.0, 0.0),
+            ("equatorial peak", Vec3::X, 4.5, 1.0),
+        ] {
+            assert_eq!(snow.cover(direction, height, 0.0), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_snow_line_rises_towards_the_equator() {
+        let map = temperatures();
+        let snow = SnowLine::height(1.4).with_temperature(&map, LAPSE_RATE, LAND_BONUS);
+        // Lowest height fully covered at a latitude
+        let snow_line = |latitude: f32| {
+            let direction = Vec3::new(latitude.cos(), latitude.sin(), 0.0);
+            (0..=600).map(|i| i as f32 * 0.01).find(|&height| snow.cover(direction, height, 0.0) == 1.0)
+        };
+
+        let heights: Vec<f32> = [0.0_f32, 30.0, 60.0, 85.0]
+            .map(|degrees| snow_line(degrees.to_radians()).expect("no snow up to 6 units"))
+            .to_vec();
+        assert!(heights.windows(2).all(|pair| pair[0] > pair[1]), "{heights:?}");
+    }
+
+    #[test]
+    fn test_no_hard_snow_edge() {
+        let map = temperatures();
+        let snow = SnowLine::height(1.4).with_temperature(&map, LAPSE_RATE, LAND_BONUS);
+
+        let covers: Vec<f32> = (0..=600).map(|i| snow.cover(Vec3::X, i as f32 * 0.01, 0.0)).collect();
+        let partial = covers.iter().filter(|&&cover| cover > 0.05 && cover < 0.95).count();
+        assert!(partial > 10, "only {partial} heights between bare and white");
+    }
+
+    #[test]
+    fn test_without_temperatures_the_threshold_holds() {
+        let snow = SnowLine::height(1.4);
+
+        // Same cover at the pole and the equator
+        for direction in [Vec3::X, Vec3::Y] {
+            assert_eq!(snow.cover(direction, 0.5, 0.2), 0.0);
+            assert_eq!(snow.cover(direction, 1.4, 0.2), 1.0);
+            assert_eq!(snow.cover(direction, 3.0, 0.2), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_threshold_snow_fades_in_below_the_threshold() {
+        let cover = SnowLine::height(1.4).cover(Vec3::X, 1.35, 0.2);
+        assert!(cover > 0.0 && cover < 1.0, "{cover}");
+    }
+}
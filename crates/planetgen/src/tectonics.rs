@@ -0,0 +1,224 @@
+//! Plates drifting after generation
+//!
+//! Every plate turns around its angular velocity. Plates move in whole cells: a plate collects
+//! its rotation until it has turned by about one cell, then all its cells move at once. Each cell
+//! of the new plate map looks back along the rotation of every moving plate, a plate claims the
+//! cell when it owned the cell the point came from (a plate that doesn't move this step claims
+//! its own cells). That way every cell ends up with exactly one owner:
+//! - no claim: the plate that was there moved away, new ocean floor forms at a ridge
+//! - one claim: the crust moved in from the source cell
+//! - several claims: the plates collide, the highest crust stays on top and is pushed up
+
+use crate::boundaries::{BoundaryData, mean_cell_angle};
+use crate::cube_grid::StitchedGrid;
+use crate::cubemap_utils::{all_cells, nearest_cell};
+use crate::generator::cell_direction;
+use crate::lakes::LakeMap;
+use crate::planet::{PlanetData, PlateType};
+use crate::trace::trace_span;
+use glam::Quat;
+use std::collections::HashMap;
+
+/// Where the crust of a cell comes from after a step
+struct Arrival {
+    plate: usize,
+    height: f32,
+    age: Option<f32>,
+}
+
+impl PlanetData {
+    /// Drift the plates by their angular velocity over `dt`, then recompute the plate boundaries
+    /// and lakes. Heights of new and colliding crust come from the `[tectonics]` config.
+    pub fn step_tectonics(&mut self, dt: f32) {
+        let n = self.face_grid_size;
+        trace_span!("step_tectonics", face_grid_size = n);
+        let config = crate::get_config();
+        // The sea level of this planet, the config may have changed since it was generated
+        let sea_level = self.sea_level;
+        let uplift_floor = sea_level - config.mountains.mountain_underwater_threshold;
+
+        // Rotation back to where the crust of each plate moving this step came from
+        let cell_angle = mean_cell_angle(n);
+        self.plate_drift.resize(self.plates.len(), Quat::IDENTITY);
+        let mut back_rotations = HashMap::new();
+        for (plate, drift) in self.plates.iter_mut().zip(&mut self.plate_drift) {
+            *drift = Quat::from_scaled_axis(plate.angular_velocity * dt) * *drift;
+            if drift.angle_between(Quat::IDENTITY) < cell_angle {
+                continue;
+            }
+            plate.center = (*drift * plate.center).normalize();
+            plate.direction = (*drift * plate.direction).normalize();
+            back_rotations.insert(plate.id, drift.inverse());
+            *drift = Quat::IDENTITY;
+        }
+        if back_rotations.is_empty() {
+            return;
+        }
+        let oceanic: HashMap<usize, bool> = self
+            .plates
+            .iter()
+            .map(|plate| (plate.id, plate.plate_type == PlateType::Oceanic))
+            .collect();
+
+        // Shared edge points are decided once, on the face that owns them
+        let grid = StitchedGrid::new(n);
+        let arrivals: Vec<Arrival> = grid
+            .vertices()
+            .iter()
+            .map(|&(face, x, y)| {
+                let dir = cell_direction(n, face, x, y);
+                let mut claims = self.plates.iter().filter_map(|plate| {
+                    let source = match back_rotations.get(&plate.id) {
+                        Some(back) => nearest_cell(*back * dir, n),
+                        None => (face, x, y),
+                    };
+                    let (sf, sx, sy) = source;
+                    (self.plate_map[sf][sy][sx] == plate.id).then(|| Arrival {
+                        plate: plate.id,
+                        height: self.faces[sf].heightmap[sy][sx],
+                        age: self.crust_age.age[sf][sy][sx],
+                    })
+                });
+
+                let Some(first) = claims.next() else {
+                    let plate = self.plate_map[face][y][x];
+                    let age = oceanic.get(&plate).copied().unwrap_or(false).then_some(0.0);
+                    return Arrival {
+                        plate,
+                        height: config.tectonics.ridge_height,
+                        age,
+                    };
+                };
+                let mut collided = false;
+                let mut highest = first;
+                for claim in claims {
+                    collided = true;
+                    if claim.height > highest.height {
+                        highest = claim;
+                    }
+                }
+                if collided && highest.height > uplift_floor {
+                    let peak = config.mountains.height.max(highest.height);
+                    highest.height = (highest.height + config.tectonics.collision_uplift).min(peak);
+                }
+                highest
+            })
+            .collect();
+
+        for (face, x, y) in all_cells(n) {
+            let arrival = &arrivals[grid.index(face, x, y) as usize];
+            self.plate_map[face][y][x] = arrival.plate;
+            self.faces[face].heightmap[y][x] = arrival.height;
+            self.crust_age.age[face][y][x] = arrival.age;
+        }
+
+        self.boundary_data = BoundaryData::calculate(
+            n,
+            &self.plate_map,
+            &self.plates,
+            self.boundary_data.band_width,
+        );
+        // Barren worlds have no lakes and shouldn't get any from moving craters around
+        if self.lakes.lake_count > 0 {
+            self.lakes = LakeMap::calculate(&self.faces, n, sea_level, config.lakes.min_lake_area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::PlanetGenerator;
+    use glam::Vec3;
+    use std::collections::HashSet;
+
+    fn planet() -> PlanetData {
+        let mut generator = PlanetGenerator::new(5.0);
+        generator.seed = 42;
+        generator.generate()
+    }
+
+    #[test]
+    fn test_every_cell_has_exactly_one_owner_after_100_steps() {
+        let mut planet = planet();
+        let n = planet.face_grid_size;
+        let initial_map = planet.plate_map.clone();
+
+        for _ in 0..100 {
+            planet.step_tectonics(0.02);
+        }
+
+        let plate_ids: HashSet<usize> = planet.plates.iter().map(|plate| plate.id).collect();
+        let grid = StitchedGrid::new(n);
+        for (face, x, y) in all_cells(n) {
+            let owner = planet.plate_map[face][y][x];
+            assert!(
+                plate_ids.contains(&owner),
+                "{face} {x} {y} belongs to unknown plate {owner}"
+            );
+            // The same point on two faces can't belong to two plates
+            let (of, ox, oy) = grid.vertices()[grid.index(face, x, y) as usize];
+            assert_eq!(owner, planet.plate_map[of][oy][ox], "{face} {x} {y}");
+            assert_eq!(
+                planet.faces[face].heightmap[y][x],
+                planet.faces[of].heightmap[oy][ox]
+            );
+        }
+        let moved = all_cells(n)
+            .filter(|&(face, x, y)| planet.plate_map[face][y][x] != initial_map[face][y][x])
+            .count();
+        assert!(moved > 0, "no plate moved");
+    }
+
+    #[test]
+    fn test_plates_turning_less_than_a_cell_stay_put() {
+        let mut planet = planet();
+        let initial_map = planet.plate_map.clone();
+        let initial_heights = planet.faces.clone();
+
+        planet.step_tectonics(1e-4);
+
+        assert_eq!(planet.plate_map, initial_map);
+        for (face, initial) in planet.faces.iter().zip(&initial_heights) {
+            assert_eq!(face.heightmap, initial.heightmap);
+        }
+    }
+
+    #[test]
+    fn test_a_plate_leaves_new_ridge_crust_behind() {
+        let mut planet = planet();
+        let n = planet.face_grid_size;
+        let ridge_height = crate::get_config().tectonics.ridge_height;
+        let moving = planet.plates[0].id;
+        for plate in &mut planet.plates {
+            if plate.id != moving {
+                plate.angular_velocity = Vec3::ZERO;
+            }
+        }
+        let was_ridge: Vec<_> = all_cells(n)
+            .map(|(face, x, y)| planet.faces[face].heightmap[y][x] == ridge_height)
+            .collect();
+
+        // A few cells at once, so a whole strip opens up behind the plate
+        let step = 3.0 * mean_cell_angle(n) / planet.plates[0].angular_velocity.length();
+        planet.step_tectonics(step);
+
+        let new_ridge: Vec<_> = all_cells(n)
+            .zip(&was_ridge)
+            .filter(|&((face, x, y), was_ridge)| {
+                !was_ridge && planet.faces[face].heightmap[y][x] == ridge_height
+            })
+            .map(|(cell, _)| cell)
+            .collect();
+        assert!(
+            !new_ridge.is_empty(),
+            "no crust formed behind the moving plate"
+        );
+        for (face, x, y) in new_ridge {
+            assert_eq!(
+                planet.plate_map[face][y][x], moving,
+                "ridge on a plate that didn't move"
+            );
+        }
+    }
+}
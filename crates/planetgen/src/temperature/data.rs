@@ -1,5 +1,9 @@
 // Pure temperature data calculation logic
 
+use crate::cubemap_utils::resample_faces;
+use crate::grid::Grid2D;
+use crate::palette::Palette;
+use crate::trace::trace_span;
 use glam::Vec3;
 
 /// Pure temperature field calculations (no engine dependencies)
@@ -50,13 +54,17 @@ impl TemperatureField {
     /// * `temp` - Temperature in Celsius
     /// * `min_temp` - Minimum temperature for color scale
     /// * `max_temp` - Maximum temperature for color scale
+    /// * `palette` - Color set, `Default` is the rainbow below
     ///
     /// # Returns
     /// RGB color as Vec3 (values in range 0.0-1.0)
-    pub fn temperature_to_color(temp: f32, min_temp: f32, max_temp: f32) -> Vec3 {
+    pub fn temperature_to_color(temp: f32, min_temp: f32, max_temp: f32, palette: Palette) -> Vec3 {
         // Map temperature range [min_temp, max_temp] to [0, 1]
         let t = (temp - min_temp) / (max_temp - min_temp);
         let t = t.clamp(0.0, 1.0);
+        if let Some(color) = palette.sequential(t) {
+            return color;
+        }
 
         // Color gradient: light blue (cold) -> cyan -> green -> yellow -> orange -> red (hot)
         // Using multiple color stops for smooth transition
@@ -109,9 +117,9 @@ impl TemperatureField {
 #[derive(Clone)]
 pub struct TemperatureCubeFace {
     /// Grid of temperature values in Celsius [y][x]
-    pub temperatures: Vec<Vec<f32>>,
+    pub temperatures: Grid2D<f32>,
     /// Grid of color values [y][x]
-    pub colors: Vec<Vec<Vec3>>,
+    pub colors: Grid2D<Vec3>,
 }
 
 /// Pre-computed temperature cube map for the entire planet
@@ -132,17 +140,34 @@ impl TemperatureCubeMap {
     /// * `pole_temp` - Temperature at poles in Celsius
     /// * `min_temp` - Minimum temperature for color scale
     /// * `max_temp` - Maximum temperature for color scale
+    /// * `palette` - Color set of the pre-computed colors
     ///
     /// # Returns
     /// Pre-computed temperature cube map ready for sampling
-    pub fn build(resolution: usize, equator_temp: f32, pole_temp: f32, min_temp: f32, max_temp: f32) -> Self {
-        Self::build_with_falloff(resolution, equator_temp, pole_temp, min_temp, max_temp, 1.0)
+    pub fn build(
+        resolution: usize,
+        equator_temp: f32,
+        pole_temp: f32,
+        min_temp: f32,
+        max_temp: f32,
+        palette: Palette,
+    ) -> Self {
+        Self::build_with_falloff(resolution, equator_temp, pole_temp, min_temp, max_temp, 1.0, palette)
     }
 
-    pub fn build_with_falloff(resolution: usize, equator_temp: f32, pole_temp: f32, min_temp: f32, max_temp: f32, falloff: f32) -> Self {
+    pub fn build_with_falloff(
+        resolution: usize,
+        equator_temp: f32,
+        pole_temp: f32,
+        min_temp: f32,
+        max_temp: f32,
+        falloff: f32,
+        palette: Palette,
+    ) -> Self {
+        trace_span!("temperature_cubemap", resolution);
         let blank_face = TemperatureCubeFace {
-            temperatures: vec![vec![0.0; resolution]; resolution],
-            colors: vec![vec![Vec3::ZERO; resolution]; resolution],
+            temperatures: Grid2D::new(resolution, resolution, 0.0),
+            colors: Grid2D::new(resolution, resolution, Vec3::ZERO),
         };
 
         let mut faces = [
@@ -166,7 +191,7 @@ impl TemperatureCubeMap {
 
                     // Calculate temperature at this position
                     let temp = TemperatureField::calculate_temperature_at_with_falloff(dir, equator_temp, pole_temp, falloff);
-                    let color = TemperatureField::temperature_to_color(temp, min_temp, max_temp);
+                    let color = TemperatureField::temperature_to_color(temp, min_temp, max_temp, palette);
 
                     faces[face_idx].temperatures[y][x] = temp;
                     faces[face_idx].colors[y][x] = color;
@@ -188,35 +213,9 @@ impl TemperatureCubeMap {
     /// # Returns
     /// Interpolated temperature in Celsius at this position
     pub fn sample_temperature(&self, position: Vec3) -> f32 {
-        let dir = position.normalize();
-
-        // Convert 3D direction to cube face coordinates
-        let (face_idx, u, v) = direction_to_cube_uv(dir);
-
-        // Convert u,v from [-1, 1] to grid coordinates [0, resolution-1]
-        let fx = ((u + 1.0) * 0.5) * (self.resolution - 1) as f32;
-        let fy = ((v + 1.0) * 0.5) * (self.resolution - 1) as f32;
-
-        // Get integer grid cell indices
-        let x0 = fx.floor() as usize;
-        let y0 = fy.floor() as usize;
-        let x1 = (x0 + 1).min(self.resolution - 1);
-        let y1 = (y0 + 1).min(self.resolution - 1);
-
-        // Get fractional parts for interpolation
-        let tx = fx - x0 as f32;
-        let ty = fy - y0 as f32;
-
-        // Bilinear interpolation
-        let face = &self.faces[face_idx];
-        let v00 = face.temperatures[y0][x0];
-        let v10 = face.temperatures[y0][x1];
-        let v01 = face.temperatures[y1][x0];
-        let v11 = face.temperatures[y1][x1];
-
-        let v0 = v00 + (v10 - v00) * tx;
-        let v1 = v01 + (v11 - v01) * tx;
-        v0 + (v1 - v0) * ty
+        crate::cubemap_utils::sample_bilinear(self.resolution, position, |face, x, y| {
+            self.faces[face].temperatures[y][x]
+        })
     }
 
     /// Sample color at a given position using bilinear interpolation
@@ -227,35 +226,74 @@ impl TemperatureCubeMap {
     /// # Returns
     /// Interpolated color as Vec3 at this position
     pub fn sample_color(&self, position: Vec3) -> Vec3 {
-        let dir = position.normalize();
-
-        // Convert 3D direction to cube face coordinates
-        let (face_idx, u, v) = direction_to_cube_uv(dir);
-
-        // Convert u,v from [-1, 1] to grid coordinates [0, resolution-1]
-        let fx = ((u + 1.0) * 0.5) * (self.resolution - 1) as f32;
-        let fy = ((v + 1.0) * 0.5) * (self.resolution - 1) as f32;
-
-        // Get integer grid cell indices
-        let x0 = fx.floor() as usize;
-        let y0 = fy.floor() as usize;
-        let x1 = (x0 + 1).min(self.resolution - 1);
-        let y1 = (y0 + 1).min(self.resolution - 1);
-
-        // Get fractional parts for interpolation
-        let tx = fx - x0 as f32;
-        let ty = fy - y0 as f32;
-
-        // Bilinear interpolation
-        let face = &self.faces[face_idx];
-        let v00 = face.colors[y0][x0];
-        let v10 = face.colors[y0][x1];
-        let v01 = face.colors[y1][x0];
-        let v11 = face.colors[y1][x1];
-
-        let v0 = v00.lerp(v10, tx);
-        let v1 = v01.lerp(v11, tx);
-        v0.lerp(v1, ty)
+        crate::cubemap_utils::sample_bilinear(self.resolution, position, |face, x, y| {
+            self.faces[face].colors[y][x]
+        })
+    }
+
+    /// The same map at `new_resolution`, temperatures and colors bilinearly resampled, so a
+    /// simulated field keeps its state when the resolution changes
+    pub fn resample(&self, new_resolution: usize) -> Self {
+        if new_resolution == self.resolution {
+            return self.clone();
+        }
+        let temperatures = resample_faces(self.resolution, new_resolution, |face, x, y| {
+            self.faces[face].temperatures[y][x]
+        });
+        let mut colors = resample_faces(self.resolution, new_resolution, |face, x, y| self.faces[face].colors[y][x])
+            .into_iter();
+        Self {
+            faces: temperatures.map(|temperatures| TemperatureCubeFace {
+                temperatures,
+                colors: colors.next().unwrap(),
+            }),
+            resolution: new_resolution,
+        }
+    }
+
+    /// Move the temperatures along the wind for `dt` seconds (semi-Lagrangian: every texel takes
+    /// the temperature from where the wind carried the air from). `wind` is the surface velocity
+    /// in world units per second, `radius` turns it into an angle on the unit sphere.
+    /// Colors are left as they are, see `update_colors`.
+    pub fn advect(&mut self, wind: impl Fn(Vec3) -> Vec3, radius: f32, dt: f32) {
+        let previous = self.clone();
+        self.for_each_texel(|dir, temperature| {
+            let upwind = (dir - wind(dir) * dt / radius).normalize();
+            *temperature = previous.sample_temperature(upwind);
+        });
+    }
+
+    /// Pull every temperature towards `target` by `amount` (0 keeps it, 1 replaces it), so the
+    /// advected field settles instead of smearing forever
+    pub fn relax_towards(&mut self, target: &TemperatureCubeMap, amount: f32) {
+        let amount = amount.clamp(0.0, 1.0);
+        for (face, target_face) in self.faces.iter_mut().zip(&target.faces) {
+            for (temperature, target) in face.temperatures.iter_mut().zip(target_face.temperatures.iter()) {
+                *temperature += (target - *temperature) * amount;
+            }
+        }
+    }
+
+    /// Recompute the colors from the current temperatures
+    pub fn update_colors(&mut self, min_temp: f32, max_temp: f32, palette: Palette) {
+        for face in self.faces.iter_mut() {
+            for (temperature, color) in face.temperatures.iter().zip(face.colors.iter_mut()) {
+                *color = TemperatureField::temperature_to_color(*temperature, min_temp, max_temp, palette);
+            }
+        }
+    }
+
+    fn for_each_texel(&mut self, mut f: impl FnMut(Vec3, &mut f32)) {
+        let resolution = self.resolution;
+        for (face_idx, face) in self.faces.iter_mut().enumerate() {
+            for (y, row) in face.temperatures.rows_mut().enumerate() {
+                let v = (y as f32 / (resolution - 1) as f32) * 2.0 - 1.0;
+                for (x, temperature) in row.iter_mut().enumerate() {
+                    let u = (x as f32 / (resolution - 1) as f32) * 2.0 - 1.0;
+                    f(cube_face_point(face_idx, u, v).normalize(), temperature);
+                }
+            }
+        }
     }
 }
 
@@ -268,7 +306,7 @@ impl TemperatureCubeMap {
 ///
 /// # Returns
 /// 3D coordinates on unit cube surface
-pub fn cube_face_point(face_idx: usize, u: f32, v: f32) -> Vec3 {
+pub(crate) fn cube_face_point(face_idx: usize, u: f32, v: f32) -> Vec3 {
     match face_idx {
         0 => Vec3::new(1.0, v, -u),   // +X face
         1 => Vec3::new(-1.0, v, u),   // -X face
@@ -280,57 +318,75 @@ pub fn cube_face_point(face_idx: usize, u: f32, v: f32) -> Vec3 {
     }
 }
 
-/// Convert 3D direction to cube face coordinates
-///
-/// # Arguments
-/// * `dir` - Normalized direction vector
-///
-/// # Returns
-/// Tuple of (face_index, u, v) where u,v are in range [-1, 1]
-pub fn direction_to_cube_uv(dir: Vec3) -> (usize, f32, f32) {
-    let abs_x = dir.x.abs();
-    let abs_y = dir.y.abs();
-    let abs_z = dir.z.abs();
-
-    // Find dominant axis to determine face
-    if abs_x >= abs_y && abs_x >= abs_z {
-        // X-axis dominant
-        if dir.x > 0.0 {
-            // +X face (0)
-            let u = -dir.z / abs_x;
-            let v = dir.y / abs_x;
-            (0, u, v)
-        } else {
-            // -X face (1)
-            let u = dir.z / abs_x;
-            let v = dir.y / abs_x;
-            (1, u, v)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::cell_direction;
+    use crate::wind::WindCubeMap;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn simulate(steps: usize) -> TemperatureCubeMap {
+        let equilibrium = TemperatureCubeMap::build(16, 30.0, -20.0, -40.0, 40.0, Palette::Default);
+        let wind = WindCubeMap::build(16, 5.0);
+        let mut temperatures = equilibrium.clone();
+        for _ in 0..steps {
+            temperatures.advect(|dir| wind.sample(dir), 10.0, 0.1);
+            temperatures.relax_towards(&equilibrium, 0.05);
         }
-    } else if abs_y >= abs_x && abs_y >= abs_z {
-        // Y-axis dominant
-        if dir.y > 0.0 {
-            // +Y face (2)
-            let u = dir.x / abs_y;
-            let v = -dir.z / abs_y;
-            (2, u, v)
-        } else {
-            // -Y face (3)
-            let u = dir.x / abs_y;
-            let v = dir.z / abs_y;
-            (3, u, v)
-        }
-    } else {
-        // Z-axis dominant
-        if dir.z > 0.0 {
-            // +Z face (4)
-            let u = dir.x / abs_z;
-            let v = dir.y / abs_z;
-            (4, u, v)
-        } else {
-            // -Z face (5)
-            let u = -dir.x / abs_z;
-            let v = dir.y / abs_z;
-            (5, u, v)
+        temperatures
+    }
+
+    fn hash(cubemap: &TemperatureCubeMap) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for face in &cubemap.faces {
+            for temperature in face.temperatures.iter() {
+                temperature.to_bits().hash(&mut hasher);
+            }
         }
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_fixed_steps_are_deterministic() {
+        assert_eq!(hash(&simulate(100)), hash(&simulate(100)));
+    }
+
+    #[test]
+    fn test_advection_moves_temperatures_along_the_wind() {
+        let equilibrium = TemperatureCubeMap::build(16, 30.0, -20.0, -40.0, 40.0, Palette::Default);
+        let advected = simulate(20);
+        assert_ne!(hash(&advected), hash(&equilibrium));
+
+        // Relaxation keeps the field close to the latitude profile
+        let (min, max) = advected
+            .faces
+            .iter()
+            .flat_map(|face| face.temperatures.iter())
+            .fold((f32::MAX, f32::MIN), |(min, max), &t| (min.min(t), max.max(t)));
+        assert!(min >= -20.0 - 1e-3 && max <= 30.0 + 1e-3);
+    }
+
+    #[test]
+    fn test_resampling_to_the_same_resolution_is_identity() {
+        let temperatures = simulate(10);
+        let resampled = temperatures.resample(temperatures.resolution);
+        assert_eq!(resampled.resolution, temperatures.resolution);
+        assert_eq!(hash(&resampled), hash(&temperatures));
+    }
+
+    #[test]
+    fn test_downsample_then_upsample_stays_close_to_the_latitude_profile() {
+        let original = TemperatureCubeMap::build(32, 30.0, -20.0, -40.0, 40.0, Palette::Default);
+        let restored = original.resample(16).resample(32);
+        assert_eq!(restored.resolution, 32);
+
+        // cos(latitude) comes to a point at the poles, the field is smooth up to 60° away from
+        // the equator. 50 °C over a quarter turn, a coarse texel spans about 6°.
+        let max_error = crate::cubemap_utils::all_cells(32)
+            .filter(|&(face, x, y)| cell_direction(32, face, x, y).y.abs() < 60f32.to_radians().sin())
+            .map(|(face, x, y)| (original.faces[face].temperatures[y][x] - restored.faces[face].temperatures[y][x]).abs())
+            .fold(0.0f32, f32::max);
+        assert!(max_error < 0.5, "round trip is off by {max_error} °C");
     }
 }
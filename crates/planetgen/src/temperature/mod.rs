@@ -2,4 +2,4 @@
 
 pub mod data;
 
-pub use data::{TemperatureCubeFace, TemperatureCubeMap, TemperatureField};
+pub use data::{TemperatureCubeMap, TemperatureField};
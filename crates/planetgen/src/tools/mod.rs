@@ -1,3 +1,5 @@
+pub mod sphere;
+
 use rand::Rng;
 use rand::distr::Uniform;
 
@@ -10,7 +12,7 @@ pub fn expand_seed64(code: u32) -> u64 {
     splitmix64(code as u64)
 }
 
-pub fn splitmix64(mut x: u64) -> u64 {
+pub(crate) fn splitmix64(mut x: u64) -> u64 {
     x = x.wrapping_add(0x9E3779B97F4A7C15);
     let mut z = x;
     z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
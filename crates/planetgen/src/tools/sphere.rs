@@ -0,0 +1,200 @@
+//! Geometry on the unit sphere
+//!
+//! Directions are vectors from the planet center and don't have to be normalized. North is the
+//! +Y pole and east is `Y × up`, the same frame the wind model uses. Bearings are radians
+//! clockwise from north, so east is `π/2`.
+
+use crate::generator::cube_face_point;
+use glam::Vec3;
+
+/// Below this squared length a tangent vector counts as zero
+const DEGENERATE: f32 = 1e-12;
+
+/// Angle between two directions in radians, 0 to `π`.
+///
+/// Uses the cross and dot product together, which stays accurate for nearly equal and nearly
+/// antipodal directions where `acos` of the dot product loses most of its digits.
+pub fn angular_distance(a: Vec3, b: Vec3) -> f32 {
+    a.cross(b).length().atan2(a.dot(b))
+}
+
+/// East and north on the surface at `dir`, both unit length and tangent to the sphere.
+///
+/// At the poles every direction is south or north, there east is +X, the limit when
+/// approaching the pole along the +Z meridian.
+pub fn tangent_basis(dir: Vec3) -> (Vec3, Vec3) {
+    let up = dir.normalize();
+    let east = Vec3::Y.cross(up);
+    let east = if east.length_squared() < DEGENERATE {
+        Vec3::X
+    } else {
+        east.normalize()
+    };
+    (east, up.cross(east))
+}
+
+/// Initial bearing of the great circle from `from` to `to`.
+///
+/// Every great circle through `from` reaches the antipode, and every bearing is as good as
+/// any other to stay in place, so both of those cases give 0.
+pub fn bearing(from: Vec3, to: Vec3) -> f32 {
+    let up = from.normalize();
+    let to = to.normalize();
+    let toward = to - up * up.dot(to);
+    if toward.length_squared() < DEGENERATE {
+        return 0.0;
+    }
+    let (east, north) = tangent_basis(up);
+    toward.dot(east).atan2(toward.dot(north))
+}
+
+/// The direction `angle` radians away from `dir` along the great circle leaving it at `bearing`.
+pub fn offset_by(dir: Vec3, bearing: f32, angle: f32) -> Vec3 {
+    let up = dir.normalize();
+    let (east, north) = tangent_basis(up);
+    let heading = north * bearing.cos() + east * bearing.sin();
+    (up * angle.cos() + heading * angle.sin()).normalize()
+}
+
+/// Solid angle in steradians of the cube map cell at `(x, y)` on `face`.
+///
+/// A cell reaches half a grid step around its sample point. Grid points on a face edge are
+/// shared with the next face, so edge cells are halves and corner cells quarters, and all
+/// cells of all faces add up to the `4π` of the whole sphere.
+pub fn solid_angle_of_cell(face: usize, x: usize, y: usize, grid_size: usize) -> f32 {
+    let step = 2.0 / (grid_size - 1) as f32;
+    let (u, v) = (x as f32 * step - 1.0, y as f32 * step - 1.0);
+    let (u0, u1) = ((u - step * 0.5).max(-1.0), (u + step * 0.5).min(1.0));
+    let (v0, v1) = ((v - step * 0.5).max(-1.0), (v + step * 0.5).min(1.0));
+
+    let corner = |u, v| Vec3::from(cube_face_point(face, u, v)).normalize();
+    let (a, b, c, d) = (corner(u0, v0), corner(u1, v0), corner(u1, v1), corner(u0, v1));
+    triangle_solid_angle(a, b, c) + triangle_solid_angle(a, c, d)
+}
+
+/// Solid angle of the spherical triangle between three unit vectors (Van Oosterom and Strackee)
+pub fn triangle_solid_angle(a: Vec3, b: Vec3, c: Vec3) -> f32 {
+    let numerator = a.dot(b.cross(c)).abs();
+    let denominator = 1.0 + a.dot(b) + b.dot(c) + c.dot(a);
+    2.0 * numerator.atan2(denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubemap_utils::all_cells;
+    use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+    const EPS: f32 = 1e-5;
+
+    /// Both poles, points on the equator and a few in between
+    fn sample_directions() -> Vec<Vec3> {
+        let mut dirs = vec![Vec3::Y, Vec3::NEG_Y, Vec3::X, Vec3::NEG_Z];
+        for i in 0..24 {
+            let lat = -1.5 + i as f32 * 0.13;
+            let lon = i as f32 * 0.9;
+            dirs.push(Vec3::new(lat.cos() * lon.sin(), lat.sin(), lat.cos() * lon.cos()));
+        }
+        dirs
+    }
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(actual.distance(expected) < 1e-4, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_angular_distance_from_equal_to_antipodal() {
+        assert_eq!(angular_distance(Vec3::Z, Vec3::Z), 0.0);
+        assert!((angular_distance(Vec3::Z, Vec3::X) - FRAC_PI_2).abs() < EPS);
+        assert!((angular_distance(Vec3::Y, Vec3::NEG_Y) - PI).abs() < EPS);
+        // Unnormalized input gives the same angle
+        assert!((angular_distance(Vec3::Z * 5.0, Vec3::X * 0.1) - FRAC_PI_2).abs() < EPS);
+
+        // A thousandth of a degree, where acos of the dot product rounds to 0
+        let tiny = 1e-3_f32.to_radians();
+        assert!((angular_distance(Vec3::Z, offset_by(Vec3::Z, 0.3, tiny)) - tiny).abs() < 1e-7);
+        let near_antipode = offset_by(Vec3::NEG_Z, 0.3, tiny);
+        assert!((angular_distance(Vec3::Z, near_antipode) - (PI - tiny)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_tangent_basis_is_orthonormal_everywhere_including_the_poles() {
+        for dir in sample_directions() {
+            let (east, north) = tangent_basis(dir);
+            assert!((east.length() - 1.0).abs() < EPS, "{dir}");
+            assert!((north.length() - 1.0).abs() < EPS, "{dir}");
+            assert!(east.dot(north).abs() < EPS, "{dir}");
+            assert!(east.dot(dir).abs() < EPS, "{dir}");
+            assert!(north.dot(dir).abs() < EPS, "{dir}");
+            // Right handed with up, like x and y with z
+            assert!(east.cross(north).dot(dir) > 0.0, "{dir}");
+            // North leans towards the +Y pole away from the poles themselves
+            if dir.y.abs() < 0.99 {
+                assert!(north.y > 0.0, "{dir}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_bearing_points_north_and_east() {
+        assert!(bearing(Vec3::Z, Vec3::Y).abs() < EPS);
+        assert!((bearing(Vec3::Z, Vec3::X) - FRAC_PI_2).abs() < EPS);
+        assert!((bearing(Vec3::Z, Vec3::NEG_Y).abs() - PI).abs() < EPS);
+        assert!((bearing(Vec3::Z, Vec3::NEG_X) + FRAC_PI_2).abs() < EPS);
+    }
+
+    #[test]
+    fn test_offset_by_the_bearing_and_distance_reaches_the_target() {
+        let dirs = sample_directions();
+        for &from in &dirs {
+            for &to in &dirs {
+                let reached = offset_by(from, bearing(from, to), angular_distance(from, to));
+                assert_near(reached, to);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bearing_and_offset_at_the_poles_and_antipodes() {
+        // Every way from a pole leads away from it, the bearing only has to be usable
+        for pole in [Vec3::Y, Vec3::NEG_Y] {
+            for bearing in [0.0, 1.0, PI, -2.0] {
+                let moved = offset_by(pole, bearing, 0.1);
+                assert!((angular_distance(pole, moved) - 0.1).abs() < EPS);
+            }
+        }
+
+        // Going straight over the north pole ends on the opposite meridian
+        assert_near(offset_by(Vec3::Z, 0.0, PI * 0.5), Vec3::Y);
+        assert_near(offset_by(Vec3::Z, 0.0, PI * 0.75), Vec3::new(0.0, 1.0, -1.0).normalize());
+
+        // Antipodes: any bearing is fine and half a turn gets there
+        for dir in sample_directions() {
+            let b = bearing(dir, -dir);
+            assert!(b.is_finite());
+            assert_near(offset_by(dir, b, PI), -dir);
+            assert_eq!(bearing(dir, dir), 0.0);
+        }
+
+        // A full turn comes back
+        assert_near(offset_by(Vec3::X, 0.7, TAU), Vec3::X);
+    }
+
+    #[test]
+    fn test_cell_solid_angles_cover_the_sphere() {
+        for n in [2, 5, 33] {
+            let total: f32 = all_cells(n).map(|(face, x, y)| solid_angle_of_cell(face, x, y, n)).sum();
+            assert!((total - 4.0 * PI).abs() < 1e-3, "{n}: {total}");
+        }
+
+        // Cells in the middle of a face are closest to the center and cover the most sky
+        let n = 33;
+        let center = solid_angle_of_cell(4, 16, 16, n);
+        let off_center = solid_angle_of_cell(4, 8, 8, n);
+        assert!(center > off_center);
+        // The same cell on every face has the same size
+        for face in 0..6 {
+            assert!((solid_angle_of_cell(face, 3, 7, n) - solid_angle_of_cell(0, 3, 7, n)).abs() < 1e-6);
+        }
+    }
+}
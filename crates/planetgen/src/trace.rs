@@ -0,0 +1,16 @@
+//! Optional profiling spans around the expensive operations
+//!
+//! With the `trace` feature every [`trace_span!`] enters a `tracing` span for the rest of its
+//! scope, so generation stages, cubemap builds and meshes show up in a profiler next to the
+//! game's own systems. Without the feature the macro expands to nothing and its fields are
+//! never evaluated.
+
+/// Enter the span `$name` until the end of the current scope, fields as in `tracing::info_span!`
+macro_rules! trace_span {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "trace")]
+        let _span = tracing::info_span!($name $(, $($fields)*)?).entered();
+    };
+}
+
+pub(crate) use trace_span;
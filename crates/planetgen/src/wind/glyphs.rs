@@ -0,0 +1,124 @@
+// Wind direction glyphs on a latitude/longitude grid
+
+use super::velocity::WindCubeMap;
+use crate::tools::sphere::tangent_basis;
+use glam::Vec3;
+
+/// Default spacing of the glyph grid, in degrees
+pub const DEFAULT_GLYPH_SPACING: f32 = 10.0;
+
+/// Spacing below this would put tens of thousands of glyphs on the planet
+pub const MIN_GLYPH_SPACING: f32 = 2.0;
+
+/// The wind at one point of the glyph grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindGlyph {
+    /// Unit direction of the point from the planet center
+    pub direction: Vec3,
+    /// Unit tangent the wind blows towards, north where there is no wind
+    pub heading: Vec3,
+    /// Speed of the wind along the surface
+    pub speed: f32,
+}
+
+impl WindGlyph {
+    /// Local frame to draw the glyph in: along the wind, to its right and away from the center.
+    /// All three are unit length and perpendicular.
+    pub fn frame(&self) -> (Vec3, Vec3, Vec3) {
+        let up = self.direction;
+        (self.heading, self.heading.cross(up), up)
+    }
+}
+
+/// Points of a latitude/longitude grid `spacing_degrees` apart. Rows run every `spacing_degrees`
+/// of latitude between the poles, each with as many points as fit around it at that spacing, so
+/// the glyphs don't crowd together towards the poles. The poles get a point each.
+pub fn glyph_grid(spacing_degrees: f32) -> Vec<Vec3> {
+    let spacing = spacing_degrees.max(MIN_GLYPH_SPACING);
+    let rows = (180.0 / spacing).round().max(2.0) as usize;
+    let mut points = Vec::new();
+
+    for row in 0..=rows {
+        let latitude = (90.0 - 180.0 * row as f32 / rows as f32).to_radians();
+        let circumference = 360.0 * latitude.cos();
+        let columns = (circumference / spacing).round().max(1.0) as usize;
+        // Alternate rows are shifted half a column so the glyphs don't line up in meridians
+        let shift = if row % 2 == 0 { 0.0 } else { 0.5 };
+        for column in 0..columns {
+            let longitude = ((column as f32 + shift) / columns as f32 * 360.0).to_radians();
+            points.push(Vec3::new(
+                latitude.cos() * longitude.sin(),
+                latitude.sin(),
+                latitude.cos() * longitude.cos(),
+            ));
+        }
+    }
+
+    points
+}
+
+/// The wind of `cubemap` at every point of the glyph grid
+pub fn sample_wind_glyphs(cubemap: &WindCubeMap, spacing_degrees: f32) -> Vec<WindGlyph> {
+    glyph_grid(spacing_degrees)
+        .into_iter()
+        .map(|direction| {
+            let velocity = cubemap.sample(direction);
+            // The cubemap wind is tangent already, drop whatever a bilinear sample adds
+            let (east, north) = tangent_basis(direction);
+            let tangent = east * velocity.dot(east) + north * velocity.dot(north);
+            WindGlyph {
+                direction,
+                heading: tangent.normalize_or(north),
+                speed: tangent.length(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::sphere::angular_distance;
+
+    #[test]
+    fn test_grid_points_are_about_the_spacing_apart() {
+        let points = glyph_grid(10.0);
+
+        // About the area of the sphere over the area of a 10° cell
+        assert!((350..=500).contains(&points.len()), "{} points", points.len());
+        for (i, &a) in points.iter().enumerate() {
+            assert!((a.length() - 1.0).abs() < 1e-5);
+            let nearest = points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, &b)| angular_distance(a, b).to_degrees())
+                .fold(f32::MAX, f32::min);
+            assert!(nearest > 5.0 && nearest < 12.0, "nearest neighbor {nearest}° away");
+        }
+    }
+
+    #[test]
+    fn test_grid_spacing_is_clamped() {
+        assert_eq!(glyph_grid(0.0).len(), glyph_grid(MIN_GLYPH_SPACING).len());
+    }
+
+    #[test]
+    fn test_glyphs_follow_the_wind_along_the_surface() {
+        let wind = WindCubeMap::build(16, 5.0);
+
+        for glyph in sample_wind_glyphs(&wind, 20.0) {
+            let (along, right, up) = glyph.frame();
+            assert!(along.dot(up).abs() < 1e-4);
+            assert!(right.dot(up).abs() < 1e-4 && right.dot(along).abs() < 1e-4);
+            assert!((right.length() - 1.0).abs() < 1e-4);
+
+            let velocity = wind.sample(glyph.direction);
+            let tangent = velocity - up * velocity.dot(up);
+            assert!((glyph.speed - tangent.length()).abs() < 1e-4);
+            if glyph.speed > 1e-3 {
+                assert!(along.dot(tangent.normalize()) > 0.999);
+            }
+        }
+    }
+}
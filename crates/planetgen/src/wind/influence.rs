@@ -1,14 +1,18 @@
 use glam::Vec3;
 
 use crate::config::WindDeflectionConfig;
+use crate::cubemap_utils::resample_faces;
+use crate::grid::Grid2D;
+use crate::palette::Palette;
 use crate::planet::PlanetData;
+use crate::trace::trace_span;
 
 use super::velocity::{cube_face_point, direction_to_cube_uv};
 
 #[derive(Clone)]
 pub struct MountainInfluenceCubeFace {
-    pub costs: Vec<Vec<f32>>,
-    pub ridge_tangents: Vec<Vec<Vec3>>,
+    pub costs: Grid2D<f32>,
+    pub ridge_tangents: Grid2D<Vec3>,
 }
 
 #[derive(Clone)]
@@ -17,39 +21,12 @@ pub struct MountainInfluenceMap {
     pub resolution: usize,
 }
 
-/// Sample the planet heightmap at an arbitrary 3D direction using bilinear interpolation.
-fn sample_heightmap(planet: &PlanetData, dir: Vec3) -> f32 {
-    let dir = dir.normalize();
-    let (face_idx, u, v) = direction_to_cube_uv(dir);
-
-    let grid = planet.face_grid_size;
-    let fx = ((u + 1.0) * 0.5) * (grid - 1) as f32;
-    let fy = ((v + 1.0) * 0.5) * (grid - 1) as f32;
-
-    let x0 = fx.floor() as usize;
-    let y0 = fy.floor() as usize;
-    let x1 = (x0 + 1).min(grid - 1);
-    let y1 = (y0 + 1).min(grid - 1);
-
-    let tx = fx - x0 as f32;
-    let ty = fy - y0 as f32;
-
-    let face = &planet.faces[face_idx];
-    let h00 = face.heightmap[y0][x0];
-    let h10 = face.heightmap[y0][x1];
-    let h01 = face.heightmap[y1][x0];
-    let h11 = face.heightmap[y1][x1];
-
-    let h0 = h00 + (h10 - h00) * tx;
-    let h1 = h01 + (h11 - h01) * tx;
-    h0 + (h1 - h0) * ty
-}
-
 impl MountainInfluenceMap {
     pub fn build(planet: &PlanetData, resolution: usize, config: &WindDeflectionConfig) -> Self {
+        trace_span!("mountain_influence_map", resolution);
         let blank_face = MountainInfluenceCubeFace {
-            costs: vec![vec![0.0; resolution]; resolution],
-            ridge_tangents: vec![vec![Vec3::ZERO; resolution]; resolution],
+            costs: Grid2D::new(resolution, resolution, 0.0),
+            ridge_tangents: Grid2D::new(resolution, resolution, Vec3::ZERO),
         };
 
         let mut faces = [
@@ -70,7 +47,7 @@ impl MountainInfluenceMap {
                     let u = (x as f32 / (resolution - 1) as f32) * 2.0 - 1.0;
 
                     let dir = cube_face_point(face_idx, u, v).normalize();
-                    let height = sample_heightmap(planet, dir);
+                    let height = planet.sample_height(dir);
 
                     let cost = ((height - config.height_threshold) / config.height_scale)
                         .clamp(0.0, 1.0);
@@ -82,10 +59,10 @@ impl MountainInfluenceMap {
                         let east = get_tangent_east(surface_normal);
                         let north = surface_normal.cross(east).normalize();
 
-                        let h_px = sample_heightmap(planet, (dir + east * eps).normalize());
-                        let h_mx = sample_heightmap(planet, (dir - east * eps).normalize());
-                        let h_py = sample_heightmap(planet, (dir + north * eps).normalize());
-                        let h_my = sample_heightmap(planet, (dir - north * eps).normalize());
+                        let h_px = planet.sample_height((dir + east * eps).normalize());
+                        let h_mx = planet.sample_height((dir - east * eps).normalize());
+                        let h_py = planet.sample_height((dir + north * eps).normalize());
+                        let h_my = planet.sample_height((dir - north * eps).normalize());
 
                         let grad_e = (h_px - h_mx) / (2.0 * eps);
                         let grad_n = (h_py - h_my) / (2.0 * eps);
@@ -106,11 +83,11 @@ impl MountainInfluenceMap {
 
         // Spread/blur pass: propagate cost outward from mountain cells
         for _ in 0..config.spread_radius {
-            let snapshot: Vec<Vec<Vec<f32>>> = faces
+            let snapshot: Vec<Grid2D<f32>> = faces
                 .iter()
                 .map(|f| f.costs.clone())
                 .collect();
-            let tangent_snapshot: Vec<Vec<Vec<Vec3>>> = faces
+            let tangent_snapshot: Vec<Grid2D<Vec3>> = faces
                 .iter()
                 .map(|f| f.ridge_tangents.clone())
                 .collect();
@@ -147,6 +124,25 @@ impl MountainInfluenceMap {
         Self { faces, resolution }
     }
 
+    /// The same map at `new_resolution`, costs and ridge tangents bilinearly resampled
+    pub fn resample(&self, new_resolution: usize) -> Self {
+        if new_resolution == self.resolution {
+            return self.clone();
+        }
+        let costs = resample_faces(self.resolution, new_resolution, |face, x, y| self.faces[face].costs[y][x]);
+        let mut ridge_tangents = resample_faces(self.resolution, new_resolution, |face, x, y| {
+            self.faces[face].ridge_tangents[y][x]
+        })
+        .into_iter();
+        Self {
+            faces: costs.map(|costs| MountainInfluenceCubeFace {
+                costs,
+                ridge_tangents: ridge_tangents.next().unwrap(),
+            }),
+            resolution: new_resolution,
+        }
+    }
+
     /// Sample cost and ridge tangent at a 3D direction using bilinear interpolation.
     pub fn sample(&self, position: Vec3) -> (f32, Vec3) {
         let dir = position.normalize();
@@ -189,6 +185,23 @@ impl MountainInfluenceMap {
     }
 }
 
+/// Color for a mountain influence cost in `0..=1`: gray where the wind flows freely, through
+/// orange to dark red where it is fully blocked. Other palettes run from free to blocked.
+pub fn influence_to_color(cost: f32, palette: Palette) -> Vec3 {
+    let t = cost.clamp(0.0, 1.0);
+    if let Some(color) = palette.sequential(t) {
+        return color;
+    }
+    let free = Vec3::new(0.6, 0.6, 0.6);
+    let partial = Vec3::new(1.0, 0.6, 0.1);
+    let blocked = Vec3::new(0.6, 0.0, 0.0);
+    if t < 0.5 {
+        free.lerp(partial, t * 2.0)
+    } else {
+        partial.lerp(blocked, (t - 0.5) * 2.0)
+    }
+}
+
 /// Get a tangent-plane east vector for a surface normal.
 fn get_tangent_east(normal: Vec3) -> Vec3 {
     let up = Vec3::Y;
@@ -0,0 +1,140 @@
+// Streamlines traced through the wind cubemap
+
+use super::velocity::WindCubeMap;
+use glam::Vec3;
+
+/// Slower than this the wind has no direction worth following, a trace ends there
+pub const MIN_STREAMLINE_SPEED: f32 = 1e-3;
+
+/// Which way along the wind a streamline is traced from its start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceDirection {
+    /// Where the air goes
+    Forward,
+    /// Where the air came from
+    Backward,
+}
+
+impl TraceDirection {
+    fn sign(self) -> f32 {
+        match self {
+            TraceDirection::Forward => 1.0,
+            TraceDirection::Backward => -1.0,
+        }
+    }
+}
+
+/// Follow the wind of `cubemap` from `start_dir`, `step_angle` radians along the sphere per
+/// step (RK4 on the wind direction, so every step covers the same arc however fast the wind
+/// blows). Returns the unit directions of the line, starting with `start_dir`.
+///
+/// The trace ends after `max_steps` or where the wind drops below `MIN_STREAMLINE_SPEED`, in
+/// the doldrums and at the centers of the circulation cells.
+pub fn trace_streamline(
+    cubemap: &WindCubeMap,
+    start_dir: Vec3,
+    step_angle: f32,
+    max_steps: usize,
+    direction: TraceDirection,
+) -> Vec<Vec3> {
+    let start = start_dir.normalize();
+    let mut points = vec![start];
+    let step = step_angle * direction.sign();
+
+    // Unit heading of the wind along the surface, none where it is too weak to follow
+    let heading = |dir: Vec3| {
+        let velocity = cubemap.sample(dir);
+        let tangent = velocity - dir * velocity.dot(dir);
+        (tangent.length() >= MIN_STREAMLINE_SPEED).then(|| tangent.normalize())
+    };
+    let advance = |dir: Vec3, heading: Vec3, angle: f32| (dir + heading * angle).normalize();
+
+    let mut current = start;
+    for _ in 0..max_steps {
+        let Some(k1) = heading(current) else { break };
+        let Some(k2) = heading(advance(current, k1, step * 0.5)) else { break };
+        let Some(k3) = heading(advance(current, k2, step * 0.5)) else { break };
+        let Some(k4) = heading(advance(current, k3, step)) else { break };
+        let next = advance(current, (k1 + 2.0 * k2 + 2.0 * k3 + k4) / 6.0, step);
+        if !next.is_finite() {
+            break;
+        }
+        points.push(next);
+        current = next;
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::cell_direction;
+    use crate::grid::Grid2D;
+    use crate::wind::velocity::WindCubeFace;
+
+    const RESOLUTION: usize = 32;
+
+    fn cubemap(velocity: impl Fn(Vec3) -> Vec3) -> WindCubeMap {
+        WindCubeMap {
+            faces: std::array::from_fn(|face| WindCubeFace {
+                velocities: Grid2D::from_fn(RESOLUTION, RESOLUTION, |x, y| {
+                    velocity(cell_direction(RESOLUTION, face, x, y))
+                }),
+            }),
+            resolution: RESOLUTION,
+        }
+    }
+
+    fn latitude(dir: Vec3) -> f32 {
+        dir.y.clamp(-1.0, 1.0).asin().to_degrees()
+    }
+
+    #[test]
+    fn test_zonal_wind_keeps_the_latitude() {
+        // West to east everywhere, like the westerlies
+        let zonal = cubemap(|dir| Vec3::Y.cross(dir) * 5.0);
+        let start = Vec3::new(30f32.to_radians().cos(), 30f32.to_radians().sin(), 0.0);
+
+        for direction in [TraceDirection::Forward, TraceDirection::Backward] {
+            let line = trace_streamline(&zonal, start, 0.02, 200, direction);
+            assert_eq!(line.len(), 201);
+            for point in &line {
+                assert!((latitude(*point) - 30.0).abs() < 0.5, "drifted to {}°", latitude(*point));
+            }
+        }
+    }
+
+    #[test]
+    fn test_forward_and_backward_go_opposite_ways() {
+        let zonal = cubemap(|dir| Vec3::Y.cross(dir));
+        let forward = trace_streamline(&zonal, Vec3::X, 0.05, 10, TraceDirection::Forward);
+        let backward = trace_streamline(&zonal, Vec3::X, 0.05, 10, TraceDirection::Backward);
+        // Y × X points along -Z
+        assert!(forward[10].z < 0.0 && backward[10].z > 0.0);
+    }
+
+    #[test]
+    fn test_trace_stops_in_the_doldrums() {
+        // Towards the equator outside a calm band 10° to either side of it
+        let calm = 10f32.to_radians().sin();
+        let trade_winds = cubemap(|dir| {
+            if dir.y.abs() < calm {
+                Vec3::ZERO
+            } else {
+                let towards_equator = -Vec3::Y * dir.y.signum();
+                (towards_equator - dir * towards_equator.dot(dir)).normalize() * 4.0
+            }
+        });
+        let start = Vec3::new(40f32.to_radians().cos(), 40f32.to_radians().sin(), 0.0);
+
+        let line = trace_streamline(&trade_winds, start, 0.02, 1000, TraceDirection::Forward);
+        assert!(line.len() > 1 && line.len() < 1000, "{} points", line.len());
+        assert!(line.iter().all(|point| point.is_finite() && (point.length() - 1.0).abs() < 1e-4));
+        // Ends at the edge of the calm band, the bilinear blend reaches a texel into it
+        let end = latitude(*line.last().unwrap());
+        assert!(end > 5.0 && end < 15.0, "stopped at {end}°");
+
+        // Starting inside the band goes nowhere
+        assert_eq!(trace_streamline(&trade_winds, Vec3::X, 0.02, 1000, TraceDirection::Forward), [Vec3::X]);
+    }
+}
@@ -1,9 +1,12 @@
 // Pure wind velocity calculation logic
 
 use super::influence::MountainInfluenceMap;
-use super::{DEFAULT_WIND_SPEED, SIGNS, TAU, TURN_POINTS, ZONAL_SIGNS};
+use super::{DEFAULT_WIND_SPEED, SIGNS, TURN_POINTS, ZONAL_SIGNS};
 use crate::config::WindDeflectionConfig;
+use crate::cubemap_utils::resample_faces;
+use crate::grid::Grid2D;
 use crate::planet::PlanetData;
+use crate::trace::trace_span;
 use glam::Vec3;
 
 /// Pure wind field calculations (no engine dependencies)
@@ -167,25 +170,13 @@ impl WindField {
         meridional_velocity + zonal_velocity
     }
 
-    /// Update latitudinal speed towards desired value using relaxation
-    ///
-    /// # Arguments
-    /// * `current_speed` - Current latitudinal speed
-    /// * `desired_speed` - Target latitudinal speed
-    /// * `dt` - Delta time in seconds
-    ///
-    /// # Returns
-    /// Updated latitudinal speed
-    pub fn update_latitudinal_speed(current_speed: f32, desired_speed: f32, dt: f32) -> f32 {
-        current_speed + (desired_speed - current_speed) * (dt / TAU)
-    }
 }
 
 /// A single cube face storing pre-computed wind velocity vectors
 #[derive(Clone)]
 pub struct WindCubeFace {
     /// Grid of velocity vectors [y][x]
-    pub velocities: Vec<Vec<Vec3>>,
+    pub velocities: Grid2D<Vec3>,
 }
 
 /// Pre-computed wind velocity cube map for the entire planet
@@ -207,8 +198,9 @@ impl WindCubeMap {
     /// # Returns
     /// Pre-computed wind cube map ready for sampling
     pub fn build(resolution: usize, zonal_speed: f32) -> Self {
+        trace_span!("wind_cubemap", resolution);
         let blank_face = WindCubeFace {
-            velocities: vec![vec![Vec3::ZERO; resolution]; resolution],
+            velocities: Grid2D::new(resolution, resolution, Vec3::ZERO),
         };
 
         let mut faces = [
@@ -249,35 +241,23 @@ impl WindCubeMap {
     /// # Returns
     /// Interpolated wind velocity vector at this position
     pub fn sample(&self, position: Vec3) -> Vec3 {
-        let dir = position.normalize();
-
-        // Convert 3D direction to cube face coordinates
-        let (face_idx, u, v) = direction_to_cube_uv(dir);
-
-        // Convert u,v from [-1, 1] to grid coordinates [0, resolution-1]
-        let fx = ((u + 1.0) * 0.5) * (self.resolution - 1) as f32;
-        let fy = ((v + 1.0) * 0.5) * (self.resolution - 1) as f32;
-
-        // Get integer grid cell indices
-        let x0 = fx.floor() as usize;
-        let y0 = fy.floor() as usize;
-        let x1 = (x0 + 1).min(self.resolution - 1);
-        let y1 = (y0 + 1).min(self.resolution - 1);
-
-        // Get fractional parts for interpolation
-        let tx = fx - x0 as f32;
-        let ty = fy - y0 as f32;
-
-        // Bilinear interpolation
-        let face = &self.faces[face_idx];
-        let v00 = face.velocities[y0][x0];
-        let v10 = face.velocities[y0][x1];
-        let v01 = face.velocities[y1][x0];
-        let v11 = face.velocities[y1][x1];
-
-        let v0 = v00.lerp(v10, tx);
-        let v1 = v01.lerp(v11, tx);
-        v0.lerp(v1, ty)
+        crate::cubemap_utils::sample_bilinear(self.resolution, position, |face, x, y| {
+            self.faces[face].velocities[y][x]
+        })
+    }
+
+    /// The same map at `new_resolution`, velocities bilinearly resampled
+    pub fn resample(&self, new_resolution: usize) -> Self {
+        if new_resolution == self.resolution {
+            return self.clone();
+        }
+        let velocities = resample_faces(self.resolution, new_resolution, |face, x, y| {
+            self.faces[face].velocities[y][x]
+        });
+        Self {
+            faces: velocities.map(|velocities| WindCubeFace { velocities }),
+            resolution: new_resolution,
+        }
     }
 
     /// Build a wind cube map with terrain-aware deflection.
@@ -287,6 +267,7 @@ impl WindCubeMap {
         planet: &PlanetData,
         config: &WindDeflectionConfig,
     ) -> (Self, MountainInfluenceMap) {
+        trace_span!("wind_cubemap_with_terrain", resolution);
         let mut wind = Self::build(resolution, zonal_speed);
         let influence = MountainInfluenceMap::build(planet, resolution, config);
         wind.apply_deflection(&influence, config);
@@ -301,7 +282,7 @@ impl WindCubeMap {
     ) {
         for _ in 0..config.deflection_iterations {
             // Snapshot current velocities
-            let snapshot: Vec<Vec<Vec<Vec3>>> =
+            let snapshot: Vec<Grid2D<Vec3>> =
                 self.faces.iter().map(|f| f.velocities.clone()).collect();
 
             for face_idx in 0..6 {
@@ -380,7 +361,7 @@ impl WindCubeMap {
 ///
 /// # Returns
 /// 3D coordinates on unit cube surface
-pub fn cube_face_point(face_idx: usize, u: f32, v: f32) -> Vec3 {
+pub(crate) fn cube_face_point(face_idx: usize, u: f32, v: f32) -> Vec3 {
     match face_idx {
         0 => Vec3::new(1.0, v, -u),  // +X face
         1 => Vec3::new(-1.0, v, u),  // -X face
@@ -399,7 +380,7 @@ pub fn cube_face_point(face_idx: usize, u: f32, v: f32) -> Vec3 {
 ///
 /// # Returns
 /// Tuple of (face_index, u, v) where u,v are in range [-1, 1]
-pub fn direction_to_cube_uv(dir: Vec3) -> (usize, f32, f32) {
+pub(crate) fn direction_to_cube_uv(dir: Vec3) -> (usize, f32, f32) {
     let abs_x = dir.x.abs();
     let abs_y = dir.y.abs();
     let abs_z = dir.z.abs();
@@ -1,13 +1,17 @@
 // Vertical air movement computed from wind field divergence
 
-use super::velocity::{WindCubeMap, cube_face_point, direction_to_cube_uv};
+use super::velocity::{WindCubeMap, cube_face_point};
+use crate::grid::Grid2D;
+use crate::palette::Palette;
+use crate::tools::sphere::triangle_solid_angle;
+use crate::trace::trace_span;
 use glam::Vec3;
 
 /// A single cube face storing pre-computed vertical air movement values
 #[derive(Clone)]
 pub struct VerticalAirCubeFace {
     /// Grid of divergence values [y][x], negative = rising, positive = sinking
-    pub values: Vec<Vec<f32>>,
+    pub values: Grid2D<f32>,
 }
 
 /// Pre-computed vertical air movement cube map for the entire planet.
@@ -24,13 +28,14 @@ const BLUR_PASSES: usize = 15;
 impl VerticalAirCubeMap {
     /// Build from an existing wind cube map by computing surface divergence.
     ///
-    /// After computing raw divergence via finite differences, applies repeated
+    /// After computing raw divergence over each cell's spherical quad, applies repeated
     /// blur passes to spread thin convergence/divergence lines into broad
     /// atmospheric zones, then normalizes and enhances contrast.
     pub fn build_from_wind(wind: &WindCubeMap) -> Self {
         let resolution = wind.resolution;
+        trace_span!("vertical_air_cubemap", resolution);
         let blank_face = VerticalAirCubeFace {
-            values: vec![vec![0.0; resolution]; resolution],
+            values: Grid2D::new(resolution, resolution, 0.0),
         };
 
         let mut faces = [
@@ -46,13 +51,14 @@ impl VerticalAirCubeMap {
         for face_idx in 0..6 {
             for y in 0..resolution {
                 for x in 0..resolution {
-                    faces[face_idx].values[y][x] = compute_divergence(wind, face_idx, x, y);
+                    faces[face_idx].values[y][x] =
+                        compute_divergence(|dir| wind.sample(dir), face_idx, x, y, resolution);
                 }
             }
         }
 
         // Step 2: Blur to spread thin lines into broad zones (cross-face to avoid edge seams)
-        let mut grids: [Vec<Vec<f32>>; 6] = std::array::from_fn(|i| faces[i].values.clone());
+        let mut grids: [Grid2D<f32>; 6] = std::array::from_fn(|i| faces[i].values.clone());
         for _ in 0..BLUR_PASSES {
             grids = crate::cubemap_utils::blur_cube_faces(&grids, resolution);
         }
@@ -63,30 +69,24 @@ impl VerticalAirCubeMap {
         // Step 3: Normalize to [-1, 1]
         let mut max_abs: f32 = 0.0;
         for face in &faces {
-            for row in &face.values {
-                for &val in row {
-                    max_abs = max_abs.max(val.abs());
-                }
+            for &val in face.values.iter() {
+                max_abs = max_abs.max(val.abs());
             }
         }
 
         if max_abs > 1e-6 {
             for face in &mut faces {
-                for row in &mut face.values {
-                    for val in row.iter_mut() {
-                        *val /= max_abs;
-                    }
+                for val in face.values.iter_mut() {
+                    *val /= max_abs;
                 }
             }
         }
 
         // Step 4: Non-linear enhancement (signed sqrt) to boost weak signals
         for face in &mut faces {
-            for row in &mut face.values {
-                for val in row.iter_mut() {
-                    let sign = val.signum();
-                    *val = sign * val.abs().sqrt();
-                }
+            for val in face.values.iter_mut() {
+                let sign = val.signum();
+                *val = sign * val.abs().sqrt();
             }
         }
 
@@ -97,83 +97,58 @@ impl VerticalAirCubeMap {
     ///
     /// Returns a value in [-1, 1]: negative = rising air, positive = sinking air.
     pub fn sample(&self, position: Vec3) -> f32 {
-        let dir = position.normalize();
-        let (face_idx, u, v) = direction_to_cube_uv(dir);
-
-        let fx = ((u + 1.0) * 0.5) * (self.resolution - 1) as f32;
-        let fy = ((v + 1.0) * 0.5) * (self.resolution - 1) as f32;
-
-        let x0 = fx.floor() as usize;
-        let y0 = fy.floor() as usize;
-        let x1 = (x0 + 1).min(self.resolution - 1);
-        let y1 = (y0 + 1).min(self.resolution - 1);
-
-        let tx = fx - x0 as f32;
-        let ty = fy - y0 as f32;
-
-        let face = &self.faces[face_idx];
-        let v00 = face.values[y0][x0];
-        let v10 = face.values[y0][x1];
-        let v01 = face.values[y1][x0];
-        let v11 = face.values[y1][x1];
-
-        let v0 = v00 + (v10 - v00) * tx;
-        let v1 = v01 + (v11 - v01) * tx;
-        v0 + (v1 - v0) * ty
+        crate::cubemap_utils::sample_bilinear(self.resolution, position, |face, x, y| {
+            self.faces[face].values[y][x]
+        })
     }
 }
 
-/// Compute surface divergence at a grid cell using central finite differences.
+/// Surface divergence of the velocity field at a grid cell, from Gauss' theorem over the
+/// cell's spherical quad: the outward flux through its four edges divided by its solid angle.
 ///
-/// Projects wind vectors onto the local tangent basis (du, dv) of the cube face,
-/// then computes d(wind_u)/du + d(wind_v)/dv.
-fn compute_divergence(wind: &WindCubeMap, face_idx: usize, x: usize, y: usize) -> f32 {
-    let res = wind.resolution;
-
-    // Get the local tangent basis at this cell
-    let u = (x as f32 / (res - 1) as f32) * 2.0 - 1.0;
-    let v = (y as f32 / (res - 1) as f32) * 2.0 - 1.0;
-
-    // Compute local tangent vectors by finite differencing the cube face mapping
-    let du = 2.0 / (res - 1) as f32;
-
-    let dir_u_plus = cube_face_point(face_idx, u + du, v).normalize();
-    let dir_u_minus = cube_face_point(face_idx, u - du, v).normalize();
-    let dir_v_plus = cube_face_point(face_idx, u, v + du).normalize();
-    let dir_v_minus = cube_face_point(face_idx, u, v - du).normalize();
-
-    // Tangent vectors on the sphere surface
-    let tangent_u = (dir_u_plus - dir_u_minus).normalize();
-    let tangent_v = (dir_v_plus - dir_v_minus).normalize();
-
-    // Use clamped indices for boundary cells
-    let x_plus = (x + 1).min(res - 1);
-    let x_minus = x.saturating_sub(1);
-    let y_plus = (y + 1).min(res - 1);
-    let y_minus = y.saturating_sub(1);
-
-    // Wind vectors at neighboring cells
-    let wind_xp = wind.faces[face_idx].velocities[y][x_plus];
-    let wind_xm = wind.faces[face_idx].velocities[y][x_minus];
-    let wind_yp = wind.faces[face_idx].velocities[y_plus][x];
-    let wind_ym = wind.faces[face_idx].velocities[y_minus][x];
-
-    // Project onto tangent directions
-    let wu_xp = wind_xp.dot(tangent_u);
-    let wu_xm = wind_xm.dot(tangent_u);
-    let wv_yp = wind_yp.dot(tangent_v);
-    let wv_ym = wind_ym.dot(tangent_v);
-
-    // Effective grid spacing (number of cells between samples)
-    let dx = (x_plus - x_minus) as f32;
-    let dy = (y_plus - y_minus) as f32;
-
-    // Central differences
-    let du_dx = if dx > 0.0 { (wu_xp - wu_xm) / dx } else { 0.0 };
-    let dv_dy = if dy > 0.0 { (wv_yp - wv_ym) / dy } else { 0.0 };
-
-    // Surface divergence: positive = diverging = sinking, negative = converging = rising
-    du_dx + dv_dy
+/// The quad spans half a cell around the grid point, its edges are great circle arcs.
+/// Velocities are sampled by direction at the arc midpoints, so cells on face edges see
+/// the neighbouring face and the cube-to-sphere cell size variation is in the edge lengths
+/// and the area. Weighting each midpoint by the chord length makes the flux exact for
+/// linear fields, solid body rotation comes out divergence free everywhere.
+fn compute_divergence(
+    velocity: impl Fn(Vec3) -> Vec3,
+    face_idx: usize,
+    x: usize,
+    y: usize,
+    res: usize,
+) -> f32 {
+    let step = 2.0 / (res - 1) as f32;
+    let u = x as f32 * step - 1.0;
+    let v = y as f32 * step - 1.0;
+    let half = step * 0.5;
+
+    let center = cube_face_point(face_idx, u, v).normalize();
+    let corner = |du: f32, dv: f32| cube_face_point(face_idx, u + du, v + dv).normalize();
+    let corners = [
+        corner(-half, -half),
+        corner(half, -half),
+        corner(half, half),
+        corner(-half, half),
+    ];
+
+    let mut flux = 0.0;
+    for i in 0..4 {
+        let (a, b) = (corners[i], corners[(i + 1) % 4]);
+        let midpoint = (a + b).normalize();
+        // Normal of the great circle through the edge, turned away from the cell
+        let mut normal = a.cross(b).normalize();
+        if normal.dot(center) > 0.0 {
+            normal = -normal;
+        }
+        flux += velocity(midpoint).dot(normal) * a.distance(b);
+    }
+
+    let [a, b, c, d] = corners;
+    let area = triangle_solid_angle(a, b, c) + triangle_solid_angle(a, c, d);
+
+    // Positive = diverging = sinking, negative = converging = rising
+    flux / area
 }
 
 /// Convert vertical air movement value to RGB color.
@@ -181,7 +156,12 @@ fn compute_divergence(wind: &WindCubeMap, face_idx: usize, x: usize, y: usize) -
 /// * Negative (rising air / convergence): blue
 /// * Zero (neutral): white
 /// * Positive (sinking air / divergence): red
-pub fn divergence_to_color(value: f32) -> Vec3 {
+///
+/// Other palettes use their diverging scale from -1 to 1.
+pub fn divergence_to_color(value: f32, palette: Palette) -> Vec3 {
+    if let Some(color) = palette.diverging(value) {
+        return color;
+    }
     let clamped = value.clamp(-1.0, 1.0);
     if clamped < 0.0 {
         // Rising: white → blue
@@ -193,3 +173,47 @@ pub fn divergence_to_color(value: f32) -> Vec3 {
         Vec3::new(1.0, 1.0 - t, 1.0 - t)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubemap_utils::all_cells;
+
+    const RES: usize = 17;
+
+    fn cell_dir(face: usize, x: usize, y: usize) -> Vec3 {
+        let step = 2.0 / (RES - 1) as f32;
+        cube_face_point(face, x as f32 * step - 1.0, y as f32 * step - 1.0).normalize()
+    }
+
+    #[test]
+    fn test_solid_body_rotation_is_divergence_free_everywhere() {
+        let axis = Vec3::new(0.3, 1.0, 0.2).normalize();
+
+        for (face, x, y) in all_cells(RES) {
+            let divergence = compute_divergence(|dir| axis.cross(dir), face, x, y, RES);
+            assert!(divergence.abs() < 1e-3, "{face} {x} {y}: {divergence}");
+        }
+    }
+
+    #[test]
+    fn test_poleward_flow_diverges_at_the_equator_and_converges_at_the_poles() {
+        // Away from the equator towards both poles, the exact divergence is 1 - 3·sin²(latitude)
+        let poleward = |dir: Vec3| (Vec3::Y - dir * dir.y) * dir.y;
+
+        for (face, x, y) in all_cells(RES) {
+            let dir = cell_dir(face, x, y);
+            let divergence = compute_divergence(poleward, face, x, y, RES);
+            let expected = 1.0 - 3.0 * dir.y * dir.y;
+            assert!(
+                (divergence - expected).abs() < 0.02,
+                "{face} {x} {y}: {divergence} vs {expected}"
+            );
+            if dir.y.abs() < 0.3 {
+                assert!(divergence > 0.0);
+            } else if dir.y.abs() > 0.8 {
+                assert!(divergence < 0.0);
+            }
+        }
+    }
+}
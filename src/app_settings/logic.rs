@@ -0,0 +1,232 @@
+use crate::audio::AudioSettings;
+use crate::planet::events::ViewKind;
+use crate::planet::logic::{MAX_PLANET_RADIUS, MAX_PLATES, MIN_PLANET_RADIUS, MIN_PLATES};
+use crate::planet::resources::{MAX_MOONS, PlanetSettingsSnapshot, TerrainSettings};
+use serde::{Deserialize, Serialize};
+
+/// Bumped when the file layout changes in a way `serde(default)` can't paper over. Files with
+/// another version are ignored.
+pub const APP_SETTINGS_VERSION: u32 = 2;
+
+pub const DEFAULT_WINDOW_WIDTH: u32 = 1500;
+pub const DEFAULT_WINDOW_HEIGHT: u32 = 900;
+
+/// Everything restored on the next launch
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub version: u32,
+    pub window: WindowSettings,
+    pub planet: PlanetSettingsSnapshot,
+    pub audio: AudioSettings,
+    pub active_view: ViewKind,
+    /// Camera distance from the planet center, None to frame the planet as after generating
+    pub camera_zoom: Option<f32>,
+    /// Open the help overlay when the game starts
+    pub show_help_on_startup: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            version: APP_SETTINGS_VERSION,
+            window: WindowSettings::default(),
+            planet: PlanetSettingsSnapshot::default(),
+            audio: AudioSettings::default(),
+            active_view: ViewKind::default(),
+            camera_zoom: None,
+            show_help_on_startup: true,
+        }
+    }
+}
+
+/// Size in physical pixels and position of the primary window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Top left corner on the desktop, None lets the OS place the window
+    pub position: Option<(i32, i32)>,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WINDOW_WIDTH,
+            height: DEFAULT_WINDOW_HEIGHT,
+            position: None,
+        }
+    }
+}
+
+/// Why a settings file wasn't used
+#[derive(Debug)]
+pub enum LoadError {
+    Parse(ron::error::SpannedError),
+    Version(u32),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Parse(error) => write!(f, "{error}"),
+            LoadError::Version(version) => {
+                write!(f, "written by version {version}, expected {APP_SETTINGS_VERSION}")
+            }
+        }
+    }
+}
+
+pub fn to_ron(settings: &AppSettings) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::default())
+}
+
+pub fn from_ron(text: &str) -> Result<AppSettings, LoadError> {
+    let settings: AppSettings = ron::from_str(text).map_err(LoadError::Parse)?;
+    if settings.version != APP_SETTINGS_VERSION {
+        return Err(LoadError::Version(settings.version));
+    }
+    Ok(sanitized(settings))
+}
+
+/// Keep a window that was saved minimized or on a disconnected monitor usable, and the terrain,
+/// moons and volumes within what the settings panel offers
+fn sanitized(mut settings: AppSettings) -> AppSettings {
+    let window = &mut settings.window;
+    if window.width < 800 || window.height < 600 {
+        *window = WindowSettings::default();
+    }
+    if window.position.is_some_and(|(x, y)| x.abs() > 100_000 || y.abs() > 100_000) {
+        window.position = None;
+    }
+    settings.camera_zoom = settings.camera_zoom.filter(|zoom| zoom.is_finite() && *zoom > 0.0);
+    sanitize_terrain(&mut settings.planet.terrain);
+    settings.planet.display.moons.truncate(MAX_MOONS);
+    let audio = &mut settings.audio;
+    for volume in [&mut audio.master_volume, &mut audio.music_volume, &mut audio.ambient_volume] {
+        *volume = if volume.is_finite() { volume.clamp(0.0, 1.0) } else { 1.0 };
+    }
+    settings
+}
+
+/// The terrain goes straight into the generator on startup, which needs at least a few plates
+/// and a planet of a size the grid budget holds
+fn sanitize_terrain(terrain: &mut TerrainSettings) {
+    let defaults = TerrainSettings::default();
+    let floats = [
+        (&mut terrain.radius, defaults.radius),
+        (&mut terrain.oblateness, defaults.oblateness),
+        (&mut terrain.flow_warp_freq, defaults.flow_warp_freq),
+        (&mut terrain.flow_warp_step_angle, defaults.flow_warp_step_angle),
+        (&mut terrain.continent_frequency, defaults.continent_frequency),
+        (&mut terrain.continent_amplitude, defaults.continent_amplitude),
+        (&mut terrain.distortion_frequency, defaults.distortion_frequency),
+        (&mut terrain.distortion_amplitude, defaults.distortion_amplitude),
+        (&mut terrain.continent_threshold, defaults.continent_threshold),
+        (&mut terrain.detail_frequency, defaults.detail_frequency),
+        (&mut terrain.detail_amplitude, defaults.detail_amplitude),
+        (&mut terrain.ocean_depth_amplitude, defaults.ocean_depth_amplitude),
+        (&mut terrain.snow_threshold, defaults.snow_threshold),
+        (&mut terrain.mountain_height, defaults.mountain_height),
+        (&mut terrain.mountain_width, defaults.mountain_width),
+    ];
+    for (value, default) in floats {
+        if !value.is_finite() {
+            *value = default;
+        }
+    }
+    terrain.radius = terrain.radius.clamp(MIN_PLANET_RADIUS, MAX_PLANET_RADIUS);
+    terrain.num_plates = terrain.num_plates.clamp(MIN_PLATES, MAX_PLATES);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_survive_a_round_trip() {
+        let mut settings = AppSettings::default();
+        settings.planet.terrain.user_seed = 12_345_678;
+        settings.planet.terrain.radius = 42.0;
+        settings.active_view = ViewKind::Temperature;
+        settings.camera_zoom = Some(80.0);
+        settings.window.position = Some((-1200, 40));
+        settings.show_help_on_startup = false;
+        settings.audio.music_volume = 0.25;
+        settings.audio.muted = true;
+
+        let restored = from_ron(&to_ron(&settings).unwrap()).unwrap();
+
+        assert_eq!(restored.planet.terrain.user_seed, 12_345_678);
+        assert_eq!(restored.planet.terrain.radius, 42.0);
+        assert_eq!(restored.active_view, ViewKind::Temperature);
+        assert_eq!(restored.camera_zoom, Some(80.0));
+        assert_eq!(restored.window, settings.window);
+        assert!(!restored.show_help_on_startup);
+        assert_eq!(restored.audio, settings.audio);
+    }
+
+    #[test]
+    fn test_another_version_is_rejected() {
+        let settings = AppSettings {
+            version: APP_SETTINGS_VERSION + 1,
+            ..AppSettings::default()
+        };
+
+        assert!(matches!(from_ron(&to_ron(&settings).unwrap()), Err(LoadError::Version(_))));
+    }
+
+    #[test]
+    fn test_corrupt_file_is_rejected() {
+        assert!(matches!(from_ron("(version: 2, window: (width: "), Err(LoadError::Parse(_))));
+        assert!(matches!(from_ron("not settings at all"), Err(LoadError::Parse(_))));
+    }
+
+    #[test]
+    fn test_missing_fields_get_their_defaults() {
+        let restored = from_ron("(version: 2, planet: (terrain: (num_plates: 3)), active_view: Wind)").unwrap();
+
+        assert_eq!(restored.planet.terrain.num_plates, 3);
+        assert_eq!(restored.planet.terrain.radius, PlanetSettingsSnapshot::default().terrain.radius);
+        assert!(restored.planet.climate == PlanetSettingsSnapshot::default().climate);
+        assert_eq!(restored.active_view, ViewKind::Wind);
+        assert_eq!(restored.window, WindowSettings::default());
+        assert!(restored.show_help_on_startup);
+        assert_eq!(restored.audio, AudioSettings::default());
+    }
+
+    #[test]
+    fn test_unusable_window_falls_back_to_the_default() {
+        let text = "(version: 2, window: (width: 0, height: 0, position: Some((-32000, -32000))))";
+
+        assert_eq!(from_ron(text).unwrap().window, WindowSettings::default());
+    }
+
+    #[test]
+    fn test_unusable_terrain_is_kept_within_the_sliders() {
+        let text = "(version: 2, planet: (terrain: (num_plates: 0, radius: NaN, mountain_height: inf)))";
+        let terrain = from_ron(text).unwrap().planet.terrain;
+        let defaults = TerrainSettings::default();
+
+        assert_eq!(terrain.num_plates, MIN_PLATES);
+        assert_eq!(terrain.radius, defaults.radius);
+        assert_eq!(terrain.mountain_height, defaults.mountain_height);
+
+        let text = "(version: 2, planet: (terrain: (num_plates: 500, radius: 1000000.0)))";
+        let terrain = from_ron(text).unwrap().planet.terrain;
+
+        assert_eq!(terrain.num_plates, MAX_PLATES);
+        assert_eq!(terrain.radius, MAX_PLANET_RADIUS);
+    }
+
+    #[test]
+    fn test_volumes_are_kept_within_the_sliders() {
+        let text = "(version: 2, audio: (master_volume: 3.0, music_volume: -1.0, ambient_volume: 0.4))";
+        let audio = from_ron(text).unwrap().audio;
+
+        assert_eq!(audio.master_volume, 1.0);
+        assert_eq!(audio.music_volume, 0.0);
+        assert_eq!(audio.ambient_volume, 0.4);
+    }
+}
@@ -0,0 +1,86 @@
+pub mod logic;
+pub mod systems;
+
+use crate::audio::AudioSettings;
+use crate::core::state::GameState;
+use crate::planet::events::ViewKind;
+use crate::planet::help::HelpOverlay;
+use crate::planet::resources::{ClimateSettings, DisplaySettings, TerrainSettings, WindSettings};
+use crate::planet::systems::spawn_planet_on_event;
+use bevy::prelude::*;
+
+pub use logic::{AppSettings, WindowSettings};
+
+/// Name of the settings file in the platform config directory
+pub const APP_SETTINGS_FILE: &str = "app_settings.ron";
+/// How often the settings are written while the game runs, besides on exit
+pub const SAVE_INTERVAL_SECS: f32 = 30.0;
+
+/// Forget the saved settings and go back to the built-in defaults
+#[derive(Message)]
+pub struct ResetAppSettingsEvent;
+
+/// View and zoom from the settings file, applied to the first planet once it's spawned
+#[derive(Resource, Default)]
+pub struct PendingRestore {
+    pub view: Option<ViewKind>,
+    pub camera_zoom: Option<f32>,
+}
+
+/// What the saves need besides the resources they read
+#[derive(Resource)]
+pub struct AppSettingsState {
+    /// Last known size and position of the window, closing it despawns it before the save on exit
+    pub window: WindowSettings,
+    /// Contents of the last write, unchanged settings aren't written again
+    pub last_saved: Option<String>,
+    pub timer: Timer,
+}
+
+/// Restores the window, the planet settings, the volumes, the active view, the camera zoom and whether the
+/// help opens by itself of the last run and saves them again on exit and every `SAVE_INTERVAL_SECS`.
+///
+/// The window is created before any plugin runs, so `main` reads the file with
+/// [`load_app_settings`] and hands the result to this plugin. Not part of
+/// `PlanetGenerationPlugin`, headless runs shouldn't touch the file.
+pub struct AppSettingsPlugin {
+    pub restored: AppSettings,
+}
+
+impl Plugin for AppSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        // Replaces the defaults before the first planet is generated from them
+        let restored = self.restored.clone();
+        app.insert_resource::<TerrainSettings>(restored.planet.terrain)
+            .insert_resource::<ClimateSettings>(restored.planet.climate)
+            .insert_resource::<WindSettings>(restored.planet.wind)
+            .insert_resource::<DisplaySettings>(restored.planet.display)
+            .insert_resource::<AudioSettings>(restored.audio)
+            .insert_resource(PendingRestore {
+                view: Some(restored.active_view),
+                camera_zoom: restored.camera_zoom,
+            })
+            .insert_resource(HelpOverlay::new(restored.show_help_on_startup))
+            .insert_resource(AppSettingsState {
+                window: restored.window,
+                last_saved: None,
+                timer: Timer::from_seconds(SAVE_INTERVAL_SECS, TimerMode::Repeating),
+            })
+            .add_message::<ResetAppSettingsEvent>()
+            .add_systems(
+                Update,
+                (
+                    systems::restore_view_on_first_planet.after(spawn_planet_on_event),
+                    systems::save_periodically,
+                    systems::handle_reset_app_settings,
+                )
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            )
+            .add_systems(Last, systems::save_on_exit);
+    }
+}
+
+/// The settings of the last run, or the defaults if there are none or they can't be used
+pub fn load_app_settings() -> AppSettings {
+    systems::read_app_settings_file()
+}
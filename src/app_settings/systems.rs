@@ -0,0 +1,202 @@
+use super::logic::{self, AppSettings, WindowSettings};
+use super::{APP_SETTINGS_FILE, AppSettingsState, PendingRestore, ResetAppSettingsEvent};
+use crate::audio::AudioSettings;
+use crate::core::files;
+use crate::planet::components::{PlanetControls, PlanetEntity};
+use crate::planet::events::{GeneratePlanetEvent, PlanetSpawnedEvent, SetActiveView, SetCameraPositionEvent, ViewKind};
+use crate::planet::help::HelpOverlay;
+use crate::planet::resources::{AllPlanetSettings, AllPlanetSettingsMut, OverlayState, PlanetSettingsSnapshot};
+use bevy::prelude::*;
+use bevy::tasks::IoTaskPool;
+use bevy::window::{MonitorSelection, PrimaryWindow, WindowPosition};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// `app_settings.ron` in the platform config directory
+fn settings_path() -> Option<PathBuf> {
+    files::config_path(APP_SETTINGS_FILE)
+}
+
+pub fn read_app_settings_file() -> AppSettings {
+    let Some(path) = settings_path() else {
+        warn!("No config directory on this platform, starting with the default settings");
+        return AppSettings::default();
+    };
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == ErrorKind::NotFound => return AppSettings::default(),
+        Err(error) => {
+            warn!("Could not read {}: {error}", path.display());
+            return AppSettings::default();
+        }
+    };
+
+    // The next save overwrites the file, nothing in it is worth keeping
+    logic::from_ron(&text).unwrap_or_else(|error| {
+        warn!("Ignoring {}, starting with the default settings: {error}", path.display());
+        AppSettings::default()
+    })
+}
+
+/// The restored view and zoom replace the ones the first planet was spawned with
+pub fn restore_view_on_first_planet(
+    mut events: MessageReader<PlanetSpawnedEvent>,
+    mut pending: ResMut<PendingRestore>,
+    mut planet_query: Query<&mut PlanetControls, With<PlanetEntity>>,
+    mut set_active_view: MessageWriter<SetActiveView>,
+    mut camera_events: MessageWriter<SetCameraPositionEvent>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+
+    if let Some(view) = pending.view.take() {
+        set_active_view.write(SetActiveView(view));
+    }
+    let Some(zoom) = pending.camera_zoom.take() else {
+        return;
+    };
+    if let Ok(mut controls) = planet_query.single_mut() {
+        controls.zoom = zoom.clamp(controls.min_zoom, controls.max_zoom);
+        camera_events.write(SetCameraPositionEvent {
+            position: Vec3::new(0.0, 0.0, controls.zoom),
+        });
+    }
+}
+
+/// Write the settings every `SAVE_INTERVAL_SECS` if anything changed since the last write
+pub fn save_periodically(
+    time: Res<Time>,
+    mut state: ResMut<AppSettingsState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    (settings, audio_settings): (AllPlanetSettings, Res<AudioSettings>),
+    (overlay_state, help): (Option<Res<OverlayState>>, Res<HelpOverlay>),
+    planet_query: Query<&PlanetControls, With<PlanetEntity>>,
+) {
+    // Kept up to date for the save on exit, the window may be gone by then
+    if let Ok(window) = windows.single() {
+        state.window = window_settings(window);
+    }
+
+    if !state.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let current = snapshot(&state, &settings, &audio_settings, overlay_state.as_deref(), &help, planet_query.single().ok());
+    let Some(text) = serialize(&current) else {
+        return;
+    };
+    if state.last_saved.as_ref() == Some(&text) {
+        return;
+    }
+    state.last_saved = Some(text.clone());
+
+    IoTaskPool::get()
+        .spawn(async move { write_app_settings_file(&text) })
+        .detach();
+}
+
+/// Write the settings one last time before the app closes, on this thread so it's done before
+/// the process ends
+pub fn save_on_exit(
+    mut exit_events: MessageReader<AppExit>,
+    state: Res<AppSettingsState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    (settings, audio_settings): (AllPlanetSettings, Res<AudioSettings>),
+    (overlay_state, help): (Option<Res<OverlayState>>, Res<HelpOverlay>),
+    planet_query: Query<&PlanetControls, With<PlanetEntity>>,
+) {
+    if exit_events.read().count() == 0 {
+        return;
+    }
+
+    let mut current = snapshot(&state, &settings, &audio_settings, overlay_state.as_deref(), &help, planet_query.single().ok());
+    if let Ok(window) = windows.single() {
+        current.window = window_settings(window);
+    }
+    if let Some(text) = serialize(&current) {
+        write_app_settings_file(&text);
+    }
+}
+
+/// Delete the settings file and put the built-in defaults back in place, the planet is
+/// generated again from them
+pub fn handle_reset_app_settings(
+    mut events: MessageReader<ResetAppSettingsEvent>,
+    mut state: ResMut<AppSettingsState>,
+    mut settings: AllPlanetSettingsMut,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut help: ResMut<HelpOverlay>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut set_active_view: MessageWriter<SetActiveView>,
+    mut planet_generation_events: MessageWriter<GeneratePlanetEvent>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+
+    if let Some(path) = settings_path() {
+        match std::fs::remove_file(&path) {
+            Ok(()) => info!("Deleted {}", path.display()),
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => warn!("Could not delete {}: {error}", path.display()),
+        }
+    }
+
+    settings.apply(PlanetSettingsSnapshot::default());
+    *audio_settings = AudioSettings::default();
+    help.show_on_startup = true;
+    let window_defaults = WindowSettings::default();
+    if let Ok(mut window) = windows.single_mut() {
+        window
+            .resolution
+            .set_physical_resolution(window_defaults.width, window_defaults.height);
+        window.position = WindowPosition::Centered(MonitorSelection::Current);
+    }
+    state.window = window_defaults;
+    state.last_saved = None;
+
+    set_active_view.write(SetActiveView(ViewKind::default()));
+    planet_generation_events.write(GeneratePlanetEvent);
+}
+
+fn snapshot(
+    state: &AppSettingsState,
+    settings: &AllPlanetSettings,
+    audio_settings: &AudioSettings,
+    overlay_state: Option<&OverlayState>,
+    help: &HelpOverlay,
+    controls: Option<&PlanetControls>,
+) -> AppSettings {
+    AppSettings {
+        version: logic::APP_SETTINGS_VERSION,
+        window: state.window,
+        planet: settings.snapshot(),
+        audio: *audio_settings,
+        active_view: overlay_state.map_or(ViewKind::default(), |overlay_state| overlay_state.active),
+        camera_zoom: controls.map(|controls| controls.zoom),
+        show_help_on_startup: help.show_on_startup,
+    }
+}
+
+fn window_settings(window: &Window) -> WindowSettings {
+    WindowSettings {
+        width: window.resolution.physical_width(),
+        height: window.resolution.physical_height(),
+        position: match window.position {
+            WindowPosition::At(position) => Some((position.x, position.y)),
+            _ => None,
+        },
+    }
+}
+
+fn serialize(settings: &AppSettings) -> Option<String> {
+    logic::to_ron(settings)
+        .inspect_err(|error| warn!("Could not serialize the app settings: {error}"))
+        .ok()
+}
+
+fn write_app_settings_file(text: &str) {
+    if let Some(path) = settings_path() {
+        files::write_file(&path, text);
+    }
+}
@@ -0,0 +1,318 @@
+use crate::core::state::GameState;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+/// Time it takes an ambient layer to fade fully in or out
+pub const FADE_DURATION_SECS: f32 = 1.5;
+
+/// Anything below this is treated as silence by the audio backend
+const SILENCE_DB: f32 = -60.0;
+
+/// Wind loudness grows with zonal speed but never drops to nothing while the tab is open
+const MIN_WIND_LEVEL: f32 = 0.2;
+const MAX_ZONAL_SPEED: f32 = 10.0;
+
+const OCEAN_LEVEL: f32 = 0.6;
+const MUSIC_LEVEL: f32 = 0.8;
+
+/// Volumes of the settings panel's Volume section, saved with the app settings
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    /// Wind and ocean loops and the generation rumble
+    pub ambient_volume: f32,
+    pub muted: bool,
+    pub music_muted: bool,
+    pub ambient_muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 0.8,
+            music_volume: 0.5,
+            ambient_volume: 0.5,
+            muted: false,
+            music_muted: false,
+            ambient_muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Amplitude the music is scaled by, 0 when muted
+    pub fn music_gain(&self) -> f32 {
+        self.channel_gain(self.music_volume, self.music_muted)
+    }
+
+    /// Amplitude the ambient layers and effects are scaled by, 0 when muted
+    pub fn ambient_gain(&self) -> f32 {
+        self.channel_gain(self.ambient_volume, self.ambient_muted)
+    }
+
+    fn channel_gain(&self, volume: f32, muted: bool) -> f32 {
+        if self.muted || muted {
+            return 0.0;
+        }
+        (self.master_volume * volume).clamp(0.0, 1.0)
+    }
+}
+
+/// Which layers should be audible, the fades take them there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerTargets {
+    pub music: bool,
+    pub wind: bool,
+    pub ocean: bool,
+}
+
+/// The menu music plays on the planet generation screen, the ambient layers only once there's a
+/// planet to hear
+pub fn layer_targets(state: GameState, planet_exists: bool, wind_tab_active: bool, ocean_visible: bool) -> LayerTargets {
+    let in_menu = state == GameState::PlanetGeneration;
+    let ambient = in_menu && planet_exists;
+    LayerTargets {
+        music: in_menu,
+        wind: ambient && wind_tab_active,
+        ocean: ambient && ocean_visible,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FadeState {
+    #[default]
+    Stopped,
+    FadingIn,
+    Playing,
+    FadingOut,
+}
+
+/// What the audio adapter has to do with the underlying sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FadeEffect {
+    None,
+    Start,
+    Stop,
+}
+
+/// Fade-in/fade-out state machine for a looping ambient layer.
+/// The sound keeps playing while fading out and is only stopped once silent, so there is no popping.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AmbientFade {
+    pub state: FadeState,
+    /// Fade multiplier in [0, 1]
+    pub gain: f32,
+}
+
+impl AmbientFade {
+    /// Request the layer to become audible or silent
+    pub fn set_active(&mut self, active: bool) -> FadeEffect {
+        match (self.state, active) {
+            (FadeState::Stopped, true) => {
+                self.state = FadeState::FadingIn;
+                FadeEffect::Start
+            }
+            (FadeState::FadingOut, true) => {
+                self.state = FadeState::FadingIn;
+                FadeEffect::None
+            }
+            (FadeState::FadingIn | FadeState::Playing, false) => {
+                self.state = FadeState::FadingOut;
+                FadeEffect::None
+            }
+            _ => FadeEffect::None,
+        }
+    }
+
+    /// Advance the fade by `dt` seconds
+    pub fn update(&mut self, dt: f32, fade_duration: f32) -> FadeEffect {
+        let step = if fade_duration > 0.0 {
+            dt / fade_duration
+        } else {
+            1.0
+        };
+
+        match self.state {
+            FadeState::FadingIn => {
+                self.gain = (self.gain + step).min(1.0);
+                if self.gain >= 1.0 {
+                    self.state = FadeState::Playing;
+                }
+                FadeEffect::None
+            }
+            FadeState::FadingOut => {
+                self.gain = (self.gain - step).max(0.0);
+                if self.gain <= 0.0 {
+                    self.state = FadeState::Stopped;
+                    return FadeEffect::Stop;
+                }
+                FadeEffect::None
+            }
+            FadeState::Stopped | FadeState::Playing => FadeEffect::None,
+        }
+    }
+
+    pub fn is_audible(&self) -> bool {
+        self.state != FadeState::Stopped
+    }
+}
+
+/// Loudness of the wind layer for a given zonal wind speed
+pub fn wind_level(zonal_speed: f32) -> f32 {
+    let t = (zonal_speed / MAX_ZONAL_SPEED).clamp(0.0, 1.0);
+    MIN_WIND_LEVEL + (1.0 - MIN_WIND_LEVEL) * t
+}
+
+pub fn ocean_level() -> f32 {
+    OCEAN_LEVEL
+}
+
+pub fn music_level() -> f32 {
+    MUSIC_LEVEL
+}
+
+/// Final layer volume in decibels, `channel_gain` is the music or ambient gain of the settings
+pub fn layer_volume_db(fade_gain: f32, level: f32, channel_gain: f32) -> f32 {
+    amplitude_to_decibels(fade_gain * level * channel_gain)
+}
+
+pub fn amplitude_to_decibels(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return SILENCE_DB;
+    }
+    (20.0 * amplitude.log10()).max(SILENCE_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activating_a_stopped_layer_starts_the_sound_once() {
+        let mut fade = AmbientFade::default();
+
+        assert_eq!(fade.set_active(true), FadeEffect::Start);
+        assert_eq!(fade.set_active(true), FadeEffect::None);
+        assert_eq!(fade.state, FadeState::FadingIn);
+    }
+
+    #[test]
+    fn test_fade_in_reaches_full_gain_after_fade_duration() {
+        let mut fade = AmbientFade::default();
+        fade.set_active(true);
+
+        fade.update(0.5, 1.0);
+        assert!((fade.gain - 0.5).abs() < 1e-6);
+        assert_eq!(fade.state, FadeState::FadingIn);
+
+        fade.update(0.6, 1.0);
+        assert_eq!(fade.gain, 1.0);
+        assert_eq!(fade.state, FadeState::Playing);
+    }
+
+    #[test]
+    fn test_sound_is_stopped_only_after_fading_out_completely() {
+        let mut fade = AmbientFade::default();
+        fade.set_active(true);
+        fade.update(1.0, 1.0);
+
+        fade.set_active(false);
+        assert_eq!(fade.update(0.5, 1.0), FadeEffect::None);
+        assert!(fade.is_audible());
+
+        assert_eq!(fade.update(0.5, 1.0), FadeEffect::Stop);
+        assert_eq!(fade.state, FadeState::Stopped);
+        assert_eq!(fade.gain, 0.0);
+    }
+
+    #[test]
+    fn test_reactivating_during_fade_out_resumes_without_restarting() {
+        let mut fade = AmbientFade::default();
+        fade.set_active(true);
+        fade.update(1.0, 1.0);
+        fade.set_active(false);
+        fade.update(0.3, 1.0);
+
+        assert_eq!(fade.set_active(true), FadeEffect::None);
+        assert_eq!(fade.state, FadeState::FadingIn);
+        assert!((fade.gain - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_wind_level_scales_with_zonal_speed() {
+        assert_eq!(wind_level(0.0), MIN_WIND_LEVEL);
+        assert_eq!(wind_level(MAX_ZONAL_SPEED), 1.0);
+        assert_eq!(wind_level(MAX_ZONAL_SPEED * 2.0), 1.0);
+        assert!(wind_level(5.0) > wind_level(2.0));
+    }
+
+    #[test]
+    fn test_zero_volume_is_silence() {
+        assert_eq!(layer_volume_db(0.0, 1.0, 1.0), SILENCE_DB);
+        assert_eq!(layer_volume_db(1.0, 1.0, 0.0), SILENCE_DB);
+        assert!(layer_volume_db(1.0, 1.0, 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_channel_gain_combines_master_and_channel_volume() {
+        let settings = AudioSettings {
+            master_volume: 0.5,
+            music_volume: 0.4,
+            ambient_volume: 1.0,
+            ..AudioSettings::default()
+        };
+
+        assert!((settings.music_gain() - 0.2).abs() < 1e-6);
+        assert!((settings.ambient_gain() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mute_flags_silence_their_channels() {
+        let music_muted = AudioSettings {
+            music_muted: true,
+            ..AudioSettings::default()
+        };
+        assert_eq!(music_muted.music_gain(), 0.0);
+        assert!(music_muted.ambient_gain() > 0.0);
+
+        let muted = AudioSettings {
+            muted: true,
+            ..AudioSettings::default()
+        };
+        assert_eq!(muted.music_gain(), 0.0);
+        assert_eq!(muted.ambient_gain(), 0.0);
+    }
+
+    #[test]
+    fn test_ambient_layers_need_a_planet() {
+        let targets = layer_targets(GameState::PlanetGeneration, false, true, true);
+
+        assert!(targets.music);
+        assert!(!targets.wind);
+        assert!(!targets.ocean);
+    }
+
+    #[test]
+    fn test_ambient_layers_follow_the_tab_and_the_ocean() {
+        let targets = layer_targets(GameState::PlanetGeneration, true, false, true);
+
+        assert!(!targets.wind);
+        assert!(targets.ocean);
+    }
+
+    #[test]
+    fn test_everything_fades_out_when_leaving_the_menu() {
+        let targets = layer_targets(GameState::InGame, true, true, true);
+
+        assert_eq!(
+            targets,
+            LayerTargets {
+                music: false,
+                wind: false,
+                ocean: false,
+            }
+        );
+    }
+}
@@ -0,0 +1,71 @@
+mod logic;
+mod systems;
+
+use crate::core::state::GameState;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use logic::AmbientFade;
+
+pub use logic::AudioSettings;
+
+pub struct InternalAudioPlugin;
+
+// This plugin is responsible to control the game audio
+impl Plugin for InternalAudioPlugin {
+    fn build(&self, app: &mut App) {
+        // AppSettingsPlugin replaces the defaults with the saved volumes
+        app.add_plugins(AudioPlugin)
+            .add_audio_channel::<MusicChannel>()
+            .add_audio_channel::<WindChannel>()
+            .add_audio_channel::<OceanChannel>()
+            .add_audio_channel::<EffectsChannel>()
+            .init_resource::<AudioSettings>()
+            .init_resource::<AmbientAudioState>()
+            .add_systems(Startup, systems::load_ambient_audio)
+            .add_systems(
+                Update,
+                (
+                    (systems::track_wind_tab, systems::play_rumble_on_planet_spawned)
+                        .run_if(in_state(GameState::PlanetGeneration)),
+                    // Outside the planet generation screen too, so the layers fade out after leaving it
+                    systems::sync_layer_targets,
+                    systems::update_audio_layers,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Looping menu music, audible on the planet generation screen
+#[derive(Resource)]
+pub struct MusicChannel;
+
+/// Looping wind layer, audible while the Wind tab is active
+#[derive(Resource)]
+pub struct WindChannel;
+
+/// Looping waves layer, audible while the ocean is visible
+#[derive(Resource)]
+pub struct OceanChannel;
+
+/// One-shot sound effects
+#[derive(Resource)]
+pub struct EffectsChannel;
+
+#[derive(Resource)]
+pub struct AmbientAudioAssets {
+    pub menu_music: Handle<AudioSource>,
+    pub wind_loop: Handle<AudioSource>,
+    pub ocean_loop: Handle<AudioSource>,
+    pub rumble: Handle<AudioSource>,
+}
+
+/// Fade state of each looping layer
+#[derive(Resource, Default)]
+pub struct AmbientAudioState {
+    /// Last view picked in the tab bar, the wind layer follows it
+    pub wind_tab_active: bool,
+    pub music: AmbientFade,
+    pub wind: AmbientFade,
+    pub ocean: AmbientFade,
+}
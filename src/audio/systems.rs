@@ -0,0 +1,145 @@
+use super::logic::{self, AmbientFade, AudioSettings, FadeEffect, FADE_DURATION_SECS};
+use super::{AmbientAudioAssets, AmbientAudioState, EffectsChannel, MusicChannel, OceanChannel, WindChannel};
+use crate::core::state::GameState;
+use crate::planet::components::{OceanEntity, PlanetEntity};
+use crate::planet::events::{PlanetSpawnedEvent, SetActiveView, ViewKind};
+use crate::planet::resources::{PendingPlanetGeneration, WindSettings};
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+pub fn load_ambient_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(AmbientAudioAssets {
+        menu_music: asset_server.load("audio/menu_music.ogg"),
+        wind_loop: asset_server.load("audio/wind_loop.ogg"),
+        ocean_loop: asset_server.load("audio/ocean_loop.ogg"),
+        rumble: asset_server.load("audio/rumble.ogg"),
+    });
+}
+
+pub fn track_wind_tab(mut events: MessageReader<SetActiveView>, mut state: ResMut<AmbientAudioState>) {
+    if let Some(&SetActiveView(view)) = events.read().last() {
+        state.wind_tab_active = view == ViewKind::Wind;
+    }
+}
+
+/// Start fading each layer in or out depending on the game state, the planet, the active tab and
+/// the ocean mesh visibility
+pub fn sync_layer_targets(
+    game_state: Res<State<GameState>>,
+    planet_query: Query<(), With<PlanetEntity>>,
+    ocean_query: Query<&Visibility, With<OceanEntity>>,
+    mut state: ResMut<AmbientAudioState>,
+    assets: Res<AmbientAudioAssets>,
+    channels: (
+        Res<AudioChannel<MusicChannel>>,
+        Res<AudioChannel<WindChannel>>,
+        Res<AudioChannel<OceanChannel>>,
+    ),
+) {
+    let (music_channel, wind_channel, ocean_channel) = channels;
+    let ocean_visible = ocean_query
+        .iter()
+        .any(|visibility| *visibility != Visibility::Hidden);
+    let targets = logic::layer_targets(
+        *game_state.get(),
+        !planet_query.is_empty(),
+        state.wind_tab_active,
+        ocean_visible,
+    );
+
+    let effect = state.music.set_active(targets.music);
+    apply_fade_effect(effect, &music_channel, &assets.menu_music);
+    let effect = state.wind.set_active(targets.wind);
+    apply_fade_effect(effect, &wind_channel, &assets.wind_loop);
+    let effect = state.ocean.set_active(targets.ocean);
+    apply_fade_effect(effect, &ocean_channel, &assets.ocean_loop);
+}
+
+/// Play a rumble once the planet is fully generated.
+/// A low-res preview also sends PlanetSpawnedEvent, but the full-res planet is still pending then.
+pub fn play_rumble_on_planet_spawned(
+    mut events: MessageReader<PlanetSpawnedEvent>,
+    pending_generation: Res<PendingPlanetGeneration>,
+    audio_settings: Res<AudioSettings>,
+    assets: Res<AmbientAudioAssets>,
+    channel: Res<AudioChannel<EffectsChannel>>,
+) {
+    if events.read().count() == 0 || pending_generation.task.is_some() {
+        return;
+    }
+
+    channel
+        .play(assets.rumble.clone())
+        .with_volume(logic::amplitude_to_decibels(audio_settings.ambient_gain()));
+}
+
+/// Advance the fades and push the volumes to the channels, which also applies the volume sliders
+/// while a layer plays
+pub fn update_audio_layers(
+    time: Res<Time>,
+    audio_settings: Res<AudioSettings>,
+    settings: Option<Res<WindSettings>>,
+    mut state: ResMut<AmbientAudioState>,
+    channels: (
+        Res<AudioChannel<MusicChannel>>,
+        Res<AudioChannel<WindChannel>>,
+        Res<AudioChannel<OceanChannel>>,
+    ),
+) {
+    let (music_channel, wind_channel, ocean_channel) = channels;
+    let dt = time.delta_secs();
+    let wind_level = settings.map_or(1.0, |settings| logic::wind_level(settings.wind_zonal_speed));
+    let state = &mut *state;
+
+    update_layer(
+        &mut state.music,
+        dt,
+        logic::music_level(),
+        audio_settings.music_gain(),
+        &music_channel,
+    );
+    update_layer(&mut state.wind, dt, wind_level, audio_settings.ambient_gain(), &wind_channel);
+    update_layer(
+        &mut state.ocean,
+        dt,
+        logic::ocean_level(),
+        audio_settings.ambient_gain(),
+        &ocean_channel,
+    );
+}
+
+fn update_layer<T: Resource>(
+    fade: &mut AmbientFade,
+    dt: f32,
+    level: f32,
+    channel_gain: f32,
+    channel: &AudioChannel<T>,
+) {
+    if !fade.is_audible() {
+        return;
+    }
+
+    let effect = fade.update(dt, FADE_DURATION_SECS);
+    channel.set_volume(logic::layer_volume_db(fade.gain, level, channel_gain));
+    if effect == FadeEffect::Stop {
+        channel.stop();
+    }
+}
+
+fn apply_fade_effect<T: Resource>(
+    effect: FadeEffect,
+    channel: &AudioChannel<T>,
+    sound: &Handle<AudioSource>,
+) {
+    match effect {
+        FadeEffect::Start => {
+            // Start silent, update_audio_layers raises the volume as the fade progresses
+            channel.set_volume(logic::amplitude_to_decibels(0.0));
+            channel.play(sound.clone()).looped();
+        }
+        FadeEffect::Stop => {
+            channel.stop();
+        }
+        FadeEffect::None => {}
+    }
+}
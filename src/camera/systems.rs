@@ -43,7 +43,10 @@ pub fn camera_control(
 ) {
     // Read ECS state
     let dt = time.delta().as_secs_f32();
-    let mut transform = camera_q.single_mut().unwrap();
+    // No camera to move while it's being respawned, or with more than one free camera
+    let Ok(mut transform) = camera_q.single_mut() else {
+        return;
+    };
 
     // Collect mouse motion delta
     let mut total_mouse_delta = Vec3::ZERO;
@@ -0,0 +1,29 @@
+use bevy::log::warn;
+use directories::ProjectDirs;
+use std::path::{Path, PathBuf};
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "Inhabitants")
+}
+
+/// `file` in the platform config directory, e.g. `~/.config/inhabitants` on Linux
+pub(crate) fn config_path(file: &str) -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.config_dir().join(file))
+}
+
+/// `file` in the platform data directory, e.g. `~/.local/share/inhabitants` on Linux
+pub(crate) fn data_path(file: &str) -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.data_dir().join(file))
+}
+
+/// Write `text` to `path`, creating its directory if it isn't there yet. Failures are only
+/// logged, the game goes on without the file.
+pub(crate) fn write_file(path: &Path, text: &str) {
+    let written = match path.parent() {
+        Some(parent) => std::fs::create_dir_all(parent).and_then(|()| std::fs::write(path, text)),
+        None => std::fs::write(path, text),
+    };
+    if let Err(error) = written {
+        warn!("Could not save {}: {error}", path.display());
+    }
+}
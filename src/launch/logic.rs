@@ -0,0 +1,233 @@
+use crate::planet::logic::{MAX_PLANET_RADIUS, MAX_PLATES, MIN_PLANET_RADIUS, MIN_PLATES};
+use crate::planet::resources::TerrainSettings;
+use bevy::prelude::Resource;
+use std::path::PathBuf;
+
+/// Printed for `--help` and after arguments that can't be used
+pub const USAGE: &str = "\
+Usage: inhabitants [OPTIONS]
+
+Options:
+  --seed <SEED>          Seed of the planet, as shown in the settings panel
+  --radius <RADIUS>      Planet radius, 5 to 100
+  --plates <COUNT>       Number of tectonic plates, 3 to 15
+  --autogenerate         Generate the planet as soon as the UI is up
+  --stats                Print the plate boundary and land statistics of the generated planet
+  --export-dir <DIR>     Write the overlays of the generated planet as PNGs to DIR
+  --exit-after-export    Quit once the overlays are written
+  --reproduce <FILE>     Generate the planet of a generation record like last_planet.ron in the
+                         game's data directory, the options above still apply on top of it
+  -h, --help             Print this help";
+
+/// Options given on the command line, mostly for reproducing a planet
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct LaunchOptions {
+    pub seed: Option<u32>,
+    pub radius: Option<f32>,
+    pub plates: Option<usize>,
+    pub autogenerate: bool,
+    /// Print the generation statistics of the planet
+    pub print_stats: bool,
+    pub export_dir: Option<PathBuf>,
+    pub exit_after_export: bool,
+    /// Generation record to take all of the settings and the generator config from
+    pub reproduce: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LaunchOptionsError {
+    /// `--help`, not a mistake but there's nothing to launch either
+    Help,
+    Unknown(String),
+    MissingValue(&'static str),
+    InvalidValue { flag: &'static str, value: String },
+    ExitWithoutExport,
+}
+
+impl std::fmt::Display for LaunchOptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LaunchOptionsError::Help => write!(f, "{USAGE}"),
+            LaunchOptionsError::Unknown(argument) => write!(f, "unknown argument {argument}"),
+            LaunchOptionsError::MissingValue(flag) => write!(f, "{flag} needs a value"),
+            LaunchOptionsError::InvalidValue { flag, value } => write!(f, "invalid value {value} for {flag}"),
+            LaunchOptionsError::ExitWithoutExport => write!(f, "--exit-after-export needs --export-dir"),
+        }
+    }
+}
+
+impl LaunchOptions {
+    /// Parse the arguments after the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, LaunchOptionsError> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(argument) = args.next() {
+            match argument.as_str() {
+                "--seed" => {
+                    // Seeds in the settings panel have up to 8 digits
+                    options.seed = Some(value(&mut args, "--seed", |seed: &u32| *seed < 100_000_000)?);
+                }
+                "--radius" => {
+                    let in_range = |radius: &f32| (MIN_PLANET_RADIUS..=MAX_PLANET_RADIUS).contains(radius);
+                    options.radius = Some(value(&mut args, "--radius", in_range)?);
+                }
+                "--plates" => {
+                    let in_range = |plates: &usize| (MIN_PLATES..=MAX_PLATES).contains(plates);
+                    options.plates = Some(value(&mut args, "--plates", in_range)?);
+                }
+                "--autogenerate" => options.autogenerate = true,
+                "--stats" => options.print_stats = true,
+                "--export-dir" => {
+                    let dir = args.next().ok_or(LaunchOptionsError::MissingValue("--export-dir"))?;
+                    options.export_dir = Some(PathBuf::from(dir));
+                }
+                "--exit-after-export" => options.exit_after_export = true,
+                "--reproduce" => {
+                    let file = args.next().ok_or(LaunchOptionsError::MissingValue("--reproduce"))?;
+                    options.reproduce = Some(PathBuf::from(file));
+                }
+                "-h" | "--help" => return Err(LaunchOptionsError::Help),
+                _ => return Err(LaunchOptionsError::Unknown(argument)),
+            }
+        }
+
+        if options.exit_after_export && options.export_dir.is_none() {
+            return Err(LaunchOptionsError::ExitWithoutExport);
+        }
+        Ok(options)
+    }
+
+    /// Whether the launch generates the planet itself instead of the usual first planet on
+    /// entering the planet generation state. An export, the statistics and a reproduced planet
+    /// need a planet, so they imply that.
+    pub fn generates_on_launch(&self) -> bool {
+        self.autogenerate || self.print_stats || self.export_dir.is_some() || self.reproduce.is_some()
+    }
+
+    /// Put the seed, radius and plate count from the command line into `settings`
+    pub fn apply_to(&self, settings: &mut TerrainSettings) {
+        if let Some(seed) = self.seed {
+            settings.set_seed(seed);
+        }
+        if let Some(radius) = self.radius {
+            settings.radius = radius;
+        }
+        if let Some(plates) = self.plates {
+            settings.num_plates = plates;
+        }
+    }
+}
+
+/// Next argument parsed as the value of `flag`, it has to pass `valid`
+fn value<T: std::str::FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &'static str,
+    valid: impl Fn(&T) -> bool,
+) -> Result<T, LaunchOptionsError> {
+    let text = args.next().ok_or(LaunchOptionsError::MissingValue(flag))?;
+    match text.parse::<T>() {
+        Ok(value) if valid(&value) => Ok(value),
+        _ => Err(LaunchOptionsError::InvalidValue { flag, value: text }),
+    }
+}
+
+/// How far the overlay export got
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportStage {
+    #[default]
+    WaitingForPlanet,
+    /// The full resolution planet is there, its climate cubemaps are being rebuilt
+    WaitingForCubemaps,
+    Exported,
+}
+
+/// Whether the climate cubemaps were rebuilt for the current planet, from the change ticks of
+/// the three resources: the wind and vertical air after the planet changed, the precipitation
+/// after the vertical air it's built from. Ticks rather than frames, the rebuilds can land in
+/// the same frame as the planet or a later one depending on the system order.
+pub fn cubemaps_follow_planet(planet: u32, vertical_air: u32, precipitation: u32) -> bool {
+    planet < vertical_air && vertical_air < precipitation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use planetgen::prelude::expand_seed64;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, LaunchOptionsError> {
+        LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_no_arguments_change_nothing() {
+        let options = parse(&[]).unwrap();
+
+        assert_eq!(options, LaunchOptions::default());
+        assert!(!options.generates_on_launch());
+    }
+
+    #[test]
+    fn test_every_option_is_parsed() {
+        let options = parse(&[
+            "--seed", "12345", "--radius", "30", "--plates", "12", "--autogenerate", "--stats",
+            "--export-dir", "./out", "--exit-after-export", "--reproduce", "last_planet.ron",
+        ])
+        .unwrap();
+
+        assert_eq!(options.seed, Some(12345));
+        assert_eq!(options.radius, Some(30.0));
+        assert_eq!(options.plates, Some(12));
+        assert!(options.autogenerate);
+        assert!(options.print_stats);
+        assert_eq!(options.export_dir, Some(PathBuf::from("./out")));
+        assert!(options.exit_after_export);
+        assert_eq!(options.reproduce, Some(PathBuf::from("last_planet.ron")));
+    }
+
+    #[test]
+    fn test_bad_arguments_are_reported() {
+        assert_eq!(parse(&["--seed"]), Err(LaunchOptionsError::MissingValue("--seed")));
+        assert_eq!(
+            parse(&["--radius", "big"]),
+            Err(LaunchOptionsError::InvalidValue {
+                flag: "--radius",
+                value: "big".into()
+            })
+        );
+        assert!(matches!(parse(&["--radius", "500"]), Err(LaunchOptionsError::InvalidValue { .. })));
+        assert!(matches!(parse(&["--plates", "1"]), Err(LaunchOptionsError::InvalidValue { .. })));
+        assert_eq!(parse(&["--fast"]), Err(LaunchOptionsError::Unknown("--fast".into())));
+        assert_eq!(parse(&["--exit-after-export"]), Err(LaunchOptionsError::ExitWithoutExport));
+        assert_eq!(parse(&["--reproduce"]), Err(LaunchOptionsError::MissingValue("--reproduce")));
+        assert_eq!(parse(&["--help"]), Err(LaunchOptionsError::Help));
+    }
+
+    #[test]
+    fn test_export_generates_without_autogenerate() {
+        assert!(parse(&["--export-dir", "out"]).unwrap().generates_on_launch());
+        assert!(parse(&["--stats"]).unwrap().generates_on_launch());
+        assert!(parse(&["--reproduce", "last_planet.ron"]).unwrap().generates_on_launch());
+    }
+
+    #[test]
+    fn test_overrides_replace_only_the_given_settings() {
+        let mut settings = TerrainSettings::default();
+        let original = settings.clone();
+
+        parse(&["--seed", "12345"]).unwrap().apply_to(&mut settings);
+
+        assert_eq!(settings.user_seed, 12345);
+        assert_eq!(settings.seed, expand_seed64(12345));
+        assert_eq!(settings.terrain_seed, 12345);
+        assert_eq!(settings.radius, original.radius);
+        assert_eq!(settings.num_plates, original.num_plates);
+    }
+
+    #[test]
+    fn test_export_waits_for_the_cubemaps_of_the_last_planet() {
+        assert!(cubemaps_follow_planet(10, 12, 15));
+        // Wind from before the planet, precipitation from the old wind
+        assert!(!cubemaps_follow_planet(10, 8, 15));
+        assert!(!cubemaps_follow_planet(10, 12, 11));
+    }
+}
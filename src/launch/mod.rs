@@ -0,0 +1,46 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::precipitation::systems::apply_rebuilt_precipitation;
+use bevy::prelude::*;
+use bevy_egui::{EguiPlugin, PrimaryEguiContext};
+
+pub use logic::{LaunchOptions, LaunchOptionsError, USAGE};
+
+/// Rows of the exported overlay PNGs, they are twice as wide
+pub const EXPORT_HEIGHT: u32 = 512;
+
+/// Applies the command line options: the settings overrides, generating the planet right away
+/// and exporting its overlays.
+///
+/// `main` parses the arguments and hands them to this plugin. Add it after the game plugins,
+/// with egui it waits for the UI before generating, headless it generates on the first frame.
+pub struct LaunchPlugin {
+    pub options: LaunchOptions,
+}
+
+impl Plugin for LaunchPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.options.clone())
+            .add_systems(Startup, systems::apply_launch_overrides);
+
+        if self.options.generates_on_launch() {
+            let generate = systems::generate_planet_once.run_if(in_state(GameState::PlanetGeneration));
+            if app.is_plugin_added::<EguiPlugin>() {
+                app.add_systems(Update, generate.run_if(any_with_component::<PrimaryEguiContext>));
+            } else {
+                app.add_systems(Update, generate);
+            }
+        }
+        if self.options.print_stats {
+            app.add_systems(Update, systems::print_stats_when_generated);
+        }
+        if self.options.export_dir.is_some() {
+            app.add_systems(
+                Update,
+                systems::export_overlays_when_ready.after(apply_rebuilt_precipitation),
+            );
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use super::EXPORT_HEIGHT;
+use super::logic::{ExportStage, LaunchOptions, cubemaps_follow_planet};
+use crate::mesh::helpers::equirect_overlay_image;
+use crate::planet::events::{GeneratePlanetEvent, PlanetSpawnedEvent};
+use crate::planet::history::logic::GAME_VERSION;
+use crate::planet::history::systems::read_generation_record;
+use crate::planet::precipitation::systems::PrecipitationCubeMap;
+use crate::planet::resources::{
+    AllPlanetSettingsMut, CurrentPlanetData, DisplaySettings, PendingPlanetGeneration,
+};
+use crate::planet::temperature::systems::TemperatureCubeMap;
+use crate::planet::wind::systems::{MountainInfluence, VerticalAirCubeMap};
+use bevy::prelude::*;
+use planetgen::prelude::{divergence_to_color, influence_to_color};
+use std::path::Path;
+
+/// Runs before the first planet is generated, which reads the settings in `Update`. A reproduced
+/// record replaces the settings and the generator config first, the other options go on top.
+pub fn apply_launch_overrides(
+    options: Res<LaunchOptions>,
+    mut settings: AllPlanetSettingsMut,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    if let Some(path) = options.reproduce.as_deref() {
+        let record = match read_generation_record(path) {
+            Ok(record) => record,
+            Err(error) => {
+                error!("Could not read the generation record {error}");
+                app_exit.write(AppExit::error());
+                return;
+            }
+        };
+        if let Some(version) = record.other_version() {
+            warn!(
+                "{} was written by version {version}, this is {GAME_VERSION}, the planet may come out different",
+                path.display()
+            );
+        }
+        info!("Reproducing the planet of {}", path.display());
+        settings.apply(record.settings);
+        planetgen::config::set_config(record.config);
+    }
+    options.apply_to(&mut settings.terrain);
+}
+
+/// Generate the planet from the launch settings, once
+pub fn generate_planet_once(mut sent: Local<bool>, mut planet_events: MessageWriter<GeneratePlanetEvent>) {
+    if *sent {
+        return;
+    }
+    *sent = true;
+    info!("Generating the planet from the command line options");
+    planet_events.write(GeneratePlanetEvent);
+}
+
+/// Print the generation statistics of the first full resolution planet, once
+pub fn print_stats_when_generated(
+    mut printed: Local<bool>,
+    mut planet_spawned_events: MessageReader<PlanetSpawnedEvent>,
+    pending_generation: Res<PendingPlanetGeneration>,
+    current_planet_data: Res<CurrentPlanetData>,
+) {
+    // A preview is followed by the full resolution planet, only that one counts
+    if *printed || planet_spawned_events.read().count() == 0 || pending_generation.task.is_some() {
+        return;
+    }
+    let Some(planet) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    *printed = true;
+    println!("{}", planet.generation_stats());
+}
+
+/// Write the overlays of the first full resolution planet to the export directory once the
+/// climate cubemaps are rebuilt for it, and quit afterwards if asked to. The overlays get the
+/// colors of the palette in the settings.
+pub fn export_overlays_when_ready(
+    options: Res<LaunchOptions>,
+    settings: Res<DisplaySettings>,
+    mut stage: Local<ExportStage>,
+    mut planet_spawned_events: MessageReader<PlanetSpawnedEvent>,
+    pending_generation: Res<PendingPlanetGeneration>,
+    current_planet_data: Res<CurrentPlanetData>,
+    temperature: Option<Res<TemperatureCubeMap>>,
+    vertical_air: Option<Res<VerticalAirCubeMap>>,
+    precipitation: Option<Res<PrecipitationCubeMap>>,
+    influence: Option<Res<MountainInfluence>>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    // A preview is followed by the full resolution planet, only that one counts
+    if planet_spawned_events.read().count() > 0
+        && pending_generation.task.is_none()
+        && *stage == ExportStage::WaitingForPlanet
+    {
+        *stage = ExportStage::WaitingForCubemaps;
+    }
+    if *stage != ExportStage::WaitingForCubemaps {
+        return;
+    }
+    let (Some(vertical_air), Some(precipitation)) = (vertical_air, precipitation) else {
+        return;
+    };
+    if !cubemaps_follow_planet(
+        current_planet_data.last_changed().get(),
+        vertical_air.last_changed().get(),
+        precipitation.last_changed().get(),
+    ) {
+        return;
+    }
+    *stage = ExportStage::Exported;
+    let Some(dir) = options.export_dir.as_deref() else {
+        return;
+    };
+
+    let mut overlays: Vec<(&str, Box<dyn Fn(Vec3) -> Vec3 + '_>)> = Vec::new();
+    if let Some(temperature) = temperature.as_deref() {
+        let color_at = move |direction: Vec3| temperature.sample_color(direction);
+        overlays.push(("temperature", Box::new(color_at)));
+    }
+    let palette = settings.palette;
+    let precipitation = &*precipitation;
+    let color_at = move |direction: Vec3| precipitation.sample_color(direction, palette);
+    overlays.push(("precipitation", Box::new(color_at)));
+    let vertical_air = &*vertical_air;
+    let color_at = move |direction: Vec3| divergence_to_color(vertical_air.sample(direction), palette);
+    overlays.push(("vertical_air", Box::new(color_at)));
+    if let Some(influence) = influence.as_deref() {
+        let color_at = move |direction: Vec3| influence_to_color(influence.sample_cost(direction), palette);
+        overlays.push(("wind_influence", Box::new(color_at)));
+    }
+
+    let result = std::fs::create_dir_all(dir).map_err(|error| error.to_string()).and_then(|()| {
+        overlays.iter().try_for_each(|(name, color_at)| {
+            save_overlay_png(&dir.join(format!("{name}.png")), color_at.as_ref())
+        })
+    });
+    match &result {
+        Ok(()) => info!("Exported {} overlays to {}", overlays.len(), dir.display()),
+        Err(error) => error!("Could not export the overlays to {}: {error}", dir.display()),
+    }
+
+    if options.exit_after_export {
+        app_exit.write(if result.is_ok() { AppExit::Success } else { AppExit::error() });
+    }
+}
+
+/// Equirectangular PNG of an overlay, `color_at` gives the linear overlay color in a direction
+fn save_overlay_png(path: &Path, color_at: &dyn Fn(Vec3) -> Vec3) -> Result<(), String> {
+    let image = equirect_overlay_image(EXPORT_HEIGHT, |direction| {
+        // The overlay colors are linear like vertex colors, PNGs are read as sRGB
+        let color = color_at(direction);
+        Srgba::from(LinearRgba::rgb(color.x, color.y, color.z)).to_f32_array()
+    });
+    // The bytes are already sRGB, bevy only converts images tagged as such
+    let (width, height) = (image.width(), image.height());
+    let data = image.data.ok_or("overlay image without data")?;
+    let image = image::RgbaImage::from_raw(width, height, data).ok_or("overlay image of the wrong size")?;
+    image
+        .save(path)
+        .map_err(|error| format!("{}: {error}", path.display()))
+}
@@ -1,16 +1,22 @@
+pub mod app_settings;
 mod audio;
 mod camera;
 mod core;
+pub mod launch;
 mod loading;
 mod mesh;
-mod planet;
+pub mod planet;
 mod player;
 mod ui;
+mod window_icon;
 
 use crate::audio::InternalAudioPlugin;
 use crate::loading::LoadingPlugin;
 use crate::planet::PlanetPlugin;
+use crate::planet::history::HistoryPlugin;
 use crate::ui::UIPlugin;
+use crate::ui::focus::FocusPlugin;
+use crate::window_icon::WindowIconPlugin;
 
 use crate::camera::CameraPlugin;
 use crate::core::state::GameState;
@@ -25,15 +31,16 @@ pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<GameState>()
-            .add_plugins(EguiPlugin::default())
+        app.add_plugins(EguiPlugin::default())
             .add_plugins((
+                PlanetGenerationPlugin,
+                HistoryPlugin,
                 CameraPlugin,
                 LoadingPlugin,
                 InternalAudioPlugin,
-                PlanetPlugin,
-                PlanetGenMenuPlugin,
                 UIPlugin,
+                FocusPlugin,
+                WindowIconPlugin,
             ));
 
         #[cfg(debug_assertions)]
@@ -42,3 +49,14 @@ impl Plugin for GamePlugin {
         }
     }
 }
+
+/// Game state and planet generation without window, camera, audio or egui.
+/// Runs headless on top of `MinimalPlugins`, which is how the integration tests use it.
+pub struct PlanetGenerationPlugin;
+
+impl Plugin for PlanetGenerationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .add_plugins((PlanetPlugin, PlanetGenMenuPlugin));
+    }
+}
@@ -3,19 +3,37 @@
 
 use bevy::DefaultPlugins;
 use bevy::prelude::*;
-use bevy::window::{PresentMode, PrimaryWindow, WindowResolution};
-use bevy::winit::WinitWindows;
+use bevy::window::{PresentMode, WindowPosition, WindowResolution};
 use inhabitants::GamePlugin;
-use std::io::Cursor;
-use winit::window::Icon;
+use inhabitants::app_settings::{AppSettingsPlugin, load_app_settings};
+use inhabitants::launch::{LaunchOptions, LaunchOptionsError, LaunchPlugin, USAGE};
 
 fn main() {
+    let launch_options = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(LaunchOptionsError::Help) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    // Read before the window exists, so it opens with the size and position of the last run
+    let app_settings = load_app_settings();
+    let window = app_settings.window;
+
     App::new()
         .insert_resource(ClearColor(Color::linear_rgb(0.4, 0.4, 0.4)))
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Inhabitants".into(),
-                resolution: WindowResolution::new(1500, 900),
+                resolution: WindowResolution::new(window.width, window.height),
+                position: window
+                    .position
+                    .map_or(WindowPosition::Automatic, |(x, y)| WindowPosition::At(IVec2::new(x, y))),
                 present_mode: PresentMode::AutoVsync,
                 resize_constraints: WindowResizeConstraints {
                     min_width: 800.0,
@@ -30,25 +48,12 @@ fn main() {
             ..default()
         }))
         .add_plugins(GamePlugin)
-        .add_systems(Startup, set_window_icon)
+        .add_plugins(AppSettingsPlugin {
+            restored: app_settings,
+        })
+        // After the game plugins, it looks for egui to know whether there is a UI to wait for
+        .add_plugins(LaunchPlugin {
+            options: launch_options,
+        })
         .run();
 }
-
-fn set_window_icon(
-    windows: Option<NonSend<WinitWindows>>,
-    primary_window: Query<Entity, With<PrimaryWindow>>,
-) {
-    let Some(windows) = windows else { return; };
-    let primary_entity = primary_window.single().unwrap();
-    let Some(primary) = windows.get_window(primary_entity) else {
-        return;
-    };
-    let icon_buf = Cursor::new(include_bytes!("../assets/icons/icon.png"));
-    if let Ok(image) = image::load(icon_buf, image::ImageFormat::Png) {
-        let image = image.into_rgba8();
-        let (width, height) = image.dimensions();
-        let rgba = image.into_raw();
-        let icon = Icon::from_rgba(rgba, width, height).unwrap();
-        primary.set_window_icon(Some(icon));
-    };
-}
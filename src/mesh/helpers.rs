@@ -1,6 +1,10 @@
 use bevy::asset::RenderAssetUsages;
-use bevy::prelude::Mesh;
+use bevy::image::{Image, ImageAddressMode, ImageFilterMode, ImageSampler, ImageSamplerDescriptor};
+use bevy::prelude::{Mesh, Vec3};
 use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
 
 pub fn arrow_mesh() -> Mesh {
     let mut positions = Vec::new();
@@ -38,3 +42,294 @@ pub fn arrow_mesh() -> Mesh {
     mesh.insert_indices(Indices::U32(indices));
     mesh
 }
+
+/// Thin square rod along the Y axis, centered on the origin
+pub fn axis_mesh(half_length: f32, thickness: f32) -> Mesh {
+    let t = thickness / 2.0;
+    let corners = [[t, t], [-t, t], [-t, -t], [t, -t]];
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+
+    // One quad per side, each with its own vertices so the normals stay flat
+    for side in 0..4 {
+        let [x0, z0] = corners[side];
+        let [x1, z1] = corners[(side + 1) % 4];
+        let normal = Vec3::new(x0 + x1, 0.0, z0 + z1).normalize();
+        let base = positions.len() as u32;
+        positions.push([x0, -half_length, z0]);
+        positions.push([x1, -half_length, z1]);
+        positions.push([x1, half_length, z1]);
+        positions.push([x0, half_length, z0]);
+        normals.extend([normal.to_array(); 4]);
+        indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+/// Vertical ribbon around the Y axis at height 0, facing outwards
+pub fn ring_mesh(radius: f32, width: f32, segments: u32) -> Mesh {
+    let half_width = width / 2.0;
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+
+    for segment in 0..=segments {
+        let angle = segment as f32 / segments as f32 * std::f32::consts::TAU;
+        let (sin, cos) = angle.sin_cos();
+        positions.push([radius * cos, -half_width, radius * sin]);
+        positions.push([radius * cos, half_width, radius * sin]);
+        normals.extend([[cos, 0.0, sin]; 2]);
+    }
+    for segment in 0..segments {
+        let base = segment * 2;
+        indices.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+/// Flat ribbon of `width` through `points`, lying across the surface of a sphere centered on
+/// the origin. Normals point away from the center.
+pub fn surface_ribbon_mesh(points: &[Vec3], width: f32) -> Mesh {
+    let half_width = width / 2.0;
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+
+    for (i, &point) in points.iter().enumerate() {
+        // Along the line at this point, the ends use their only segment
+        let along = points[(i + 1).min(points.len() - 1)] - points[i.saturating_sub(1)];
+        let up = point.normalize_or_zero();
+        let side = along.cross(up).normalize_or_zero() * half_width;
+        positions.push((point - side).to_array());
+        positions.push((point + side).to_array());
+        normals.extend([up.to_array(); 2]);
+    }
+    for segment in 0..points.len().saturating_sub(1) as u32 {
+        let base = segment * 2;
+        indices.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+/// Vertex positions of a mesh, empty when it has none
+pub fn mesh_positions(mesh: &Mesh) -> &[[f32; 3]] {
+    mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        .and_then(|positions| positions.as_float3())
+        .unwrap_or(&[])
+}
+
+/// Equirectangular texture coordinates of a direction: longitude along U (wrapping around at
+/// the -X side), latitude along V with the north pole at the top
+pub fn spherical_uv(direction: Vec3) -> [f32; 2] {
+    let direction = direction.normalize_or_zero();
+    let longitude = direction.z.atan2(direction.x);
+    let latitude = direction.y.clamp(-1.0, 1.0).asin();
+    [longitude / TAU + 0.5, 0.5 - latitude / PI]
+}
+
+/// Direction at the equirectangular texture coordinates `uv`, the inverse of `spherical_uv`
+pub fn spherical_direction(uv: [f32; 2]) -> Vec3 {
+    let longitude = (uv[0] - 0.5) * TAU;
+    let latitude = (0.5 - uv[1]) * PI;
+    Vec3::new(
+        latitude.cos() * longitude.cos(),
+        latitude.sin(),
+        latitude.cos() * longitude.sin(),
+    )
+}
+
+/// Copy of `original` with spherical UVs for an equirectangular overlay texture.
+/// Triangles across the U seam get their own copies of the vertices on the low side with
+/// U past 1, the texture repeats along U so they sample the right texels. Those copies come
+/// after the original vertices, the second value is the original index of each of them.
+pub fn spherical_uv_mesh_copy(original: &Mesh) -> (Mesh, Vec<u32>) {
+    let mut positions = mesh_positions(original).to_vec();
+    let mut normals = original
+        .attribute(Mesh::ATTRIBUTE_NORMAL)
+        .and_then(|attr| attr.as_float3())
+        .map(<[_]>::to_vec);
+    let mut uvs: Vec<[f32; 2]> = positions.iter().map(|&p| spherical_uv(Vec3::from(p))).collect();
+    let mut indices: Vec<u32> = original
+        .indices()
+        .map(|indices| indices.iter().map(|i| i as u32).collect())
+        .unwrap_or_default();
+
+    let mut wrapped = HashMap::new();
+    let mut seam_sources = Vec::new();
+    for triangle in indices.chunks_exact_mut(3) {
+        let us = triangle.iter().map(|&i| uvs[i as usize][0]);
+        let (min_u, max_u) = us.fold((f32::MAX, f32::MIN), |(min, max), u| (min.min(u), max.max(u)));
+        if max_u - min_u <= 0.5 {
+            continue;
+        }
+        for index in triangle.iter_mut() {
+            let original_index = *index as usize;
+            let [u, v] = uvs[original_index];
+            if u >= 0.5 {
+                continue;
+            }
+            *index = *wrapped.entry(original_index).or_insert_with(|| {
+                positions.push(positions[original_index]);
+                if let Some(normals) = normals.as_mut() {
+                    normals.push(normals[original_index]);
+                }
+                uvs.push([u + 1.0, v]);
+                seam_sources.push(original_index as u32);
+                (uvs.len() - 1) as u32
+            });
+        }
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    if let Some(normals) = normals {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(indices));
+    (mesh, seam_sources)
+}
+
+/// Per-vertex colors of an original mesh extended to its `spherical_uv_mesh_copy`, the seam
+/// copies get the color of the vertex they were copied from
+pub fn colors_with_seam(colors: &[[f32; 4]], seam_sources: &[u32]) -> Vec<[f32; 4]> {
+    let mut extended = Vec::with_capacity(colors.len() + seam_sources.len());
+    extended.extend_from_slice(colors);
+    extended.extend(seam_sources.iter().map(|&source| colors[source as usize]));
+    extended
+}
+
+/// Equirectangular 2:1 overlay texture of `height` rows, `color_at` gives the linear RGBA color
+/// in a direction. Works with any cubemap sampler (temperature, precipitation, biomes).
+pub fn equirect_overlay_image(height: u32, color_at: impl Fn(Vec3) -> [f32; 4]) -> Image {
+    let width = height * 2;
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let uv = [(x as f32 + 0.5) / width as f32, (y as f32 + 0.5) / height as f32];
+            let color = color_at(spherical_direction(uv));
+            data.extend(color.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8));
+        }
+    }
+
+    // Linear like vertex colors, so both overlay paths look the same
+    let mut image = Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::ClampToEdge,
+        mag_filter: ImageFilterMode::Linear,
+        min_filter: ImageFilterMode::Linear,
+        ..Default::default()
+    });
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_ribbon_lies_across_the_sphere() {
+        let points: Vec<Vec3> = (0..5)
+            .map(|i| Vec3::new((i as f32 * 0.1).cos(), 0.0, (i as f32 * 0.1).sin()) * 10.0)
+            .collect();
+        let mesh = surface_ribbon_mesh(&points, 0.5);
+
+        let positions = mesh_positions(&mesh);
+        assert_eq!(positions.len(), 10);
+        assert_eq!(mesh.indices().unwrap().len(), 4 * 6);
+        for (pair, &point) in positions.chunks(2).zip(&points) {
+            let (left, right) = (Vec3::from(pair[0]), Vec3::from(pair[1]));
+            // Across the equator, so the edges are above and below it at the same distance
+            assert!((left.distance(right) - 0.5).abs() < 1e-4);
+            assert!(((left + right) / 2.0).distance(point) < 1e-4);
+            assert!(left.y.abs() > 0.2 && left.y * right.y < 0.0);
+        }
+    }
+
+    #[test]
+    fn test_spherical_uv_round_trips() {
+        for direction in [
+            Vec3::X,
+            Vec3::new(0.3, 0.8, -0.5).normalize(),
+            Vec3::new(-0.6, -0.2, 0.7).normalize(),
+        ] {
+            let back = spherical_direction(spherical_uv(direction));
+            assert!(back.distance(direction) < 1e-5, "{direction} -> {back}");
+        }
+    }
+
+    #[test]
+    fn test_triangles_across_the_seam_get_wrapped_vertices() {
+        // One triangle on each side of the seam at -X, one far from it
+        let positions = vec![
+            [-1.0, 0.0, 0.1],
+            [-1.0, 0.0, -0.1],
+            [-1.0, 0.2, 0.0],
+            [1.0, 0.0, 0.1],
+            [1.0, 0.0, -0.1],
+            [1.0, 0.2, 0.0],
+        ];
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_indices(Indices::U32(vec![0, 1, 2, 3, 4, 5]));
+
+        let (textured, seam_sources) = spherical_uv_mesh_copy(&mesh);
+        let Some(bevy::mesh::VertexAttributeValues::Float32x2(uvs)) = textured.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("no uvs");
+        };
+        let indices: Vec<usize> = textured.indices().unwrap().iter().collect();
+        for triangle in indices.chunks_exact(3) {
+            let us: Vec<f32> = triangle.iter().map(|&i| uvs[i][0]).collect();
+            let spread = us.iter().cloned().fold(f32::MIN, f32::max) - us.iter().cloned().fold(f32::MAX, f32::min);
+            assert!(spread < 0.1, "triangle spans {us:?}");
+        }
+        // Only the low side of the seam triangle was duplicated
+        assert_eq!(uvs.len(), 7);
+        assert_eq!(seam_sources, vec![1]);
+
+        let colors = [[0.0; 4], [0.1; 4], [0.2; 4], [0.3; 4], [0.4; 4], [0.5; 4]];
+        let extended = colors_with_seam(&colors, &seam_sources);
+        assert_eq!(extended.len(), 7);
+        assert_eq!(extended[6], colors[1]);
+    }
+}
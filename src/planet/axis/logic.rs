@@ -0,0 +1,76 @@
+use bevy::math::Vec3;
+use planetgen::prelude::oblate_radius;
+
+/// How far the axis reaches out of the planet at each pole, relative to the radius
+pub const AXIS_EXTENT: f32 = 1.3;
+
+/// Rings smaller than this (a polar circle at zero tilt) aren't worth drawing
+const MIN_RING_RADIUS: f32 = 1e-3;
+
+/// A circle of latitude drawn around the planet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatitudeRing {
+    /// Latitude in degrees, positive to the north
+    pub latitude: f32,
+    /// Radius of the circle around the axis
+    pub radius: f32,
+    /// Height of the circle along the axis
+    pub height: f32,
+}
+
+/// Half the length of the axis, measured from the planet center
+pub fn axis_half_length(radius: f32) -> f32 {
+    radius * AXIS_EXTENT
+}
+
+/// Tropics at ±tilt and polar circles at ±(90 - tilt), `lift` above the flattened surface
+pub fn tilt_rings(radius: f32, oblateness: f32, axial_tilt: f32, lift: f32) -> Vec<LatitudeRing> {
+    let tilt = axial_tilt.clamp(0.0, 90.0);
+    [tilt, -tilt, 90.0 - tilt, tilt - 90.0]
+        .into_iter()
+        .map(|latitude| {
+            let (sin, cos) = latitude.to_radians().sin_cos();
+            let surface = oblate_radius(radius, oblateness, Vec3::new(cos, sin, 0.0)) + lift;
+            LatitudeRing {
+                latitude,
+                radius: surface * cos,
+                height: surface * sin,
+            }
+        })
+        .filter(|ring| ring.radius > MIN_RING_RADIUS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rings_follow_the_axial_tilt() {
+        let rings = tilt_rings(50.0, 0.0, 23.5, 1.0);
+        let latitudes: Vec<f32> = rings.iter().map(|ring| ring.latitude).collect();
+        assert_eq!(latitudes, [23.5, -23.5, 66.5, -66.5]);
+
+        for ring in &rings {
+            let distance = (ring.radius * ring.radius + ring.height * ring.height).sqrt();
+            assert!((distance - 51.0).abs() < 1e-3);
+        }
+        assert!(rings[0].height > 0.0 && rings[1].height < 0.0);
+        assert!((rings[0].radius - rings[1].radius).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_polar_circles_vanish_without_tilt() {
+        let rings = tilt_rings(50.0, 0.0, 0.0, 1.0);
+        // Both tropics fall onto the equator, the polar circles shrink to the poles
+        assert_eq!(rings.len(), 2);
+        assert!(rings.iter().all(|ring| ring.height.abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_rings_sit_lower_on_a_flattened_planet() {
+        let round = tilt_rings(50.0, 0.0, 23.5, 1.0);
+        let flat = tilt_rings(50.0, 0.1, 23.5, 1.0);
+        assert!(flat[2].height < round[2].height);
+    }
+}
@@ -0,0 +1,24 @@
+pub mod logic;
+pub mod systems;
+
+use crate::planet::SettingsDiffSet;
+use crate::planet::systems::{rescale_planet_on_event, spawn_planet_on_event};
+use bevy::prelude::*;
+
+/// Rotation axis and the tropics/polar circles of the axial tilt, children of the planet
+#[derive(Component)]
+pub struct AxisGizmo;
+
+pub struct AxisPlugin;
+
+impl Plugin for AxisPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            systems::update_axis_gizmo
+                .after(SettingsDiffSet)
+                .after(spawn_planet_on_event)
+                .after(rescale_planet_on_event),
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use super::AxisGizmo;
+use super::logic::{axis_half_length, tilt_rings};
+use crate::mesh::helpers::{axis_mesh, ring_mesh};
+use crate::planet::components::PlanetEntity;
+use crate::planet::events::{PlanetSpawnedEvent, SettingsDiff, SettingsGroup};
+use crate::planet::logic::{SurfaceLayer, static_layer_offset};
+use crate::planet::resources::{CurrentPlanetData, DisplaySettings, PlanetAssetStores};
+use bevy::prelude::*;
+
+const RING_SEGMENTS: u32 = 128;
+
+/// Rebuild the axis gizmo when it is toggled, the tilt or the planet size changes, or a new
+/// planet is spawned (the old gizmo went away with the old planet)
+pub fn update_axis_gizmo(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut planet_spawned_events: MessageReader<PlanetSpawnedEvent>,
+    settings: Res<DisplaySettings>,
+    current_planet_data: Res<CurrentPlanetData>,
+    planet_query: Query<Entity, With<PlanetEntity>>,
+    existing_gizmos: Query<Entity, With<AxisGizmo>>,
+    (mut meshes, mut materials, mut planet_assets): PlanetAssetStores,
+    mut commands: Commands,
+) {
+    let settings_changed =
+        SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Terrain, SettingsGroup::Visual]);
+    let planet_spawned = planet_spawned_events.read().count() > 0;
+    if !settings_changed && !planet_spawned {
+        return;
+    }
+
+    for entity in existing_gizmos.iter() {
+        commands.entity(entity).despawn();
+    }
+    if !settings.show_axis {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    let Some(planet_entity) = planet_query.iter().next() else {
+        return;
+    };
+
+    // Thin relative to the planet so it reads as a line at any radius
+    let thickness = planet_data.radius * 0.006;
+    let material = planet_assets.add_material(&mut materials, StandardMaterial {
+        base_color: Color::srgb(0.95, 0.85, 0.3),
+        unlit: true,
+        // Rings are single ribbons, seen from inside on the far side
+        cull_mode: None,
+        ..default()
+    });
+
+    let axis = commands
+        .spawn((
+            Mesh3d(planet_assets.add_mesh(&mut meshes, axis_mesh(axis_half_length(planet_data.radius), thickness))),
+            MeshMaterial3d(material.clone()),
+            Transform::default(),
+            AxisGizmo,
+        ))
+        .id();
+    commands.entity(planet_entity).add_child(axis);
+
+    // Over the sea, mountains may still poke through
+    let lift = planet_data.sea_level().max(0.0) + static_layer_offset(SurfaceLayer::LatitudeRings, planet_data.radius);
+    for ring in tilt_rings(planet_data.radius, planet_data.oblateness, settings.axial_tilt, lift) {
+        let ring_entity = commands
+            .spawn((
+                Mesh3d(planet_assets.add_mesh(&mut meshes, ring_mesh(ring.radius, thickness, RING_SEGMENTS))),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(0.0, ring.height, 0.0),
+                AxisGizmo,
+            ))
+            .id();
+        commands.entity(planet_entity).add_child(ring_entity);
+    }
+}
@@ -1,12 +1,19 @@
 pub mod systems;
 
 use bevy::prelude::*;
+use crate::planet::SettingsDiffSet;
+use crate::planet::systems::sync_water_level;
 
 pub struct BiomePlugin;
 
 impl Plugin for BiomePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<systems::BiomeColorState>()
-            .add_systems(Update, systems::update_continent_biome_colors);
+            .add_systems(
+                Update,
+                systems::update_continent_biome_colors
+                    .after(SettingsDiffSet)
+                    .after(sync_water_level),
+            );
     }
 }
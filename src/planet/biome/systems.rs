@@ -1,60 +1,25 @@
 use crate::planet::components::ContinentViewMesh;
+use crate::planet::events::{SettingsDiff, SettingsGroup};
 use crate::planet::precipitation::systems::PrecipitationCubeMap;
-use crate::planet::resources::PlanetGenerationSettings;
+use crate::planet::resources::{
+    ClimateSettings, CurrentPlanetData, DisplaySettings, TerrainSettings, WaterLevel,
+};
 use crate::planet::temperature::systems::TemperatureCubeMap;
 use bevy::prelude::*;
-use planetgen::biome::{BiomeColors, BiomeThresholds};
+use planetgen::prelude::{
+    BiomeColorInputs, BiomeColors, BiomeThresholds, calculate_biome_colors, hillshade_factor,
+    oblate_radius,
+};
 
 /// Tracks whether biome colors have been applied for the current planet.
 /// Reset to false when a new planet is spawned or when biome settings change.
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct BiomeColorState {
     pub applied: bool,
-    // Snapshot of last-applied thresholds/colors to detect changes
-    prev_ice_temp: f32,
-    prev_tundra_temp: f32,
-    prev_boreal_temp: f32,
-    prev_temperate_temp: f32,
-    prev_hot_temp: f32,
-    prev_desert_precip: f32,
-    prev_savanna_precip: f32,
-    prev_jungle_precip: f32,
-    prev_temperate_precip: f32,
-    prev_ice_color: [f32; 3],
-    prev_tundra_color: [f32; 3],
-    prev_desert_color: [f32; 3],
-    prev_savanna_color: [f32; 3],
-    prev_temperate_color: [f32; 3],
-    prev_jungle_color: [f32; 3],
-    prev_land_temp_bonus: f32,
-}
-
-impl Default for BiomeColorState {
-    fn default() -> Self {
-        Self {
-            applied: false,
-            prev_ice_temp: f32::NAN,
-            prev_tundra_temp: f32::NAN,
-            prev_boreal_temp: f32::NAN,
-            prev_temperate_temp: f32::NAN,
-            prev_hot_temp: f32::NAN,
-            prev_desert_precip: f32::NAN,
-            prev_savanna_precip: f32::NAN,
-            prev_jungle_precip: f32::NAN,
-            prev_temperate_precip: f32::NAN,
-            prev_ice_color: [f32::NAN; 3],
-            prev_tundra_color: [f32::NAN; 3],
-            prev_desert_color: [f32::NAN; 3],
-            prev_savanna_color: [f32::NAN; 3],
-            prev_temperate_color: [f32::NAN; 3],
-            prev_jungle_color: [f32::NAN; 3],
-            prev_land_temp_bonus: f32::NAN,
-        }
-    }
 }
 
 /// Build a BiomeColors struct from the current settings.
-fn biome_colors_from_settings(settings: &PlanetGenerationSettings) -> BiomeColors {
+fn biome_colors_from_settings(settings: &ClimateSettings) -> BiomeColors {
     BiomeColors {
         ice: settings.biome_ice_color,
         tundra: settings.biome_tundra_color,
@@ -66,7 +31,7 @@ fn biome_colors_from_settings(settings: &PlanetGenerationSettings) -> BiomeColor
 }
 
 /// Build a BiomeThresholds struct from the current settings.
-fn biome_thresholds_from_settings(settings: &PlanetGenerationSettings) -> BiomeThresholds {
+pub fn biome_thresholds_from_settings(settings: &ClimateSettings) -> BiomeThresholds {
     BiomeThresholds {
         ice_temp: settings.biome_ice_temp,
         tundra_temp: settings.biome_tundra_temp,
@@ -83,10 +48,13 @@ fn biome_thresholds_from_settings(settings: &PlanetGenerationSettings) -> BiomeT
 /// Updates continent mesh vertex colors with biome-based coloring
 /// once both temperature and precipitation cubemaps are available.
 pub fn update_continent_biome_colors(
-    settings: Res<PlanetGenerationSettings>,
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
     temperature_cubemap: Option<Res<TemperatureCubeMap>>,
     precipitation_cubemap: Option<Res<PrecipitationCubeMap>>,
     mut biome_state: ResMut<BiomeColorState>,
+    current_planet_data: Res<CurrentPlanetData>,
+    water_level: Res<WaterLevel>,
     continent_query: Query<&Mesh3d, With<ContinentViewMesh>>,
     mut meshes: ResMut<Assets<Mesh>>,
 ) {
@@ -102,24 +70,9 @@ pub fn update_continent_biome_colors(
         biome_state.applied = false;
     }
 
-    // Detect if any biome-relevant setting changed since last apply
+    // Biome thresholds/colors, or the land temperature bonus and lapse rate used for land vertices
     let settings_changed =
-        biome_state.prev_ice_temp != settings.biome_ice_temp
-        || biome_state.prev_tundra_temp != settings.biome_tundra_temp
-        || biome_state.prev_boreal_temp != settings.biome_boreal_temp
-        || biome_state.prev_temperate_temp != settings.biome_temperate_temp
-        || biome_state.prev_hot_temp != settings.biome_hot_temp
-        || biome_state.prev_desert_precip != settings.biome_desert_precip
-        || biome_state.prev_savanna_precip != settings.biome_savanna_precip
-        || biome_state.prev_jungle_precip != settings.biome_jungle_precip
-        || biome_state.prev_temperate_precip != settings.biome_temperate_precip
-        || biome_state.prev_ice_color != settings.biome_ice_color
-        || biome_state.prev_tundra_color != settings.biome_tundra_color
-        || biome_state.prev_desert_color != settings.biome_desert_color
-        || biome_state.prev_savanna_color != settings.biome_savanna_color
-        || biome_state.prev_temperate_color != settings.biome_temperate_color
-        || biome_state.prev_jungle_color != settings.biome_jungle_color
-        || biome_state.prev_land_temp_bonus != settings.land_temperature_bonus;
+        SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Biome, SettingsGroup::Temperature]);
 
     if settings_changed {
         biome_state.applied = false;
@@ -129,12 +82,17 @@ pub fn update_continent_biome_colors(
         return;
     }
 
-    let planet_radius = settings.radius;
-    let continent_threshold = settings.continent_threshold;
-    let snow_threshold = settings.snow_threshold;
-    let land_temp_bonus = settings.land_temperature_bonus;
-    let biome_colors = biome_colors_from_settings(&settings);
-    let biome_thresholds = biome_thresholds_from_settings(&settings);
+    let Some(sea_level) = water_level.0 else {
+        return;
+    };
+    let planet_radius = terrain_settings.radius;
+    let oblateness = terrain_settings.oblateness;
+    let snow = terrain_settings.snow_line(&climate_settings, Some(&temp_map.inner));
+    let land_temp_bonus = climate_settings.land_temperature_bonus;
+    let biome_colors = biome_colors_from_settings(&climate_settings);
+    let biome_thresholds = biome_thresholds_from_settings(&climate_settings);
+    let hillshade = display_settings.hillshade();
+    let color_variation = display_settings.color_variation(terrain_settings.seed);
 
     for mesh_handle in continent_query.iter() {
         let Some(mesh) = meshes.get_mut(&mesh_handle.0) else {
@@ -150,38 +108,35 @@ pub fn update_continent_biome_colors(
 
         let positions_owned: Vec<[f32; 3]> = positions.to_vec();
 
-        let colors = planetgen::mesh_data::calculate_biome_colors(
+        let colors = calculate_biome_colors(
             &positions_owned,
-            planet_radius,
-            continent_threshold,
-            snow_threshold,
-            land_temp_bonus,
-            &biome_colors,
-            &biome_thresholds,
-            |direction| temp_map.sample_temperature(direction),
-            |direction| precip_map.sample(direction),
+            &BiomeColorInputs {
+                surface_radius: &|direction| oblate_radius(planet_radius, oblateness, direction),
+                sea_level,
+                snow,
+                land_temperature_bonus: land_temp_bonus,
+                colors: &biome_colors,
+                thresholds: &biome_thresholds,
+                variation: &color_variation,
+                temperature: &|direction| temp_map.sample_temperature(direction),
+                precipitation: &|direction| precip_map.sample(direction),
+                is_lake: &|direction| {
+                    current_planet_data
+                        .planet_data
+                        .as_ref()
+                        .is_some_and(|planet| planet.has_lake(direction))
+                },
+                hillshade: &|direction| {
+                    current_planet_data
+                        .planet_data
+                        .as_ref()
+                        .map_or(1.0, |planet| hillshade_factor(planet, direction, &hillshade))
+                },
+            },
         );
 
         mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
     }
 
-    // Snapshot current settings
-    biome_state.prev_ice_temp = settings.biome_ice_temp;
-    biome_state.prev_tundra_temp = settings.biome_tundra_temp;
-    biome_state.prev_boreal_temp = settings.biome_boreal_temp;
-    biome_state.prev_temperate_temp = settings.biome_temperate_temp;
-    biome_state.prev_hot_temp = settings.biome_hot_temp;
-    biome_state.prev_desert_precip = settings.biome_desert_precip;
-    biome_state.prev_savanna_precip = settings.biome_savanna_precip;
-    biome_state.prev_jungle_precip = settings.biome_jungle_precip;
-    biome_state.prev_temperate_precip = settings.biome_temperate_precip;
-    biome_state.prev_ice_color = settings.biome_ice_color;
-    biome_state.prev_tundra_color = settings.biome_tundra_color;
-    biome_state.prev_desert_color = settings.biome_desert_color;
-    biome_state.prev_savanna_color = settings.biome_savanna_color;
-    biome_state.prev_temperate_color = settings.biome_temperate_color;
-    biome_state.prev_jungle_color = settings.biome_jungle_color;
-    biome_state.prev_land_temp_bonus = settings.land_temperature_bonus;
-
     biome_state.applied = true;
 }
@@ -0,0 +1,23 @@
+pub mod systems;
+
+use crate::planet::systems::{rescale_planet_on_event, spawn_planet_on_event};
+use bevy::prelude::*;
+
+/// Debug lines along the coasts of the planet, children of it
+#[derive(Component)]
+pub struct CoastlineGizmo;
+
+/// "Coastline" in the display settings draws where the terrain meets the sea, just above the
+/// water, and logs how long the coasts are
+pub struct CoastlinePlugin;
+
+impl Plugin for CoastlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            systems::update_coastline_gizmo
+                .after(spawn_planet_on_event)
+                .after(rescale_planet_on_event),
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use super::CoastlineGizmo;
+use crate::mesh::helpers::surface_ribbon_mesh;
+use crate::planet::components::PlanetEntity;
+use crate::planet::events::PlanetSpawnedEvent;
+use crate::planet::logic::{SurfaceLayer, static_layer_offset};
+use crate::planet::resources::{CurrentPlanetData, DisplaySettings, PlanetAssetStores};
+use bevy::prelude::*;
+use planetgen::prelude::extract_coastline;
+
+/// Width of the lines as a share of the planet radius
+const LINE_WIDTH: f32 = 0.003;
+
+/// Rebuild the coastline when a new planet is spawned (the old lines went away with the old
+/// planet), when the sea level or the planet size changes, or when it is toggled
+pub fn update_coastline_gizmo(
+    mut planet_spawned_events: MessageReader<PlanetSpawnedEvent>,
+    settings: Res<DisplaySettings>,
+    current_planet_data: Res<CurrentPlanetData>,
+    (planet_query, existing_gizmos): (
+        Query<Entity, With<PlanetEntity>>,
+        Query<Entity, With<CoastlineGizmo>>,
+    ),
+    (mut meshes, mut materials, mut planet_assets): PlanetAssetStores,
+    mut commands: Commands,
+    // Whether the lines were shown, and the sea level and radius they were drawn at
+    mut drawn_for: Local<Option<(bool, Option<(f32, f32)>)>>,
+) {
+    let planet_spawned = planet_spawned_events.read().count() > 0;
+    let shape = current_planet_data.planet_data.as_ref().map(|planet| (planet.sea_level(), planet.radius));
+    let state = (settings.show_coastline, shape);
+    if !planet_spawned && *drawn_for == Some(state) {
+        return;
+    }
+    *drawn_for = Some(state);
+
+    for entity in existing_gizmos.iter() {
+        commands.entity(entity).despawn();
+    }
+    if !settings.show_coastline {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    let Some(planet_entity) = planet_query.iter().next() else {
+        return;
+    };
+
+    let coastline = extract_coastline(planet_data, planet_data.sea_level());
+    info!("{} coastline loops, {:.1} long", coastline.loops.len(), coastline.length);
+
+    let material = planet_assets.add_material(&mut materials, StandardMaterial {
+        base_color: Color::srgb(1.0, 0.3, 0.2),
+        unlit: true,
+        // Flat ribbons, seen from below where they pass behind a wave
+        cull_mode: None,
+        ..default()
+    });
+    let lift = static_layer_offset(SurfaceLayer::LatitudeRings, planet_data.radius);
+    let width = planet_data.radius * LINE_WIDTH;
+    for coast in &coastline.loops {
+        // Closed, the ribbon runs back to its first point
+        let points: Vec<Vec3> = coast
+            .points
+            .iter()
+            .chain(coast.points.first())
+            .map(|&point| point + point.normalize() * lift)
+            .collect();
+        let line = commands
+            .spawn((
+                Mesh3d(planet_assets.add_mesh(&mut meshes, surface_ribbon_mesh(&points, width))),
+                MeshMaterial3d(material.clone()),
+                Transform::default(),
+                CoastlineGizmo,
+            ))
+            .id();
+        commands.entity(planet_entity).add_child(line);
+    }
+}
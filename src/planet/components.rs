@@ -1,4 +1,7 @@
+use crate::planet::resources::{OverlayKind, PlanetGenerationId};
+use crate::planet::logic::SpinInertia;
 use bevy::prelude::*;
+use ocean::OceanConfig;
 
 #[derive(Component)]
 pub struct PlanetEntity;
@@ -6,6 +9,15 @@ pub struct PlanetEntity;
 #[derive(Component)]
 pub struct OceanEntity;
 
+/// What the ocean mesh was built from. Only a mesh of the same planet can have its waves
+/// updated in place, the depth tint comes from the terrain.
+#[derive(Component, Clone, Copy)]
+pub struct OceanMeshSource {
+    pub config: OceanConfig,
+    /// The stored planet the depth tint comes from, None for one that wasn't stored yet
+    pub generation: Option<PlanetGenerationId>,
+}
+
 #[derive(Component)]
 pub struct ContinentViewMesh;
 
@@ -15,6 +27,34 @@ pub struct PlateViewMesh;
 #[derive(Component)]
 pub struct ArrowEntity;
 
+/// An entity pinned to the planet surface in `direction` (planet space, unit length).
+/// It is hidden while that point is on the far side of the planet.
+#[derive(Component)]
+pub struct SurfaceAnchored {
+    pub direction: Vec3,
+}
+
+/// Terrain mesh of a view at every level of detail, finest first. The entity's Mesh3d holds
+/// the one at `level`.
+#[derive(Component)]
+pub struct TerrainLods {
+    pub meshes: Vec<Handle<Mesh>>,
+    pub level: usize,
+}
+
+/// Continent and ocean meshes, the overlays are colored copies of them
+pub type OverlaySourceMeshes<'w, 's> =
+    Query<'w, 's, &'static Mesh3d, Or<(With<ContinentViewMesh>, With<OceanEntity>)>>;
+
+/// Overlay entity showing the shared copy of `source` from `OverlayMeshes`. `colors` is the
+/// overlay whose vertex colors the copy needs while this entity is shown, None when the
+/// material brings a texture instead.
+#[derive(Component)]
+pub struct OverlayMesh {
+    pub source: AssetId<Mesh>,
+    pub colors: Option<OverlayKind>,
+}
+
 /// Marker component for entities that should only be visible in continent view mode
 #[derive(Component)]
 pub struct ContinentView;
@@ -38,9 +78,13 @@ pub struct PrecipitationView;
 #[derive(Component)]
 pub struct PlanetControls {
     pub rotation: Quat,
+    /// Rotation a camera focus is turning the planet to, None when the planet is left alone
+    pub target_rotation: Option<Quat>,
     pub zoom: f32,
     pub min_zoom: f32,
     pub max_zoom: f32,
+    /// Spin left over from the last drag
+    pub inertia: SpinInertia,
 }
 
 #[derive(Component)]
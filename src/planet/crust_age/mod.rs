@@ -0,0 +1,23 @@
+pub mod systems;
+
+use crate::planet::systems::{rescale_planet_on_event, spawn_planet_on_event, swap_in_full_resolution_planet};
+use bevy::prelude::*;
+
+/// Crust age tab: the planet colored by how old its ocean floor is
+pub struct CrustAgePlugin;
+
+impl Plugin for CrustAgePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                systems::handle_crust_age_tab_events,
+                // The new planet's meshes have to exist before they are copied
+                systems::regenerate_crust_age_meshes
+                    .after(spawn_planet_on_event)
+                    .after(swap_in_full_resolution_planet)
+                    .after(rescale_planet_on_event),
+            ),
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::mesh::helpers::mesh_positions;
+use crate::planet::components::{OverlayMesh, OverlaySourceMeshes, PlanetEntity};
+use crate::planet::events::{SetActiveView, TerrainLodChangedEvent, ViewKind};
+use crate::planet::resources::{
+    CurrentPlanetData, DisplaySettings, OverlayCacheKey, OverlayColorCache, OverlayKind, OverlayMeshes, PlanetAssets,
+    OverlayState, PlanetGenerationId,
+};
+use bevy::ecs::change_detection::Tick;
+use bevy::prelude::*;
+use planetgen::prelude::{Palette, PlanetData, crust_age_to_color};
+
+/// Marker component for crust age visualization meshes
+#[derive(Component)]
+pub struct CrustAgeMesh;
+
+/// Build the crust age overlay when its tab opens
+pub fn handle_crust_age_tab_events(
+    mut set_active_view: MessageReader<SetActiveView>,
+    settings: Res<DisplaySettings>,
+    planet_data: Res<CurrentPlanetData>,
+    mut color_cache: ResMut<OverlayColorCache>,
+    (mut overlay_meshes, mut planet_assets): (ResMut<OverlayMeshes>, ResMut<PlanetAssets>),
+    planet_query: Query<Entity, With<PlanetEntity>>,
+    source_query: OverlaySourceMeshes,
+    existing_meshes: Query<Entity, With<CrustAgeMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    // Only the last switch of the frame counts, every overlay ends up agreeing on it
+    let Some(&SetActiveView(view)) = set_active_view.read().last() else {
+        return;
+    };
+    if view != ViewKind::CrustAge || !existing_meshes.is_empty() {
+        return;
+    }
+    let Some(planet_entity) = planet_query.iter().next() else {
+        return;
+    };
+
+    spawn_crust_age_meshes(
+        planet_entity,
+        &source_query,
+        &planet_data,
+        settings.palette,
+        &mut color_cache,
+        &mut overlay_meshes,
+        &mut planet_assets,
+        &mut meshes,
+        &mut materials,
+        &mut commands,
+    );
+}
+
+/// Drop the overlay when the planet, the palette or the terrain LOD changes, and build it again
+/// right away if its tab is open. Hidden copies would show the old planet the next time the tab
+/// opens.
+pub fn regenerate_crust_age_meshes(
+    mut lod_events: MessageReader<TerrainLodChangedEvent>,
+    overlay_state: Res<OverlayState>,
+    settings: Res<DisplaySettings>,
+    planet_data: Res<CurrentPlanetData>,
+    mut built_for: Local<Option<(PlanetGenerationId, Palette)>>,
+    mut color_cache: ResMut<OverlayColorCache>,
+    (mut overlay_meshes, mut planet_assets): (ResMut<OverlayMeshes>, ResMut<PlanetAssets>),
+    planet_query: Query<Entity, With<PlanetEntity>>,
+    source_query: OverlaySourceMeshes,
+    existing_meshes: Query<Entity, With<CrustAgeMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    let lod_changed = lod_events.read().count() > 0;
+    let built = (planet_data.generation, settings.palette);
+    if !lod_changed && *built_for == Some(built) {
+        return;
+    }
+    *built_for = Some(built);
+
+    for entity in existing_meshes.iter() {
+        commands.entity(entity).despawn();
+    }
+    if overlay_state.active != ViewKind::CrustAge {
+        return;
+    }
+    let Some(planet_entity) = planet_query.iter().next() else {
+        return;
+    };
+
+    spawn_crust_age_meshes(
+        planet_entity,
+        &source_query,
+        &planet_data,
+        settings.palette,
+        &mut color_cache,
+        &mut overlay_meshes,
+        &mut planet_assets,
+        &mut meshes,
+        &mut materials,
+        &mut commands,
+    );
+}
+
+/// Spawn the crust age overlay on the shared copies of the continent and ocean meshes
+fn spawn_crust_age_meshes(
+    planet_entity: Entity,
+    source_query: &OverlaySourceMeshes,
+    current_planet_data: &CurrentPlanetData,
+    palette: Palette,
+    color_cache: &mut OverlayColorCache,
+    overlay_meshes: &mut OverlayMeshes,
+    planet_assets: &mut PlanetAssets,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    commands: &mut Commands,
+) {
+    let Some(planet) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    // Crust age comes from the planet alone, there is no cubemap or setting behind the colors
+    // other than the palette
+    let key = OverlayCacheKey::new(current_planet_data.generation, Tick::new(0), palette, &[]);
+
+    for mesh_handle in source_query.iter() {
+        let Some(original_mesh) = meshes.get(&mesh_handle.0) else {
+            continue;
+        };
+        color_cache.ensure_colors(OverlayKind::CrustAge, key, mesh_handle.0.id(), || {
+            crust_age_colors(original_mesh, planet, palette)
+        });
+        let Some(mesh) = overlay_meshes.copy_of(&mesh_handle.0, meshes, planet_assets) else {
+            continue;
+        };
+        let material = planet_assets.add_material(materials, StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            ..default()
+        });
+
+        let entity = commands
+            .spawn((
+                Mesh3d(mesh),
+                MeshMaterial3d(material),
+                Transform::default(),
+                GlobalTransform::default(),
+                Visibility::Visible,
+                CrustAgeMesh,
+                OverlayMesh {
+                    source: mesh_handle.0.id(),
+                    colors: Some(OverlayKind::CrustAge),
+                },
+            ))
+            .id();
+        commands.entity(planet_entity).add_child(entity);
+    }
+}
+
+fn crust_age_colors(original_mesh: &Mesh, planet: &PlanetData, palette: Palette) -> Vec<[f32; 4]> {
+    mesh_positions(original_mesh)
+        .iter()
+        .map(|&[x, y, z]| {
+            let color = crust_age_to_color(planet.crust_age(Vec3::new(x, y, z)), palette);
+            [color.x, color.y, color.z, 1.0]
+        })
+        .collect()
+}
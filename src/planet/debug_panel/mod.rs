@@ -0,0 +1,22 @@
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::ui::systems::render_planet_generation_ui;
+use bevy::prelude::*;
+use bevy_egui::EguiPrimaryContextPass;
+
+/// Foldable "Debug" window with the number of assets alive, to spot what regenerating the
+/// planet leaves behind
+pub struct DebugPanelPlugin;
+
+impl Plugin for DebugPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            systems::render_debug_panel
+                // Drawn first so the settings panel sees the pointer over it
+                .before(render_planet_generation_ui)
+                .run_if(in_state(GameState::PlanetGeneration)),
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use crate::planet::jobs::ClimateJobQueue;
+use crate::planet::resources::{CurrentPlanetData, PlanetAssets, PlanetGenerationId};
+use crate::ui::locale::Strings;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use planetgen::prelude::GenerationStats;
+
+const PANEL_MARGIN: f32 = 10.0;
+
+/// Window in the bottom left corner with the assets of each kind alive and how many of them
+/// belong to the current planet, the climate jobs running and waiting and the generation
+/// statistics of the planet
+pub fn render_debug_panel(
+    mut contexts: EguiContexts,
+    planet_assets: Res<PlanetAssets>,
+    (climate_jobs, strings): (Res<ClimateJobQueue>, Res<Strings>),
+    (planet_data, mut stats): (Res<CurrentPlanetData>, Local<Option<(PlanetGenerationId, GenerationStats)>>),
+    meshes: Res<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    images: Res<Assets<Image>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    // Measuring goes over every cell, once per planet
+    if let Some(planet) = planet_data.planet_data.as_ref()
+        && stats.is_none_or(|(generation, _)| generation != planet_data.generation)
+    {
+        *stats = Some((planet_data.generation, planet.generation_stats()));
+    }
+
+    egui::Window::new(strings.get("debug.window"))
+        .id(egui::Id::new("debug_window"))
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(PANEL_MARGIN, -PANEL_MARGIN))
+        .default_open(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let planet = planet_assets.current_counts(&meshes, &materials, &images);
+            egui::Grid::new("asset_counts")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(strings.get("debug.assets"));
+                    ui.label(strings.get("debug.alive"));
+                    ui.label(strings.get("debug.planet"));
+                    ui.end_row();
+
+                    for (label, alive, of_planet) in [
+                        ("debug.meshes", meshes.len(), planet.meshes),
+                        ("debug.materials", materials.len(), planet.materials),
+                        ("debug.images", images.len(), planet.images),
+                    ] {
+                        ui.label(strings.get(label));
+                        ui.label(alive.to_string());
+                        ui.label(of_planet.to_string());
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+            let pending: Vec<&str> = climate_jobs.pending().map(|kind| strings.get(kind.label())).collect();
+            egui::Grid::new("climate_jobs")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(strings.get("debug.climate_job"));
+                    ui.label(climate_jobs.running().map_or("-", |kind| strings.get(kind.label())));
+                    ui.end_row();
+                    ui.label(strings.get("debug.queued"));
+                    ui.label(if pending.is_empty() { "-".to_string() } else { pending.join(", ") });
+                    ui.end_row();
+                    ui.label(strings.get("debug.finished"));
+                    ui.label(climate_jobs.finished.to_string());
+                    ui.end_row();
+                    ui.label(strings.get("debug.cancelled"));
+                    ui.label(climate_jobs.cancelled.to_string());
+                    ui.end_row();
+                });
+
+            let Some((_, stats)) = stats.as_ref() else {
+                return;
+            };
+            ui.separator();
+            egui::Grid::new("generation_stats")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (label, value) in [
+                        ("debug.boundaries", format!("{:.3} rad", stats.boundary_length)),
+                        ("debug.convergent", format!("{:.3} rad", stats.convergent_length)),
+                        ("debug.divergent", format!("{:.3} rad", stats.divergent_length)),
+                        ("debug.transform", format!("{:.3} rad", stats.transform_length)),
+                        ("debug.triple_junctions", stats.triple_junctions.to_string()),
+                        ("debug.compactness", format!("{:.4}", stats.mean_compactness)),
+                        ("debug.land", format!("{:.1}%", stats.land_fraction * 100.0)),
+                    ] {
+                        ui.label(strings.get(label));
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                });
+        });
+}
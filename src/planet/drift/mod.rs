@@ -0,0 +1,67 @@
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::crust_age::systems::regenerate_crust_age_meshes;
+use crate::planet::systems::{rescale_planet_on_event, spawn_planet_on_event, swap_in_full_resolution_planet};
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use std::time::Duration;
+
+/// How often the plates move while the drift is playing
+pub const DRIFT_STEPS_PER_SECOND: f64 = 4.0;
+
+/// Plate time simulated per step. The fastest plates turn by 1 rad per unit, so a step moves
+/// them about a cell on a mid-sized planet and the slow ones every few steps.
+pub const DRIFT_STEP_DT: f32 = 0.02;
+
+/// Play/pause state of the plate drift, which moves the plates of the current planet
+#[derive(Resource, Default)]
+pub struct PlateDriftState {
+    pub running: bool,
+    /// Single steps requested while paused, taken on the next ticks
+    pub pending_steps: u32,
+    /// Steps taken since the planet was generated
+    pub step: u64,
+}
+
+impl PlateDriftState {
+    /// Whether this tick moves the plates, uses up a requested single step
+    pub fn take_step(&mut self) -> bool {
+        if self.running {
+            return true;
+        }
+        if self.pending_steps > 0 {
+            self.pending_steps -= 1;
+            return true;
+        }
+        false
+    }
+
+    /// A new planet came in, it starts paused at step 0
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Tectonic tab: the plates of the generated planet drifting apart and into each other
+pub struct DriftPlugin;
+
+impl Plugin for DriftPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlateDriftState>().add_systems(
+            Update,
+            (
+                systems::reset_drift_on_new_planet
+                    .after(spawn_planet_on_event)
+                    .after(swap_in_full_resolution_planet),
+                systems::step_plate_drift
+                    .after(systems::reset_drift_on_new_planet)
+                    .after(rescale_planet_on_event)
+                    // Overlays copy the terrain meshes, they have to be refreshed first
+                    .before(regenerate_crust_age_meshes)
+                    .run_if(on_timer(Duration::from_secs_f64(1.0 / DRIFT_STEPS_PER_SECOND)))
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            ),
+        );
+    }
+}
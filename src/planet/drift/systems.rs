@@ -0,0 +1,60 @@
+use super::{DRIFT_STEP_DT, PlateDriftState};
+use crate::planet::components::{ContinentViewMesh, PlateViewMesh, TerrainLods};
+use crate::planet::events::PlanetSpawnedEvent;
+use crate::planet::resources::{
+    ClimateSettings, CurrentPlanetData, DisplaySettings, PendingPlanetGeneration, TerrainSettings,
+};
+use crate::planet::systems::refresh_terrain_lods;
+use crate::planet::temperature::systems::TemperatureCubeMap;
+use bevy::prelude::*;
+use planetgen::prelude::ViewMode;
+
+/// A new planet starts with its plates where the generator put them, paused
+pub fn reset_drift_on_new_planet(
+    mut planet_spawned_events: MessageReader<PlanetSpawnedEvent>,
+    mut drift: ResMut<PlateDriftState>,
+) {
+    if planet_spawned_events.read().count() > 0 {
+        drift.reset();
+    }
+}
+
+/// Move the plates by one step while the drift is playing or a single step was requested, then
+/// refresh the heights and colors of the continent and plate meshes in place
+pub fn step_plate_drift(
+    mut drift: ResMut<PlateDriftState>,
+    pending_generation: Res<PendingPlanetGeneration>,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
+    temperature_cubemap: Option<Res<TemperatureCubeMap>>,
+    mut current_planet_data: ResMut<CurrentPlanetData>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    continent_lods: Query<&TerrainLods, With<ContinentViewMesh>>,
+    plate_lods: Query<&TerrainLods, With<PlateViewMesh>>,
+) {
+    // The full resolution planet is on its way and would replace whatever the preview drifted to
+    if pending_generation.task.is_some() {
+        return;
+    }
+    // Checked through a shared borrow, borrowing the planet mutably marks it changed and every
+    // climate map depending on it would be rebuilt on each paused tick
+    if current_planet_data.planet_data.is_none() || !drift.take_step() {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_mut() else {
+        return;
+    };
+
+    planet_data.step_tectonics(DRIFT_STEP_DT);
+    drift.step += 1;
+
+    let temperature = temperature_cubemap.as_ref().map(|cubemap| &cubemap.inner);
+    let color_settings = (&*terrain_settings, &*climate_settings, &*display_settings);
+    for lods in &continent_lods {
+        refresh_terrain_lods(planet_data, ViewMode::Continents, color_settings, temperature, lods, &mut meshes);
+    }
+    for lods in &plate_lods {
+        let view_mode = display_settings.plate_view_mode();
+        refresh_terrain_lods(planet_data, view_mode, color_settings, None, lods, &mut meshes);
+    }
+    current_planet_data.changed_in_place();
+}
@@ -1,4 +1,8 @@
+use crate::planet::precipitation::systems::PrecipitationCubeMap;
+use crate::planet::wind::systems::{MountainInfluence, WindCubeMap};
 use bevy::prelude::*;
+use planetgen::prelude::PrecipitationCubeMap as PlanetgenPrecipitationCubeMap;
+use serde::{Deserialize, Serialize};
 
 #[derive(Message)]
 pub struct GeneratePlanetEvent;
@@ -6,6 +10,21 @@ pub struct GeneratePlanetEvent;
 #[derive(Message)]
 pub struct GenerateNewSeedEvent;
 
+/// Generate the planet again with a new seed for one part of it, the other part stays
+#[derive(Message, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RerollPlanetEvent {
+    /// New terrain on the plates of the current planet
+    Terrain,
+    /// New plates under the same terrain noise
+    Plates,
+}
+
+/// Resize the existing planet without regenerating it
+#[derive(Message)]
+pub struct RescalePlanetEvent {
+    pub radius: f32,
+}
+
 #[derive(Message)]
 pub struct ToggleArrowsEvent {
     pub show_arrows: bool,
@@ -16,46 +35,128 @@ pub struct SetCameraPositionEvent {
     pub position: Vec3,
 }
 
-#[derive(Message)]
-pub struct SettingsChanged;
+/// Logical group of planet settings fields that downstream systems react to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsGroup {
+    /// Anything baked into the generated terrain, applied on Generate
+    Terrain,
+    /// Appearance of the ocean mesh
+    Ocean,
+    Temperature,
+    Precipitation,
+    /// Resolution of the climate cubemaps, the existing maps are resampled instead of rebuilt
+    ClimateResolution,
+    Wind,
+    /// Mountain deflection of the wind, rebuilds the wind cubemap and what is derived from it
+    WindDeflection,
+    /// Biome thresholds and colors, and the relief shading on top of them
+    Biome,
+    /// Overlay toggles, view mode and other presentation-only values
+    Visual,
+    /// Framing and handling of the camera, read by the camera systems as they go
+    Camera,
+    /// Theme and language of the interface
+    Ui,
+    /// Coloring of the tectonic plate view, rebuilds the plate meshes
+    PlateView,
+    /// Color palette of the overlays and the plate view, recolors both
+    Palette,
+    /// Number, sizes and orbits of the moons
+    Moons,
+}
+
+/// Sent once per settings change with every group whose fields differ from the last applied settings
+#[derive(Message, Clone, Debug)]
+pub struct SettingsDiff {
+    pub changed: Vec<SettingsGroup>,
+}
+
+impl SettingsDiff {
+    pub fn contains(&self, group: SettingsGroup) -> bool {
+        self.changed.contains(&group)
+    }
+
+    /// Read all pending diffs and tell whether any of them touches one of `groups`.
+    /// Everything is read so the same change isn't handled again next frame.
+    pub fn read_any(reader: &mut MessageReader<SettingsDiff>, groups: &[SettingsGroup]) -> bool {
+        reader
+            .read()
+            .filter(|diff| groups.iter().any(|&group| diff.contains(group)))
+            .count()
+            > 0
+    }
+
+    /// Read all pending diffs and collect every group they touch, for systems that react to
+    /// some groups differently than to others
+    pub fn read_groups(reader: &mut MessageReader<SettingsDiff>) -> Vec<SettingsGroup> {
+        let mut groups = Vec::new();
+        for group in reader.read().flat_map(|diff| &diff.changed) {
+            if !groups.contains(group) {
+                groups.push(*group);
+            }
+        }
+        groups
+    }
+}
 
-#[derive(Message, Clone, Copy, Debug, PartialEq)]
-pub enum ViewTabType {
+/// The views of the planet tab bar, each shows one overlay (or none)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ViewKind {
+    #[default]
     Continent,
     Tectonic,
     Wind,
     Temperature,
     Precipitations,
     Biomes,
+    /// Age of the ocean floor by distance from the mid-ocean ridges
+    CrustAge,
 }
 
-#[derive(Message)]
-pub struct TabSwitchEvent {
-    pub tab: ViewTabType,
-}
+/// Switch the planet to another view. The only way to change the active view, every overlay
+/// reacts to it, so there is no way to end up with two of them active at once.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct SetActiveView(pub ViewKind);
 
 #[derive(Message)]
-pub struct WindTabActiveEvent {
-    pub active: bool,
-}
+pub struct PlanetSpawnedEvent;
 
+/// The visible terrain meshes switched to another level of detail
 #[derive(Message)]
-pub struct TectonicTabActiveEvent {
-    pub active: bool,
-}
+pub struct TerrainLodChangedEvent;
 
 #[derive(Message)]
-pub struct TemperatureTabActiveEvent {
-    pub active: bool,
-}
+pub struct ResetCameraEvent;
 
-#[derive(Message)]
-pub struct PrecipitationTabActiveEvent {
-    pub active: bool,
+/// What the camera shortcuts turn towards the camera
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraFocus {
+    /// Back to the framing after generation
+    Planet,
+    NorthPole,
+    SouthPole,
+    HighestPeak,
+    LargestContinent,
+    /// The middle of the plate with this id
+    Plate(usize),
 }
 
-#[derive(Message)]
-pub struct PlanetSpawnedEvent;
+/// Turn the planet so the focus faces the camera, smoothly
+#[derive(Message, Clone, Copy, Debug)]
+pub struct FocusCameraEvent(pub CameraFocus);
 
-#[derive(Message)]
-pub struct ResetCameraEvent;
+/// A wind rebuild job finished: the wind deflected by the terrain and the mountain influence
+/// that comes with it
+#[derive(Message, Clone)]
+pub struct WindRebuilt {
+    pub wind: WindCubeMap,
+    pub influence: MountainInfluence,
+}
+
+/// A precipitation rebuild job finished
+#[derive(Message, Clone)]
+pub enum PrecipitationRebuilt {
+    Annual(Box<PrecipitationCubeMap>),
+    /// One map per phase of the year, from the northern spring equinox on
+    Seasons(Vec<PlanetgenPrecipitationCubeMap>),
+}
@@ -0,0 +1,40 @@
+use crate::planet::events::ViewKind;
+
+/// The view `step` tabs after `current` in `views`, wrapping around at both ends. A view that
+/// isn't in the list counts as the first one.
+pub fn cycle_view(views: &[ViewKind], current: ViewKind, step: isize) -> ViewKind {
+    let position = views.iter().position(|&view| view == current).unwrap_or(0);
+    views[(position as isize + step).rem_euclid(views.len() as isize) as usize]
+}
+
+/// Zoom speed of the two triggers, each 0 to 1: the right one zooms in, the left one out
+pub fn trigger_zoom(left: f32, right: f32) -> f32 {
+    (right - left).clamp(-1.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const VIEWS: [ViewKind; 3] = [ViewKind::Continent, ViewKind::Tectonic, ViewKind::Wind];
+
+    #[rstest]
+    #[case::next(ViewKind::Continent, 1, ViewKind::Tectonic)]
+    #[case::previous(ViewKind::Tectonic, -1, ViewKind::Continent)]
+    #[case::past_the_last(ViewKind::Wind, 1, ViewKind::Continent)]
+    #[case::before_the_first(ViewKind::Continent, -1, ViewKind::Wind)]
+    #[case::not_listed(ViewKind::Biomes, 1, ViewKind::Tectonic)]
+    fn test_cycle_view(#[case] current: ViewKind, #[case] step: isize, #[case] expected: ViewKind) {
+        assert_eq!(cycle_view(&VIEWS, current, step), expected);
+    }
+
+    #[rstest]
+    #[case::released(0.0, 0.0, 0.0)]
+    #[case::zoom_in(0.0, 0.75, 0.75)]
+    #[case::zoom_out(1.0, 0.0, -1.0)]
+    #[case::both_pulled(0.5, 1.0, 0.5)]
+    fn test_trigger_zoom(#[case] left: f32, #[case] right: f32, #[case] expected: f32) {
+        assert_eq!(trigger_zoom(left, right), expected);
+    }
+}
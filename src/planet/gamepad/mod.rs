@@ -0,0 +1,41 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::systems::planet_control;
+use bevy::prelude::*;
+
+/// Turn speed of a fully pushed right stick, in radians a second at a zoom of 60. Closer in it
+/// turns slower, like a drag.
+pub const STICK_TURN_SPEED: f32 = 1.5;
+
+/// Wheel notches a second a fully pulled trigger zooms by
+pub const TRIGGER_ZOOM_SPEED: f32 = 8.0;
+
+/// Turning and zooming the gamepads ask for this frame, `planet_control` applies it along with
+/// the mouse
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct GamepadView {
+    /// Right stick sideways, -1 to 1
+    pub turn: f32,
+    /// Right trigger minus left trigger, -1 to 1, positive zooms in
+    pub zoom: f32,
+}
+
+/// Couch controls of the planet: the right stick turns it, the triggers zoom, the shoulder
+/// buttons switch tabs and Start generates. The left stick moves through the settings panel,
+/// see `FocusPlugin`.
+pub struct GamepadControlsPlugin;
+
+impl Plugin for GamepadControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadView>().add_systems(
+            Update,
+            (
+                systems::read_gamepad_view.before(planet_control),
+                systems::handle_gamepad_buttons,
+            )
+                .run_if(in_state(GameState::PlanetGeneration)),
+        );
+    }
+}
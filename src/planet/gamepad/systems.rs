@@ -0,0 +1,47 @@
+use super::{GamepadView, logic};
+use crate::planet::events::{GeneratePlanetEvent, SetActiveView};
+use crate::planet::resources::OverlayState;
+use crate::planet::ui::systems::VIEW_TABS;
+use bevy::prelude::*;
+
+/// Right stick and triggers of every connected gamepad, added up
+pub fn read_gamepad_view(gamepads: Query<&Gamepad>, mut view: ResMut<GamepadView>) {
+    let mut turn = 0.0;
+    let mut zoom = 0.0;
+    for gamepad in &gamepads {
+        turn += gamepad.right_stick().x;
+        zoom += logic::trigger_zoom(
+            gamepad.get(GamepadButton::LeftTrigger2).unwrap_or(0.0),
+            gamepad.get(GamepadButton::RightTrigger2).unwrap_or(0.0),
+        );
+    }
+    view.set_if_neq(GamepadView {
+        turn: turn.clamp(-1.0, 1.0),
+        zoom: zoom.clamp(-1.0, 1.0),
+    });
+}
+
+/// The shoulder buttons step through the tabs like clicking them, Start generates like the
+/// Generate Planet button
+pub fn handle_gamepad_buttons(
+    gamepads: Query<&Gamepad>,
+    overlay_state: Res<OverlayState>,
+    mut set_active_view: MessageWriter<SetActiveView>,
+    mut planet_generation_events: MessageWriter<GeneratePlanetEvent>,
+) {
+    let mut step = 0;
+    let mut generate = false;
+    for gamepad in &gamepads {
+        step += gamepad.just_pressed(GamepadButton::RightTrigger) as isize;
+        step -= gamepad.just_pressed(GamepadButton::LeftTrigger) as isize;
+        generate |= gamepad.just_pressed(GamepadButton::Start);
+    }
+
+    if step != 0 {
+        let views = VIEW_TABS.map(|(view, _)| view);
+        set_active_view.write(SetActiveView(logic::cycle_view(&views, overlay_state.active, step)));
+    }
+    if generate {
+        planet_generation_events.write(GeneratePlanetEvent);
+    }
+}
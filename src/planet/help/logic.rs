@@ -0,0 +1,56 @@
+use crate::planet::events::ViewKind;
+use crate::ui::locale::StringId;
+
+/// Inputs of the planet view and what they do, in the order the help lists them
+pub const CONTROLS: [(StringId, StringId); 12] = [
+    ("help.input.left_drag", "help.action.left_drag"),
+    ("help.input.mouse_wheel", "help.action.mouse_wheel"),
+    ("help.input.click", "help.action.click"),
+    ("help.input.pin_click", "help.action.pin_click"),
+    ("help.input.alt_click", "help.action.alt_click"),
+    ("help.input.keyboard", "help.action.keyboard"),
+    ("help.input.right_stick", "help.action.right_stick"),
+    ("help.input.triggers", "help.action.triggers"),
+    ("help.input.bumpers", "help.action.bumpers"),
+    ("help.input.start", "help.action.start"),
+    ("help.input.gamepad_navigation", "help.action.gamepad_navigation"),
+    ("help.input.f1", "help.action.f1"),
+];
+
+/// What `tab` shows and what its settings change. A match, so a new tab doesn't build without
+/// its help.
+pub fn tab_help(tab: ViewKind) -> StringId {
+    match tab {
+        ViewKind::Continent => "help.tab.continent",
+        ViewKind::Tectonic => "help.tab.tectonic",
+        ViewKind::Wind => "help.tab.wind",
+        ViewKind::Temperature => "help.tab.temperature",
+        ViewKind::Precipitations => "help.tab.precipitation",
+        ViewKind::Biomes => "help.tab.biomes",
+        ViewKind::CrustAge => "help.tab.crust_age",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planet::ui::systems::VIEW_TABS;
+    use crate::ui::locale::{Language, Strings};
+
+    #[test]
+    fn test_every_tab_has_help() {
+        let strings = Strings::load(Language::English);
+        for (tab, label) in VIEW_TABS {
+            assert_ne!(strings.get(tab_help(tab)), tab_help(tab), "no help for the {label} tab");
+        }
+    }
+
+    #[test]
+    fn test_every_control_has_its_texts() {
+        let strings = Strings::load(Language::English);
+        for (input, action) in CONTROLS {
+            assert_ne!(strings.get(input), input);
+            assert_ne!(strings.get(action), action);
+        }
+    }
+}
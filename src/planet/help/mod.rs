@@ -0,0 +1,55 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::ui::systems::render_planet_generation_ui;
+use bevy::prelude::*;
+use bevy_egui::EguiPrimaryContextPass;
+
+/// Whether the help overlay is open, and whether it opens by itself when the game starts.
+/// `AppSettingsPlugin` restores it from the last run.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HelpOverlay {
+    pub open: bool,
+    pub show_on_startup: bool,
+}
+
+impl HelpOverlay {
+    pub fn new(show_on_startup: bool) -> Self {
+        Self {
+            open: show_on_startup,
+            show_on_startup,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+}
+
+impl Default for HelpOverlay {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+/// F1 or the "?" next to the settings heading opens a panel over the whole window with the
+/// controls and what the open tab shows. While it's open the planet doesn't react to the mouse.
+pub struct HelpPlugin;
+
+impl Plugin for HelpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HelpOverlay>()
+            .add_systems(
+                Update,
+                systems::toggle_help_on_key.run_if(in_state(GameState::PlanetGeneration)),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                systems::render_help_overlay
+                    // Takes over the pointer the settings panel reported
+                    .after(render_planet_generation_ui)
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            );
+    }
+}
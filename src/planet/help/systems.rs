@@ -0,0 +1,73 @@
+use super::HelpOverlay;
+use super::logic::{CONTROLS, tab_help};
+use crate::planet::resources::OverlayState;
+use crate::planet::ui::systems::{PointerOverUi, VIEW_TABS};
+use crate::ui::locale::Strings;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+const PANEL_WIDTH: f32 = 460.0;
+
+pub fn toggle_help_on_key(keys: Res<ButtonInput<KeyCode>>, mut help: ResMut<HelpOverlay>) {
+    if keys.just_pressed(KeyCode::F1) {
+        help.toggle();
+    }
+}
+
+/// Controls and a word on the open tab, over a dimmed window. Escape or a click beside the
+/// panel closes it. Nothing under it gets the pointer: egui keeps it from the other panels, and
+/// `PointerOverUi` from the planet.
+pub fn render_help_overlay(
+    mut contexts: EguiContexts,
+    mut help: ResMut<HelpOverlay>,
+    overlay_state: Res<OverlayState>,
+    strings: Res<Strings>,
+    mut pointer_over_ui: ResMut<PointerOverUi>,
+) {
+    if !help.open {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    pointer_over_ui.0 = true;
+
+    let tab_label = VIEW_TABS
+        .iter()
+        .find(|(view, _)| *view == overlay_state.active)
+        .map_or("", |(_, label)| strings.get(label));
+    let mut hide_on_startup = !help.show_on_startup;
+    let mut close_clicked = false;
+    let response = egui::Modal::new(egui::Id::new("help_overlay")).show(ctx, |ui| {
+        ui.set_width(PANEL_WIDTH);
+        ui.heading(strings.get("help.controls"));
+        ui.add_space(6.0);
+        egui::Grid::new("help_controls").num_columns(2).striped(true).show(ui, |ui| {
+            for (input, action) in CONTROLS {
+                ui.strong(strings.get(input));
+                ui.label(strings.get(action));
+                ui.end_row();
+            }
+        });
+
+        ui.add_space(12.0);
+        ui.heading(strings.format("help.tab_heading", &[("tab", &tab_label)]));
+        ui.add_space(6.0);
+        ui.label(strings.get(tab_help(overlay_state.active)));
+
+        ui.add_space(12.0);
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut hide_on_startup, strings.get("help.hide_on_startup"));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                close_clicked = ui.button(strings.get("help.close")).clicked();
+            });
+        });
+    });
+
+    let closed = close_clicked || response.should_close();
+    help.set_if_neq(HelpOverlay {
+        open: !closed,
+        show_on_startup: !hide_on_startup,
+    });
+}
@@ -0,0 +1,373 @@
+use crate::planet::logic::grid_budget;
+use crate::planet::resources::{PlanetSettingsSnapshot, TerrainSettings};
+use crate::ui::locale::Strings;
+use planetgen::config::PlanetGenConfig;
+use planetgen::prelude::{GenerationStats, PlanetData, WorldType};
+use serde::{Deserialize, Serialize};
+
+/// How many generations are kept, older ones are dropped
+pub const MAX_HISTORY_ENTRIES: usize = 50;
+
+const NAME_STARTS: [&str; 16] = [
+    "Ar", "Bel", "Cor", "Dra", "El", "Fen", "Gal", "Hel", "Ith", "Kor", "Lun", "Mar", "Nor", "Pel", "Sol", "Vel",
+];
+const NAME_MIDDLES: [&str; 8] = ["a", "e", "i", "o", "u", "ae", "io", "ar"];
+const NAME_ENDS: [&str; 12] = ["ra", "nis", "thos", "via", "mir", "dor", "lia", "x", "ron", "sa", "tis", "nor"];
+
+/// One generated planet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub name: String,
+    /// Seconds since the Unix epoch
+    pub timestamp_secs: u64,
+    /// Fraction of the surface above sea level
+    pub land_fraction: f32,
+    pub params: GenerationParams,
+    /// None in files from before the statistics were recorded
+    #[serde(default)]
+    pub stats: Option<GenerationStats>,
+}
+
+/// Everything the planet generator reads from the terrain settings, so the same planet can be
+/// generated again. Has to follow the `PlanetGenerator` made from [`TerrainSettings`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationParams {
+    pub user_seed: u32,
+    pub seed: u64,
+    /// None in files from before the plates and the terrain had seeds of their own, both
+    /// followed `user_seed` then
+    #[serde(default)]
+    pub plate_seed: Option<u32>,
+    #[serde(default)]
+    pub terrain_seed: Option<u32>,
+    pub radius: f32,
+    pub oblateness: f32,
+    pub world_type: WorldType,
+    pub num_plates: usize,
+    pub num_micro_plates: usize,
+    pub flow_warp_freq: f32,
+    pub flow_warp_steps: usize,
+    pub flow_warp_step_angle: f32,
+    pub continent_frequency: f32,
+    pub continent_amplitude: f32,
+    pub distortion_frequency: f32,
+    pub distortion_amplitude: f32,
+    pub continent_threshold: f32,
+    pub detail_frequency: f32,
+    pub detail_amplitude: f32,
+    pub ocean_depth_amplitude: f32,
+    pub mountain_height: f32,
+    pub mountain_width: f32,
+}
+
+/// Fields missing in an older history file get today's defaults
+impl Default for GenerationParams {
+    fn default() -> Self {
+        Self::from_settings(&TerrainSettings::default())
+    }
+}
+
+impl GenerationParams {
+    pub fn from_settings(settings: &TerrainSettings) -> Self {
+        Self {
+            user_seed: settings.user_seed,
+            seed: settings.seed,
+            plate_seed: Some(settings.plate_seed),
+            terrain_seed: Some(settings.terrain_seed),
+            radius: settings.radius,
+            oblateness: settings.oblateness,
+            world_type: settings.world_type,
+            num_plates: settings.num_plates,
+            num_micro_plates: settings.num_micro_plates,
+            flow_warp_freq: settings.flow_warp_freq,
+            flow_warp_steps: settings.flow_warp_steps,
+            flow_warp_step_angle: settings.flow_warp_step_angle,
+            continent_frequency: settings.continent_frequency,
+            continent_amplitude: settings.continent_amplitude,
+            distortion_frequency: settings.distortion_frequency,
+            distortion_amplitude: settings.distortion_amplitude,
+            continent_threshold: settings.continent_threshold,
+            detail_frequency: settings.detail_frequency,
+            detail_amplitude: settings.detail_amplitude,
+            ocean_depth_amplitude: settings.ocean_depth_amplitude,
+            mountain_height: settings.mountain_height,
+            mountain_width: settings.mountain_width,
+        }
+    }
+
+    /// Put the parameters back, everything else in the settings stays as it is
+    pub fn apply_to(&self, settings: &mut TerrainSettings) {
+        settings.user_seed = self.user_seed;
+        settings.seed = self.seed;
+        settings.plate_seed = self.plate_seed.unwrap_or(self.user_seed);
+        settings.terrain_seed = self.terrain_seed.unwrap_or(self.user_seed);
+        settings.radius = self.radius;
+        settings.oblateness = self.oblateness;
+        settings.world_type = self.world_type;
+        settings.num_plates = self.num_plates;
+        settings.num_micro_plates = self.num_micro_plates;
+        settings.flow_warp_freq = self.flow_warp_freq;
+        settings.flow_warp_steps = self.flow_warp_steps;
+        settings.flow_warp_step_angle = self.flow_warp_step_angle;
+        settings.continent_frequency = self.continent_frequency;
+        settings.continent_amplitude = self.continent_amplitude;
+        settings.distortion_frequency = self.distortion_frequency;
+        settings.distortion_amplitude = self.distortion_amplitude;
+        settings.continent_threshold = self.continent_threshold;
+        settings.detail_frequency = self.detail_frequency;
+        settings.detail_amplitude = self.detail_amplitude;
+        settings.ocean_depth_amplitude = self.ocean_depth_amplitude;
+        settings.mountain_height = self.mountain_height;
+        settings.mountain_width = self.mountain_width;
+    }
+}
+
+/// A pronounceable name picked by the seed, the same seed always gets the same name
+pub fn planet_name(seed: u64) -> String {
+    let start = NAME_STARTS[(seed % NAME_STARTS.len() as u64) as usize];
+    let middle = NAME_MIDDLES[((seed >> 16) % NAME_MIDDLES.len() as u64) as usize];
+    let end = NAME_ENDS[((seed >> 32) % NAME_ENDS.len() as u64) as usize];
+    format!("{start}{middle}{end}")
+}
+
+/// Add the newest entry at the end, dropping the oldest past `MAX_HISTORY_ENTRIES`
+pub fn push_entry(entries: &mut Vec<HistoryEntry>, entry: HistoryEntry) {
+    entries.push(entry);
+    trim(entries);
+}
+
+/// Entries read from disk come before the ones recorded while the file was loading
+pub fn merge_loaded(loaded: Vec<HistoryEntry>, recorded: Vec<HistoryEntry>) -> Vec<HistoryEntry> {
+    let mut entries = loaded;
+    entries.extend(recorded);
+    trim(&mut entries);
+    entries
+}
+
+fn trim(entries: &mut Vec<HistoryEntry>) {
+    let excess = entries.len().saturating_sub(MAX_HISTORY_ENTRIES);
+    entries.drain(..excess);
+}
+
+/// How long ago `timestamp_secs` was, for the history list
+pub fn format_age(now_secs: u64, timestamp_secs: u64, strings: &Strings) -> String {
+    let age = now_secs.saturating_sub(timestamp_secs);
+    match age {
+        0..60 => strings.get("age.just_now").to_string(),
+        60..3600 => strings.format("age.minutes", &[("count", &(age / 60))]),
+        3600..86400 => strings.format("age.hours", &[("count", &(age / 3600))]),
+        86400..172800 => strings.get("age.one_day").to_string(),
+        _ => strings.format("age.days", &[("count", &(age / 86400))]),
+    }
+}
+
+pub fn to_ron(entries: &[HistoryEntry]) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(entries, ron::ser::PrettyConfig::default())
+}
+
+pub fn from_ron(text: &str) -> Result<Vec<HistoryEntry>, ron::error::SpannedError> {
+    ron::from_str(text)
+}
+
+/// Version of the game, recorded with every planet
+pub const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Everything a planet was generated from and what came out of it, logged and written to
+/// `GENERATION_RECORD_FILE` for every planet, `--reproduce` generates the planet again from it.
+/// Unlike [`GenerationParams`] it holds all of the settings and the generator config, a seed
+/// alone makes another planet once their defaults change.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GenerationRecord {
+    /// Version of the game that generated the planet
+    pub version: String,
+    pub settings: PlanetSettingsSnapshot,
+    /// `planetgen_config.toml` as it was loaded
+    pub config: PlanetGenConfig,
+    pub derived: DerivedValues,
+    pub stats: GenerationStats,
+}
+
+/// What the generator made of the settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DerivedValues {
+    /// Vertices along one face edge the radius asks for
+    pub requested_face_grid_size: usize,
+    /// Vertices along one face edge after the `max_face_grid_size` clamp
+    pub face_grid_size: usize,
+    /// Height of the ocean surface, the continent threshold the terrain was cut at
+    pub sea_level: f32,
+}
+
+impl DerivedValues {
+    pub fn of(planet: &PlanetData) -> Self {
+        Self {
+            requested_face_grid_size: grid_budget(planet.radius).requested,
+            face_grid_size: planet.face_grid_size,
+            sea_level: planet.sea_level(),
+        }
+    }
+}
+
+impl GenerationRecord {
+    /// The whole record on one line, for the log
+    pub fn to_log_line(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string(self)
+    }
+
+    /// Version that wrote the record when it isn't this one, the same settings may make
+    /// another planet then
+    pub fn other_version(&self) -> Option<&str> {
+        (self.version != GAME_VERSION).then_some(self.version.as_str())
+    }
+}
+
+pub fn record_to_ron(record: &GenerationRecord) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(record, ron::ser::PrettyConfig::default())
+}
+
+pub fn record_from_ron(text: &str) -> Result<GenerationRecord, ron::error::SpannedError> {
+    ron::from_str(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn entry(name: &str) -> HistoryEntry {
+        HistoryEntry {
+            name: name.to_string(),
+            timestamp_secs: 1_700_000_000,
+            land_fraction: 0.3,
+            params: GenerationParams::from_settings(&TerrainSettings::default()),
+            stats: Some(GenerationStats {
+                boundary_length: 12.5,
+                convergent_length: 5.0,
+                divergent_length: 4.5,
+                transform_length: 3.0,
+                triple_junctions: 6,
+                mean_compactness: 0.07,
+                land_fraction: 0.3,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_regenerating_restores_the_generation_params() {
+        let original = TerrainSettings::default();
+        let params = GenerationParams::from_settings(&original);
+
+        let mut settings = TerrainSettings {
+            user_seed: original.user_seed.wrapping_add(1),
+            seed: original.seed.wrapping_add(1),
+            terrain_seed: original.terrain_seed.wrapping_add(1),
+            continent_threshold: original.continent_threshold + 0.2,
+            num_plates: original.num_plates + 3,
+            ..original.clone()
+        };
+        params.apply_to(&mut settings);
+
+        assert_eq!(GenerationParams::from_settings(&settings), params);
+    }
+
+    #[test]
+    fn test_older_entries_use_the_seed_for_plates_and_terrain() {
+        let params: GenerationParams = ron::from_str("(user_seed: 12345, seed: 99)").unwrap();
+        let mut settings = TerrainSettings::default();
+
+        params.apply_to(&mut settings);
+
+        assert_eq!((settings.plate_seed, settings.terrain_seed), (12345, 12345));
+    }
+
+    #[test]
+    fn test_history_survives_a_ron_round_trip() {
+        let entries = vec![entry("Korathos"), entry("Velira")];
+
+        let text = to_ron(&entries).unwrap();
+
+        assert_eq!(from_ron(&text).unwrap(), entries);
+    }
+
+    #[test]
+    fn test_older_entries_have_no_stats() {
+        let mut older = entry("Korathos");
+        older.stats = None;
+        let text = to_ron(&[older.clone()]).unwrap().replace("stats: None,", "");
+        assert!(!text.contains("stats"));
+
+        assert_eq!(from_ron(&text).unwrap(), vec![older]);
+    }
+
+    #[test]
+    fn test_loaded_entries_go_first_and_the_oldest_are_dropped() {
+        let loaded: Vec<_> = (0..MAX_HISTORY_ENTRIES).map(|i| entry(&format!("old {i}"))).collect();
+
+        let merged = merge_loaded(loaded, vec![entry("new")]);
+
+        assert_eq!(merged.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(merged[0].name, "old 1");
+        assert_eq!(merged.last().unwrap().name, "new");
+    }
+
+    fn record() -> GenerationRecord {
+        let mut settings = PlanetSettingsSnapshot::default();
+        settings.terrain.set_seed(12345);
+        settings.terrain.radius = 42.0;
+        settings.terrain.continent_threshold = 0.15;
+        GenerationRecord {
+            version: GAME_VERSION.to_string(),
+            settings,
+            config: planetgen::config::get_config(),
+            derived: DerivedValues {
+                requested_face_grid_size: 300,
+                face_grid_size: 256,
+                sea_level: 0.15,
+            },
+            stats: entry("Korathos").stats.unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_generation_record_survives_a_ron_round_trip() {
+        let record = record();
+
+        let restored = record_from_ron(&record_to_ron(&record).unwrap()).unwrap();
+
+        // Neither the settings nor the config compare, their text has to come out the same
+        assert_eq!(record_to_ron(&restored).unwrap(), record_to_ron(&record).unwrap());
+        assert!(restored.settings == record.settings);
+        assert_eq!(restored.derived, record.derived);
+        assert_eq!(restored.stats, record.stats);
+    }
+
+    #[test]
+    fn test_generation_record_is_logged_on_one_line() {
+        let line = record().to_log_line().unwrap();
+
+        assert!(!line.contains('\n'));
+        assert!(line.contains(GAME_VERSION));
+        assert!(record_from_ron(&line).is_ok());
+    }
+
+    #[test]
+    fn test_records_of_other_versions_are_told_apart() {
+        let mut record = record();
+        assert_eq!(record.other_version(), None);
+
+        record.version = "0.0.1".to_string();
+
+        assert_eq!(record.other_version(), Some("0.0.1"));
+    }
+
+    #[rstest]
+    #[case(30, "just now")]
+    #[case(150, "2 min ago")]
+    #[case(7200, "2 h ago")]
+    #[case(90000, "1 day ago")]
+    #[case(3 * 86400, "3 days ago")]
+    fn test_age_is_readable(#[case] age: u64, #[case] expected: &str) {
+        assert_eq!(format_age(1_000_000 + age, 1_000_000, &Strings::default()), expected);
+    }
+}
@@ -0,0 +1,57 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::ui::systems::render_planet_generation_ui;
+use bevy::prelude::*;
+use bevy::tasks::Task;
+use bevy_egui::EguiPrimaryContextPass;
+use logic::HistoryEntry;
+
+/// Generation history in the platform data directory
+pub const HISTORY_FILE: &str = "history.ron";
+
+/// Record of the last planet for reproducing it, next to the history
+pub const GENERATION_RECORD_FILE: &str = "last_planet.ron";
+
+/// Every planet generated so far, oldest first
+#[derive(Resource, Default)]
+pub struct PlanetHistory {
+    pub entries: Vec<HistoryEntry>,
+    /// Until the file is read, new entries are only kept in memory so saving them can't
+    /// overwrite the older ones
+    pub loaded: bool,
+}
+
+/// The history file being read in the background at startup
+#[derive(Resource, Default)]
+pub struct PendingHistoryLoad {
+    pub task: Option<Task<Vec<HistoryEntry>>>,
+}
+
+/// Records generated planets in `history.ron` and lists them so they can be generated again.
+/// Logs the full record of every planet and keeps the last one in `last_planet.ron`. Both files
+/// are in the platform data directory, e.g. `~/.local/share/inhabitants` on Linux. Not part of
+/// `PlanetGenerationPlugin`, headless runs shouldn't touch the files.
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlanetHistory>()
+            .init_resource::<PendingHistoryLoad>()
+            .add_systems(Startup, systems::start_history_load)
+            .add_systems(
+                Update,
+                (systems::finish_history_load, systems::record_generation)
+                    .chain()
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                systems::render_history_panel
+                    // Drawn first so the settings panel sees the pointer over it
+                    .before(render_planet_generation_ui)
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            );
+    }
+}
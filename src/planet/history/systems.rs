@@ -0,0 +1,222 @@
+use super::logic::{self, DerivedValues, GAME_VERSION, GenerationParams, GenerationRecord, HistoryEntry};
+use super::{GENERATION_RECORD_FILE, HISTORY_FILE, PendingHistoryLoad, PlanetHistory};
+use crate::core::files::{self, data_path};
+use crate::planet::events::{GeneratePlanetEvent, PlanetSpawnedEvent};
+use crate::planet::resources::{AllPlanetSettings, CurrentPlanetData, PendingPlanetGeneration, TerrainSettings};
+use crate::ui::locale::Strings;
+use bevy::prelude::*;
+use bevy::tasks::IoTaskPool;
+use bevy::tasks::futures::check_ready;
+use bevy_egui::{EguiContexts, egui};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PANEL_MARGIN: f32 = 10.0;
+const LIST_MAX_HEIGHT: f32 = 320.0;
+
+/// Read the history file on the IO pool, the game doesn't wait for it
+pub fn start_history_load(mut pending: ResMut<PendingHistoryLoad>) {
+    pending.task = Some(IoTaskPool::get().spawn(async { read_history_file() }));
+}
+
+pub fn finish_history_load(mut pending: ResMut<PendingHistoryLoad>, mut history: ResMut<PlanetHistory>) {
+    let Some(task) = pending.task.as_mut() else {
+        return;
+    };
+    let Some(loaded) = check_ready(task) else {
+        return;
+    };
+    pending.task = None;
+
+    let recorded = std::mem::take(&mut history.entries);
+    let had_recorded = !recorded.is_empty();
+    history.entries = logic::merge_loaded(loaded, recorded);
+    history.loaded = true;
+    info!("Loaded {} planets from the history", history.entries.len());
+
+    if had_recorded {
+        save_history(&history.entries);
+    }
+}
+
+/// Add every finished planet to the history, log its generation record and write it to
+/// `GENERATION_RECORD_FILE`. Previews are skipped, the full planet is recorded once it's in.
+pub fn record_generation(
+    mut events: MessageReader<PlanetSpawnedEvent>,
+    pending_generation: Res<PendingPlanetGeneration>,
+    current_planet_data: Res<CurrentPlanetData>,
+    settings: AllPlanetSettings,
+    mut history: ResMut<PlanetHistory>,
+) {
+    if events.read().count() == 0 || pending_generation.task.is_some() {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+
+    let stats = planet_data.generation_stats();
+    let entry = HistoryEntry {
+        name: logic::planet_name(settings.terrain.seed),
+        timestamp_secs: now_secs(),
+        land_fraction: planet_data.report.land_fraction,
+        params: GenerationParams::from_settings(&settings.terrain),
+        stats: Some(stats),
+    };
+    logic::push_entry(&mut history.entries, entry);
+
+    if history.loaded {
+        save_history(&history.entries);
+    }
+
+    save_generation_record(&GenerationRecord {
+        version: GAME_VERSION.to_string(),
+        settings: settings.snapshot(),
+        config: planetgen::config::get_config(),
+        derived: DerivedValues::of(planet_data),
+        stats,
+    });
+}
+
+/// Foldable list of past planets in the bottom left corner, newest first
+pub fn render_history_panel(
+    mut contexts: EguiContexts,
+    history: Res<PlanetHistory>,
+    mut settings: ResMut<TerrainSettings>,
+    mut planet_generation_events: MessageWriter<GeneratePlanetEvent>,
+    strings: Res<Strings>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let now = now_secs();
+    egui::Window::new(strings.get("history.window"))
+        .id(egui::Id::new("history_window"))
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(PANEL_MARGIN, -PANEL_MARGIN))
+        .default_open(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if history.entries.is_empty() {
+                ui.label(strings.get("history.empty"));
+                return;
+            }
+
+            egui::ScrollArea::vertical().max_height(LIST_MAX_HEIGHT).show(ui, |ui| {
+                for entry in history.entries.iter().rev() {
+                    ui.horizontal(|ui| {
+                        ui.strong(&entry.name);
+                        if ui.button(strings.get("history.regenerate")).clicked() {
+                            entry.params.apply_to(&mut settings);
+                            planet_generation_events.write(GeneratePlanetEvent);
+                        }
+                    });
+                    ui.label(strings.format(
+                        "history.summary",
+                        &[
+                            ("seed", &entry.params.user_seed),
+                            ("land", &format!("{:.0}", entry.land_fraction * 100.0)),
+                            ("age", &logic::format_age(now, entry.timestamp_secs, &strings)),
+                        ],
+                    ));
+                    ui.label(strings.format(
+                        "history.shape",
+                        &[
+                            ("radius", &format!("{:.1}", entry.params.radius)),
+                            ("plates", &entry.params.num_plates),
+                            ("sea_level", &format!("{:.2}", entry.params.continent_threshold)),
+                        ],
+                    ));
+                    if let Some(stats) = &entry.stats {
+                        ui.label(strings.format(
+                            "history.stats",
+                            &[
+                                ("length", &format!("{:.2}", stats.boundary_length)),
+                                ("junctions", &stats.triple_junctions),
+                                ("compactness", &format!("{:.3}", stats.mean_compactness)),
+                            ],
+                        ));
+                    }
+                    ui.separator();
+                }
+            });
+        });
+}
+
+fn read_history_file() -> Vec<HistoryEntry> {
+    let Some(path) = data_path(HISTORY_FILE) else {
+        warn!("No data directory on this platform, the planet history isn't kept");
+        return Vec::new();
+    };
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Vec::new(),
+        Err(error) => {
+            warn!("Could not read {}: {error}", path.display());
+            return Vec::new();
+        }
+    };
+
+    logic::from_ron(&text).unwrap_or_else(|error| {
+        // Moved aside, the next save would overwrite it otherwise
+        let backup = path.with_extension("ron.bak");
+        warn!("Could not parse {}, keeping it as {}: {error}", path.display(), backup.display());
+        if let Err(error) = std::fs::rename(&path, &backup) {
+            warn!("Could not move {} aside: {error}", path.display());
+        }
+        Vec::new()
+    })
+}
+
+/// Write the whole history on the IO pool
+fn save_history(entries: &[HistoryEntry]) {
+    let text = match logic::to_ron(entries) {
+        Ok(text) => text,
+        Err(error) => {
+            warn!("Could not serialize the planet history: {error}");
+            return;
+        }
+    };
+
+    IoTaskPool::get()
+        .spawn(async move { write_data_file(HISTORY_FILE, &text) })
+        .detach();
+}
+
+/// Log the record on one line and write it on the IO pool, replacing the one of the last planet
+fn save_generation_record(record: &GenerationRecord) {
+    match record.to_log_line() {
+        Ok(line) => info!("Generation record: {line}"),
+        Err(error) => warn!("Could not serialize the generation record: {error}"),
+    }
+    let text = match logic::record_to_ron(record) {
+        Ok(text) => text,
+        Err(error) => {
+            warn!("Could not serialize the generation record: {error}");
+            return;
+        }
+    };
+
+    IoTaskPool::get()
+        .spawn(async move { write_data_file(GENERATION_RECORD_FILE, &text) })
+        .detach();
+}
+
+fn write_data_file(file: &str, text: &str) {
+    if let Some(path) = data_path(file) {
+        files::write_file(&path, text);
+    }
+}
+
+/// Read a record written by [`save_generation_record`], for `--reproduce`
+pub fn read_generation_record(path: &Path) -> Result<GenerationRecord, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))?;
+    logic::record_from_ron(&text).map_err(|error| format!("{}: {error}", path.display()))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs())
+}
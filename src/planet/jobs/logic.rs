@@ -0,0 +1,126 @@
+use crate::ui::locale::StringId;
+use std::collections::VecDeque;
+
+/// Which precipitation a rebuild makes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrecipitationPhase {
+    /// The annual map
+    Annual,
+    /// A map for every phase of the year
+    Seasons,
+}
+
+/// What a climate job rebuilds. Two pending jobs of one kind are duplicates, only the newer
+/// one is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClimateJobKind {
+    RebuildWind,
+    RebuildPrecipitation { phase: PrecipitationPhase },
+}
+
+impl ClimateJobKind {
+    /// Locale key of the name in the debug panel
+    pub fn label(self) -> StringId {
+        match self {
+            ClimateJobKind::RebuildWind => "job.wind",
+            ClimateJobKind::RebuildPrecipitation { phase: PrecipitationPhase::Annual } => "job.precipitation",
+            ClimateJobKind::RebuildPrecipitation { phase: PrecipitationPhase::Seasons } => {
+                "job.precipitation_seasons"
+            }
+        }
+    }
+}
+
+/// Jobs waiting for the worker, oldest first. A job queued while one of its kind is waiting
+/// takes that one's place in the queue, it was made from newer inputs.
+#[derive(Debug)]
+pub struct PendingJobs<J> {
+    jobs: VecDeque<(ClimateJobKind, J)>,
+}
+
+impl<J> Default for PendingJobs<J> {
+    fn default() -> Self {
+        Self { jobs: VecDeque::new() }
+    }
+}
+
+impl<J> PendingJobs<J> {
+    /// Queue `job`, true when it replaced a pending job of its kind
+    pub fn push(&mut self, kind: ClimateJobKind, job: J) -> bool {
+        match self.jobs.iter_mut().find(|(pending, _)| *pending == kind) {
+            Some(pending) => {
+                pending.1 = job;
+                true
+            }
+            None => {
+                self.jobs.push_back((kind, job));
+                false
+            }
+        }
+    }
+
+    /// Take the oldest job
+    pub fn pop(&mut self) -> Option<(ClimateJobKind, J)> {
+        self.jobs.pop_front()
+    }
+
+    /// Drop every pending job, the number dropped
+    pub fn clear(&mut self) -> usize {
+        let dropped = self.jobs.len();
+        self.jobs.clear();
+        dropped
+    }
+
+    /// Kinds of the pending jobs, oldest first
+    pub fn kinds(&self) -> impl Iterator<Item = ClimateJobKind> + '_ {
+        self.jobs.iter().map(|(kind, _)| *kind)
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANNUAL: ClimateJobKind = ClimateJobKind::RebuildPrecipitation { phase: PrecipitationPhase::Annual };
+    const SEASONS: ClimateJobKind = ClimateJobKind::RebuildPrecipitation { phase: PrecipitationPhase::Seasons };
+
+    #[test]
+    fn test_second_job_of_a_kind_replaces_the_queued_one() {
+        let mut pending = PendingJobs::default();
+        assert!(!pending.push(ClimateJobKind::RebuildWind, 1));
+        assert!(!pending.push(ANNUAL, 2));
+        assert!(pending.push(ClimateJobKind::RebuildWind, 3));
+
+        assert_eq!(pending.len(), 2);
+        // The replacement keeps the place of the job it replaced
+        assert_eq!(pending.pop(), Some((ClimateJobKind::RebuildWind, 3)));
+        assert_eq!(pending.pop(), Some((ANNUAL, 2)));
+        assert_eq!(pending.pop(), None);
+    }
+
+    #[test]
+    fn test_precipitation_phases_are_different_jobs() {
+        let mut pending = PendingJobs::default();
+        pending.push(ANNUAL, 1);
+        pending.push(SEASONS, 2);
+        assert_eq!(pending.kinds().collect::<Vec<_>>(), vec![ANNUAL, SEASONS]);
+    }
+
+    #[test]
+    fn test_clear_drops_every_pending_job() {
+        let mut pending = PendingJobs::default();
+        pending.push(ClimateJobKind::RebuildWind, 1);
+        pending.push(SEASONS, 2);
+        assert_eq!(pending.clear(), 2);
+        assert!(pending.is_empty());
+        assert_eq!(pending.pop(), None);
+    }
+}
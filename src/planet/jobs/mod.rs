@@ -0,0 +1,107 @@
+pub mod logic;
+pub mod systems;
+
+use crate::planet::ClimateRebuildSet;
+use crate::planet::events::{PrecipitationRebuilt, WindRebuilt};
+use crate::planet::systems::{spawn_planet_on_event, swap_in_full_resolution_planet};
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use bevy::tasks::futures::check_ready;
+use logic::{ClimateJobKind, PendingJobs};
+
+/// What a climate job built, published as the completion message of its kind
+pub enum ClimateJobOutput {
+    Wind(Box<WindRebuilt>),
+    Precipitation(PrecipitationRebuilt),
+}
+
+/// A climate rebuild with everything it is built from, run away from the world
+pub type ClimateJob = Box<dyn FnOnce() -> ClimateJobOutput + Send + Sync>;
+
+/// Climate rebuilds too slow for a frame. Systems queue them, `run_climate_jobs` runs one at a
+/// time on the async compute pool and publishes what it built as a message, so nothing has to
+/// handle its own task.
+#[derive(Resource, Default)]
+pub struct ClimateJobQueue {
+    pending: PendingJobs<ClimateJob>,
+    running: Option<(ClimateJobKind, Task<ClimateJobOutput>)>,
+    /// Jobs finished since the start, for the debug panel
+    pub finished: usize,
+    /// Jobs dropped before they finished because a new planet took the place of theirs
+    pub cancelled: usize,
+}
+
+impl ClimateJobQueue {
+    /// Queue a job, it replaces a pending job of the same kind
+    pub fn push(
+        &mut self,
+        kind: ClimateJobKind,
+        job: impl FnOnce() -> ClimateJobOutput + Send + Sync + 'static,
+    ) {
+        if self.pending.push(kind, Box::new(job)) {
+            debug!("{kind:?} rebuild replaced the queued one");
+        }
+    }
+
+    /// Drop every pending job and the running one
+    pub fn cancel(&mut self) {
+        self.cancelled += self.pending.clear();
+        // Dropping the task cancels it
+        if self.running.take().is_some() {
+            self.cancelled += 1;
+        }
+    }
+
+    pub fn running(&self) -> Option<ClimateJobKind> {
+        self.running.as_ref().map(|(kind, _)| *kind)
+    }
+
+    /// Kinds of the pending jobs, in the order they will run
+    pub fn pending(&self) -> impl Iterator<Item = ClimateJobKind> + '_ {
+        self.pending.kinds()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.running.is_none() && self.pending.is_empty()
+    }
+
+    /// The output of the running job once it is done
+    fn poll_running(&mut self) -> Option<ClimateJobOutput> {
+        let (_, task) = self.running.as_mut()?;
+        let output = check_ready(task)?;
+        self.running = None;
+        self.finished += 1;
+        Some(output)
+    }
+
+    /// Start the oldest pending job unless one is running
+    fn start_next(&mut self) {
+        if self.running.is_some() {
+            return;
+        }
+        if let Some((kind, job)) = self.pending.pop() {
+            self.running = Some((kind, AsyncComputeTaskPool::get().spawn(async move { job() })));
+        }
+    }
+}
+
+pub struct ClimateJobsPlugin;
+
+impl Plugin for ClimateJobsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClimateJobQueue>()
+            // A new planet drops the jobs of the old one before it queues its own
+            .add_systems(
+                Update,
+                systems::cancel_climate_jobs_on_new_planet
+                    .after(spawn_planet_on_event)
+                    .after(swap_in_full_resolution_planet)
+                    .before(ClimateRebuildSet::Wind),
+            )
+            // Runs after every rebuild queued its jobs, what it builds is picked up next frame
+            .add_systems(
+                Update,
+                systems::run_climate_jobs.after(ClimateRebuildSet::Precipitation),
+            );
+    }
+}
@@ -0,0 +1,42 @@
+use super::{ClimateJobOutput, ClimateJobQueue};
+use crate::planet::events::{PlanetSpawnedEvent, PrecipitationRebuilt, WindRebuilt};
+use bevy::prelude::*;
+
+/// A new planet, the preview or the full resolution one that follows it, makes every queued
+/// and running climate job stale. The new planet queues its own right after.
+pub fn cancel_climate_jobs_on_new_planet(
+    mut events: MessageReader<PlanetSpawnedEvent>,
+    mut queue: ResMut<ClimateJobQueue>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    if !queue.is_idle() {
+        info!("New planet, cancelling the climate jobs of the old one");
+    }
+    queue.cancel();
+}
+
+/// Run the queued climate jobs one at a time on the async compute pool and publish what they
+/// built. They never run on the main thread, not even when the planet is generated in one go,
+/// so a rebuild never stalls a frame and a new planet can still cancel them.
+pub fn run_climate_jobs(
+    mut queue: ResMut<ClimateJobQueue>,
+    mut wind_rebuilt: MessageWriter<WindRebuilt>,
+    mut precipitation_rebuilt: MessageWriter<PrecipitationRebuilt>,
+) {
+    let output = queue.poll_running();
+    queue.start_next();
+
+    let Some(output) = output else {
+        return;
+    };
+    match output {
+        ClimateJobOutput::Wind(rebuilt) => {
+            wind_rebuilt.write(*rebuilt);
+        }
+        ClimateJobOutput::Precipitation(rebuilt) => {
+            precipitation_rebuilt.write(rebuilt);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use planetgen::prelude::FeatureKind;
+
+/// Labels fade out over this much of the facing, from the horizon up
+const FADE_FACING: f32 = 0.3;
+
+/// Camera height above the surface, in planet radii, at which labels have their base size
+const REFERENCE_ALTITUDE: f32 = 1.5;
+
+/// Labels grow and shrink with the zoom within these factors of their base size
+const MIN_SCALE: f32 = 0.6;
+const MAX_SCALE: f32 = 2.0;
+
+/// Opacity of a label from its facing, the cosine between the surface normal under it and the
+/// direction to the camera: opaque when seen from above, gone at the horizon and beyond
+pub fn label_alpha(facing: f32) -> f32 {
+    (facing / FADE_FACING).clamp(0.0, 1.0)
+}
+
+/// Font size of a label of `kind`, larger the closer the camera at `camera_distance` from the
+/// center of a planet of `radius` gets to its surface
+pub fn label_font_size(kind: FeatureKind, radius: f32, camera_distance: f32) -> f32 {
+    let altitude = (camera_distance - radius).max(f32::EPSILON);
+    base_font_size(kind) * (REFERENCE_ALTITUDE * radius / altitude).clamp(MIN_SCALE, MAX_SCALE)
+}
+
+/// Bigger features get bigger names
+fn base_font_size(kind: FeatureKind) -> f32 {
+    match kind {
+        FeatureKind::Ocean | FeatureKind::Continent => 18.0,
+        FeatureKind::Sea | FeatureKind::MountainRange => 14.0,
+        FeatureKind::Island => 12.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1.0, 1.0)]
+    #[case(FADE_FACING, 1.0)]
+    #[case(FADE_FACING / 2.0, 0.5)]
+    #[case(0.0, 0.0)]
+    #[case(-0.5, 0.0)]
+    fn test_labels_fade_towards_the_horizon(#[case] facing: f32, #[case] expected: f32) {
+        assert!((label_alpha(facing) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_labels_grow_as_the_camera_closes_in() {
+        let radius = 10.0;
+        let size = |distance| label_font_size(FeatureKind::Continent, radius, distance);
+
+        assert_eq!(size(radius + REFERENCE_ALTITUDE * radius), 18.0);
+        assert!(size(20.0) > size(25.0));
+        assert_eq!(size(radius + 0.01), 18.0 * MAX_SCALE);
+        assert_eq!(size(radius * 100.0), 18.0 * MIN_SCALE);
+    }
+
+    #[test]
+    fn test_oceans_are_named_larger_than_islands() {
+        assert!(label_font_size(FeatureKind::Ocean, 10.0, 25.0) > label_font_size(FeatureKind::Island, 10.0, 25.0));
+    }
+}
@@ -0,0 +1,41 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::SettingsDiffSet;
+use crate::planet::systems::{rescale_planet_on_event, spawn_planet_on_event, swap_in_full_resolution_planet};
+use crate::planet::ui::systems::render_planet_generation_ui;
+use bevy::prelude::*;
+use bevy_egui::EguiPrimaryContextPass;
+use planetgen::prelude::FeatureKind;
+
+/// Anchor of the name of a continent, ocean or mountain range, a child of the planet
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct FeatureLabel {
+    pub name: String,
+    pub kind: FeatureKind,
+}
+
+/// Names of the continents, oceans and mountain ranges written over the planet. The names
+/// follow the seed, the same planet is always named the same.
+pub struct FeatureLabelsPlugin;
+
+impl Plugin for FeatureLabelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            systems::update_feature_labels
+                .after(SettingsDiffSet)
+                .after(spawn_planet_on_event)
+                .after(swap_in_full_resolution_planet)
+                .after(rescale_planet_on_event),
+        )
+        .add_systems(
+            EguiPrimaryContextPass,
+            systems::draw_feature_labels
+                // Under the panels, drawn in the background layer anyway
+                .before(render_planet_generation_ui)
+                .run_if(in_state(GameState::PlanetGeneration)),
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use super::FeatureLabel;
+use super::logic::{label_alpha, label_font_size};
+use crate::camera::components::MainCamera;
+use crate::planet::components::{PlanetEntity, SurfaceAnchored};
+use crate::planet::events::{PlanetSpawnedEvent, SettingsDiff, SettingsGroup};
+use crate::planet::resources::{CurrentPlanetData, DisplaySettings, TerrainSettings};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use planetgen::prelude::{FeatureKind, FeatureNaming};
+
+/// Offset of the shadow under the text, keeps it readable over bright terrain
+const SHADOW_OFFSET: f32 = 1.0;
+
+/// Rebuild the labels of the current planet when a planet is spawned or rescaled, or when they
+/// are toggled. Names come from the seed the planet was
+/// generated with, editing the seed doesn't rename the planet on screen.
+pub fn update_feature_labels(
+    (mut settings_diffs, mut planet_spawned_events): (MessageReader<SettingsDiff>, MessageReader<PlanetSpawnedEvent>),
+    (terrain_settings, display_settings): (Res<TerrainSettings>, Res<DisplaySettings>),
+    current_planet_data: Res<CurrentPlanetData>,
+    planet_query: Query<Entity, With<PlanetEntity>>,
+    existing_labels: Query<Entity, With<FeatureLabel>>,
+    mut named_seed: Local<u64>,
+    mut commands: Commands,
+) {
+    let toggled = SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Visual]);
+    let planet_spawned = planet_spawned_events.read().count() > 0;
+    if planet_spawned {
+        *named_seed = terrain_settings.seed;
+    }
+    if !toggled && !planet_spawned && !current_planet_data.is_changed() {
+        return;
+    }
+
+    for entity in existing_labels.iter() {
+        commands.entity(entity).despawn();
+    }
+    if !display_settings.show_feature_labels {
+        return;
+    }
+    let Some(planet) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    let Some(planet_entity) = planet_query.iter().next() else {
+        return;
+    };
+
+    for feature in planet.named_features(*named_seed, &FeatureNaming::default()) {
+        let direction = feature.direction;
+        // On the water over sunken features, on the ground elsewhere
+        let ground = planet.surface_radius(direction) + planet.sample_height(direction).max(planet.sea_level());
+        let label = commands
+            .spawn((
+                Transform::from_translation(direction * ground),
+                Visibility::default(),
+                FeatureLabel {
+                    name: feature.name,
+                    kind: feature.kind,
+                },
+                SurfaceAnchored { direction },
+            ))
+            .id();
+        commands.entity(planet_entity).add_child(label);
+    }
+}
+
+/// Write every label over its feature, fading towards the horizon and sized by the zoom
+pub fn draw_feature_labels(
+    mut contexts: EguiContexts,
+    (terrain_settings, display_settings): (Res<TerrainSettings>, Res<DisplaySettings>),
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    planet_query: Query<&GlobalTransform, With<PlanetEntity>>,
+    labels: Query<(&FeatureLabel, &SurfaceAnchored, &GlobalTransform)>,
+) {
+    if !display_settings.show_feature_labels || labels.is_empty() {
+        return;
+    }
+    let (Ok((camera, camera_transform)), Ok(planet_transform)) = (camera_query.single(), planet_query.single()) else {
+        return;
+    };
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let camera_position = camera_transform.translation();
+    let camera_distance = camera_position.distance(planet_transform.translation());
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    for (label, anchor, transform) in labels.iter() {
+        let position = transform.translation();
+        let normal = planet_transform.rotation() * anchor.direction;
+        let alpha = label_alpha(normal.dot((camera_position - position).normalize_or_zero()));
+        if alpha <= 0.0 {
+            continue;
+        }
+        let Ok(screen) = camera.world_to_viewport(camera_transform, position) else {
+            continue;
+        };
+
+        let font = egui::FontId::proportional(label_font_size(label.kind, terrain_settings.radius, camera_distance));
+        let at = egui::pos2(screen.x, screen.y);
+        painter.text(
+            at + egui::vec2(SHADOW_OFFSET, SHADOW_OFFSET),
+            egui::Align2::CENTER_CENTER,
+            &label.name,
+            font.clone(),
+            egui::Color32::BLACK.gamma_multiply(alpha * 0.8),
+        );
+        painter.text(
+            at,
+            egui::Align2::CENTER_CENTER,
+            &label.name,
+            font,
+            kind_color(label.kind).gamma_multiply(alpha),
+        );
+    }
+}
+
+/// Water names in pale blue, mountains in sand, land in white
+fn kind_color(kind: FeatureKind) -> egui::Color32 {
+    match kind {
+        FeatureKind::Ocean | FeatureKind::Sea => egui::Color32::from_rgb(170, 210, 255),
+        FeatureKind::MountainRange => egui::Color32::from_rgb(235, 215, 170),
+        FeatureKind::Continent | FeatureKind::Island => egui::Color32::WHITE,
+    }
+}
@@ -0,0 +1,126 @@
+use crate::planet::events::ViewKind;
+use crate::planet::resources::{ClimateSettings, DisplaySettings};
+use crate::ui::locale::Strings;
+use planetgen::prelude::{ColorRamp, PrecipitationCalibration};
+
+/// Color scale and labels of the overlay on the open tab
+#[derive(Debug, Clone, PartialEq)]
+pub struct Legend {
+    pub title: String,
+    pub ramp: ColorRamp,
+    /// Labels at the bottom, the middle and the top of the bar
+    pub low: String,
+    pub mid: String,
+    pub high: String,
+}
+
+/// Legend for `tab`, `None` where the planet is shown in its own colors. The Wind tab only
+/// has one while one of its overlays is switched on. Precipitation is labelled in mm a year
+/// by `precipitation`. Titles and labels are in the language of `strings`.
+pub fn legend_for(
+    tab: ViewKind,
+    (climate, display): (&ClimateSettings, &DisplaySettings),
+    precipitation: &PrecipitationCalibration,
+    strings: &Strings,
+) -> Option<Legend> {
+    let labels = |low: &str, mid: &str, high: &str| {
+        (strings.get(low).to_string(), strings.get(mid).to_string(), strings.get(high).to_string())
+    };
+
+    let (title, ramp, (low, mid, high)) = match tab {
+        ViewKind::Continent | ViewKind::Tectonic | ViewKind::Biomes => return None,
+        ViewKind::Temperature => {
+            let (min, max) = (climate.temperature_min_temp, climate.temperature_max_temp);
+            let celsius = |value: f32| format!("{value:.0}°C");
+            (
+                "legend.temperature",
+                ColorRamp::Temperature,
+                (celsius(min), celsius((min + max) * 0.5), celsius(max)),
+            )
+        }
+        ViewKind::Precipitations => {
+            let unit = strings.get("unit.mm_per_year");
+            let mm = |value: f32| format!("{:.0} {unit}", precipitation.to_mm(value));
+            ("legend.precipitation", ColorRamp::Precipitation, (mm(0.0), mm(0.5), mm(1.0)))
+        }
+        // Same precedence as the overlays, influence is drawn over vertical air
+        ViewKind::Wind if display.show_wind_influence => (
+            "legend.mountain_influence",
+            ColorRamp::WindInfluence,
+            labels("legend.free_flow", "legend.partly_blocked", "legend.blocked"),
+        ),
+        ViewKind::Wind if display.show_vertical_air => (
+            "legend.vertical_air",
+            ColorRamp::VerticalAir,
+            labels("legend.rising", "legend.neutral", "legend.sinking"),
+        ),
+        ViewKind::Wind => return None,
+        ViewKind::CrustAge => (
+            "legend.crust_age",
+            ColorRamp::CrustAge,
+            labels("legend.ridge", "legend.middle", "legend.oldest"),
+        ),
+    };
+
+    Some(Legend { title: strings.get(title).to_string(), ramp, low, mid, high })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALIBRATION: PrecipitationCalibration = PrecipitationCalibration {
+        max_annual_mm: 4000.0,
+        gamma: 2.0,
+    };
+
+    #[test]
+    fn test_temperature_legend_follows_the_color_scale_settings() {
+        let climate = ClimateSettings {
+            temperature_min_temp: -40.0,
+            temperature_max_temp: 60.0,
+            ..Default::default()
+        };
+
+        let legend = legend_for(ViewKind::Temperature, (&climate, &DisplaySettings::default()), &CALIBRATION, &Strings::default()).unwrap();
+
+        assert_eq!(legend.ramp, ColorRamp::Temperature);
+        assert_eq!((legend.low.as_str(), legend.mid.as_str(), legend.high.as_str()), ("-40°C", "10°C", "60°C"));
+    }
+
+    #[test]
+    fn test_precipitation_legend_is_in_mm_a_year() {
+        let settings = (&ClimateSettings::default(), &DisplaySettings::default());
+
+        let legend = legend_for(ViewKind::Precipitations, settings, &CALIBRATION, &Strings::default()).unwrap();
+
+        assert_eq!(
+            (legend.low.as_str(), legend.mid.as_str(), legend.high.as_str()),
+            ("0 mm/yr", "1000 mm/yr", "4000 mm/yr")
+        );
+    }
+
+    #[test]
+    fn test_plain_views_have_no_legend() {
+        let settings = (&ClimateSettings::default(), &DisplaySettings::default());
+
+        for tab in [ViewKind::Continent, ViewKind::Tectonic, ViewKind::Biomes] {
+            assert_eq!(legend_for(tab, settings, &CALIBRATION, &Strings::default()), None, "{tab:?}");
+        }
+    }
+
+    #[test]
+    fn test_wind_legend_follows_the_shown_overlay() {
+        let climate = ClimateSettings::default();
+        let mut display = DisplaySettings::default();
+        assert_eq!(legend_for(ViewKind::Wind, (&climate, &display), &CALIBRATION, &Strings::default()), None);
+
+        display.show_vertical_air = true;
+        let legend = legend_for(ViewKind::Wind, (&climate, &display), &CALIBRATION, &Strings::default());
+        assert_eq!(legend.unwrap().ramp, ColorRamp::VerticalAir);
+
+        display.show_wind_influence = true;
+        let legend = legend_for(ViewKind::Wind, (&climate, &display), &CALIBRATION, &Strings::default());
+        assert_eq!(legend.unwrap().ramp, ColorRamp::WindInfluence);
+    }
+}
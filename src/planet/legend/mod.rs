@@ -0,0 +1,23 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::ui::systems::render_planet_generation_ui;
+use bevy::prelude::*;
+use bevy_egui::EguiPrimaryContextPass;
+
+/// Color scale of the open overlay tab in the bottom right corner of the planet view, or a hint
+/// why the overlay can't be shown yet
+pub struct LegendPlugin;
+
+impl Plugin for LegendPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            systems::render_overlay_legend
+                // After the settings panel so the corner is the one of the remaining area
+                .after(render_planet_generation_ui)
+                .run_if(in_state(GameState::PlanetGeneration)),
+        );
+    }
+}
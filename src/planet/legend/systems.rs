@@ -0,0 +1,99 @@
+use super::logic;
+use crate::planet::precipitation::PrecipitationSettings;
+use crate::planet::resources::{ClimateSettings, DisplaySettings, OverlayHint, OverlayState};
+use crate::ui::locale::Strings;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use planetgen::prelude::{ColorRamp, Palette};
+
+const PANEL_MARGIN: f32 = 10.0;
+const BAR_WIDTH: f32 = 16.0;
+const BAR_HEIGHT: f32 = 140.0;
+/// Room right of the bar for the labels
+const LABEL_WIDTH: f32 = 90.0;
+/// Texels of the gradient texture, stretched over the bar with linear filtering
+const RAMP_TEXELS: usize = 64;
+
+/// Vertical gradient bar of the open overlay's color scale with its range next to it. The
+/// gradient is sampled from the same ramp and palette the overlay is colored with. While the
+/// overlay can't be drawn its hint is shown in the middle of the view instead.
+pub fn render_overlay_legend(
+    mut contexts: EguiContexts,
+    overlay_state: Res<OverlayState>,
+    hint: Res<OverlayHint>,
+    (climate_settings, settings): (Res<ClimateSettings>, Res<DisplaySettings>),
+    precipitation: Res<PrecipitationSettings>,
+    strings: Res<Strings>,
+    mut gradient: Local<Option<(ColorRamp, Palette, egui::TextureHandle)>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    if let Some(hint) = hint.0 {
+        egui::Area::new(egui::Id::new("overlay_hint"))
+            .pivot(egui::Align2::CENTER_CENTER)
+            .fixed_pos(ctx.available_rect().center())
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(strings.get(hint));
+                });
+            });
+        return;
+    }
+    let Some(legend) =
+        logic::legend_for(overlay_state.active, (&climate_settings, &settings), &precipitation.calibration, &strings)
+    else {
+        return;
+    };
+
+    let wanted = (legend.ramp, settings.palette);
+    if gradient.as_ref().is_none_or(|(ramp, palette, _)| (*ramp, *palette) != wanted) {
+        let image = ramp_image(legend.ramp, settings.palette);
+        let texture = ctx.load_texture("overlay_legend", image, egui::TextureOptions::LINEAR);
+        *gradient = Some((legend.ramp, settings.palette, texture));
+    }
+    let Some((_, _, texture)) = gradient.as_ref() else {
+        return;
+    };
+
+    egui::Area::new(egui::Id::new("overlay_legend"))
+        .pivot(egui::Align2::RIGHT_BOTTOM)
+        .fixed_pos(ctx.available_rect().right_bottom() - egui::vec2(PANEL_MARGIN, PANEL_MARGIN))
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.strong(&legend.title);
+
+                let (response, painter) =
+                    ui.allocate_painter(egui::vec2(BAR_WIDTH + LABEL_WIDTH, BAR_HEIGHT), egui::Sense::hover());
+                let bar = egui::Rect::from_min_size(response.rect.min, egui::vec2(BAR_WIDTH, BAR_HEIGHT));
+                let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                painter.image(texture.id(), bar, uv, egui::Color32::WHITE);
+
+                let color = ui.visuals().text_color();
+                let font = egui::FontId::proportional(12.0);
+                let x = bar.right() + 6.0;
+                let labels = [
+                    (bar.top(), egui::Align2::LEFT_TOP, &legend.high),
+                    (bar.center().y, egui::Align2::LEFT_CENTER, &legend.mid),
+                    (bar.bottom(), egui::Align2::LEFT_BOTTOM, &legend.low),
+                ];
+                for (y, align, text) in labels {
+                    painter.text(egui::pos2(x, y), align, text, font.clone(), color);
+                }
+            });
+        });
+}
+
+/// One texel wide column of the ramp, the high end at the top
+fn ramp_image(ramp: ColorRamp, palette: Palette) -> egui::ColorImage {
+    let rgb: Vec<u8> = (0..RAMP_TEXELS)
+        .flat_map(|row| {
+            let t = 1.0 - row as f32 / (RAMP_TEXELS - 1) as f32;
+            let color = ramp.ramp(t, palette);
+            [color.x, color.y, color.z].map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+        })
+        .collect();
+    egui::ColorImage::from_rgb([1, RAMP_TEXELS], &rgb)
+}
@@ -1,39 +1,1017 @@
-use crate::planet::resources::PlanetGenerationSettings;
-use planetgen::generator::PlanetGenerator;
-use planetgen::planet::PlanetData;
-
-pub fn generate_planet_data(settings: &PlanetGenerationSettings) -> PlanetData {
-    planetgen::reload_config();
-    let generator = configure_planet_generator(settings);
-    generator.generate()
-}
-
-/// Pure business logic: Configure planet generator from settings
-fn configure_planet_generator(settings: &PlanetGenerationSettings) -> PlanetGenerator {
-    let mut generator = PlanetGenerator::new(settings.radius);
-    generator.num_plates = settings.num_plates;
-    generator.num_micro_plates = settings.num_micro_plates;
-    generator.seed = settings.seed;
-    generator.flow_warp_freq = settings.flow_warp_freq;
-    generator.flow_warp_steps = settings.flow_warp_steps;
-    generator.flow_warp_step_angle = settings.flow_warp_step_angle;
-
-    // Apply custom continent configuration from UI settings
-    let continent_config = planetgen::config::ContinentConfig {
-        continent_frequency: settings.continent_frequency,
-        continent_amplitude: settings.continent_amplitude,
-        distortion_frequency: settings.distortion_frequency,
-        distortion_amplitude: settings.distortion_amplitude,
-        detail_frequency: settings.detail_frequency,
-        detail_amplitude: settings.detail_amplitude,
-        continent_threshold: settings.continent_threshold,
-        ocean_depth_amplitude: settings.ocean_depth_amplitude,
+use crate::planet::events::SettingsGroup;
+use crate::planet::resources::{ClimateSettings, DisplaySettings, TerrainSettings, WindSettings};
+use bevy::math::{Quat, Vec3};
+use planetgen::prelude::{MeshUvs, PlanetData, PlanetGenerator, PlateLayout, expand_seed64, oblate_radius};
+
+/// Grid steps of the terrain LOD meshes, finest first
+pub const TERRAIN_LOD_STEPS: [usize; 3] = [1, 2, 4];
+
+/// The terrain meshes map each cube face onto its tile of a 3 × 2 atlas laid out like the
+/// cubemaps, ready for textures over the terrain. Costs a copy of the cube edge vertices.
+pub const TERRAIN_MESH_UVS: MeshUvs = MeshUvs::FaceAtlas;
+
+/// Zoom, as a multiple of the radius, past which the next coarser LOD is used
+const LOD_ZOOM_THRESHOLDS: [f32; 2] = [2.2, 2.9];
+
+/// How far past a threshold the zoom has to go before the LOD switches, so it doesn't flicker
+/// while zooming around a threshold
+const LOD_HYSTERESIS: f32 = 0.1;
+
+/// Terrain LOD for a camera zoom, 0 is the finest. `current` is the LOD in use now.
+pub fn terrain_lod_level(current: usize, zoom: f32, radius: f32) -> usize {
+    let zoom = zoom / radius;
+    let mut level = current.min(LOD_ZOOM_THRESHOLDS.len());
+    while level < LOD_ZOOM_THRESHOLDS.len() && zoom > LOD_ZOOM_THRESHOLDS[level] + LOD_HYSTERESIS {
+        level += 1;
+    }
+    while level > 0 && zoom < LOD_ZOOM_THRESHOLDS[level - 1] - LOD_HYSTERESIS {
+        level -= 1;
+    }
+    level
+}
+
+/// Relative change of the radius past which a regenerated planet gets the default view even
+/// when the view is kept, the old zoom would frame it badly
+const KEEP_VIEW_MAX_RADIUS_CHANGE: f32 = 0.2;
+
+/// Closest and farthest camera zoom for a planet of `radius`
+pub fn zoom_limits(radius: f32) -> (f32, f32) {
+    (radius * 1.5, radius * 3.5)
+}
+
+/// How fast a zoom outside the limits closes in on them, per second
+const ZOOM_SETTLE_RATE: f32 = 4.0;
+/// How close to the limit, relative to it, the settling zoom lands on it
+const ZOOM_SETTLE_SNAP: f32 = 1e-3;
+
+/// `zoom` after easing into `limits` for `dt` seconds. A zoom within them stays, one outside
+/// closes in on the nearest limit exponentially and lands on it once it is close, so a planet
+/// regenerated much smaller or larger is approached instead of jumped to.
+pub fn settle_zoom(zoom: f32, (min_zoom, max_zoom): (f32, f32), dt: f32) -> f32 {
+    let target = zoom.clamp(min_zoom, max_zoom);
+    let eased = zoom + (target - zoom) * (1.0 - (-ZOOM_SETTLE_RATE * dt).exp());
+    if (eased - target).abs() <= target * ZOOM_SETTLE_SNAP {
+        target
+    } else {
+        eased
+    }
+}
+
+/// Where the planet sits in the view. The camera and the point it looks at are moved right by
+/// these shares of the zoom, which leaves the planet left of the settings panel. Zero for both
+/// centers it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraComposition {
+    pub camera_offset: f32,
+    pub look_at_offset: f32,
+}
+
+impl CameraComposition {
+    /// Camera position and the point it looks at, framing the planet from `zoom` away
+    pub fn framing(self, zoom: f32) -> (Vec3, Vec3) {
+        (
+            Vec3::new(zoom * self.camera_offset, 0.0, zoom),
+            Vec3::new(zoom * self.look_at_offset, 0.0, 0.0),
+        )
+    }
+
+    /// Distance of the framing camera from the planet center per unit of zoom
+    pub fn distance_per_zoom(self) -> f32 {
+        (1.0 + self.camera_offset * self.camera_offset).sqrt()
+    }
+}
+
+/// What is drawn over the ground, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceLayer {
+    /// Tropics and polar circles of the axis gizmo, and the debug coastline, lines that only
+    /// ever cross each other
+    LatitudeRings,
+    PlateArrows,
+}
+
+/// Gap between two layers as a share of the radius, small enough that they hug a small planet
+const LAYER_STEP: f32 = 0.004;
+
+/// Gap between two layers as a share of the camera distance, closer ones flicker in the depth
+/// buffer
+const LAYER_DEPTH_STEP: f32 = 0.002;
+
+/// How far above the ground `layer` is drawn on a planet of `radius` seen from
+/// `camera_distance`. Each layer is a step above the one below, so they never share a depth.
+pub fn layer_offset(layer: SurfaceLayer, radius: f32, camera_distance: f32) -> f32 {
+    let step = (radius * LAYER_STEP).max(camera_distance * LAYER_DEPTH_STEP);
+    step * (layer as usize + 1) as f32
+}
+
+/// `layer_offset` for the farthest the camera gets, layers that aren't moved with the camera
+/// are placed for it
+pub fn static_layer_offset(layer: SurfaceLayer, radius: f32) -> f32 {
+    layer_offset(layer, radius, zoom_limits(radius).1)
+}
+
+/// Whether a planet regenerated from `old_radius` to `radius` is seen the way the old one was.
+/// Only with `keep_view`, and only when the radius didn't change too much for it.
+pub fn keeps_view(keep_view: bool, old_radius: f32, radius: f32) -> bool {
+    keep_view && (radius - old_radius).abs() <= old_radius * KEEP_VIEW_MAX_RADIUS_CHANGE
+}
+
+/// Zoom of a regenerated planet of `radius` and whether the camera has to move to it.
+/// `previous` is the zoom and radius of the planet it replaces. With `keep_view` the zoom stays,
+/// `settle_zoom` eases it into the new limits if the radius changed, otherwise the planet is
+/// framed from as far as the limits allow.
+pub fn regenerated_zoom(previous: Option<(f32, f32)>, radius: f32, keep_view: bool) -> (f32, bool) {
+    let max_zoom = zoom_limits(radius).1;
+    match previous {
+        Some((zoom, old_radius)) if keeps_view(keep_view, old_radius, radius) => (zoom, false),
+        _ => (max_zoom, true),
+    }
+}
+
+/// Generate the planet, on the plates of `plate_layout` if there is one
+pub fn generate_planet_data(settings: &TerrainSettings, plate_layout: Option<&PlateLayout>) -> PlanetData {
+    planetgen::config::reload_config();
+    let generator = PlanetGenerator::from(settings);
+    match plate_layout {
+        Some(layout) => generator.generate_with_plate_layout(layout),
+        None => generator.generate(),
+    }
+}
+
+/// Generate a coarse version of the planet for immediate display.
+/// Uses the same seed and settings, only the grid density is lowered.
+pub fn generate_preview_planet_data(
+    settings: &TerrainSettings,
+    plate_layout: Option<&PlateLayout>,
+) -> PlanetData {
+    planetgen::config::reload_config();
+    let mut generator = PlanetGenerator::from(settings);
+    generator.cells_per_unit = planetgen::config::get_config().generation.preview_cells_per_unit;
+    match plate_layout {
+        Some(layout) => generator.generate_with_plate_layout(layout),
+        None => generator.generate(),
+    }
+}
+
+/// Pure business logic: the planet generator configured from the terrain settings
+impl From<&TerrainSettings> for PlanetGenerator {
+    fn from(settings: &TerrainSettings) -> Self {
+        let mut generator = PlanetGenerator::new(settings.radius);
+        generator.oblateness = settings.oblateness;
+        generator.world_type = settings.world_type;
+        generator.num_plates = settings.num_plates;
+        generator.num_micro_plates = settings.num_micro_plates;
+        generator.seed = settings.seed;
+        generator.plate_seed = Some(expand_seed64(settings.plate_seed));
+        generator.terrain_seed = Some(expand_seed64(settings.terrain_seed));
+        generator.flow_warp_freq = settings.flow_warp_freq;
+        generator.flow_warp_steps = settings.flow_warp_steps;
+        generator.flow_warp_step_angle = settings.flow_warp_step_angle;
+
+        // Apply custom continent configuration from UI settings
+        let continent_config = planetgen::config::ContinentConfig {
+            continent_frequency: settings.continent_frequency,
+            continent_amplitude: settings.continent_amplitude,
+            distortion_frequency: settings.distortion_frequency,
+            distortion_amplitude: settings.distortion_amplitude,
+            detail_frequency: settings.detail_frequency,
+            detail_amplitude: settings.detail_amplitude,
+            continent_threshold: settings.continent_threshold,
+            ocean_depth_amplitude: settings.ocean_depth_amplitude,
+            // Ocean floor detail isn't exposed in the UI
+            ..planetgen::config::get_config().continents
+        };
+        generator.with_continent_config(continent_config);
+
+        // Apply mountain configuration from UI settings
+        generator.mountain_height = settings.mountain_height;
+        generator.mountain_width = settings.mountain_width;
+
+        generator
+    }
+}
+
+/// Lower end of the radius slider
+pub const MIN_PLANET_RADIUS: f32 = 5.0;
+/// Upper end of the radius slider, the grid budget has to hold up to here
+pub const MAX_PLANET_RADIUS: f32 = 100.0;
+
+/// Range of the plate count slider
+pub const MIN_PLATES: usize = 3;
+pub const MAX_PLATES: usize = 15;
+
+/// Terrain grid cells covered by one texel of the climate cubemaps. Climate varies much more
+/// slowly than the terrain, so the maps can be a lot coarser than the mesh.
+const TERRAIN_CELLS_PER_CLIMATE_TEXEL: usize = 4;
+
+/// Coarsest climate cubemap, below this the latitude bands get visibly blocky
+const MIN_CLIMATE_CUBEMAP_RESOLUTION: usize = 16;
+
+/// Coarsest and finest ocean sphere. Past the upper bound more segments don't show at any zoom.
+const MIN_OCEAN_GRID_SIZE: usize = 32;
+const MAX_OCEAN_GRID_SIZE: usize = 256;
+
+/// Grid the full generation will use for the current settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridBudget {
+    /// Vertices along one face edge the radius asks for
+    pub requested: usize,
+    /// Vertices along one face edge after the `max_face_grid_size` clamp
+    pub effective: usize,
+}
+
+impl GridBudget {
+    /// Whether the radius asks for more than the budget allows
+    pub fn is_clamped(&self) -> bool {
+        self.effective < self.requested
+    }
+
+    /// Vertices of the finest terrain mesh, six faces of `effective`² each
+    pub fn vertex_count(&self) -> usize {
+        face_vertex_count(self.effective)
+    }
+}
+
+/// Vertices of a cube sphere with `face_grid_size` vertices along each face edge
+pub fn face_vertex_count(face_grid_size: usize) -> usize {
+    6 * face_grid_size * face_grid_size
+}
+
+/// Grid a Generate at `radius` ends up with, shown before generating
+pub fn grid_budget(radius: f32) -> GridBudget {
+    let generator = PlanetGenerator::new(radius);
+    GridBudget {
+        requested: generator.requested_face_grid_size(),
+        effective: generator.face_grid_size(),
+    }
+}
+
+/// Resolution of a climate cubemap for a planet with `face_grid_size` vertices along each face
+/// edge. Follows the terrain grid so small planets don't pay for texels they can't show, capped
+/// at `max_resolution`: the maps hold 6 · resolution² texels and are resampled for every overlay.
+pub fn climate_cubemap_resolution(face_grid_size: usize, max_resolution: usize) -> usize {
+    let max_resolution = max_resolution.max(MIN_CLIMATE_CUBEMAP_RESOLUTION);
+    (face_grid_size.saturating_sub(1) / TERRAIN_CELLS_PER_CLIMATE_TEXEL)
+        .clamp(MIN_CLIMATE_CUBEMAP_RESOLUTION, max_resolution)
+}
+
+/// Segments of the ocean sphere for a planet with `face_grid_size` vertices along each face
+/// edge. A cube face spans a quarter of the equator, so four faces' worth of cells go around,
+/// which keeps the ocean as dense as the terrain until the cap.
+pub fn ocean_grid_size(face_grid_size: usize) -> u32 {
+    (face_grid_size.saturating_sub(1) * 4).clamp(MIN_OCEAN_GRID_SIZE, MAX_OCEAN_GRID_SIZE) as u32
+}
+
+/// How far below the horizon a surface point may be and still count as facing the camera,
+/// as the cosine between its normal and the direction to the camera. Keeps markers that
+/// stick out of the surface from popping before they disappear behind the planet.
+const HORIZON_MARGIN: f32 = 0.05;
+
+/// Whether the point on the planet surface in world direction `direction` from the planet
+/// center can be seen from `camera`, or is on the far side of the planet
+pub fn faces_camera(direction: Vec3, planet_center: Vec3, radius: f32, camera: Vec3) -> bool {
+    let surface = planet_center + direction * radius;
+    direction.dot((camera - surface).normalize_or_zero()) >= -HORIZON_MARGIN
+}
+
+/// Planet rotation that turns the surface point in planet direction `direction` to face
+/// `toward_camera`, reached from `current` by the smallest turn
+pub fn focus_rotation(current: Quat, direction: Vec3, toward_camera: Vec3) -> Quat {
+    let from = (current * direction).normalize();
+    (Quat::from_rotation_arc(from, toward_camera.normalize()) * current).normalize()
+}
+
+/// One smoothing step from `current` towards `target`, `factor` 0 to 1 of the way. `q` and
+/// `-q` are the same rotation, the step goes the short way around whichever sign `target` has.
+pub fn rotation_step(current: Quat, target: Quat, factor: f32) -> Quat {
+    let target = if current.dot(target) < 0.0 { -target } else { target };
+    current.slerp(target, factor.clamp(0.0, 1.0)).normalize()
+}
+
+/// Longest frame a spin advances by, so a frame stalled by an overlay rebuild doesn't fling the
+/// planet around
+pub const MAX_SPIN_STEP_SECS: f32 = 1.0 / 30.0;
+
+/// Spin the view keeps after a drag is released, slowing down until it stops
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpinInertia {
+    /// Exponential decay rate of the spin, per second
+    pub damping: f32,
+    /// Fastest spin a flick starts, radians per second
+    pub max_speed: f32,
+    /// Spins slower than this stop, radians per second
+    pub stop_speed: f32,
+    /// Current spin around the vertical axis, radians per second
+    pub velocity: f32,
+}
+
+impl Default for SpinInertia {
+    fn default() -> Self {
+        Self {
+            damping: 2.5,
+            max_speed: 6.0,
+            stop_speed: 0.02,
+            velocity: 0.0,
+        }
+    }
+}
+
+impl SpinInertia {
+    /// Follow a drag that turned the view by `angle` over the last `delta_secs`. Averaged with
+    /// the frames before, so one jittery frame doesn't decide the flick and holding still before
+    /// letting go leaves no spin.
+    pub fn track(&mut self, angle: f32, delta_secs: f32) {
+        if delta_secs <= 0.0 {
+            return;
+        }
+        let speed = (angle / delta_secs).clamp(-self.max_speed, self.max_speed);
+        self.velocity = (self.velocity + speed) * 0.5;
+    }
+
+    pub fn stop(&mut self) {
+        self.velocity = 0.0;
+    }
+
+    /// Angle to turn the view by this frame, slowing the spin down
+    pub fn step(&mut self, delta_secs: f32) -> f32 {
+        let delta_secs = delta_secs.clamp(0.0, MAX_SPIN_STEP_SECS);
+        let angle = self.velocity * delta_secs;
+        self.velocity *= (-self.damping * delta_secs).exp();
+        if self.velocity.abs() < self.stop_speed {
+            self.velocity = 0.0;
+        }
+        angle
+    }
+}
+
+/// Who a left button drag belongs to. A drag belongs to where it started: one started over the
+/// 3D view keeps turning the planet when the pointer passes over a panel, one started over the UI
+/// never turns it, even after leaving the UI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ViewDrag {
+    owned_by_view: bool,
+}
+
+impl ViewDrag {
+    /// Follow the button this frame, true while the view should follow the mouse
+    pub fn update(&mut self, just_pressed: bool, pressed: bool, pointer_over_ui: bool) -> bool {
+        if just_pressed {
+            self.owned_by_view = !pointer_over_ui;
+        }
+        if !pressed {
+            self.owned_by_view = false;
+        }
+        self.owned_by_view
+    }
+}
+
+/// Where the ray from `origin` along the normalized `direction` first meets the sphere of
+/// `radius` around `center`, None if it misses
+pub fn ray_sphere_hit(origin: Vec3, direction: Vec3, center: Vec3, radius: f32) -> Option<Vec3> {
+    let to_origin = origin - center;
+    let half_b = to_origin.dot(direction);
+    let discriminant = half_b * half_b - (to_origin.length_squared() - radius * radius);
+    if discriminant < 0.0 {
+        return None;
+    }
+    let near = -half_b - discriminant.sqrt();
+    let far = -half_b + discriminant.sqrt();
+    let distance = if near >= 0.0 { near } else { far };
+    (distance >= 0.0).then(|| origin + direction * distance)
+}
+
+/// Where a point on (or above) the surface ends up when the planet radius changes.
+/// With `scale_heights` the whole planet is scaled uniformly, otherwise the point keeps
+/// its absolute height above the (possibly oblate) surface.
+pub fn rescale_surface_position(
+    position: Vec3,
+    old_radius: f32,
+    new_radius: f32,
+    oblateness: f32,
+    scale_heights: bool,
+) -> Vec3 {
+    if scale_heights {
+        position * (new_radius / old_radius)
+    } else {
+        let surface_change =
+            oblate_radius(new_radius, oblateness, position) - oblate_radius(old_radius, oblateness, position);
+        position + position.normalize_or_zero() * surface_change
+    }
+}
+
+/// Every settings group whose terrain fields differ between `old` and `new`
+pub fn changed_terrain_groups(old: &TerrainSettings, new: &TerrainSettings) -> Vec<SettingsGroup> {
+    // No `..` on purpose: a new settings field doesn't compile until it is assigned a group
+    let TerrainSettings {
+        radius,
+        oblateness,
+        scale_heights_with_radius,
+        world_type,
+        num_plates,
+        num_micro_plates,
+        user_seed,
+        seed,
+        plate_seed,
+        terrain_seed,
+        preview_generation,
+        flow_warp_freq,
+        flow_warp_steps,
+        flow_warp_step_angle,
+        continent_frequency,
+        continent_amplitude,
+        distortion_frequency,
+        distortion_amplitude,
+        continent_threshold,
+        detail_frequency,
+        detail_amplitude,
+        ocean_depth_amplitude,
+        snow_threshold,
+        mountain_height,
+        mountain_width,
+    } = new;
+
+    let mut changed = Vec::new();
+    let mut mark = |group: SettingsGroup, differs: bool| {
+        if differs && !changed.contains(&group) {
+            changed.push(group);
+        }
+    };
+
+    mark(SettingsGroup::Terrain, *radius != old.radius);
+    mark(SettingsGroup::Terrain, *oblateness != old.oblateness);
+    mark(SettingsGroup::Terrain, *scale_heights_with_radius != old.scale_heights_with_radius);
+    mark(SettingsGroup::Terrain, *world_type != old.world_type);
+    mark(SettingsGroup::Terrain, *num_plates != old.num_plates);
+    mark(SettingsGroup::Terrain, *num_micro_plates != old.num_micro_plates);
+    mark(SettingsGroup::Terrain, *user_seed != old.user_seed);
+    mark(SettingsGroup::Terrain, *seed != old.seed);
+    mark(SettingsGroup::Terrain, *plate_seed != old.plate_seed);
+    mark(SettingsGroup::Terrain, *terrain_seed != old.terrain_seed);
+    mark(SettingsGroup::Terrain, *preview_generation != old.preview_generation);
+    mark(SettingsGroup::Terrain, *flow_warp_freq != old.flow_warp_freq);
+    mark(SettingsGroup::Terrain, *flow_warp_steps != old.flow_warp_steps);
+    mark(SettingsGroup::Terrain, *flow_warp_step_angle != old.flow_warp_step_angle);
+    mark(SettingsGroup::Terrain, *continent_frequency != old.continent_frequency);
+    mark(SettingsGroup::Terrain, *continent_amplitude != old.continent_amplitude);
+    mark(SettingsGroup::Terrain, *distortion_frequency != old.distortion_frequency);
+    mark(SettingsGroup::Terrain, *distortion_amplitude != old.distortion_amplitude);
+    mark(SettingsGroup::Terrain, *continent_threshold != old.continent_threshold);
+    mark(SettingsGroup::Terrain, *detail_frequency != old.detail_frequency);
+    mark(SettingsGroup::Terrain, *detail_amplitude != old.detail_amplitude);
+    mark(SettingsGroup::Terrain, *ocean_depth_amplitude != old.ocean_depth_amplitude);
+    mark(SettingsGroup::Terrain, *snow_threshold != old.snow_threshold);
+    mark(SettingsGroup::Terrain, *mountain_height != old.mountain_height);
+    mark(SettingsGroup::Terrain, *mountain_width != old.mountain_width);
+
+    changed
+}
+
+/// Every settings group whose climate fields differ between `old` and `new`
+pub fn changed_climate_groups(old: &ClimateSettings, new: &ClimateSettings) -> Vec<SettingsGroup> {
+    // No `..` on purpose: a new settings field doesn't compile until it is assigned a group
+    let ClimateSettings {
+        land_temperature_bonus,
+        temperature_equator_temp,
+        temperature_pole_temp,
+        temperature_max_temp,
+        temperature_min_temp,
+        temperature_latitude_falloff,
+        temperature_lapse_rate,
+        temperature_cubemap_resolution,
+        temperature_cubemap_override,
+        precipitation_temperature_weight,
+        precipitation_ocean_weight,
+        precipitation_cubemap_resolution,
+        precipitation_cubemap_override,
+        biome_ice_temp,
+        biome_tundra_temp,
+        biome_boreal_temp,
+        biome_temperate_temp,
+        biome_hot_temp,
+        biome_desert_precip,
+        biome_savanna_precip,
+        biome_jungle_precip,
+        biome_temperate_precip,
+        biome_ice_color,
+        biome_tundra_color,
+        biome_desert_color,
+        biome_savanna_color,
+        biome_temperate_color,
+        biome_jungle_color,
+    } = new;
+
+    let mut changed = Vec::new();
+    let mut mark = |group: SettingsGroup, differs: bool| {
+        if differs && !changed.contains(&group) {
+            changed.push(group);
+        }
+    };
+
+    mark(SettingsGroup::Temperature, *land_temperature_bonus != old.land_temperature_bonus);
+    mark(SettingsGroup::Temperature, *temperature_equator_temp != old.temperature_equator_temp);
+    mark(SettingsGroup::Temperature, *temperature_pole_temp != old.temperature_pole_temp);
+    mark(SettingsGroup::Temperature, *temperature_max_temp != old.temperature_max_temp);
+    mark(SettingsGroup::Temperature, *temperature_min_temp != old.temperature_min_temp);
+    mark(SettingsGroup::Temperature, *temperature_latitude_falloff != old.temperature_latitude_falloff);
+    mark(SettingsGroup::Temperature, *temperature_lapse_rate != old.temperature_lapse_rate);
+    mark(SettingsGroup::ClimateResolution, *temperature_cubemap_resolution != old.temperature_cubemap_resolution);
+    mark(SettingsGroup::ClimateResolution, *temperature_cubemap_override != old.temperature_cubemap_override);
+    mark(SettingsGroup::Precipitation, *precipitation_temperature_weight != old.precipitation_temperature_weight);
+    mark(SettingsGroup::Precipitation, *precipitation_ocean_weight != old.precipitation_ocean_weight);
+    mark(SettingsGroup::ClimateResolution, *precipitation_cubemap_resolution != old.precipitation_cubemap_resolution);
+    mark(SettingsGroup::ClimateResolution, *precipitation_cubemap_override != old.precipitation_cubemap_override);
+    mark(SettingsGroup::Biome, *biome_ice_temp != old.biome_ice_temp);
+    mark(SettingsGroup::Biome, *biome_tundra_temp != old.biome_tundra_temp);
+    mark(SettingsGroup::Biome, *biome_boreal_temp != old.biome_boreal_temp);
+    mark(SettingsGroup::Biome, *biome_temperate_temp != old.biome_temperate_temp);
+    mark(SettingsGroup::Biome, *biome_hot_temp != old.biome_hot_temp);
+    mark(SettingsGroup::Biome, *biome_desert_precip != old.biome_desert_precip);
+    mark(SettingsGroup::Biome, *biome_savanna_precip != old.biome_savanna_precip);
+    mark(SettingsGroup::Biome, *biome_jungle_precip != old.biome_jungle_precip);
+    mark(SettingsGroup::Biome, *biome_temperate_precip != old.biome_temperate_precip);
+    mark(SettingsGroup::Biome, *biome_ice_color != old.biome_ice_color);
+    mark(SettingsGroup::Biome, *biome_tundra_color != old.biome_tundra_color);
+    mark(SettingsGroup::Biome, *biome_desert_color != old.biome_desert_color);
+    mark(SettingsGroup::Biome, *biome_savanna_color != old.biome_savanna_color);
+    mark(SettingsGroup::Biome, *biome_temperate_color != old.biome_temperate_color);
+    mark(SettingsGroup::Biome, *biome_jungle_color != old.biome_jungle_color);
+
+    changed
+}
+
+/// Every settings group whose wind fields differ between `old` and `new`
+pub fn changed_wind_groups(old: &WindSettings, new: &WindSettings) -> Vec<SettingsGroup> {
+    // No `..` on purpose: a new settings field doesn't compile until it is assigned a group
+    let WindSettings {
+        wind_particle_count,
+        wind_particle_height_offset,
+        wind_terrain_following,
+        wind_zonal_speed,
+        wind_particle_lifespan,
+        wind_display,
+        wind_glyph_spacing,
+        streamline_arc_length,
+        wind_deflection_height_threshold,
+        wind_deflection_height_scale,
+        wind_deflection_spread_radius,
+        wind_deflection_spread_decay,
+        wind_deflection_strength,
+        wind_deflection_iterations,
+    } = new;
+
+    let mut changed = Vec::new();
+    let mut mark = |group: SettingsGroup, differs: bool| {
+        if differs && !changed.contains(&group) {
+            changed.push(group);
+        }
+    };
+
+    mark(SettingsGroup::Wind, *wind_particle_count != old.wind_particle_count);
+    mark(SettingsGroup::Wind, *wind_particle_height_offset != old.wind_particle_height_offset);
+    mark(SettingsGroup::Wind, *wind_terrain_following != old.wind_terrain_following);
+    mark(SettingsGroup::Wind, *wind_zonal_speed != old.wind_zonal_speed);
+    mark(SettingsGroup::Wind, *wind_particle_lifespan != old.wind_particle_lifespan);
+    mark(SettingsGroup::Wind, *wind_display != old.wind_display);
+    mark(SettingsGroup::Wind, *wind_glyph_spacing != old.wind_glyph_spacing);
+    mark(SettingsGroup::Wind, *streamline_arc_length != old.streamline_arc_length);
+    mark(SettingsGroup::WindDeflection, *wind_deflection_height_threshold != old.wind_deflection_height_threshold);
+    mark(SettingsGroup::WindDeflection, *wind_deflection_height_scale != old.wind_deflection_height_scale);
+    mark(SettingsGroup::WindDeflection, *wind_deflection_spread_radius != old.wind_deflection_spread_radius);
+    mark(SettingsGroup::WindDeflection, *wind_deflection_spread_decay != old.wind_deflection_spread_decay);
+    mark(SettingsGroup::WindDeflection, *wind_deflection_strength != old.wind_deflection_strength);
+    mark(SettingsGroup::WindDeflection, *wind_deflection_iterations != old.wind_deflection_iterations);
+
+    changed
+}
+
+/// Every settings group whose display fields differ between `old` and `new`
+pub fn changed_display_groups(old: &DisplaySettings, new: &DisplaySettings) -> Vec<SettingsGroup> {
+    // No `..` on purpose: a new settings field doesn't compile until it is assigned a group
+    let DisplaySettings {
+        show_arrows,
+        cull_far_side,
+        rotation_inertia,
+        keep_view,
+        show_axis,
+        show_feature_labels,
+        show_coastline,
+        axial_tilt,
+        moons,
+        overlay_textures,
+        palette,
+        ui_theme,
+        language,
+        camera_offset,
+        look_at_offset,
+        view_mode_plates,
+        plate_crust_colors,
+        hillshade_azimuth,
+        hillshade_elevation,
+        hillshade_strength,
+        color_variation_strength,
+        show_ocean,
+        ocean_foam_color,
+        ocean_foam_width,
+        show_wind,
+        show_wind_influence,
+        show_vertical_air,
+        show_temperature,
+        show_precipitation,
+    } = new;
+
+    let mut changed = Vec::new();
+    let mut mark = |group: SettingsGroup, differs: bool| {
+        if differs && !changed.contains(&group) {
+            changed.push(group);
+        }
     };
-    generator.with_continent_config(continent_config);
 
-    // Apply mountain configuration from UI settings
-    generator.mountain_height = settings.mountain_height;
-    generator.mountain_width = settings.mountain_width;
+    mark(SettingsGroup::Visual, *show_arrows != old.show_arrows);
+    mark(SettingsGroup::Visual, *cull_far_side != old.cull_far_side);
+    mark(SettingsGroup::Camera, *rotation_inertia != old.rotation_inertia);
+    mark(SettingsGroup::Camera, *keep_view != old.keep_view);
+    mark(SettingsGroup::Visual, *show_axis != old.show_axis);
+    mark(SettingsGroup::Visual, *show_feature_labels != old.show_feature_labels);
+    mark(SettingsGroup::Visual, *show_coastline != old.show_coastline);
+    mark(SettingsGroup::Visual, *axial_tilt != old.axial_tilt);
+    mark(SettingsGroup::Moons, *moons != old.moons);
+    mark(SettingsGroup::Visual, *overlay_textures != old.overlay_textures);
+    mark(SettingsGroup::Palette, *palette != old.palette);
+    mark(SettingsGroup::Ui, *ui_theme != old.ui_theme);
+    mark(SettingsGroup::Ui, *language != old.language);
+    mark(SettingsGroup::Camera, *camera_offset != old.camera_offset);
+    mark(SettingsGroup::Camera, *look_at_offset != old.look_at_offset);
+    mark(SettingsGroup::Visual, *view_mode_plates != old.view_mode_plates);
+    mark(SettingsGroup::PlateView, *plate_crust_colors != old.plate_crust_colors);
+    mark(SettingsGroup::Biome, *hillshade_azimuth != old.hillshade_azimuth);
+    mark(SettingsGroup::Biome, *hillshade_elevation != old.hillshade_elevation);
+    mark(SettingsGroup::Biome, *hillshade_strength != old.hillshade_strength);
+    mark(SettingsGroup::Biome, *color_variation_strength != old.color_variation_strength);
+    mark(SettingsGroup::Visual, *show_ocean != old.show_ocean);
+    mark(SettingsGroup::Ocean, *ocean_foam_color != old.ocean_foam_color);
+    mark(SettingsGroup::Ocean, *ocean_foam_width != old.ocean_foam_width);
+    mark(SettingsGroup::Visual, *show_wind != old.show_wind);
+    mark(SettingsGroup::Visual, *show_wind_influence != old.show_wind_influence);
+    mark(SettingsGroup::Visual, *show_vertical_air != old.show_vertical_air);
+    mark(SettingsGroup::Visual, *show_temperature != old.show_temperature);
+    mark(SettingsGroup::Visual, *show_precipitation != old.show_precipitation);
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planet::resources::{MoonSettings, PlanetSettings};
+    use crate::ui::locale::Language;
+    use crate::ui::theme::ThemePreset;
+    use planetgen::prelude::Palette;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Vec3::Y)]
+    #[case(Vec3::NEG_Y)]
+    #[case(Vec3::new(0.3, -0.2, -0.9).normalize())]
+    #[case(Vec3::NEG_Z)]
+    fn test_focused_direction_faces_the_camera(#[case] direction: Vec3) {
+        let toward_camera = Vec3::new(0.25, 0.0, 1.0).normalize();
+        let current = Quat::from_rotation_y(0.8) * Quat::from_rotation_x(0.3);
+
+        let target = focus_rotation(current, direction, toward_camera);
+
+        assert!((target * direction).distance(toward_camera) < 1e-4);
+    }
+
+    #[test]
+    fn test_focus_turns_no_further_than_needed() {
+        let toward_camera = Vec3::Z;
+        let direction = Vec3::new(0.0, 0.5, 1.0).normalize();
+
+        let target = focus_rotation(Quat::IDENTITY, direction, toward_camera);
+
+        let needed = direction.angle_between(toward_camera);
+        assert!((target.angle_between(Quat::IDENTITY) - needed).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_rotation_steps_take_the_short_way_for_either_sign() {
+        let current = Quat::from_rotation_y(0.1);
+        let target = Quat::from_rotation_y(0.5);
+
+        for target in [target, -target] {
+            let step = rotation_step(current, target, 0.5);
+            // Halfway is 0.3 around Y, the long way would pass through the opposite side
+            assert!(step.angle_between(Quat::from_rotation_y(0.3)) < 1e-4);
+            assert!(rotation_step(current, target, 1.0).angle_between(target) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_released_spin_slows_down_and_stops() {
+        let mut inertia = SpinInertia::default();
+        for _ in 0..4 {
+            inertia.track(0.05, 0.02);
+        }
+        let flick = inertia.velocity;
+        assert!(flick > 2.0 && flick <= inertia.max_speed);
+
+        let first = inertia.step(0.02);
+        assert!((first - flick * 0.02).abs() < 1e-6);
+        assert!(inertia.velocity < flick);
+        let mut frames = 0;
+        while inertia.velocity != 0.0 {
+            inertia.step(0.02);
+            frames += 1;
+            assert!(frames < 1000, "never stopped");
+        }
+        assert_eq!(inertia.step(0.02), 0.0);
+    }
+
+    #[test]
+    fn test_spin_is_clamped_and_hitches_are_capped() {
+        let mut inertia = SpinInertia::default();
+        inertia.track(10.0, 0.01);
+        inertia.track(10.0, 0.01);
+        assert!(inertia.velocity <= inertia.max_speed);
+
+        // A frame stalled for a second turns no further than a normal long frame
+        let velocity = inertia.velocity;
+        assert!((inertia.step(1.0) - velocity * MAX_SPIN_STEP_SECS).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_holding_still_before_release_leaves_no_spin() {
+        let mut inertia = SpinInertia::default();
+        inertia.track(0.05, 0.02);
+        for _ in 0..20 {
+            inertia.track(0.0, 0.02);
+        }
+        inertia.step(0.02);
+        assert_eq!(inertia.velocity, 0.0);
+    }
+
+    #[test]
+    fn test_drag_started_over_the_view_keeps_turning_it_over_the_ui() {
+        let mut drag = ViewDrag::default();
+        assert!(drag.update(true, true, false));
+        assert!(drag.update(false, true, true));
+        assert!(!drag.update(false, false, false));
+        assert!(!drag.update(false, false, false));
+    }
+
+    #[test]
+    fn test_drag_started_over_the_ui_never_turns_the_view() {
+        let mut drag = ViewDrag::default();
+        assert!(!drag.update(true, true, true));
+        assert!(!drag.update(false, true, false));
+
+        // The next press over the view is a drag of its own
+        drag.update(false, false, false);
+        assert!(drag.update(true, true, false));
+    }
+
+    #[test]
+    fn test_cursor_rays_hit_the_near_side_of_the_planet() {
+        let origin = Vec3::new(0.0, 0.0, 30.0);
+        let hit = ray_sphere_hit(origin, Vec3::NEG_Z, Vec3::ZERO, 10.0);
+        assert_eq!(hit, Some(Vec3::new(0.0, 0.0, 10.0)));
+        assert_eq!(ray_sphere_hit(origin, Vec3::Z, Vec3::ZERO, 10.0), None);
+        assert_eq!(ray_sphere_hit(origin, Vec3::NEG_Z, Vec3::new(20.0, 0.0, 0.0), 10.0), None);
+    }
+
+    #[test]
+    fn test_absolute_heights_keep_distance_above_surface() {
+        let mountain_top = Vec3::new(0.0, 53.0, 0.0);
+        let rescaled = rescale_surface_position(mountain_top, 50.0, 20.0, 0.0, false);
+
+        assert!((rescaled - Vec3::new(0.0, 23.0, 0.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_absolute_heights_follow_the_flattened_poles() {
+        // 3 above the pole of an oblate planet, where the surface is at 50 * 0.9
+        let mountain_top = Vec3::new(0.0, 48.0, 0.0);
+        let rescaled = rescale_surface_position(mountain_top, 50.0, 20.0, 0.1, false);
+
+        assert!((rescaled - Vec3::new(0.0, 21.0, 0.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_scaled_heights_grow_with_radius() {
+        let mountain_top = Vec3::new(30.0, 0.0, 40.0); // 50 units from the center
+        let rescaled = rescale_surface_position(mountain_top, 40.0, 80.0, 0.0, true);
+
+        assert!((rescaled.length() - 100.0).abs() < 1e-3);
+        assert!((rescaled.normalize() - mountain_top.normalize()).length() < 1e-6);
+    }
+
+    #[rstest]
+    #[case(5.0)]
+    #[case(50.0)]
+    #[case(MAX_PLANET_RADIUS)]
+    fn test_layers_hug_the_surface_at_any_radius(#[case] radius: f32) {
+        let top = static_layer_offset(SurfaceLayer::PlateArrows, radius);
+        assert!(top < radius * 0.02, "{top} above a planet of radius {radius}");
+    }
+
+    #[rstest]
+    #[case(5.0, 7.5)]
+    #[case(50.0, 175.0)]
+    #[case(50.0, 1000.0)]
+    fn test_layers_stay_apart_in_depth(#[case] radius: f32, #[case] camera_distance: f32) {
+        let rings = layer_offset(SurfaceLayer::LatitudeRings, radius, camera_distance);
+        let arrows = layer_offset(SurfaceLayer::PlateArrows, radius, camera_distance);
+
+        let min_gap = camera_distance * LAYER_DEPTH_STEP;
+        assert!(rings >= min_gap && arrows - rings >= min_gap, "{rings}, {arrows}");
+    }
+
+    #[rstest]
+    #[case(Vec3::Z, true)]
+    #[case(Vec3::new(0.6, 0.0, 0.8), true)]
+    // From 3 radii away the horizon is at acos(1/3) ≈ 70.5° from the camera axis
+    #[case(Vec3::new(70f32.to_radians().sin(), 0.0, 70f32.to_radians().cos()), true)]
+    #[case(Vec3::new(75f32.to_radians().sin(), 0.0, 75f32.to_radians().cos()), false)]
+    #[case(Vec3::X, false)]
+    #[case(-Vec3::Z, false)]
+    fn test_only_the_near_side_faces_the_camera(#[case] direction: Vec3, #[case] expected: bool) {
+        let camera = Vec3::new(0.0, 0.0, 150.0);
+        assert_eq!(faces_camera(direction, Vec3::ZERO, 50.0, camera), expected);
+    }
+
+    #[rstest]
+    #[case(0, 1.5, 0)]
+    #[case(0, 3.5, 2)]
+    #[case(2, 1.5, 0)]
+    // Inside the hysteresis band the current LOD stays
+    #[case(0, 2.25, 0)]
+    #[case(1, 2.15, 1)]
+    #[case(1, 2.0, 0)]
+    #[case(1, 3.05, 2)]
+    fn test_terrain_lod_follows_zoom_with_hysteresis(
+        #[case] current: usize,
+        #[case] zoom_in_radii: f32,
+        #[case] expected: usize,
+    ) {
+        assert_eq!(terrain_lod_level(current, zoom_in_radii * 50.0, 50.0), expected);
+    }
+
+    #[rstest]
+    // The same radius keeps the zoom as it is, even past the limits
+    #[case(Some((80.0, 50.0)), 50.0, true, (80.0, false))]
+    // A small change keeps it too, settle_zoom eases it into the new limits
+    #[case(Some((80.0, 50.0)), 55.0, true, (80.0, false))]
+    #[case(Some((100.0, 50.0)), 45.0, true, (100.0, false))]
+    // A large change, no view to keep or the view not kept frame the planet from afar
+    #[case(Some((80.0, 50.0)), 65.0, true, (227.5, true))]
+    #[case(None, 50.0, true, (175.0, true))]
+    #[case(Some((80.0, 50.0)), 50.0, false, (175.0, true))]
+    fn test_regenerated_planet_keeps_the_zoom_unless_the_radius_jumps(
+        #[case] previous: Option<(f32, f32)>,
+        #[case] radius: f32,
+        #[case] keep_view: bool,
+        #[case] expected: (f32, bool),
+    ) {
+        assert_eq!(regenerated_zoom(previous, radius, keep_view), expected);
+    }
+
+    #[rstest]
+    // From the farthest zoom of radius 50 to a planet of radius 5, and the other way around
+    #[case(175.0, 5.0, 17.5)]
+    #[case(7.5, 50.0, 75.0)]
+    #[case(100.0, 50.0, 100.0)]
+    fn test_zoom_settles_into_the_limits_of_a_new_radius(#[case] zoom: f32, #[case] radius: f32, #[case] settled: f32) {
+        let limits = zoom_limits(radius);
+        let mut current = zoom;
+        for _ in 0..300 {
+            let next = settle_zoom(current, limits, 1.0 / 60.0);
+            // Every frame moves towards the limits without passing them
+            assert!((next - settled).abs() <= (current - settled).abs());
+            assert!((next - current).abs() <= (zoom - settled).abs() * 0.1, "{current} jumped to {next}");
+            current = next;
+        }
+        assert_eq!(current, settled);
+    }
+
+    #[test]
+    fn test_settle_zoom_is_frame_rate_independent() {
+        let limits = zoom_limits(5.0);
+        let one_step = settle_zoom(175.0, limits, 0.1);
+        let two_steps = settle_zoom(settle_zoom(175.0, limits, 0.05), limits, 0.05);
+        assert!((one_step - two_steps).abs() < 1e-3);
+        assert!(one_step < 175.0 && one_step > 17.5);
+    }
+
+    #[test]
+    fn test_centered_composition_looks_at_the_planet_center() {
+        let composition = CameraComposition {
+            camera_offset: 0.0,
+            look_at_offset: 0.0,
+        };
+        assert_eq!(composition.framing(60.0), (Vec3::new(0.0, 0.0, 60.0), Vec3::ZERO));
+        assert_eq!(composition.distance_per_zoom(), 1.0);
+    }
+
+    #[test]
+    fn test_framing_scales_with_the_zoom() {
+        let composition = CameraComposition {
+            camera_offset: 0.25,
+            look_at_offset: 0.15,
+        };
+        let (camera, look_at) = composition.framing(100.0);
+        assert!(camera.abs_diff_eq(Vec3::new(25.0, 0.0, 100.0), 1e-4));
+        assert!(look_at.abs_diff_eq(Vec3::new(15.0, 0.0, 0.0), 1e-4));
+        assert!((camera.length() - 100.0 * composition.distance_per_zoom()).abs() < 1e-3);
+    }
+
+    /// Groups the settings diff reports after `change` is applied to the default settings
+    fn groups_after<T: PlanetSettings + Default>(change: impl FnOnce(&mut T)) -> Vec<SettingsGroup> {
+        let old = T::default();
+        let mut new = old.clone();
+        change(&mut new);
+        new.changed_groups(&old)
+    }
+
+    #[test]
+    fn test_identical_settings_have_no_changes() {
+        assert!(groups_after(|_: &mut TerrainSettings| {}).is_empty());
+        assert!(groups_after(|_: &mut ClimateSettings| {}).is_empty());
+        assert!(groups_after(|_: &mut WindSettings| {}).is_empty());
+        assert!(groups_after(|_: &mut DisplaySettings| {}).is_empty());
+    }
+
+    #[rstest]
+    #[case(groups_after(|s: &mut TerrainSettings| s.continent_threshold += 0.1), SettingsGroup::Terrain)]
+    #[case(groups_after(|s: &mut TerrainSettings| s.terrain_seed = s.terrain_seed.wrapping_add(1)), SettingsGroup::Terrain)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.ocean_foam_width += 0.1), SettingsGroup::Ocean)]
+    #[case(groups_after(|s: &mut ClimateSettings| s.land_temperature_bonus += 1.0), SettingsGroup::Temperature)]
+    #[case(groups_after(|s: &mut ClimateSettings| s.temperature_lapse_rate += 1.0), SettingsGroup::Temperature)]
+    #[case(groups_after(|s: &mut ClimateSettings| s.precipitation_ocean_weight += 0.1), SettingsGroup::Precipitation)]
+    #[case(groups_after(|s: &mut ClimateSettings| s.temperature_cubemap_override = Some(32)), SettingsGroup::ClimateResolution)]
+    #[case(groups_after(|s: &mut ClimateSettings| s.precipitation_cubemap_resolution += 16), SettingsGroup::ClimateResolution)]
+    #[case(groups_after(|s: &mut WindSettings| s.wind_zonal_speed += 1.0), SettingsGroup::Wind)]
+    #[case(groups_after(|s: &mut WindSettings| s.streamline_arc_length += 10.0), SettingsGroup::Wind)]
+    #[case(groups_after(|s: &mut WindSettings| s.wind_glyph_spacing += 5.0), SettingsGroup::Wind)]
+    #[case(groups_after(|s: &mut WindSettings| s.wind_deflection_strength += 0.1), SettingsGroup::WindDeflection)]
+    #[case(groups_after(|s: &mut ClimateSettings| s.biome_jungle_color[1] += 0.1), SettingsGroup::Biome)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.hillshade_strength += 0.1), SettingsGroup::Biome)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.color_variation_strength += 0.01), SettingsGroup::Biome)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.show_temperature = !s.show_temperature), SettingsGroup::Visual)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.plate_crust_colors = !s.plate_crust_colors), SettingsGroup::PlateView)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.palette = Palette::Cividis), SettingsGroup::Palette)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.ui_theme = ThemePreset::Light), SettingsGroup::Ui)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.language = Language::German), SettingsGroup::Ui)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.camera_offset = 0.0), SettingsGroup::Camera)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.look_at_offset = 0.0), SettingsGroup::Camera)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.rotation_inertia = !s.rotation_inertia), SettingsGroup::Camera)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.keep_view = !s.keep_view), SettingsGroup::Camera)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.show_feature_labels = !s.show_feature_labels), SettingsGroup::Visual)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.show_coastline = !s.show_coastline), SettingsGroup::Visual)]
+    #[case(groups_after(|s: &mut DisplaySettings| s.moons.push(MoonSettings::default())), SettingsGroup::Moons)]
+    fn test_single_field_change_reports_its_group(#[case] changed: Vec<SettingsGroup>, #[case] expected: SettingsGroup) {
+        assert_eq!(changed, vec![expected]);
+    }
+
+    #[test]
+    fn test_each_group_is_reported_once() {
+        let changed = groups_after(|s: &mut ClimateSettings| {
+            s.temperature_pole_temp += 1.0;
+            s.temperature_equator_temp += 1.0;
+            s.precipitation_ocean_weight += 0.1;
+        });
+
+        assert_eq!(changed, vec![SettingsGroup::Temperature, SettingsGroup::Precipitation]);
+    }
+
+    #[test]
+    fn test_no_reachable_radius_exceeds_the_vertex_budget() {
+        let max_face_grid_size = planetgen::config::get_config().generation.max_face_grid_size;
+        let budget = face_vertex_count(max_face_grid_size);
+
+        // Every stop of the radius slider, 5 to 100 in steps of 0.5
+        for step in 0..=190 {
+            let radius = 5.0 + step as f32 * 0.5;
+            let grid = grid_budget(radius);
+
+            assert!(grid.vertex_count() <= budget, "radius {radius}: {} vertices", grid.vertex_count());
+        }
+        assert!(grid_budget(MAX_PLANET_RADIUS).is_clamped());
+        assert!(!grid_budget(5.0).is_clamped());
+    }
+
+    #[rstest]
+    #[case(6, 16)]
+    #[case(251, 62)]
+    #[case(321, 80)]
+    #[case(2001, 128)]
+    fn test_climate_cubemaps_follow_the_grid_up_to_the_cap(#[case] face_grid_size: usize, #[case] expected: usize) {
+        assert_eq!(climate_cubemap_resolution(face_grid_size, 128), expected);
+    }
 
-    generator
+    #[test]
+    fn test_ocean_grid_stays_within_bounds() {
+        assert_eq!(ocean_grid_size(2), 32);
+        assert_eq!(ocean_grid_size(26), 100);
+        assert_eq!(ocean_grid_size(321), 256);
+    }
 }
@@ -1,62 +1,195 @@
+pub mod axis;
 pub mod biome;
+pub mod coastline;
 pub mod components;
+pub mod crust_age;
+pub mod debug_panel;
+pub mod drift;
 pub mod events;
+pub mod gamepad;
+pub mod help;
+pub mod history;
+pub mod jobs;
+pub mod labels;
+pub mod legend;
+pub mod moons;
+pub mod picking;
+pub mod plate_panel;
+pub mod probe;
 pub mod resources;
+pub mod streamlines;
 pub mod systems;
 pub mod ui;
 pub mod view;
 pub mod wind;
+pub mod wind_glyphs;
 pub mod temperature;
 pub mod precipitation;
-mod logic;
+pub mod profiles;
+pub mod randomize;
+pub(crate) mod logic;
 
 use crate::core::state::GameState;
 use crate::planet::events::*;
 use crate::planet::resources::*;
 use crate::planet::systems::*;
-use crate::planet::view::handle_tab_visibility;
+use crate::planet::view::{apply_overlay_colors, handle_tab_visibility, update_overlay_hint};
+use crate::planet::axis::AxisPlugin;
 use crate::planet::biome::BiomePlugin;
+use crate::planet::coastline::CoastlinePlugin;
+use crate::planet::crust_age::CrustAgePlugin;
+use crate::planet::debug_panel::DebugPanelPlugin;
+use crate::planet::drift::DriftPlugin;
+use crate::planet::gamepad::GamepadControlsPlugin;
+use crate::planet::help::HelpPlugin;
+use crate::planet::jobs::ClimateJobsPlugin;
+use crate::planet::labels::FeatureLabelsPlugin;
+use crate::planet::legend::LegendPlugin;
+use crate::planet::moons::MoonsPlugin;
+use crate::planet::picking::PickingPlugin;
+use crate::planet::plate_panel::PlatePanelPlugin;
+use crate::planet::probe::ProbePlugin;
+use crate::planet::streamlines::StreamlinesPlugin;
 use crate::planet::wind::WindPlugin;
+use crate::planet::wind_glyphs::WindGlyphsPlugin;
 use crate::planet::temperature::TemperaturePlugin;
 use crate::planet::precipitation::PrecipitationPlugin;
+use crate::planet::profiles::ProfilesPlugin;
 use bevy::prelude::*;
 
+/// Rebuilds of the climate cubemaps in the order they depend on each other: the wind, the
+/// vertical air from it, the temperature and the precipitation from both. None of them exist
+/// before the first planet is spawned. The slow rebuilds are queued as climate jobs, what they
+/// built is applied in the set of its cubemap.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClimateRebuildSet {
+    Wind,
+    VerticalAir,
+    Temperature,
+    Precipitation,
+}
+
+/// Where every settings resource is compared against its last applied copy, systems reading
+/// `SettingsDiff` the same frame run after it
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SettingsDiffSet;
+
 pub struct PlanetPlugin;
 
 impl Plugin for PlanetPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_plugins(AxisPlugin)
+            .add_plugins(CoastlinePlugin)
             .add_plugins(BiomePlugin)
             .add_plugins(WindPlugin)
             .add_plugins(TemperaturePlugin)
             .add_plugins(PrecipitationPlugin)
+            .add_plugins(ClimateJobsPlugin)
+            .add_plugins(ProfilesPlugin)
+            .add_plugins(CrustAgePlugin)
+            .add_plugins(DriftPlugin)
+            .add_plugins(PlatePanelPlugin)
+            .add_plugins(LegendPlugin)
+            .add_plugins(MoonsPlugin)
+            .add_plugins(PickingPlugin)
+            .add_plugins(ProbePlugin)
+            .add_plugins(StreamlinesPlugin)
+            .add_plugins(WindGlyphsPlugin)
+            .add_plugins(FeatureLabelsPlugin)
+            .add_plugins(DebugPanelPlugin)
+            .add_plugins(GamepadControlsPlugin)
+            .add_plugins(HelpPlugin)
             .add_message::<GeneratePlanetEvent>()
             .add_message::<GenerateNewSeedEvent>()
+            .add_message::<RerollPlanetEvent>()
+            .add_message::<RescalePlanetEvent>()
             .add_message::<ToggleArrowsEvent>()
             .add_message::<SetCameraPositionEvent>()
-            .add_message::<SettingsChanged>()
-            .add_message::<TabSwitchEvent>()
-            .add_message::<WindTabActiveEvent>()
-            .add_message::<TectonicTabActiveEvent>()
-            .add_message::<TemperatureTabActiveEvent>()
-            .add_message::<PrecipitationTabActiveEvent>()
+            .add_message::<SettingsDiff>()
+            .add_message::<SetActiveView>()
             .add_message::<PlanetSpawnedEvent>()
+            .add_message::<TerrainLodChangedEvent>()
             .add_message::<ResetCameraEvent>()
+            .add_message::<FocusCameraEvent>()
+            .add_message::<WindRebuilt>()
+            .add_message::<PrecipitationRebuilt>()
             .init_resource::<CurrentPlanetData>()
+            .init_resource::<OverlayColorCache>()
+            .init_resource::<OverlayMeshes>()
+            .init_resource::<PlanetAssets>()
+            .init_resource::<PendingPlanetGeneration>()
+            .init_resource::<KeptPlateLayout>()
+            .init_resource::<OverlayHint>()
+            .init_resource::<WaterLevel>()
+            .configure_sets(
+                Update,
+                (
+                    ClimateRebuildSet::Wind,
+                    ClimateRebuildSet::VerticalAir,
+                    ClimateRebuildSet::Temperature,
+                    ClimateRebuildSet::Precipitation,
+                )
+                    .chain()
+                    .after(spawn_planet_on_event)
+                    .after(swap_in_full_resolution_planet)
+                    .after(sync_water_level)
+                    .after(SettingsDiffSet),
+            )
             .add_systems(
                 OnEnter(GameState::PlanetGeneration),
                 auto_generate_initial_planet,
             )
-            .add_systems(Update, (spawn_planet_on_event, handle_arrow_toggle))
             .add_systems(
                 Update,
                 (
+                    despawn_stale_planets.before(spawn_planet_on_event),
+                    spawn_planet_on_event,
+                    release_replaced_planet_assets.after(spawn_planet_on_event),
+                    swap_in_full_resolution_planet.after(spawn_planet_on_event),
+                    rescale_planet_on_event.after(swap_in_full_resolution_planet),
+                    sync_water_level.after(rescale_planet_on_event),
+                    (
+                        emit_settings_diff::<TerrainSettings>,
+                        emit_settings_diff::<ClimateSettings>,
+                        emit_settings_diff::<WindSettings>,
+                        emit_settings_diff::<DisplaySettings>,
+                    )
+                        .in_set(SettingsDiffSet),
+                    rebuild_ocean_on_settings_change
+                        .after(rescale_planet_on_event)
+                        .after(SettingsDiffSet),
+                    rebuild_plate_view_on_settings_change
+                        .after(rescale_planet_on_event)
+                        .after(SettingsDiffSet),
+                    handle_arrow_toggle,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    settle_zoom_into_limits.before(handle_camera_position_events),
+                    reframe_on_composition_change.before(handle_camera_position_events),
                     handle_camera_position_events,
                     handle_generate_new_seed,
+                    handle_reroll_planet.before(spawn_planet_on_event),
                     handle_reset_camera,
+                    handle_camera_focus,
                     planet_control,
+                    smooth_planet_rotation
+                        .after(handle_camera_focus)
+                        .after(planet_control),
+                    update_terrain_lod.after(planet_control),
                     smooth_camera_movement,
-                    handle_tab_visibility, // Centralized tab visibility handling
+                    cull_far_side_anchored
+                        .after(smooth_planet_rotation)
+                        .after(smooth_camera_movement),
+                    // Centralized tab visibility handling, after overlays are created/destroyed
+                    handle_tab_visibility
+                        .after(wind::systems::handle_vertical_air_toggle)
+                        .after(wind::systems::handle_wind_influence_toggle),
+                    apply_overlay_colors.after(handle_tab_visibility),
+                    update_overlay_hint.after(handle_tab_visibility),
                 )
                     .run_if(in_state(GameState::PlanetGeneration)),
             );
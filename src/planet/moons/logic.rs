@@ -0,0 +1,101 @@
+use crate::planet::resources::MoonSettings;
+use bevy::math::{Quat, Vec3};
+use std::f32::consts::TAU;
+
+/// Angle between the starting points of consecutive moons, the golden angle keeps any
+/// number of them spread around the planet
+const START_ANGLE_STEP: f32 = 2.399_963;
+
+/// Position and facing of a moon on its orbit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitPose {
+    /// Offset of the moon center from the planet center, in planet radii
+    pub offset: Vec3,
+    /// Turns the same side of the moon towards the planet all the time
+    pub rotation: Quat,
+}
+
+/// Where the `index`-th moon is `elapsed` seconds into its orbit. The orbit is a circle in the
+/// equatorial plane tilted by the inclination around X, travelled counterclockwise seen from
+/// above the north pole. A period of 0 keeps the moon still.
+pub fn orbit_pose(moon: &MoonSettings, index: usize, elapsed: f32) -> OrbitPose {
+    let progress = if moon.orbit_period > 0.0 {
+        elapsed / moon.orbit_period
+    } else {
+        0.0
+    };
+    let angle = index as f32 * START_ANGLE_STEP + progress * TAU;
+    let tilt = Quat::from_rotation_x(moon.inclination.to_radians());
+    let rotation = tilt * Quat::from_rotation_y(angle);
+    OrbitPose {
+        offset: rotation * (Vec3::X * moon.orbit_radius),
+        rotation,
+    }
+}
+
+/// Seed of the `index`-th moon of the planet with `planet_seed`, every moon looks different
+/// and a new planet brings new moons
+pub fn moon_seed(planet_seed: u64, index: usize) -> u64 {
+    planet_seed ^ (index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moon_stays_on_its_circle_and_comes_back_after_a_period() {
+        let moon = MoonSettings {
+            orbit_radius: 4.0,
+            orbit_period: 30.0,
+            inclination: 20.0,
+            ..MoonSettings::default()
+        };
+
+        for elapsed in [0.0, 3.7, 12.0, 29.0] {
+            assert!((orbit_pose(&moon, 1, elapsed).offset.length() - 4.0).abs() < 1e-4);
+        }
+        let start = orbit_pose(&moon, 1, 0.0).offset;
+        assert!(start.distance(orbit_pose(&moon, 1, 30.0).offset) < 1e-3);
+        assert!(start.distance(orbit_pose(&moon, 1, 15.0).offset) > 7.9);
+    }
+
+    #[test]
+    fn test_inclination_tilts_the_orbit_out_of_the_equator() {
+        let flat = MoonSettings {
+            inclination: 0.0,
+            ..MoonSettings::default()
+        };
+        let tilted = MoonSettings {
+            inclination: 30.0,
+            ..flat
+        };
+        let heights = |moon: &MoonSettings| -> f32 {
+            (0..12)
+                .map(|step| orbit_pose(moon, 0, moon.orbit_period * step as f32 / 12.0).offset.y.abs())
+                .fold(0.0, f32::max)
+        };
+
+        assert!(heights(&flat) < 1e-4);
+        let expected = tilted.orbit_radius * 30f32.to_radians().sin();
+        assert!((heights(&tilted) - expected).abs() < expected * 0.05);
+    }
+
+    #[test]
+    fn test_moon_keeps_one_side_towards_the_planet() {
+        let moon = MoonSettings::default();
+        for elapsed in [0.0, 7.0, 21.0] {
+            let pose = orbit_pose(&moon, 2, elapsed);
+            let facing_planet = pose.rotation * Vec3::NEG_X;
+            assert!(facing_planet.distance(-pose.offset.normalize()) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_moons_start_apart_and_get_their_own_seeds() {
+        let moon = MoonSettings::default();
+        assert!(orbit_pose(&moon, 0, 0.0).offset.distance(orbit_pose(&moon, 1, 0.0).offset) > 1.0);
+        assert_ne!(moon_seed(42, 0), moon_seed(42, 1));
+        assert_ne!(moon_seed(42, 0), moon_seed(43, 0));
+    }
+}
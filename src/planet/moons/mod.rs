@@ -0,0 +1,34 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::SettingsDiffSet;
+use crate::planet::systems::{rescale_planet_on_event, spawn_planet_on_event};
+use bevy::prelude::*;
+
+/// A moon orbiting the planet. Not a child of the planet, so it keeps its orbit while the
+/// planet is dragged around, and without any of the planet view markers the overlays look for.
+#[derive(Component)]
+pub struct Moon {
+    /// Position in `DisplaySettings::moons`
+    pub index: usize,
+}
+
+pub struct MoonsPlugin;
+
+impl Plugin for MoonsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                systems::rebuild_moons
+                    .after(SettingsDiffSet)
+                    .after(spawn_planet_on_event),
+                systems::orbit_moons
+                    .after(systems::rebuild_moons)
+                    .after(rescale_planet_on_event),
+            )
+                .run_if(in_state(GameState::PlanetGeneration)),
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use super::Moon;
+use super::logic::{moon_seed, orbit_pose};
+use crate::planet::components::PlanetEntity;
+use crate::planet::events::{PlanetSpawnedEvent, SettingsDiff, SettingsGroup};
+use crate::planet::resources::{CurrentPlanetData, DisplaySettings, TerrainSettings};
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+use planetgen::prelude::generate_moon;
+
+/// Generate the moons again when their settings change or a new planet is spawned,
+/// the seeds follow the planet seed
+pub fn rebuild_moons(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut planet_spawned_events: MessageReader<PlanetSpawnedEvent>,
+    (terrain_settings, settings): (Res<TerrainSettings>, Res<DisplaySettings>),
+    current_planet_data: Res<CurrentPlanetData>,
+    existing_moons: Query<Entity, With<Moon>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    let settings_changed = SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Moons]);
+    let planet_spawned = planet_spawned_events.read().count() > 0;
+    if !settings_changed && !planet_spawned {
+        return;
+    }
+
+    for entity in existing_moons.iter() {
+        commands.entity(entity).despawn();
+    }
+    if current_planet_data.planet_data.is_none() {
+        return;
+    }
+
+    // Vertex colors carry the surface, the light from the sun direction does the shading
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        perceptual_roughness: 0.95,
+        reflectance: 0.1,
+        ..default()
+    });
+    for (index, moon) in settings.moons.iter().enumerate() {
+        let moon_data = generate_moon(moon_seed(terrain_settings.seed, index), moon.radius);
+        let mesh_data = moon_data.mesh_data();
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, mesh_data.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, mesh_data.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, mesh_data.colors);
+        mesh.insert_indices(Indices::U32(mesh_data.indices));
+
+        // Placed by `orbit_moons` in the same frame
+        commands.spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(material.clone()),
+            Transform::default(),
+            Moon { index },
+        ));
+    }
+}
+
+/// Move every moon along its orbit around the planet center. The moon meshes are in planet
+/// radii, so the scale follows a rescaled planet.
+pub fn orbit_moons(
+    time: Res<Time>,
+    settings: Res<DisplaySettings>,
+    current_planet_data: Res<CurrentPlanetData>,
+    planet_query: Query<&Transform, (With<PlanetEntity>, Without<Moon>)>,
+    mut moons: Query<(&Moon, &mut Transform)>,
+) {
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    let center = planet_query.iter().next().map_or(Vec3::ZERO, |transform| transform.translation);
+    let radius = planet_data.radius;
+
+    for (moon, mut transform) in moons.iter_mut() {
+        let Some(settings) = settings.moons.get(moon.index) else {
+            continue;
+        };
+        let pose = orbit_pose(settings, moon.index, time.elapsed_secs());
+        transform.translation = center + pose.offset * radius;
+        transform.rotation = pose.rotation;
+        transform.scale = Vec3::splat(radius);
+    }
+}
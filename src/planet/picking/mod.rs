@@ -0,0 +1,51 @@
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::resources::{CurrentPlanetData, PlanetGenerationId};
+use crate::planet::systems::planet_control;
+use bevy::prelude::*;
+use planetgen::prelude::{SurfaceCollider, SurfaceHit};
+
+/// Picking collider of the current planet, built on the first pick after the terrain changed
+#[derive(Resource, Default)]
+pub struct PlanetCollider {
+    collider: Option<SurfaceCollider>,
+    /// Planet and radius the collider was built for, rescaling keeps the generation
+    built_for: Option<(PlanetGenerationId, f32)>,
+}
+
+impl PlanetCollider {
+    /// The collider of the current planet, None before a planet is generated
+    pub fn get(&mut self, current_planet_data: &CurrentPlanetData) -> Option<&SurfaceCollider> {
+        let planet_data = current_planet_data.planet_data.as_ref()?;
+        let key = (current_planet_data.generation, planet_data.radius);
+        if self.built_for != Some(key) {
+            self.collider = Some(SurfaceCollider::build(planet_data));
+            self.built_for = Some(key);
+        }
+        self.collider.as_ref()
+    }
+}
+
+/// The terrain point clicked last
+#[derive(Resource, Default)]
+pub struct SurfacePick {
+    pub clicked: Option<SurfaceHit>,
+}
+
+/// Clicking the planet picks the point of the terrain under the cursor
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlanetCollider>()
+            .init_resource::<SurfacePick>()
+            .add_systems(
+                Update,
+                systems::pick_surface_on_click
+                    // The planet has its rotation for this frame
+                    .after(planet_control)
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            );
+    }
+}
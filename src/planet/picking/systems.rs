@@ -0,0 +1,58 @@
+use super::{PlanetCollider, SurfacePick};
+use crate::planet::components::PlanetEntity;
+use crate::planet::resources::CurrentPlanetData;
+use crate::planet::ui::systems::PointerOverUi;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// How far in pixels the cursor may move between press and release for a click, further is a
+/// drag turning the planet
+const CLICK_TOLERANCE: f32 = 4.0;
+
+/// Pick the terrain under the cursor when the left button is clicked over the 3D view
+pub fn pick_surface_on_click(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    pointer_over_ui: Res<PointerOverUi>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    planet_query: Query<&Transform, With<PlanetEntity>>,
+    current_planet_data: Res<CurrentPlanetData>,
+    mut collider: ResMut<PlanetCollider>,
+    mut pick: ResMut<SurfacePick>,
+    mut press_position: Local<Option<Vec2>>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let cursor = window.cursor_position();
+    if mouse_input.just_pressed(MouseButton::Left) {
+        *press_position = cursor.filter(|_| !pointer_over_ui.0);
+    }
+    if !mouse_input.just_released(MouseButton::Left) {
+        return;
+    }
+    let (Some(pressed), Some(cursor)) = (press_position.take(), cursor) else {
+        return;
+    };
+    if pressed.distance(cursor) > CLICK_TOLERANCE {
+        return;
+    }
+
+    let Ok((camera, camera_transform)) = camera_query.single() else {
+        return;
+    };
+    let Ok(planet_transform) = planet_query.single() else {
+        return;
+    };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+    let Some(collider) = collider.get(&current_planet_data) else {
+        return;
+    };
+
+    pick.clicked = collider.raycast(ray.origin, *ray.direction, planet_transform.rotation);
+    if let Some(hit) = pick.clicked {
+        info!("Picked face {} cell {:?} at {:?}", hit.face, hit.cell, hit.position);
+    }
+}
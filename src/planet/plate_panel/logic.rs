@@ -0,0 +1,116 @@
+use crate::ui::locale::StringId;
+use planetgen::prelude::{PlateStats, PlateType};
+
+/// How long a picked plate stays brightened
+pub const HIGHLIGHT_SECONDS: f32 = 3.0;
+
+/// Share of the way to white a highlighted vertex color is lifted
+const HIGHLIGHT_LIFT: f32 = 0.5;
+
+/// A plate brightened in the plate view until the time runs out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlateHighlight {
+    pub plate: usize,
+    pub remaining_secs: f32,
+}
+
+impl PlateHighlight {
+    pub fn new(plate: usize) -> Self {
+        Self {
+            plate,
+            remaining_secs: HIGHLIGHT_SECONDS,
+        }
+    }
+
+    /// Count down by `delta_secs`, true once the highlight is over
+    pub fn tick(&mut self, delta_secs: f32) -> bool {
+        self.remaining_secs -= delta_secs;
+        self.remaining_secs <= 0.0
+    }
+}
+
+/// Vertex color of a highlighted plate, lifted towards white with the alpha kept
+pub fn highlight_color(color: [f32; 4]) -> [f32; 4] {
+    let lift = |channel: f32| channel + (1.0 - channel) * HIGHLIGHT_LIFT;
+    [lift(color[0]), lift(color[1]), lift(color[2]), color[3]]
+}
+
+/// Locale key of the type column of the list, microplates are listed as such whatever their
+/// crust
+pub fn plate_kind_label(stats: &PlateStats) -> StringId {
+    match (stats.micro, stats.plate_type) {
+        (true, _) => "plates.kind.micro",
+        (false, PlateType::Continental) => "plates.kind.continental",
+        (false, PlateType::Oceanic) => "plates.kind.oceanic",
+    }
+}
+
+/// Neighbours column of the list, `-` for a plate alone on the planet
+pub fn format_neighbors(neighbors: &[usize]) -> String {
+    if neighbors.is_empty() {
+        return "-".to_string();
+    }
+    neighbors
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Vec3;
+
+    fn stats(micro: bool, plate_type: PlateType) -> PlateStats {
+        PlateStats {
+            id: 0,
+            color: [1.0; 4],
+            plate_type,
+            micro,
+            cells: 1,
+            area_fraction: 0.1,
+            mean_elevation: 0.0,
+            speed: 0.5,
+            center: Vec3::X,
+            neighbors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_highlight_runs_out() {
+        let mut highlight = PlateHighlight::new(4);
+
+        assert!(!highlight.tick(HIGHLIGHT_SECONDS * 0.5));
+        assert!(highlight.tick(HIGHLIGHT_SECONDS * 0.5));
+    }
+
+    #[test]
+    fn test_highlight_brightens_and_keeps_alpha() {
+        let color = highlight_color([0.25, 0.0, 1.0, 0.75]);
+
+        assert_eq!(color, [0.625, 0.5, 1.0, 0.75]);
+    }
+
+    #[test]
+    fn test_microplates_are_listed_as_micro() {
+        assert_eq!(
+            plate_kind_label(&stats(true, PlateType::Continental)),
+            "plates.kind.micro"
+        );
+        assert_eq!(
+            plate_kind_label(&stats(false, PlateType::Continental)),
+            "plates.kind.continental"
+        );
+        assert_eq!(
+            plate_kind_label(&stats(false, PlateType::Oceanic)),
+            "plates.kind.oceanic"
+        );
+    }
+
+    #[test]
+    fn test_neighbors_are_comma_separated() {
+        assert_eq!(format_neighbors(&[1, 4, 7]), "1, 4, 7");
+        assert_eq!(format_neighbors(&[]), "-");
+    }
+}
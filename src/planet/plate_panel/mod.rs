@@ -0,0 +1,50 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::drift::systems::step_plate_drift;
+use crate::planet::resources::PlanetGenerationId;
+use crate::planet::systems::rebuild_plate_view_on_settings_change;
+use crate::planet::ui::systems::render_planet_generation_ui;
+use bevy::prelude::*;
+use bevy_egui::EguiPrimaryContextPass;
+use logic::PlateHighlight;
+use planetgen::prelude::PlateStats;
+
+/// The "Plates" window listing every plate, opened from the tectonic tab
+#[derive(Resource, Default)]
+pub struct PlatesPanel {
+    pub open: bool,
+    /// Rows of the list, refreshed while the window is open
+    pub stats: Vec<PlateStats>,
+    /// Planet the stats were computed for
+    pub stats_for: Option<PlanetGenerationId>,
+    /// The plate picked last, brightened in the plate view for a moment
+    pub highlight: Option<PlateHighlight>,
+}
+
+/// Per plate statistics in a window, clicking a plate highlights it and turns it to the camera
+pub struct PlatePanelPlugin;
+
+impl Plugin for PlatePanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlatesPanel>()
+            .add_systems(
+                Update,
+                (
+                    systems::refresh_plate_stats,
+                    systems::apply_plate_highlight
+                        .after(step_plate_drift)
+                        .after(rebuild_plate_view_on_settings_change),
+                )
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                systems::render_plates_panel
+                    // Drawn first so the settings panel sees the pointer over it
+                    .before(render_planet_generation_ui)
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            );
+    }
+}
@@ -0,0 +1,184 @@
+use super::PlatesPanel;
+use super::logic::{self, PlateHighlight};
+use crate::planet::components::{PlateViewMesh, TerrainLods};
+use crate::planet::events::{CameraFocus, FocusCameraEvent};
+use crate::planet::resources::{
+    ClimateSettings, CurrentPlanetData, DisplaySettings, PlanetGenerationId, TerrainSettings,
+};
+use crate::planet::systems::refresh_terrain_lods;
+use crate::ui::locale::{StringId, Strings};
+use crate::ui::widgets::scrollable_list;
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+const PANEL_MARGIN: f32 = 10.0;
+const LIST_MAX_HEIGHT: f32 = 320.0;
+const SWATCH_SIZE: f32 = 12.0;
+
+/// Locale keys of the column headers, the swatch column has none
+const COLUMNS: [StringId; 7] = [
+    "plates.column.plate",
+    "",
+    "plates.column.type",
+    "plates.column.area",
+    "plates.column.elevation",
+    "plates.column.speed",
+    "plates.column.neighbors",
+];
+
+/// Compute the rows for the current planet while the window is open
+pub fn refresh_plate_stats(
+    mut panel: ResMut<PlatesPanel>,
+    current_planet_data: Res<CurrentPlanetData>,
+) {
+    if !panel.open || panel.stats_for == Some(current_planet_data.generation) {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    panel.stats = planet_data.plate_stats();
+    panel.stats_for = Some(current_planet_data.generation);
+}
+
+/// Window in the bottom right corner with a row per plate. Clicking a plate highlights it and
+/// turns it to the camera.
+pub fn render_plates_panel(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<PlatesPanel>,
+    settings: Res<DisplaySettings>,
+    mut focus_camera_events: MessageWriter<FocusCameraEvent>,
+    strings: Res<Strings>,
+) {
+    if !panel.open {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let mut open = panel.open;
+    let mut picked = None;
+    let columns = COLUMNS.map(|column| if column.is_empty() { "" } else { strings.get(column) });
+    egui::Window::new(strings.get("plates.window"))
+        .id(egui::Id::new("plates_window"))
+        .open(&mut open)
+        .anchor(
+            egui::Align2::RIGHT_BOTTOM,
+            egui::vec2(-PANEL_MARGIN, -PANEL_MARGIN),
+        )
+        .resizable(false)
+        .show(ctx, |ui| {
+            if panel.stats.is_empty() {
+                ui.label(strings.get("plates.empty"));
+                return;
+            }
+            let highlighted = panel.highlight.map(|highlight| highlight.plate);
+            scrollable_list(
+                ui,
+                "plates_list",
+                &columns,
+                panel.stats.len(),
+                LIST_MAX_HEIGHT,
+                |ui, index| {
+                    let plate = &panel.stats[index];
+                    if ui
+                        .selectable_label(highlighted == Some(plate.id), format!("#{}", plate.id))
+                        .clicked()
+                    {
+                        picked = Some(plate.id);
+                    }
+                    let [r, g, b, _] = settings.palette.plate_color(plate.id, plate.color);
+                    let (rect, _) = ui.allocate_exact_size(
+                        egui::vec2(SWATCH_SIZE, SWATCH_SIZE),
+                        egui::Sense::hover(),
+                    );
+                    ui.painter()
+                        .rect_filled(rect, 2.0, egui::Rgba::from_rgb(r, g, b));
+                    ui.label(strings.get(logic::plate_kind_label(plate)));
+                    ui.label(format!("{:.1}%", plate.area_fraction * 100.0));
+                    ui.label(format!("{:+.2}", plate.mean_elevation));
+                    ui.label(format!("{:.2}", plate.speed));
+                    ui.label(logic::format_neighbors(&plate.neighbors));
+                },
+            );
+        });
+
+    panel.open = open;
+    if let Some(plate) = picked {
+        panel.highlight = Some(PlateHighlight::new(plate));
+        focus_camera_events.write(FocusCameraEvent(CameraFocus::Plate(plate)));
+    }
+}
+
+/// Brighten the cells of the picked plate in the plate view meshes, and give them their
+/// colors back once the highlight runs out. Drift steps recolor the meshes, the highlight is
+/// applied again after them.
+pub fn apply_plate_highlight(
+    time: Res<Time>,
+    mut panel: ResMut<PlatesPanel>,
+    mut shown: Local<Option<(Option<usize>, PlanetGenerationId)>>,
+    current_planet_data: Res<CurrentPlanetData>,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
+    mut meshes: ResMut<Assets<Mesh>>,
+    plate_lods: Query<&TerrainLods, With<PlateViewMesh>>,
+) {
+    if let Some(highlight) = panel.highlight.as_mut()
+        && highlight.tick(time.delta_secs())
+    {
+        panel.highlight = None;
+    }
+    let plate = panel.highlight.map(|highlight| highlight.plate);
+    let wanted = (plate, current_planet_data.generation);
+    if *shown == Some(wanted) {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    let was_highlighted = shown.is_some_and(|(plate, _)| plate.is_some());
+    *shown = Some(wanted);
+    if !was_highlighted && plate.is_none() {
+        return;
+    }
+
+    for lods in &plate_lods {
+        refresh_terrain_lods(
+            planet_data,
+            display_settings.plate_view_mode(),
+            (&terrain_settings, &climate_settings, &display_settings),
+            None,
+            lods,
+            &mut meshes,
+        );
+        let Some(plate) = plate else {
+            continue;
+        };
+        for mesh_handle in &lods.meshes {
+            let Some(mesh) = meshes.get_mut(mesh_handle) else {
+                continue;
+            };
+            let Some(VertexAttributeValues::Float32x3(positions)) =
+                mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                continue;
+            };
+            let in_plate: Vec<bool> = positions
+                .iter()
+                .map(|position| planet_data.plate_at(Vec3::from(*position)) == plate)
+                .collect();
+            if let Some(VertexAttributeValues::Float32x4(colors)) =
+                mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR)
+            {
+                for (color, _) in colors
+                    .iter_mut()
+                    .zip(&in_plate)
+                    .filter(|(_, inside)| **inside)
+                {
+                    *color = logic::highlight_color(*color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::ui::locale::StringId;
+
+/// Precipitation maps the year is split into, evenly spread from the northern spring equinox
+pub const SEASON_PHASES: usize = 12;
+
+/// Months from the northern spring equinox on, the month a phase of the year falls in
+const MONTHS: [StringId; 12] = [
+    "month.mar", "month.apr", "month.may", "month.jun", "month.jul", "month.aug", "month.sep", "month.oct",
+    "month.nov", "month.dec", "month.jan", "month.feb",
+];
+
+/// The two of `phases` evenly spread maps `phase` lies between and how far it is from the first
+/// to the second. Past the last map the year wraps back to the first.
+pub fn phase_blend(phase: f32, phases: usize) -> (usize, usize, f32) {
+    let position = phase.rem_euclid(1.0) * phases as f32;
+    let from = (position.floor() as usize).min(phases - 1);
+    (from, (from + 1) % phases, (position - from as f32).clamp(0.0, 1.0))
+}
+
+/// Phase of the year after `dt` seconds of playback at `years_per_minute`
+pub fn advance_phase(phase: f32, dt: f32, years_per_minute: f32) -> f32 {
+    (phase + dt * years_per_minute / 60.0).rem_euclid(1.0)
+}
+
+/// Write the colors between `from` (`t` = 0) and `to` (`t` = 1) into `out`
+pub fn blend_colors(out: &mut [[f32; 4]], from: &[[f32; 4]], to: &[[f32; 4]], t: f32) {
+    for ((color, from), to) in out.iter_mut().zip(from).zip(to) {
+        *color = std::array::from_fn(|channel| from[channel] + (to[channel] - from[channel]) * t);
+    }
+}
+
+/// Month shown next to the phase scrubber
+pub fn phase_month(phase: f32) -> StringId {
+    MONTHS[((phase.rem_euclid(1.0) * 12.0) as usize).min(11)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::first_map(0.0, (0, 1, 0.0))]
+    #[case::between_maps(0.375, (1, 2, 0.5))]
+    #[case::last_map_blends_into_the_first(0.875, (3, 0, 0.5))]
+    #[case::next_year(1.125, (0, 1, 0.5))]
+    #[case::before_the_start(-0.125, (3, 0, 0.5))]
+    fn test_phase_blend(#[case] phase: f32, #[case] expected: (usize, usize, f32)) {
+        assert_eq!(phase_blend(phase, 4), expected);
+    }
+
+    #[test]
+    fn test_playback_wraps_into_the_next_year() {
+        // 6 years a minute is a year every 10 seconds
+        assert!((advance_phase(0.25, 5.0, 6.0) - 0.75).abs() < 1e-6);
+        assert!((advance_phase(0.75, 5.0, 6.0) - 0.25).abs() < 1e-6);
+        assert_eq!(advance_phase(0.4, 1.0, 0.0), 0.4);
+    }
+
+    #[test]
+    fn test_blend_colors_in_place() {
+        let mut out = [[0.0; 4]; 2];
+        let from = [[0.0, 0.0, 1.0, 1.0], [1.0, 1.0, 0.0, 1.0]];
+        let to = [[1.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]];
+
+        blend_colors(&mut out, &from, &to, 0.25);
+
+        assert_eq!(out, [[0.25, 0.0, 0.75, 1.0], [1.0, 1.0, 0.25, 1.0]]);
+    }
+
+    #[rstest]
+    #[case::spring_equinox(0.0, "month.mar")]
+    #[case::northern_summer(0.25, "month.jun")]
+    #[case::end_of_the_year(0.99, "month.feb")]
+    fn test_phase_month(#[case] phase: f32, #[case] month: &str) {
+        assert_eq!(phase_month(phase), month);
+    }
+}
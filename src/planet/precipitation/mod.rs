@@ -1,7 +1,10 @@
+pub mod logic;
 pub mod systems;
 
 use bevy::prelude::*;
-use crate::planet::resources::PlanetGenerationSettings;
+use crate::planet::ClimateRebuildSet;
+use crate::planet::view::apply_overlay_colors;
+use planetgen::prelude::PrecipitationCalibration;
 
 /// Resource to store precipitation visualization settings
 #[derive(Resource, Clone)]
@@ -11,28 +14,46 @@ pub struct PrecipitationSettings {
     pub temperature_weight: f32,
     pub ocean_weight: f32,
     pub cubemap_resolution: usize,
+    /// Annual amounts in mm of the map values
+    pub calibration: PrecipitationCalibration,
 }
 
 impl Default for PrecipitationSettings {
     fn default() -> Self {
-        let config = planetgen::get_config();
+        let config = planetgen::config::get_config();
         Self {
             planet_radius: 50.0,
             enabled: false,
             temperature_weight: config.precipitation.temperature_weight,
             ocean_weight: config.precipitation.ocean_weight,
             cubemap_resolution: config.precipitation.cubemap_resolution,
+            calibration: PrecipitationCalibration::from_config(&config.precipitation),
         }
     }
 }
 
-/// Resource that stores a copy of the last settings used to build the precipitation cubemap
-#[derive(Resource, Clone)]
-pub struct PreviousPrecipitationSettings(pub PlanetGenerationSettings);
+/// Most times a second the playing year recolors the precipitation overlay
+pub const MAX_SEASON_COLOR_UPDATES_PER_SECOND: f32 = 10.0;
+
+/// Playback of the precipitation over a year, cross-fading between the phase maps
+#[derive(Resource)]
+pub struct SeasonPlayback {
+    /// Show the phase of the year instead of the annual precipitation
+    pub enabled: bool,
+    pub playing: bool,
+    /// Position in the year, 0 to 1 from the northern spring equinox
+    pub phase: f32,
+    pub years_per_minute: f32,
+}
 
-impl Default for PreviousPrecipitationSettings {
+impl Default for SeasonPlayback {
     fn default() -> Self {
-        Self(PlanetGenerationSettings::default())
+        Self {
+            enabled: false,
+            playing: false,
+            phase: 0.0,
+            years_per_minute: 6.0,
+        }
     }
 }
 
@@ -41,10 +62,41 @@ pub struct PrecipitationPlugin;
 impl Plugin for PrecipitationPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PrecipitationSettings>()
-            .init_resource::<PreviousPrecipitationSettings>()
-            .add_systems(Startup, systems::initialize_precipitation_cubemap)
-            .add_systems(Update, systems::update_precipitation_settings)
-            .add_systems(Update, systems::regenerate_precipitation_meshes_on_settings_change)
-            .add_systems(Update, systems::handle_precipitation_tab_events);
+            .init_resource::<SeasonPlayback>()
+            .init_resource::<systems::PrecipitationSeasons>()
+            // The year is queued from the annual map that was just put in place
+            .add_systems(
+                Update,
+                (
+                    systems::update_precipitation_settings,
+                    systems::apply_rebuilt_precipitation,
+                    systems::queue_precipitation_seasons,
+                )
+                    .chain()
+                    .in_set(ClimateRebuildSet::Precipitation),
+            )
+            // New overlays get their colors in the frame they are spawned in, the year is
+            // written over them right after
+            .add_systems(
+                Update,
+                systems::regenerate_precipitation_meshes_on_settings_change
+                    .after(systems::apply_rebuilt_precipitation)
+                    .before(apply_overlay_colors),
+            )
+            // A rebuilt cubemap and the tab switch can come in the same frame, the overlay
+            // built for the new cubemap is already there when the switch is handled
+            .add_systems(
+                Update,
+                systems::handle_precipitation_tab_events
+                    .after(systems::regenerate_precipitation_meshes_on_settings_change)
+                    .before(apply_overlay_colors),
+            )
+            // Overlays shown again get the annual colors first, the phase is written over them
+            .add_systems(
+                Update,
+                systems::animate_precipitation_seasons
+                    .after(systems::queue_precipitation_seasons)
+                    .after(apply_overlay_colors),
+            );
     }
 }
@@ -1,13 +1,29 @@
-use super::{PreviousPrecipitationSettings, PrecipitationSettings};
-use crate::planet::components::PlanetEntity;
-use crate::planet::events::PrecipitationTabActiveEvent;
-use crate::planet::resources::{CurrentPlanetData, PlanetGenerationSettings};
+use super::logic::{self, SEASON_PHASES};
+use super::{MAX_SEASON_COLOR_UPDATES_PER_SECOND, PrecipitationSettings, SeasonPlayback};
+use crate::mesh::helpers::mesh_positions;
+use crate::planet::components::{OverlayMesh, OverlaySourceMeshes, PlanetEntity, PrecipitationView};
+use crate::planet::events::{
+    PrecipitationRebuilt, SetActiveView, SettingsDiff, SettingsGroup, TerrainLodChangedEvent,
+    ViewKind,
+};
+use crate::planet::jobs::logic::{ClimateJobKind, PrecipitationPhase};
+use crate::planet::jobs::{ClimateJobOutput, ClimateJobQueue};
+use crate::planet::resources::{
+    ClimateSettings, CurrentPlanetData, DisplaySettings, OverlayCacheKey, OverlayColorCache, OverlayKind, OverlayMeshes,
+    PlanetAssets, TerrainSettings,
+};
 use crate::planet::temperature::systems::TemperatureCubeMap;
 use crate::planet::wind::systems::VerticalAirCubeMap;
-use bevy::asset::RenderAssetUsages;
-use bevy::mesh::PrimitiveTopology;
+use bevy::ecs::change_detection::Tick;
+use bevy::mesh::VertexAttributeValues;
 use bevy::prelude::*;
-use planetgen::precipitations::{PrecipitationCubeMap as PlanetgenPrecipitationCubeMap, precipitation_to_color};
+use std::collections::HashMap;
+use std::time::Instant;
+use planetgen::prelude::{
+    Palette, PlanetData, PrecipitationCubeMap as PlanetgenPrecipitationCubeMap,
+    TemperatureCubeMap as PlanetgenTemperatureCubeMap,
+    VerticalAirCubeMap as PlanetgenVerticalAirCubeMap, precipitation_to_color,
+};
 
 /// Bevy-compatible PrecipitationCubeMap resource
 #[derive(Resource, Clone)]
@@ -17,14 +33,13 @@ pub struct PrecipitationCubeMap {
 
 impl PrecipitationCubeMap {
     pub fn build(
-        vertical_air: &planetgen::wind::VerticalAirCubeMap,
-        temperature: Option<&planetgen::temperature::TemperatureCubeMap>,
-        planet: Option<&planetgen::planet::PlanetData>,
+        vertical_air: &PlanetgenVerticalAirCubeMap,
+        temperature: Option<&PlanetgenTemperatureCubeMap>,
+        planet: Option<&PlanetData>,
         temperature_weight: f32,
         ocean_weight: f32,
         equator_temp: f32,
         pole_temp: f32,
-        continent_threshold: f32,
     ) -> Self {
         let inner = PlanetgenPrecipitationCubeMap::build(
             vertical_air,
@@ -34,7 +49,6 @@ impl PrecipitationCubeMap {
             ocean_weight,
             equator_temp,
             pole_temp,
-            continent_threshold,
         );
         Self { inner }
     }
@@ -43,9 +57,14 @@ impl PrecipitationCubeMap {
         self.inner.sample(position)
     }
 
-    pub fn sample_color(&self, position: Vec3) -> Vec3 {
+    /// Annual precipitation in mm
+    pub fn sample_mm(&self, position: Vec3) -> f32 {
+        self.inner.sample_mm(position)
+    }
+
+    pub fn sample_color(&self, position: Vec3, palette: Palette) -> Vec3 {
         let value = self.inner.sample(position);
-        precipitation_to_color(value)
+        precipitation_to_color(value, palette)
     }
 }
 
@@ -53,117 +72,314 @@ impl PrecipitationCubeMap {
 #[derive(Component)]
 pub struct PrecipitationMesh;
 
-/// Initialize the precipitation cube map resource at startup
-pub fn initialize_precipitation_cubemap(
-    mut commands: Commands,
-    settings: Res<PrecipitationSettings>,
-    planet_settings: Res<PlanetGenerationSettings>,
-    planet_data: Res<CurrentPlanetData>,
-    vertical_air: Option<Res<VerticalAirCubeMap>>,
-    temperature: Option<Res<TemperatureCubeMap>>,
-) {
-    info!("Initializing precipitation cube map...");
-
-    // We need the vertical air map to build precipitation
-    // If it doesn't exist yet, create a placeholder that will be rebuilt later
-    if let Some(vertical_air) = vertical_air {
-        let temp_inner = temperature.as_ref().map(|t| &t.inner);
-        let planet_inner = planet_data.planet_data.as_ref();
-        let cubemap = PrecipitationCubeMap::build(
-            &vertical_air.inner,
-            temp_inner,
-            planet_inner,
-            settings.temperature_weight,
-            settings.ocean_weight,
-            planet_settings.temperature_equator_temp,
-            planet_settings.temperature_pole_temp,
-            planet_settings.continent_threshold,
-        );
-        commands.insert_resource(cubemap);
-    } else {
-        info!("Vertical air map not available yet, precipitation map will be built later");
+/// Precipitation maps spread over the year and the overlay colors of each, queued the first
+/// time the year is shown and again for every new annual map after that
+#[derive(Resource, Default)]
+pub struct PrecipitationSeasons {
+    maps: Vec<PlanetgenPrecipitationCubeMap>,
+    /// Change tick of the annual map the phases were last queued along with
+    queued_for: Option<Tick>,
+    /// Colors of the overlay copy of each source mesh, one list per phase, in `palette`
+    colors: HashMap<AssetId<Mesh>, Vec<Vec<[f32; 4]>>>,
+    palette: Option<Palette>,
+    /// Phase the overlay shows, `None` while it shows the annual colors
+    shown: Option<f32>,
+}
+
+impl PrecipitationSeasons {
+    /// Phase of the year the overlay colors were last written for
+    pub fn shown_phase(&self) -> Option<f32> {
+        self.shown
+    }
+
+    pub fn is_built(&self) -> bool {
+        !self.maps.is_empty()
     }
 }
 
 /// Update precipitation settings from planet generation settings
 pub fn update_precipitation_settings(
-    planet_settings: Res<PlanetGenerationSettings>,
-    mut previous_settings: ResMut<PreviousPrecipitationSettings>,
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
     mut precipitation_settings: ResMut<PrecipitationSettings>,
-    mut precipitation_cubemap: Option<ResMut<PrecipitationCubeMap>>,
+    mut queue: ResMut<ClimateJobQueue>,
     planet_data: Res<CurrentPlanetData>,
     vertical_air: Option<Res<VerticalAirCubeMap>>,
     temperature: Option<Res<TemperatureCubeMap>>,
-    mut commands: Commands,
 ) {
     // Always update basic settings
-    precipitation_settings.planet_radius = planet_settings.radius;
-    precipitation_settings.enabled = planet_settings.show_precipitation;
-    precipitation_settings.temperature_weight = planet_settings.precipitation_temperature_weight;
-    precipitation_settings.ocean_weight = planet_settings.precipitation_ocean_weight;
+    precipitation_settings.planet_radius = terrain_settings.radius;
+    precipitation_settings.enabled = display_settings.show_precipitation;
+    precipitation_settings.temperature_weight = climate_settings.precipitation_temperature_weight;
+    precipitation_settings.ocean_weight = climate_settings.precipitation_ocean_weight;
 
     // Check if precipitation-related values have changed
-    let precip_changed =
-        previous_settings.0.precipitation_temperature_weight != planet_settings.precipitation_temperature_weight ||
-        previous_settings.0.precipitation_ocean_weight != planet_settings.precipitation_ocean_weight ||
-        previous_settings.0.precipitation_cubemap_resolution != planet_settings.precipitation_cubemap_resolution;
+    let precip_changed = SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Precipitation]);
 
-    // Rebuild cubemap if settings changed or if vertical air map was updated
-    let vertical_air_changed = vertical_air.as_ref().map_or(false, |v| v.is_changed());
-    let temperature_changed = temperature.as_ref().map_or(false, |t| t.is_changed());
-    let planet_changed = planet_data.is_changed();
+    // Rebuild cubemap if settings changed or if vertical air map was updated. A new planet gets
+    // a new wind and vertical air map, the rebuild follows them instead of the planet data.
+    let vertical_air_changed = vertical_air.as_ref().is_some_and(|v| v.is_changed());
+    let temperature_changed = temperature.as_ref().is_some_and(|t| t.is_changed());
 
-    if precip_changed || vertical_air_changed || temperature_changed || planet_changed {
+    if precip_changed || vertical_air_changed || temperature_changed {
+        // The vertical air is built for the first planet, there is nothing to build on before it
         if let Some(vertical_air) = vertical_air {
-            info!("Rebuilding precipitation cubemap with new settings...");
-            let temp_inner = temperature.as_ref().map(|t| &t.inner);
-            let planet_inner = planet_data.planet_data.as_ref();
-            let new_cubemap = PrecipitationCubeMap::build(
-                &vertical_air.inner,
-                temp_inner,
-                planet_inner,
-                planet_settings.precipitation_temperature_weight,
-                planet_settings.precipitation_ocean_weight,
-                planet_settings.temperature_equator_temp,
-                planet_settings.temperature_pole_temp,
-                planet_settings.continent_threshold,
-            );
-
-            if let Some(ref mut cubemap) = precipitation_cubemap {
-                **cubemap = new_cubemap;
-            } else {
-                commands.insert_resource(new_cubemap);
+            info!("Queueing a precipitation cubemap rebuild with new settings...");
+            let inputs = PrecipitationInputs::new(&vertical_air, temperature.as_deref(), &planet_data, &climate_settings);
+            let kind = ClimateJobKind::RebuildPrecipitation { phase: PrecipitationPhase::Annual };
+            queue.push(kind, move || {
+                let cubemap = PrecipitationCubeMap::build(
+                    &inputs.vertical_air,
+                    inputs.temperature.as_ref(),
+                    inputs.planet.as_ref(),
+                    inputs.temperature_weight,
+                    inputs.ocean_weight,
+                    inputs.equator_temp,
+                    inputs.pole_temp,
+                );
+                ClimateJobOutput::Precipitation(PrecipitationRebuilt::Annual(Box::new(cubemap)))
+            });
+        }
+    }
+}
+
+/// Put the precipitation built by finished precipitation jobs in place
+pub fn apply_rebuilt_precipitation(
+    mut rebuilt: MessageReader<PrecipitationRebuilt>,
+    mut precipitation_cubemap: Option<ResMut<PrecipitationCubeMap>>,
+    mut seasons: ResMut<PrecipitationSeasons>,
+    mut commands: Commands,
+) {
+    for rebuilt in rebuilt.read() {
+        match rebuilt {
+            PrecipitationRebuilt::Annual(new_cubemap) => {
+                if let Some(ref mut cubemap) = precipitation_cubemap {
+                    **cubemap = (**new_cubemap).clone();
+                } else {
+                    commands.insert_resource((**new_cubemap).clone());
+                }
+            }
+            PrecipitationRebuilt::Seasons(maps) => {
+                seasons.maps = maps.clone();
+                seasons.colors.clear();
+                seasons.shown = None;
             }
+        }
+    }
+}
 
-            // Update tracking
-            previous_settings.0.precipitation_temperature_weight = planet_settings.precipitation_temperature_weight;
-            previous_settings.0.precipitation_ocean_weight = planet_settings.precipitation_ocean_weight;
-            previous_settings.0.precipitation_cubemap_resolution = planet_settings.precipitation_cubemap_resolution;
+/// Queue the precipitation of every phase of the year along with the annual map, once the
+/// year is shown. The phases are built from the same wind and temperatures.
+pub fn queue_precipitation_seasons(
+    playback: Res<SeasonPlayback>,
+    mut seasons: ResMut<PrecipitationSeasons>,
+    mut queue: ResMut<ClimateJobQueue>,
+    (climate_settings, planet_data): (Res<ClimateSettings>, Res<CurrentPlanetData>),
+    precipitation_cubemap: Option<Res<PrecipitationCubeMap>>,
+    vertical_air: Option<Res<VerticalAirCubeMap>>,
+    temperature: Option<Res<TemperatureCubeMap>>,
+) {
+    if !playback.enabled {
+        return;
+    }
+    // Built for the first planet
+    let (Some(precipitation_cubemap), Some(vertical_air)) = (precipitation_cubemap, vertical_air) else {
+        return;
+    };
+    if seasons.queued_for == Some(precipitation_cubemap.last_changed()) {
+        return;
+    }
+    seasons.queued_for = Some(precipitation_cubemap.last_changed());
+
+    let inputs = PrecipitationInputs::new(&vertical_air, temperature.as_deref(), &planet_data, &climate_settings);
+    let kind = ClimateJobKind::RebuildPrecipitation { phase: PrecipitationPhase::Seasons };
+    queue.push(kind, move || {
+        let start = Instant::now();
+        let maps = (0..SEASON_PHASES)
+            .map(|phase| {
+                PlanetgenPrecipitationCubeMap::build_phase(
+                    phase as f32 / SEASON_PHASES as f32,
+                    &inputs.vertical_air,
+                    inputs.temperature.as_ref(),
+                    inputs.planet.as_ref(),
+                    inputs.temperature_weight,
+                    inputs.ocean_weight,
+                    inputs.equator_temp,
+                    inputs.pole_temp,
+                )
+            })
+            .collect();
+        info!("Precipitation of {SEASON_PHASES} phases of the year built in {:?}", start.elapsed());
+        ClimateJobOutput::Precipitation(PrecipitationRebuilt::Seasons(maps))
+    });
+}
+
+/// Everything a precipitation map is built from, copied for a climate job
+struct PrecipitationInputs {
+    vertical_air: PlanetgenVerticalAirCubeMap,
+    temperature: Option<PlanetgenTemperatureCubeMap>,
+    planet: Option<PlanetData>,
+    temperature_weight: f32,
+    ocean_weight: f32,
+    equator_temp: f32,
+    pole_temp: f32,
+}
+
+impl PrecipitationInputs {
+    fn new(
+        vertical_air: &VerticalAirCubeMap,
+        temperature: Option<&TemperatureCubeMap>,
+        planet_data: &CurrentPlanetData,
+        climate_settings: &ClimateSettings,
+    ) -> Self {
+        Self {
+            vertical_air: vertical_air.inner.clone(),
+            temperature: temperature.map(|t| t.inner.clone()),
+            planet: planet_data.planet_data.clone(),
+            temperature_weight: climate_settings.precipitation_temperature_weight,
+            ocean_weight: climate_settings.precipitation_ocean_weight,
+            equator_temp: climate_settings.temperature_equator_temp,
+            pole_temp: climate_settings.temperature_pole_temp,
+        }
+    }
+}
+
+/// Precipitation overlays spawned or shown again since the last run, they come with the annual colors
+type ReshownPrecipitationOverlays<'w, 's> =
+    Query<'w, 's, (), (With<PrecipitationMesh>, Or<(Added<OverlayMesh>, Changed<Visibility>)>)>;
+
+/// Play the year and cross-fade the overlay colors between the phase maps. The colors of each
+/// phase are made once per mesh, after that they are blended into the mesh in place, at most
+/// `MAX_SEASON_COLOR_UPDATES_PER_SECOND` times a second.
+pub fn animate_precipitation_seasons(
+    (time, mut since_update): (Res<Time>, Local<f32>),
+    mut playback: ResMut<SeasonPlayback>,
+    mut seasons: ResMut<PrecipitationSeasons>,
+    display_settings: Res<DisplaySettings>,
+    overlays: Query<(&Mesh3d, &OverlayMesh, &Visibility), With<PrecipitationMesh>>,
+    reshown: ReshownPrecipitationOverlays,
+    (overlay_meshes, color_cache, mut meshes): (Res<OverlayMeshes>, Res<OverlayColorCache>, ResMut<Assets<Mesh>>),
+) {
+    if playback.playing {
+        playback.phase = logic::advance_phase(playback.phase, time.delta_secs(), playback.years_per_minute);
+    }
+    *since_update += time.delta_secs();
+
+    // Overlays shown again or built anew got the annual colors
+    if !reshown.is_empty() {
+        seasons.shown = None;
+    }
+    let visible = overlays.iter().filter(|(_, _, visibility)| **visibility != Visibility::Hidden);
+
+    if !playback.enabled {
+        if seasons.shown.take().is_some() {
+            for (mesh_handle, overlay, _) in visible {
+                let (Some(colors), Some(mesh)) = (
+                    color_cache.colors(OverlayKind::Precipitation, overlay.source),
+                    meshes.get_mut(&mesh_handle.0),
+                ) else {
+                    continue;
+                };
+                mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, overlay_meshes.copy_colors(overlay.source, colors));
+            }
+        }
+        return;
+    }
+    if !seasons.is_built() || !display_settings.show_precipitation {
+        return;
+    }
+    if seasons.palette != Some(display_settings.palette) {
+        seasons.colors.clear();
+        seasons.palette = Some(display_settings.palette);
+        seasons.shown = None;
+    }
+    let due = *since_update >= 1.0 / MAX_SEASON_COLOR_UPDATES_PER_SECOND;
+    if seasons.shown.is_some_and(|shown| shown == playback.phase || !due) {
+        return;
+    }
+    *since_update = 0.0;
+
+    let seasons = &mut *seasons;
+    let (from, to, t) = logic::phase_blend(playback.phase, seasons.maps.len());
+    for (mesh_handle, overlay, _) in visible {
+        let Some(phase_colors) = seasonal_colors(seasons, overlay.source, &overlay_meshes, &meshes, display_settings.palette)
+        else {
+            continue;
+        };
+        let Some(mesh) = meshes.get_mut(&mesh_handle.0) else {
+            continue;
+        };
+        match mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) if colors.len() == phase_colors[from].len() => {
+                logic::blend_colors(colors, &phase_colors[from], &phase_colors[to], t);
+            }
+            // The overlay shows a texture or nothing yet
+            _ => {
+                let mut colors = phase_colors[from].clone();
+                logic::blend_colors(&mut colors, &phase_colors[from], &phase_colors[to], t);
+                mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+            }
         }
     }
+    seasons.shown = Some(playback.phase);
 }
 
-/// Regenerate precipitation meshes when cubemap changes
+/// Colors of the overlay copy of `source` at every phase, made on first use
+fn seasonal_colors<'a>(
+    seasons: &'a mut PrecipitationSeasons,
+    source: AssetId<Mesh>,
+    overlay_meshes: &OverlayMeshes,
+    meshes: &Assets<Mesh>,
+    palette: Palette,
+) -> Option<&'a Vec<Vec<[f32; 4]>>> {
+    if !seasons.colors.contains_key(&source) {
+        let positions = mesh_positions(meshes.get(source)?);
+        let phase_colors = seasons
+            .maps
+            .iter()
+            .map(|map| {
+                let colors: Vec<[f32; 4]> = positions
+                    .iter()
+                    .map(|&position| {
+                        let color = precipitation_to_color(map.sample(Vec3::from(position)), palette);
+                        [color.x, color.y, color.z, 1.0]
+                    })
+                    .collect();
+                overlay_meshes.copy_colors(source, &colors)
+            })
+            .collect();
+        seasons.colors.insert(source, phase_colors);
+    }
+    seasons.colors.get(&source)
+}
+
+/// Regenerate precipitation meshes when the cubemap, the palette or the terrain LOD changes
 pub fn regenerate_precipitation_meshes_on_settings_change(
-    planet_settings: Res<PlanetGenerationSettings>,
-    previous_settings: Res<PreviousPrecipitationSettings>,
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut lod_events: MessageReader<TerrainLodChangedEvent>,
+    display_settings: Res<DisplaySettings>,
     precipitation_cubemap: Option<Res<PrecipitationCubeMap>>,
+    planet_data: Res<CurrentPlanetData>,
+    mut color_cache: ResMut<OverlayColorCache>,
+    (mut overlay_meshes, mut planet_assets): (ResMut<OverlayMeshes>, ResMut<PlanetAssets>),
     planet_query: Query<Entity, With<PlanetEntity>>,
-    continent_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::ContinentViewMesh>,
-    >,
-    ocean_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::OceanEntity>,
-    >,
+    source_query: OverlaySourceMeshes,
     existing_precip_meshes: Query<Entity, With<PrecipitationMesh>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut commands: Commands,
 ) {
-    // Only regenerate if precipitation view is active
-    if !planet_settings.show_precipitation {
+    let lod_changed = lod_events.read().count() > 0;
+    let palette_changed = SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Palette]);
+
+    // Only regenerate if precipitation view is active. Hidden copies of the old LOD or palette
+    // would be shown again as they are, drop them so the tab builds new ones.
+    if !display_settings.show_precipitation {
+        if lod_changed || palette_changed {
+            for entity in existing_precip_meshes.iter() {
+                commands.entity(entity).despawn();
+            }
+        }
         return;
     }
 
@@ -172,7 +388,7 @@ pub fn regenerate_precipitation_meshes_on_settings_change(
     };
 
     // Regenerate meshes if cubemap changed
-    if !precipitation_cubemap.is_changed() && !previous_settings.is_changed() {
+    if !precipitation_cubemap.is_changed() && !lod_changed && !palette_changed {
         return;
     }
 
@@ -187,209 +403,153 @@ pub fn regenerate_precipitation_meshes_on_settings_change(
         return;
     };
 
-    // Recreate continent precipitation mesh
-    for (_entity, mesh_handle, _material) in continent_query.iter() {
-        if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-            let precip_mesh = create_precipitation_colored_mesh(original_mesh, precipitation_cubemap);
-            let precip_mesh_handle = meshes.add(precip_mesh);
-
-            let precip_material = materials.add(StandardMaterial {
-                base_color: Color::WHITE,
-                unlit: true,
-                ..default()
-            });
-
-            let precip_entity = commands
-                .spawn((
-                    Mesh3d(precip_mesh_handle),
-                    MeshMaterial3d(precip_material),
-                    Transform::default(),
-                    GlobalTransform::default(),
-                    Visibility::Visible,
-                    PrecipitationMesh,
-                    crate::planet::components::PrecipitationView,
-                ))
-                .id();
-
-            commands.entity(planet_entity).add_child(precip_entity);
-        }
-    }
-
-    // Recreate ocean precipitation mesh
-    for (_entity, mesh_handle, _material) in ocean_query.iter() {
-        if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-            let precip_mesh = create_precipitation_colored_mesh(original_mesh, precipitation_cubemap);
-            let precip_mesh_handle = meshes.add(precip_mesh);
-
-            let precip_material = materials.add(StandardMaterial {
-                base_color: Color::WHITE,
-                unlit: true,
-                ..default()
-            });
-
-            let precip_entity = commands
-                .spawn((
-                    Mesh3d(precip_mesh_handle),
-                    MeshMaterial3d(precip_material),
-                    Transform::default(),
-                    GlobalTransform::default(),
-                    Visibility::Visible,
-                    PrecipitationMesh,
-                    crate::planet::components::PrecipitationView,
-                ))
-                .id();
-
-            commands.entity(planet_entity).add_child(precip_entity);
-        }
-    }
+    spawn_precipitation_meshes(
+        planet_entity,
+        &source_query,
+        precipitation_cubemap,
+        display_settings.palette,
+        &planet_data,
+        &mut color_cache,
+        &mut overlay_meshes,
+        &mut planet_assets,
+        &mut meshes,
+        &mut materials,
+        &mut commands,
+    );
 }
 
 /// Handle precipitation tab activation/deactivation
 pub fn handle_precipitation_tab_events(
-    mut precipitation_tab_events: MessageReader<PrecipitationTabActiveEvent>,
-    mut planet_settings: ResMut<PlanetGenerationSettings>,
+    mut set_active_view: MessageReader<SetActiveView>,
+    mut display_settings: ResMut<DisplaySettings>,
+    planet_data: Res<CurrentPlanetData>,
+    mut color_cache: ResMut<OverlayColorCache>,
+    (mut overlay_meshes, mut planet_assets): (ResMut<OverlayMeshes>, ResMut<PlanetAssets>),
     planet_query: Query<Entity, With<PlanetEntity>>,
-    continent_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::ContinentViewMesh>,
-    >,
-    ocean_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::OceanEntity>,
-    >,
+    source_query: OverlaySourceMeshes,
     existing_precip_meshes: Query<Entity, With<PrecipitationMesh>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     precipitation_cubemap: Option<Res<PrecipitationCubeMap>>,
     mut commands: Commands,
 ) {
-    for event in precipitation_tab_events.read() {
-        planet_settings.show_precipitation = event.active;
-
-        if event.active {
-            // Only create precipitation meshes if they don't already exist
-            if !existing_precip_meshes.is_empty() {
-                info!("Precipitation meshes already exist, skipping creation");
-                continue;
-            }
-
-            let Some(ref precipitation_cubemap) = precipitation_cubemap else {
-                warn!("Precipitation cubemap not available");
-                continue;
-            };
-
-            info!("Creating precipitation-colored mesh copies");
-
-            let Some(planet_entity) = planet_query.iter().next() else {
-                warn!("No planet entity found");
-                continue;
-            };
-
-            // Create precipitation mesh for continent
-            for (_entity, mesh_handle, _material) in continent_query.iter() {
-                if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-                    let precip_mesh = create_precipitation_colored_mesh(original_mesh, precipitation_cubemap);
-                    let precip_mesh_handle = meshes.add(precip_mesh);
-
-                    let precip_material = materials.add(StandardMaterial {
-                        base_color: Color::WHITE,
-                        unlit: true,
-                        ..default()
-                    });
-
-                    let precip_entity = commands
-                        .spawn((
-                            Mesh3d(precip_mesh_handle),
-                            MeshMaterial3d(precip_material),
-                            Transform::default(),
-                            GlobalTransform::default(),
-                            Visibility::Visible,
-                            PrecipitationMesh,
-                            crate::planet::components::PrecipitationView,
-                        ))
-                        .id();
-
-                    commands.entity(planet_entity).add_child(precip_entity);
-                }
-            }
-
-            // Create precipitation mesh for ocean
-            for (_entity, mesh_handle, _material) in ocean_query.iter() {
-                if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-                    let precip_mesh = create_precipitation_colored_mesh(original_mesh, precipitation_cubemap);
-                    let precip_mesh_handle = meshes.add(precip_mesh);
-
-                    let precip_material = materials.add(StandardMaterial {
-                        base_color: Color::WHITE,
-                        unlit: true,
-                        ..default()
-                    });
-
-                    let precip_entity = commands
-                        .spawn((
-                            Mesh3d(precip_mesh_handle),
-                            MeshMaterial3d(precip_material),
-                            Transform::default(),
-                            GlobalTransform::default(),
-                            Visibility::Visible,
-                            PrecipitationMesh,
-                            crate::planet::components::PrecipitationView,
-                        ))
-                        .id();
-
-                    commands.entity(planet_entity).add_child(precip_entity);
-                }
-            }
-        } else {
-            info!("Hiding precipitation-colored mesh copies");
-
-            // Hide precipitation mesh copies
-            for entity in existing_precip_meshes.iter() {
-                commands.entity(entity).try_insert(Visibility::Hidden);
-            }
+    // Only the last switch of the frame counts, every overlay ends up agreeing on it
+    let Some(&SetActiveView(view)) = set_active_view.read().last() else {
+        return;
+    };
+    let active = view == ViewKind::Precipitations;
+    display_settings.show_precipitation = active;
+
+    if active {
+        // Only create precipitation meshes if they don't already exist
+        if !existing_precip_meshes.is_empty() {
+            info!("Precipitation meshes already exist, skipping creation");
+            return;
         }
-    }
-}
 
-/// Create a copy of a mesh with precipitation-based vertex colors
-fn create_precipitation_colored_mesh(
-    original_mesh: &Mesh,
-    precipitation_cubemap: &PrecipitationCubeMap,
-) -> Mesh {
-    let mut new_mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::default(),
-    );
+        // Built with the first planet, the new cubemap regenerates the meshes
+        let Some(ref precipitation_cubemap) = precipitation_cubemap else {
+            info!("No precipitation cubemap before the first planet");
+            return;
+        };
+
+        let Some(planet_entity) = planet_query.iter().next() else {
+            warn!("No planet entity found");
+            return;
+        };
+
+        spawn_precipitation_meshes(
+            planet_entity,
+            &source_query,
+            precipitation_cubemap,
+            display_settings.palette,
+            &planet_data,
+            &mut color_cache,
+            &mut overlay_meshes,
+            &mut planet_assets,
+            &mut meshes,
+            &mut materials,
+            &mut commands,
+        );
+    } else {
+        info!("Hiding precipitation-colored mesh copies");
 
-    // Copy positions and generate colors
-    if let Some(positions_attr) = original_mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
-        if let Some(positions) = positions_attr.as_float3() {
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions.to_vec());
-
-            let colors: Vec<[f32; 4]> = positions
-                .iter()
-                .map(|&[x, y, z]| {
-                    let position = Vec3::new(x, y, z);
-                    let color = precipitation_cubemap.sample_color(position);
-                    [color.x, color.y, color.z, 1.0]
-                })
-                .collect();
-
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        // Hide precipitation mesh copies
+        for entity in existing_precip_meshes.iter() {
+            commands.entity(entity).try_insert(Visibility::Hidden);
         }
     }
 
-    // Copy normals
-    if let Some(normals_attr) = original_mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
-        if let Some(normals) = normals_attr.as_float3() {
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals.to_vec());
-        }
-    }
+}
 
-    // Copy indices
-    if let Some(indices) = original_mesh.indices() {
-        new_mesh.insert_indices(indices.clone());
+/// Spawn the precipitation overlay on the shared copies of the continent and ocean meshes.
+/// Precipitation colors depend on nothing but the cubemap and the palette.
+fn spawn_precipitation_meshes(
+    planet_entity: Entity,
+    source_query: &OverlaySourceMeshes,
+    precipitation_cubemap: &Res<PrecipitationCubeMap>,
+    palette: Palette,
+    planet_data: &CurrentPlanetData,
+    color_cache: &mut OverlayColorCache,
+    overlay_meshes: &mut OverlayMeshes,
+    planet_assets: &mut PlanetAssets,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    commands: &mut Commands,
+) {
+    let start = Instant::now();
+    let key = OverlayCacheKey::new(planet_data.generation, precipitation_cubemap.last_changed(), palette, &[]);
+
+    for mesh_handle in source_query.iter() {
+        let Some(original_mesh) = meshes.get(&mesh_handle.0) else {
+            continue;
+        };
+        color_cache.ensure_colors(OverlayKind::Precipitation, key, mesh_handle.0.id(), || {
+            precipitation_colors(original_mesh, precipitation_cubemap, palette)
+        });
+        let Some(precip_mesh_handle) = overlay_meshes.copy_of(&mesh_handle.0, meshes, planet_assets) else {
+            continue;
+        };
+
+        let precip_material = planet_assets.add_material(materials, StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            ..default()
+        });
+
+        let precip_entity = commands
+            .spawn((
+                Mesh3d(precip_mesh_handle),
+                MeshMaterial3d(precip_material),
+                Transform::default(),
+                GlobalTransform::default(),
+                Visibility::Visible,
+                PrecipitationMesh,
+                PrecipitationView,
+                OverlayMesh {
+                    source: mesh_handle.0.id(),
+                    colors: Some(OverlayKind::Precipitation),
+                },
+            ))
+            .id();
+
+        commands.entity(planet_entity).add_child(precip_entity);
     }
 
-    new_mesh
+    info!("Precipitation overlay ready in {:?}", start.elapsed());
+}
+
+/// Precipitation-based vertex colors
+fn precipitation_colors(
+    original_mesh: &Mesh,
+    precipitation_cubemap: &PrecipitationCubeMap,
+    palette: Palette,
+) -> Vec<[f32; 4]> {
+    mesh_positions(original_mesh)
+        .iter()
+        .map(|&[x, y, z]| {
+            let color = precipitation_cubemap.sample_color(Vec3::new(x, y, z), palette);
+            [color.x, color.y, color.z, 1.0]
+        })
+        .collect()
 }
@@ -0,0 +1,182 @@
+use crate::ui::locale::{StringId, Strings};
+use bevy::prelude::*;
+use planetgen::prelude::Biome;
+
+/// Terrain and climate at one point of the surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbeReading {
+    /// Unit direction of the point in the planet's own frame
+    pub direction: Vec3,
+    /// Terrain height above the sea level, negative under water
+    pub height: f32,
+    /// Air temperature (°C), on land with the land bonus the biomes see
+    pub temperature: Option<f32>,
+    /// Annual precipitation (mm/yr)
+    pub precipitation: Option<f32>,
+    pub wind_speed: Option<f32>,
+    /// Positive where the air rises, negative where it sinks
+    pub vertical_air: Option<f32>,
+    pub plate: usize,
+    pub lake: bool,
+    /// Biome of the land, None under water and before the climate maps are built
+    pub biome: Option<Biome>,
+}
+
+impl ProbeReading {
+    /// What covers the point: the sea, a lake or the biome of the land, None on land before
+    /// the climate maps are built
+    pub fn surface_label(&self) -> Option<StringId> {
+        if self.height <= 0.0 {
+            Some("probe.ocean")
+        } else if self.lake {
+            Some("probe.lake")
+        } else {
+            self.biome.map(biome_name)
+        }
+    }
+
+    /// [`Self::surface_label`] in the language of `strings`
+    pub fn surface_text<'a>(&self, strings: &'a Strings) -> &'a str {
+        self.surface_label().map_or(MISSING, |label| strings.get(label))
+    }
+}
+
+fn biome_name(biome: Biome) -> StringId {
+    match biome {
+        Biome::Ice => "biome.ice",
+        Biome::Tundra => "biome.tundra",
+        Biome::Desert => "biome.desert",
+        Biome::Savanna => "biome.savanna",
+        Biome::Temperate => "biome.temperate",
+        Biome::Jungle => "biome.jungle",
+    }
+}
+
+/// Shown for a value that can't be read
+const MISSING: &str = "–";
+
+/// One line of the comparison table: the value at A, at B and the change from A to B
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonRow {
+    pub label: String,
+    pub a: String,
+    pub b: String,
+    pub delta: String,
+}
+
+/// The comparison table of two readings, either may be missing, in the language of `strings`
+pub fn comparison_rows(a: Option<&ProbeReading>, b: Option<&ProbeReading>, strings: &Strings) -> Vec<ComparisonRow> {
+    let value = |reading: Option<&ProbeReading>, read: fn(&ProbeReading) -> Option<f32>| reading.and_then(read);
+    let mm = format!(" {}", strings.get("unit.mm_per_year"));
+    let quantity = |label: StringId, unit: &str, decimals: usize, a: Option<f32>, b: Option<f32>| {
+        number_row(strings.get(label), unit, decimals, a, b)
+    };
+    let surface = |reading: Option<&ProbeReading>| reading.map(|r| r.surface_text(strings).to_string());
+    vec![
+        quantity("probe.height", "", 2, value(a, |r| Some(r.height)), value(b, |r| Some(r.height))),
+        quantity("probe.temperature", " °C", 1, value(a, |r| r.temperature), value(b, |r| r.temperature)),
+        quantity("probe.precipitation", &mm, 0, value(a, |r| r.precipitation), value(b, |r| r.precipitation)),
+        quantity("probe.wind_speed", "", 2, value(a, |r| r.wind_speed), value(b, |r| r.wind_speed)),
+        quantity("probe.vertical_air", "", 2, value(a, |r| r.vertical_air), value(b, |r| r.vertical_air)),
+        label_row(
+            strings,
+            "probe.plate",
+            a.map(|r| format!("#{}", r.plate)),
+            b.map(|r| format!("#{}", r.plate)),
+        ),
+        label_row(strings, "probe.biome", surface(a), surface(b)),
+    ]
+}
+
+/// Row of a quantity with `decimals` places, the change is B minus A
+fn number_row(label: &str, unit: &str, decimals: usize, a: Option<f32>, b: Option<f32>) -> ComparisonRow {
+    let text = |value: Option<f32>| value.map_or(MISSING.to_string(), |value| format!("{value:.decimals$}{unit}"));
+    let delta = match (a, b) {
+        (Some(a), Some(b)) => format!("{:+.decimals$}{unit}", b - a),
+        _ => MISSING.to_string(),
+    };
+    ComparisonRow {
+        label: label.to_string(),
+        a: text(a),
+        b: text(b),
+        delta,
+    }
+}
+
+/// Row of something without a difference, the change only tells whether it's the same
+fn label_row(strings: &Strings, label: StringId, a: Option<String>, b: Option<String>) -> ComparisonRow {
+    let delta = match (&a, &b) {
+        (Some(a), Some(b)) if a == b => strings.get("probe.same"),
+        (Some(_), Some(_)) => strings.get("probe.differs"),
+        _ => MISSING,
+    };
+    ComparisonRow {
+        label: strings.get(label).to_string(),
+        a: a.unwrap_or_else(|| MISSING.to_string()),
+        b: b.unwrap_or_else(|| MISSING.to_string()),
+        delta: delta.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn reading(height: f32, temperature: f32, plate: usize, biome: Biome) -> ProbeReading {
+        ProbeReading {
+            direction: Vec3::X,
+            height,
+            temperature: Some(temperature),
+            precipitation: Some(800.0),
+            wind_speed: Some(2.5),
+            vertical_air: None,
+            plate,
+            lake: false,
+            biome: Some(biome),
+        }
+    }
+
+    fn row<'a>(rows: &'a [ComparisonRow], label: &str) -> &'a ComparisonRow {
+        rows.iter().find(|row| row.label == label).unwrap()
+    }
+
+    #[test]
+    fn test_delta_is_b_minus_a() {
+        let desert = reading(0.8, 31.0, 2, Biome::Desert);
+        let jungle = reading(0.3, 26.5, 2, Biome::Jungle);
+
+        let rows = comparison_rows(Some(&desert), Some(&jungle), &Strings::default());
+
+        assert_eq!(row(&rows, "Temperature").delta, "-4.5 °C");
+        assert_eq!(row(&rows, "Height").delta, "-0.50");
+        assert_eq!(row(&rows, "Precipitation").delta, "+0 mm/yr");
+        assert_eq!(row(&rows, "Plate").delta, "same");
+        assert_eq!(row(&rows, "Biome").delta, "differs");
+    }
+
+    #[test]
+    fn test_values_not_read_have_no_delta() {
+        let pinned = reading(0.8, 31.0, 2, Biome::Desert);
+
+        let strings = Strings::default();
+        let rows = comparison_rows(Some(&pinned), None, &strings);
+        assert!(rows.iter().all(|row| row.b == MISSING && row.delta == MISSING));
+        assert_eq!(row(&rows, "Temperature").a, "31.0 °C");
+
+        let both = comparison_rows(Some(&pinned), Some(&pinned), &strings);
+        assert_eq!(row(&both, "Vertical air").delta, MISSING);
+    }
+
+    #[rstest]
+    #[case(-0.4, false, "Ocean")]
+    #[case(0.4, true, "Lake")]
+    #[case(0.4, false, "Savanna")]
+    fn test_surface_is_water_or_the_biome(#[case] height: f32, #[case] lake: bool, #[case] expected: &str) {
+        let reading = ProbeReading {
+            lake,
+            ..reading(height, 24.0, 0, Biome::Savanna)
+        };
+        assert_eq!(reading.surface_text(&Strings::default()), expected);
+    }
+}
@@ -0,0 +1,101 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::picking::systems::pick_surface_on_click;
+use crate::planet::systems::{cull_far_side_anchored, spawn_planet_on_event, swap_in_full_resolution_planet};
+use crate::planet::ui::systems::render_planet_generation_ui;
+use crate::planet::ClimateRebuildSet;
+use bevy::prelude::*;
+use bevy_egui::EguiPrimaryContextPass;
+use logic::ProbeReading;
+
+/// One of the two points the probe compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbePin {
+    A,
+    B,
+}
+
+impl ProbePin {
+    pub const ALL: [ProbePin; 2] = [ProbePin::A, ProbePin::B];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProbePin::A => "A",
+            ProbePin::B => "B",
+        }
+    }
+
+    /// Color of the marker on the surface and of the column in the panel
+    pub fn color(self) -> Color {
+        match self {
+            ProbePin::A => Color::srgb(1.0, 0.55, 0.1),
+            ProbePin::B => Color::srgb(0.2, 0.8, 1.0),
+        }
+    }
+}
+
+/// The terrain and climate at the clicked point and at the two pinned ones. The readings are
+/// taken again whenever the planet or a climate map changes.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct SurfaceProbe {
+    /// The point clicked last
+    pub current: Option<ProbeReading>,
+    pub a: Option<ProbeReading>,
+    pub b: Option<ProbeReading>,
+    /// Radius of the planet the readings were taken on
+    pub sampled_radius: Option<f32>,
+}
+
+impl SurfaceProbe {
+    pub fn pin(&self, pin: ProbePin) -> Option<&ProbeReading> {
+        match pin {
+            ProbePin::A => self.a.as_ref(),
+            ProbePin::B => self.b.as_ref(),
+        }
+    }
+
+    pub fn set_pin(&mut self, pin: ProbePin, reading: Option<ProbeReading>) {
+        match pin {
+            ProbePin::A => self.a = reading,
+            ProbePin::B => self.b = reading,
+        }
+    }
+}
+
+/// Marker of a pinned point, a child of the planet
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProbeMarker(pub ProbePin);
+
+/// Clicking the planet probes the terrain and climate there. Shift-click pins the point as A,
+/// Ctrl-click as B, and the "Probe" window compares the two.
+pub struct ProbePlugin;
+
+impl Plugin for ProbePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SurfaceProbe>()
+            .add_systems(
+                Update,
+                (
+                    systems::probe_clicked_point.after(pick_surface_on_click),
+                    systems::resample_probe
+                        .after(spawn_planet_on_event)
+                        .after(swap_in_full_resolution_planet)
+                        .after(ClimateRebuildSet::Precipitation),
+                    systems::sync_probe_markers
+                        .after(systems::probe_clicked_point)
+                        .after(systems::resample_probe)
+                        .before(cull_far_side_anchored),
+                )
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                systems::render_probe_panel
+                    // Drawn first so the settings panel sees the pointer over it
+                    .before(render_planet_generation_ui)
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            );
+    }
+}
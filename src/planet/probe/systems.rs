@@ -0,0 +1,256 @@
+use super::logic::{self, ProbeReading};
+use super::{ProbeMarker, ProbePin, SurfaceProbe};
+use crate::planet::biome::systems::biome_thresholds_from_settings;
+use crate::planet::components::{PlanetEntity, SurfaceAnchored};
+use crate::planet::logic::keeps_view;
+use crate::planet::picking::SurfacePick;
+use crate::planet::precipitation::systems::PrecipitationCubeMap;
+use crate::planet::resources::{ClimateSettings, CurrentPlanetData, DisplaySettings};
+use crate::planet::streamlines::Streamlines;
+use crate::planet::temperature::systems::TemperatureCubeMap;
+use crate::planet::wind::systems::{VerticalAirCubeMap, WindCubeMap};
+use crate::ui::locale::Strings;
+use crate::ui::theme::color32;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use planetgen::prelude::{PlanetData, dominant_biome};
+
+const PANEL_MARGIN: f32 = 10.0;
+
+/// Radius of a pin marker as a share of the planet radius
+const MARKER_SIZE: f32 = 0.012;
+
+/// The climate maps a reading is taken from, each is missing until it is first built
+type ClimateMaps<'w> = (
+    Option<Res<'w, TemperatureCubeMap>>,
+    Option<Res<'w, PrecipitationCubeMap>>,
+    Option<Res<'w, WindCubeMap>>,
+    Option<Res<'w, VerticalAirCubeMap>>,
+);
+
+/// Sphere mesh and the material of each pin, shared by the markers
+type MarkerAssets = (Handle<Mesh>, [Handle<StandardMaterial>; 2]);
+
+/// Everything the probe shows at `direction` of `planet`
+fn read_probe(
+    direction: Vec3,
+    planet: &PlanetData,
+    (temperature, precipitation, wind, vertical_air): &ClimateMaps,
+    settings: &ClimateSettings,
+) -> ProbeReading {
+    let height = planet.sample_height(direction) - planet.sea_level();
+    let land_bonus = if height > 0.0 { settings.land_temperature_bonus } else { 0.0 };
+    let temperature = temperature.as_ref().map(|map| map.sample_temperature(direction) + land_bonus);
+    let biome = match (temperature, precipitation) {
+        (Some(temperature), Some(precipitation)) if height > 0.0 => Some(dominant_biome(
+            temperature,
+            precipitation.sample(direction),
+            &biome_thresholds_from_settings(settings),
+        )),
+        _ => None,
+    };
+    ProbeReading {
+        direction,
+        height,
+        temperature,
+        precipitation: precipitation.as_ref().map(|map| map.sample_mm(direction)),
+        wind_speed: wind.as_ref().map(|map| map.sample(direction).length()),
+        vertical_air: vertical_air.as_ref().map(|map| map.inner.sample(direction)),
+        plate: planet.plate_id(direction),
+        lake: planet.has_lake(direction),
+        biome,
+    }
+}
+
+/// Probe the point picked this frame, and pin it with Shift (A) or Ctrl (B) held
+pub fn probe_clicked_point(
+    pick: Res<SurfacePick>,
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<ClimateSettings>,
+    current_planet_data: Res<CurrentPlanetData>,
+    maps: ClimateMaps,
+    mut probe: ResMut<SurfaceProbe>,
+) {
+    if !pick.is_changed() {
+        return;
+    }
+    let (Some(hit), Some(planet)) = (pick.clicked, current_planet_data.planet_data.as_ref()) else {
+        return;
+    };
+
+    let reading = read_probe(hit.direction, planet, &maps, &settings);
+    probe.current = Some(reading);
+    probe.sampled_radius = Some(planet.radius);
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        probe.a = Some(reading);
+    }
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        probe.b = Some(reading);
+    }
+}
+
+/// Read the probed points again when the planet or a climate map changed. A new planet drops
+/// them, unless the view is kept for it: then the same directions are read on the new planet.
+pub fn resample_probe(
+    (climate_settings, display_settings): (Res<ClimateSettings>, Res<DisplaySettings>),
+    current_planet_data: Res<CurrentPlanetData>,
+    maps: ClimateMaps,
+    new_planets: Query<(), Added<PlanetEntity>>,
+    mut probe: ResMut<SurfaceProbe>,
+) {
+    let Some(planet) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+    let new_planet = !new_planets.is_empty();
+    let maps_changed = maps.0.as_ref().is_some_and(|map| map.is_changed())
+        || maps.1.as_ref().is_some_and(|map| map.is_changed())
+        || maps.2.as_ref().is_some_and(|map| map.is_changed())
+        || maps.3.as_ref().is_some_and(|map| map.is_changed());
+    if !new_planet && !maps_changed && !current_planet_data.is_changed() {
+        return;
+    }
+
+    let mut resampled = probe.clone();
+    let kept = resampled
+        .sampled_radius
+        .is_some_and(|old_radius| keeps_view(display_settings.keep_view, old_radius, planet.radius));
+    if new_planet && !kept {
+        resampled = SurfaceProbe::default();
+    }
+    for reading in [&mut resampled.current, &mut resampled.a, &mut resampled.b].into_iter().flatten() {
+        *reading = read_probe(reading.direction, planet, &maps, &climate_settings);
+    }
+    resampled.sampled_radius = Some(planet.radius);
+    // The markers are rebuilt on every change, the temperature map changes on every step
+    probe.set_if_neq(resampled);
+}
+
+/// Put a marker on each pinned point, again whenever the pins or the planet change
+pub fn sync_probe_markers(
+    mut commands: Commands,
+    probe: Res<SurfaceProbe>,
+    current_planet_data: Res<CurrentPlanetData>,
+    planets: Query<(Entity, Ref<PlanetEntity>)>,
+    markers: Query<Entity, With<ProbeMarker>>,
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    mut marker_assets: Local<Option<MarkerAssets>>,
+) {
+    let Ok((planet_entity, planet_marker)) = planets.single() else {
+        return;
+    };
+    if !probe.is_changed() && !planet_marker.is_added() {
+        return;
+    }
+    let Some(planet) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+
+    for marker in markers.iter() {
+        commands.entity(marker).try_despawn();
+    }
+    let (mesh, pin_materials) = marker_assets.get_or_insert_with(|| {
+        let material = |pin: ProbePin| StandardMaterial {
+            base_color: pin.color(),
+            emissive: pin.color().to_linear() * 0.5,
+            unlit: true,
+            ..default()
+        };
+        (
+            meshes.add(Sphere::new(1.0)),
+            ProbePin::ALL.map(|pin| materials.add(material(pin))),
+        )
+    });
+
+    // Resting on the ground, a ball is too round to fight with it in the depth buffer
+    let size = planet.radius * MARKER_SIZE;
+    for (pin, material) in ProbePin::ALL.into_iter().zip(pin_materials.iter()) {
+        let Some(reading) = probe.pin(pin) else {
+            continue;
+        };
+        let direction = reading.direction;
+        let ground = planet.surface_radius(direction) + planet.sample_height(direction).max(planet.sea_level());
+        let marker = commands
+            .spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(direction * (ground + size)).with_scale(Vec3::splat(size)),
+                ProbeMarker(pin),
+                SurfaceAnchored { direction },
+            ))
+            .id();
+        commands.entity(planet_entity).add_child(marker);
+    }
+}
+
+/// Foldable window on the left with the clicked point and the A / B comparison table
+pub fn render_probe_panel(
+    mut contexts: EguiContexts,
+    mut probe: ResMut<SurfaceProbe>,
+    mut streamlines: ResMut<Streamlines>,
+    strings: Res<Strings>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new(strings.get("probe.window"))
+        .id(egui::Id::new("probe_window"))
+        .anchor(egui::Align2::LEFT_CENTER, egui::vec2(PANEL_MARGIN, 0.0))
+        .default_open(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            // Only written on a click, the markers are rebuilt on any change of the probe
+            match probe.current {
+                Some(current) => {
+                    ui.label(strings.format(
+                        "probe.clicked",
+                        &[
+                            ("surface", &current.surface_text(&strings)),
+                            ("height", &format!("{:.2}", current.height)),
+                        ],
+                    ));
+                    ui.horizontal(|ui| {
+                        for pin in ProbePin::ALL {
+                            if ui.button(strings.format("probe.pin", &[("pin", &pin.label())])).clicked() {
+                                probe.set_pin(pin, Some(current));
+                            }
+                        }
+                        if ui.button(strings.get("probe.trace_wind")).clicked() {
+                            streamlines.add(current.direction);
+                        }
+                    });
+                }
+                None => {
+                    ui.weak(strings.get("probe.click_hint"));
+                }
+            }
+            ui.weak(strings.get("probe.pin_hint"));
+            ui.add_space(4.0);
+
+            egui::Grid::new("probe_comparison")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    for pin in ProbePin::ALL {
+                        ui.colored_label(color32(pin.color()), pin.label());
+                    }
+                    ui.label("Δ");
+                    ui.end_row();
+
+                    for row in logic::comparison_rows(probe.a.as_ref(), probe.b.as_ref(), &strings) {
+                        ui.label(row.label);
+                        ui.label(row.a);
+                        ui.label(row.b);
+                        ui.label(row.delta);
+                        ui.end_row();
+                    }
+                });
+
+            let pinned = probe.a.is_some() || probe.b.is_some();
+            if ui.add_enabled(pinned, egui::Button::new(strings.get("probe.clear_pins"))).clicked() {
+                probe.a = None;
+                probe.b = None;
+            }
+        });
+}
@@ -0,0 +1,92 @@
+use planetgen::prelude::ProfileSample;
+
+/// Share of the value span added above and below a curve so it doesn't touch the frame
+const RANGE_PADDING: f32 = 0.1;
+
+/// Values the vertical axis of a plot spans
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl PlotRange {
+    /// Where `value` is on the vertical axis, 0 at the bottom and 1 at the top
+    pub fn normalize(&self, value: f32) -> f32 {
+        (value - self.min) / (self.max - self.min)
+    }
+
+    /// Height of the zero line, if zero is inside the range
+    pub fn zero(&self) -> Option<f32> {
+        (self.min < 0.0 && self.max > 0.0).then(|| self.normalize(0.0))
+    }
+}
+
+/// Padded range of the values in `samples`, `None` for an empty profile. A flat profile gets a
+/// range of ±1 around its value so the line ends up in the middle.
+pub fn plot_range(samples: &[ProfileSample]) -> Option<PlotRange> {
+    let min = samples.iter().map(|sample| sample.value).reduce(f32::min)?;
+    let max = samples.iter().map(|sample| sample.value).reduce(f32::max)?;
+    let span = max - min;
+    if span <= f32::EPSILON {
+        return Some(PlotRange { min: min - 1.0, max: max + 1.0 });
+    }
+    Some(PlotRange {
+        min: min - span * RANGE_PADDING,
+        max: max + span * RANGE_PADDING,
+    })
+}
+
+/// Profile in plot coordinates, both in 0..1: latitude -90 to 90 left to right, `range`
+/// bottom to top
+pub fn plot_points(samples: &[ProfileSample], range: PlotRange) -> Vec<[f32; 2]> {
+    samples
+        .iter()
+        .map(|sample| [(sample.latitude + 90.0) / 180.0, range.normalize(sample.value)])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(values: &[f32]) -> Vec<ProfileSample> {
+        let band = 180.0 / values.len() as f32;
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| ProfileSample {
+                latitude: -90.0 + (i as f32 + 0.5) * band,
+                value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_points_stay_inside_the_plot() {
+        let profile = samples(&[-20.0, 5.0, 30.0, 5.0, -20.0]);
+        let range = plot_range(&profile).unwrap();
+
+        let points = plot_points(&profile, range);
+        assert!(points.windows(2).all(|pair| pair[0][0] < pair[1][0]));
+        for [x, y] in points {
+            assert!((0.0..=1.0).contains(&x));
+            assert!(y > 0.0 && y < 1.0, "{y} touches the frame");
+        }
+        assert!(range.zero().is_some());
+    }
+
+    #[test]
+    fn test_flat_profile_is_drawn_in_the_middle() {
+        let profile = samples(&[3.0, 3.0, 3.0]);
+        let range = plot_range(&profile).unwrap();
+
+        assert_eq!(range.normalize(3.0), 0.5);
+        assert_eq!(range.zero(), None);
+    }
+
+    #[test]
+    fn test_empty_profile_has_no_range() {
+        assert_eq!(plot_range(&[]), None);
+    }
+}
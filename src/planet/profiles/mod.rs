@@ -0,0 +1,63 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::ui::systems::render_planet_generation_ui;
+use bevy::prelude::*;
+use bevy_egui::EguiPrimaryContextPass;
+use planetgen::prelude::ProfileSample;
+
+/// Latitude bands of every profile, 5° each. Odd, so one sample sits on the equator.
+pub const PROFILE_LATITUDES: usize = 37;
+
+/// Where along the longitudes the profiles are read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileMode {
+    /// Average over all longitudes
+    ZonalMean,
+    /// Along the meridian at this longitude in degrees
+    Meridian(f32),
+}
+
+/// Latitude profiles of the climate maps, resampled whenever one of the maps is rebuilt
+#[derive(Resource)]
+pub struct ClimateProfiles {
+    pub mode: ProfileMode,
+    pub temperature: Vec<ProfileSample>,
+    pub precipitation: Vec<ProfileSample>,
+    pub zonal_wind: Vec<ProfileSample>,
+    pub meridional_wind: Vec<ProfileSample>,
+}
+
+impl Default for ClimateProfiles {
+    fn default() -> Self {
+        Self {
+            mode: ProfileMode::ZonalMean,
+            temperature: Vec::new(),
+            precipitation: Vec::new(),
+            zonal_wind: Vec::new(),
+            meridional_wind: Vec::new(),
+        }
+    }
+}
+
+/// Foldable "Profiles" window with line plots of temperature, precipitation and wind against
+/// latitude, for tuning the climate model
+pub struct ProfilesPlugin;
+
+impl Plugin for ProfilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClimateProfiles>()
+            .add_systems(
+                Update,
+                systems::refresh_climate_profiles.run_if(in_state(GameState::PlanetGeneration)),
+            )
+            .add_systems(
+                EguiPrimaryContextPass,
+                systems::render_profiles_panel
+                    // Drawn first so the settings panel sees the pointer over it
+                    .before(render_planet_generation_ui)
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            );
+    }
+}
@@ -0,0 +1,174 @@
+use super::logic::{self, PlotRange};
+use super::{ClimateProfiles, PROFILE_LATITUDES, ProfileMode};
+use crate::planet::precipitation::systems::PrecipitationCubeMap;
+use crate::planet::temperature::systems::TemperatureCubeMap;
+use crate::planet::wind::systems::WindCubeMap;
+use crate::ui::locale::{StringId, Strings};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use planetgen::prelude::{
+    MeridionalWind, ProfileSample, SphereField, ZonalWind, meridian_profile, zonal_mean,
+};
+
+const PANEL_MARGIN: f32 = 10.0;
+const PLOT_WIDTH: f32 = 260.0;
+const PLOT_HEIGHT: f32 = 90.0;
+/// Room below a plot for the latitude labels
+const AXIS_LABEL_HEIGHT: f32 = 12.0;
+
+/// Sample the climate maps again when one of them is rebuilt or the profile mode changes.
+/// The temperature map changes on every simulated step, so the plots follow the simulation.
+pub fn refresh_climate_profiles(
+    mut profiles: ResMut<ClimateProfiles>,
+    temperature: Option<Res<TemperatureCubeMap>>,
+    precipitation: Option<Res<PrecipitationCubeMap>>,
+    wind: Option<Res<WindCubeMap>>,
+    mut sampled_mode: Local<Option<ProfileMode>>,
+) {
+    let mode = profiles.mode;
+    let maps_changed = temperature.as_ref().is_some_and(|map| map.is_changed())
+        || precipitation.as_ref().is_some_and(|map| map.is_changed())
+        || wind.as_ref().is_some_and(|map| map.is_changed());
+    if *sampled_mode == Some(mode) && !maps_changed {
+        return;
+    }
+    *sampled_mode = Some(mode);
+
+    profiles.temperature = temperature.map_or_else(Vec::new, |map| {
+        sample_profile(&|direction: Vec3| map.sample_temperature(direction), mode)
+    });
+    profiles.precipitation = precipitation.map_or_else(Vec::new, |map| {
+        sample_profile(&|direction: Vec3| map.sample_mm(direction), mode)
+    });
+    match wind {
+        Some(wind) => {
+            profiles.zonal_wind = sample_profile(&ZonalWind(|direction: Vec3| wind.sample(direction)), mode);
+            profiles.meridional_wind =
+                sample_profile(&MeridionalWind(|direction: Vec3| wind.sample(direction)), mode);
+        }
+        None => {
+            profiles.zonal_wind.clear();
+            profiles.meridional_wind.clear();
+        }
+    }
+}
+
+fn sample_profile(field: &impl SphereField, mode: ProfileMode) -> Vec<ProfileSample> {
+    match mode {
+        ProfileMode::ZonalMean => zonal_mean(field, PROFILE_LATITUDES),
+        ProfileMode::Meridian(longitude) => meridian_profile(field, longitude, PROFILE_LATITUDES),
+    }
+}
+
+/// Foldable window in the top left corner with one plot per climate quantity against latitude
+pub fn render_profiles_panel(
+    mut contexts: EguiContexts,
+    mut profiles: ResMut<ClimateProfiles>,
+    strings: Res<Strings>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new(strings.get("profiles.window"))
+        .id(egui::Id::new("profiles_window"))
+        .anchor(egui::Align2::LEFT_TOP, egui::vec2(PANEL_MARGIN, PANEL_MARGIN))
+        .default_open(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let mut mode = profiles.mode;
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut mode, ProfileMode::ZonalMean, strings.get("profiles.zonal_mean"));
+                let meridian = match mode {
+                    ProfileMode::Meridian(longitude) => longitude,
+                    ProfileMode::ZonalMean => 0.0,
+                };
+                if ui.radio(matches!(mode, ProfileMode::Meridian(_)), strings.get("profiles.meridian")).clicked() {
+                    mode = ProfileMode::Meridian(meridian);
+                }
+            });
+            if let ProfileMode::Meridian(longitude) = &mut mode {
+                ui.add(egui::Slider::new(longitude, -180.0..=180.0).suffix("°").text(strings.get("profiles.longitude")));
+            }
+            // Only written on a change, the refresh system resamples on any change of the mode
+            if mode != profiles.mode {
+                profiles.mode = mode;
+            }
+
+            let mm = format!(" {}", strings.get("unit.mm_per_year"));
+            let plots = [
+                ("profiles.temperature", "°C", &profiles.temperature, egui::Color32::from_rgb(230, 120, 60)),
+                ("profiles.precipitation", mm.as_str(), &profiles.precipitation, egui::Color32::from_rgb(80, 150, 230)),
+                ("profiles.zonal_wind", "", &profiles.zonal_wind, egui::Color32::from_rgb(120, 200, 120)),
+                ("profiles.meridional_wind", "", &profiles.meridional_wind, egui::Color32::from_rgb(200, 200, 110)),
+            ];
+            for (title, unit, samples, color) in plots {
+                profile_plot(ui, &strings, title, unit, samples, color);
+            }
+        });
+}
+
+/// Line plot of one profile, latitude from the south pole on the left to the north pole on
+/// the right, with the value range and a zero line when zero is in range
+fn profile_plot(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    title: StringId,
+    unit: &str,
+    samples: &[ProfileSample],
+    color: egui::Color32,
+) {
+    ui.add_space(4.0);
+    ui.label(strings.get(title));
+
+    let Some(range) = logic::plot_range(samples) else {
+        ui.weak(strings.get("profiles.not_available"));
+        return;
+    };
+
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(PLOT_WIDTH, PLOT_HEIGHT + AXIS_LABEL_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let rect = egui::Rect::from_min_size(response.rect.min, egui::vec2(PLOT_WIDTH, PLOT_HEIGHT));
+    let axis_color = ui.visuals().weak_text_color();
+    let to_screen = |[x, y]: [f32; 2]| egui::pos2(rect.left() + x * rect.width(), rect.bottom() - y * rect.height());
+
+    painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, axis_color), egui::StrokeKind::Inside);
+    if let Some(zero) = range.zero() {
+        painter.line_segment(
+            [to_screen([0.0, zero]), to_screen([1.0, zero])],
+            egui::Stroke::new(0.5, axis_color),
+        );
+    }
+    // Equator
+    painter.line_segment(
+        [to_screen([0.5, 0.0]), to_screen([0.5, 1.0])],
+        egui::Stroke::new(0.5, axis_color),
+    );
+
+    let points = logic::plot_points(samples, range).into_iter().map(to_screen).collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+
+    axis_labels(&painter, rect, range, unit, axis_color);
+}
+
+fn axis_labels(painter: &egui::Painter, rect: egui::Rect, range: PlotRange, unit: &str, color: egui::Color32) {
+    let font = egui::FontId::monospace(9.0);
+    let value_labels = [
+        (rect.left_top(), egui::Align2::LEFT_TOP, range.max),
+        (rect.left_bottom(), egui::Align2::LEFT_BOTTOM, range.min),
+    ];
+    for (position, align, value) in value_labels {
+        painter.text(position, align, format!("{value:.2}{unit}"), font.clone(), color);
+    }
+
+    let latitude_labels = [
+        (rect.left_bottom(), egui::Align2::LEFT_TOP, "90°S"),
+        (rect.center_bottom(), egui::Align2::CENTER_TOP, "0°"),
+        (rect.right_bottom(), egui::Align2::RIGHT_TOP, "90°N"),
+    ];
+    for (position, align, text) in latitude_labels {
+        painter.text(position, align, text, font.clone(), color);
+    }
+}
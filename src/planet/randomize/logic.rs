@@ -0,0 +1,211 @@
+use crate::planet::resources::{ClimateSettings, PlanetSettingsSnapshot};
+use crate::ui::tooltips::SettingId;
+use planetgen::config::RandomizationProfile;
+use planetgen::prelude::generate_seed8;
+use rand::Rng;
+use rand::distr::uniform::SampleUniform;
+use std::collections::HashSet;
+
+/// Smallest difference kept between temperatures that have to stay in order
+const TEMPERATURE_GAP: f32 = 1.0;
+
+/// Give every setting with a lock in the settings panel a new value from `profile`, unless it's
+/// in `locks`, and the planet a new seed. The radius stays, it decides how long generating takes.
+///
+/// The temperatures depend on each other and are picked in order, so whatever is locked the
+/// pole stays colder than the equator and the color scale reaches past both.
+pub fn randomize_settings(
+    settings: &mut PlanetSettingsSnapshot,
+    profile: &RandomizationProfile,
+    locks: &HashSet<SettingId>,
+    rng: &mut impl Rng,
+) {
+    let unlocked = |id: SettingId| !locks.contains(&id);
+
+    let PlanetSettingsSnapshot { terrain, climate, display, .. } = settings;
+    terrain.set_seed(generate_seed8());
+
+    if unlocked(SettingId::ContinentThreshold) {
+        terrain.continent_threshold = pick(rng, profile.continent_threshold);
+    }
+    if unlocked(SettingId::DistortionFrequency) {
+        terrain.distortion_frequency = pick(rng, profile.distortion_frequency);
+    }
+    if unlocked(SettingId::DistortionAmplitude) {
+        terrain.distortion_amplitude = pick(rng, profile.distortion_amplitude);
+    }
+    if unlocked(SettingId::DetailFrequency) {
+        terrain.detail_frequency = pick(rng, profile.detail_frequency);
+    }
+    if unlocked(SettingId::DetailAmplitude) {
+        terrain.detail_amplitude = pick(rng, profile.detail_amplitude);
+    }
+    if unlocked(SettingId::MountainHeight) {
+        terrain.mountain_height = pick(rng, profile.mountain_height);
+    }
+    if unlocked(SettingId::MountainWidth) {
+        terrain.mountain_width = pick(rng, profile.mountain_width);
+    }
+    if unlocked(SettingId::Oblateness) {
+        terrain.oblateness = pick(rng, profile.oblateness);
+    }
+    if unlocked(SettingId::AxialTilt) {
+        display.axial_tilt = pick(rng, profile.axial_tilt);
+    }
+    if unlocked(SettingId::NumPlates) {
+        terrain.num_plates = pick(rng, profile.num_plates);
+    }
+    if unlocked(SettingId::NumMicroPlates) {
+        terrain.num_micro_plates = pick(rng, profile.num_micro_plates);
+    }
+    if unlocked(SettingId::FlowWarpFrequency) {
+        terrain.flow_warp_freq = pick(rng, profile.flow_warp_freq);
+    }
+    if unlocked(SettingId::FlowWarpSteps) {
+        terrain.flow_warp_steps = pick(rng, profile.flow_warp_steps);
+    }
+    if unlocked(SettingId::FlowWarpStepAngle) {
+        terrain.flow_warp_step_angle = pick(rng, profile.flow_warp_step_angle);
+    }
+    if unlocked(SettingId::LatitudeFalloff) {
+        climate.temperature_latitude_falloff = pick(rng, profile.latitude_falloff);
+    }
+    if unlocked(SettingId::PrecipitationTemperatureWeight) {
+        climate.precipitation_temperature_weight = pick(rng, profile.precipitation_temperature_weight);
+    }
+    if unlocked(SettingId::PrecipitationOceanWeight) {
+        climate.precipitation_ocean_weight = pick(rng, profile.precipitation_ocean_weight);
+    }
+
+    randomize_temperatures(climate, profile, &unlocked, rng);
+}
+
+/// Land bonus, equator, pole, then the color scale around them. A locked value narrows the
+/// range of everything picked after it.
+fn randomize_temperatures(
+    settings: &mut ClimateSettings,
+    profile: &RandomizationProfile,
+    unlocked: &impl Fn(SettingId) -> bool,
+    rng: &mut impl Rng,
+) {
+    if unlocked(SettingId::LandTemperatureBonus) {
+        settings.land_temperature_bonus = pick(rng, profile.land_temperature_bonus);
+    }
+
+    if unlocked(SettingId::EquatorTemperature) {
+        let floor = if unlocked(SettingId::PoleTemperature) {
+            f32::NEG_INFINITY
+        } else {
+            settings.temperature_pole_temp + TEMPERATURE_GAP
+        };
+        let ceiling = if unlocked(SettingId::MaxTemperature) {
+            f32::INFINITY
+        } else {
+            settings.temperature_max_temp - settings.land_temperature_bonus - TEMPERATURE_GAP
+        };
+        settings.temperature_equator_temp = pick_within(rng, profile.equator_temp, floor, ceiling);
+    }
+
+    if unlocked(SettingId::PoleTemperature) {
+        let floor = if unlocked(SettingId::MinTemperature) {
+            f32::NEG_INFINITY
+        } else {
+            settings.temperature_min_temp + TEMPERATURE_GAP
+        };
+        let ceiling = settings.temperature_equator_temp - TEMPERATURE_GAP;
+        settings.temperature_pole_temp = pick_within(rng, profile.pole_temp, floor, ceiling);
+    }
+
+    if unlocked(SettingId::MinTemperature) {
+        settings.temperature_min_temp = settings.temperature_pole_temp - pick(rng, profile.color_scale_margin);
+    }
+    if unlocked(SettingId::MaxTemperature) {
+        let hottest = settings.temperature_equator_temp + settings.land_temperature_bonus;
+        settings.temperature_max_temp = hottest + pick(rng, profile.color_scale_margin);
+    }
+}
+
+/// Uniform pick from a `[min, max]` range, `min` if the range is empty
+fn pick<T: SampleUniform + PartialOrd + Copy>(rng: &mut impl Rng, [min, max]: [T; 2]) -> T {
+    if min < max { rng.random_range(min..=max) } else { min }
+}
+
+/// Uniform pick from the part of `range` between `floor` and `ceiling`. When locked values
+/// leave nothing of the range, the allowed value closest to it is used instead.
+fn pick_within(rng: &mut impl Rng, range: [f32; 2], floor: f32, ceiling: f32) -> f32 {
+    let min = range[0].max(floor);
+    let max = range[1].min(ceiling);
+    if min <= max {
+        pick(rng, [min, max])
+    } else {
+        max.max(floor).min(ceiling)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn profile() -> RandomizationProfile {
+        planetgen::config::get_config().randomization
+    }
+
+    fn assert_temperatures_ordered(settings: &ClimateSettings) {
+        assert!(settings.temperature_min_temp < settings.temperature_pole_temp);
+        assert!(settings.temperature_pole_temp < settings.temperature_equator_temp);
+        assert!(
+            settings.temperature_equator_temp + settings.land_temperature_bonus < settings.temperature_max_temp
+        );
+    }
+
+    #[test]
+    fn test_picks_stay_in_the_profile_and_keep_the_temperatures_in_order() {
+        let profile = profile();
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut settings = PlanetSettingsSnapshot::default();
+
+        for _ in 0..200 {
+            randomize_settings(&mut settings, &profile, &HashSet::new(), &mut rng);
+
+            let [min, max] = profile.num_plates;
+            assert!((min..=max).contains(&settings.terrain.num_plates));
+            let [min, max] = profile.continent_threshold;
+            assert!((min..=max).contains(&settings.terrain.continent_threshold));
+            assert_temperatures_ordered(&settings.climate);
+        }
+    }
+
+    #[test]
+    fn test_locked_settings_keep_their_values() {
+        let profile = profile();
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut settings = PlanetSettingsSnapshot::default();
+        settings.terrain.num_plates = 3;
+        settings.climate.temperature_equator_temp = 50.0;
+        let locks = HashSet::from([SettingId::NumPlates, SettingId::EquatorTemperature]);
+
+        randomize_settings(&mut settings, &profile, &locks, &mut rng);
+
+        assert_eq!(settings.terrain.num_plates, 3);
+        assert_eq!(settings.climate.temperature_equator_temp, 50.0);
+        assert_temperatures_ordered(&settings.climate);
+    }
+
+    #[test]
+    fn test_locked_color_scale_squeezes_the_temperatures_inside_it() {
+        let profile = profile();
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut settings = PlanetSettingsSnapshot::default();
+        // Narrower than the profile allows, the picks have to leave their ranges
+        settings.climate.temperature_min_temp = -20.0;
+        settings.climate.temperature_max_temp = 30.0;
+        let locks = HashSet::from([SettingId::MinTemperature, SettingId::MaxTemperature]);
+
+        for _ in 0..50 {
+            randomize_settings(&mut settings, &profile, &locks, &mut rng);
+            assert_temperatures_ordered(&settings.climate);
+        }
+    }
+}
@@ -0,0 +1,30 @@
+pub mod logic;
+
+use crate::ui::tooltips::SettingId;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// Settings the user locked, "Surprise Me" leaves them as they are. Kept for the whole session
+/// like the folded settings sections.
+#[derive(Resource, Default)]
+pub struct RandomizationLocks {
+    locked: HashSet<SettingId>,
+}
+
+impl RandomizationLocks {
+    pub fn is_locked(&self, id: SettingId) -> bool {
+        self.locked.contains(&id)
+    }
+
+    pub fn set(&mut self, id: SettingId, locked: bool) {
+        if locked {
+            self.locked.insert(id);
+        } else {
+            self.locked.remove(&id);
+        }
+    }
+
+    pub fn locked(&self) -> &HashSet<SettingId> {
+        &self.locked
+    }
+}
@@ -1,14 +1,52 @@
-use bevy::prelude::Resource;
-use planetgen::planet::PlanetData;
+use crate::mesh::helpers::{colors_with_seam, spherical_uv_mesh_copy};
+use bevy::asset::{AssetId, Assets, Handle};
+use bevy::ecs::change_detection::Tick;
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{DetectChangesMut, Image, Mesh, Res, ResMut, Resource, StandardMaterial};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use bevy::tasks::Task;
+use crate::planet::events::{SettingsGroup, ViewKind};
+use crate::planet::logic;
+use crate::planet::wind::WindDisplay;
+use crate::ui::locale::{Language, StringId};
+use crate::ui::theme::ThemePreset;
+use planetgen::config::HillshadeConfig;
+use planetgen::prelude::{
+    ColorVariation, DEFAULT_GLYPH_SPACING, Palette, PlanetData, PlateLayout, SnowLine, TemperatureCubeMap, ViewMode, WorldType, expand_seed64, generate_seed8,
+};
+use serde::{Deserialize, Serialize};
 
-#[derive(Resource, Clone)]
-pub struct PlanetGenerationSettings {
+/// A planet settings resource. The settings are split by what they change so each resource
+/// has its own change detection, `emit_settings_diff` then tells which groups of one changed.
+pub trait PlanetSettings: Resource + Clone {
+    /// Every settings group whose fields differ between `old` and `self`
+    fn changed_groups(&self, old: &Self) -> Vec<SettingsGroup>;
+}
+
+/// What the planet generator makes the terrain from, applied on Generate. Saved between runs by
+/// the app settings like the other planet settings, fields missing in an older file get their
+/// defaults.
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerrainSettings {
     pub radius: f32,
+    // Flattening at the poles, 0 is a sphere
+    pub oblateness: f32,
+    // Whether rescaling an existing planet scales terrain heights too (set in config)
+    #[serde(skip)]
+    pub scale_heights_with_radius: bool,
+    // Terran planets get continents and oceans, barren ones craters and no water
+    pub world_type: WorldType,
     pub num_plates: usize,
     pub num_micro_plates: usize,
-    pub show_arrows: bool,
     pub user_seed: u32,
     pub seed: u64,
+    // Seeds of the plate layout and of the terrain noise on it, each re-rolled on its own
+    pub plate_seed: u32,
+    pub terrain_seed: u32,
+    // Show a low-res preview while the full-res planet generates in the background
+    pub preview_generation: bool,
     pub flow_warp_freq: f32,
     pub flow_warp_steps: usize,
     pub flow_warp_step_angle: f32,
@@ -21,44 +59,98 @@ pub struct PlanetGenerationSettings {
     pub detail_frequency: f32,
     pub detail_amplitude: f32,
     pub ocean_depth_amplitude: f32,
-    // View mode
-    pub view_mode_plates: bool, // false = continents, true = plates
     // Mountain snow threshold
     pub snow_threshold: f32,
     // Mountain generation
     pub mountain_height: f32,
     pub mountain_width: f32,
-    // Ocean settings
-    pub show_ocean: bool,
-    // Wind visualization settings
-    pub show_wind: bool,
-    pub wind_particle_count: usize,
-    pub wind_particle_height_offset: f32,
-    pub wind_zonal_speed: f32,
-    pub wind_particle_lifespan: f32,
-    // Wind deflection settings
-    pub wind_deflection_height_threshold: f32,
-    pub wind_deflection_height_scale: f32,
-    pub wind_deflection_spread_radius: usize,
-    pub wind_deflection_spread_decay: f32,
-    pub wind_deflection_strength: f32,
-    pub wind_deflection_iterations: usize,
-    // Vertical air movement
-    pub show_vertical_air: bool,
-    // Temperature visualization settings
-    pub show_temperature: bool,
+}
+
+impl Default for TerrainSettings {
+    fn default() -> Self {
+        let config = planetgen::config::get_config();
+        let seed_8 = generate_seed8();
+        Self {
+            radius: config.generation.radius,
+            oblateness: config.generation.oblateness,
+            scale_heights_with_radius: config.generation.scale_heights_with_radius,
+            world_type: WorldType::default(),
+            num_plates: config.generation.default_num_plates,
+            num_micro_plates: config.generation.default_num_micro_plates,
+            user_seed: seed_8,
+            seed: expand_seed64(seed_8),
+            plate_seed: seed_8,
+            terrain_seed: seed_8,
+            preview_generation: true,
+            flow_warp_freq: config.flow_warp.default_freq,
+            flow_warp_steps: config.flow_warp.default_steps,
+            flow_warp_step_angle: config.flow_warp.default_step_angle,
+            continent_frequency: config.continents.continent_frequency,
+            continent_amplitude: config.continents.continent_amplitude,
+            distortion_frequency: config.continents.distortion_frequency,
+            distortion_amplitude: config.continents.distortion_amplitude,
+            continent_threshold: config.continents.continent_threshold,
+            detail_frequency: config.continents.detail_frequency,
+            detail_amplitude: config.continents.detail_amplitude,
+            ocean_depth_amplitude: config.continents.ocean_depth_amplitude,
+            snow_threshold: config.mountains.snow_threshold,
+            mountain_height: config.mountains.height,
+            mountain_width: config.mountains.width,
+        }
+    }
+}
+
+impl TerrainSettings {
+    /// Use the planet of seed `code`, the plates and the terrain follow it
+    pub fn set_seed(&mut self, code: u32) {
+        self.user_seed = code;
+        self.seed = expand_seed64(code);
+        self.plate_seed = code;
+        self.terrain_seed = code;
+    }
+
+    /// Where the continent view is white, from `temperature` once there is one and above the
+    /// snow threshold until then
+    pub fn snow_line<'a>(&self, climate: &ClimateSettings, temperature: Option<&'a TemperatureCubeMap>) -> SnowLine<'a> {
+        let snow = SnowLine::height(self.snow_threshold);
+        match temperature {
+            Some(temperature) => {
+                snow.with_temperature(temperature, climate.temperature_lapse_rate, climate.land_temperature_bonus)
+            }
+            None => snow,
+        }
+    }
+}
+
+impl PlanetSettings for TerrainSettings {
+    fn changed_groups(&self, old: &Self) -> Vec<SettingsGroup> {
+        logic::changed_terrain_groups(old, self)
+    }
+}
+
+/// Temperature, precipitation and the biomes made of both
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClimateSettings {
     pub land_temperature_bonus: f32, // Temperature increase for land (above sea level)
     pub temperature_equator_temp: f32,
     pub temperature_pole_temp: f32,
     pub temperature_max_temp: f32,
     pub temperature_min_temp: f32,
     pub temperature_latitude_falloff: f32,
+    /// °C colder per unit of height above sea level, places the snow line
+    pub temperature_lapse_rate: f32,
+    /// Finest temperature cubemap, the resolution follows the planet's grid up to this
     pub temperature_cubemap_resolution: usize,
-    // Precipitation visualization settings
-    pub show_precipitation: bool,
+    /// Resolution of the temperature cubemap regardless of the planet's grid
+    pub temperature_cubemap_override: Option<usize>,
     pub precipitation_temperature_weight: f32,
     pub precipitation_ocean_weight: f32,
+    /// Finest wind, vertical air and precipitation cubemaps, the resolution follows the
+    /// planet's grid up to this
     pub precipitation_cubemap_resolution: usize,
+    /// Resolution of the wind, vertical air and precipitation cubemaps regardless of the planet's grid
+    pub precipitation_cubemap_override: Option<usize>,
     // Biome thresholds
     pub biome_ice_temp: f32,
     pub biome_tundra_temp: f32,
@@ -78,57 +170,23 @@ pub struct PlanetGenerationSettings {
     pub biome_jungle_color: [f32; 3],
 }
 
-impl Default for PlanetGenerationSettings {
+impl Default for ClimateSettings {
     fn default() -> Self {
-        let config = planetgen::get_config();
-        let seed_8 = planetgen::tools::generate_seed8();
+        let config = planetgen::config::get_config();
         Self {
-            radius: config.generation.radius,
-            num_plates: config.generation.default_num_plates,
-            num_micro_plates: config.generation.default_num_micro_plates,
-            show_arrows: false,
-            user_seed: seed_8,
-            seed: planetgen::tools::expand_seed64(seed_8),
-            flow_warp_freq: config.flow_warp.default_freq,
-            flow_warp_steps: config.flow_warp.default_steps,
-            flow_warp_step_angle: config.flow_warp.default_step_angle,
-            continent_frequency: config.continents.continent_frequency,
-            continent_amplitude: config.continents.continent_amplitude,
-            distortion_frequency: config.continents.distortion_frequency,
-            distortion_amplitude: config.continents.distortion_amplitude,
-            continent_threshold: config.continents.continent_threshold,
-            detail_frequency: config.continents.detail_frequency,
-            detail_amplitude: config.continents.detail_amplitude,
-            ocean_depth_amplitude: config.continents.ocean_depth_amplitude,
-            view_mode_plates: false,
-            snow_threshold: config.mountains.snow_threshold,
-            mountain_height: config.mountains.height,
-            mountain_width: config.mountains.width,
-            show_ocean: true,
-            show_wind: false,
-            wind_particle_count: config.wind.particle_count,
-            wind_particle_height_offset: config.wind.particle_height_offset,
-            wind_zonal_speed: config.wind.zonal_speed,
-            wind_particle_lifespan: config.wind.particle_lifespan,
-            wind_deflection_height_threshold: config.wind_deflection.height_threshold,
-            wind_deflection_height_scale: config.wind_deflection.height_scale,
-            wind_deflection_spread_radius: config.wind_deflection.spread_radius,
-            wind_deflection_spread_decay: config.wind_deflection.spread_decay,
-            wind_deflection_strength: config.wind_deflection.deflection_strength,
-            wind_deflection_iterations: config.wind_deflection.deflection_iterations,
-            show_vertical_air: false,
-            show_temperature: false,
             land_temperature_bonus: config.temperature.land_temperature_bonus,
             temperature_equator_temp: config.temperature.equator_temp,
             temperature_pole_temp: config.temperature.pole_temp,
             temperature_max_temp: config.temperature.max_temp,
             temperature_min_temp: config.temperature.min_temp,
             temperature_latitude_falloff: config.temperature.latitude_falloff,
+            temperature_lapse_rate: config.temperature.lapse_rate,
             temperature_cubemap_resolution: config.temperature.cubemap_resolution,
-            show_precipitation: false,
+            temperature_cubemap_override: None,
             precipitation_temperature_weight: config.precipitation.temperature_weight,
             precipitation_ocean_weight: config.precipitation.ocean_weight,
             precipitation_cubemap_resolution: config.precipitation.cubemap_resolution,
+            precipitation_cubemap_override: None,
             biome_ice_temp: config.biome.ice_temp,
             biome_tundra_temp: config.biome.tundra_temp,
             biome_boreal_temp: config.biome.boreal_temp,
@@ -148,13 +206,658 @@ impl Default for PlanetGenerationSettings {
     }
 }
 
-#[derive(Resource)]
+impl ClimateSettings {
+    /// Resolution of the temperature cubemap for a planet with `face_grid_size` vertices along
+    /// each face edge
+    pub fn temperature_resolution_for(&self, face_grid_size: usize) -> usize {
+        self.temperature_cubemap_override
+            .unwrap_or_else(|| logic::climate_cubemap_resolution(face_grid_size, self.temperature_cubemap_resolution))
+    }
+
+    /// Resolution of the wind, vertical air and precipitation cubemaps for a planet with
+    /// `face_grid_size` vertices along each face edge
+    pub fn precipitation_resolution_for(&self, face_grid_size: usize) -> usize {
+        self.precipitation_cubemap_override
+            .unwrap_or_else(|| logic::climate_cubemap_resolution(face_grid_size, self.precipitation_cubemap_resolution))
+    }
+}
+
+impl PlanetSettings for ClimateSettings {
+    fn changed_groups(&self, old: &Self) -> Vec<SettingsGroup> {
+        logic::changed_climate_groups(old, self)
+    }
+}
+
+/// The wind model, its mountain deflection and how the wind tab draws it
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindSettings {
+    pub wind_particle_count: usize,
+    pub wind_particle_height_offset: f32,
+    // Particles skim over mountains and the sea instead of the mean surface
+    pub wind_terrain_following: bool,
+    pub wind_zonal_speed: f32,
+    pub wind_particle_lifespan: f32,
+    /// Particles or the glyph grid on the wind tab
+    pub wind_display: WindDisplay,
+    /// Degrees between the wind glyphs
+    pub wind_glyph_spacing: f32,
+    /// How far a traced streamline reaches to either side of its seed, in degrees of arc
+    pub streamline_arc_length: f32,
+    // Wind deflection settings
+    pub wind_deflection_height_threshold: f32,
+    pub wind_deflection_height_scale: f32,
+    pub wind_deflection_spread_radius: usize,
+    pub wind_deflection_spread_decay: f32,
+    pub wind_deflection_strength: f32,
+    pub wind_deflection_iterations: usize,
+}
+
+impl Default for WindSettings {
+    fn default() -> Self {
+        let config = planetgen::config::get_config();
+        Self {
+            wind_particle_count: config.wind.particle_count,
+            wind_particle_height_offset: config.wind.particle_height_offset,
+            wind_terrain_following: true,
+            wind_zonal_speed: config.wind.zonal_speed,
+            wind_particle_lifespan: config.wind.particle_lifespan,
+            wind_display: WindDisplay::default(),
+            wind_glyph_spacing: DEFAULT_GLYPH_SPACING,
+            streamline_arc_length: 60.0,
+            wind_deflection_height_threshold: config.wind_deflection.height_threshold,
+            wind_deflection_height_scale: config.wind_deflection.height_scale,
+            wind_deflection_spread_radius: config.wind_deflection.spread_radius,
+            wind_deflection_spread_decay: config.wind_deflection.spread_decay,
+            wind_deflection_strength: config.wind_deflection.deflection_strength,
+            wind_deflection_iterations: config.wind_deflection.deflection_iterations,
+        }
+    }
+}
+
+impl PlanetSettings for WindSettings {
+    fn changed_groups(&self, old: &Self) -> Vec<SettingsGroup> {
+        logic::changed_wind_groups(old, self)
+    }
+}
+
+/// How the planet and the menus look: overlay toggles, colors, the ocean, the moons and the camera
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub show_arrows: bool,
+    // Hide surface markers on the far side of the planet, off for debugging
+    pub cull_far_side: bool,
+    // A released drag leaves the planet spinning down
+    pub rotation_inertia: bool,
+    // Generating a new planet keeps the zoom instead of framing the planet again
+    pub keep_view: bool,
+    // Rotation axis with the tropics and polar circles
+    pub show_axis: bool,
+    // Names of the continents, oceans and mountain ranges over the planet
+    pub show_feature_labels: bool,
+    // Debug lines where the terrain meets the sea
+    pub show_coastline: bool,
+    pub axial_tilt: f32, // Degrees
+    // Moons orbiting the planet, up to MAX_MOONS
+    pub moons: Vec<MoonSettings>,
+    // Climate overlays as textures at the full cubemap resolution, off = vertex colors
+    pub overlay_textures: bool,
+    // Colors of the overlays and the plate view, with color-blind friendly alternatives
+    pub palette: Palette,
+    // Colors of the menus and the panels
+    pub ui_theme: ThemePreset,
+    // Language of the menus, the tooltips and the help
+    pub language: Language,
+    // Shares of the zoom the camera and the point it looks at sit right of the planet, 0 centers it
+    pub camera_offset: f32,
+    pub look_at_offset: f32,
+    // View mode
+    pub view_mode_plates: bool, // false = continents, true = plates
+    // Plate view colored by crust type instead of random colors
+    pub plate_crust_colors: bool,
+    // Relief shading baked into the terrain colors
+    pub hillshade_azimuth: f32,
+    pub hillshade_elevation: f32,
+    pub hillshade_strength: f32,
+    // Faint brightness and saturation noise over the terrain colors, 0 keeps the plain colors
+    pub color_variation_strength: f32,
+    // Ocean settings
+    pub show_ocean: bool,
+    pub ocean_foam_color: [f32; 3],
+    pub ocean_foam_width: f32, // Depth band below sea level covered by foam
+    // Overlays of the climate tabs
+    pub show_wind: bool,
+    /// Color the planet by how strongly mountains block the wind
+    pub show_wind_influence: bool,
+    pub show_vertical_air: bool,
+    pub show_temperature: bool,
+    pub show_precipitation: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        let config = planetgen::config::get_config();
+        Self {
+            show_arrows: false,
+            cull_far_side: true,
+            rotation_inertia: true,
+            keep_view: true,
+            show_axis: false,
+            show_feature_labels: true,
+            show_coastline: false,
+            axial_tilt: 23.44, // Earth's
+            moons: Vec::new(),
+            overlay_textures: true,
+            palette: Palette::default(),
+            ui_theme: ThemePreset::default(),
+            language: Language::default(),
+            camera_offset: 0.25,
+            look_at_offset: 0.15,
+            view_mode_plates: false,
+            plate_crust_colors: false,
+            hillshade_azimuth: config.hillshade.azimuth,
+            hillshade_elevation: config.hillshade.elevation,
+            hillshade_strength: config.hillshade.strength,
+            color_variation_strength: config.color_variation.strength,
+            show_ocean: true,
+            ocean_foam_color: [0.85, 0.92, 0.95],
+            ocean_foam_width: 0.05,
+            show_wind: false,
+            show_wind_influence: false,
+            show_vertical_air: false,
+            show_temperature: false,
+            show_precipitation: false,
+        }
+    }
+}
+
+impl DisplaySettings {
+    pub fn hillshade(&self) -> HillshadeConfig {
+        HillshadeConfig {
+            azimuth: self.hillshade_azimuth,
+            elevation: self.hillshade_elevation,
+            strength: self.hillshade_strength,
+        }
+    }
+
+    /// Color noise of the terrain, its pattern follows the planet `seed`
+    pub fn color_variation(&self, seed: u64) -> ColorVariation {
+        ColorVariation {
+            strength: self.color_variation_strength,
+            seed,
+        }
+    }
+
+    /// Where the camera frames the planet from
+    pub fn camera_composition(&self) -> logic::CameraComposition {
+        logic::CameraComposition {
+            camera_offset: self.camera_offset,
+            look_at_offset: self.look_at_offset,
+        }
+    }
+
+    /// Mesh coloring of the tectonic plate view
+    pub fn plate_view_mode(&self) -> ViewMode {
+        if self.plate_crust_colors {
+            ViewMode::PlateCrust
+        } else {
+            ViewMode::Plates
+        }
+    }
+}
+
+impl PlanetSettings for DisplaySettings {
+    fn changed_groups(&self, old: &Self) -> Vec<SettingsGroup> {
+        logic::changed_display_groups(old, self)
+    }
+}
+
+/// The settings the colors of the surface come from: the snow line and the relief shading of
+/// the terrain, the climate overlays on top of it and the palette of both
+pub type SurfaceColorSettings<'a> = (&'a TerrainSettings, &'a ClimateSettings, &'a DisplaySettings);
+
+/// All of the planet settings at once, for what stores and restores them together: the app
+/// settings file and the generation records
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanetSettingsSnapshot {
+    pub terrain: TerrainSettings,
+    pub climate: ClimateSettings,
+    pub wind: WindSettings,
+    pub display: DisplaySettings,
+}
+
+/// Every planet settings resource, for the systems that save all of them
+#[derive(SystemParam)]
+pub struct AllPlanetSettings<'w> {
+    pub terrain: Res<'w, TerrainSettings>,
+    pub climate: Res<'w, ClimateSettings>,
+    pub wind: Res<'w, WindSettings>,
+    pub display: Res<'w, DisplaySettings>,
+}
+
+impl AllPlanetSettings<'_> {
+    pub fn snapshot(&self) -> PlanetSettingsSnapshot {
+        PlanetSettingsSnapshot {
+            terrain: self.terrain.clone(),
+            climate: self.climate.clone(),
+            wind: self.wind.clone(),
+            display: self.display.clone(),
+        }
+    }
+}
+
+/// Every planet settings resource, for the systems that edit or restore all of them
+#[derive(SystemParam)]
+pub struct AllPlanetSettingsMut<'w> {
+    pub terrain: ResMut<'w, TerrainSettings>,
+    pub climate: ResMut<'w, ClimateSettings>,
+    pub wind: ResMut<'w, WindSettings>,
+    pub display: ResMut<'w, DisplaySettings>,
+}
+
+impl AllPlanetSettingsMut<'_> {
+    pub fn snapshot(&self) -> PlanetSettingsSnapshot {
+        PlanetSettingsSnapshot {
+            terrain: self.terrain.clone(),
+            climate: self.climate.clone(),
+            wind: self.wind.clone(),
+            display: self.display.clone(),
+        }
+    }
+
+    /// Put `snapshot` in place. Only the resources it changes are marked as changed, so what
+    /// follows one domain isn't rebuilt for an edit in another.
+    pub fn apply(&mut self, snapshot: PlanetSettingsSnapshot) {
+        self.terrain.set_if_neq(snapshot.terrain);
+        self.climate.set_if_neq(snapshot.climate);
+        self.wind.set_if_neq(snapshot.wind);
+        self.display.set_if_neq(snapshot.display);
+    }
+}
+
+/// Most moons the settings panel offers
+pub const MAX_MOONS: usize = 3;
+
+/// Size and orbit of one moon, lengths in planet radii so they follow a rescaled planet
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MoonSettings {
+    pub radius: f32,
+    /// Distance of the moon center from the planet center
+    pub orbit_radius: f32,
+    /// Seconds per orbit
+    pub orbit_period: f32,
+    /// Tilt of the orbit against the equator, in degrees
+    pub inclination: f32,
+}
+
+impl Default for MoonSettings {
+    fn default() -> Self {
+        Self {
+            radius: 0.27,
+            orbit_radius: 4.0,
+            orbit_period: 60.0,
+            inclination: 5.0,
+        }
+    }
+}
+
+impl MoonSettings {
+    /// Defaults for the `index`-th moon, each further out and slower than the one before
+    /// so a new moon doesn't start inside an existing one
+    pub fn nth(index: usize) -> Self {
+        let default = Self::default();
+        let step = index as f32;
+        Self {
+            radius: default.radius * 0.7f32.powf(step),
+            orbit_radius: default.orbit_radius + 2.5 * step,
+            orbit_period: default.orbit_period * (1.0 + step),
+            inclination: default.inclination + 7.0 * step,
+        }
+    }
+}
+
+/// Identifies one generated planet, increases every time a new planet is stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PlanetGenerationId(pub u64);
+
+#[derive(Resource, Default)]
 pub struct CurrentPlanetData {
     pub planet_data: Option<PlanetData>,
+    pub generation: PlanetGenerationId,
 }
 
-impl Default for CurrentPlanetData {
-    fn default() -> Self {
-        Self { planet_data: None }
+impl CurrentPlanetData {
+    /// Store a newly generated planet, anything derived from the old one is stale now
+    pub fn replace(&mut self, planet_data: PlanetData) {
+        self.planet_data = Some(planet_data);
+        self.generation.0 += 1;
+    }
+
+    /// The stored planet was changed without replacing it, like `replace` its overlays are stale
+    pub fn changed_in_place(&mut self) {
+        self.generation.0 += 1;
+    }
+}
+
+/// Sea level of the current planet in heightmap units, None before the first planet. It comes
+/// with the planet, so moving the continent threshold slider only changes it on the next
+/// generation.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct WaterLevel(pub Option<f32>);
+
+/// The view picked in the tab bar. Only changed by `SetActiveView` messages, which the tab
+/// visibility system applies, so every system agrees on what is active.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayState {
+    pub active: ViewKind,
+}
+
+/// Locale key of the text shown over the planet view instead of the open tab's overlay while
+/// it can't be drawn
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayHint(pub Option<StringId>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverlayKind {
+    Temperature,
+    Precipitation,
+    VerticalAir,
+    WindInfluence,
+    CrustAge,
+}
+
+/// What overlay colors were computed from: the planet, the cubemap they were sampled from, the
+/// palette and every setting that goes into the colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayCacheKey {
+    generation: PlanetGenerationId,
+    settings_hash: u64,
+}
+
+impl OverlayCacheKey {
+    /// `cubemap_changed` is the change tick of the cubemap resource, so rebuilding the cubemap
+    /// gives a new key even when the settings come back to old values
+    pub fn new(
+        generation: PlanetGenerationId,
+        cubemap_changed: Tick,
+        palette: Palette,
+        settings: &[f32],
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        cubemap_changed.get().hash(&mut hasher);
+        palette.hash(&mut hasher);
+        for value in settings {
+            value.to_bits().hash(&mut hasher);
+        }
+        Self {
+            generation,
+            settings_hash: hasher.finish(),
+        }
+    }
+}
+
+/// Vertex colors of the overlays, per overlay and per source mesh. Opening an overlay again
+/// with nothing changed doesn't sample every vertex again, and the shared overlay copy gets
+/// the colors of whichever overlay is shown from here.
+#[derive(Resource, Default)]
+pub struct OverlayColorCache {
+    overlays: HashMap<OverlayKind, CachedOverlayColors>,
+}
+
+struct CachedOverlayColors {
+    key: OverlayCacheKey,
+    colors: HashMap<AssetId<Mesh>, Vec<[f32; 4]>>,
+}
+
+impl OverlayColorCache {
+    /// Compute the colors of `source` for `overlay` unless they were cached under the same key.
+    /// A different key drops everything cached for that overlay.
+    pub fn ensure_colors(
+        &mut self,
+        overlay: OverlayKind,
+        key: OverlayCacheKey,
+        source: AssetId<Mesh>,
+        compute: impl FnOnce() -> Vec<[f32; 4]>,
+    ) {
+        let cached = self.overlays.entry(overlay).or_insert_with(|| CachedOverlayColors {
+            key,
+            colors: HashMap::new(),
+        });
+        if cached.key != key {
+            cached.key = key;
+            cached.colors.clear();
+        }
+        cached.colors.entry(source).or_insert_with(compute);
+    }
+
+    /// The last colors computed for `source` and `overlay`
+    pub fn colors(&self, overlay: OverlayKind, source: AssetId<Mesh>) -> Option<&[[f32; 4]]> {
+        self.overlays.get(&overlay)?.colors.get(&source).map(Vec::as_slice)
+    }
+}
+
+/// One overlay copy per source mesh, shared by every overlay. Only one overlay is shown at a
+/// time, so the copy carries the spherical UVs for textured overlays and gets the vertex
+/// colors of the overlay that is shown (see `apply_overlay_colors`).
+#[derive(Resource, Default)]
+pub struct OverlayMeshes {
+    copies: HashMap<AssetId<Mesh>, OverlayMeshCopy>,
+}
+
+struct OverlayMeshCopy {
+    /// Not a handle, the copy goes away with the last overlay entity that uses it
+    mesh: AssetId<Mesh>,
+    /// Original index of every vertex duplicated along the UV seam
+    seam_sources: Vec<u32>,
+}
+
+impl OverlayMeshes {
+    /// The shared copy of `source`, made if there is none or the last one was dropped
+    pub fn copy_of(
+        &mut self,
+        source: &Handle<Mesh>,
+        meshes: &mut Assets<Mesh>,
+        planet_assets: &mut PlanetAssets,
+    ) -> Option<Handle<Mesh>> {
+        if let Some(copy) = self.copies.get(&source.id())
+            && let Some(handle) = meshes.get_strong_handle(copy.mesh)
+        {
+            return Some(handle);
+        }
+
+        let (mesh, seam_sources) = spherical_uv_mesh_copy(meshes.get(source)?);
+        let handle = planet_assets.add_mesh(meshes, mesh);
+        // Copies of terrain LODs and planets that are gone
+        self.copies.retain(|source, _| meshes.contains(*source));
+        self.copies.insert(
+            source.id(),
+            OverlayMeshCopy {
+                mesh: handle.id(),
+                seam_sources,
+            },
+        );
+        Some(handle)
+    }
+
+    /// Colors of the vertices of `source` extended to its shared copy
+    pub fn copy_colors(&self, source: AssetId<Mesh>, colors: &[[f32; 4]]) -> Vec<[f32; 4]> {
+        let seam_sources = self.copies.get(&source).map_or(&[][..], |copy| copy.seam_sources.as_slice());
+        colors_with_seam(colors, seam_sources)
+    }
+}
+
+/// Mesh and material stores with the tracker of the planet's assets, for the systems that
+/// spawn parts of the planet
+pub type PlanetAssetStores<'w> = (
+    ResMut<'w, Assets<Mesh>>,
+    ResMut<'w, Assets<StandardMaterial>>,
+    ResMut<'w, PlanetAssets>,
+);
+
+/// Meshes, materials and images made for one planet
+#[derive(Default)]
+struct TrackedAssets {
+    meshes: Vec<AssetId<Mesh>>,
+    materials: Vec<AssetId<StandardMaterial>>,
+    images: Vec<AssetId<Image>>,
+}
+
+/// How many assets of each kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AssetCounts {
+    pub meshes: usize,
+    pub materials: usize,
+    pub images: usize,
+}
+
+/// Every asset made for the current planet: its terrain, ocean, arrows, overlays, gizmos and
+/// particles. When a new planet replaces it they are removed from the asset stores outright,
+/// so a handle a cache still holds on to can't keep them alive. Ids, not handles: an asset a
+/// rebuild replaced still goes away with its last handle.
+#[derive(Resource, Default)]
+pub struct PlanetAssets {
+    current: TrackedAssets,
+    /// Assets of the planets replaced since the last release
+    replaced: TrackedAssets,
+}
+
+impl PlanetAssets {
+    pub fn add_mesh(&mut self, meshes: &mut Assets<Mesh>, mesh: impl Into<Mesh>) -> Handle<Mesh> {
+        let handle = meshes.add(mesh);
+        self.current.meshes.push(handle.id());
+        handle
+    }
+
+    pub fn add_material(
+        &mut self,
+        materials: &mut Assets<StandardMaterial>,
+        material: impl Into<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        let handle = materials.add(material);
+        self.current.materials.push(handle.id());
+        handle
+    }
+
+    pub fn add_image(&mut self, images: &mut Assets<Image>, image: impl Into<Image>) -> Handle<Image> {
+        let handle = images.add(image);
+        self.current.images.push(handle.id());
+        handle
+    }
+
+    /// A new planet takes the place of the current one, everything made so far belongs to the
+    /// old planet and goes on the next release
+    pub fn replace_planet(&mut self) {
+        let old = std::mem::take(&mut self.current);
+        self.replaced.meshes.extend(old.meshes);
+        self.replaced.materials.extend(old.materials);
+        self.replaced.images.extend(old.images);
+    }
+
+    /// Remove the assets of the replaced planets, how many of each were still there
+    pub fn release_replaced(
+        &mut self,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+        images: &mut Assets<Image>,
+    ) -> AssetCounts {
+        let replaced = std::mem::take(&mut self.replaced);
+        AssetCounts {
+            meshes: replaced.meshes.into_iter().filter_map(|id| meshes.remove(id)).count(),
+            materials: replaced.materials.into_iter().filter_map(|id| materials.remove(id)).count(),
+            images: replaced.images.into_iter().filter_map(|id| images.remove(id)).count(),
+        }
+    }
+
+    pub fn has_replaced(&self) -> bool {
+        let replaced = &self.replaced;
+        !replaced.meshes.is_empty() || !replaced.materials.is_empty() || !replaced.images.is_empty()
+    }
+
+    /// Assets made for the current planet that are still alive
+    pub fn current_counts(
+        &self,
+        meshes: &Assets<Mesh>,
+        materials: &Assets<StandardMaterial>,
+        images: &Assets<Image>,
+    ) -> AssetCounts {
+        let current = &self.current;
+        AssetCounts {
+            meshes: current.meshes.iter().filter(|&&id| meshes.contains(id)).count(),
+            materials: current.materials.iter().filter(|&&id| materials.contains(id)).count(),
+            images: current.images.iter().filter(|&&id| images.contains(id)).count(),
+        }
+    }
+}
+
+/// Plates of the planet a terrain re-roll keeps, used up by the next generation
+#[derive(Resource, Default)]
+pub struct KeptPlateLayout(pub Option<PlateLayout>);
+
+/// Full-resolution planet that is being generated in the background while the preview is shown
+#[derive(Resource, Default)]
+pub struct PendingPlanetGeneration {
+    pub task: Option<Task<PlanetData>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_colors_are_reused_until_the_key_changes() {
+        let mut cache = OverlayColorCache::default();
+        let source = AssetId::<Mesh>::default();
+        let key = |generation, tick, palette, value| {
+            OverlayCacheKey::new(PlanetGenerationId(generation), Tick::new(tick), palette, &[value, 20.0])
+        };
+        let computed = std::cell::Cell::new(0);
+        let colors = |cache: &mut OverlayColorCache, key| {
+            cache.ensure_colors(OverlayKind::Temperature, key, source, || {
+                computed.set(computed.get() + 1);
+                vec![[1.0; 4]]
+            })
+        };
+
+        colors(&mut cache, key(1, 5, Palette::Default, 0.5));
+        colors(&mut cache, key(1, 5, Palette::Default, 0.5));
+        assert_eq!(computed.get(), 1);
+
+        colors(&mut cache, key(2, 5, Palette::Default, 0.5));
+        colors(&mut cache, key(2, 6, Palette::Default, 0.5));
+        colors(&mut cache, key(2, 6, Palette::Default, 0.6));
+        colors(&mut cache, key(2, 6, Palette::Viridis, 0.6));
+        assert_eq!(computed.get(), 5);
+
+        assert_eq!(cache.colors(OverlayKind::Temperature, source), Some(&[[1.0; 4]][..]));
+        assert!(cache.colors(OverlayKind::CrustAge, source).is_none());
+    }
+
+    #[test]
+    fn test_replaced_planet_assets_are_removed_even_with_handles_left() {
+        let mut meshes = Assets::<Mesh>::default();
+        let mut materials = Assets::<StandardMaterial>::default();
+        let mut images = Assets::<Image>::default();
+        let mut planet_assets = PlanetAssets::default();
+
+        let old_mesh = planet_assets.add_mesh(&mut meshes, Mesh::from(bevy::math::primitives::Sphere::new(1.0)));
+        let old_material = planet_assets.add_material(&mut materials, StandardMaterial::default());
+        let shared = meshes.add(Mesh::from(bevy::math::primitives::Cuboid::default()));
+        planet_assets.replace_planet();
+        let new_mesh = planet_assets.add_mesh(&mut meshes, Mesh::from(bevy::math::primitives::Sphere::new(2.0)));
+
+        let released = planet_assets.release_replaced(&mut meshes, &mut materials, &mut images);
+
+        assert_eq!(released, AssetCounts {
+            meshes: 1,
+            materials: 1,
+            images: 0
+        });
+        assert!(!meshes.contains(&old_mesh) && !materials.contains(&old_material));
+        assert!(meshes.contains(&new_mesh) && meshes.contains(&shared));
+        assert!(!planet_assets.has_replaced());
+        assert_eq!(planet_assets.current_counts(&meshes, &materials, &images).meshes, 1);
     }
 }
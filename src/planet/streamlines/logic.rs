@@ -0,0 +1,67 @@
+use bevy::math::Vec3;
+use planetgen::prelude::{TraceDirection, WindCubeMap, trace_streamline};
+
+/// Angle along the sphere between two points of a streamline, in radians
+pub const STREAMLINE_STEP_ANGLE: f32 = 0.01;
+
+/// Colors the streamlines take in turn, in sRGB
+pub const STREAMLINE_COLORS: [[f32; 3]; 6] = [
+    [1.0, 0.35, 0.75],
+    [0.3, 1.0, 0.45],
+    [1.0, 0.85, 0.2],
+    [0.55, 0.5, 1.0],
+    [0.2, 0.95, 0.95],
+    [1.0, 0.5, 0.25],
+];
+
+/// Color of the `index`th streamline, cycling through `STREAMLINE_COLORS`
+pub fn streamline_color(index: usize) -> [f32; 3] {
+    STREAMLINE_COLORS[index % STREAMLINE_COLORS.len()]
+}
+
+/// Directions of the streamline through `seed`, `arc_length_degrees` long to either side: where
+/// the air came from, then where it goes. Shorter where the wind dies down.
+pub fn streamline_through(wind: &WindCubeMap, seed: Vec3, arc_length_degrees: f32) -> Vec<Vec3> {
+    let max_steps = (arc_length_degrees.to_radians() / STREAMLINE_STEP_ANGLE).round() as usize;
+    let mut points = trace_streamline(wind, seed, STREAMLINE_STEP_ANGLE, max_steps, TraceDirection::Backward);
+    points.reverse();
+    // Both halves start at the seed
+    points.pop();
+    points.extend(trace_streamline(wind, seed, STREAMLINE_STEP_ANGLE, max_steps, TraceDirection::Forward));
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, STREAMLINE_COLORS[0])]
+    #[case(5, STREAMLINE_COLORS[5])]
+    #[case(6, STREAMLINE_COLORS[0])]
+    #[case(13, STREAMLINE_COLORS[1])]
+    fn test_colors_cycle(#[case] index: usize, #[case] expected: [f32; 3]) {
+        assert_eq!(streamline_color(index), expected);
+    }
+
+    #[test]
+    fn test_streamline_runs_through_the_seed() {
+        let wind = WindCubeMap::build(16, 5.0);
+        let seed = Vec3::new(1.0, 0.6, 0.2).normalize();
+        let points = streamline_through(&wind, seed, 10.0);
+
+        let seed_index = points
+            .iter()
+            .position(|point| point.distance(seed) < 1e-6)
+            .expect("the seed is on the line");
+        assert!(seed_index > 0 && seed_index < points.len() - 1);
+        // Consecutive points are a step apart, the seed isn't doubled
+        for pair in points.windows(2) {
+            let angle = pair[0].angle_between(pair[1]);
+            assert!((angle - STREAMLINE_STEP_ANGLE).abs() < 1e-3, "step of {angle}");
+        }
+        // 10° to either side at most
+        assert!(points.len() <= 2 * 17 + 1);
+    }
+}
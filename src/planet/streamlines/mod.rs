@@ -0,0 +1,53 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::{ClimateRebuildSet, SettingsDiffSet};
+use crate::planet::picking::systems::pick_surface_on_click;
+use crate::planet::systems::{rescale_planet_on_event, spawn_planet_on_event};
+use bevy::prelude::*;
+
+/// Points the wind is traced through, in the order they were picked. Cleared when the wind
+/// cubemap is rebuilt, the lines would no longer follow it.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct Streamlines {
+    pub seeds: Vec<Vec3>,
+}
+
+impl Streamlines {
+    pub fn add(&mut self, seed: Vec3) {
+        self.seeds.push(seed.normalize());
+    }
+
+    pub fn clear(&mut self) {
+        self.seeds.clear();
+    }
+}
+
+/// Line of a traced streamline, a child of the planet
+#[derive(Component)]
+pub struct StreamlineMesh;
+
+/// Alt-clicking the planet, or "Trace Wind" in the probe window, traces the wind through the
+/// point: where the air came from and where it goes, highlighted until cleared
+pub struct StreamlinesPlugin;
+
+impl Plugin for StreamlinesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Streamlines>().add_systems(
+            Update,
+            (
+                systems::seed_streamline_on_click.after(pick_surface_on_click),
+                systems::clear_streamlines_on_new_wind
+                    .after(spawn_planet_on_event)
+                    .after(ClimateRebuildSet::Wind),
+                systems::sync_streamline_meshes
+                    .after(systems::seed_streamline_on_click)
+                    .after(systems::clear_streamlines_on_new_wind)
+                    .after(SettingsDiffSet)
+                    .after(rescale_planet_on_event),
+            )
+                .run_if(in_state(GameState::PlanetGeneration)),
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use super::logic::{streamline_color, streamline_through};
+use super::{StreamlineMesh, Streamlines};
+use crate::mesh::helpers::surface_ribbon_mesh;
+use crate::planet::components::PlanetEntity;
+use crate::planet::events::{PlanetSpawnedEvent, SettingsDiff, SettingsGroup};
+use crate::planet::picking::SurfacePick;
+use crate::planet::resources::{CurrentPlanetData, PlanetAssetStores, WindSettings};
+use crate::planet::wind::WindParticleSettings;
+use crate::planet::wind::systems::WindCubeMap;
+use bevy::prelude::*;
+
+/// Width of a streamline as a share of the planet radius
+const LINE_WIDTH: f32 = 0.004;
+
+/// Trace the wind through the point picked this frame while Alt is held
+pub fn seed_streamline_on_click(
+    pick: Res<SurfacePick>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut streamlines: ResMut<Streamlines>,
+) {
+    if !pick.is_changed() || !keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+        return;
+    }
+    if let Some(hit) = pick.clicked {
+        streamlines.add(hit.direction);
+    }
+}
+
+/// Drop the streamlines of the old wind when the wind cubemap is rebuilt or a new planet comes
+pub fn clear_streamlines_on_new_wind(
+    wind_cubemap: Option<Res<WindCubeMap>>,
+    mut planet_spawned_events: MessageReader<PlanetSpawnedEvent>,
+    mut streamlines: ResMut<Streamlines>,
+) {
+    let planet_spawned = planet_spawned_events.read().count() > 0;
+    let wind_changed = wind_cubemap.is_some_and(|wind| wind.is_changed());
+    if (planet_spawned || wind_changed) && !streamlines.seeds.is_empty() {
+        streamlines.clear();
+    }
+}
+
+/// Rebuild the lines when a streamline is added or cleared, or the planet size, the particle
+/// height or the arc length change. The lines fly at the height of the wind particles.
+pub fn sync_streamline_meshes(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    (streamlines, wind_cubemap): (Res<Streamlines>, Option<Res<WindCubeMap>>),
+    (settings, wind_settings): (Res<WindSettings>, Res<WindParticleSettings>),
+    current_planet_data: Res<CurrentPlanetData>,
+    (planet_query, existing_lines): (
+        Query<Entity, With<PlanetEntity>>,
+        Query<Entity, With<StreamlineMesh>>,
+    ),
+    (mut meshes, mut materials, mut planet_assets): PlanetAssetStores,
+    mut commands: Commands,
+) {
+    let settings_changed =
+        SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Terrain, SettingsGroup::Wind]);
+    if !settings_changed && !streamlines.is_changed() {
+        return;
+    }
+
+    for entity in existing_lines.iter() {
+        commands.entity(entity).despawn();
+    }
+    let (Some(wind_cubemap), Some(planet_data)) = (wind_cubemap, current_planet_data.planet_data.as_ref()) else {
+        return;
+    };
+    let Some(planet_entity) = planet_query.iter().next() else {
+        return;
+    };
+
+    let width = planet_data.radius * LINE_WIDTH;
+    for (index, &seed) in streamlines.seeds.iter().enumerate() {
+        let positions: Vec<Vec3> = streamline_through(&wind_cubemap.inner, seed, settings.streamline_arc_length)
+            .into_iter()
+            .map(|direction| direction * wind_settings.target_radius(Some(planet_data), direction))
+            .collect();
+        let [r, g, b] = streamline_color(index);
+        let color = Color::srgb(r, g, b);
+        let material = planet_assets.add_material(&mut materials, StandardMaterial {
+            base_color: color,
+            emissive: color.to_linear() * 0.5,
+            unlit: true,
+            // A flat ribbon, seen from below where it dips behind a ridge
+            cull_mode: None,
+            ..default()
+        });
+        let line = commands
+            .spawn((
+                Mesh3d(planet_assets.add_mesh(&mut meshes, surface_ribbon_mesh(&positions, width))),
+                MeshMaterial3d(material),
+                Transform::default(),
+                StreamlineMesh,
+            ))
+            .id();
+        commands.entity(planet_entity).add_child(line);
+    }
+}
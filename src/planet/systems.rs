@@ -1,185 +1,445 @@
 use crate::camera::components::MainCamera;
+use crate::launch::LaunchOptions;
 use crate::mesh::helpers::arrow_mesh;
 use crate::planet::components::{
-    ArrowEntity, CameraLerp, CameraRotationMode, ContinentView, ContinentViewMesh, OceanEntity,
-    PlanetControls, PlanetEntity, PlateViewMesh, TectonicPlateView,
+    ArrowEntity, CameraLerp, CameraRotationMode, ContinentView, ContinentViewMesh, OceanEntity, OceanMeshSource,
+    PlanetControls, PlanetEntity, PlateViewMesh, SurfaceAnchored, TectonicPlateView, TerrainLods,
 };
 use crate::planet::events::*;
+use crate::planet::gamepad::{GamepadView, STICK_TURN_SPEED, TRIGGER_ZOOM_SPEED};
 use crate::planet::logic;
 use crate::planet::resources::*;
 use crate::planet::biome::systems::BiomeColorState;
-use crate::planet::temperature::systems::TemperatureMesh;
-use crate::planet::ui::systems::ViewTab;
+use crate::planet::crust_age::systems::CrustAgeMesh;
+use crate::planet::precipitation::systems::PrecipitationMesh;
+use crate::planet::temperature::systems::{TemperatureCubeMap, TemperatureMesh};
+use crate::planet::wind::systems::{VerticalAirMesh, WindCubeMap};
+use crate::planet::ui::systems::PointerOverUi;
 use bevy::asset::{Assets, RenderAssetUsages};
 use bevy::color::{Color, LinearRgba};
+use bevy::ecs::system::SystemChangeTick;
 use bevy::input::mouse::{MouseMotion, MouseWheel};
 use bevy::math::{Quat, Vec3};
-use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
 use bevy::pbr::{MeshMaterial3d, StandardMaterial};
 use bevy::prelude::*;
-use ocean::{OceanConfig, OceanMeshBuilder};
-use planetgen::planet::PlanetData;
+use bevy::tasks::AsyncComputeTaskPool;
+use bevy::tasks::futures::check_ready;
+use bevy::window::PrimaryWindow;
+use ocean::{DepthAlpha, OceanConfig, OceanMeshBuilder, OceanOutput};
+use planetgen::config::HillshadeConfig;
+use planetgen::prelude::{
+    ColorVariation, MeshData, Palette, PlanetData, SnowLine, TemperatureCubeMap as PlanetgenTemperatureCubeMap, ViewMode,
+    WorldType, calculate_plate_arrows, generate_seed8, oblate_radius, validate_mesh,
+};
 
 pub fn spawn_planet_on_event(
     mut commands: Commands,
     mut camera_events: MessageWriter<SetCameraPositionEvent>,
     mut planet_spawned_events: MessageWriter<PlanetSpawnedEvent>,
-    mut temperature_tab_events: MessageWriter<TemperatureTabActiveEvent>,
+    mut set_active_view: MessageWriter<SetActiveView>,
     mut events: MessageReader<GeneratePlanetEvent>,
     mut current_planet_data: ResMut<CurrentPlanetData>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    settings: Res<PlanetGenerationSettings>,
-    view_tab: Res<ViewTab>,
+    (mut pending_generation, mut kept_plate_layout): (ResMut<PendingPlanetGeneration>, ResMut<KeptPlateLayout>),
+    (mut meshes, mut materials, mut planet_assets): PlanetAssetStores,
+    (terrain_settings, climate_settings, wind_settings, display_settings): (
+        Res<TerrainSettings>,
+        Res<ClimateSettings>,
+        Res<WindSettings>,
+        Res<DisplaySettings>,
+    ),
+    overlay_state: Res<OverlayState>,
+    temperature_cubemap: Option<Res<TemperatureCubeMap>>,
     mut biome_state: ResMut<BiomeColorState>,
     planet_entities: Query<Entity, With<PlanetEntity>>,
     planet_controls_query: Query<&PlanetControls, With<PlanetEntity>>,
     temperature_meshes: Query<Entity, With<TemperatureMesh>>,
 ) {
-    for _ in events.read() {
-        // Capture current rotation before despawning
-        let current_rotation = planet_controls_query
-            .iter()
-            .next()
-            .map(|controls| controls.rotation)
-            .unwrap_or(Quat::IDENTITY);
-
-        // Despawn temperature meshes first to avoid stale references
-        for entity in temperature_meshes.iter() {
-            commands.entity(entity).despawn();
-        }
+    // Requests of the same frame all use the same settings, they make one planet. Spawning one
+    // per request would leave all but the last alive, their despawns only see older planets.
+    if events.read().count() == 0 {
+        return;
+    }
 
-        // Despawn existing planet entities before generating new ones (children will be cleaned up automatically)
-        for entity in planet_entities.iter() {
-            commands.entity(entity).despawn();
-        }
+    // Capture current rotation and zoom before despawning
+    let previous_controls = planet_controls_query.iter().next();
+    let current_rotation = previous_controls.map_or(Quat::IDENTITY, |controls| controls.rotation);
+    let previous_view = previous_controls
+        .zip(current_planet_data.planet_data.as_ref())
+        .map(|(controls, planet)| (controls.zoom, planet.radius));
 
-        let planet_data = logic::generate_planet_data(&settings);
+    // Despawn temperature meshes first to avoid stale references
+    for entity in temperature_meshes.iter() {
+        commands.entity(entity).despawn();
+    }
 
-        // PRESENTATION: Generate BOTH meshes (continent view and plate view)
-        let continent_mesh = build_stitched_planet_mesh(
-            &planet_data,
-            false,
-            settings.snow_threshold,
-            settings.continent_threshold,
-        );
-        let plate_mesh = build_stitched_planet_mesh(
-            &planet_data,
-            true,
-            settings.snow_threshold,
-            settings.continent_threshold,
+    // Despawn existing planet entities before generating new ones (children will be cleaned up automatically)
+    for entity in planet_entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    // Their assets go once they are gone, see `release_replaced_planet_assets`
+    planet_assets.replace_planet();
+
+    // Show a coarse preview right away and generate the full-res planet in the background.
+    // Replacing the task drops (and cancels) any generation still running for an older request.
+    // A kept plate layout is only for this generation, the next one makes its own plates again
+    let plate_layout = kept_plate_layout.0.take();
+    let planet_data = if terrain_settings.preview_generation {
+        let task_settings = terrain_settings.clone();
+        let task_layout = plate_layout.clone();
+        pending_generation.task = Some(
+            AsyncComputeTaskPool::get()
+                .spawn(async move { logic::generate_planet_data(&task_settings, task_layout.as_ref()) }),
         );
+        logic::generate_preview_planet_data(&terrain_settings, plate_layout.as_ref())
+    } else {
+        pending_generation.task = None;
+        logic::generate_planet_data(&terrain_settings, plate_layout.as_ref())
+    };
 
-        let continent_mesh_handle = meshes.add(continent_mesh);
-        let plate_mesh_handle = meshes.add(plate_mesh);
+    // PRESENTATION: Generate BOTH meshes (continent view and plate view) at every LOD
+    let temperature = temperature_cubemap.as_ref().map(|cubemap| &cubemap.inner);
+    let color_settings = (&*terrain_settings, &*climate_settings, &*display_settings);
+    let continent_lods = build_terrain_lods(
+        &planet_data,
+        ViewMode::Continents,
+        color_settings,
+        temperature,
+        (&mut meshes, &mut planet_assets),
+    );
+    let plate_lods = build_terrain_lods(
+        &planet_data,
+        display_settings.plate_view_mode(),
+        color_settings,
+        None,
+        (&mut meshes, &mut planet_assets),
+    );
 
-        let planet_material = materials.add(StandardMaterial {
-            base_color: Color::WHITE,
-            ..default()
-        });
+    let planet_material = planet_assets.add_material(&mut materials, StandardMaterial {
+        base_color: Color::WHITE,
+        ..default()
+    });
 
-        let expected_zoom = settings.radius * 3.5;
+    let radius = terrain_settings.radius;
+    let (zoom, move_camera) = logic::regenerated_zoom(previous_view, radius, display_settings.keep_view);
+    let (min_zoom, max_zoom) = logic::zoom_limits(radius);
+    let lod_level = logic::terrain_lod_level(0, zoom, radius);
 
-        // Spawn parent planet entity with controls
-        let planet_entity = commands
-            .spawn((
-                Transform::from_xyz(0.0, 0.0, 0.0).with_rotation(current_rotation),
+    // Spawn parent planet entity with controls
+    let planet_entity = commands
+        .spawn((
+            Transform::from_xyz(0.0, 0.0, 0.0).with_rotation(current_rotation),
+            GlobalTransform::default(),
+            Visibility::default(),
+            InheritedVisibility::default(),
+            ViewVisibility::default(),
+            PlanetEntity,
+            PlanetControls {
+                rotation: current_rotation,
+                target_rotation: None,
+                zoom,
+                min_zoom,
+                max_zoom,
+                inertia: logic::SpinInertia::default(),
+            },
+        ))
+        .with_children(|parent| {
+            // Determine visibility based on current view tab
+            let is_tectonic_view = overlay_state.active == ViewKind::Tectonic;
+            let is_continent_or_wind_view = matches!(overlay_state.active, ViewKind::Continent | ViewKind::Wind);
+
+            // Continent view mesh (visible only in Continent or Wind view)
+            parent.spawn((
+                Mesh3d(continent_lods[lod_level].clone()),
+                TerrainLods {
+                    meshes: continent_lods,
+                    level: lod_level,
+                },
+                MeshMaterial3d(planet_material.clone()),
+                Transform::default(),
                 GlobalTransform::default(),
-                Visibility::default(),
-                InheritedVisibility::default(),
-                ViewVisibility::default(),
-                PlanetEntity,
-                PlanetControls {
-                    rotation: current_rotation,
-                    zoom: expected_zoom,
-                    min_zoom: settings.radius * 1.5,
-                    max_zoom: settings.radius * 3.5,
+                if is_continent_or_wind_view {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
                 },
-            ))
-            .with_children(|parent| {
-                // Determine visibility based on current view tab
-                let is_tectonic_view = *view_tab == ViewTab::Tectonic;
-                let is_continent_or_wind_view = *view_tab == ViewTab::Continent || *view_tab == ViewTab::Wind;
-
-                // Continent view mesh (visible only in Continent or Wind view)
-                parent.spawn((
-                    Mesh3d(continent_mesh_handle),
-                    MeshMaterial3d(planet_material.clone()),
-                    Transform::default(),
-                    GlobalTransform::default(),
-                    if is_continent_or_wind_view {
-                        Visibility::Visible
-                    } else {
-                        Visibility::Hidden
-                    },
-                    ContinentViewMesh,
-                    ContinentView, // Marker component
-                ));
-
-                // Plate view mesh (visible only in Tectonic view)
-                parent.spawn((
-                    Mesh3d(plate_mesh_handle),
-                    MeshMaterial3d(planet_material.clone()),
-                    Transform::default(),
-                    GlobalTransform::default(),
-                    if is_tectonic_view {
-                        Visibility::Visible
-                    } else {
-                        Visibility::Hidden
-                    },
-                    PlateViewMesh,
-                    TectonicPlateView, // Marker component
-                ));
-            })
-            .id();
+                ContinentViewMesh,
+                ContinentView, // Marker component
+            ));
+
+            // Plate view mesh (visible only in Tectonic view)
+            parent.spawn((
+                Mesh3d(plate_lods[lod_level].clone()),
+                TerrainLods {
+                    meshes: plate_lods,
+                    level: lod_level,
+                },
+                MeshMaterial3d(planet_material.clone()),
+                Transform::default(),
+                GlobalTransform::default(),
+                if is_tectonic_view {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
+                },
+                PlateViewMesh,
+                TectonicPlateView, // Marker component
+            ));
+        })
+        .id();
 
+    // A kept view leaves the camera where it is
+    if move_camera {
         camera_events.write(SetCameraPositionEvent {
-            position: Vec3::new(0.0, 0.0, expected_zoom),
+            position: Vec3::new(0.0, 0.0, zoom),
         });
+    }
 
-        if settings.show_arrows {
-            spawn_plate_direction_arrows(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                &planet_data,
-                planet_entity,
-            );
-        }
+    if display_settings.show_arrows {
+        spawn_plate_direction_arrows(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut planet_assets,
+            &planet_data,
+            planet_entity,
+        );
+    }
 
-        // Spawn ocean sphere at sea level (only visible in continent/temperature view mode)
-        if settings.show_ocean {
-            let is_tectonic_view = *view_tab == ViewTab::Tectonic;
-            spawn_ocean(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                &settings,
-                planet_entity,
-                is_tectonic_view, // Hide ocean only in tectonic view
-            );
+    // Spawn ocean sphere at sea level (only visible in continent/temperature view mode).
+    // Barren worlds have no water, the climate overlays still work on the bare rock.
+    // The wind map still belongs to the previous planet, the ocean is rebuilt once it's updated
+    if display_settings.show_ocean
+        && terrain_settings.world_type == WorldType::Terran
+        && let Some(ocean) = build_ocean((&display_settings, &wind_settings), &planet_data, None)
+    {
+        // The crust age overlay carries its own copy of the ocean surface
+        let is_tectonic_view = matches!(overlay_state.active, ViewKind::Tectonic | ViewKind::CrustAge);
+        spawn_ocean(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut planet_assets,
+            ocean,
+            planet_entity,
+            is_tectonic_view, // Hide ocean only in tectonic view
+        );
+    }
+
+    // Store planet data after using it for generation
+    current_planet_data.replace(planet_data);
+
+    // Reset biome color state so deferred coloring system will recolor the new mesh
+    biome_state.applied = false;
+
+    // Emit event to notify that planet was spawned
+    planet_spawned_events.write(PlanetSpawnedEvent);
+
+    // If we're on the temperature tab, announce it again so the temperature meshes are
+    // built for the new planet
+    if overlay_state.active == ViewKind::Temperature {
+        set_active_view.write(SetActiveView(ViewKind::Temperature));
+    }
+}
+
+/// Swap the preview meshes for the full-resolution planet once background generation finishes.
+/// Everything is replaced in the same frame so the planet never disappears.
+pub fn swap_in_full_resolution_planet(
+    mut commands: Commands,
+    mut pending_generation: ResMut<PendingPlanetGeneration>,
+    mut planet_spawned_events: MessageWriter<PlanetSpawnedEvent>,
+    mut set_active_view: MessageWriter<SetActiveView>,
+    mut current_planet_data: ResMut<CurrentPlanetData>,
+    (mut meshes, mut materials, mut planet_assets): PlanetAssetStores,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
+    overlay_state: Res<OverlayState>,
+    temperature_cubemap: Option<Res<TemperatureCubeMap>>,
+    mut biome_state: ResMut<BiomeColorState>,
+    planet_entities: Query<Entity, With<PlanetEntity>>,
+    mut terrain: Query<(&mut Mesh3d, &mut TerrainLods, Has<PlateViewMesh>)>,
+    arrow_entities: Query<Entity, With<ArrowEntity>>,
+    temperature_meshes: Query<Entity, With<TemperatureMesh>>,
+) {
+    let Some(task) = pending_generation.task.as_mut() else {
+        return;
+    };
+    let Some(mut planet_data) = check_ready(task) else {
+        return;
+    };
+    pending_generation.task = None;
+
+    // The radius may have been rescaled while the planet was generating
+    if planet_data.radius != terrain_settings.radius {
+        planet_data.rescale(terrain_settings.radius, terrain_settings.scale_heights_with_radius);
+    }
+
+    let Some(planet_entity) = planet_entities.iter().next() else {
+        return;
+    };
+
+    info!("Full-resolution planet ready, replacing preview");
+
+    // The LOD in use stays, only its meshes are replaced
+    let temperature = temperature_cubemap.as_ref().map(|cubemap| &cubemap.inner);
+    let color_settings = (&*terrain_settings, &*climate_settings, &*display_settings);
+    let continent_lods = build_terrain_lods(
+        &planet_data,
+        ViewMode::Continents,
+        color_settings,
+        temperature,
+        (&mut meshes, &mut planet_assets),
+    );
+    let plate_lods = build_terrain_lods(
+        &planet_data,
+        display_settings.plate_view_mode(),
+        color_settings,
+        None,
+        (&mut meshes, &mut planet_assets),
+    );
+
+    for (mut mesh, mut lods, is_plate_view) in terrain.iter_mut() {
+        lods.meshes = if is_plate_view { plate_lods.clone() } else { continent_lods.clone() };
+        mesh.0 = lods.meshes[lods.level].clone();
+    }
+
+    // Arrow positions depend on the plate map resolution
+    if !arrow_entities.is_empty() {
+        for entity in arrow_entities.iter() {
+            commands.entity(entity).despawn();
         }
+        spawn_plate_direction_arrows(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut planet_assets,
+            &planet_data,
+            planet_entity,
+        );
+    }
 
-        // Store planet data after using it for generation
-        current_planet_data.planet_data = Some(planet_data);
+    // Temperature meshes are copies of the preview mesh, rebuild them from the new one
+    for entity in temperature_meshes.iter() {
+        commands.entity(entity).despawn();
+    }
 
-        // Reset biome color state so deferred coloring system will recolor the new mesh
-        biome_state.applied = false;
+    current_planet_data.replace(planet_data);
+    biome_state.applied = false;
 
-        // Emit event to notify that planet was spawned
-        planet_spawned_events.write(PlanetSpawnedEvent);
+    planet_spawned_events.write(PlanetSpawnedEvent);
 
-        // If we're on the temperature tab, trigger temperature mesh generation
-        if *view_tab == ViewTab::Temperature {
-            temperature_tab_events.write(TemperatureTabActiveEvent { active: true });
+    if overlay_state.active == ViewKind::Temperature {
+        set_active_view.write(SetActiveView(ViewKind::Temperature));
+    }
+}
+
+/// Resize the current planet in place: terrain and overlay meshes, ocean, arrows and camera limits.
+/// Heightmaps and the plate map are kept, so the terrain looks the same at the new size.
+pub fn rescale_planet_on_event(
+    mut events: MessageReader<RescalePlanetEvent>,
+    mut camera_events: MessageWriter<SetCameraPositionEvent>,
+    mut current_planet_data: ResMut<CurrentPlanetData>,
+    (mut meshes, mut planet_assets): (ResMut<Assets<Mesh>>, ResMut<PlanetAssets>),
+    (terrain_settings, wind_settings, display_settings): (Res<TerrainSettings>, Res<WindSettings>, Res<DisplaySettings>),
+    surface_meshes: Query<
+        &Mesh3d,
+        (
+            Or<(
+                With<ContinentViewMesh>,
+                With<PlateViewMesh>,
+                With<TemperatureMesh>,
+                With<PrecipitationMesh>,
+                With<VerticalAirMesh>,
+                With<CrustAgeMesh>,
+            )>,
+            Without<OceanEntity>,
+        ),
+    >,
+    terrain_lods: Query<&TerrainLods>,
+    mut oceans: Query<(&mut Mesh3d, &mut OceanMeshSource), With<OceanEntity>>,
+    wind_cubemap: Option<Res<WindCubeMap>>,
+    mut arrows: Query<&mut Transform, With<ArrowEntity>>,
+    mut planet_controls: Query<&mut PlanetControls, With<PlanetEntity>>,
+) {
+    // Only the latest radius matters while the slider is being dragged
+    let Some(new_radius) = events.read().last().map(|event| event.radius) else {
+        return;
+    };
+    let generation = Some(current_planet_data.generation);
+    let Some(planet_data) = current_planet_data.planet_data.as_mut() else {
+        return;
+    };
+    let old_radius = planet_data.radius;
+    if new_radius <= 0.0 || new_radius == old_radius {
+        return;
+    }
+    let scale_heights = terrain_settings.scale_heights_with_radius;
+
+    // Overlay meshes are copies of the terrain mesh, some may share a mesh asset.
+    // Terrain LODs that aren't shown right now are rescaled too.
+    let mut rescaled = std::collections::HashSet::new();
+    let lod_handles = terrain_lods.iter().flat_map(|lods| lods.meshes.iter());
+    for mesh_handle in surface_meshes.iter().map(|mesh| &mesh.0).chain(lod_handles) {
+        if !rescaled.insert(mesh_handle.id()) {
+            continue;
         }
+        let Some(mesh) = meshes.get_mut(mesh_handle) else {
+            continue;
+        };
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            for position in positions.iter_mut() {
+                *position = logic::rescale_surface_position(
+                    Vec3::from(*position),
+                    old_radius,
+                    new_radius,
+                    planet_data.oblateness,
+                    scale_heights,
+                )
+                .to_array();
+            }
+        }
+    }
+
+    planet_data.rescale(new_radius, scale_heights);
+
+    // The ocean sits at the planet's sea level above the radius, rebuild it at the new radius
+    if let Some((ocean, config)) =
+        build_ocean((&display_settings, &wind_settings), planet_data, wind_cubemap.as_deref())
+    {
+        let ocean_mesh_handle = planet_assets.add_mesh(&mut meshes, ocean.mesh);
+        for (mut ocean_mesh, mut source) in oceans.iter_mut() {
+            ocean_mesh.0 = ocean_mesh_handle.clone();
+            *source = OceanMeshSource { config, generation };
+        }
+    }
+
+    // Arrows float just above the surface and are sized relative to the radius
+    let scale = new_radius / old_radius;
+    for mut transform in arrows.iter_mut() {
+        transform.translation = logic::rescale_surface_position(
+            transform.translation,
+            old_radius,
+            new_radius,
+            planet_data.oblateness,
+            scale_heights,
+        );
+        transform.scale *= scale;
+    }
+
+    for mut controls in planet_controls.iter_mut() {
+        (controls.min_zoom, controls.max_zoom) = logic::zoom_limits(new_radius);
+        controls.zoom = (controls.zoom * scale).clamp(controls.min_zoom, controls.max_zoom);
+
+        camera_events.write(SetCameraPositionEvent {
+            position: Vec3::new(0.0, 0.0, controls.zoom),
+        });
     }
 }
 
 pub fn handle_arrow_toggle(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    (mut meshes, mut materials, mut planet_assets): PlanetAssetStores,
     mut events: MessageReader<ToggleArrowsEvent>,
     arrow_entities: Query<Entity, With<ArrowEntity>>,
     planet_entities: Query<Entity, (With<PlanetEntity>, With<PlanetControls>)>,
@@ -195,6 +455,7 @@ pub fn handle_arrow_toggle(
                             &mut commands,
                             &mut meshes,
                             &mut materials,
+                            &mut planet_assets,
                             planet_data,
                             planet_entity,
                         );
@@ -210,25 +471,80 @@ pub fn handle_arrow_toggle(
     }
 }
 
+/// Terrain meshes of one view for every step in TERRAIN_LOD_STEPS, finest first. The snow line
+/// follows `temperature` when there is one.
+fn build_terrain_lods(
+    planet: &PlanetData,
+    view_mode: ViewMode,
+    (terrain_settings, climate_settings, display_settings): SurfaceColorSettings,
+    temperature: Option<&PlanetgenTemperatureCubeMap>,
+    (meshes, planet_assets): (&mut Assets<Mesh>, &mut PlanetAssets),
+) -> Vec<Handle<Mesh>> {
+    logic::TERRAIN_LOD_STEPS
+        .iter()
+        .map(|&step| {
+            let mesh = build_stitched_planet_mesh(
+                planet,
+                view_mode,
+                terrain_settings.snow_line(climate_settings, temperature),
+                (&display_settings.hillshade(), &display_settings.color_variation(terrain_settings.seed)),
+                display_settings.palette,
+                step,
+            );
+            planet_assets.add_mesh(meshes, mesh)
+        })
+        .collect()
+}
+
+/// Overwrite the vertex positions, normals and colors of existing terrain LOD meshes after the
+/// planet changed in place. The grid is the same, so the indices and UVs are kept.
+pub(crate) fn refresh_terrain_lods(
+    planet: &PlanetData,
+    view_mode: ViewMode,
+    (terrain_settings, climate_settings, display_settings): SurfaceColorSettings,
+    temperature: Option<&PlanetgenTemperatureCubeMap>,
+    lods: &TerrainLods,
+    meshes: &mut Assets<Mesh>,
+) {
+    for (&step, mesh_handle) in logic::TERRAIN_LOD_STEPS.iter().zip(&lods.meshes) {
+        let Some(mesh) = meshes.get_mut(mesh_handle) else {
+            continue;
+        };
+        let mesh_data = MeshData::from_planet_lod(
+            planet,
+            view_mode,
+            terrain_settings.snow_line(climate_settings, temperature),
+            (&display_settings.hillshade(), &display_settings.color_variation(terrain_settings.seed)),
+            display_settings.palette,
+            step,
+            logic::TERRAIN_MESH_UVS,
+        );
+        warn_on_mesh_defects(&mesh_data, view_mode, step);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, mesh_data.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, mesh_data.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, mesh_data.colors);
+    }
+}
+
 fn build_stitched_planet_mesh(
     planet: &PlanetData,
-    view_mode_plates: bool,
-    snow_threshold: f32,
-    continent_threshold: f32,
+    view_mode: ViewMode,
+    snow: SnowLine,
+    shading: (&HillshadeConfig, &ColorVariation),
+    palette: Palette,
+    step: usize,
 ) -> Mesh {
     // Use planetgen's pure business logic to generate mesh data
-    let view_mode = if view_mode_plates {
-        planetgen::mesh_data::ViewMode::Plates
-    } else {
-        planetgen::mesh_data::ViewMode::Continents
-    };
-
-    let mesh_data = planetgen::mesh_data::MeshData::from_planet(
+    let mesh_data = MeshData::from_planet_lod(
         planet,
         view_mode,
-        snow_threshold,
-        continent_threshold,
+        snow,
+        shading,
+        palette,
+        step,
+        logic::TERRAIN_MESH_UVS,
     );
+    warn_on_mesh_defects(&mesh_data, view_mode, step);
 
     // Convert to Bevy mesh (thin presentation layer)
     let mut mesh = Mesh::new(
@@ -238,40 +554,63 @@ fn build_stitched_planet_mesh(
     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, mesh_data.positions);
     mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, mesh_data.normals);
     mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, mesh_data.colors);
+    if let Some(uvs) = mesh_data.uvs {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    }
     mesh.insert_indices(Indices::U32(mesh_data.indices));
     mesh
 }
 
+/// Debug builds check every terrain mesh for collapsed, doubled or flipped triangles
+fn warn_on_mesh_defects(mesh_data: &MeshData, view_mode: ViewMode, step: usize) {
+    if cfg!(debug_assertions) {
+        let report = validate_mesh(mesh_data);
+        if !report.is_clean() {
+            warn!("{view_mode:?} terrain mesh at LOD step {step} has defects, {report}");
+        }
+    }
+}
+
+/// How far the arrow mesh reaches below its center, as a share of its scale
+const ARROW_HEAD_DEPTH: f32 = 0.2;
+
 fn spawn_plate_direction_arrows(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
+    planet_assets: &mut PlanetAssets,
     planet: &PlanetData,
     planet_entity: Entity,
 ) {
     // Use planetgen's pure business logic to calculate arrow data
-    let arrow_data = planetgen::arrows::calculate_plate_arrows(planet);
+    let arrow_data = calculate_plate_arrows(planet);
 
     // Prepare Bevy resources (presentation layer)
     let arrow_mesh = arrow_mesh();
-    let arrow_mesh_handle = meshes.add(arrow_mesh);
-    let arrow_material = materials.add(StandardMaterial {
+    let arrow_mesh_handle = planet_assets.add_mesh(meshes, arrow_mesh);
+    let arrow_material = planet_assets.add_material(materials, StandardMaterial {
         base_color: Color::srgb(0.3, 0.8, 0.4),
         emissive: LinearRgba::BLUE,
         ..default()
     });
 
-    // Spawn arrow entities from calculated data
+    // Spawn arrow entities from calculated data, lifted off the ground far enough that the
+    // lower half of the arrow head clears it
+    let lift = logic::static_layer_offset(logic::SurfaceLayer::PlateArrows, planet.radius);
     for arrow in arrow_data {
+        let position = arrow.position + arrow.position.normalize() * (ARROW_HEAD_DEPTH * arrow.scale + lift);
         let arrow_entity = commands
             .spawn((
                 Mesh3d(arrow_mesh_handle.clone()),
                 MeshMaterial3d(arrow_material.clone()),
-                Transform::from_translation(arrow.position)
+                Transform::from_translation(position)
                     .with_rotation(arrow.rotation)
                     .with_scale(Vec3::splat(arrow.scale)),
                 GlobalTransform::default(),
                 ArrowEntity,
+                SurfaceAnchored {
+                    direction: position.normalize(),
+                },
             ))
             .id();
 
@@ -280,26 +619,187 @@ fn spawn_plate_direction_arrows(
     }
 }
 
+/// Ocean of the planet, its calm surface lies at the planet's sea level
+pub fn ocean_config(
+    (display_settings, wind_settings): (&DisplaySettings, &WindSettings),
+    planet_data: &PlanetData,
+) -> OceanConfig {
+    OceanConfig {
+        sea_level: planet_data.radius + planet_data.sea_level(),
+        // Same drop towards the poles as the terrain surface
+        polar_flattening: planet_data.radius * planet_data.oblateness,
+        grid_size: logic::ocean_grid_size(planet_data.face_grid_size),
+        ocean_color: Color::srgba(0.02, 0.15, 0.35, 0.9),
+        shallow_color: Color::srgba(0.1, 0.45, 0.55, 0.9),
+        deep_color: Color::srgba(0.02, 0.1, 0.3, 0.9),
+        max_depth_for_tint: 1.0,
+        foam_color: Color::srgb_from_array(display_settings.ocean_foam_color),
+        foam_width: display_settings.ocean_foam_width,
+        wave_amplitude: 0.04,
+        wave_frequency: 0.5,
+        wave_speed: 1.0,
+        // Waves reach full height at the configured trade wind speed, westerlies go above it
+        full_wave_wind_speed: wind_settings.wind_zonal_speed,
+        // The continental shelf shows through, trenches stay dark
+        depth_alpha: Some(DepthAlpha {
+            near_depth: 0.0,
+            far_depth: 1.5,
+            min_alpha: 0.35,
+            max_alpha: 0.95,
+        }),
+    }
+}
+
+/// Ocean mesh at sea level, tinted by the depth of the terrain below it.
+/// With a wind map the waves follow the wind, otherwise they're the same everywhere.
+/// None if the settings make an ocean that can't be built, the caller keeps the one it has.
+fn build_ocean(
+    settings: (&DisplaySettings, &WindSettings),
+    planet_data: &PlanetData,
+    wind_cubemap: Option<&WindCubeMap>,
+) -> Option<(OceanOutput, OceanConfig)> {
+    let config = ocean_config(settings, planet_data);
+    ocean_builder(config, planet_data, wind_cubemap)
+        .build()
+        .inspect_err(|error| warn!("Not building the ocean: {error}"))
+        .ok()
+        .map(|ocean| (ocean, config))
+}
+
+/// Builder of the ocean over `planet_data` with the waves at rest, see `build_ocean`
+fn ocean_builder<'a>(
+    config: OceanConfig,
+    planet_data: &'a PlanetData,
+    wind_cubemap: Option<&'a WindCubeMap>,
+) -> OceanMeshBuilder<'a> {
+    let mut builder = OceanMeshBuilder::new(config)
+        .with_time(0.0)
+        .with_height_sampler(Box::new(|position| {
+            planet_data.surface_radius(position) + planet_data.sample_height(position)
+        }));
+    if let Some(wind_cubemap) = wind_cubemap {
+        builder = builder.with_wind_sampler(Box::new(|position| wind_cubemap.sample(position)));
+    }
+    builder
+}
+
+/// Keep `WaterLevel` on the sea level of the current planet, a new planet or rescaling it
+/// with the heights moves it
+pub fn sync_water_level(current_planet_data: Res<CurrentPlanetData>, mut water_level: ResMut<WaterLevel>) {
+    if !current_planet_data.is_changed() {
+        return;
+    }
+    let sea_level = current_planet_data.planet_data.as_ref().map(PlanetData::sea_level);
+    water_level.set_if_neq(WaterLevel(sea_level));
+}
+
+/// Compare one settings resource against the last applied snapshot and announce which groups
+/// changed. This is the only place that diffs settings, everything else reacts to `SettingsDiff`.
+pub fn emit_settings_diff<T: PlanetSettings>(
+    settings: Res<T>,
+    mut applied: Local<Option<T>>,
+    mut settings_diffs: MessageWriter<SettingsDiff>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if let Some(previous) = applied.as_ref() {
+        let changed = settings.changed_groups(previous);
+        if !changed.is_empty() {
+            settings_diffs.write(SettingsDiff { changed });
+        }
+    }
+    *applied = Some(settings.clone());
+}
+
+/// Update the ocean mesh when the ocean settings or the wind change, the terrain stays as it is.
+/// New waves or wind move the vertices of the mesh there is, anything else builds a new one.
+/// The wind map is rebuilt for every new planet, which also refreshes the depth tint.
+pub fn rebuild_ocean_on_settings_change(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    (display_settings, wind_settings): (Res<DisplaySettings>, Res<WindSettings>),
+    current_planet_data: Res<CurrentPlanetData>,
+    wind_cubemap: Option<Res<WindCubeMap>>,
+    (mut meshes, mut planet_assets): (ResMut<Assets<Mesh>>, ResMut<PlanetAssets>),
+    mut oceans: Query<(&mut Mesh3d, &mut OceanMeshSource), With<OceanEntity>>,
+) {
+    // Wave height is relative to the wind speed setting
+    let settings_changed =
+        SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Ocean, SettingsGroup::Wind]);
+    let wind_changed = wind_cubemap.as_ref().is_some_and(|wind| wind.is_changed());
+    if !settings_changed && !wind_changed {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+
+    let config = ocean_config((&display_settings, &wind_settings), planet_data);
+    let generation = Some(current_planet_data.generation);
+    for (mut ocean_mesh, mut source) in oceans.iter_mut() {
+        let mut builder = ocean_builder(source.config, planet_data, wind_cubemap.as_deref());
+        builder.set_config(config);
+        if source.generation == generation
+            && !builder.needs_rebuild()
+            && let Some(mesh) = meshes.get_mut(&ocean_mesh.0)
+            && builder.update_mesh(mesh, 0.0).is_ok()
+        {
+            source.config = config;
+            continue;
+        }
+
+        match builder.rebuild() {
+            Ok(ocean) => {
+                ocean_mesh.0 = planet_assets.add_mesh(&mut meshes, ocean.mesh);
+                *source = OceanMeshSource { config, generation };
+            }
+            Err(error) => warn!("Not building the ocean: {error}"),
+        }
+    }
+}
+
+/// Rebuild the plate view meshes when their coloring or the palette changes, the LOD in use stays
+pub fn rebuild_plate_view_on_settings_change(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
+    current_planet_data: Res<CurrentPlanetData>,
+    (mut meshes, mut planet_assets): (ResMut<Assets<Mesh>>, ResMut<PlanetAssets>),
+    mut plate_view: Query<(&mut Mesh3d, &mut TerrainLods), With<PlateViewMesh>>,
+) {
+    if !SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::PlateView, SettingsGroup::Palette]) {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+
+    let plate_lods = build_terrain_lods(
+        planet_data,
+        display_settings.plate_view_mode(),
+        (&terrain_settings, &climate_settings, &display_settings),
+        None,
+        (&mut meshes, &mut planet_assets),
+    );
+    for (mut mesh, mut lods) in plate_view.iter_mut() {
+        lods.meshes = plate_lods.clone();
+        mesh.0 = lods.meshes[lods.level].clone();
+    }
+}
+
 fn spawn_ocean(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
-    settings: &PlanetGenerationSettings,
+    planet_assets: &mut PlanetAssets,
+    (ocean, config): (OceanOutput, OceanConfig),
     planet_entity: Entity,
     view_mode_plates: bool,
 ) {
-    let ocean_config = OceanConfig {
-        sea_level: settings.radius + settings.continent_threshold, // Raise ocean to hide flat coastal areas
-        grid_size: 256,
-        ocean_color: Color::srgba(0.02, 0.15, 0.35, 0.9),
-    };
-
-    let ocean = OceanMeshBuilder::new(ocean_config).with_time(0.0).build();
-
     let ocean_entity = commands
         .spawn((
-            Mesh3d(meshes.add(ocean.mesh)),
-            MeshMaterial3d(materials.add(ocean.material)),
+            Mesh3d(planet_assets.add_mesh(meshes, ocean.mesh)),
+            MeshMaterial3d(planet_assets.add_material(materials, ocean.material)),
             Transform::default(),
             GlobalTransform::default(),
             if view_mode_plates {
@@ -308,6 +808,11 @@ fn spawn_ocean(
                 Visibility::Visible
             },
             OceanEntity,
+            // Built before the planet is stored
+            OceanMeshSource {
+                config,
+                generation: None,
+            },
         ))
         .id();
 
@@ -315,107 +820,333 @@ fn spawn_ocean(
     commands.entity(planet_entity).add_child(ocean_entity);
 }
 
+/// Remove the assets of a replaced planet, its entities were despawned with their handles
+pub fn release_replaced_planet_assets(
+    mut planet_assets: ResMut<PlanetAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !planet_assets.has_replaced() {
+        return;
+    }
+    let released = planet_assets.release_replaced(&mut meshes, &mut materials, &mut images);
+    debug!(
+        "Released {} meshes, {} materials and {} images of the replaced planet",
+        released.meshes, released.materials, released.images
+    );
+}
+
+/// The planet systems expect a single planet. Should another one ever be left over, the newest
+/// stays and the older ones are despawned with their children, with a warning the first time.
+pub fn despawn_stale_planets(
+    mut commands: Commands,
+    planets: Query<(Entity, Ref<PlanetEntity>)>,
+    change_tick: SystemChangeTick,
+    mut warned: Local<bool>,
+) {
+    if planets.iter().count() < 2 {
+        return;
+    }
+    let this_run = change_tick.this_run();
+    let Some((newest, _)) = planets.iter().reduce(|newest, planet| {
+        if planet.1.added().is_newer_than(newest.1.added(), this_run) {
+            planet
+        } else {
+            newest
+        }
+    }) else {
+        return;
+    };
+
+    if !*warned {
+        warn!("{} planets alive, keeping the newest", planets.iter().count());
+        *warned = true;
+    }
+    for (entity, _) in planets.iter().filter(|(entity, _)| *entity != newest) {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Drag turns the view, a released drag keeps spinning if `rotation_inertia` is on, the wheel
+/// zooms towards the point under the cursor. The gamepad right stick turns and the triggers
+/// zoom towards the middle of the view.
 pub fn planet_control(
     mouse_input: Res<ButtonInput<MouseButton>>,
     mut mouse_motion: MessageReader<MouseMotion>,
     mut mouse_wheel: MessageReader<MouseWheel>,
+    gamepad: Res<GamepadView>,
     camera_mode: Res<CameraRotationMode>,
+    pointer_over_ui: Res<PointerOverUi>,
+    (terrain_settings, display_settings): (Res<TerrainSettings>, Res<DisplaySettings>),
+    // Real time, a paused or slowed virtual clock must not stop a spin the user started
+    real_time: Res<Time<Real>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
     mut planet_query: Query<
         (&mut Transform, &mut PlanetControls),
         (With<PlanetEntity>, With<PlanetControls>),
     >,
-    mut camera_query: Query<(&mut Transform, &mut CameraLerp), (With<Camera3d>, Without<PlanetEntity>)>,
-    windows: Query<&Window>,
+    mut camera_query: Query<
+        (&Camera, &mut Transform, &mut CameraLerp),
+        (With<Camera3d>, Without<PlanetEntity>),
+    >,
+    mut view_drag: Local<logic::ViewDrag>,
 ) {
-    if let Ok((mut planet_transform, mut controls)) = planet_query.single_mut() {
-        if let Ok((mut camera_transform, mut camera_lerp)) = camera_query.single_mut() {
-            let window = windows.single().unwrap();
-            let cursor_position = window.cursor_position();
+    let Ok((mut planet_transform, mut controls)) = planet_query.single_mut() else {
+        return;
+    };
+    let Ok((camera, mut camera_transform, mut camera_lerp)) = camera_query.single_mut() else {
+        return;
+    };
 
-            // Check if cursor is over UI (right 25% of screen)
-            let is_over_ui = if let Some(cursor_pos) = cursor_position {
-                cursor_pos.x > window.width() * 0.75
-            } else {
-                false
-            };
-
-            // Handle mouse dragging - only if not over UI
-            if mouse_input.pressed(MouseButton::Left) && !is_over_ui {
-                for motion in mouse_motion.read() {
-                    let sensitivity = 0.002 * (controls.zoom / 60.0);
-
-                    if camera_mode.rotate_camera {
-                        // Rotate camera around the planet
-                        // Negate for correct direction (drag right = camera moves right = planet appears to rotate left)
-                        let rotation = Quat::from_rotation_y(-motion.delta.x * sensitivity);
-
-                        // Rotate both camera position and look_at point to preserve composition
-                        let new_position = rotation * camera_transform.translation;
-                        let new_look_at = rotation * camera_lerp.current_look_at;
-
-                        // Directly set camera position (no lerping for rotation)
-                        camera_transform.translation = new_position;
-                        camera_transform.look_at(new_look_at, Vec3::Y);
-
-                        // Keep lerp state in sync
-                        camera_lerp.target_position = new_position;
-                        camera_lerp.target_look_at = new_look_at;
-                        camera_lerp.current_look_at = new_look_at;
-                        camera_lerp.is_lerping = false;
-                    } else {
-                        // Rotate the planet (original behavior)
-                        let yaw = Quat::from_rotation_y(motion.delta.x * sensitivity);
-
-                        controls.rotation = controls.rotation * yaw;
-                        planet_transform.rotation = controls.rotation;
-                    }
-                }
-            }
+    // Wheel over the settings panel scrolls it, over the 3D view it zooms. A drag is followed
+    // wherever the pointer goes if it started over the 3D view.
+    let is_over_ui = pointer_over_ui.0;
+    let dragging_view = view_drag.update(
+        mouse_input.just_pressed(MouseButton::Left),
+        mouse_input.pressed(MouseButton::Left),
+        is_over_ui,
+    );
+    if is_over_ui {
+        mouse_wheel.clear();
+        if !dragging_view {
+            mouse_motion.clear();
+        }
+    }
 
-            // Handle mouse wheel for zoom - only if not over UI
-            if !is_over_ui {
-                for wheel in mouse_wheel.read() {
-                    controls.zoom -= wheel.y * 2.0;
-                    controls.zoom = controls.zoom.clamp(controls.min_zoom, controls.max_zoom);
-
-                    if camera_mode.rotate_camera {
-                        // Scale both camera and look_at positions proportionally toward/away from origin
-                        // This preserves the composition offset relationship at any orbital angle
-
-                        // Calculate current conceptual zoom from camera distance
-                        // In default view: camera at (0.25*zoom, 0, zoom), distance = zoom * sqrt(1 + 0.25²)
-                        let offset_factor = (1.0_f32 + 0.25 * 0.25).sqrt(); // ≈ 1.031
-                        let current_dist = camera_transform.translation.length();
-                        let current_conceptual_zoom = current_dist / offset_factor;
-
-                        // Scale factor to achieve new zoom
-                        if current_conceptual_zoom > 0.001 {
-                            let scale = controls.zoom / current_conceptual_zoom;
-
-                            let new_position = camera_transform.translation * scale;
-                            let new_look_at = camera_lerp.current_look_at * scale;
-
-                            camera_transform.translation = new_position;
-                            camera_transform.look_at(new_look_at, Vec3::Y);
-
-                            // Keep lerp state in sync
-                            camera_lerp.target_position = new_position;
-                            camera_lerp.target_look_at = new_look_at;
-                            camera_lerp.current_look_at = new_look_at;
-                        }
-                        camera_lerp.is_lerping = false;
-                    } else {
-                        // Recompute composition offsets from current distance
-                        let camera_x_offset = controls.zoom * 0.25;
-                        let look_at_x_offset = controls.zoom * 0.15;
-
-                        camera_lerp.target_position = Vec3::new(camera_x_offset, 0.0, controls.zoom);
-                        camera_lerp.target_look_at = Vec3::new(look_at_x_offset, 0.0, 0.0);
-                        camera_lerp.is_lerping = true;
-                    }
-                }
-            }
+    // Handle mouse dragging - only drags started over the 3D view
+    let delta_secs = real_time.delta_secs();
+    if dragging_view {
+        let sensitivity = 0.002 * (controls.zoom / 60.0);
+        let angle: f32 = mouse_motion.read().map(|motion| motion.delta.x * sensitivity).sum();
+        if angle != 0.0 {
+            turn_view(
+                angle,
+                camera_mode.rotate_camera,
+                &mut camera_transform,
+                &mut camera_lerp,
+                &mut planet_transform,
+                &mut controls,
+            );
+        }
+        controls.inertia.track(angle, delta_secs);
+    } else if gamepad.turn != 0.0 {
+        // The stick turns at a steady pace and stops when it's let go
+        controls.inertia.stop();
+        let angle = gamepad.turn * STICK_TURN_SPEED * (controls.zoom / 60.0) * delta_secs;
+        turn_view(
+            angle,
+            camera_mode.rotate_camera,
+            &mut camera_transform,
+            &mut camera_lerp,
+            &mut planet_transform,
+            &mut controls,
+        );
+    } else {
+        // A camera focus or a zoom turning the planet stops the spin
+        if !display_settings.rotation_inertia || controls.target_rotation.is_some() {
+            controls.inertia.stop();
+        }
+        let angle = controls.inertia.step(delta_secs);
+        if angle != 0.0 {
+            turn_view(
+                angle,
+                camera_mode.rotate_camera,
+                &mut camera_transform,
+                &mut camera_lerp,
+                &mut planet_transform,
+                &mut controls,
+            );
+        }
+    }
+
+    // Handle mouse wheel for zoom - only if not over UI, the wheel messages are cleared then
+    let wheel: f32 = mouse_wheel.read().map(|wheel| wheel.y).sum();
+    let scroll = wheel + gamepad.zoom * TRIGGER_ZOOM_SPEED * delta_secs;
+    if scroll == 0.0 {
+        return;
+    }
+    // The surface point under the cursor, in world space around the planet center. The
+    // triggers zoom without a cursor, straight towards the view.
+    let cursor = windows.single().ok().and_then(Window::cursor_position).filter(|_| wheel != 0.0);
+    let surface_under_cursor = |camera_transform: &Transform| {
+        let ray = camera.viewport_to_world(&GlobalTransform::from(*camera_transform), cursor?).ok()?;
+        let center = planet_transform.translation;
+        let hit = logic::ray_sphere_hit(ray.origin, *ray.direction, center, terrain_settings.radius)?;
+        Some(hit - center)
+    };
+    let before = surface_under_cursor(&camera_transform);
+
+    controls.zoom -= scroll * 2.0;
+    controls.zoom = controls.zoom.clamp(controls.min_zoom, controls.max_zoom);
+
+    if camera_mode.rotate_camera {
+        // Scale both camera and look_at positions proportionally toward/away from origin
+        // This preserves the composition offset relationship at any orbital angle
+
+        // Calculate current conceptual zoom from camera distance
+        let current_dist = camera_transform.translation.length();
+        let current_conceptual_zoom = current_dist / display_settings.camera_composition().distance_per_zoom();
+
+        // Scale factor to achieve new zoom
+        if current_conceptual_zoom > 0.001 {
+            let scale = controls.zoom / current_conceptual_zoom;
+
+            let new_position = camera_transform.translation * scale;
+            let new_look_at = camera_lerp.current_look_at * scale;
+
+            camera_transform.translation = new_position;
+            camera_transform.look_at(new_look_at, Vec3::Y);
+
+            // Keep lerp state in sync
+            camera_lerp.target_position = new_position;
+            camera_lerp.target_look_at = new_look_at;
+            camera_lerp.current_look_at = new_look_at;
         }
+        camera_lerp.is_lerping = false;
+    } else {
+        // Recompute composition offsets from current distance
+        (camera_lerp.target_position, camera_lerp.target_look_at) =
+            display_settings.camera_composition().framing(controls.zoom);
+        camera_lerp.is_lerping = true;
+    }
+
+    // Turn the planet so the point under the cursor is still under it where the camera ends
+    // up, smoothed by smooth_planet_rotation at the pace of the camera
+    let camera_target =
+        Transform::from_translation(camera_lerp.target_position).looking_at(camera_lerp.target_look_at, Vec3::Y);
+    if let (Some(before), Some(after)) = (before, surface_under_cursor(&camera_target)) {
+        let planet_direction = controls.rotation.inverse() * before;
+        controls.target_rotation = Some(logic::focus_rotation(controls.rotation, planet_direction, after));
+    }
+}
+
+/// Turn the view by `angle` around the vertical axis: the camera around the planet, or the
+/// planet in front of the camera
+fn turn_view(
+    angle: f32,
+    rotate_camera: bool,
+    camera_transform: &mut Transform,
+    camera_lerp: &mut CameraLerp,
+    planet_transform: &mut Transform,
+    controls: &mut PlanetControls,
+) {
+    if rotate_camera {
+        // Rotate camera around the planet
+        // Negate for correct direction (drag right = camera moves right = planet appears to rotate left)
+        let rotation = Quat::from_rotation_y(-angle);
+
+        // Rotate both camera position and look_at point to preserve composition
+        let new_position = rotation * camera_transform.translation;
+        let new_look_at = rotation * camera_lerp.current_look_at;
+
+        // Directly set camera position (no lerping for rotation)
+        camera_transform.translation = new_position;
+        camera_transform.look_at(new_look_at, Vec3::Y);
+
+        // Keep lerp state in sync
+        camera_lerp.target_position = new_position;
+        camera_lerp.target_look_at = new_look_at;
+        camera_lerp.current_look_at = new_look_at;
+        camera_lerp.is_lerping = false;
+    } else {
+        // Rotate the planet (original behavior)
+        // Dragging takes over from a running camera focus
+        controls.target_rotation = None;
+        controls.rotation = controls.rotation * Quat::from_rotation_y(angle);
+        planet_transform.rotation = controls.rotation;
+    }
+}
+
+/// Hide surface-anchored entities on the far side of the planet, they would be drawn through
+/// it otherwise. Everything is shown again while `cull_far_side` is off.
+pub fn cull_far_side_anchored(
+    (terrain_settings, display_settings): (Res<TerrainSettings>, Res<DisplaySettings>),
+    // Both are top-level entities, their transforms are already this frame's
+    planet_query: Query<&Transform, With<PlanetEntity>>,
+    camera_query: Query<&Transform, (With<MainCamera>, Without<PlanetEntity>)>,
+    mut anchored: Query<(&SurfaceAnchored, &mut Visibility)>,
+) {
+    let (Ok(planet), Ok(camera)) = (planet_query.single(), camera_query.single()) else {
+        return;
+    };
+
+    for (anchor, mut visibility) in anchored.iter_mut() {
+        let direction = planet.rotation * anchor.direction;
+        let radius = oblate_radius(terrain_settings.radius, terrain_settings.oblateness, anchor.direction);
+        let visible = !display_settings.cull_far_side
+            || logic::faces_camera(direction, planet.translation, radius, camera.translation);
+        visibility.set_if_neq(if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+/// Show the terrain LOD that fits the camera zoom. Overlays are copies of the visible terrain
+/// mesh, so they are told to rebuild, and biome colors are applied to the new mesh.
+pub fn update_terrain_lod(
+    settings: Res<TerrainSettings>,
+    planet_controls: Query<&PlanetControls, With<PlanetEntity>>,
+    mut terrain: Query<(&mut Mesh3d, &mut TerrainLods)>,
+    mut lod_events: MessageWriter<TerrainLodChangedEvent>,
+    mut biome_state: ResMut<BiomeColorState>,
+) {
+    let Ok(controls) = planet_controls.single() else {
+        return;
+    };
+
+    let mut changed = false;
+    for (mut mesh, mut lods) in terrain.iter_mut() {
+        let level = logic::terrain_lod_level(lods.level, controls.zoom, settings.radius);
+        if level == lods.level {
+            continue;
+        }
+        lods.level = level;
+        mesh.0 = lods.meshes[level].clone();
+        changed = true;
+    }
+
+    if changed {
+        lod_events.write(TerrainLodChangedEvent);
+        biome_state.applied = false;
+    }
+}
+
+/// Ease a zoom the radius of a regenerated planet left outside the limits into them, the camera
+/// following it
+pub fn settle_zoom_into_limits(
+    time: Res<Time>,
+    mut planet_controls: Query<&mut PlanetControls, With<PlanetEntity>>,
+    mut camera_events: MessageWriter<SetCameraPositionEvent>,
+) {
+    for mut controls in planet_controls.iter_mut() {
+        let zoom = logic::settle_zoom(controls.zoom, (controls.min_zoom, controls.max_zoom), time.delta_secs());
+        if zoom != controls.zoom {
+            controls.zoom = zoom;
+            camera_events.write(SetCameraPositionEvent {
+                position: Vec3::new(0.0, 0.0, zoom),
+            });
+        }
+    }
+}
+
+/// Frame the planet again when the camera composition settings change
+pub fn reframe_on_composition_change(
+    settings: Res<DisplaySettings>,
+    mut last: Local<Option<logic::CameraComposition>>,
+    planet_controls: Query<&PlanetControls, With<PlanetEntity>>,
+    mut camera_events: MessageWriter<SetCameraPositionEvent>,
+) {
+    let composition = settings.camera_composition();
+    if last.replace(composition).is_none_or(|last| last == composition) {
+        return;
+    }
+    if let Ok(controls) = planet_controls.single() {
+        camera_events.write(SetCameraPositionEvent {
+            position: Vec3::new(0.0, 0.0, controls.zoom),
+        });
     }
 }
 
@@ -462,6 +1193,7 @@ pub fn smooth_camera_movement(
 
 pub fn handle_camera_position_events(
     mut events: MessageReader<SetCameraPositionEvent>,
+    settings: Res<DisplaySettings>,
     mut camera_query: Query<&mut CameraLerp, With<MainCamera>>,
 ) {
     for event in events.read() {
@@ -469,11 +1201,9 @@ pub fn handle_camera_position_events(
             let distance = event.position.z.max(0.0);
 
             // Recompute offsets from current distance to keep composition stable
-            let camera_x_offset = distance * 0.25;
-            let look_at_x_offset = distance * 0.15;
-
-            camera_lerp.target_position = Vec3::new(camera_x_offset, event.position.y, distance);
-            camera_lerp.target_look_at = Vec3::new(look_at_x_offset, 0.0, 0.0);
+            let (position, look_at) = settings.camera_composition().framing(distance);
+            camera_lerp.target_position = position + Vec3::Y * event.position.y;
+            camera_lerp.target_look_at = look_at;
 
             // Immediately align the current look to new target to prevent sideways motion on regen
             camera_lerp.current_look_at = camera_lerp.target_look_at;
@@ -489,22 +1219,39 @@ pub fn handle_camera_position_events(
 
 pub fn handle_generate_new_seed(
     mut events: MessageReader<GenerateNewSeedEvent>,
-    mut settings: ResMut<PlanetGenerationSettings>,
-    mut settings_changed_events: MessageWriter<SettingsChanged>,
+    mut settings: ResMut<TerrainSettings>,
 ) {
     for _ in events.read() {
         // Generate a new 8-bit user seed using planetgen
-        let new_user_seed = planetgen::tools::generate_seed8();
+        settings.set_seed(generate_seed8());
+    }
+}
 
-        // Update both user seed and the expanded 64-bit seed
-        settings.user_seed = new_user_seed;
-        settings.seed = planetgen::tools::expand_seed64(new_user_seed);
-        settings_changed_events.write(SettingsChanged);
+pub fn handle_reroll_planet(
+    mut events: MessageReader<RerollPlanetEvent>,
+    mut planet_generation_events: MessageWriter<GeneratePlanetEvent>,
+    mut settings: ResMut<TerrainSettings>,
+    mut kept_plate_layout: ResMut<KeptPlateLayout>,
+    current_planet_data: Res<CurrentPlanetData>,
+) {
+    for event in events.read() {
+        match event {
+            RerollPlanetEvent::Terrain => {
+                settings.terrain_seed = generate_seed8();
+                kept_plate_layout.0 = current_planet_data.planet_data.as_ref().map(PlanetData::extract_plate_layout);
+            }
+            RerollPlanetEvent::Plates => {
+                settings.plate_seed = generate_seed8();
+                kept_plate_layout.0 = None;
+            }
+        }
+        planet_generation_events.write(GeneratePlanetEvent);
     }
 }
 
 pub fn handle_reset_camera(
     mut events: MessageReader<ResetCameraEvent>,
+    settings: Res<DisplaySettings>,
     mut camera_query: Query<(&mut Transform, &mut CameraLerp), With<MainCamera>>,
     mut planet_query: Query<(&mut Transform, &mut PlanetControls), (With<PlanetEntity>, Without<MainCamera>)>,
 ) {
@@ -518,11 +1265,7 @@ pub fn handle_reset_camera(
 
         if let Ok((mut camera_transform, mut camera_lerp)) = camera_query.single_mut() {
             // Reset to default position (looking at planet from front)
-            let camera_x_offset = zoom * 0.25;
-            let look_at_x_offset = zoom * 0.15;
-
-            let new_position = Vec3::new(camera_x_offset, 0.0, zoom);
-            let new_look_at = Vec3::new(look_at_x_offset, 0.0, 0.0);
+            let (new_position, new_look_at) = settings.camera_composition().framing(zoom);
 
             // Directly set camera position (no lerping)
             camera_transform.translation = new_position;
@@ -538,16 +1281,90 @@ pub fn handle_reset_camera(
         // Also reset planet rotation
         if let Ok((mut transform, mut controls)) = planet_query.single_mut() {
             controls.rotation = Quat::IDENTITY;
+            controls.target_rotation = None;
+            controls.inertia.stop();
             transform.rotation = Quat::IDENTITY;
         }
     }
 }
 
+/// Turn the planet so the chosen feature faces the camera. The camera goes back to its usual
+/// framing at the current zoom, the planet rotation follows in `smooth_planet_rotation`.
+pub fn handle_camera_focus(
+    mut events: MessageReader<FocusCameraEvent>,
+    current_planet_data: Res<CurrentPlanetData>,
+    display_settings: Res<DisplaySettings>,
+    mut camera_query: Query<&mut CameraLerp, With<MainCamera>>,
+    mut planet_query: Query<(&Transform, &mut PlanetControls), With<PlanetEntity>>,
+) {
+    for event in events.read() {
+        let (Ok(mut camera_lerp), Ok((planet_transform, mut controls))) =
+            (camera_query.single_mut(), planet_query.single_mut())
+        else {
+            continue;
+        };
+
+        (camera_lerp.target_position, camera_lerp.target_look_at) =
+            display_settings.camera_composition().framing(controls.zoom);
+        camera_lerp.is_lerping = true;
+
+        let planet_data = current_planet_data.planet_data.as_ref();
+        let direction = match event.0 {
+            CameraFocus::Planet => {
+                controls.target_rotation = Some(Quat::IDENTITY);
+                continue;
+            }
+            CameraFocus::NorthPole => Some(Vec3::Y),
+            CameraFocus::SouthPole => Some(Vec3::NEG_Y),
+            CameraFocus::HighestPeak => planet_data.map(PlanetData::highest_peak_direction),
+            CameraFocus::LargestContinent => planet_data.and_then(PlanetData::largest_continent_direction),
+            CameraFocus::Plate(id) => planet_data.and_then(|planet| {
+                let stats = planet.plate_stats();
+                stats.into_iter().find(|plate| plate.id == id).map(|plate| plate.center)
+            }),
+        };
+        let Some(direction) = direction else {
+            continue;
+        };
+
+        let toward_camera = camera_lerp.target_position - planet_transform.translation;
+        controls.target_rotation = Some(logic::focus_rotation(controls.rotation, direction, toward_camera));
+    }
+}
+
+/// Turn the planet towards the rotation a camera focus picked, as smooth as the camera moves
+pub fn smooth_planet_rotation(
+    time: Res<Time>,
+    camera_query: Query<&CameraLerp, With<MainCamera>>,
+    mut planet_query: Query<(&mut Transform, &mut PlanetControls), With<PlanetEntity>>,
+) {
+    let (Ok(camera_lerp), Ok((mut transform, mut controls))) = (camera_query.single(), planet_query.single_mut())
+    else {
+        return;
+    };
+    let Some(target) = controls.target_rotation else {
+        return;
+    };
+
+    let factor = (camera_lerp.lerp_speed * time.delta_secs()).min(1.0);
+    controls.rotation = logic::rotation_step(controls.rotation, target, factor);
+    if controls.rotation.angle_between(target) < 1e-3 {
+        controls.rotation = target;
+        controls.target_rotation = None;
+    }
+    transform.rotation = controls.rotation;
+}
+
 /// Automatically generate a planet when entering PlanetGeneration state
-/// This eliminates the need to click "Generate" button on startup
+/// This eliminates the need to click "Generate" button on startup.
+/// Left to the launch options when they generate the planet themselves.
 pub fn auto_generate_initial_planet(
+    launch_options: Option<Res<LaunchOptions>>,
     mut planet_events: MessageWriter<GeneratePlanetEvent>,
 ) {
+    if launch_options.is_some_and(|options| options.generates_on_launch()) {
+        return;
+    }
     info!("Auto-generating initial planet on game load");
     planet_events.write(GeneratePlanetEvent);
 }
@@ -1,47 +1,83 @@
 pub mod systems;
 
 use bevy::prelude::*;
-use crate::planet::resources::PlanetGenerationSettings;
+use crate::planet::ClimateRebuildSet;
 
 /// Resource to store temperature visualization settings
 #[derive(Resource, Clone)]
 pub struct TemperatureSettings {
     pub planet_radius: f32,
     pub enabled: bool,
-    pub temperature_cubemap_resolution: usize,
+}
+
+/// Fixed rate of the climate simulation, independent of the frame rate
+pub const CLIMATE_STEPS_PER_SECOND: f64 = 10.0;
+
+/// Share of the way back to the latitude temperatures the advected air moves per second
+pub const TEMPERATURE_RELAXATION_PER_SECOND: f32 = 0.5;
+
+/// Play/pause state of the climate simulation, which advances in fixed steps
+#[derive(Resource, Default)]
+pub struct ClimateSimState {
+    pub running: bool,
+    /// Single steps requested while paused, taken on the next fixed ticks
+    pub pending_steps: u32,
+    /// Steps simulated since the temperatures were last rebuilt
+    pub step: u64,
+}
+
+impl ClimateSimState {
+    /// Whether this fixed tick simulates a step, uses up a requested single step
+    pub fn take_step(&mut self) -> bool {
+        if self.running {
+            return true;
+        }
+        if self.pending_steps > 0 {
+            self.pending_steps -= 1;
+            return true;
+        }
+        false
+    }
+
+    /// The temperatures were rebuilt, counting starts over
+    pub fn reset(&mut self) {
+        self.step = 0;
+        self.pending_steps = 0;
+    }
 }
 
 impl Default for TemperatureSettings {
     fn default() -> Self {
-        let config = planetgen::get_config();
         Self {
             planet_radius: 50.0,
             enabled: false,
-            temperature_cubemap_resolution: config.temperature.cubemap_resolution,
         }
     }
 }
 
-/// Resource that stores a copy of the last planet settings used to build the temperature cubemap
-/// This allows us to detect when temperature values actually change
-#[derive(Resource, Clone)]
-pub struct PreviousPlanetSettings(pub PlanetGenerationSettings);
-
-impl Default for PreviousPlanetSettings {
-    fn default() -> Self {
-        Self(PlanetGenerationSettings::default())
-    }
-}
-
 pub struct TemperaturePlugin;
 
 impl Plugin for TemperaturePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<TemperatureSettings>()
-            .init_resource::<PreviousPlanetSettings>()
-            .add_systems(Startup, systems::initialize_temperature_cubemap)
-            .add_systems(Update, systems::update_temperature_settings)
-            .add_systems(Update, systems::regenerate_temperature_meshes_on_settings_change)
-            .add_systems(Update, systems::handle_temperature_tab_events);
+            .init_resource::<ClimateSimState>()
+            .insert_resource(Time::<Fixed>::from_hz(CLIMATE_STEPS_PER_SECOND))
+            .add_systems(FixedUpdate, systems::advect_temperature_by_wind)
+            .add_systems(
+                Update,
+                systems::update_temperature_settings.in_set(ClimateRebuildSet::Temperature),
+            )
+            .add_systems(
+                Update,
+                systems::regenerate_temperature_meshes_on_settings_change
+                    .after(systems::update_temperature_settings),
+            )
+            // The first planet brings the cubemap and the tab switch in the same frame, the
+            // overlay built for the new cubemap is already there when the switch is handled
+            .add_systems(
+                Update,
+                systems::handle_temperature_tab_events
+                    .after(systems::regenerate_temperature_meshes_on_settings_change),
+            );
     }
 }
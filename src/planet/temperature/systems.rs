@@ -1,22 +1,69 @@
-use super::{PreviousPlanetSettings, TemperatureSettings};
-use crate::planet::components::PlanetEntity;
-use crate::planet::events::TemperatureTabActiveEvent;
-use crate::planet::resources::PlanetGenerationSettings;
-use bevy::asset::RenderAssetUsages;
-use bevy::mesh::{PrimitiveTopology};
+use super::{ClimateSimState, TEMPERATURE_RELAXATION_PER_SECOND, TemperatureSettings};
+use crate::mesh::helpers::{equirect_overlay_image, mesh_positions};
+use crate::planet::components::{
+    ContinentViewMesh, OceanEntity, OverlayMesh, PlanetEntity, TemperatureView,
+};
+use crate::planet::events::{
+    SetActiveView, SettingsDiff, SettingsGroup, TerrainLodChangedEvent, ViewKind,
+};
+use crate::planet::resources::{
+    ClimateSettings, CurrentPlanetData, DisplaySettings, OverlayCacheKey, OverlayColorCache, OverlayKind, OverlayMeshes,
+    PlanetAssets, SurfaceColorSettings, TerrainSettings,
+};
+use crate::planet::wind::systems::WindCubeMap;
 use bevy::prelude::*;
-use planetgen::temperature::TemperatureCubeMap as PlanetgenTemperatureCubeMap;
+use std::time::Instant;
+use planetgen::prelude::{
+    Palette, PlanetData, TemperatureCubeMap as PlanetgenTemperatureCubeMap, TemperatureField,
+};
 
 /// Bevy-compatible TemperatureCubeMap resource
 #[derive(Resource, Clone)]
 pub struct TemperatureCubeMap {
+    /// Temperatures after the simulated steps
     pub inner: PlanetgenTemperatureCubeMap,
+    /// Latitude temperatures the simulation relaxes towards
+    pub equilibrium: PlanetgenTemperatureCubeMap,
 }
 
 impl TemperatureCubeMap {
-    pub fn build(resolution: usize, equator_temp: f32, pole_temp: f32, min_temp: f32, max_temp: f32, falloff: f32) -> Self {
-        let inner = PlanetgenTemperatureCubeMap::build_with_falloff(resolution, equator_temp, pole_temp, min_temp, max_temp, falloff);
-        Self { inner }
+    pub fn build(
+        resolution: usize,
+        equator_temp: f32,
+        pole_temp: f32,
+        min_temp: f32,
+        max_temp: f32,
+        falloff: f32,
+        palette: Palette,
+    ) -> Self {
+        let inner = PlanetgenTemperatureCubeMap::build_with_falloff(
+            resolution,
+            equator_temp,
+            pole_temp,
+            min_temp,
+            max_temp,
+            falloff,
+            palette,
+        );
+        Self {
+            equilibrium: inner.clone(),
+            inner,
+        }
+    }
+
+    /// The simulated and the latitude temperatures at `resolution`, the simulation carries on
+    /// from where it was
+    pub fn resample(&mut self, resolution: usize) {
+        self.inner = self.inner.resample(resolution);
+        self.equilibrium = self.equilibrium.resample(resolution);
+    }
+
+    /// One simulation step of `dt` seconds: carry the temperatures along the wind, then pull
+    /// them back towards the latitude temperatures
+    pub fn step(&mut self, wind: &WindCubeMap, radius: f32, dt: f32, (climate, palette): (&ClimateSettings, Palette)) {
+        self.inner.advect(|dir| wind.sample(dir), radius, dt);
+        self.inner.relax_towards(&self.equilibrium, TEMPERATURE_RELAXATION_PER_SECOND * dt);
+        self.inner.update_colors(climate.temperature_min_temp, climate.temperature_max_temp, palette);
     }
 
     pub fn sample_temperature(&self, position: Vec3) -> f32 {
@@ -32,98 +79,142 @@ impl TemperatureCubeMap {
 #[derive(Component)]
 pub struct TemperatureMesh;
 
-/// Initialize the temperature cube map resource at startup
-pub fn initialize_temperature_cubemap(mut commands: Commands, settings: Res<TemperatureSettings>) {
-    info!("Initializing temperature cube map...");
-    let config = planetgen::get_config();
-    let cubemap = TemperatureCubeMap::build(
-        settings.temperature_cubemap_resolution,
-        config.temperature.equator_temp,
-        config.temperature.pole_temp,
-        config.temperature.min_temp,
-        config.temperature.max_temp,
-        config.temperature.latitude_falloff,
-    );
-    commands.insert_resource(cubemap);
+/// Advance the climate simulation by one fixed step while it is playing or a single step was
+/// requested. Runs in `FixedUpdate`, so the result doesn't depend on the frame rate.
+pub fn advect_temperature_by_wind(
+    time: Res<Time>,
+    mut sim: ResMut<ClimateSimState>,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
+    wind_cubemap: Option<Res<WindCubeMap>>,
+    temperature_cubemap: Option<ResMut<TemperatureCubeMap>>,
+) {
+    // Both are built for the first planet
+    let (Some(wind_cubemap), Some(mut temperature_cubemap)) = (wind_cubemap, temperature_cubemap) else {
+        return;
+    };
+    if !sim.take_step() {
+        return;
+    }
+
+    let colors = (&*climate_settings, display_settings.palette);
+    temperature_cubemap.step(&wind_cubemap, terrain_settings.radius, time.delta_secs(), colors);
+    sim.step += 1;
 }
 
 /// Update temperature settings from planet generation settings
-/// Only rebuilds the cubemap when temperature values actually change
+/// Builds the cubemap for the first planet, after that only rebuilds it when a temperature
+/// setting or the planet grid actually changed, a new palette only recolors it and a new
+/// resolution setting resamples it
 pub fn update_temperature_settings(
-    planet_settings: Res<PlanetGenerationSettings>,
-    mut previous_settings: ResMut<PreviousPlanetSettings>,
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
+    planet_data: Res<CurrentPlanetData>,
     mut temperature_settings: ResMut<TemperatureSettings>,
-    mut temperature_cubemap: ResMut<TemperatureCubeMap>,
+    temperature_cubemap: Option<ResMut<TemperatureCubeMap>>,
+    mut sim: ResMut<ClimateSimState>,
+    mut commands: Commands,
 ) {
     // Always update these basic settings
-    temperature_settings.planet_radius = planet_settings.radius;
-    temperature_settings.enabled = planet_settings.show_temperature;
-
-    // Check if temperature-related values have actually changed
-    let temp_changed =
-        previous_settings.0.temperature_equator_temp != planet_settings.temperature_equator_temp ||
-        previous_settings.0.temperature_pole_temp != planet_settings.temperature_pole_temp ||
-        previous_settings.0.temperature_max_temp != planet_settings.temperature_max_temp ||
-        previous_settings.0.temperature_min_temp != planet_settings.temperature_min_temp ||
-        previous_settings.0.temperature_latitude_falloff != planet_settings.temperature_latitude_falloff ||
-        previous_settings.0.temperature_cubemap_resolution != planet_settings.temperature_cubemap_resolution;
-
-    // Only rebuild cubemap if temperature values actually changed
-    if temp_changed {
-        info!("Rebuilding temperature cubemap with new settings...");
-        *temperature_cubemap = TemperatureCubeMap::build(
-            planet_settings.temperature_cubemap_resolution,
-            planet_settings.temperature_equator_temp,
-            planet_settings.temperature_pole_temp,
-            planet_settings.temperature_min_temp,
-            planet_settings.temperature_max_temp,
-            planet_settings.temperature_latitude_falloff,
-        );
+    temperature_settings.planet_radius = terrain_settings.radius;
+    temperature_settings.enabled = display_settings.show_temperature;
 
-        // Update the previous settings to track current values
-        previous_settings.0.temperature_equator_temp = planet_settings.temperature_equator_temp;
-        previous_settings.0.temperature_pole_temp = planet_settings.temperature_pole_temp;
-        previous_settings.0.temperature_max_temp = planet_settings.temperature_max_temp;
-        previous_settings.0.temperature_min_temp = planet_settings.temperature_min_temp;
-        previous_settings.0.temperature_latitude_falloff = planet_settings.temperature_latitude_falloff;
-        previous_settings.0.temperature_cubemap_resolution = planet_settings.temperature_cubemap_resolution;
-    }
+    let changed = SettingsDiff::read_groups(&mut settings_diffs);
+    let Some(planet) = planet_data.planet_data.as_ref() else {
+        return;
+    };
 
-    // Check if land_temperature_bonus changed (doesn't require cubemap rebuild, just mesh update)
-    if previous_settings.0.land_temperature_bonus != planet_settings.land_temperature_bonus {
-        previous_settings.0.land_temperature_bonus = planet_settings.land_temperature_bonus;
+    // The cubemap follows the grid of the generated planet, not the radius slider, which
+    // only rescales the existing planet
+    let resolution = climate_settings.temperature_resolution_for(planet.face_grid_size);
+    let resized = temperature_cubemap
+        .as_ref()
+        .is_some_and(|cubemap| resolution != cubemap.inner.resolution);
+    let resolution_setting_changed = changed.contains(&SettingsGroup::ClimateResolution);
+
+    // land_temperature_bonus is in the same group, rebuilding the cubemap for it is cheap
+    // and makes the temperature meshes regenerate
+    let rebuild = temperature_cubemap.is_none()
+        || changed.contains(&SettingsGroup::Temperature)
+        || (resized && !resolution_setting_changed);
+    if rebuild {
+        info!("Rebuilding temperature cubemap with new settings...");
+        let cubemap = TemperatureCubeMap::build(
+            resolution,
+            climate_settings.temperature_equator_temp,
+            climate_settings.temperature_pole_temp,
+            climate_settings.temperature_min_temp,
+            climate_settings.temperature_max_temp,
+            climate_settings.temperature_latitude_falloff,
+            display_settings.palette,
+        );
+        match temperature_cubemap {
+            Some(mut existing) => *existing = cubemap,
+            None => commands.insert_resource(cubemap),
+        }
+        sim.reset();
+    } else if resized
+        && let Some(mut temperature_cubemap) = temperature_cubemap
+    {
+        // Only the resolution setting changed, the simulated temperatures are kept
+        info!("Resampling temperature cubemap to {resolution}");
+        temperature_cubemap.resample(resolution);
+    } else if changed.contains(&SettingsGroup::Palette)
+        && let Some(mut temperature_cubemap) = temperature_cubemap
+    {
+        // Recoloring marks the cubemap changed, which regenerates the temperature meshes
+        temperature_cubemap.inner.update_colors(
+            climate_settings.temperature_min_temp,
+            climate_settings.temperature_max_temp,
+            display_settings.palette,
+        );
     }
 }
 
-/// Regenerate temperature meshes when cubemap OR land_temperature_bonus changes
+/// Regenerate temperature meshes when the cubemap or the terrain LOD changes
 pub fn regenerate_temperature_meshes_on_settings_change(
-    planet_settings: Res<PlanetGenerationSettings>,
-    previous_settings: Res<PreviousPlanetSettings>,
-    temperature_cubemap: Res<TemperatureCubeMap>,
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut lod_events: MessageReader<TerrainLodChangedEvent>,
+    (terrain_settings, climate_settings, display_settings): (Res<TerrainSettings>, Res<ClimateSettings>, Res<DisplaySettings>),
+    temperature_cubemap: Option<Res<TemperatureCubeMap>>,
+    planet_data: Res<CurrentPlanetData>,
+    mut color_cache: ResMut<OverlayColorCache>,
+    (mut overlay_meshes, mut planet_assets): (ResMut<OverlayMeshes>, ResMut<PlanetAssets>),
     planet_query: Query<Entity, With<PlanetEntity>>,
-    continent_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::ContinentViewMesh>,
-    >,
-    ocean_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::OceanEntity>,
-    >,
+    continent_query: Query<&Mesh3d, With<ContinentViewMesh>>,
+    ocean_query: Query<&Mesh3d, With<OceanEntity>>,
     existing_temp_meshes: Query<Entity, With<TemperatureMesh>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    mut built_with_textures: Local<Option<bool>>,
     mut commands: Commands,
 ) {
-    // Only regenerate if temperature view is active
-    if !planet_settings.show_temperature {
+    let lod_changed = lod_events.read().count() > 0;
+    let overlay_mode_changed = built_with_textures.is_some_and(|textures| textures != display_settings.overlay_textures);
+
+    // Only regenerate if temperature view is active. Hidden copies of the old LOD or palette
+    // would be shown again as they are, drop them so the tab builds new ones. While it is
+    // active the recolored cubemap regenerates them.
+    let palette_changed = SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Palette]);
+    if !display_settings.show_temperature {
+        if lod_changed || palette_changed {
+            for entity in existing_temp_meshes.iter() {
+                commands.entity(entity).despawn();
+            }
+        }
         return;
     }
 
-    // Regenerate meshes if cubemap OR land_temperature_bonus changed
-    // Both trigger the same action: regenerate the temperature-colored meshes
-    if !temperature_cubemap.is_changed() && !previous_settings.is_changed() {
+    // Built for the first planet, the tab shows a hint until then
+    let Some(ref temperature_cubemap) = temperature_cubemap else {
+        return;
+    };
+
+    // Any temperature setting change rebuilds the cubemap
+    if !temperature_cubemap.is_changed() && !lod_changed && !overlay_mode_changed {
         return;
     }
+    *built_with_textures = Some(display_settings.overlay_textures);
 
     info!("Regenerating temperature meshes due to settings change");
 
@@ -136,314 +227,299 @@ pub fn regenerate_temperature_meshes_on_settings_change(
         return;
     };
 
-    // Recreate continent temperature mesh
-    for (_entity, mesh_handle, _material) in continent_query.iter() {
-        if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-            let temp_mesh = create_temperature_colored_mesh(
-                original_mesh,
-                &temperature_cubemap,
-                planet_settings.radius,
-                planet_settings.continent_threshold,
-                planet_settings.land_temperature_bonus,
-                planet_settings.temperature_min_temp,
-                planet_settings.temperature_max_temp,
-            );
-            let temp_mesh_handle = meshes.add(temp_mesh);
-
-            let temp_material = materials.add(StandardMaterial {
-                base_color: Color::WHITE,
-                unlit: true,
-                ..default()
-            });
-
-            let temp_entity = commands
-                .spawn((
-                    Mesh3d(temp_mesh_handle),
-                    MeshMaterial3d(temp_material),
-                    Transform::default(),
-                    GlobalTransform::default(),
-                    Visibility::Visible,
-                    TemperatureMesh,
-                    crate::planet::components::TemperatureView,
-                ))
-                .id();
-
-            commands.entity(planet_entity).add_child(temp_entity);
-        }
-    }
-
-    // Recreate ocean temperature mesh
-    for (_entity, mesh_handle, _material) in ocean_query.iter() {
-        if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-            let temp_mesh = create_simple_temperature_mesh(original_mesh, &temperature_cubemap);
-            let temp_mesh_handle = meshes.add(temp_mesh);
-
-            let temp_material = materials.add(StandardMaterial {
-                base_color: Color::WHITE,
-                unlit: true,
-                ..default()
-            });
-
-            let temp_entity = commands
-                .spawn((
-                    Mesh3d(temp_mesh_handle),
-                    MeshMaterial3d(temp_material),
-                    Transform::default(),
-                    GlobalTransform::default(),
-                    Visibility::Visible,
-                    TemperatureMesh,
-                    crate::planet::components::TemperatureView,
-                ))
-                .id();
-
-            commands.entity(planet_entity).add_child(temp_entity);
-        }
-    }
+    let surface_settings = (&*terrain_settings, &*climate_settings, &*display_settings);
+    spawn_temperature_meshes(
+        planet_entity,
+        &continent_query,
+        &ocean_query,
+        surface_settings,
+        temperature_cubemap,
+        planet_data.planet_data.as_ref(),
+        overlay_key(surface_settings, temperature_cubemap, &planet_data),
+        &mut color_cache,
+        &mut overlay_meshes,
+        &mut planet_assets,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut commands,
+    );
 }
 
 /// Handle temperature tab activation/deactivation
 pub fn handle_temperature_tab_events(
-    mut temperature_tab_events: MessageReader<TemperatureTabActiveEvent>,
-    mut planet_settings: ResMut<PlanetGenerationSettings>,
+    mut set_active_view: MessageReader<SetActiveView>,
+    (terrain_settings, climate_settings, mut display_settings): (
+        Res<TerrainSettings>,
+        Res<ClimateSettings>,
+        ResMut<DisplaySettings>,
+    ),
+    planet_data: Res<CurrentPlanetData>,
+    mut color_cache: ResMut<OverlayColorCache>,
+    (mut overlay_meshes, mut planet_assets): (ResMut<OverlayMeshes>, ResMut<PlanetAssets>),
     planet_query: Query<Entity, With<PlanetEntity>>,
-    continent_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::ContinentViewMesh>,
-    >,
-    ocean_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::OceanEntity>,
-    >,
+    continent_query: Query<&Mesh3d, With<ContinentViewMesh>>,
+    ocean_query: Query<&Mesh3d, With<OceanEntity>>,
     existing_temp_meshes: Query<Entity, With<TemperatureMesh>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    temperature_cubemap: Res<TemperatureCubeMap>,
+    mut images: ResMut<Assets<Image>>,
+    temperature_cubemap: Option<Res<TemperatureCubeMap>>,
     mut commands: Commands,
 ) {
-    for event in temperature_tab_events.read() {
-        planet_settings.show_temperature = event.active;
-
-        if event.active {
-            // Only create temperature meshes if they don't already exist
-            if !existing_temp_meshes.is_empty() {
-                info!("Temperature meshes already exist, skipping creation");
-                continue;
-            }
-
-            info!("Creating temperature-colored mesh copies");
-
-            let Some(planet_entity) = planet_query.iter().next() else {
-                warn!("No planet entity found");
-                continue;
-            };
+    // Only the last switch of the frame counts, every overlay ends up agreeing on it
+    let Some(&SetActiveView(view)) = set_active_view.read().last() else {
+        return;
+    };
+    let active = view == ViewKind::Temperature;
+    display_settings.show_temperature = active;
+
+    if active {
+        // Only create temperature meshes if they don't already exist
+        if !existing_temp_meshes.is_empty() {
+            info!("Temperature meshes already exist, skipping creation");
+            return;
+        }
 
-            // Hide original continent mesh and create temperature-colored copy
-            for (_entity, mesh_handle, _material) in continent_query.iter() {
-                // DO NOT manipulate visibility - centralized system handles it
-                // Just create the temperature mesh copy
+        // Built with the first planet, which builds the meshes when it comes in
+        let Some(ref temperature_cubemap) = temperature_cubemap else {
+            info!("No temperature cubemap before the first planet");
+            return;
+        };
+
+        let Some(planet_entity) = planet_query.iter().next() else {
+            warn!("No planet entity found");
+            return;
+        };
+
+        // DO NOT manipulate visibility - centralized system handles it
+        // Just create the temperature mesh copies
+        let surface_settings = (&*terrain_settings, &*climate_settings, &*display_settings);
+        spawn_temperature_meshes(
+            planet_entity,
+            &continent_query,
+            &ocean_query,
+            surface_settings,
+            temperature_cubemap,
+            planet_data.planet_data.as_ref(),
+            overlay_key(surface_settings, temperature_cubemap, &planet_data),
+            &mut color_cache,
+            &mut overlay_meshes,
+            &mut planet_assets,
+            &mut meshes,
+            &mut materials,
+            &mut images,
+            &mut commands,
+        );
+    } else {
+        info!("Hiding temperature-colored mesh copies");
 
-                if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-                    let temp_mesh = create_temperature_colored_mesh(
-                        original_mesh,
-                        &temperature_cubemap,
-                        planet_settings.radius,
-                        planet_settings.continent_threshold,
-                        planet_settings.land_temperature_bonus,
-                        planet_settings.temperature_min_temp,
-                        planet_settings.temperature_max_temp,
-                    );
-                    let temp_mesh_handle = meshes.add(temp_mesh);
-
-                    // Create solid unlit material for temperature colors
-                    let temp_material = materials.add(StandardMaterial {
-                        base_color: Color::WHITE,
-                        unlit: true, // Show temperature colors without lighting
-                        ..default()
-                    });
-
-                    let temp_entity = commands
-                        .spawn((
-                            Mesh3d(temp_mesh_handle),
-                            MeshMaterial3d(temp_material),
-                            Transform::default(),
-                            GlobalTransform::default(),
-                            Visibility::Visible,
-                            TemperatureMesh,
-                            crate::planet::components::TemperatureView, // Add marker for visibility control
-                        ))
-                        .id();
-
-                    commands.entity(planet_entity).add_child(temp_entity);
-                }
-            }
+        // Hide temperature mesh copies instead of despawning
+        for entity in existing_temp_meshes.iter() {
+            commands.entity(entity).try_insert(Visibility::Hidden);
+        }
 
-            // Hide original ocean mesh and create temperature-colored copy (no edges)
-            for (_entity, mesh_handle, _material) in ocean_query.iter() {
-                // DO NOT manipulate visibility - centralized system handles it
-                // Just create the temperature mesh copy
-
-                if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-                    // Ocean gets temperature colors but no edge detection
-                    let temp_mesh =
-                        create_simple_temperature_mesh(original_mesh, &temperature_cubemap);
-                    let temp_mesh_handle = meshes.add(temp_mesh);
-
-                    // Create solid unlit material for temperature colors
-                    let temp_material = materials.add(StandardMaterial {
-                        base_color: Color::WHITE,
-                        unlit: true, // Show temperature colors without lighting
-                        ..default()
-                    });
-
-                    let temp_entity = commands
-                        .spawn((
-                            Mesh3d(temp_mesh_handle),
-                            MeshMaterial3d(temp_material),
-                            Transform::default(),
-                            GlobalTransform::default(),
-                            Visibility::Visible,
-                            TemperatureMesh,
-                            crate::planet::components::TemperatureView, // Add marker for visibility control
-                        ))
-                        .id();
-
-                    commands.entity(planet_entity).add_child(temp_entity);
-                }
-            }
-        } else {
-            info!("Hiding temperature-colored mesh copies");
+        // DO NOT manipulate continent or ocean visibility here!
+        // The centralized tab visibility system handles ALL mesh visibility
+    }
 
-            // Hide temperature mesh copies instead of despawning
-            for entity in existing_temp_meshes.iter() {
-                commands.entity(entity).try_insert(Visibility::Hidden);
-            }
+}
 
-            // DO NOT manipulate continent or ocean visibility here!
-            // The centralized tab visibility system handles ALL mesh visibility
-        }
-    }
+/// Temperature colors depend on the cubemap plus the land bonus and sea level applied on top
+fn overlay_key(
+    (terrain_settings, climate_settings, display_settings): SurfaceColorSettings,
+    temperature_cubemap: &Res<TemperatureCubeMap>,
+    planet_data: &CurrentPlanetData,
+) -> OverlayCacheKey {
+    OverlayCacheKey::new(
+        planet_data.generation,
+        temperature_cubemap.last_changed(),
+        display_settings.palette,
+        &[
+            terrain_settings.radius,
+            terrain_settings.oblateness,
+            planet_data.planet_data.as_ref().map_or(0.0, PlanetData::sea_level),
+            climate_settings.land_temperature_bonus,
+            climate_settings.temperature_min_temp,
+            climate_settings.temperature_max_temp,
+        ],
+    )
 }
 
-/// Create a copy of a mesh with temperature-based vertex colors and continent darkening
-fn create_temperature_colored_mesh(
-    original_mesh: &Mesh,
+/// Spawn the temperature overlay on the shared copies of the continent mesh (darkened land)
+/// and the ocean mesh. With `overlay_textures` the copies sample an equirectangular texture,
+/// which keeps the full cubemap resolution; otherwise they get vertex colors.
+fn spawn_temperature_meshes(
+    planet_entity: Entity,
+    continent_query: &Query<&Mesh3d, With<ContinentViewMesh>>,
+    ocean_query: &Query<&Mesh3d, With<OceanEntity>>,
+    (_, climate_settings, display_settings): SurfaceColorSettings,
     temperature_cubemap: &TemperatureCubeMap,
-    planet_radius: f32,
-    continent_threshold: f32,
-    land_temperature_bonus: f32,
-    min_temp: f32,
-    max_temp: f32,
-) -> Mesh {
-    let mut new_mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::default(),
-    );
-
-    // Copy positions
-    if let Some(positions_attr) = original_mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
-        if let Some(positions) = positions_attr.as_float3() {
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions.to_vec());
-
-            // Ocean level is now at planet_radius + continent_threshold
-            let ocean_level = planet_radius + continent_threshold;
-
-            // Generate temperature colors, applying land bonus and darkening land
-            let colors: Vec<[f32; 4]> = positions
-                .iter()
-                .map(|&[x, y, z]| {
-                    let position = Vec3::new(x, y, z);
-                    let direction = position.normalize();
-                    let vertex_radius = (x * x + y * y + z * z).sqrt();
-
-                    // Check if this is land (above ocean level)
-                    let is_land = vertex_radius > ocean_level;
-
-                    // Get base temperature from latitude
-                    let base_temp = temperature_cubemap.sample_temperature(direction);
-
-                    // Apply land temperature bonus if on land
-                    let adjusted_temp = if is_land {
-                        base_temp + land_temperature_bonus
-                    } else {
-                        base_temp
-                    };
-
-                    // Get color for the adjusted temperature
-                    let mut color = planetgen::temperature::TemperatureField::temperature_to_color(
-                        adjusted_temp,
-                        min_temp,
-                        max_temp,
-                    );
-
-                    // Darken land vertices for visual distinction
-                    if is_land {
-                        color *= 0.3; // Darken to 30%
-                    }
-
-                    [color.x, color.y, color.z, 1.0]
+    planet: Option<&PlanetData>,
+    key: OverlayCacheKey,
+    color_cache: &mut OverlayColorCache,
+    overlay_meshes: &mut OverlayMeshes,
+    planet_assets: &mut PlanetAssets,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    images: &mut Assets<Image>,
+    commands: &mut Commands,
+) {
+    let start = Instant::now();
+    let land_colors = (climate_settings, display_settings.palette);
+    let continents = continent_query.iter().map(|mesh| (mesh, true));
+    let oceans = ocean_query.iter().map(|mesh| (mesh, false));
+
+    // One texture for the land-aware continent colors and one for the plain ocean colors,
+    // made on first use
+    let texture_height = temperature_cubemap.inner.resolution as u32 * 2;
+    let mut continent_texture = None;
+    let mut ocean_texture = None;
+
+    for (mesh_handle, is_continent) in continents.chain(oceans) {
+        let Some(original_mesh) = meshes.get(&mesh_handle.0) else {
+            continue;
+        };
+
+        let texture = if display_settings.overlay_textures {
+            let texture = if is_continent {
+                continent_texture.get_or_insert_with(|| {
+                    planet_assets.add_image(images, equirect_overlay_image(texture_height, |direction| {
+                        let is_land = planet.is_some_and(|planet| !planet.is_ocean(direction));
+                        land_temperature_color(
+                            temperature_cubemap.sample_temperature(direction),
+                            is_land,
+                            land_colors,
+                        )
+                    }))
                 })
-                .collect();
-
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
-        }
-    }
-
-    // Copy normals
-    if let Some(normals_attr) = original_mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
-        if let Some(normals) = normals_attr.as_float3() {
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals.to_vec());
-        }
-    }
-
-    // Copy indices
-    if let Some(indices) = original_mesh.indices() {
-        new_mesh.insert_indices(indices.clone());
+            } else {
+                ocean_texture.get_or_insert_with(|| {
+                    planet_assets.add_image(images, equirect_overlay_image(texture_height, |direction| {
+                        let color = temperature_cubemap.sample_color(direction);
+                        [color.x, color.y, color.z, 1.0]
+                    }))
+                })
+            };
+            Some(texture.clone())
+        } else {
+            color_cache.ensure_colors(OverlayKind::Temperature, key, mesh_handle.0.id(), || {
+                if is_continent {
+                    temperature_colors(
+                        original_mesh,
+                        temperature_cubemap,
+                        |position| {
+                            planet.is_some_and(|planet| {
+                                position.length() > planet.sea_surface_radius(position)
+                            })
+                        },
+                        land_colors,
+                    )
+                } else {
+                    // Ocean gets temperature colors but no land darkening
+                    simple_temperature_colors(original_mesh, temperature_cubemap)
+                }
+            });
+            None
+        };
+        let Some(temp_mesh_handle) = overlay_meshes.copy_of(&mesh_handle.0, meshes, planet_assets) else {
+            continue;
+        };
+        // Textured copies get their colors from the material
+        let colors = texture.is_none().then_some(OverlayKind::Temperature);
+
+        // Create solid unlit material for temperature colors
+        let temp_material = planet_assets.add_material(materials, StandardMaterial {
+            base_color: Color::WHITE,
+            base_color_texture: texture,
+            unlit: true, // Show temperature colors without lighting
+            ..default()
+        });
+
+        let temp_entity = commands
+            .spawn((
+                Mesh3d(temp_mesh_handle),
+                MeshMaterial3d(temp_material),
+                Transform::default(),
+                GlobalTransform::default(),
+                Visibility::Visible,
+                TemperatureMesh,
+                TemperatureView, // Add marker for visibility control
+                OverlayMesh {
+                    source: mesh_handle.0.id(),
+                    colors,
+                },
+            ))
+            .id();
+
+        commands.entity(planet_entity).add_child(temp_entity);
     }
 
-    new_mesh
+    info!("Temperature overlay ready in {:?}", start.elapsed());
 }
 
-/// Create a simple temperature-colored mesh without edge detection (for ocean)
-fn create_simple_temperature_mesh(
-    original_mesh: &Mesh,
-    temperature_cubemap: &TemperatureCubeMap,
-) -> Mesh {
-    let mut new_mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::default(),
-    );
+/// Color of a temperature on the continent view: land gets the land bonus and is darkened
+fn land_temperature_color(
+    base_temp: f32,
+    is_land: bool,
+    (climate_settings, palette): (&ClimateSettings, Palette),
+) -> [f32; 4] {
+    // Apply land temperature bonus if on land
+    let adjusted_temp = if is_land {
+        base_temp + climate_settings.land_temperature_bonus
+    } else {
+        base_temp
+    };
 
-    // Copy positions
-    if let Some(positions_attr) = original_mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
-        if let Some(positions) = positions_attr.as_float3() {
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions.to_vec());
-
-            // Generate temperature colors without edges
-            let colors: Vec<[f32; 4]> = positions
-                .iter()
-                .map(|&[x, y, z]| {
-                    let position = Vec3::new(x, y, z);
-                    let direction = position.normalize();
-                    let color = temperature_cubemap.sample_color(direction);
-                    [color.x, color.y, color.z, 1.0]
-                })
-                .collect();
+    // Get color for the adjusted temperature
+    let mut color = TemperatureField::temperature_to_color(
+        adjusted_temp,
+        climate_settings.temperature_min_temp,
+        climate_settings.temperature_max_temp,
+        palette,
+    );
 
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
-        }
+    // Darken land vertices for visual distinction
+    if is_land {
+        color *= 0.3; // Darken to 30%
     }
 
-    // Copy normals
-    if let Some(normals_attr) = original_mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
-        if let Some(normals) = normals_attr.as_float3() {
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals.to_vec());
-        }
-    }
+    [color.x, color.y, color.z, 1.0]
+}
 
-    // Copy indices
-    if let Some(indices) = original_mesh.indices() {
-        new_mesh.insert_indices(indices.clone());
-    }
+/// Temperature-based vertex colors with the land bonus applied and land darkened.
+/// `is_land` tells whether a mesh vertex position lies above the sea surface.
+fn temperature_colors(
+    original_mesh: &Mesh,
+    temperature_cubemap: &TemperatureCubeMap,
+    is_land: impl Fn(Vec3) -> bool,
+    land_colors: (&ClimateSettings, Palette),
+) -> Vec<[f32; 4]> {
+    mesh_positions(original_mesh)
+        .iter()
+        .map(|&[x, y, z]| {
+            let position = Vec3::new(x, y, z);
+            let direction = position.normalize();
+            let is_land = is_land(position);
+
+            // Get base temperature from latitude
+            let base_temp = temperature_cubemap.sample_temperature(direction);
+            land_temperature_color(base_temp, is_land, land_colors)
+        })
+        .collect()
+}
 
-    new_mesh
+/// Temperature colors straight from the cubemap, without the land adjustments (for ocean)
+fn simple_temperature_colors(
+    original_mesh: &Mesh,
+    temperature_cubemap: &TemperatureCubeMap,
+) -> Vec<[f32; 4]> {
+    mesh_positions(original_mesh)
+        .iter()
+        .map(|&[x, y, z]| {
+            let direction = Vec3::new(x, y, z).normalize();
+            let color = temperature_cubemap.sample_color(direction);
+            [color.x, color.y, color.z, 1.0]
+        })
+        .collect()
 }
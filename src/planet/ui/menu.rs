@@ -1,6 +1,10 @@
 use crate::core::state::GameState;
+use crate::planet::randomize::RandomizationLocks;
 use crate::planet::resources::*;
 use crate::planet::ui::systems::*;
+use crate::ui::locale::Strings;
+use crate::ui::theme::UiTheme;
+use crate::ui::toasts::{Toasts, render_toasts, tick_toasts};
 use bevy::prelude::*;
 use bevy_egui::EguiPrimaryContextPass;
 
@@ -8,7 +12,15 @@ pub struct PlanetGenMenuPlugin;
 
 impl Plugin for PlanetGenMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<PlanetGenerationSettings>()
+        app.init_resource::<TerrainSettings>()
+            .init_resource::<ClimateSettings>()
+            .init_resource::<WindSettings>()
+            .init_resource::<DisplaySettings>()
+            .init_resource::<SettingsSections>()
+            .init_resource::<RandomizationLocks>()
+            .init_resource::<Toasts>()
+            .init_resource::<UiTheme>()
+            .init_resource::<Strings>()
             .add_systems(
                 OnEnter(GameState::PlanetGeneration),
                 setup_world_generation_menu,
@@ -17,9 +29,16 @@ impl Plugin for PlanetGenMenuPlugin {
                 OnExit(GameState::PlanetGeneration),
                 cleanup_world_generation_menu,
             )
+            .add_systems(
+                Update,
+                (show_generation_warnings, tick_toasts, apply_ui_theme, apply_language)
+                    .chain()
+                    .run_if(in_state(GameState::PlanetGeneration)),
+            )
             .add_systems(
                 EguiPrimaryContextPass,
-                render_planet_generation_ui
+                (render_planet_generation_ui, render_toasts)
+                    .chain()
                     .run_if(in_state(GameState::PlanetGeneration)),
             );
     }
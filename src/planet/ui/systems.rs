@@ -1,133 +1,281 @@
+use crate::app_settings::ResetAppSettingsEvent;
+use crate::audio::AudioSettings;
 use crate::planet::components::CameraRotationMode;
+use crate::planet::drift::PlateDriftState;
 use crate::planet::events::*;
-use crate::planet::resources::PlanetGenerationSettings;
+use crate::planet::help::HelpOverlay;
+use crate::planet::logic;
+use crate::planet::plate_panel::PlatesPanel;
+use crate::planet::precipitation::SeasonPlayback;
+use crate::planet::precipitation::logic::phase_month;
+use crate::planet::randomize::{self, RandomizationLocks};
+use crate::planet::resources::{
+    AllPlanetSettingsMut, ClimateSettings, CurrentPlanetData, DisplaySettings, MAX_MOONS, MoonSettings, OverlayState,
+    PendingPlanetGeneration, PlanetSettingsSnapshot, TerrainSettings, WaterLevel, WindSettings,
+};
+use crate::planet::streamlines::Streamlines;
+use crate::planet::temperature::ClimateSimState;
+use crate::planet::wind::WindDisplay;
+use crate::planet::wind_glyphs::logic::BARB_SPEED;
+use crate::ui::locale::{Language, StringId, Strings};
+use crate::ui::toasts::Toasts;
+use crate::ui::theme::{ThemePreset, UiTheme, configure_theme};
+use crate::ui::tooltips::{SettingId, configure_tooltips, setting_label};
 use bevy::app::AppExit;
-use bevy::ecs::message::MessageWriter;
+use bevy::ecs::message::{MessageReader, MessageWriter};
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, egui};
+use planetgen::prelude::{GenerationWarning, MIN_GLYPH_SPACING, Palette, WorldType};
+use std::collections::HashSet;
+
+/// Camera shortcut buttons, in order
+const CAMERA_FOCUS_BUTTONS: [(CameraFocus, StringId); 5] = [
+    (CameraFocus::Planet, "focus.planet"),
+    (CameraFocus::NorthPole, "focus.north_pole"),
+    (CameraFocus::SouthPole, "focus.south_pole"),
+    (CameraFocus::HighestPeak, "focus.highest_peak"),
+    (CameraFocus::LargestContinent, "focus.largest_continent"),
+];
+
+/// Tab bar entries, in order
+pub const VIEW_TABS: [(ViewKind, StringId); 7] = [
+    (ViewKind::Continent, "tab.continent"),
+    (ViewKind::Tectonic, "tab.tectonic"),
+    (ViewKind::Wind, "tab.wind"),
+    (ViewKind::Temperature, "tab.temperature"),
+    (ViewKind::Precipitations, "tab.precipitation"),
+    (ViewKind::Biomes, "tab.biomes"),
+    (ViewKind::CrustAge, "tab.crust_age"),
+];
+
+/// Foldable groups of sliders in the settings panel
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SettingsSection {
+    General,
+    ContinentGeneration,
+    Mountains,
+    Ocean,
+    Display,
+    Volume,
+    Plates,
+    PlateBoundaryFlow,
+    PlateVisualization,
+    PlateDrift,
+    WindSpeed,
+    WindParticles,
+    WindGlyphs,
+    Streamlines,
+    VerticalAir,
+    WindDeflection,
+    TemperatureGeneration,
+    TemperatureColorScale,
+    LandTemperature,
+    ClimateSimulation,
+    TemperatureMap,
+    CubemapResolution,
+    PrecipitationTemperature,
+    WaterAvailability,
+    Seasons,
+    BiomeThresholds,
+    BiomeColors,
+}
+
+impl SettingsSection {
+    pub fn title(self) -> StringId {
+        match self {
+            SettingsSection::General => "section.general",
+            SettingsSection::ContinentGeneration => "section.continent_generation",
+            SettingsSection::Mountains => "section.mountains",
+            SettingsSection::Ocean => "section.ocean",
+            SettingsSection::Display => "section.display",
+            SettingsSection::Volume => "section.volume",
+            SettingsSection::Plates => "section.plates",
+            SettingsSection::PlateBoundaryFlow => "section.plate_boundary_flow",
+            SettingsSection::PlateVisualization => "section.plate_visualization",
+            SettingsSection::PlateDrift => "section.plate_drift",
+            SettingsSection::WindSpeed => "section.wind_speed",
+            SettingsSection::WindParticles => "section.wind_particles",
+            SettingsSection::WindGlyphs => "section.wind_glyphs",
+            SettingsSection::Streamlines => "section.streamlines",
+            SettingsSection::VerticalAir => "section.vertical_air",
+            SettingsSection::WindDeflection => "section.wind_deflection",
+            SettingsSection::TemperatureGeneration => "section.temperature_generation",
+            SettingsSection::TemperatureColorScale => "section.temperature_color_scale",
+            SettingsSection::LandTemperature => "section.land_temperature",
+            SettingsSection::ClimateSimulation => "section.climate_simulation",
+            SettingsSection::TemperatureMap => "section.temperature_map",
+            SettingsSection::CubemapResolution => "section.cubemap_resolution",
+            SettingsSection::PrecipitationTemperature => "section.precipitation_temperature",
+            SettingsSection::WaterAvailability => "section.water_availability",
+            SettingsSection::Seasons => "section.seasons",
+            SettingsSection::BiomeThresholds => "section.biome_thresholds",
+            SettingsSection::BiomeColors => "section.biome_colors",
+        }
+    }
+}
 
-#[derive(Resource, Default, Clone, PartialEq)]
-pub enum ViewTab {
-    #[default]
-    Continent,
-    Tectonic,
-    Wind,
-    Temperature,
-    Precipitations,
-    Biomes,
+/// Which settings sections the user folded away. Everything starts expanded, and the state
+/// survives tab switches and leaving the planet generation screen.
+#[derive(Resource, Default)]
+pub struct SettingsSections {
+    collapsed: HashSet<SettingsSection>,
 }
 
+impl SettingsSections {
+    pub fn is_open(&self, section: SettingsSection) -> bool {
+        !self.collapsed.contains(&section)
+    }
+
+    pub fn toggle(&mut self, section: SettingsSection) {
+        if !self.collapsed.remove(&section) {
+            self.collapsed.insert(section);
+        }
+    }
+}
+
+/// Whether the mouse is over the egui panels, so the 3D view doesn't react to
+/// drags and scrolling meant for the UI. Updated every frame by the UI pass from egui's own hit
+/// test of the panels and windows, so it follows their actual size, and held while the help
+/// overlay covers the view. Turning, zooming and picking all go by it.
+#[derive(Resource, Default)]
+pub struct PointerOverUi(pub bool);
+
 pub fn setup_world_generation_menu(mut commands: Commands) {
-    commands.init_resource::<ViewTab>();
+    commands.init_resource::<OverlayState>();
     commands.init_resource::<CameraRotationMode>();
+    commands.init_resource::<PointerOverUi>();
 }
 
 pub fn cleanup_world_generation_menu(mut commands: Commands) {
-    commands.remove_resource::<ViewTab>();
+    commands.remove_resource::<OverlayState>();
     commands.remove_resource::<CameraRotationMode>();
+    commands.remove_resource::<PointerOverUi>();
+}
+
+/// Switch the UI to the theme picked in the settings
+pub fn apply_ui_theme(settings: Res<DisplaySettings>, mut theme: ResMut<UiTheme>) {
+    if theme.preset != settings.ui_theme {
+        *theme = UiTheme::preset(settings.ui_theme);
+    }
+}
+
+/// Switch the UI to the language picked in the settings
+pub fn apply_language(settings: Res<DisplaySettings>, mut strings: ResMut<Strings>) {
+    if strings.language() != settings.language {
+        *strings = Strings::load(settings.language);
+    }
+}
+
+/// Tell the user why a planet may look odd. Previews are skipped, the full planet reports once it's in.
+pub fn show_generation_warnings(
+    mut events: MessageReader<PlanetSpawnedEvent>,
+    pending_generation: Res<PendingPlanetGeneration>,
+    current_planet_data: Res<CurrentPlanetData>,
+    mut toasts: ResMut<Toasts>,
+    strings: Res<Strings>,
+) {
+    if events.read().count() == 0 || pending_generation.task.is_some() {
+        return;
+    }
+    let Some(planet_data) = current_planet_data.planet_data.as_ref() else {
+        return;
+    };
+
+    for warning in &planet_data.report.warnings {
+        warn!("Generated planet: {warning}");
+        toasts.push(warning_text(warning, &strings));
+    }
+}
+
+/// Toast of a generation warning in the chosen language, the log keeps the English one
+fn warning_text(warning: &GenerationWarning, strings: &Strings) -> String {
+    let percent = |fraction: f32| format!("{:.0}", fraction * 100.0);
+    match *warning {
+        GenerationWarning::MostlyOcean { land_fraction } => {
+            strings.format("warning.mostly_ocean", &[("percent", &percent(1.0 - land_fraction))])
+        }
+        GenerationWarning::MostlyLand { land_fraction } => {
+            strings.format("warning.mostly_land", &[("percent", &percent(land_fraction))])
+        }
+        GenerationWarning::TinyPlate { plate_id, cells } => {
+            strings.format("warning.tiny_plate", &[("plate", &plate_id), ("cells", &cells)])
+        }
+        GenerationWarning::SinglePlate => strings.get("warning.single_plate").to_string(),
+        GenerationWarning::OnlyConvergentBoundaries => strings.get("warning.only_convergent").to_string(),
+        GenerationWarning::InvalidHeights { cells } => strings.format("warning.invalid_heights", &[("cells", &cells)]),
+    }
 }
 
 pub fn render_planet_generation_ui(
     mut contexts: EguiContexts,
-    mut settings: ResMut<PlanetGenerationSettings>,
-    mut view_tab: ResMut<ViewTab>,
+    mut planet_settings: AllPlanetSettingsMut,
+    (overlay_state, water_level, theme, current_planet, strings): (
+        Res<OverlayState>,
+        Res<WaterLevel>,
+        Res<UiTheme>,
+        Res<CurrentPlanetData>,
+        Res<Strings>,
+    ),
+    (mut sections, mut help, mut audio_settings): (
+        ResMut<SettingsSections>,
+        ResMut<HelpOverlay>,
+        ResMut<AudioSettings>,
+    ),
+    mut locks: ResMut<RandomizationLocks>,
+    (mut climate_sim, mut season_playback, mut streamlines): (
+        ResMut<ClimateSimState>,
+        ResMut<SeasonPlayback>,
+        ResMut<Streamlines>,
+    ),
+    mut plate_drift: ResMut<PlateDriftState>,
+    mut plates_panel: ResMut<PlatesPanel>,
+    mut pointer_over_ui: ResMut<PointerOverUi>,
     mut planet_generation_events: MessageWriter<GeneratePlanetEvent>,
-    mut generate_new_seed_events: MessageWriter<GenerateNewSeedEvent>,
-    mut tab_switch_events: MessageWriter<TabSwitchEvent>,
-    mut wind_tab_events: MessageWriter<WindTabActiveEvent>,
-    mut temperature_tab_events: MessageWriter<TemperatureTabActiveEvent>,
-    mut precipitation_tab_events: MessageWriter<PrecipitationTabActiveEvent>,
+    (mut generate_new_seed_events, mut reroll_planet_events): (
+        MessageWriter<GenerateNewSeedEvent>,
+        MessageWriter<RerollPlanetEvent>,
+    ),
+    mut rescale_planet_events: MessageWriter<RescalePlanetEvent>,
+    mut set_active_view: MessageWriter<SetActiveView>,
+    mut focus_camera_events: MessageWriter<FocusCameraEvent>,
+    mut reset_app_settings_events: MessageWriter<ResetAppSettingsEvent>,
     mut app_exit_events: MessageWriter<AppExit>,
 ) {
     let Ok(ctx) = contexts.ctx_mut() else {
         return;
     };
+    configure_tooltips(ctx);
+    configure_theme(ctx, &theme);
+    // The panel edits a copy, written back below so only the edited settings count as changed
+    let mut settings = planet_settings.snapshot();
+    // The climate cubemaps follow the generated grid, before the first planet the one Generate will use
+    let face_grid_size = current_planet
+        .planet_data
+        .as_ref()
+        .map_or_else(|| logic::grid_budget(settings.terrain.radius).effective, |planet| planet.face_grid_size);
 
     egui::SidePanel::right("settings_panel")
         .default_width(350.0)
         .resizable(true)
         .show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
-                ui.heading("Planet Settings");
+                ui.horizontal(|ui| {
+                    ui.heading(strings.get("settings.heading"));
+                    if ui.button("?").on_hover_text(strings.get("settings.help_button.tooltip")).clicked() {
+                        help.toggle();
+                    }
+                });
                 ui.add_space(10.0);
 
                 // View tabs
                 ui.horizontal(|ui| {
-                    let mut tab_changed = false;
-                    let old_tab = (*view_tab).clone();
-
-                    if ui
-                        .selectable_label(*view_tab == ViewTab::Continent, "Continent")
-                        .clicked()
-                    {
-                        *view_tab = ViewTab::Continent;
-                        tab_changed = old_tab != *view_tab;
-                    }
-                    if ui
-                        .selectable_label(*view_tab == ViewTab::Tectonic, "Tectonic")
-                        .clicked()
-                    {
-                        *view_tab = ViewTab::Tectonic;
-                        tab_changed = old_tab != *view_tab;
-                    }
-                    if ui
-                        .selectable_label(*view_tab == ViewTab::Wind, "Wind")
-                        .clicked()
-                    {
-                        *view_tab = ViewTab::Wind;
-                        tab_changed = old_tab != *view_tab;
-                    }
-                    if ui
-                        .selectable_label(*view_tab == ViewTab::Temperature, "Temp")
-                        .clicked()
-                    {
-                        *view_tab = ViewTab::Temperature;
-                        tab_changed = old_tab != *view_tab;
-                    }
-                    if ui
-                        .selectable_label(*view_tab == ViewTab::Precipitations, "Precip")
-                        .clicked()
-                    {
-                        *view_tab = ViewTab::Precipitations;
-                        tab_changed = old_tab != *view_tab;
-                    }
-                    if ui
-                        .selectable_label(*view_tab == ViewTab::Biomes, "Biomes")
-                        .clicked()
-                    {
-                        *view_tab = ViewTab::Biomes;
-                        tab_changed = old_tab != *view_tab;
-                    }
-
-                    // Update visibility when tab changes
-                    if tab_changed {
-                        // Emit ONE event with the target tab
-                        let tab_type = match *view_tab {
-                            ViewTab::Continent => ViewTabType::Continent,
-                            ViewTab::Tectonic => ViewTabType::Tectonic,
-                            ViewTab::Wind => ViewTabType::Wind,
-                            ViewTab::Temperature => ViewTabType::Temperature,
-                            ViewTab::Precipitations => ViewTabType::Precipitations,
-                            ViewTab::Biomes => ViewTabType::Biomes,
-                        };
-
-                        tab_switch_events.write(TabSwitchEvent { tab: tab_type });
-
-                        // Emit wind event for particle spawning/despawning
-                        let is_wind = *view_tab == ViewTab::Wind;
-                        wind_tab_events.write(WindTabActiveEvent { active: is_wind });
-
-                        // Emit temperature event for mesh generation
-                        let is_temperature = *view_tab == ViewTab::Temperature;
-                        temperature_tab_events.write(TemperatureTabActiveEvent {
-                            active: is_temperature,
-                        });
-
-                        // Emit precipitation event for mesh generation
-                        let is_precipitation = *view_tab == ViewTab::Precipitations;
-                        precipitation_tab_events.write(PrecipitationTabActiveEvent {
-                            active: is_precipitation,
-                        });
+                    // The overlays and the visibility follow the message, the highlight
+                    // moves once it's applied to OverlayState
+                    for (view, label) in VIEW_TABS {
+                        let selected = overlay_state.active == view;
+                        if ui.selectable_label(selected, strings.get(label)).clicked() && !selected {
+                            set_active_view.write(SetActiveView(view));
+                        }
                     }
                 });
 
@@ -136,35 +284,75 @@ pub fn render_planet_generation_ui(
                 ui.add_space(10.0);
 
                 // Tab-specific content
-                match *view_tab {
-                    ViewTab::Continent => {
+                match overlay_state.active {
+                    ViewKind::Continent => {
                         // Continent tab content
                         render_continent_tab(
                             ui,
+                            &strings,
                             &mut settings,
+                            *water_level,
+                            &mut sections,
+                            &mut locks,
                             &mut generate_new_seed_events,
+                            &mut reroll_planet_events,
+                            &mut rescale_planet_events,
                             &mut planet_generation_events,
                         );
                     }
-                    ViewTab::Tectonic => {
+                    ViewKind::Tectonic => {
                         // Tectonic tab content
-                        render_tectonic_tab(ui, &mut settings);
+                        render_tectonic_tab(
+                            ui,
+                            &strings,
+                            (&mut settings.terrain, &mut settings.display),
+                            &mut sections,
+                            &mut locks,
+                            &mut plate_drift,
+                            &mut plates_panel,
+                        );
                     }
-                    ViewTab::Wind => {
+                    ViewKind::Wind => {
                         // Wind tab content
-                        render_wind_tab(ui, &mut settings);
+                        render_wind_tab(
+                            ui,
+                            &strings,
+                            (&mut settings.wind, &mut settings.display),
+                            &mut sections,
+                            &mut streamlines,
+                        );
                     }
-                    ViewTab::Temperature => {
+                    ViewKind::Temperature => {
                         // Temperature tab content
-                        render_temperature_tab(ui, &mut settings);
+                        render_temperature_tab(
+                            ui,
+                            &strings,
+                            &mut settings.climate,
+                            &mut sections,
+                            &mut locks,
+                            &mut climate_sim,
+                            face_grid_size,
+                        );
                     }
-                    ViewTab::Precipitations => {
+                    ViewKind::Precipitations => {
                         // Precipitations tab content
-                        render_precipitation_tab(ui, &mut settings);
+                        render_precipitation_tab(
+                            ui,
+                            &strings,
+                            &mut settings.climate,
+                            &mut sections,
+                            &mut locks,
+                            &mut season_playback,
+                            face_grid_size,
+                        );
                     }
-                    ViewTab::Biomes => {
+                    ViewKind::Biomes => {
                         // Biomes dev tab content
-                        render_biomes_tab(ui, &mut settings);
+                        render_biomes_tab(ui, &strings, &mut settings.climate, &mut sections);
+                    }
+                    ViewKind::CrustAge => {
+                        // Crust age tab content
+                        render_crust_age_tab(ui, &strings);
                     }
                 }
 
@@ -172,29 +360,181 @@ pub fn render_planet_generation_ui(
                 ui.separator();
                 ui.add_space(10.0);
 
-                if ui.button("Quit").clicked() {
+                // Shown on every tab, the volumes aren't part of any view
+                render_volume_section(ui, &strings, &mut audio_settings, &mut sections);
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(10.0);
+
+                ui.label(strings.get("focus.heading"));
+                ui.horizontal_wrapped(|ui| {
+                    for (focus, label) in CAMERA_FOCUS_BUTTONS {
+                        if ui.button(strings.get(label)).clicked() {
+                            focus_camera_events.write(FocusCameraEvent(focus));
+                        }
+                    }
+                });
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(10.0);
+
+                if ui
+                    .button(strings.get("settings.reset"))
+                    .on_hover_text(strings.get("settings.reset.tooltip"))
+                    .clicked()
+                {
+                    reset_app_settings_events.write(ResetAppSettingsEvent);
+                }
+
+                if ui.button(strings.get("settings.quit")).clicked() {
                     app_exit_events.write(AppExit::Success);
                 }
             });
         });
+
+    planet_settings.apply(settings);
+
+    // Also true while dragging a slider past the panel edge
+    pointer_over_ui.0 = ctx.is_pointer_over_area() || ctx.is_using_pointer();
+}
+
+/// Master, music and ambient volumes, the audio plugin applies them to the playing channels
+fn render_volume_section(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    audio_settings: &mut AudioSettings,
+    sections: &mut SettingsSections,
+) {
+    settings_section(ui, strings, sections, SettingsSection::Volume, |ui| {
+        ui.checkbox(&mut audio_settings.muted, strings.get("volume.mute"))
+            .on_hover_text(strings.get("volume.mute.tooltip"));
+
+        ui.add_enabled_ui(!audio_settings.muted, |ui| {
+            setting_label(ui, strings, SettingId::MasterVolume);
+            ui.add(egui::Slider::new(&mut audio_settings.master_volume, 0.0..=1.0).step_by(0.01));
+
+            volume_row(
+                ui,
+                strings,
+                SettingId::MusicVolume,
+                &mut audio_settings.music_volume,
+                &mut audio_settings.music_muted,
+            );
+            volume_row(
+                ui,
+                strings,
+                SettingId::AmbientVolume,
+                &mut audio_settings.ambient_volume,
+                &mut audio_settings.ambient_muted,
+            );
+        });
+    });
+}
+
+/// Channel volume slider with its own mute checkbox next to the label
+fn volume_row(ui: &mut egui::Ui, strings: &Strings, id: SettingId, volume: &mut f32, muted: &mut bool) {
+    ui.horizontal(|ui| {
+        setting_label(ui, strings, id);
+        ui.checkbox(muted, "🔇")
+            .on_hover_text(strings.get("volume.mute_channel.tooltip"));
+    });
+    ui.add_enabled(!*muted, egui::Slider::new(volume, 0.0..=1.0).step_by(0.01));
+}
+
+/// Setting label with a lock checkbox, a locked setting keeps its value on "Surprise Me"
+fn randomizable_label(ui: &mut egui::Ui, strings: &Strings, id: SettingId, locks: &mut RandomizationLocks) {
+    ui.horizontal(|ui| {
+        setting_label(ui, strings, id);
+        let mut locked = locks.is_locked(id);
+        if ui
+            .checkbox(&mut locked, "🔒")
+            .on_hover_text(strings.get("settings.lock.tooltip"))
+            .changed()
+        {
+            locks.set(id, locked);
+        }
+    });
+}
+
+/// Heading with an arrow that folds its contents away, open state kept in `SettingsSections`
+fn settings_section(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    sections: &mut SettingsSections,
+    section: SettingsSection,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) {
+    let response = egui::CollapsingHeader::new(egui::RichText::new(strings.get(section.title())).heading())
+        .id_salt(section)
+        .open(Some(sections.is_open(section)))
+        .show(ui, add_contents);
+
+    if response.header_response.clicked() {
+        sections.toggle(section);
+    }
 }
 
 fn render_continent_tab(
     ui: &mut egui::Ui,
-    settings: &mut PlanetGenerationSettings,
+    strings: &Strings,
+    settings: &mut PlanetSettingsSnapshot,
+    water_level: WaterLevel,
+    sections: &mut SettingsSections,
+    locks: &mut RandomizationLocks,
     generate_new_seed_events: &mut MessageWriter<GenerateNewSeedEvent>,
+    reroll_planet_events: &mut MessageWriter<RerollPlanetEvent>,
+    rescale_planet_events: &mut MessageWriter<RescalePlanetEvent>,
     planet_generation_events: &mut MessageWriter<GeneratePlanetEvent>,
 ) {
     // Seed section
-    ui.heading("General");
-    ui.add_space(5.0);
-
-    ui.label("Seed");
-    ui.horizontal(|ui| {
-        ui.label(settings.user_seed.to_string());
-        if ui.button("Random").clicked() {
-            generate_new_seed_events.write(GenerateNewSeedEvent);
+    settings_section(ui, strings, sections, SettingsSection::General, |ui| {
+        ui.label(strings.get("general.seed"));
+        ui.horizontal(|ui| {
+            ui.label(settings.terrain.user_seed.to_string());
+            if ui.button(strings.get("general.random_seed")).clicked() {
+                generate_new_seed_events.write(GenerateNewSeedEvent);
+            }
+        });
+        ui.label(strings.format(
+            "general.sub_seeds",
+            &[("plates", &settings.terrain.plate_seed), ("terrain", &settings.terrain.terrain_seed)],
+        ))
+        .on_hover_text(strings.get("general.sub_seeds.tooltip"));
+
+        ui.add_space(5.0);
+
+        // Resizes the existing planet live, Generate is only needed for new terrain
+        setting_label(ui, strings, SettingId::PlanetRadius);
+        if ui
+            .add(
+                egui::Slider::new(&mut settings.terrain.radius, logic::MIN_PLANET_RADIUS..=logic::MAX_PLANET_RADIUS)
+                    .step_by(0.5),
+            )
+            .changed()
+        {
+            rescale_planet_events.write(RescalePlanetEvent {
+                radius: settings.terrain.radius,
+            });
         }
+        grid_budget_label(ui, strings, logic::grid_budget(settings.terrain.radius));
+
+        randomizable_label(ui, strings, SettingId::Oblateness, locks);
+        ui.add(egui::Slider::new(&mut settings.terrain.oblateness, 0.0..=0.1).step_by(0.0001));
+
+        setting_label(ui, strings, SettingId::WorldType);
+        egui::ComboBox::from_id_salt("world_type")
+            .selected_text(strings.get(world_type_name(settings.terrain.world_type)))
+            .show_ui(ui, |ui| {
+                for world_type in [WorldType::Terran, WorldType::Barren] {
+                    ui.selectable_value(&mut settings.terrain.world_type, world_type, strings.get(world_type_name(world_type)));
+                }
+            });
+
+        ui.add_space(5.0);
+        ui.checkbox(&mut settings.display.rotation_inertia, strings.get("general.keep_spinning"));
+        ui.checkbox(&mut settings.display.keep_view, strings.get("general.keep_view"));
     });
 
     ui.add_space(10.0);
@@ -202,54 +542,245 @@ fn render_continent_tab(
     ui.add_space(10.0);
 
     // Generate Planet button (only on Continent tab)
-    if ui.button("Generate Planet").clicked() {
-        planet_generation_events.write(GeneratePlanetEvent);
-    }
+    ui.horizontal(|ui| {
+        if ui.button(strings.get("generate.generate_planet")).clicked() {
+            planet_generation_events.write(GeneratePlanetEvent);
+        }
+        if ui
+            .button(strings.get("generate.surprise_me"))
+            .on_hover_text(strings.get("generate.surprise_me.tooltip"))
+            .clicked()
+        {
+            let profile = planetgen::config::get_config().randomization;
+            randomize::logic::randomize_settings(settings, &profile, locks.locked(), &mut rand::rng());
+            planet_generation_events.write(GeneratePlanetEvent);
+        }
+    });
+    ui.horizontal(|ui| {
+        if ui
+            .button(strings.get("generate.reroll_terrain"))
+            .on_hover_text(strings.get("generate.reroll_terrain.tooltip"))
+            .clicked()
+        {
+            reroll_planet_events.write(RerollPlanetEvent::Terrain);
+        }
+        if ui
+            .button(strings.get("generate.reroll_plates"))
+            .on_hover_text(strings.get("generate.reroll_plates.tooltip"))
+            .clicked()
+        {
+            reroll_planet_events.write(RerollPlanetEvent::Plates);
+        }
+    });
+    ui.checkbox(&mut settings.terrain.preview_generation, strings.get("generate.preview"));
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
     // Continent generation settings
-    ui.heading("Continent Generation");
-    ui.add_space(5.0);
+    settings_section(ui, strings, sections, SettingsSection::ContinentGeneration, |ui| {
+        randomizable_label(ui, strings, SettingId::DistortionFrequency, locks);
+        ui.add(egui::Slider::new(&mut settings.terrain.distortion_frequency, 1.0..=10.0).step_by(0.1));
+
+        randomizable_label(ui, strings, SettingId::DistortionAmplitude, locks);
+        ui.add(egui::Slider::new(&mut settings.terrain.distortion_amplitude, 0.0..=1.0).step_by(0.01));
+
+        randomizable_label(ui, strings, SettingId::ContinentThreshold, locks);
+        ui.add(egui::Slider::new(&mut settings.terrain.continent_threshold, -1.0..=1.0).step_by(0.01));
+        // The water belongs to the generated planet, the slider only shapes the next one
+        if let Some(sea_level) = water_level.0
+            && sea_level != settings.terrain.continent_threshold
+        {
+            ui.weak(strings.format("continent.sea_level_pending", &[("level", &format!("{sea_level:.2}"))]));
+        }
+
+        randomizable_label(ui, strings, SettingId::DetailFrequency, locks);
+        ui.add(egui::Slider::new(&mut settings.terrain.detail_frequency, 5.0..=20.0).step_by(0.1));
 
-    ui.label("Continent Distortion Frequency");
-    ui.add(egui::Slider::new(&mut settings.distortion_frequency, 1.0..=10.0).step_by(0.1));
+        randomizable_label(ui, strings, SettingId::DetailAmplitude, locks);
+        ui.add(egui::Slider::new(&mut settings.terrain.detail_amplitude, 0.05..=0.5).step_by(0.01));
+    });
 
-    ui.label("Continent Distortion Strength");
-    ui.add(egui::Slider::new(&mut settings.distortion_amplitude, 0.0..=1.0).step_by(0.01));
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
 
-    ui.label("Ocean Coverage");
-    ui.add(egui::Slider::new(&mut settings.continent_threshold, -1.0..=1.0).step_by(0.01));
+    // Mountain settings
+    settings_section(ui, strings, sections, SettingsSection::Mountains, |ui| {
+        setting_label(ui, strings, SettingId::SnowThreshold);
+        ui.add(egui::Slider::new(&mut settings.terrain.snow_threshold, 0.5..=4.0).step_by(0.01));
 
-    ui.label("Continent Shore Distortion Frequency");
-    ui.add(egui::Slider::new(&mut settings.detail_frequency, 5.0..=20.0).step_by(0.1));
+        randomizable_label(ui, strings, SettingId::MountainHeight, locks);
+        ui.add(egui::Slider::new(&mut settings.terrain.mountain_height, 2.0..=5.0).step_by(0.01));
 
-    ui.label("Continent Shore Distortion Scale");
-    ui.add(egui::Slider::new(&mut settings.detail_amplitude, 0.05..=0.5).step_by(0.01));
+        randomizable_label(ui, strings, SettingId::MountainWidth, locks);
+        ui.add(egui::Slider::new(&mut settings.terrain.mountain_width, 0.03..=0.25).step_by(0.001));
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    // Mountain settings
-    ui.heading("Mountain Settings");
-    ui.add_space(5.0);
+    // Ocean appearance, applied to the current planet without regenerating
+    settings_section(ui, strings, sections, SettingsSection::Ocean, |ui| {
+        setting_label(ui, strings, SettingId::OceanFoamWidth);
+        ui.add(egui::Slider::new(&mut settings.display.ocean_foam_width, 0.0..=0.3).step_by(0.005));
 
-    ui.label("Mountain Snow Threshold");
-    ui.add(egui::Slider::new(&mut settings.snow_threshold, 0.5..=4.0).step_by(0.01));
+        biome_color_row(ui, strings, "ocean.foam_color", &mut settings.display.ocean_foam_color);
+    });
 
-    ui.label("Mountain Height");
-    ui.add(egui::Slider::new(&mut settings.mountain_height, 2.0..=5.0).step_by(0.01));
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    // Relief shading of the terrain colors, the rotation axis and the moons, applied without regenerating
+    settings_section(ui, strings, sections, SettingsSection::Display, |ui| {
+        setting_label(ui, strings, SettingId::HillshadeStrength);
+        ui.add(egui::Slider::new(&mut settings.display.hillshade_strength, 0.0..=1.0).step_by(0.01));
+
+        setting_label(ui, strings, SettingId::HillshadeAzimuth);
+        ui.add(egui::Slider::new(&mut settings.display.hillshade_azimuth, 0.0..=360.0).step_by(1.0));
+
+        setting_label(ui, strings, SettingId::HillshadeElevation);
+        ui.add(egui::Slider::new(&mut settings.display.hillshade_elevation, 5.0..=90.0).step_by(1.0));
 
-    ui.label("Mountain Width");
-    ui.add(egui::Slider::new(&mut settings.mountain_width, 0.03..=0.25).step_by(0.001));
+        setting_label(ui, strings, SettingId::ColorVariation);
+        ui.add(egui::Slider::new(&mut settings.display.color_variation_strength, 0.0..=0.15).step_by(0.005));
+
+        ui.add_space(8.0);
+
+        ui.checkbox(&mut settings.display.overlay_textures, strings.get("display.sharp_overlays"))
+            .on_hover_text(strings.get("display.sharp_overlays.tooltip"));
+        setting_label(ui, strings, SettingId::Palette);
+        egui::ComboBox::from_id_salt("palette")
+            .selected_text(strings.get(palette_name(settings.display.palette)))
+            .show_ui(ui, |ui| {
+                for palette in Palette::ALL {
+                    ui.selectable_value(&mut settings.display.palette, palette, strings.get(palette_name(palette)));
+                }
+            });
+        setting_label(ui, strings, SettingId::UiTheme);
+        egui::ComboBox::from_id_salt("ui_theme")
+            .selected_text(strings.get(settings.display.ui_theme.label()))
+            .show_ui(ui, |ui| {
+                for preset in ThemePreset::ALL {
+                    ui.selectable_value(&mut settings.display.ui_theme, preset, strings.get(preset.label()));
+                }
+            });
+        setting_label(ui, strings, SettingId::Language);
+        egui::ComboBox::from_id_salt("language")
+            .selected_text(settings.display.language.native_name())
+            .show_ui(ui, |ui| {
+                for language in Language::ALL {
+                    ui.selectable_value(&mut settings.display.language, language, language.native_name());
+                }
+            });
+        setting_label(ui, strings, SettingId::CameraOffset);
+        ui.add(egui::Slider::new(&mut settings.display.camera_offset, 0.0..=0.5).step_by(0.01));
+        setting_label(ui, strings, SettingId::LookAtOffset);
+        ui.add(egui::Slider::new(&mut settings.display.look_at_offset, 0.0..=0.5).step_by(0.01));
+        ui.checkbox(&mut settings.display.show_axis, strings.get("display.axis"));
+        ui.checkbox(&mut settings.display.show_feature_labels, strings.get("display.feature_names"))
+            .on_hover_text(strings.get("display.feature_names.tooltip"));
+        ui.checkbox(&mut settings.display.show_coastline, strings.get("display.coastline"))
+            .on_hover_text(strings.get("display.coastline.tooltip"));
+        randomizable_label(ui, strings, SettingId::AxialTilt, locks);
+        ui.add(egui::Slider::new(&mut settings.display.axial_tilt, 0.0..=90.0).step_by(0.5));
+
+        ui.add_space(8.0);
+
+        setting_label(ui, strings, SettingId::MoonCount);
+        let mut moon_count = settings.display.moons.len();
+        if ui.add(egui::Slider::new(&mut moon_count, 0..=MAX_MOONS)).changed() {
+            settings.display.moons.truncate(moon_count);
+            while settings.display.moons.len() < moon_count {
+                settings.display.moons.push(MoonSettings::nth(settings.display.moons.len()));
+            }
+        }
+        for (index, moon) in settings.display.moons.iter_mut().enumerate() {
+            ui.add_space(4.0);
+            ui.label(egui::RichText::new(strings.format("display.moon", &[("number", &(index + 1))])).strong());
+
+            setting_label(ui, strings, SettingId::MoonRadius);
+            ui.add(egui::Slider::new(&mut moon.radius, 0.05..=0.5).step_by(0.01));
+
+            setting_label(ui, strings, SettingId::MoonOrbitRadius);
+            ui.add(egui::Slider::new(&mut moon.orbit_radius, 2.0..=12.0).step_by(0.1));
+
+            setting_label(ui, strings, SettingId::MoonOrbitPeriod);
+            ui.add(egui::Slider::new(&mut moon.orbit_period, 5.0..=600.0).step_by(1.0));
+
+            setting_label(ui, strings, SettingId::MoonInclination);
+            ui.add(egui::Slider::new(&mut moon.inclination, 0.0..=90.0).step_by(0.5));
+        }
+    });
+}
+
+fn world_type_name(world_type: WorldType) -> StringId {
+    match world_type {
+        WorldType::Terran => "world_type.terran",
+        WorldType::Barren => "world_type.barren",
+    }
 }
 
-fn biome_color_row(ui: &mut egui::Ui, label: &str, color: &mut [f32; 3]) {
+fn palette_name(palette: Palette) -> StringId {
+    match palette {
+        Palette::Default => "palette.default",
+        Palette::Viridis => "palette.viridis",
+        Palette::Cividis => "palette.cividis",
+        Palette::HighContrast => "palette.high_contrast",
+    }
+}
+
+/// Grid and vertex count the next Generate will use, red when the radius asks for more than
+/// the vertex budget and the cells get coarser instead
+fn grid_budget_label(ui: &mut egui::Ui, strings: &Strings, budget: logic::GridBudget) {
+    let text = strings.format(
+        "general.grid_budget",
+        &[("size", &budget.effective), ("thousands", &(budget.vertex_count() / 1000))],
+    );
+    if budget.is_clamped() {
+        ui.colored_label(egui::Color32::LIGHT_RED, text)
+            .on_hover_text(strings.format("general.grid_budget.capped", &[("size", &budget.requested)]));
+    } else {
+        ui.weak(text);
+    }
+}
+
+/// Resolution a climate cubemap follows from the planet's grid, or a slider for it once overridden.
+/// Overriding starts from the current resolution, so the map doesn't change until the slider moves.
+fn cubemap_resolution_row(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    label: StringId,
+    resolution: usize,
+    override_resolution: &mut Option<usize>,
+) {
+    let label = strings.get(label);
     ui.horizontal(|ui| {
-        ui.label(label);
+        let mut overridden = override_resolution.is_some();
+        if ui
+            .checkbox(&mut overridden, strings.get("cubemap.override"))
+            .on_hover_text(strings.get("cubemap.override.tooltip"))
+            .changed()
+        {
+            *override_resolution = overridden.then_some(resolution);
+        }
+        match override_resolution {
+            Some(resolution) => {
+                ui.add(egui::Slider::new(resolution, 16..=256).text(label));
+            }
+            None => {
+                ui.weak(strings.format("cubemap.per_face", &[("label", &label), ("size", &resolution)]));
+            }
+        }
+    });
+}
+
+fn biome_color_row(ui: &mut egui::Ui, strings: &Strings, label: StringId, color: &mut [f32; 3]) {
+    ui.horizontal(|ui| {
+        ui.label(strings.get(label));
         let mut srgb = [
             (color[0] * 255.0) as u8,
             (color[1] * 255.0) as u8,
@@ -263,325 +794,529 @@ fn biome_color_row(ui: &mut egui::Ui, label: &str, color: &mut [f32; 3]) {
     });
 }
 
-fn render_biomes_tab(ui: &mut egui::Ui, settings: &mut PlanetGenerationSettings) {
-    ui.heading("Biome Thresholds");
+fn render_crust_age_tab(ui: &mut egui::Ui, strings: &Strings) {
     ui.add_space(5.0);
+    ui.label(strings.get("crust_age.description"));
+    ui.add_space(5.0);
+    for (color, text) in [
+        (egui::Color32::from_rgb(217, 38, 26), "crust_age.young"),
+        (egui::Color32::from_rgb(26, 64, 166), "crust_age.oldest"),
+        (egui::Color32::from_rgb(140, 140, 140), "crust_age.continental"),
+    ] {
+        ui.horizontal(|ui| {
+            ui.colored_label(color, "■");
+            ui.label(strings.get(text));
+        });
+    }
+    ui.add_space(5.0);
+    ui.weak(strings.get("crust_age.subsidence_note"));
+}
 
-    ui.label("Ice Temp");
-    ui.add(
-        egui::Slider::new(&mut settings.biome_ice_temp, -50.0..=50.0)
-            .step_by(1.0)
-            .suffix("\u{00b0}C"),
-    );
-    ui.label("Tundra Temp");
-    ui.add(
-        egui::Slider::new(&mut settings.biome_tundra_temp, -50.0..=50.0)
-            .step_by(1.0)
-            .suffix("\u{00b0}C"),
-    );
-    ui.label("Boreal Temp");
-    ui.add(
-        egui::Slider::new(&mut settings.biome_boreal_temp, -50.0..=50.0)
-            .step_by(1.0)
-            .suffix("\u{00b0}C"),
-    );
-    ui.label("Temperate Temp");
-    ui.add(
-        egui::Slider::new(&mut settings.biome_temperate_temp, -50.0..=50.0)
-            .step_by(1.0)
-            .suffix("\u{00b0}C"),
-    );
-    ui.label("Hot Temp");
-    ui.add(
-        egui::Slider::new(&mut settings.biome_hot_temp, -50.0..=50.0)
-            .step_by(1.0)
-            .suffix("\u{00b0}C"),
-    );
-
-    ui.add_space(8.0);
-
-    ui.label("Desert Precip");
-    ui.add(egui::Slider::new(&mut settings.biome_desert_precip, 0.0..=1.0).step_by(0.01));
-    ui.label("Savanna Precip");
-    ui.add(egui::Slider::new(&mut settings.biome_savanna_precip, 0.0..=1.0).step_by(0.01));
-    ui.label("Jungle Precip");
-    ui.add(egui::Slider::new(&mut settings.biome_jungle_precip, 0.0..=1.0).step_by(0.01));
-    ui.label("Temperate Precip");
-    ui.add(egui::Slider::new(&mut settings.biome_temperate_precip, 0.0..=1.0).step_by(0.01));
+fn render_biomes_tab(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    settings: &mut ClimateSettings,
+    sections: &mut SettingsSections,
+) {
+    settings_section(ui, strings, sections, SettingsSection::BiomeThresholds, |ui| {
+        setting_label(ui, strings, SettingId::BiomeIceTemperature);
+        ui.add(
+            egui::Slider::new(&mut settings.biome_ice_temp, -50.0..=50.0)
+                .step_by(1.0)
+                .suffix("\u{00b0}C"),
+        );
+        setting_label(ui, strings, SettingId::BiomeTundraTemperature);
+        ui.add(
+            egui::Slider::new(&mut settings.biome_tundra_temp, -50.0..=50.0)
+                .step_by(1.0)
+                .suffix("\u{00b0}C"),
+        );
+        setting_label(ui, strings, SettingId::BiomeBorealTemperature);
+        ui.add(
+            egui::Slider::new(&mut settings.biome_boreal_temp, -50.0..=50.0)
+                .step_by(1.0)
+                .suffix("\u{00b0}C"),
+        );
+        setting_label(ui, strings, SettingId::BiomeTemperateTemperature);
+        ui.add(
+            egui::Slider::new(&mut settings.biome_temperate_temp, -50.0..=50.0)
+                .step_by(1.0)
+                .suffix("\u{00b0}C"),
+        );
+        setting_label(ui, strings, SettingId::BiomeHotTemperature);
+        ui.add(
+            egui::Slider::new(&mut settings.biome_hot_temp, -50.0..=50.0)
+                .step_by(1.0)
+                .suffix("\u{00b0}C"),
+        );
+
+        ui.add_space(8.0);
+
+        setting_label(ui, strings, SettingId::BiomeDesertPrecipitation);
+        ui.add(egui::Slider::new(&mut settings.biome_desert_precip, 0.0..=1.0).step_by(0.01));
+        setting_label(ui, strings, SettingId::BiomeSavannaPrecipitation);
+        ui.add(egui::Slider::new(&mut settings.biome_savanna_precip, 0.0..=1.0).step_by(0.01));
+        setting_label(ui, strings, SettingId::BiomeJunglePrecipitation);
+        ui.add(egui::Slider::new(&mut settings.biome_jungle_precip, 0.0..=1.0).step_by(0.01));
+        setting_label(ui, strings, SettingId::BiomeTemperatePrecipitation);
+        ui.add(egui::Slider::new(&mut settings.biome_temperate_precip, 0.0..=1.0).step_by(0.01));
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Biome Colors");
-    ui.add_space(4.0);
-
-    biome_color_row(ui, "Ice", &mut settings.biome_ice_color);
-    biome_color_row(ui, "Tundra", &mut settings.biome_tundra_color);
-    biome_color_row(ui, "Desert", &mut settings.biome_desert_color);
-    biome_color_row(ui, "Savanna", &mut settings.biome_savanna_color);
-    biome_color_row(ui, "Temperate", &mut settings.biome_temperate_color);
-    biome_color_row(ui, "Jungle", &mut settings.biome_jungle_color);
+    settings_section(ui, strings, sections, SettingsSection::BiomeColors, |ui| {
+        biome_color_row(ui, strings, "biome.ice", &mut settings.biome_ice_color);
+        biome_color_row(ui, strings, "biome.tundra", &mut settings.biome_tundra_color);
+        biome_color_row(ui, strings, "biome.desert", &mut settings.biome_desert_color);
+        biome_color_row(ui, strings, "biome.savanna", &mut settings.biome_savanna_color);
+        biome_color_row(ui, strings, "biome.temperate", &mut settings.biome_temperate_color);
+        biome_color_row(ui, strings, "biome.jungle", &mut settings.biome_jungle_color);
+    });
 }
 
-fn render_tectonic_tab(ui: &mut egui::Ui, settings: &mut PlanetGenerationSettings) {
-    ui.heading("Tectonic Plate Settings");
-    ui.add_space(5.0);
-
-    ui.label("Number of Major Plates");
-    ui.add(egui::Slider::new(&mut settings.num_plates, 3..=15));
+fn render_tectonic_tab(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    (terrain, display): (&mut TerrainSettings, &mut DisplaySettings),
+    sections: &mut SettingsSections,
+    locks: &mut RandomizationLocks,
+    plate_drift: &mut PlateDriftState,
+    plates_panel: &mut PlatesPanel,
+) {
+    settings_section(ui, strings, sections, SettingsSection::Plates, |ui| {
+        randomizable_label(ui, strings, SettingId::NumPlates, locks);
+        ui.add(egui::Slider::new(&mut terrain.num_plates, logic::MIN_PLATES..=logic::MAX_PLATES));
 
-    ui.label("Number of Micro Plates");
-    ui.add(egui::Slider::new(&mut settings.num_micro_plates, 0..=20));
+        randomizable_label(ui, strings, SettingId::NumMicroPlates, locks);
+        ui.add(egui::Slider::new(&mut terrain.num_micro_plates, 0..=20));
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Plate Boundary Flow");
-    ui.add_space(5.0);
-
-    ui.label("Flow Warp Frequency");
-    ui.add(egui::Slider::new(&mut settings.flow_warp_freq, 0.1..=2.0).step_by(0.05));
+    settings_section(ui, strings, sections, SettingsSection::PlateBoundaryFlow, |ui| {
+        randomizable_label(ui, strings, SettingId::FlowWarpFrequency, locks);
+        ui.add(egui::Slider::new(&mut terrain.flow_warp_freq, 0.1..=2.0).step_by(0.05));
 
-    ui.label("Flow Warp Steps");
-    ui.add(egui::Slider::new(&mut settings.flow_warp_steps, 1..=8));
+        randomizable_label(ui, strings, SettingId::FlowWarpSteps, locks);
+        ui.add(egui::Slider::new(&mut terrain.flow_warp_steps, 1..=8));
 
-    ui.label("Flow Step Angle");
-    ui.add(egui::Slider::new(&mut settings.flow_warp_step_angle, 0.01..=0.5).step_by(0.01));
+        randomizable_label(ui, strings, SettingId::FlowWarpStepAngle, locks);
+        ui.add(egui::Slider::new(&mut terrain.flow_warp_step_angle, 0.01..=0.5).step_by(0.01));
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Visualization");
-    ui.add_space(5.0);
+    settings_section(ui, strings, sections, SettingsSection::PlateVisualization, |ui| {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut display.plate_crust_colors, false, strings.get("plates.random_colors"));
+            ui.radio_value(&mut display.plate_crust_colors, true, strings.get("plates.crust_type"));
+        });
+        ui.checkbox(&mut display.show_arrows, strings.get("plates.show_arrows"));
+        ui.checkbox(&mut display.cull_far_side, strings.get("plates.cull_far_side"));
+        ui.checkbox(&mut plates_panel.open, strings.get("plates.show_panel"));
+    });
 
-    ui.checkbox(&mut settings.show_arrows, "Show Plate Direction Arrows");
-}
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
 
-fn render_wind_tab(ui: &mut egui::Ui, settings: &mut PlanetGenerationSettings) {
-    ui.add_space(5.0);
+    // Moves the plates of the generated planet, Generate puts them back
+    settings_section(ui, strings, sections, SettingsSection::PlateDrift, |ui| {
+        ui.horizontal(|ui| {
+            let play_label = if plate_drift.running { "playback.pause" } else { "playback.play" };
+            if ui.button(strings.get(play_label)).clicked() {
+                plate_drift.running = !plate_drift.running;
+            }
+            if ui
+                .add_enabled(!plate_drift.running, egui::Button::new(strings.get("playback.step")))
+                .clicked()
+            {
+                plate_drift.pending_steps += 1;
+            }
+            ui.label(strings.format("playback.step_count", &[("step", &plate_drift.step)]));
+        });
+    });
+}
 
-    ui.heading("Wind Speed");
+fn render_wind_tab(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    (wind, display): (&mut WindSettings, &mut DisplaySettings),
+    sections: &mut SettingsSections,
+    streamlines: &mut Streamlines,
+) {
     ui.add_space(5.0);
 
-    ui.label("Zonal Speed (East/West)");
-    ui.add(egui::Slider::new(&mut settings.wind_zonal_speed, 0.0..=10.0).step_by(0.1));
+    settings_section(ui, strings, sections, SettingsSection::WindSpeed, |ui| {
+        setting_label(ui, strings, SettingId::WindZonalSpeed);
+        ui.add(egui::Slider::new(&mut wind.wind_zonal_speed, 0.0..=10.0).step_by(0.1));
+    });
 
+    ui.separator();
     ui.add_space(10.0);
 
-    ui.separator();
+    ui.label(strings.get("wind.display"));
+    ui.horizontal(|ui| {
+        for display in WindDisplay::ALL {
+            ui.selectable_value(&mut wind.wind_display, display, strings.get(display.label()));
+        }
+    });
     ui.add_space(10.0);
 
-    ui.heading("Particle Settings");
-    ui.add_space(5.0);
+    match wind.wind_display {
+        WindDisplay::Particles => {
+            settings_section(ui, strings, sections, SettingsSection::WindParticles, |ui| {
+                // Display current particle count (read-only, set via config)
+                ui.label(strings.format("wind.particle_count", &[("count", &wind.wind_particle_count)]));
 
-    // Display current particle count (read-only, set via config)
-    ui.label(format!(
-        "Particle Count: {} (set in config)",
-        settings.wind_particle_count
-    ));
+                ui.add_space(10.0);
 
-    ui.add_space(10.0);
+                setting_label(ui, strings, SettingId::WindParticleLifespan);
+                ui.add(egui::Slider::new(&mut wind.wind_particle_lifespan, 1.0..=10.0).step_by(0.1));
+                ui.label(strings.get("wind.lifespan_note"));
 
-    ui.label("Particle Lifespan (seconds)");
-    ui.add(egui::Slider::new(&mut settings.wind_particle_lifespan, 1.0..=10.0).step_by(0.1));
-    ui.label("Lower lifespan = faster respawn rate");
-    ui.add_space(5.0);
+                ui.add_space(5.0);
+                ui.checkbox(&mut wind.wind_terrain_following, strings.get("wind.terrain_following"));
+                ui.label(strings.get("wind.terrain_following_note"));
+            });
+        }
+        WindDisplay::Glyphs => {
+            settings_section(ui, strings, sections, SettingsSection::WindGlyphs, |ui| {
+                setting_label(ui, strings, SettingId::WindGlyphSpacing);
+                ui.add(
+                    egui::Slider::new(&mut wind.wind_glyph_spacing, MIN_GLYPH_SPACING..=30.0)
+                        .step_by(1.0)
+                        .suffix("°"),
+                );
+                ui.label(strings.format("wind.glyphs_note", &[("speed", &BARB_SPEED)]));
+
+                ui.add_space(5.0);
+                // The glyphs fly at the particle height too
+                ui.checkbox(&mut wind.wind_terrain_following, strings.get("wind.terrain_following"));
+            });
+        }
+    }
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Vertical Air Movement");
-    ui.add_space(5.0);
+    settings_section(ui, strings, sections, SettingsSection::Streamlines, |ui| {
+        ui.add(
+            egui::Slider::new(&mut wind.streamline_arc_length, 10.0..=180.0)
+                .step_by(5.0)
+                .suffix("°")
+                .text(strings.get("streamlines.arc_length")),
+        );
+        ui.label(strings.get("streamlines.arc_length_note"));
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label(strings.format("streamlines.traced", &[("count", &streamlines.seeds.len())]));
+            if ui
+                .add_enabled(
+                    !streamlines.seeds.is_empty(),
+                    egui::Button::new(strings.get("streamlines.clear")),
+                )
+                .clicked()
+            {
+                streamlines.clear();
+            }
+        });
+        ui.weak(strings.get("streamlines.hint"));
+    });
 
-    ui.checkbox(&mut settings.show_vertical_air, "Show Vertical Air Movement");
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
 
-    ui.add_space(5.0);
-    ui.label("Color Scale:");
-    ui.horizontal(|ui| {
-        ui.label("Blue: Rising air (convergence)");
-    });
-    ui.horizontal(|ui| {
-        ui.label("White: Neutral");
-    });
-    ui.horizontal(|ui| {
-        ui.label("Red: Sinking air (divergence)");
+    settings_section(ui, strings, sections, SettingsSection::VerticalAir, |ui| {
+        ui.checkbox(&mut display.show_vertical_air, strings.get("vertical_air.show"));
+
+        ui.add_space(5.0);
+        ui.label(strings.get("legend.color_scale"));
+        ui.horizontal(|ui| {
+            ui.label(strings.get("vertical_air.rising"));
+        });
+        ui.horizontal(|ui| {
+            ui.label(strings.get("vertical_air.neutral"));
+        });
+        ui.horizontal(|ui| {
+            ui.label(strings.get("vertical_air.sinking"));
+        });
     });
 
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Wind Deflection");
-    ui.add_space(5.0);
-
-    ui.label("Height Threshold");
-    ui.add(egui::Slider::new(&mut settings.wind_deflection_height_threshold, 0.0..=1.0).step_by(0.01));
+    settings_section(ui, strings, sections, SettingsSection::WindDeflection, |ui| {
+        setting_label(ui, strings, SettingId::WindDeflectionHeightThreshold);
+        ui.add(egui::Slider::new(&mut wind.wind_deflection_height_threshold, 0.0..=1.0).step_by(0.01));
 
-    ui.label("Height Scale");
-    ui.add(egui::Slider::new(&mut settings.wind_deflection_height_scale, 0.5..=5.0).step_by(0.1));
+        setting_label(ui, strings, SettingId::WindDeflectionHeightScale);
+        ui.add(egui::Slider::new(&mut wind.wind_deflection_height_scale, 0.5..=5.0).step_by(0.1));
 
-    ui.label("Spread Radius");
-    ui.add(egui::Slider::new(&mut settings.wind_deflection_spread_radius, 1..=8));
+        setting_label(ui, strings, SettingId::WindDeflectionSpreadRadius);
+        ui.add(egui::Slider::new(&mut wind.wind_deflection_spread_radius, 1..=8));
 
-    ui.label("Spread Decay");
-    ui.add(egui::Slider::new(&mut settings.wind_deflection_spread_decay, 0.1..=0.9).step_by(0.01));
+        setting_label(ui, strings, SettingId::WindDeflectionSpreadDecay);
+        ui.add(egui::Slider::new(&mut wind.wind_deflection_spread_decay, 0.1..=0.9).step_by(0.01));
 
-    ui.label("Deflection Strength");
-    ui.add(egui::Slider::new(&mut settings.wind_deflection_strength, 0.0..=1.0).step_by(0.01));
+        setting_label(ui, strings, SettingId::WindDeflectionStrength);
+        ui.add(egui::Slider::new(&mut wind.wind_deflection_strength, 0.0..=1.0).step_by(0.01));
 
-    ui.label("Deflection Iterations");
-    ui.add(egui::Slider::new(&mut settings.wind_deflection_iterations, 1..=10));
+        setting_label(ui, strings, SettingId::WindDeflectionIterations);
+        ui.add(egui::Slider::new(&mut wind.wind_deflection_iterations, 1..=10));
 
-    ui.add_space(5.0);
+        ui.add_space(5.0);
+        ui.checkbox(&mut display.show_wind_influence, strings.get("wind_deflection.show_influence"));
+        ui.label(strings.get("wind_deflection.legend"));
+    });
 }
 
-fn render_temperature_tab(ui: &mut egui::Ui, settings: &mut PlanetGenerationSettings) {
+fn render_temperature_tab(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    settings: &mut ClimateSettings,
+    sections: &mut SettingsSections,
+    locks: &mut RandomizationLocks,
+    climate_sim: &mut ClimateSimState,
+    face_grid_size: usize,
+) {
     ui.add_space(5.0);
 
-    ui.heading("Temperature Generation");
-    ui.add_space(5.0);
+    settings_section(ui, strings, sections, SettingsSection::TemperatureGeneration, |ui| {
+        randomizable_label(ui, strings, SettingId::EquatorTemperature, locks);
+        ui.add(egui::Slider::new(&mut settings.temperature_equator_temp, 20.0..=50.0).step_by(1.0));
+        ui.label(strings.get("temperature.equator_note"));
 
-    ui.label("Equator Temperature (°C)");
-    ui.add(egui::Slider::new(&mut settings.temperature_equator_temp, 20.0..=50.0).step_by(1.0));
-    ui.label("Hottest temperature at the equator");
+        ui.add_space(5.0);
 
-    ui.add_space(5.0);
+        randomizable_label(ui, strings, SettingId::PoleTemperature, locks);
+        ui.add(egui::Slider::new(&mut settings.temperature_pole_temp, -50.0..=-10.0).step_by(1.0));
+        ui.label(strings.get("temperature.pole_note"));
 
-    ui.label("Pole Temperature (°C)");
-    ui.add(egui::Slider::new(&mut settings.temperature_pole_temp, -50.0..=-10.0).step_by(1.0));
-    ui.label("Coldest temperature at the poles");
+        ui.add_space(5.0);
 
-    ui.add_space(5.0);
-
-    ui.label("Latitude Falloff");
-    ui.add(egui::Slider::new(&mut settings.temperature_latitude_falloff, 0.2..=4.0).step_by(0.1));
-    ui.label("< 1 = wider warm zone, > 1 = wider cold zone");
+        randomizable_label(ui, strings, SettingId::LatitudeFalloff, locks);
+        ui.add(egui::Slider::new(&mut settings.temperature_latitude_falloff, 0.2..=4.0).step_by(0.1));
+        ui.label(strings.get("temperature.falloff_note"));
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Color Scale Range");
-    ui.add_space(5.0);
+    settings_section(ui, strings, sections, SettingsSection::TemperatureColorScale, |ui| {
+        randomizable_label(ui, strings, SettingId::MaxTemperature, locks);
+        ui.add(egui::Slider::new(&mut settings.temperature_max_temp, 30.0..=100.0).step_by(5.0));
+        ui.label(strings.get("temperature.max_note"));
 
-    ui.label("Maximum Temperature (°C)");
-    ui.add(egui::Slider::new(&mut settings.temperature_max_temp, 30.0..=100.0).step_by(5.0));
-    ui.label("Red end of color gradient");
+        ui.add_space(5.0);
 
-    ui.add_space(5.0);
-
-    ui.label("Minimum Temperature (°C)");
-    ui.add(egui::Slider::new(&mut settings.temperature_min_temp, -100.0..=-20.0).step_by(5.0));
-    ui.label("Blue end of color gradient");
+        randomizable_label(ui, strings, SettingId::MinTemperature, locks);
+        ui.add(egui::Slider::new(&mut settings.temperature_min_temp, -100.0..=-20.0).step_by(5.0));
+        ui.label(strings.get("temperature.min_note"));
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Temperature Settings");
-    ui.add_space(5.0);
-
-    ui.label("Land Temperature Bonus");
-    ui.add(
-        egui::Slider::new(&mut settings.land_temperature_bonus, 0.0..=20.0)
-            .step_by(0.5)
-            .suffix("°C"),
-    );
-    ui.label("Extra warmth for land above sea level");
+    settings_section(ui, strings, sections, SettingsSection::LandTemperature, |ui| {
+        randomizable_label(ui, strings, SettingId::LandTemperatureBonus, locks);
+        ui.add(
+            egui::Slider::new(&mut settings.land_temperature_bonus, 0.0..=20.0)
+                .step_by(0.5)
+                .suffix("°C"),
+        );
+        ui.label(strings.get("temperature.land_bonus_note"));
+
+        ui.add_space(5.0);
+
+        setting_label(ui, strings, SettingId::LapseRate);
+        ui.add(
+            egui::Slider::new(&mut settings.temperature_lapse_rate, 0.0..=30.0)
+                .step_by(0.5)
+                .suffix("°C"),
+        );
+        ui.label(strings.get("temperature.lapse_rate_note"));
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Temperature Map");
-    ui.add_space(5.0);
+    // The wind carries the temperatures around in fixed steps, paused until started
+    settings_section(ui, strings, sections, SettingsSection::ClimateSimulation, |ui| {
+        ui.horizontal(|ui| {
+            let play_label = if climate_sim.running { "playback.pause" } else { "playback.play" };
+            if ui.button(strings.get(play_label)).clicked() {
+                climate_sim.running = !climate_sim.running;
+            }
+            if ui
+                .add_enabled(!climate_sim.running, egui::Button::new(strings.get("playback.step")))
+                .clicked()
+            {
+                climate_sim.pending_steps += 1;
+            }
+            ui.label(strings.format("playback.step_count", &[("step", &climate_sim.step)]));
+        });
+    });
 
-    ui.label("Displaying latitude-based temperature distribution:");
+    ui.add_space(10.0);
+    ui.separator();
     ui.add_space(10.0);
 
-    // Color legend showing the actual range
-    ui.label("Color Scale:");
-    ui.horizontal(|ui| {
-        ui.label("🔵 Light Blue:");
-        ui.label(format!("{:.0}°C", settings.temperature_min_temp));
-    });
-    ui.horizontal(|ui| {
-        ui.label("🟦 Cyan:");
-        ui.label(format!(
-            "{:.0}°C",
-            settings.temperature_min_temp * 0.8 + settings.temperature_max_temp * 0.2
-        ));
-    });
-    ui.horizontal(|ui| {
-        ui.label("🟢 Green:");
-        ui.label(format!(
-            "{:.0}°C",
-            settings.temperature_min_temp * 0.6 + settings.temperature_max_temp * 0.4
-        ));
-    });
-    ui.horizontal(|ui| {
-        ui.label("🟡 Yellow:");
-        ui.label(format!(
-            "{:.0}°C",
-            settings.temperature_min_temp * 0.4 + settings.temperature_max_temp * 0.6
-        ));
+    settings_section(ui, strings, sections, SettingsSection::CubemapResolution, |ui| {
+        let resolution = settings.temperature_resolution_for(face_grid_size);
+        cubemap_resolution_row(
+            ui,
+            strings,
+            "cubemap.temperature",
+            resolution,
+            &mut settings.temperature_cubemap_override,
+        );
     });
-    ui.horizontal(|ui| {
-        ui.label("🟠 Orange:");
-        ui.label(format!(
-            "{:.0}°C",
-            settings.temperature_min_temp * 0.2 + settings.temperature_max_temp * 0.8
-        ));
-    });
-    ui.horizontal(|ui| {
-        ui.label("🔴 Red:");
-        ui.label(format!("{:.0}°C", settings.temperature_max_temp));
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    settings_section(ui, strings, sections, SettingsSection::TemperatureMap, |ui| {
+        ui.label(strings.get("temperature.map_description"));
+        ui.add_space(10.0);
+
+        // Color legend showing the actual range
+        ui.label(strings.get("legend.color_scale"));
+        ui.horizontal(|ui| {
+            ui.label(strings.get("temperature.light_blue"));
+            ui.label(format!("{:.0}°C", settings.temperature_min_temp));
+        });
+        ui.horizontal(|ui| {
+            ui.label(strings.get("temperature.cyan"));
+            ui.label(format!(
+                "{:.0}°C",
+                settings.temperature_min_temp * 0.8 + settings.temperature_max_temp * 0.2
+            ));
+        });
+        ui.horizontal(|ui| {
+            ui.label(strings.get("temperature.green"));
+            ui.label(format!(
+                "{:.0}°C",
+                settings.temperature_min_temp * 0.6 + settings.temperature_max_temp * 0.4
+            ));
+        });
+        ui.horizontal(|ui| {
+            ui.label(strings.get("temperature.yellow"));
+            ui.label(format!(
+                "{:.0}°C",
+                settings.temperature_min_temp * 0.4 + settings.temperature_max_temp * 0.6
+            ));
+        });
+        ui.horizontal(|ui| {
+            ui.label(strings.get("temperature.orange"));
+            ui.label(format!(
+                "{:.0}°C",
+                settings.temperature_min_temp * 0.2 + settings.temperature_max_temp * 0.8
+            ));
+        });
+        ui.horizontal(|ui| {
+            ui.label(strings.get("temperature.red"));
+            ui.label(format!("{:.0}°C", settings.temperature_max_temp));
+        });
     });
 }
 
-fn render_precipitation_tab(ui: &mut egui::Ui, settings: &mut PlanetGenerationSettings) {
+fn render_precipitation_tab(
+    ui: &mut egui::Ui,
+    strings: &Strings,
+    settings: &mut ClimateSettings,
+    sections: &mut SettingsSections,
+    locks: &mut RandomizationLocks,
+    season_playback: &mut SeasonPlayback,
+    face_grid_size: usize,
+) {
     ui.add_space(5.0);
 
-    ui.heading("Temperature Influence");
-    ui.add_space(5.0);
+    settings_section(ui, strings, sections, SettingsSection::PrecipitationTemperature, |ui| {
+        randomizable_label(ui, strings, SettingId::PrecipitationTemperatureWeight, locks);
+        ui.add(
+            egui::Slider::new(&mut settings.precipitation_temperature_weight, 0.0..=1.0)
+                .step_by(0.05),
+        );
+        ui.label(strings.get("precipitation.temperature_note"));
+    });
 
-    ui.label("Temperature Weight");
-    ui.add(
-        egui::Slider::new(&mut settings.precipitation_temperature_weight, 0.0..=1.0)
-            .step_by(0.05),
-    );
-    ui.label("Warm air = high moisture capacity");
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    settings_section(ui, strings, sections, SettingsSection::WaterAvailability, |ui| {
+        randomizable_label(ui, strings, SettingId::PrecipitationOceanWeight, locks);
+        ui.add(
+            egui::Slider::new(&mut settings.precipitation_ocean_weight, 0.0..=1.0)
+                .step_by(0.05),
+        );
+        ui.label(strings.get("precipitation.ocean_note"));
+        ui.label(strings.get("precipitation.land_note"));
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.heading("Water Availability");
-    ui.add_space(5.0);
+    // The wind and the vertical air share the precipitation resolution
+    settings_section(ui, strings, sections, SettingsSection::CubemapResolution, |ui| {
+        let resolution = settings.precipitation_resolution_for(face_grid_size);
+        cubemap_resolution_row(
+            ui,
+            strings,
+            "cubemap.wind_and_rain",
+            resolution,
+            &mut settings.precipitation_cubemap_override,
+        );
+    });
 
-    ui.label("Ocean Weight");
-    ui.add(
-        egui::Slider::new(&mut settings.precipitation_ocean_weight, 0.0..=1.0)
-            .step_by(0.05),
-    );
-    ui.label("Ocean = high evaporation");
-    ui.label("Land = low evaporation");
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+
+    // The rain bands follow the thermal equator over a year, the annual map until shown
+    settings_section(ui, strings, sections, SettingsSection::Seasons, |ui| {
+        ui.checkbox(&mut season_playback.enabled, strings.get("seasons.show_year"))
+            .on_hover_text(strings.get("seasons.show_year.tooltip"));
+        ui.add_enabled_ui(season_playback.enabled, |ui| {
+            ui.horizontal(|ui| {
+                let play_label = if season_playback.playing { "playback.pause" } else { "playback.play" };
+                if ui.button(strings.get(play_label)).clicked() {
+                    season_playback.playing = !season_playback.playing;
+                }
+                ui.label(strings.get(phase_month(season_playback.phase)));
+            });
+            ui.add(egui::Slider::new(&mut season_playback.phase, 0.0..=1.0).text(strings.get("seasons.phase")));
+            ui.add(egui::Slider::new(&mut season_playback.years_per_minute, 1.0..=30.0).text(strings.get("seasons.years_per_minute")));
+        });
+    });
 
     ui.add_space(10.0);
     ui.separator();
     ui.add_space(10.0);
 
-    ui.label("Color Scale:");
+    ui.label(strings.get("legend.color_scale"));
     ui.horizontal(|ui| {
-        ui.label("Yellow: Dry (0%)");
+        ui.label(strings.get("precipitation.dry"));
     });
     ui.horizontal(|ui| {
-        ui.label("Light Blue: Moderate (50%)");
+        ui.label(strings.get("precipitation.moderate"));
     });
     ui.horizontal(|ui| {
-        ui.label("Blue: Wet (100%)");
+        ui.label(strings.get("precipitation.wet"));
     });
 }
@@ -0,0 +1,121 @@
+use crate::planet::events::ViewKind;
+use crate::ui::locale::StringId;
+
+/// Which groups of planet meshes are visible for a tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewVisibility {
+    pub continent: bool,
+    pub ocean: bool,
+    pub plates: bool,
+    pub temperature: bool,
+    pub precipitation: bool,
+    pub vertical_air: bool,
+    pub wind_influence: bool,
+    pub crust_age: bool,
+}
+
+/// Overlay that replaces the continent and ocean meshes on the Wind tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindOverlay {
+    None,
+    VerticalAir,
+    /// Mountain influence cost, shown over vertical air when both are enabled
+    Influence,
+}
+
+/// Overlays replace the continent and ocean meshes while their tab is open. The Wind tab shows
+/// `wind_overlay` if there is one, the originals otherwise.
+pub fn view_visibility(tab: ViewKind, wind_overlay: WindOverlay) -> ViewVisibility {
+    let hidden = ViewVisibility {
+        continent: false,
+        ocean: false,
+        plates: false,
+        temperature: false,
+        precipitation: false,
+        vertical_air: false,
+        wind_influence: false,
+        crust_age: false,
+    };
+
+    match tab {
+        ViewKind::Continent | ViewKind::Biomes => ViewVisibility {
+            continent: true,
+            ocean: true,
+            ..hidden
+        },
+        ViewKind::Tectonic => ViewVisibility {
+            plates: true,
+            ..hidden
+        },
+        ViewKind::Wind => ViewVisibility {
+            continent: wind_overlay == WindOverlay::None,
+            ocean: wind_overlay == WindOverlay::None,
+            vertical_air: wind_overlay == WindOverlay::VerticalAir,
+            wind_influence: wind_overlay == WindOverlay::Influence,
+            ..hidden
+        },
+        ViewKind::Temperature => ViewVisibility {
+            temperature: true,
+            ..hidden
+        },
+        ViewKind::Precipitations => ViewVisibility {
+            precipitation: true,
+            ..hidden
+        },
+        ViewKind::CrustAge => ViewVisibility {
+            crust_age: true,
+            ..hidden
+        },
+    }
+}
+
+/// Locale key of the hint on the tabs that draw over a planet while there is none
+pub const NO_PLANET_HINT: StringId = "overlay.no_planet";
+
+/// What to show instead of the overlay of `tab`, None once there is something to show. The
+/// Continent tab is where planets are generated, it never needs a hint.
+pub fn overlay_hint(tab: ViewKind, has_planet: bool) -> Option<StringId> {
+    (tab != ViewKind::Continent && !has_planet).then_some(NO_PLANET_HINT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vertical_air_only_replaces_originals_on_wind_tab() {
+        let wind = view_visibility(ViewKind::Wind, WindOverlay::VerticalAir);
+        assert!(wind.vertical_air);
+        assert!(!wind.continent && !wind.ocean);
+
+        let wind = view_visibility(ViewKind::Wind, WindOverlay::None);
+        assert!(!wind.vertical_air);
+        assert!(wind.continent && wind.ocean);
+
+        for tab in [ViewKind::Continent, ViewKind::Biomes] {
+            let visibility = view_visibility(tab, WindOverlay::VerticalAir);
+            assert!(visibility.continent && visibility.ocean);
+            assert!(!visibility.vertical_air);
+        }
+        assert!(!view_visibility(ViewKind::Temperature, WindOverlay::VerticalAir).vertical_air);
+    }
+
+    #[test]
+    fn test_wind_influence_shows_alone_on_wind_tab() {
+        let wind = view_visibility(ViewKind::Wind, WindOverlay::Influence);
+        assert!(wind.wind_influence);
+        assert!(!wind.vertical_air && !wind.continent && !wind.ocean);
+
+        let continent = view_visibility(ViewKind::Continent, WindOverlay::Influence);
+        assert!(!continent.wind_influence);
+    }
+
+    #[test]
+    fn test_overlay_tabs_ask_for_a_planet_until_there_is_one() {
+        for tab in [ViewKind::Tectonic, ViewKind::Wind, ViewKind::Temperature, ViewKind::CrustAge] {
+            assert_eq!(overlay_hint(tab, false), Some(NO_PLANET_HINT));
+            assert_eq!(overlay_hint(tab, true), None);
+        }
+        assert_eq!(overlay_hint(ViewKind::Continent, false), None);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod logic;
 pub mod systems;
 
-pub use systems::handle_tab_visibility;
+pub use systems::{apply_overlay_colors, handle_tab_visibility, update_overlay_hint};
@@ -1,208 +1,118 @@
-use crate::planet::components::{ContinentView, OceanEntity, TectonicPlateView};
-use crate::planet::events::{TabSwitchEvent, ViewTabType};
-use crate::planet::resources::PlanetGenerationSettings;
+use crate::planet::components::{ContinentView, OceanEntity, OverlayMesh, TectonicPlateView};
+use crate::planet::crust_age::systems::CrustAgeMesh;
+use crate::planet::events::{SetActiveView, SettingsDiff, SettingsGroup};
+use crate::planet::resources::{
+    CurrentPlanetData, DisplaySettings, OverlayColorCache, OverlayHint, OverlayMeshes, OverlayState,
+};
 use crate::planet::temperature::systems::TemperatureMesh;
 use crate::planet::precipitation::systems::PrecipitationMesh;
-use crate::planet::wind::systems::VerticalAirMesh;
+use crate::planet::view::logic::{overlay_hint, view_visibility, WindOverlay};
+use crate::planet::wind::systems::{VerticalAirMesh, WindInfluenceMesh};
 use bevy::prelude::*;
 
-/// CENTRALIZED tab visibility handler - handles ALL tab switching in ONE place
-/// Wind particles are managed by their own systems (handle_wind_tab_events + spawn_debug_particles)
+/// CENTRALIZED tab visibility handler - handles ALL mesh visibility in ONE place.
+/// Overlay systems only create and destroy their meshes; this decides what is shown on a tab
+/// switch, when an overlay toggle changes and when new wind overlay meshes appear.
+/// Also the one place that applies `SetActiveView` to `OverlayState`.
+/// Wind particles and glyphs are managed by their own systems (handle_wind_tab_events +
+/// spawn_debug_particles, rebuild_wind_glyph_mesh + show_wind_glyphs_on_wind_tab)
 pub fn handle_tab_visibility(
-    mut tab_switch_events: MessageReader<TabSwitchEvent>,
-    planet_settings: Res<PlanetGenerationSettings>,
+    mut set_active_view: MessageReader<SetActiveView>,
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut overlay_state: ResMut<OverlayState>,
+    planet_settings: Res<DisplaySettings>,
     continent_view_query: Query<Entity, With<ContinentView>>,
     ocean_query: Query<Entity, With<OceanEntity>>,
     plate_view_query: Query<Entity, With<TectonicPlateView>>,
     temperature_mesh_query: Query<Entity, With<TemperatureMesh>>,
     precipitation_mesh_query: Query<Entity, With<PrecipitationMesh>>,
     vertical_air_query: Query<Entity, With<VerticalAirMesh>>,
+    wind_influence_query: Query<Entity, With<WindInfluenceMesh>>,
+    crust_age_query: Query<Entity, With<CrustAgeMesh>>,
+    added_vertical_air: Query<(), Added<VerticalAirMesh>>,
+    added_wind_influence: Query<(), Added<WindInfluenceMesh>>,
     mut commands: Commands,
 ) {
-    for event in tab_switch_events.read() {
-        info!("Switching to {:?} tab - handling ALL visibility", event.tab);
-
-        match event.tab {
-            ViewTabType::Continent => {
-                // Show: Continent mesh + Ocean
-                // Hide: Tectonic plates, Temperature meshes, Precipitation meshes, Vertical air
-
-                for entity in continent_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Visible);
-                }
-
-                for entity in ocean_query.iter() {
-                    commands.entity(entity).insert(Visibility::Visible);
-                }
-
-                for entity in plate_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in temperature_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in precipitation_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in vertical_air_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-            }
-
-            ViewTabType::Wind => {
-                // Wind particles are managed by handle_wind_tab_events + spawn_debug_particles
-                // If vertical air overlay is enabled, hide originals (meshes will be created by toggle system)
-                let show_vertical_air = planet_settings.show_vertical_air;
-
-                for entity in continent_view_query.iter() {
-                    commands.entity(entity).insert(if show_vertical_air {
-                        Visibility::Hidden
-                    } else {
-                        Visibility::Visible
-                    });
-                }
-
-                for entity in ocean_query.iter() {
-                    commands.entity(entity).insert(if show_vertical_air {
-                        Visibility::Hidden
-                    } else {
-                        Visibility::Visible
-                    });
-                }
-
-                for entity in plate_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in temperature_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in precipitation_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in vertical_air_query.iter() {
-                    commands.entity(entity).insert(Visibility::Visible);
-                }
-            }
-
-            ViewTabType::Tectonic => {
-                // Show: Tectonic plates ONLY
-                // Hide: Continent mesh, Ocean, Temperature meshes, Precipitation meshes, Vertical air
-
-                for entity in continent_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in ocean_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in plate_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Visible);
-                }
-
-                for entity in temperature_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in precipitation_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in vertical_air_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-            }
-
-            ViewTabType::Temperature => {
-                // Show: Temperature meshes ONLY
-                // Hide: Continent mesh, Ocean, Tectonic plates, Precipitation meshes, Vertical air
-
-                for entity in continent_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in ocean_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in plate_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in temperature_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Visible);
-                }
-
-                for entity in precipitation_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in vertical_air_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-            }
-
-            ViewTabType::Precipitations => {
-                // Show: Precipitation meshes ONLY
-                // Hide: Continent mesh, Ocean, Tectonic plates, Temperature meshes, Vertical air
-
-                for entity in continent_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in ocean_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in plate_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in temperature_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in precipitation_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Visible);
-                }
+    let switched = set_active_view.read().last().map(|SetActiveView(view)| *view);
+    let toggled = SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Visual]);
+
+    if let Some(view) = switched {
+        info!("Switching to {:?} tab - handling ALL visibility", view);
+        overlay_state.active = view;
+    } else if !toggled && added_vertical_air.is_empty() && added_wind_influence.is_empty() {
+        return;
+    }
+    let tab = overlay_state.active;
+
+    // Originals stay up until the overlay that replaces them actually exists
+    let wind_overlay = if planet_settings.show_wind_influence && !wind_influence_query.is_empty() {
+        WindOverlay::Influence
+    } else if planet_settings.show_vertical_air && !vertical_air_query.is_empty() {
+        WindOverlay::VerticalAir
+    } else {
+        WindOverlay::None
+    };
+    let visibility = view_visibility(tab, wind_overlay);
+
+    let groups = [
+        (continent_view_query.iter().collect::<Vec<_>>(), visibility.continent),
+        (ocean_query.iter().collect(), visibility.ocean),
+        (plate_view_query.iter().collect(), visibility.plates),
+        (temperature_mesh_query.iter().collect(), visibility.temperature),
+        (precipitation_mesh_query.iter().collect(), visibility.precipitation),
+        (vertical_air_query.iter().collect(), visibility.vertical_air),
+        (wind_influence_query.iter().collect(), visibility.wind_influence),
+        (crust_age_query.iter().collect(), visibility.crust_age),
+    ];
+    for (entities, visible) in groups {
+        let visibility = if visible {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        for entity in entities {
+            commands.entity(entity).insert(visibility);
+        }
+    }
+}
 
-                for entity in vertical_air_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
+/// Give the shared overlay copies the colors of the overlay that was just shown or built.
+/// Every overlay draws the same copy of a source mesh and only one of them is visible at a
+/// time, so the copy only needs the colors of that one.
+pub fn apply_overlay_colors(
+    overlays: Query<(&Mesh3d, &OverlayMesh, &Visibility), Or<(Added<OverlayMesh>, Changed<Visibility>)>>,
+    overlay_meshes: Res<OverlayMeshes>,
+    color_cache: Res<OverlayColorCache>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (mesh_handle, overlay, visibility) in overlays.iter() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        let Some(mesh) = meshes.get_mut(&mesh_handle.0) else {
+            continue;
+        };
+        match overlay.colors.and_then(|kind| color_cache.colors(kind, overlay.source)) {
+            Some(colors) => {
+                mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, overlay_meshes.copy_colors(overlay.source, colors));
             }
-
-            ViewTabType::Biomes => {
-                // Same visibility as Continent: show continent mesh + ocean
-                for entity in continent_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Visible);
-                }
-
-                for entity in ocean_query.iter() {
-                    commands.entity(entity).insert(Visibility::Visible);
-                }
-
-                for entity in plate_view_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in temperature_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in precipitation_mesh_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-
-                for entity in vertical_air_query.iter() {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
+            // Vertex colors would tint the texture
+            None => {
+                mesh.remove_attribute(Mesh::ATTRIBUTE_COLOR);
             }
         }
     }
 }
+
+/// Keep the hint of the open tab up to date, the overlay tabs have nothing to draw on before the
+/// first planet
+pub fn update_overlay_hint(
+    overlay_state: Res<OverlayState>,
+    current_planet_data: Res<CurrentPlanetData>,
+    mut hint: ResMut<OverlayHint>,
+) {
+    let wanted = OverlayHint(overlay_hint(overlay_state.active, current_planet_data.planet_data.is_some()));
+    if *hint != wanted {
+        *hint = wanted;
+    }
+}
@@ -0,0 +1,201 @@
+use super::TERRAIN_FOLLOW_RATE;
+use bevy::math::Vec3;
+
+/// Holds a rebuild back until its input stopped changing for a while, so dragging a slider
+/// rebuilds once when it is released instead of on every frame
+#[derive(Debug, Default)]
+pub struct Debounce {
+    remaining_secs: Option<f32>,
+}
+
+impl Debounce {
+    /// (Re)start the countdown
+    pub fn trigger(&mut self, delay_secs: f32) {
+        self.remaining_secs = Some(delay_secs);
+    }
+
+    /// Count down, true exactly once when the delay since the last trigger ran out
+    pub fn tick(&mut self, delta_secs: f32) -> bool {
+        let Some(remaining) = self.remaining_secs.as_mut() else {
+            return false;
+        };
+        *remaining -= delta_secs;
+        if *remaining > 0.0 {
+            return false;
+        }
+        self.remaining_secs = None;
+        true
+    }
+}
+
+/// Fixed steps of a simulation running at its own rate, whatever the frame rate. Frames add
+/// their time, every full step of it is taken and the rest carries over.
+#[derive(Debug, Clone)]
+pub struct FixedStepClock {
+    step_secs: f32,
+    max_steps: u32,
+    accumulator: f32,
+}
+
+impl FixedStepClock {
+    /// Steps of `step_secs`, at most `max_steps` for one frame. Time beyond them is dropped, a
+    /// frame spike slows the simulation down instead of making it jump.
+    pub fn new(step_secs: f32, max_steps: u32) -> Self {
+        Self {
+            step_secs,
+            max_steps,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn step_secs(&self) -> f32 {
+        self.step_secs
+    }
+
+    /// Add a frame of `delta_secs`, the number of steps to take for it
+    pub fn advance(&mut self, delta_secs: f32) -> u32 {
+        self.accumulator += delta_secs;
+        let steps = (self.accumulator / self.step_secs).floor() as u32;
+        if steps > self.max_steps {
+            self.accumulator = 0.0;
+            return self.max_steps;
+        }
+        self.accumulator -= steps as f32 * self.step_secs;
+        steps
+    }
+
+    /// How far into the next step the frame is, 0 to 1
+    pub fn overstep_fraction(&self) -> f32 {
+        (self.accumulator / self.step_secs).clamp(0.0, 1.0)
+    }
+}
+
+/// Position of a particle at `position` blown by `velocity` for `delta_secs`, eased towards
+/// `target_radius` of the ground below it so cliffs don't make it jump
+pub fn advect_particle(position: Vec3, velocity: Vec3, target_radius: impl Fn(Vec3) -> f32, delta_secs: f32) -> Vec3 {
+    let direction = (position + velocity * delta_secs).normalize();
+    direction * approach(position.length(), target_radius(direction), TERRAIN_FOLLOW_RATE, delta_secs)
+}
+
+/// Point `t` of the way from `previous` to `current` around the planet, the radius eased
+/// separately so the particle doesn't dip towards the center between the two
+pub fn interpolate_on_sphere(previous: Vec3, current: Vec3, t: f32) -> Vec3 {
+    let direction = previous.lerp(current, t).normalize_or(current.normalize_or_zero());
+    direction * (previous.length() + (current.length() - previous.length()) * t)
+}
+
+/// Distance from the planet center to fly at `offset` above the ground, the calm sea surface
+/// standing in for the ground below it
+pub fn terrain_following_radius(
+    surface_radius: f32,
+    terrain_height: f32,
+    sea_level: f32,
+    offset: f32,
+) -> f32 {
+    surface_radius + terrain_height.max(sea_level) + offset
+}
+
+/// Move `current` towards `target` by the share of the way `rate` covers in `delta_secs`.
+/// Frame rate independent and never overshoots.
+pub fn approach(current: f32, target: f32, rate: f32, delta_secs: f32) -> f32 {
+    current + (target - current) * (1.0 - (-rate * delta_secs).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_fires_once_after_the_last_trigger() {
+        let mut debounce = Debounce::default();
+        assert!(!debounce.tick(1.0));
+
+        debounce.trigger(0.3);
+        assert!(!debounce.tick(0.2));
+        // Another change restarts the countdown
+        debounce.trigger(0.3);
+        assert!(!debounce.tick(0.2));
+        assert!(debounce.tick(0.2));
+        assert!(!debounce.tick(0.2));
+    }
+
+    #[test]
+    fn test_terrain_following_radius_skims_the_sea_or_the_mountains() {
+        // Deep ocean floor, the particle stays above the water
+        assert_eq!(terrain_following_radius(50.0, -2.0, 0.1, 1.0), 51.1);
+        // Mountain above the sea
+        assert_eq!(terrain_following_radius(50.0, 3.0, 0.1, 1.0), 54.0);
+    }
+
+    #[test]
+    fn test_approach_eases_without_overshooting() {
+        let halfway = approach(0.0, 10.0, 5.0, 0.1);
+        assert!(halfway > 0.0 && halfway < 10.0);
+        // Two short steps cover the same ground as one long one
+        let stepped = approach(approach(0.0, 10.0, 5.0, 0.05), 10.0, 5.0, 0.05);
+        assert!((stepped - halfway).abs() < 1e-5);
+        assert!((approach(0.0, 10.0, 5.0, 10.0) - 10.0).abs() < 1e-3);
+        assert_eq!(approach(4.0, 4.0, 5.0, 0.1), 4.0);
+    }
+
+    #[test]
+    fn test_clock_carries_the_rest_of_a_frame_over() {
+        let mut clock = FixedStepClock::new(0.1, 8);
+
+        assert_eq!(clock.advance(0.25), 2);
+        assert!((clock.overstep_fraction() - 0.5).abs() < 1e-4);
+        assert_eq!(clock.advance(0.06), 1);
+        assert_eq!(clock.advance(0.0), 0);
+    }
+
+    #[test]
+    fn test_clock_drops_the_time_of_a_frame_spike() {
+        let mut clock = FixedStepClock::new(0.1, 8);
+
+        assert_eq!(clock.advance(5.0), 8);
+        assert_eq!(clock.overstep_fraction(), 0.0);
+    }
+
+    #[test]
+    fn test_interpolation_stays_on_the_sphere() {
+        let previous = Vec3::X * 10.0;
+        let current = Vec3::Y * 12.0;
+
+        assert_eq!(interpolate_on_sphere(previous, current, 0.0), previous);
+        assert!(interpolate_on_sphere(previous, current, 1.0).distance(current) < 1e-5);
+        assert!((interpolate_on_sphere(previous, current, 0.5).length() - 11.0).abs() < 1e-5);
+    }
+
+    /// Great-circle distance a particle flies in 10 s of steady wind around the pole, rendered
+    /// at `fps` frames per second
+    fn distance_flown(fps: f32) -> f32 {
+        let radius = 10.0;
+        let speed = 2.0;
+        let wind = |position: Vec3| Vec3::Y.cross(position).normalize() * speed;
+        let mut clock = FixedStepClock::new(1.0 / 30.0, 8);
+        let mut previous = Vec3::X * radius;
+        let mut current = previous;
+        let mut shown = previous;
+        let mut distance = 0.0;
+        for _ in 0..(10.0 * fps).round() as usize {
+            for _ in 0..clock.advance(1.0 / fps) {
+                previous = current;
+                current = advect_particle(current, wind(current), |_| radius, clock.step_secs());
+            }
+            let next = interpolate_on_sphere(previous, current, clock.overstep_fraction());
+            distance += shown.angle_between(next) * radius;
+            shown = next;
+        }
+        distance
+    }
+
+    #[test]
+    fn test_particles_fly_as_far_at_any_frame_rate() {
+        let slow = distance_flown(15.0);
+        let fast = distance_flown(144.0);
+
+        assert!((slow - fast).abs() / fast < 0.01, "{slow} at 15 fps, {fast} at 144 fps");
+        // About the 20 units the wind blows in 10 s, the steps cut the corners of the arc
+        assert!((fast - 20.0).abs() < 0.5, "{fast}");
+    }
+}
@@ -1,22 +1,82 @@
+pub mod logic;
 pub mod systems;
 
 use bevy::prelude::*;
-use planetgen::wind::DEFAULT_CUBEMAP_RESOLUTION;
+use crate::planet::ClimateRebuildSet;
+use planetgen::prelude::{PlanetData, oblate_radius};
+use serde::{Deserialize, Serialize};
 
 /// Number of particles to simulate
 pub const PARTICLE_COUNT: u32 = 2500;
 
+/// How long the deflection settings have to stay unchanged before the wind is rebuilt
+pub const DEFLECTION_REBUILD_DELAY_SECS: f32 = 0.3;
+
+/// How quickly terrain following particles climb and sink to their height, per second
+pub const TERRAIN_FOLLOW_RATE: f32 = 6.0;
+
+/// Rate of the particle simulation, drawn frames interpolate between its steps
+pub const PARTICLE_STEPS_PER_SECOND: f32 = 30.0;
+
+/// Simulation seconds of one particle step, also the `delta_time` a compute shader moving
+/// the particles would get
+pub const PARTICLE_STEP_SECS: f32 = 1.0 / PARTICLE_STEPS_PER_SECOND;
+
+/// Most particle steps taken in one frame, a longer frame slows the particles down instead
+const MAX_PARTICLE_STEPS_PER_FRAME: u32 = 8;
+
+/// Clock of the particle simulation. It keeps its own fixed rate, `FixedUpdate` runs at the
+/// pace of the climate simulation.
+#[derive(Resource, Debug, Clone)]
+pub struct ParticleClock {
+    pub clock: logic::FixedStepClock,
+    /// Steps taken this frame
+    pub steps: u32,
+}
+
+impl Default for ParticleClock {
+    fn default() -> Self {
+        Self {
+            clock: logic::FixedStepClock::new(PARTICLE_STEP_SECS, MAX_PARTICLE_STEPS_PER_FRAME),
+            steps: 0,
+        }
+    }
+}
+
+/// How the wind tab shows the wind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindDisplay {
+    /// Particles blown around by the wind
+    #[default]
+    Particles,
+    /// A grid of arrows with barbs, see `WindGlyphsPlugin`
+    Glyphs,
+}
+
+impl WindDisplay {
+    pub const ALL: [WindDisplay; 2] = [WindDisplay::Particles, WindDisplay::Glyphs];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WindDisplay::Particles => "wind.display.particles",
+            WindDisplay::Glyphs => "wind.display.glyphs",
+        }
+    }
+}
+
 /// Resource to store wind particle settings
 #[derive(Resource, Clone)]
 pub struct WindParticleSettings {
     pub planet_radius: f32,
+    pub planet_oblateness: f32,
     pub particle_height_offset: f32,
+    /// Keep the offset above the terrain and the sea instead of above the mean surface
+    pub terrain_following: bool,
     pub enabled: bool,
     pub zonal_speed: f32,
     pub particle_lifespan: f32,
     pub fade_in_duration: f32,
     pub fade_out_duration: f32,
-    pub wind_cubemap_resolution: usize,
     pub show_vertical_air: bool,
 }
 
@@ -24,36 +84,86 @@ impl Default for WindParticleSettings {
     fn default() -> Self {
         Self {
             planet_radius: 50.0,
+            planet_oblateness: 0.0,
             particle_height_offset: 2.0,
+            terrain_following: true,
             enabled: true,
             zonal_speed: 5.0,
             particle_lifespan: 1.5,
             fade_in_duration: 0.6,
             fade_out_duration: 0.6,
-            wind_cubemap_resolution: DEFAULT_CUBEMAP_RESOLUTION,
             show_vertical_air: false,
         }
     }
 }
 
+impl WindParticleSettings {
+    /// Distance from the planet center at which particles fly in `direction`, the same height
+    /// above the flattened poles as above the equator
+    pub fn particle_radius(&self, direction: Vec3) -> f32 {
+        oblate_radius(self.planet_radius, self.planet_oblateness, direction) + self.particle_height_offset
+    }
+
+    /// Distance from the planet center particles head for in `direction`, over the mountains and
+    /// the sea of `planet` when following the terrain
+    pub fn target_radius(&self, planet: Option<&PlanetData>, direction: Vec3) -> f32 {
+        match planet.filter(|_| self.terrain_following) {
+            Some(planet) => logic::terrain_following_radius(
+                planet.surface_radius(direction),
+                planet.sample_height(direction),
+                planet.sea_level(),
+                self.particle_height_offset,
+            ),
+            None => self.particle_radius(direction),
+        }
+    }
+}
+
 pub struct WindPlugin;
 
 impl Plugin for WindPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<WindParticleSettings>()
-            .add_systems(Startup, systems::initialize_wind_cubemap)
-            .add_systems(Update, systems::update_wind_settings)
-            .add_systems(Update, systems::rebuild_wind_cubemap_after_planet)
+            .init_resource::<ParticleClock>()
+            // The wind is built for the first planet, there is none before it
+            .add_systems(
+                Update,
+                (
+                    systems::update_wind_settings,
+                    (
+                        systems::rebuild_wind_cubemap_after_planet,
+                        systems::rebuild_wind_cubemap_on_deflection_change,
+                        systems::resample_wind_on_resolution_change,
+                    )
+                        .after(systems::update_wind_settings),
+                    systems::apply_rebuilt_wind,
+                )
+                    .in_set(ClimateRebuildSet::Wind),
+            )
+            .add_systems(
+                Update,
+                systems::rebuild_vertical_air.in_set(ClimateRebuildSet::VerticalAir),
+            )
             .add_systems(Update, systems::handle_wind_tab_events)
-            .add_systems(Update, systems::handle_vertical_air_toggle)
             .add_systems(
                 Update,
                 (
+                    systems::handle_vertical_air_toggle,
+                    systems::handle_wind_influence_toggle,
+                )
+                    .after(ClimateRebuildSet::VerticalAir),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::despawn_disabled_particles,
                     systems::spawn_debug_particles,
-                    systems::update_particles,
+                    systems::step_particles,
+                    systems::interpolate_particles,
                     systems::update_particle_fade,
                 )
-                    .chain(),
+                    .chain()
+                    .after(systems::handle_wind_tab_events),
             );
     }
 }
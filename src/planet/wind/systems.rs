@@ -1,29 +1,34 @@
 // Wind particle systems
 
-use crate::planet::components::{PlanetEntity, VerticalAirView};
-use crate::planet::events::{PlanetSpawnedEvent, WindTabActiveEvent};
-use crate::planet::resources::{CurrentPlanetData, PlanetGenerationSettings};
-use super::{WindParticleSettings, PARTICLE_COUNT};
-use bevy::asset::RenderAssetUsages;
-use bevy::mesh::PrimitiveTopology;
+use crate::mesh::helpers::mesh_positions;
+use crate::planet::components::{OverlayMesh, OverlaySourceMeshes, PlanetEntity, VerticalAirView};
+use crate::planet::events::{
+    PlanetSpawnedEvent, SetActiveView, SettingsDiff, SettingsGroup, TerrainLodChangedEvent,
+    ViewKind, WindRebuilt,
+};
+use crate::planet::jobs::logic::ClimateJobKind;
+use crate::planet::jobs::{ClimateJobOutput, ClimateJobQueue};
+use crate::planet::resources::{
+    ClimateSettings, CurrentPlanetData, DisplaySettings, OverlayCacheKey, OverlayColorCache, OverlayKind, OverlayMeshes,
+    PlanetAssetStores, PlanetAssets, TerrainSettings, WindSettings,
+};
+use super::logic::{Debounce, advect_particle, interpolate_on_sphere};
+use super::{ParticleClock, WindDisplay, WindParticleSettings, DEFLECTION_REBUILD_DELAY_SECS, PARTICLE_COUNT};
 use bevy::prelude::*;
+use std::time::Instant;
 use rand::Rng;
-use planetgen::wind::WindCubeMap as PlanetgenWindCubeMap;
-use planetgen::wind::VerticalAirCubeMap as PlanetgenVerticalAirCubeMap;
-use planetgen::wind::vertical::divergence_to_color;
+use planetgen::prelude::{
+    MountainInfluenceMap, Palette, PlanetData, VerticalAirCubeMap as PlanetgenVerticalAirCubeMap,
+    WindCubeMap as PlanetgenWindCubeMap, divergence_to_color, influence_to_color,
+};
 
 /// Bevy-compatible WindCubeMap resource
 #[derive(Resource, Clone)]
 pub struct WindCubeMap {
-    inner: PlanetgenWindCubeMap,
+    pub inner: PlanetgenWindCubeMap,
 }
 
 impl WindCubeMap {
-    pub fn build(resolution: usize, zonal_speed: f32) -> Self {
-        let inner = PlanetgenWindCubeMap::build(resolution, zonal_speed);
-        Self { inner }
-    }
-
     pub fn sample(&self, position: Vec3) -> Vec3 {
         self.inner.sample(position)
     }
@@ -46,79 +51,115 @@ impl VerticalAirCubeMap {
     }
 }
 
+/// How much mountains block the wind, built together with the deflected wind cubemap
+#[derive(Resource, Clone)]
+pub struct MountainInfluence {
+    pub inner: MountainInfluenceMap,
+}
+
+impl MountainInfluence {
+    pub fn sample_cost(&self, position: Vec3) -> f32 {
+        self.inner.sample(position).0
+    }
+}
+
 /// Marker component for vertical air movement overlay mesh
 #[derive(Component)]
 pub struct VerticalAirMesh;
 
-/// Marker component for wind particle visualization
+/// Marker component for the mountain influence overlay mesh
+#[derive(Component)]
+pub struct WindInfluenceMesh;
+
+/// Marker component for wind particle visualization. Age and lifetime are in simulation
+/// seconds, the transform is drawn between the last two simulated positions.
 #[derive(Component)]
 pub struct WindParticle {
     pub velocity: Vec3,
     pub latitudinal_speed: f32, // Current latitudinal velocity component
     pub age: f32,
     pub lifetime: f32,
-}
-
-/// Initialize the wind cube map resource at startup
-pub fn initialize_wind_cubemap(
-    mut commands: Commands,
-    settings: Res<WindParticleSettings>,
-) {
-    info!("Initializing wind cube map...");
-    let cubemap = WindCubeMap::build(settings.wind_cubemap_resolution, settings.zonal_speed);
-    let vertical = VerticalAirCubeMap::build_from_wind(&cubemap.inner);
-    commands.insert_resource(cubemap);
-    commands.insert_resource(vertical);
+    /// Position before the last simulation step, in planet space
+    pub previous: Vec3,
+    /// Position after the last simulation step, in planet space
+    pub current: Vec3,
 }
 
 /// Update wind particle settings from planet generation settings
 pub fn update_wind_settings(
-    planet_settings: Res<PlanetGenerationSettings>,
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    (terrain_settings, planet_wind_settings, display_settings): (
+        Res<TerrainSettings>,
+        Res<WindSettings>,
+        Res<DisplaySettings>,
+    ),
     mut wind_settings: ResMut<WindParticleSettings>,
 ) {
-    if planet_settings.is_changed() {
-        wind_settings.planet_radius = planet_settings.radius;
-        wind_settings.particle_height_offset = planet_settings.wind_particle_height_offset;
-        wind_settings.enabled = planet_settings.show_wind;
-        wind_settings.zonal_speed = planet_settings.wind_zonal_speed;
-        wind_settings.particle_lifespan = planet_settings.wind_particle_lifespan;
-        wind_settings.show_vertical_air = planet_settings.show_vertical_air;
+    // Radius is a terrain setting, the show_* toggles are visual ones
+    let groups = [SettingsGroup::Wind, SettingsGroup::Visual, SettingsGroup::Terrain];
+    let changed = SettingsDiff::read_any(&mut settings_diffs, &groups);
+    // There is no diff for the initial settings, pick them up on the first run
+    let added = terrain_settings.is_added() || planet_wind_settings.is_added() || display_settings.is_added();
+    if changed || added {
+        wind_settings.planet_radius = terrain_settings.radius;
+        wind_settings.planet_oblateness = terrain_settings.oblateness;
+        wind_settings.particle_height_offset = planet_wind_settings.wind_particle_height_offset;
+        wind_settings.terrain_following = planet_wind_settings.wind_terrain_following;
+        wind_settings.enabled =
+            display_settings.show_wind && planet_wind_settings.wind_display == WindDisplay::Particles;
+        wind_settings.zonal_speed = planet_wind_settings.wind_zonal_speed;
+        wind_settings.particle_lifespan = planet_wind_settings.wind_particle_lifespan;
+        wind_settings.show_vertical_air = display_settings.show_vertical_air;
     }
 }
 
 /// Handle wind tab activation/deactivation
 pub fn handle_wind_tab_events(
-    mut wind_tab_events: MessageReader<WindTabActiveEvent>,
-    mut planet_settings: ResMut<PlanetGenerationSettings>,
+    mut set_active_view: MessageReader<SetActiveView>,
+    (mut display_settings, planet_wind_settings): (ResMut<DisplaySettings>, Res<WindSettings>),
+    mut wind_settings: ResMut<WindParticleSettings>,
+) {
+    let Some(&SetActiveView(view)) = set_active_view.read().last() else {
+        return;
+    };
+    let active = view == ViewKind::Wind;
+    display_settings.show_wind = active;
+    // Applied right away, waiting for the settings diff would respawn the particles for a frame
+    wind_settings.enabled = active && planet_wind_settings.wind_display == WindDisplay::Particles;
+}
+
+/// Despawn the particles when switching away from the wind tab or over to the glyphs
+pub fn despawn_disabled_particles(
+    settings: Res<WindParticleSettings>,
     existing_particles: Query<Entity, With<WindParticle>>,
     mut commands: Commands,
 ) {
-    for event in wind_tab_events.read() {
-        planet_settings.show_wind = event.active;
-
-        // Despawn debug particles when switching away from wind tab
-        if !event.active {
-            for entity in existing_particles.iter() {
-                commands.entity(entity).despawn();
-            }
-        }
+    if settings.enabled {
+        return;
+    }
+    for entity in existing_particles.iter() {
+        commands.entity(entity).despawn();
     }
 }
 
 /// Spawn wind particle visualization spheres
 pub fn spawn_debug_particles(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    (mut meshes, mut materials, mut planet_assets): PlanetAssetStores,
     planet_query: Query<Entity, With<PlanetEntity>>,
     existing_particles: Query<Entity, With<WindParticle>>,
     settings: Res<WindParticleSettings>,
-    wind_cubemap: Res<WindCubeMap>,
+    wind_cubemap: Option<Res<WindCubeMap>>,
+    planet_data: Res<CurrentPlanetData>,
 ) {
     // Only spawn if enabled and not already spawned
     if !settings.enabled || !existing_particles.is_empty() {
         return;
     }
+    // No wind before the first planet
+    let Some(wind_cubemap) = wind_cubemap else {
+        return;
+    };
 
     let Some(planet_entity) = planet_query.iter().next() else {
         return;
@@ -126,16 +167,14 @@ pub fn spawn_debug_particles(
 
     info!("Spawning {} wind particles with random positions", PARTICLE_COUNT);
 
-    let sphere_mesh = meshes.add(Sphere::new(0.3).mesh().ico(2).unwrap());
-
-    let sphere_radius = settings.planet_radius + settings.particle_height_offset;
+    let sphere_mesh = planet_assets.add_mesh(&mut meshes, Sphere::new(0.3).mesh().ico(2).unwrap());
 
     let mut rng = rand::rng();
 
     // Spawn particles at random positions on sphere
     for _ in 0..PARTICLE_COUNT {
         let direction = random_sphere_point(&mut rng);
-        let position = direction * sphere_radius;
+        let position = direction * settings.target_radius(planet_data.planet_data.as_ref(), direction);
 
         // Get initial velocity from pre-computed wind cube map
         let velocity = wind_cubemap.sample(direction);
@@ -148,7 +187,7 @@ pub fn spawn_debug_particles(
         let age: f32 = rng.random_range(0.0..lifetime);
 
         // Create material with alpha blending enabled
-        let material = materials.add(StandardMaterial {
+        let material = planet_assets.add_material(&mut materials, StandardMaterial {
             base_color: Color::srgba(1.0, 1.0, 0.8, 1.0),
             emissive: LinearRgba::rgb(1.0, 1.0, 0.8) * 2.0,
             alpha_mode: AlphaMode::Blend,
@@ -165,6 +204,8 @@ pub fn spawn_debug_particles(
                     latitudinal_speed: 0.0, // No longer used, kept for compatibility
                     age,
                     lifetime,
+                    previous: position,
+                    current: position,
                 },
             ));
         });
@@ -188,14 +229,14 @@ fn random_sphere_point(rng: &mut impl Rng) -> Vec3 {
 
 fn respawn_particle(
     particle: &mut WindParticle,
-    transform: &mut Transform,
     settings: &WindParticleSettings,
-    sphere_radius: f32,
     wind_cubemap: &WindCubeMap,
+    planet: Option<&PlanetData>,
     rng: &mut impl Rng,
 ) {
+    // Born right at its height, the fade in hides the jump
     let direction = random_sphere_point(rng);
-    let position = direction * sphere_radius;
+    let position = direction * settings.target_radius(planet, direction);
 
     // Get wind velocity from pre-computed cube map
     let velocity = wind_cubemap.sample(direction);
@@ -207,55 +248,76 @@ fn respawn_particle(
     particle.lifetime = settings.particle_lifespan * variation;
     particle.age = 0.0;
 
-    transform.translation = position;
+    // Not drawn sliding over from where it died
+    particle.previous = position;
+    particle.current = position;
 }
 
-/// Update particle positions and handle respawning
-pub fn update_particles(
-    mut particles: ParamSet<(
-        Query<&Transform, With<WindParticle>>,
-        Query<(&mut Transform, &mut WindParticle)>,
-    )>,
+/// Advance the particles by the fixed steps due this frame, respawning the ones that lived
+/// out their lifetime. Steps are the same length at any frame rate, so the particles fly as
+/// far and leave the same trails at 30 and at 144 fps.
+pub fn step_particles(
+    mut particles: Query<&mut WindParticle>,
     time: Res<Time>,
+    mut clock: ResMut<ParticleClock>,
     settings: Res<WindParticleSettings>,
-    wind_cubemap: Res<WindCubeMap>,
+    wind_cubemap: Option<Res<WindCubeMap>>,
+    planet_data: Res<CurrentPlanetData>,
 ) {
-    if !settings.enabled {
+    let steps = clock.clock.advance(time.delta_secs());
+    clock.steps = steps;
+    if !settings.enabled || steps == 0 {
         return;
     }
-
-    let delta = time.delta_secs();
-    let sphere_radius = settings.planet_radius + settings.particle_height_offset;
+    let Some(wind_cubemap) = wind_cubemap else {
+        return;
+    };
+    let planet = planet_data.planet_data.as_ref();
+    let step_secs = clock.clock.step_secs();
+    let target_radius = |direction: Vec3| settings.target_radius(planet, direction);
 
     let mut rng = rand::rng();
 
-    for (mut transform, mut particle) in particles.p1().iter_mut() {
-        particle.age += delta;
-
-        let direction = transform.translation.normalize();
+    for mut particle in particles.iter_mut() {
+        for _ in 0..steps {
+            particle.age += step_secs;
+            if particle.age >= particle.lifetime {
+                respawn_particle(&mut particle, &settings, &wind_cubemap, planet, &mut rng);
+                continue;
+            }
 
-        if particle.age >= particle.lifetime {
-            respawn_particle(&mut particle, &mut transform, &settings, sphere_radius, &wind_cubemap, &mut rng);
-            continue;
+            // Sample wind velocity from pre-computed cube map
+            particle.velocity = wind_cubemap.sample(particle.current.normalize());
+            particle.previous = particle.current;
+            particle.current = advect_particle(particle.current, particle.velocity, target_radius, step_secs);
         }
+    }
+}
 
-        // Sample wind velocity from pre-computed cube map
-        particle.velocity = wind_cubemap.sample(direction);
-
-        let current_pos = transform.translation;
-        let new_pos = current_pos + particle.velocity * delta;
-
-        transform.translation = new_pos.normalize() * sphere_radius;
+/// Draw every particle between its last two simulated positions, by how far the frame is
+/// into the next step
+pub fn interpolate_particles(
+    mut particles: Query<(&WindParticle, &mut Transform)>,
+    clock: Res<ParticleClock>,
+    settings: Res<WindParticleSettings>,
+) {
+    if !settings.enabled {
+        return;
+    }
+    let t = clock.clock.overstep_fraction();
+    for (particle, mut transform) in particles.iter_mut() {
+        transform.translation = interpolate_on_sphere(particle.previous, particle.current, t);
     }
 }
 
-/// Update particle transparency for fade in/out effects
+/// Update particle transparency for fade in/out effects, the ages only change on a step
 pub fn update_particle_fade(
     mut particles: Query<(&WindParticle, &MeshMaterial3d<StandardMaterial>)>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     settings: Res<WindParticleSettings>,
+    clock: Res<ParticleClock>,
 ) {
-    if !settings.enabled {
+    if !settings.enabled || clock.steps == 0 {
         return;
     }
 
@@ -291,119 +353,193 @@ pub fn update_particle_fade(
 
 /// Rebuild wind cubemap with terrain deflection after a planet is generated.
 pub fn rebuild_wind_cubemap_after_planet(
-    mut commands: Commands,
+    mut queue: ResMut<ClimateJobQueue>,
     mut events: MessageReader<PlanetSpawnedEvent>,
     planet_data: Res<CurrentPlanetData>,
     settings: Res<WindParticleSettings>,
-    planet_settings: Res<PlanetGenerationSettings>,
+    (planet_wind_settings, climate_settings): (Res<WindSettings>, Res<ClimateSettings>),
 ) {
-    for _ in events.read() {
-        let Some(ref planet) = planet_data.planet_data else {
-            continue;
-        };
+    if events.read().count() == 0 {
+        return;
+    }
+    let Some(ref planet) = planet_data.planet_data else {
+        return;
+    };
+    queue_deflected_wind(planet, &settings, (&planet_wind_settings, &climate_settings), &mut queue);
+}
 
-        let deflection_config = planetgen::config::WindDeflectionConfig {
-            height_threshold: planet_settings.wind_deflection_height_threshold,
-            height_scale: planet_settings.wind_deflection_height_scale,
-            spread_radius: planet_settings.wind_deflection_spread_radius,
-            spread_decay: planet_settings.wind_deflection_spread_decay,
-            deflection_strength: planet_settings.wind_deflection_strength,
-            deflection_iterations: planet_settings.wind_deflection_iterations,
-        };
-        let (wind_map, _influence) = PlanetgenWindCubeMap::build_with_terrain(
-            settings.wind_cubemap_resolution,
-            settings.zonal_speed,
-            planet,
-            &deflection_config,
-        );
+/// Rebuild only the wind cubemap when a deflection setting changes, the terrain stays as it is.
+/// Vertical air, precipitation and their overlays follow the new cubemap on their own.
+/// Waits until the sliders stop moving, a rebuild takes too long to run every frame.
+pub fn rebuild_wind_cubemap_on_deflection_change(
+    mut queue: ResMut<ClimateJobQueue>,
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut debounce: Local<Debounce>,
+    time: Res<Time>,
+    planet_data: Res<CurrentPlanetData>,
+    settings: Res<WindParticleSettings>,
+    (planet_wind_settings, climate_settings): (Res<WindSettings>, Res<ClimateSettings>),
+) {
+    if SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::WindDeflection]) {
+        debounce.trigger(DEFLECTION_REBUILD_DELAY_SECS);
+    }
+    if !debounce.tick(time.delta_secs()) {
+        return;
+    }
+    let Some(ref planet) = planet_data.planet_data else {
+        return;
+    };
+    queue_deflected_wind(planet, &settings, (&planet_wind_settings, &climate_settings), &mut queue);
+}
+
+/// Put the wind built by the last finished wind job in place
+pub fn apply_rebuilt_wind(mut rebuilt: MessageReader<WindRebuilt>, mut commands: Commands) {
+    let Some(rebuilt) = rebuilt.read().last() else {
+        return;
+    };
+    commands.insert_resource(rebuilt.wind.clone());
+    commands.insert_resource(rebuilt.influence.clone());
+}
 
-        let vertical = VerticalAirCubeMap::build_from_wind(&wind_map);
-        commands.insert_resource(WindCubeMap { inner: wind_map });
-        commands.insert_resource(vertical);
-        info!("Wind cubemap rebuilt with terrain deflection");
+/// Resample the wind cubemap and the mountain influence when the resolution setting changes,
+/// the deflection isn't computed again. Vertical air and precipitation follow the new cubemap.
+pub fn resample_wind_on_resolution_change(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    planet_data: Res<CurrentPlanetData>,
+    climate_settings: Res<ClimateSettings>,
+    wind_cubemap: Option<ResMut<WindCubeMap>>,
+    influence: Option<ResMut<MountainInfluence>>,
+) {
+    if !SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::ClimateResolution]) {
+        return;
+    }
+    let (Some(planet), Some(mut wind_cubemap)) = (planet_data.planet_data.as_ref(), wind_cubemap) else {
+        return;
+    };
+    let resolution = climate_settings.precipitation_resolution_for(planet.face_grid_size);
+    if resolution == wind_cubemap.inner.resolution {
+        return;
+    }
+
+    info!("Resampling wind cubemap to {resolution}");
+    wind_cubemap.inner = wind_cubemap.inner.resample(resolution);
+    if let Some(mut influence) = influence {
+        influence.inner = influence.inner.resample(resolution);
     }
 }
 
+/// Queue the wind cubemap deflected by the planet's mountains, with the mountain influence that
+/// comes with it
+fn queue_deflected_wind(
+    planet: &PlanetData,
+    settings: &WindParticleSettings,
+    (planet_wind_settings, climate_settings): (&WindSettings, &ClimateSettings),
+    queue: &mut ClimateJobQueue,
+) {
+    let deflection_config = planetgen::config::WindDeflectionConfig {
+        height_threshold: planet_wind_settings.wind_deflection_height_threshold,
+        height_scale: planet_wind_settings.wind_deflection_height_scale,
+        spread_radius: planet_wind_settings.wind_deflection_spread_radius,
+        spread_decay: planet_wind_settings.wind_deflection_spread_decay,
+        deflection_strength: planet_wind_settings.wind_deflection_strength,
+        deflection_iterations: planet_wind_settings.wind_deflection_iterations,
+    };
+    // The precipitation map is built on the vertical air from this wind, so the precipitation
+    // resolution setting caps both
+    let resolution = climate_settings.precipitation_resolution_for(planet.face_grid_size);
+    let zonal_speed = settings.zonal_speed;
+    let planet = planet.clone();
+    queue.push(ClimateJobKind::RebuildWind, move || {
+        let start = Instant::now();
+        let (wind_map, influence) =
+            PlanetgenWindCubeMap::build_with_terrain(resolution, zonal_speed, &planet, &deflection_config);
+        info!("Wind cubemap rebuilt with terrain deflection in {:?}", start.elapsed());
+        ClimateJobOutput::Wind(Box::new(WindRebuilt {
+            wind: WindCubeMap { inner: wind_map },
+            influence: MountainInfluence { inner: influence },
+        }))
+    });
+}
+
+/// Build the vertical air from the wind cubemap whenever the wind was rebuilt
+pub fn rebuild_vertical_air(wind_cubemap: Option<Res<WindCubeMap>>, mut commands: Commands) {
+    let Some(wind_cubemap) = wind_cubemap.filter(|wind| wind.is_changed()) else {
+        return;
+    };
+    commands.insert_resource(VerticalAirCubeMap::build_from_wind(&wind_cubemap.inner));
+}
+
 /// Toggle vertical air movement overlay on/off.
-/// Creates colored mesh copies when enabled (hiding originals), despawns them when disabled.
+/// Only creates colored mesh copies when enabled and despawns them when disabled, the
+/// centralized tab visibility system decides what is shown.
 pub fn handle_vertical_air_toggle(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut lod_events: MessageReader<TerrainLodChangedEvent>,
     settings: Res<WindParticleSettings>,
-    planet_settings: Res<PlanetGenerationSettings>,
-    vertical_cubemap: Res<VerticalAirCubeMap>,
+    display_settings: Res<DisplaySettings>,
+    vertical_cubemap: Option<Res<VerticalAirCubeMap>>,
+    planet_data: Res<CurrentPlanetData>,
+    mut color_cache: ResMut<OverlayColorCache>,
+    (mut overlay_meshes, mut planet_assets): (ResMut<OverlayMeshes>, ResMut<PlanetAssets>),
     planet_query: Query<Entity, With<PlanetEntity>>,
-    continent_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::ContinentViewMesh>,
-    >,
-    ocean_query: Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::OceanEntity>,
-    >,
-    continent_view_query: Query<Entity, With<crate::planet::components::ContinentView>>,
-    ocean_view_query: Query<Entity, With<crate::planet::components::OceanEntity>>,
+    source_query: OverlaySourceMeshes,
     existing_meshes: Query<Entity, With<VerticalAirMesh>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut commands: Commands,
 ) {
-    if !planet_settings.is_changed() && !vertical_cubemap.is_changed() {
+    let changed = SettingsDiff::read_groups(&mut settings_diffs);
+    let toggled = changed.contains(&SettingsGroup::Wind) || changed.contains(&SettingsGroup::Visual);
+    let palette_changed = changed.contains(&SettingsGroup::Palette);
+    let lod_changed = lod_events.read().count() > 0;
+    // Without a planet there is no vertical air and nothing to draw it on
+    let Some(vertical_cubemap) = vertical_cubemap else {
+        return;
+    };
+    if !toggled && !lod_changed && !palette_changed && !vertical_cubemap.is_changed() {
         return;
     }
 
     let should_show = settings.show_vertical_air && settings.enabled;
     let has_meshes = !existing_meshes.is_empty();
+    // Colors only depend on the cubemap, which is rebuilt for every new planet, and the palette
+    let palette = display_settings.palette;
+    let key = OverlayCacheKey::new(planet_data.generation, vertical_cubemap.last_changed(), palette, &[]);
 
     if should_show && !has_meshes {
         spawn_vertical_air_meshes(
-            &planet_query, &continent_query, &ocean_query,
-            &vertical_cubemap, &mut meshes, &mut materials, &mut commands,
+            &planet_query, &source_query, &vertical_cubemap, palette, key,
+            &mut color_cache, &mut overlay_meshes, &mut planet_assets, &mut meshes, &mut materials, &mut commands,
         );
-        // Hide original continent + ocean meshes so overlay is visible
-        for entity in continent_view_query.iter() {
-            commands.entity(entity).insert(Visibility::Hidden);
-        }
-        for entity in ocean_view_query.iter() {
-            commands.entity(entity).insert(Visibility::Hidden);
-        }
     } else if !should_show && has_meshes {
-        // Despawn overlay
+        // DO NOT manipulate continent or ocean visibility here!
+        // The centralized tab visibility system shows them again once the overlay is gone
         for entity in existing_meshes.iter() {
             commands.entity(entity).despawn();
         }
-        // Only restore continent/ocean if we're still on Wind tab (user unchecked the box)
-        // If leaving Wind tab (enabled=false), let centralized tab system handle visibility
-        if settings.enabled {
-            for entity in continent_view_query.iter() {
-                commands.entity(entity).insert(Visibility::Visible);
-            }
-            for entity in ocean_view_query.iter() {
-                commands.entity(entity).insert(Visibility::Visible);
-            }
-        }
-    } else if should_show && has_meshes && vertical_cubemap.is_changed() {
-        // Rebuild after wind cubemap changed
+    } else if should_show && has_meshes && (vertical_cubemap.is_changed() || lod_changed || palette_changed) {
+        // Rebuild after wind cubemap, the terrain LOD or the palette changed
         for entity in existing_meshes.iter() {
             commands.entity(entity).despawn();
         }
         spawn_vertical_air_meshes(
-            &planet_query, &continent_query, &ocean_query,
-            &vertical_cubemap, &mut meshes, &mut materials, &mut commands,
+            &planet_query, &source_query, &vertical_cubemap, palette, key,
+            &mut color_cache, &mut overlay_meshes, &mut planet_assets, &mut meshes, &mut materials, &mut commands,
         );
     }
 }
 
-/// Helper to spawn vertical air overlay meshes from continent + ocean originals.
+/// Helper to spawn the vertical air overlay on the shared copies of the continent and ocean
+/// meshes.
 fn spawn_vertical_air_meshes(
     planet_query: &Query<Entity, With<PlanetEntity>>,
-    continent_query: &Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::ContinentViewMesh>,
-    >,
-    ocean_query: &Query<
-        (Entity, &Mesh3d, &MeshMaterial3d<StandardMaterial>),
-        With<crate::planet::components::OceanEntity>,
-    >,
+    source_query: &OverlaySourceMeshes,
     vertical_cubemap: &VerticalAirCubeMap,
+    palette: Palette,
+    key: OverlayCacheKey,
+    color_cache: &mut OverlayColorCache,
+    overlay_meshes: &mut OverlayMeshes,
+    planet_assets: &mut PlanetAssets,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
     commands: &mut Commands,
@@ -412,97 +548,155 @@ fn spawn_vertical_air_meshes(
         return;
     };
 
-    info!("Creating vertical air movement overlay");
-
-    for (_entity, mesh_handle, _material) in continent_query.iter() {
-        if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-            let colored_mesh = create_vertical_air_mesh(original_mesh, vertical_cubemap);
-            let mesh_handle = meshes.add(colored_mesh);
-            let material = materials.add(StandardMaterial {
-                base_color: Color::WHITE,
-                unlit: true,
-                ..default()
-            });
-
-            let entity = commands
-                .spawn((
-                    Mesh3d(mesh_handle),
-                    MeshMaterial3d(material),
-                    Transform::default(),
-                    GlobalTransform::default(),
-                    Visibility::Visible,
-                    VerticalAirMesh,
-                    VerticalAirView,
-                ))
-                .id();
-            commands.entity(planet_entity).add_child(entity);
-        }
-    }
+    let start = Instant::now();
 
-    for (_entity, mesh_handle, _material) in ocean_query.iter() {
-        if let Some(original_mesh) = meshes.get(&mesh_handle.0) {
-            let colored_mesh = create_vertical_air_mesh(original_mesh, vertical_cubemap);
-            let mesh_handle = meshes.add(colored_mesh);
-            let material = materials.add(StandardMaterial {
-                base_color: Color::WHITE,
-                unlit: true,
-                ..default()
-            });
-
-            let entity = commands
-                .spawn((
-                    Mesh3d(mesh_handle),
-                    MeshMaterial3d(material),
-                    Transform::default(),
-                    GlobalTransform::default(),
-                    Visibility::Visible,
-                    VerticalAirMesh,
-                    VerticalAirView,
-                ))
-                .id();
-            commands.entity(planet_entity).add_child(entity);
-        }
+    for mesh_handle in source_query.iter() {
+        let Some(original_mesh) = meshes.get(&mesh_handle.0) else {
+            continue;
+        };
+        color_cache.ensure_colors(OverlayKind::VerticalAir, key, mesh_handle.0.id(), || {
+            vertical_air_colors(original_mesh, vertical_cubemap, palette)
+        });
+        let Some(copy_handle) = overlay_meshes.copy_of(&mesh_handle.0, meshes, planet_assets) else {
+            continue;
+        };
+        let material = planet_assets.add_material(materials, StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            ..default()
+        });
+
+        let entity = commands
+            .spawn((
+                Mesh3d(copy_handle),
+                MeshMaterial3d(material),
+                Transform::default(),
+                GlobalTransform::default(),
+                Visibility::Visible,
+                VerticalAirMesh,
+                VerticalAirView,
+                OverlayMesh {
+                    source: mesh_handle.0.id(),
+                    colors: Some(OverlayKind::VerticalAir),
+                },
+            ))
+            .id();
+        commands.entity(planet_entity).add_child(entity);
     }
+
+    info!("Vertical air overlay ready in {:?}", start.elapsed());
 }
 
-/// Create a mesh copy with vertex colors based on vertical air movement
-fn create_vertical_air_mesh(
+/// Vertex colors based on vertical air movement
+fn vertical_air_colors(
     original_mesh: &Mesh,
     vertical_cubemap: &VerticalAirCubeMap,
-) -> Mesh {
-    let mut new_mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::default(),
-    );
-
-    if let Some(positions_attr) = original_mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
-        if let Some(positions) = positions_attr.as_float3() {
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions.to_vec());
-
-            let colors: Vec<[f32; 4]> = positions
-                .iter()
-                .map(|&[x, y, z]| {
-                    let direction = Vec3::new(x, y, z).normalize();
-                    let value = vertical_cubemap.sample(direction);
-                    let color = divergence_to_color(value);
-                    [color.x, color.y, color.z, 1.0]
-                })
-                .collect();
-
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
-        }
+    palette: Palette,
+) -> Vec<[f32; 4]> {
+    mesh_positions(original_mesh)
+        .iter()
+        .map(|&[x, y, z]| {
+            let direction = Vec3::new(x, y, z).normalize();
+            let value = vertical_cubemap.sample(direction);
+            let color = divergence_to_color(value, palette);
+            [color.x, color.y, color.z, 1.0]
+        })
+        .collect()
+}
+
+
+/// Toggle the mountain influence overlay on/off, same lifecycle as the vertical air overlay.
+/// The influence map only exists once a planet was generated.
+pub fn handle_wind_influence_toggle(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut lod_events: MessageReader<TerrainLodChangedEvent>,
+    settings: Res<WindParticleSettings>,
+    display_settings: Res<DisplaySettings>,
+    influence: Option<Res<MountainInfluence>>,
+    planet_data: Res<CurrentPlanetData>,
+    mut color_cache: ResMut<OverlayColorCache>,
+    (mut overlay_meshes, mut planet_assets): (ResMut<OverlayMeshes>, ResMut<PlanetAssets>),
+    planet_query: Query<Entity, With<PlanetEntity>>,
+    source_query: OverlaySourceMeshes,
+    existing_meshes: Query<Entity, With<WindInfluenceMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    let changed = SettingsDiff::read_groups(&mut settings_diffs);
+    let toggled = changed.contains(&SettingsGroup::Wind) || changed.contains(&SettingsGroup::Visual);
+    let palette_changed = changed.contains(&SettingsGroup::Palette);
+    let lod_changed = lod_events.read().count() > 0;
+    let influence_changed = influence.as_ref().is_some_and(|influence| influence.is_changed());
+    if !toggled && !lod_changed && !palette_changed && !influence_changed {
+        return;
     }
 
-    if let Some(normals_attr) = original_mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
-        if let Some(normals) = normals_attr.as_float3() {
-            new_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals.to_vec());
+    let has_meshes = !existing_meshes.is_empty();
+    let influence = influence.filter(|_| display_settings.show_wind_influence && settings.enabled);
+    let rebuild = !has_meshes || influence_changed || lod_changed || palette_changed;
+    if has_meshes && (influence.is_none() || rebuild) {
+        for entity in existing_meshes.iter() {
+            commands.entity(entity).despawn();
         }
     }
+    let Some(influence) = influence.filter(|_| rebuild) else {
+        return;
+    };
+    let Some(planet_entity) = planet_query.iter().next() else {
+        return;
+    };
+
+    let start = Instant::now();
+    let palette = display_settings.palette;
+    let key = OverlayCacheKey::new(planet_data.generation, influence.last_changed(), palette, &[]);
+    for mesh_handle in source_query.iter() {
+        let Some(original_mesh) = meshes.get(&mesh_handle.0) else {
+            continue;
+        };
+        color_cache.ensure_colors(OverlayKind::WindInfluence, key, mesh_handle.0.id(), || {
+            wind_influence_colors(original_mesh, &influence, palette)
+        });
+        let Some(copy_handle) = overlay_meshes.copy_of(&mesh_handle.0, &mut meshes, &mut planet_assets) else {
+            continue;
+        };
+        let material = planet_assets.add_material(&mut materials, StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            ..default()
+        });
 
-    if let Some(indices) = original_mesh.indices() {
-        new_mesh.insert_indices(indices.clone());
+        let entity = commands
+            .spawn((
+                Mesh3d(copy_handle),
+                MeshMaterial3d(material),
+                Transform::default(),
+                GlobalTransform::default(),
+                Visibility::Visible,
+                WindInfluenceMesh,
+                OverlayMesh {
+                    source: mesh_handle.0.id(),
+                    colors: Some(OverlayKind::WindInfluence),
+                },
+            ))
+            .id();
+        commands.entity(planet_entity).add_child(entity);
     }
 
-    new_mesh
+    info!("Wind influence overlay ready in {:?}", start.elapsed());
 }
 
+/// Vertex colors based on the mountain influence cost
+fn wind_influence_colors(
+    original_mesh: &Mesh,
+    influence: &MountainInfluence,
+    palette: Palette,
+) -> Vec<[f32; 4]> {
+    mesh_positions(original_mesh)
+        .iter()
+        .map(|&[x, y, z]| {
+            let color = influence_to_color(influence.sample_cost(Vec3::new(x, y, z)), palette);
+            [color.x, color.y, color.z, 1.0]
+        })
+        .collect()
+}
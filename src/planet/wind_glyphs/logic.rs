@@ -0,0 +1,127 @@
+use bevy::math::{Vec2, Vec3};
+use planetgen::prelude::WindGlyph;
+
+/// Glyph length as a share of the arc between two grid points, the rest is the gap to the next
+pub const GLYPH_FILL: f32 = 0.6;
+
+/// Wind speed a barb stands for
+pub const BARB_SPEED: f32 = 2.0;
+
+/// Barbs on the fastest glyphs, more wouldn't fit along the shaft
+pub const MAX_BARBS: usize = 4;
+
+/// Shaft width, head and barb sizes, all as shares of the glyph length
+const SHAFT_WIDTH: f32 = 0.05;
+const HEAD_LENGTH: f32 = 0.3;
+const HEAD_WIDTH: f32 = 0.3;
+const BARB_LENGTH: f32 = 0.3;
+const BARB_WIDTH: f32 = 0.05;
+const BARB_SPACING: f32 = 0.12;
+
+/// Barbs on the tail of a glyph for wind of `speed`, one per `BARB_SPEED` rounded to the nearest
+pub fn barb_count(speed: f32) -> usize {
+    ((speed / BARB_SPEED).round().max(0.0) as usize).min(MAX_BARBS)
+}
+
+/// Triangles of a glyph of unit length in its own 2D frame: x along the wind, y to its right,
+/// centered on the origin. An arrow pointing downwind with the barbs at its upwind tail,
+/// slanting back from the shaft like on a weather map.
+pub fn glyph_triangles(speed: f32) -> Vec<[Vec2; 3]> {
+    let tail = -0.5;
+    let head_base = 0.5 - HEAD_LENGTH;
+    let half_width = SHAFT_WIDTH / 2.0;
+    let mut triangles = vec![
+        // Shaft
+        [Vec2::new(tail, -half_width), Vec2::new(head_base, -half_width), Vec2::new(head_base, half_width)],
+        [Vec2::new(tail, -half_width), Vec2::new(head_base, half_width), Vec2::new(tail, half_width)],
+        // Head
+        [Vec2::new(head_base, -HEAD_WIDTH / 2.0), Vec2::new(0.5, 0.0), Vec2::new(head_base, HEAD_WIDTH / 2.0)],
+    ];
+
+    for barb in 0..barb_count(speed) {
+        let root = tail + barb as f32 * BARB_SPACING;
+        triangles.push([
+            Vec2::new(root, 0.0),
+            Vec2::new(root + BARB_WIDTH, 0.0),
+            Vec2::new(root - BARB_LENGTH / 2.0, BARB_LENGTH),
+        ]);
+    }
+
+    triangles
+}
+
+/// Length of the glyphs on a grid `spacing_degrees` apart around a planet of `radius`
+pub fn glyph_length(radius: f32, spacing_degrees: f32) -> f32 {
+    radius * spacing_degrees.to_radians() * GLYPH_FILL
+}
+
+/// Vertices of every glyph as one triangle list, with a normal per vertex pointing away from
+/// the planet. Each glyph lies flat in the tangent plane at `radius_at` its direction.
+pub fn glyph_vertices(
+    glyphs: &[WindGlyph],
+    length: f32,
+    radius_at: impl Fn(Vec3) -> f32,
+) -> (Vec<[f32; 3]>, Vec<[f32; 3]>) {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+
+    for glyph in glyphs {
+        let (along, right, up) = glyph.frame();
+        let center = glyph.direction * radius_at(glyph.direction);
+        let place = |point: Vec2| center + (along * point.x + right * point.y) * length;
+        for triangle in glyph_triangles(glyph.speed) {
+            // Counter-clockwise seen from above
+            for point in [triangle[0], triangle[2], triangle[1]] {
+                positions.push(place(point).to_array());
+                normals.push(up.to_array());
+            }
+        }
+    }
+
+    (positions, normals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use planetgen::prelude::{WindCubeMap, sample_wind_glyphs};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0.0, 0)]
+    #[case(0.9, 0)]
+    #[case(1.0, 1)]
+    #[case(BARB_SPEED * 3.0, 3)]
+    #[case(100.0, MAX_BARBS)]
+    fn test_barbs_count_the_speed(#[case] speed: f32, #[case] expected: usize) {
+        assert_eq!(barb_count(speed), expected);
+    }
+
+    #[test]
+    fn test_glyphs_lie_flat_at_their_radius() {
+        let wind = WindCubeMap::build(16, 5.0);
+        let glyphs = sample_wind_glyphs(&wind, 30.0);
+        let (positions, normals) = glyph_vertices(&glyphs, 1.0, |_| 10.0);
+
+        assert_eq!(positions.len(), normals.len());
+        assert_eq!(positions.len() % 3, 0);
+        for (position, normal) in positions.iter().zip(&normals) {
+            let up = Vec3::from_array(*normal);
+            // Off the radius only by how far the flat glyph leaves the curved surface
+            let height = Vec3::from_array(*position).dot(up);
+            assert!((height - 10.0).abs() < 1e-3, "{height}");
+        }
+    }
+
+    #[test]
+    fn test_glyphs_point_downwind() {
+        let triangles = glyph_triangles(0.0);
+        let tip = triangles
+            .iter()
+            .flatten()
+            .max_by(|a, b| a.x.total_cmp(&b.x))
+            .unwrap();
+
+        assert_eq!(*tip, Vec2::new(0.5, 0.0));
+    }
+}
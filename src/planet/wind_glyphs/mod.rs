@@ -0,0 +1,33 @@
+pub mod logic;
+pub mod systems;
+
+use crate::core::state::GameState;
+use crate::planet::{ClimateRebuildSet, SettingsDiffSet};
+use crate::planet::systems::{rescale_planet_on_event, spawn_planet_on_event};
+use bevy::prelude::*;
+
+/// Every wind glyph merged into one mesh, a child of the planet
+#[derive(Component)]
+pub struct WindGlyphMesh;
+
+/// The "Glyphs" way of showing the wind tab: a grid of arrows pointing downwind with a barb
+/// per `logic::BARB_SPEED` of wind, far cheaper than the particles and how weather maps show it
+pub struct WindGlyphsPlugin;
+
+impl Plugin for WindGlyphsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                systems::rebuild_wind_glyph_mesh
+                    .after(spawn_planet_on_event)
+                    .after(ClimateRebuildSet::Wind)
+                    .after(SettingsDiffSet)
+                    .after(rescale_planet_on_event),
+                systems::show_wind_glyphs_on_wind_tab,
+            )
+                .chain()
+                .run_if(in_state(GameState::PlanetGeneration)),
+        );
+    }
+}
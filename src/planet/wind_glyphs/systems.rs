@@ -0,0 +1,100 @@
+use super::WindGlyphMesh;
+use super::logic::{glyph_length, glyph_vertices};
+use crate::planet::components::PlanetEntity;
+use crate::planet::events::{PlanetSpawnedEvent, SettingsDiff, SettingsGroup};
+use crate::planet::resources::{CurrentPlanetData, DisplaySettings, PlanetAssetStores, WindSettings};
+use crate::planet::wind::systems::WindCubeMap;
+use crate::planet::wind::{WindDisplay, WindParticleSettings};
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::PrimitiveTopology;
+use bevy::prelude::*;
+use planetgen::prelude::sample_wind_glyphs;
+
+const GLYPH_COLOR: Color = Color::srgb(0.95, 0.95, 0.85);
+
+/// Rebuild the glyphs when the wind cubemap is rebuilt, a new planet comes or the grid spacing,
+/// the display mode, the particle height or the planet size change. Only while the glyphs are
+/// the chosen display, the particles don't need them.
+///
+/// The glyphs fly at the height of the particles and are depth tested like them, the planet
+/// hides the ones on its far side.
+pub fn rebuild_wind_glyph_mesh(
+    mut settings_diffs: MessageReader<SettingsDiff>,
+    mut planet_spawned_events: MessageReader<PlanetSpawnedEvent>,
+    wind_cubemap: Option<Res<WindCubeMap>>,
+    (settings, display_settings, wind_settings): (Res<WindSettings>, Res<DisplaySettings>, Res<WindParticleSettings>),
+    current_planet_data: Res<CurrentPlanetData>,
+    (planet_query, existing_glyphs): (
+        Query<Entity, With<PlanetEntity>>,
+        Query<Entity, With<WindGlyphMesh>>,
+    ),
+    (mut meshes, mut materials, mut planet_assets): PlanetAssetStores,
+    mut commands: Commands,
+) {
+    let settings_changed =
+        SettingsDiff::read_any(&mut settings_diffs, &[SettingsGroup::Terrain, SettingsGroup::Wind]);
+    let planet_spawned = planet_spawned_events.read().count() > 0;
+    let wind_changed = wind_cubemap.as_ref().is_some_and(|wind| wind.is_changed());
+    if !settings_changed && !planet_spawned && !wind_changed {
+        return;
+    }
+
+    for entity in existing_glyphs.iter() {
+        commands.entity(entity).despawn();
+    }
+    if settings.wind_display != WindDisplay::Glyphs {
+        return;
+    }
+    let (Some(wind_cubemap), Some(planet_data)) = (wind_cubemap, current_planet_data.planet_data.as_ref()) else {
+        return;
+    };
+    let Some(planet_entity) = planet_query.iter().next() else {
+        return;
+    };
+
+    let glyphs = sample_wind_glyphs(&wind_cubemap.inner, settings.wind_glyph_spacing);
+    let length = glyph_length(planet_data.radius, settings.wind_glyph_spacing);
+    let (positions, normals) =
+        glyph_vertices(&glyphs, length, |direction| wind_settings.target_radius(Some(planet_data), direction));
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+
+    let material = planet_assets.add_material(&mut materials, StandardMaterial {
+        base_color: GLYPH_COLOR,
+        emissive: GLYPH_COLOR.to_linear() * 0.5,
+        unlit: true,
+        // Flat glyphs, seen from below where they dip behind a ridge
+        cull_mode: None,
+        ..default()
+    });
+    let glyph_mesh = commands
+        .spawn((
+            Mesh3d(planet_assets.add_mesh(&mut meshes, mesh)),
+            MeshMaterial3d(material),
+            Transform::default(),
+            if display_settings.show_wind {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
+            },
+            WindGlyphMesh,
+        ))
+        .id();
+    commands.entity(planet_entity).add_child(glyph_mesh);
+}
+
+/// The glyphs are kept when leaving the wind tab, only hidden
+pub fn show_wind_glyphs_on_wind_tab(
+    settings: Res<DisplaySettings>,
+    mut glyphs: Query<&mut Visibility, With<WindGlyphMesh>>,
+) {
+    for mut visibility in glyphs.iter_mut() {
+        visibility.set_if_neq(if settings.show_wind {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
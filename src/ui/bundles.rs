@@ -1,4 +1,5 @@
 use crate::ui::components::*;
+use crate::ui::theme::UiTheme;
 use bevy::color::Color;
 use bevy::prelude::*;
 
@@ -127,13 +128,7 @@ pub struct ToggleBundle {
 }
 
 impl ToggleBundle {
-    pub fn new(width: f32, height: f32, initial_state: bool, border_radius: f32) -> Self {
-        let background_color = if initial_state {
-            Color::srgb(0.3, 0.7, 0.3)
-        } else {
-            Color::srgb(0.6, 0.6, 0.6)
-        };
-
+    pub fn new(width: f32, height: f32, initial_state: bool, border_radius: f32, theme: &UiTheme) -> Self {
         Self {
             button: Button,
             node: Node {
@@ -144,7 +139,7 @@ impl ToggleBundle {
                 border_radius: BorderRadius::all(Val::Px(border_radius)),
                 ..default()
             },
-            background: BackgroundColor(background_color),
+            background: theme.toggle_background(initial_state),
             interaction: Interaction::None,
             ui_toggle: UIToggle,
             toggle_state: ToggleState {
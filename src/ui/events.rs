@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+/// New value of a slider or a toggle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WidgetValue {
+    Slider(f32),
+    Toggle(bool),
+}
+
+/// Set a slider or toggle from code, e.g. for presets, undo or loaded settings. The handle,
+/// value text and toggle color follow as if the user had changed it.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct SetWidgetValue {
+    /// The entity with the `Slider` or `ToggleState`
+    pub widget: Entity,
+    pub value: WidgetValue,
+    /// Don't announce the change with `WidgetValueChanged`. For values that come from the
+    /// state the widget edits, writing them back would loop.
+    pub silent: bool,
+}
+
+/// A slider or toggle got a new value, from the user or from a `SetWidgetValue` that isn't silent
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct WidgetValueChanged {
+    pub widget: Entity,
+    pub value: WidgetValue,
+}
@@ -0,0 +1,130 @@
+use bevy::math::Vec2;
+use bevy_egui::egui;
+
+/// How far the stick has to be pushed before it moves the focus
+pub const STICK_THRESHOLD: f32 = 0.5;
+
+/// A direction held this long starts repeating
+pub const REPEAT_DELAY_SECS: f32 = 0.4;
+
+/// Time between the repeats of a held direction
+pub const REPEAT_INTERVAL_SECS: f32 = 0.1;
+
+/// Where the left stick or the d-pad is pushed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Direction of a stick, the axis pushed further wins. None while it's inside the threshold.
+pub fn stick_direction(stick: Vec2) -> Option<NavDirection> {
+    if stick.max_element() < STICK_THRESHOLD && stick.min_element() > -STICK_THRESHOLD {
+        return None;
+    }
+    Some(if stick.x.abs() > stick.y.abs() {
+        if stick.x > 0.0 { NavDirection::Right } else { NavDirection::Left }
+    } else if stick.y > 0.0 {
+        NavDirection::Up
+    } else {
+        NavDirection::Down
+    })
+}
+
+/// Turns a held direction into presses like a held key: one when it's pushed, more after
+/// `REPEAT_DELAY_SECS` every `REPEAT_INTERVAL_SECS` while it stays there
+#[derive(Debug, Default)]
+pub struct NavRepeat {
+    held: Option<NavDirection>,
+    until_repeat: f32,
+}
+
+impl NavRepeat {
+    /// The press `direction`, held for another `delta_secs`, makes, if any
+    pub fn update(&mut self, direction: Option<NavDirection>, delta_secs: f32) -> Option<NavDirection> {
+        if direction != self.held {
+            self.held = direction;
+            self.until_repeat = REPEAT_DELAY_SECS;
+            return direction;
+        }
+        let held = direction?;
+        self.until_repeat -= delta_secs;
+        if self.until_repeat > 0.0 {
+            return None;
+        }
+        self.until_repeat += REPEAT_INTERVAL_SECS;
+        Some(held)
+    }
+}
+
+/// The key egui gets for a direction. Up and down go through the widgets in panel order like
+/// Shift+Tab and Tab, left and right are arrows that adjust a focused slider.
+pub fn navigation_key(direction: NavDirection) -> (egui::Key, egui::Modifiers) {
+    match direction {
+        NavDirection::Up => (egui::Key::Tab, egui::Modifiers::SHIFT),
+        NavDirection::Down => (egui::Key::Tab, egui::Modifiers::NONE),
+        NavDirection::Left => (egui::Key::ArrowLeft, egui::Modifiers::NONE),
+        NavDirection::Right => (egui::Key::ArrowRight, egui::Modifiers::NONE),
+    }
+}
+
+/// Press and release of `key`, what egui gets from a keyboard
+pub fn key_tap(key: egui::Key, modifiers: egui::Modifiers) -> [egui::Event; 2] {
+    [true, false].map(|pressed| egui::Event::Key {
+        key,
+        physical_key: None,
+        pressed,
+        repeat: false,
+        modifiers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::resting(Vec2::ZERO, None)]
+    #[case::barely_pushed(Vec2::new(0.3, -0.4), None)]
+    #[case::up(Vec2::new(0.2, 0.9), Some(NavDirection::Up))]
+    #[case::down(Vec2::new(0.0, -0.6), Some(NavDirection::Down))]
+    #[case::diagonal_leaning_right(Vec2::new(0.8, 0.7), Some(NavDirection::Right))]
+    #[case::left(Vec2::new(-1.0, 0.0), Some(NavDirection::Left))]
+    fn test_stick_direction(#[case] stick: Vec2, #[case] expected: Option<NavDirection>) {
+        assert_eq!(stick_direction(stick), expected);
+    }
+
+    #[test]
+    fn test_held_direction_repeats_after_the_delay() {
+        let mut repeat = NavRepeat::default();
+        let down = Some(NavDirection::Down);
+
+        assert_eq!(repeat.update(down, 0.0), down);
+        assert_eq!(repeat.update(down, REPEAT_DELAY_SECS - 0.05), None);
+        assert_eq!(repeat.update(down, 0.1), down);
+        assert_eq!(repeat.update(down, REPEAT_INTERVAL_SECS - 0.07), None);
+        assert_eq!(repeat.update(down, 0.05), down);
+    }
+
+    #[test]
+    fn test_new_direction_presses_right_away() {
+        let mut repeat = NavRepeat::default();
+
+        assert_eq!(repeat.update(Some(NavDirection::Down), 0.1), Some(NavDirection::Down));
+        assert_eq!(repeat.update(Some(NavDirection::Down), 0.1), None);
+        assert_eq!(repeat.update(Some(NavDirection::Right), 0.1), Some(NavDirection::Right));
+        assert_eq!(repeat.update(None, 0.1), None);
+        assert_eq!(repeat.update(Some(NavDirection::Right), 0.1), Some(NavDirection::Right));
+    }
+
+    #[test]
+    fn test_tab_up_goes_back() {
+        assert_eq!(navigation_key(NavDirection::Up), (egui::Key::Tab, egui::Modifiers::SHIFT));
+        let [press, release] = key_tap(egui::Key::Enter, egui::Modifiers::NONE);
+        assert!(matches!(press, egui::Event::Key { key: egui::Key::Enter, pressed: true, .. }));
+        assert!(matches!(release, egui::Event::Key { pressed: false, .. }));
+    }
+}
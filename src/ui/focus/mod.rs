@@ -0,0 +1,38 @@
+//! Keyboard and gamepad focus of the egui panels
+//!
+//! egui keeps the focused widget and moves it with Tab and Shift+Tab in the order the widgets
+//! are laid out, arrows adjust a focused slider and Enter or Space activates it. The gamepad
+//! left stick, the d-pad and A are turned into those same keys, so both walk the same
+//! navigation order. A ring around the focused widget shows where the focus is while it's
+//! being moved that way, a mouse click hides it again.
+
+pub mod logic;
+mod systems;
+
+use crate::planet::ui::systems::render_planet_generation_ui;
+use bevy::prelude::*;
+use bevy_egui::{EguiInputSet, EguiPrimaryContextPass};
+
+/// Width of the ring around the focused widget
+pub const FOCUS_RING_WIDTH: f32 = 2.0;
+
+/// Whether the focus was last moved with the keyboard or a gamepad, the ring is only drawn then
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiFocus {
+    pub ring_shown: bool,
+}
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiFocus>()
+            .add_systems(
+                PreUpdate,
+                // Read along with the keyboard, egui gets the gamepad keys with this frame's input
+                (systems::track_focus_input, systems::gamepad_ui_navigation).in_set(EguiInputSet::ReadBevyMessages),
+            )
+            // Scroll requests only reach the scroll areas drawn after them in the same pass
+            .add_systems(EguiPrimaryContextPass, systems::draw_focus_ring.before(render_planet_generation_ui));
+    }
+}
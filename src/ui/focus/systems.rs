@@ -0,0 +1,87 @@
+use super::logic::{self, NavRepeat};
+use super::{FOCUS_RING_WIDTH, UiFocus};
+use bevy::prelude::*;
+use bevy_egui::input::EguiInputEvent;
+use bevy_egui::{EguiContexts, PrimaryEguiContext, egui};
+
+/// Keys that move the focus show the ring, any mouse click hides it
+pub fn track_focus_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut focus: ResMut<UiFocus>,
+) {
+    if mouse_input.get_just_pressed().next().is_some() {
+        focus.set_if_neq(UiFocus { ring_shown: false });
+    } else if keyboard_input.any_just_pressed([
+        KeyCode::Tab,
+        KeyCode::ArrowUp,
+        KeyCode::ArrowDown,
+        KeyCode::ArrowLeft,
+        KeyCode::ArrowRight,
+    ]) {
+        focus.set_if_neq(UiFocus { ring_shown: true });
+    }
+}
+
+/// Left stick and d-pad of every gamepad move the focus, A activates the focused widget. They
+/// reach egui as the keys a keyboard would press.
+pub fn gamepad_ui_navigation(
+    gamepads: Query<&Gamepad>,
+    contexts: Query<Entity, With<PrimaryEguiContext>>,
+    // Real time, the menu works the same with a paused clock
+    real_time: Res<Time<Real>>,
+    mut focus: ResMut<UiFocus>,
+    mut repeat: Local<NavRepeat>,
+    mut egui_input: MessageWriter<EguiInputEvent>,
+) {
+    let Ok(context) = contexts.single() else {
+        return;
+    };
+    let mut stick = Vec2::ZERO;
+    let mut activate = false;
+    for gamepad in &gamepads {
+        stick += gamepad.left_stick() + gamepad.dpad();
+        activate |= gamepad.just_pressed(GamepadButton::South);
+    }
+
+    let mut taps = Vec::new();
+    if let Some(direction) = repeat.update(logic::stick_direction(stick), real_time.delta_secs()) {
+        let (key, modifiers) = logic::navigation_key(direction);
+        taps.push(logic::key_tap(key, modifiers));
+    }
+    if activate {
+        taps.push(logic::key_tap(egui::Key::Enter, egui::Modifiers::NONE));
+    }
+    if taps.is_empty() {
+        return;
+    }
+    focus.set_if_neq(UiFocus { ring_shown: true });
+    egui_input.write_batch(taps.into_iter().flatten().map(|event| EguiInputEvent { context, event }));
+}
+
+/// Ring around the focused widget, on top of the panels. A widget the focus moved to is
+/// scrolled into view, egui leaves that to the app.
+pub fn draw_focus_ring(mut contexts: EguiContexts, focus: Res<UiFocus>, mut scrolled_to: Local<Option<egui::Id>>) {
+    if !focus.ring_shown {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+    // Where the widget was drawn last pass, the focus moves while the widgets are drawn
+    let Some(response) = ctx.memory(|memory| memory.focused()).and_then(|id| ctx.read_response(id)) else {
+        return;
+    };
+    if *scrolled_to != Some(response.id) {
+        *scrolled_to = Some(response.id);
+        response.scroll_to_me(None);
+    }
+    // Only the part the scroll area shows, none of it when the widget is scrolled away
+    let rect = response.interact_rect;
+    if !rect.is_positive() {
+        return;
+    }
+    let stroke = egui::Stroke::new(FOCUS_RING_WIDTH, ctx.style().visuals.selection.stroke.color);
+    ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("focus_ring")))
+        .rect_stroke(rect.expand(FOCUS_RING_WIDTH), 3.0, stroke, egui::StrokeKind::Outside);
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+/// Text of every key of one language
+pub type Table = HashMap<String, String>;
+
+/// A locale file, a RON map from keys to their text
+pub fn parse_table(source: &str) -> Result<Table, ron::error::SpannedError> {
+    ron::from_str(source)
+}
+
+/// Keys of `reference` that `table` has no text for, sorted
+pub fn missing_keys<'a>(table: &Table, reference: &'a Table) -> Vec<&'a str> {
+    let mut missing: Vec<&str> = reference
+        .keys()
+        .filter(|key| !table.contains_key(*key))
+        .map(String::as_str)
+        .collect();
+    missing.sort_unstable();
+    missing
+}
+
+/// `template` with every `{name}` replaced by the value given for `name`. Placeholders
+/// without a value stay as they are.
+pub fn fill(template: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    args.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), &value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::locale::Language;
+
+    fn table(language: Language) -> Table {
+        parse_table(language.source()).unwrap_or_else(|error| panic!("{language:?} table: {error}"))
+    }
+
+    /// Names of the `{name}` placeholders in `text`, in order
+    fn placeholders(text: &str) -> Vec<&str> {
+        text.split('{').skip(1).filter_map(|part| part.split_once('}').map(|(name, _)| name)).collect()
+    }
+
+    #[test]
+    fn test_every_language_has_every_english_key() {
+        let english = table(Language::English);
+        for language in Language::ALL {
+            let translated = table(language);
+            assert_eq!(missing_keys(&translated, &english), Vec::<&str>::new(), "{language:?} lacks them");
+            assert_eq!(missing_keys(&english, &translated), Vec::<&str>::new(), "{language:?} has unused keys");
+        }
+    }
+
+    #[test]
+    fn test_translations_keep_the_placeholders() {
+        let english = table(Language::English);
+        for language in Language::ALL {
+            for (key, text) in &table(language) {
+                let mut expected = placeholders(&english[key]);
+                let mut found = placeholders(text);
+                expected.sort_unstable();
+                found.sort_unstable();
+                assert_eq!(found, expected, "{language:?} {key}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill_replaces_every_placeholder_of_a_name() {
+        let text = fill("Grid {size}×{size}, {missing}", &[("size", &64)]);
+        assert_eq!(text, "Grid 64×64, {missing}");
+    }
+
+    #[test]
+    fn test_missing_keys_are_sorted() {
+        let reference = Table::from([
+            ("b".to_string(), String::new()),
+            ("a".to_string(), String::new()),
+            ("c".to_string(), String::new()),
+        ]);
+        let table = Table::from([("c".to_string(), String::new())]);
+        assert_eq!(missing_keys(&table, &reference), vec!["a", "b"]);
+    }
+}
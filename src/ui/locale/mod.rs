@@ -0,0 +1,106 @@
+//! Translated UI text
+//!
+//! Every text the UI shows is looked up by its [`StringId`] in the [`Strings`] of the picked
+//! [`Language`]. The tables are RON maps in assets/locales, built into the binary like the
+//! window icon. A key a language has no text for falls back to English, the keys it lacks are
+//! logged when its table loads.
+
+pub mod logic;
+mod systems;
+
+pub use systems::relocalize_text;
+
+use bevy::prelude::*;
+use logic::Table;
+use serde::{Deserialize, Serialize};
+
+/// Key of a text in the locale tables, like `"section.ocean"`
+pub type StringId = &'static str;
+
+/// Languages of the UI, each with a table in assets/locales
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// Name of the language in that language, so it can be found whatever the UI shows
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    pub(crate) fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../../assets/locales/en.ron"),
+            Language::German => include_str!("../../../assets/locales/de.ron"),
+        }
+    }
+}
+
+/// Text of the UI in one language, English for the keys it lacks
+#[derive(Resource, Debug, Clone)]
+pub struct Strings {
+    language: Language,
+    table: Table,
+    english: Table,
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Self::load(Language::default())
+    }
+}
+
+impl Strings {
+    /// A table that doesn't parse is logged and left empty, its language then shows English
+    pub fn load(language: Language) -> Self {
+        let load_table = |language: Language| {
+            logic::parse_table(language.source()).unwrap_or_else(|error| {
+                error!("Couldn't read the {language:?} texts: {error}");
+                Table::new()
+            })
+        };
+        let english = load_table(Language::English);
+        let table = match language {
+            Language::English => english.clone(),
+            _ => load_table(language),
+        };
+        for key in logic::missing_keys(&table, &english) {
+            warn!("No {language:?} text for {key}, showing it in English");
+        }
+        Self {
+            language,
+            table,
+            english,
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Text of `id`, the English one when the language lacks it and the key itself when
+    /// English does too
+    pub fn get<'a>(&'a self, id: &'a str) -> &'a str {
+        self.table
+            .get(id)
+            .or_else(|| self.english.get(id))
+            .map_or(id, String::as_str)
+    }
+
+    /// Text of `id` with its `{name}` placeholders filled in from `args`
+    pub fn format(&self, id: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+        logic::fill(self.get(id), args)
+    }
+}
+
+/// A spawned text showing the text of a key, rewritten when the language changes
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalizedText(pub StringId);
@@ -0,0 +1,14 @@
+use super::{LocalizedText, Strings};
+use bevy::prelude::*;
+
+/// Give new localized texts their text, and all of them the new one when the language changes
+pub fn relocalize_text(strings: Res<Strings>, mut texts: Query<(Ref<LocalizedText>, &mut Text)>) {
+    for (localized, mut text) in &mut texts {
+        if strings.is_changed() || localized.is_changed() {
+            let translated = strings.get(localized.0);
+            if text.0 != translated {
+                text.0 = translated.to_string();
+            }
+        }
+    }
+}
@@ -1,26 +1,49 @@
 mod bundles;
 pub mod components;
+pub mod events;
+pub mod focus;
+pub mod locale;
 mod systems;
+pub mod theme;
+pub mod toasts;
+pub mod tooltips;
 pub mod widgets;
 
 use bevy::prelude::*;
+use events::{SetWidgetValue, WidgetValueChanged};
+use locale::Strings;
+use theme::UiTheme;
 
 pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                systems::handle_button_interactions,
-                systems::handle_slider_interactions,
-                systems::handle_toggle_interactions,
-                systems::handle_value_adjuster_interactions,
-                systems::update_toggle_text,
-                systems::update_value_displays,
-                systems::update_slider_handles,
-                systems::update_slider_value_displays,
-            ),
-        );
+        app.init_resource::<UiTheme>()
+            .init_resource::<Strings>()
+            .add_message::<SetWidgetValue>()
+            .add_message::<WidgetValueChanged>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        systems::handle_button_interactions,
+                        systems::handle_slider_interactions,
+                        systems::handle_toggle_interactions,
+                        systems::handle_value_adjuster_interactions,
+                        systems::apply_widget_values,
+                    ),
+                    // Every visual part follows the value in the same frame, however it was set
+                    (
+                        systems::update_toggle_text,
+                        systems::update_toggle_colors,
+                        systems::update_value_displays,
+                        systems::update_slider_handles,
+                        systems::update_slider_value_displays,
+                        theme::restyle_themed,
+                        locale::relocalize_text,
+                    ),
+                )
+                    .chain(),
+            );
     }
 }
@@ -1,5 +1,7 @@
 use crate::ui::components::*;
-use bevy::color::Color;
+use crate::ui::events::{SetWidgetValue, WidgetValue, WidgetValueChanged};
+use crate::ui::locale::Strings;
+use crate::ui::theme::UiTheme;
 use bevy::prelude::*;
 use bevy::ui::RelativeCursorPosition;
 
@@ -24,24 +26,91 @@ pub fn handle_button_interactions(
     }
 }
 
+/// Width and height of a slider handle
+const HANDLE_SIZE: f32 = 18.0;
+
 pub fn handle_toggle_interactions(
     mut toggle_query: Query<
-        (&Interaction, &mut ToggleState, &mut BackgroundColor),
+        (Entity, &Interaction, &mut ToggleState),
         (Changed<Interaction>, With<UIToggle>),
     >,
+    mut changed: MessageWriter<WidgetValueChanged>,
 ) {
-    for (interaction, mut toggle_state, mut bg_color) in toggle_query.iter_mut() {
+    for (entity, interaction, mut toggle_state) in toggle_query.iter_mut() {
         if *interaction == Interaction::Pressed {
             toggle_state.is_on = !toggle_state.is_on;
-            *bg_color = if toggle_state.is_on {
-                BackgroundColor(Color::srgb(0.3, 0.7, 0.3)) // Green when on
-            } else {
-                BackgroundColor(Color::srgb(0.6, 0.6, 0.6)) // Gray when off
-            };
+            changed.write(WidgetValueChanged {
+                widget: entity,
+                value: WidgetValue::Toggle(toggle_state.is_on),
+            });
         }
     }
 }
 
+/// Put the values set from code into the widgets, the systems updating the visuals take it from
+/// there
+pub fn apply_widget_values(
+    mut events: MessageReader<SetWidgetValue>,
+    mut sliders: Query<&mut Slider>,
+    mut toggles: Query<&mut ToggleState, With<UIToggle>>,
+    mut changed: MessageWriter<WidgetValueChanged>,
+) {
+    for event in events.read() {
+        let value = match event.value {
+            WidgetValue::Slider(value) => {
+                let Ok(mut slider) = sliders.get_mut(event.widget) else {
+                    warn!("SetWidgetValue for {} which isn't a slider", event.widget);
+                    continue;
+                };
+                let value = snap_slider_value(&slider, value);
+                if slider.current_value == value {
+                    continue;
+                }
+                slider.current_value = value;
+                WidgetValue::Slider(value)
+            }
+            WidgetValue::Toggle(is_on) => {
+                let Ok(mut toggle_state) = toggles.get_mut(event.widget) else {
+                    warn!("SetWidgetValue for {} which isn't a toggle", event.widget);
+                    continue;
+                };
+                if toggle_state.is_on == is_on {
+                    continue;
+                }
+                toggle_state.is_on = is_on;
+                WidgetValue::Toggle(is_on)
+            }
+        };
+        if !event.silent {
+            changed.write(WidgetValueChanged {
+                widget: event.widget,
+                value,
+            });
+        }
+    }
+}
+
+/// Keep a value inside the slider's range, rounded for integer sliders
+fn snap_slider_value(slider: &Slider, value: f32) -> f32 {
+    let value = if slider.is_integer { value.round() } else { value };
+    value.clamp(slider.min_value, slider.max_value)
+}
+
+/// Left edge of the handle on a track `track_width` wide, in pixels
+fn slider_handle_left(slider: &Slider, track_width: f32) -> f32 {
+    let value_ratio = (slider.current_value - slider.min_value) / (slider.max_value - slider.min_value);
+    value_ratio * (track_width - HANDLE_SIZE)
+}
+
+pub fn update_toggle_colors(
+    theme: Res<UiTheme>,
+    mut toggle_query: Query<(&ToggleState, &mut BackgroundColor), (Changed<ToggleState>, With<UIToggle>)>,
+) {
+    for (toggle_state, mut background) in toggle_query.iter_mut() {
+        *background = theme.toggle_background(toggle_state.is_on);
+    }
+}
+
 pub fn handle_value_adjuster_interactions(
     increment_query: Query<
         (&Interaction, &AdjusterTarget),
@@ -74,18 +143,20 @@ pub fn handle_value_adjuster_interactions(
     }
 }
 
+/// ON or OFF in the language of the UI, rewritten when the toggle or the language changes
 pub fn update_toggle_text(
+    strings: Res<Strings>,
     mut text_query: Query<&mut Text>,
-    toggle_query: Query<(&ToggleState, &Children), (Changed<ToggleState>, With<UIToggle>)>,
+    toggle_query: Query<(Ref<ToggleState>, &Children), With<UIToggle>>,
 ) {
     for (toggle_state, children) in &toggle_query {
+        if !toggle_state.is_changed() && !strings.is_changed() {
+            continue;
+        }
+        let label = strings.get(if toggle_state.is_on { "widget.on" } else { "widget.off" });
         for child in children.iter() {
             if let Ok(mut text) = text_query.get_mut(child) {
-                text.0 = if toggle_state.is_on {
-                    "ON".to_string()
-                } else {
-                    "OFF".to_string()
-                };
+                text.0 = label.to_string();
             }
         }
     }
@@ -124,6 +195,7 @@ pub fn handle_slider_interactions(
         (With<SliderTrack>, Without<SliderHandle>),
     >,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    mut changed: MessageWriter<WidgetValueChanged>,
     mut drag_state: Local<Option<(Entity, f32)>>, // Store slider entity + initial click offset
 ) {
     // Stop dragging immediately on mouse release
@@ -142,9 +214,8 @@ pub fn handle_slider_interactions(
             {
                 if let (Val::Px(track_width), Val::Px(handle_left)) = (track_node.width, handle_node.left) {
                     if let Some(normalized_pos) = rel_cursor.normalized {
-                        let handle_size = 18.0;
                         let cursor_x = normalized_pos.x * track_width;
-                        let handle_center_x = handle_left + handle_size * 0.5;
+                        let handle_center_x = handle_left + HANDLE_SIZE * 0.5;
 
                         // Store the offset between cursor and handle center
                         let offset = cursor_x - handle_center_x;
@@ -165,13 +236,12 @@ pub fn handle_slider_interactions(
             {
                 if let Val::Px(track_width) = track_node.width {
                     if let Some(normalized_pos) = rel_cursor.normalized {
-                        let handle_size = 18.0;
-                        let usable_track_width = track_width - handle_size;
+                        let usable_track_width = track_width - HANDLE_SIZE;
 
                         // Get cursor position and subtract the initial offset
                         let cursor_x = normalized_pos.x * track_width;
                         let handle_center_x = cursor_x - initial_offset;
-                        let handle_left = (handle_center_x - handle_size * 0.5).clamp(0.0, usable_track_width);
+                        let handle_left = (handle_center_x - HANDLE_SIZE * 0.5).clamp(0.0, usable_track_width);
 
                         // Convert position to slider value
                         let position_ratio = if usable_track_width > 0.0 {
@@ -183,11 +253,14 @@ pub fn handle_slider_interactions(
                         let value_range = slider.max_value - slider.min_value;
                         let new_value = slider.min_value + position_ratio * value_range;
 
-                        slider.current_value = if slider.is_integer {
-                            new_value.round().clamp(slider.min_value, slider.max_value)
-                        } else {
-                            new_value.clamp(slider.min_value, slider.max_value)
-                        };
+                        let new_value = snap_slider_value(&slider, new_value);
+                        if slider.current_value != new_value {
+                            slider.current_value = new_value;
+                            changed.write(WidgetValueChanged {
+                                widget: slider_entity,
+                                value: WidgetValue::Slider(new_value),
+                            });
+                        }
                     }
                 }
             }
@@ -198,25 +271,19 @@ pub fn handle_slider_interactions(
 pub fn update_slider_handles(
     slider_query: Query<(Entity, &Slider), Changed<Slider>>,
     mut handle_query: Query<(&SliderTarget, &mut Node), With<SliderHandle>>,
-    track_query: Query<&Node, (With<SliderTrack>, Without<SliderHandle>)>,
+    track_query: Query<(&Node, &SliderTarget), (With<SliderTrack>, Without<SliderHandle>)>,
 ) {
     for (slider_entity, slider) in slider_query.iter() {
         // Find the track width for this slider
         let track_width = track_query
             .iter()
-            .find_map(|track_node| {
-                if let Val::Px(width) = track_node.width {
-                    Some(width)
-                } else {
-                    None
-                }
+            .find_map(|(track_node, target)| match track_node.width {
+                Val::Px(width) if target.0 == slider_entity => Some(width),
+                _ => None,
             })
             .unwrap_or(200.0);
 
-        let handle_size = 18.0; // Fixed: match the actual handle size from widget
-        let value_ratio =
-            (slider.current_value - slider.min_value) / (slider.max_value - slider.min_value);
-        let handle_position = value_ratio * (track_width - handle_size);
+        let handle_position = slider_handle_left(slider, track_width);
 
         // Update handle position
         for (target, mut handle_node) in handle_query.iter_mut() {
@@ -248,3 +315,196 @@ pub fn update_slider_value_displays(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::UIPlugin;
+    use crate::ui::locale::{Language, LocalizedText};
+    use crate::ui::theme::ThemePreset;
+    use crate::ui::widgets::{spawn_slider_with_marker, spawn_toggle_with_marker};
+    use bevy::input::InputPlugin;
+
+    #[derive(Component)]
+    struct TestWidget;
+
+    #[derive(Resource)]
+    struct Widgets {
+        slider: Entity,
+        toggle: Entity,
+    }
+
+    fn headless_ui() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, UIPlugin));
+        app.add_systems(Startup, |mut commands: Commands, theme: Res<UiTheme>, strings: Res<Strings>| {
+            let mut slider = Entity::PLACEHOLDER;
+            let mut toggle = Entity::PLACEHOLDER;
+            commands.spawn(Node::default()).with_children(|parent| {
+                slider = spawn_slider_with_marker(
+                    parent,
+                    "setting.planet_radius.label",
+                    20.0,
+                    10.0,
+                    50.0,
+                    false,
+                    240.0,
+                    (&theme, &strings),
+                    TestWidget,
+                );
+                toggle = spawn_toggle_with_marker(parent, "section.ocean", false, (&theme, &strings), TestWidget);
+            });
+            commands.insert_resource(Widgets { slider, toggle });
+        });
+        app.update();
+        app
+    }
+
+    fn set(app: &mut App, widget: Entity, value: WidgetValue, silent: bool) {
+        app.world_mut().write_message(SetWidgetValue { widget, value, silent });
+        app.update();
+    }
+
+    /// How far along its usable track the slider's handle is, from 0 to 1
+    fn handle_position(app: &mut App, slider: Entity) -> f32 {
+        let world = app.world_mut();
+        let mut tracks = world.query_filtered::<(&Node, &SliderTarget), With<SliderTrack>>();
+        let track_width = tracks
+            .iter(world)
+            .find_map(|(node, target)| match node.width {
+                Val::Px(width) if target.0 == slider => Some(width),
+                _ => None,
+            })
+            .expect("a track");
+        let mut handles = world.query_filtered::<(&Node, &SliderTarget), With<SliderHandle>>();
+        let (handle, _) = handles
+            .iter(world)
+            .find(|(_, target)| target.0 == slider)
+            .expect("a handle");
+        let Val::Px(left) = handle.left else {
+            panic!("handle placed in {:?}", handle.left);
+        };
+        left / (track_width - HANDLE_SIZE)
+    }
+
+    fn value_text(app: &mut App, slider: Entity) -> String {
+        let world = app.world_mut();
+        let mut displays = world.query_filtered::<(&Text, &SliderTarget), With<SliderValueDisplay>>();
+        let (text, _) = displays
+            .iter(world)
+            .find(|(_, target)| target.0 == slider)
+            .expect("a value display");
+        text.0.clone()
+    }
+
+    fn changes(app: &App) -> Vec<WidgetValueChanged> {
+        let messages = app.world().resource::<Messages<WidgetValueChanged>>();
+        messages.iter_current_update_messages().copied().collect()
+    }
+
+    #[test]
+    fn test_set_slider_value_moves_the_handle_and_text() {
+        let mut app = headless_ui();
+        let slider = app.world().resource::<Widgets>().slider;
+        assert!((handle_position(&mut app, slider) - 0.25).abs() < 1e-5);
+
+        set(&mut app, slider, WidgetValue::Slider(40.0), false);
+
+        assert_eq!(app.world().get::<Slider>(slider).unwrap().current_value, 40.0);
+        assert!((handle_position(&mut app, slider) - 0.75).abs() < 1e-5);
+        assert_eq!(value_text(&mut app, slider), "40.0");
+        assert_eq!(
+            changes(&app),
+            vec![WidgetValueChanged {
+                widget: slider,
+                value: WidgetValue::Slider(40.0)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_set_slider_value_is_clamped_to_the_range() {
+        let mut app = headless_ui();
+        let slider = app.world().resource::<Widgets>().slider;
+
+        set(&mut app, slider, WidgetValue::Slider(80.0), true);
+
+        assert_eq!(app.world().get::<Slider>(slider).unwrap().current_value, 50.0);
+        assert!((handle_position(&mut app, slider) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_silent_set_announces_nothing() {
+        let mut app = headless_ui();
+        let &Widgets { slider, toggle } = app.world().resource::<Widgets>();
+
+        set(&mut app, slider, WidgetValue::Slider(30.0), true);
+        assert!((handle_position(&mut app, slider) - 0.5).abs() < 1e-5);
+        assert!(changes(&app).is_empty());
+
+        set(&mut app, toggle, WidgetValue::Toggle(true), true);
+        assert!(app.world().get::<ToggleState>(toggle).unwrap().is_on);
+        assert!(changes(&app).is_empty());
+    }
+
+    #[test]
+    fn test_set_toggle_updates_its_text_and_color() {
+        let mut app = headless_ui();
+        let toggle = app.world().resource::<Widgets>().toggle;
+
+        set(&mut app, toggle, WidgetValue::Toggle(true), false);
+
+        let world = app.world();
+        let on = world.resource::<UiTheme>().toggle_background(true);
+        assert_eq!(world.get::<BackgroundColor>(toggle), Some(&on));
+        let label = world.get::<Children>(toggle).unwrap()[0];
+        assert_eq!(world.get::<Text>(label).unwrap().0, "ON");
+        assert_eq!(
+            changes(&app),
+            vec![WidgetValueChanged {
+                widget: toggle,
+                value: WidgetValue::Toggle(true)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_a_new_theme_restyles_the_spawned_widgets() {
+        let mut app = headless_ui();
+        let &Widgets { slider, toggle } = app.world().resource::<Widgets>();
+        set(&mut app, toggle, WidgetValue::Toggle(true), true);
+
+        *app.world_mut().resource_mut::<UiTheme>() = UiTheme::preset(ThemePreset::Light);
+        app.update();
+
+        let light = UiTheme::preset(ThemePreset::Light);
+        let world = app.world_mut();
+        assert_eq!(world.get::<BackgroundColor>(toggle), Some(&light.toggle_background(true)));
+        let label = world.get::<Children>(toggle).unwrap()[0];
+        assert_eq!(world.get::<TextColor>(label).unwrap().0, light.text_primary);
+        let mut handles = world.query_filtered::<(&BackgroundColor, &SliderTarget), With<SliderHandle>>();
+        let (handle, _) = handles.iter(world).find(|(_, target)| target.0 == slider).expect("a handle");
+        assert_eq!(handle.0, light.slider_handle);
+    }
+
+    #[test]
+    fn test_a_new_language_rewrites_the_spawned_text() {
+        let mut app = headless_ui();
+        let toggle = app.world().resource::<Widgets>().toggle;
+        set(&mut app, toggle, WidgetValue::Toggle(true), true);
+
+        *app.world_mut().resource_mut::<Strings>() = Strings::load(Language::German);
+        app.update();
+
+        let world = app.world_mut();
+        let mut labels = world.query::<(&LocalizedText, &Text)>();
+        let mut texts: Vec<(&str, &str)> = labels.iter(world).map(|(id, text)| (id.0, text.0.as_str())).collect();
+        texts.sort_unstable();
+        assert_eq!(
+            texts,
+            vec![("section.ocean", "Ozean"), ("setting.planet_radius.label", "Planetenradius")]
+        );
+        let label = world.get::<Children>(toggle).unwrap()[0];
+        assert_eq!(world.get::<Text>(label).unwrap().0, "AN");
+    }
+}
@@ -0,0 +1,212 @@
+use crate::ui::components::{ButtonConfig, ToggleState};
+use crate::ui::locale::StringId;
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+/// Built-in color sets of the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 2] = [ThemePreset::Dark, ThemePreset::Light];
+
+    pub fn label(self) -> StringId {
+        match self {
+            ThemePreset::Dark => "theme.dark",
+            ThemePreset::Light => "theme.light",
+        }
+    }
+}
+
+/// Colors of the whole UI, the egui panels and the spawned widgets alike. Changing it restyles
+/// what is already on screen.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct UiTheme {
+    pub preset: ThemePreset,
+    pub panel_background: Color,
+    /// Selections and the filled part of sliders
+    pub accent: Color,
+    pub button_normal: Color,
+    pub button_hover: Color,
+    pub button_pressed: Color,
+    pub text_primary: Color,
+    /// Values and other text next to a label
+    pub text_secondary: Color,
+    pub slider_track: Color,
+    /// Center line of the track
+    pub slider_fill: Color,
+    pub slider_handle: Color,
+    /// Ends of the track
+    pub slider_marker: Color,
+    pub toggle_on: Color,
+    pub toggle_off: Color,
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self::preset(ThemePreset::default())
+    }
+}
+
+impl UiTheme {
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Self {
+                preset,
+                panel_background: Color::srgb(0.1, 0.1, 0.12),
+                accent: Color::srgb(0.4, 0.55, 0.9),
+                button_normal: Color::srgb(0.15, 0.15, 0.15),
+                button_hover: Color::srgb(0.25, 0.25, 0.25),
+                button_pressed: Color::srgb(0.35, 0.35, 0.35),
+                text_primary: Color::WHITE,
+                text_secondary: Color::srgb(0.8, 0.8, 1.0),
+                slider_track: Color::srgb(0.2, 0.2, 0.2),
+                slider_fill: Color::srgb(0.5, 0.5, 0.5),
+                slider_handle: Color::srgb(0.8, 0.8, 1.0),
+                slider_marker: Color::srgb(0.6, 0.6, 0.6),
+                toggle_on: Color::srgb(0.3, 0.7, 0.3),
+                toggle_off: Color::srgb(0.6, 0.6, 0.6),
+            },
+            ThemePreset::Light => Self {
+                preset,
+                panel_background: Color::srgb(0.94, 0.94, 0.95),
+                accent: Color::srgb(0.2, 0.4, 0.8),
+                button_normal: Color::srgb(0.85, 0.85, 0.87),
+                button_hover: Color::srgb(0.78, 0.78, 0.8),
+                button_pressed: Color::srgb(0.7, 0.7, 0.72),
+                text_primary: Color::srgb(0.1, 0.1, 0.12),
+                text_secondary: Color::srgb(0.3, 0.3, 0.45),
+                slider_track: Color::srgb(0.8, 0.8, 0.8),
+                slider_fill: Color::srgb(0.55, 0.55, 0.55),
+                slider_handle: Color::srgb(0.25, 0.35, 0.7),
+                slider_marker: Color::srgb(0.5, 0.5, 0.5),
+                toggle_on: Color::srgb(0.25, 0.6, 0.25),
+                toggle_off: Color::srgb(0.7, 0.7, 0.7),
+            },
+        }
+    }
+
+    pub fn color(&self, role: ThemeRole) -> Color {
+        match role {
+            ThemeRole::PanelBackground => self.panel_background,
+            ThemeRole::Accent => self.accent,
+            ThemeRole::Button => self.button_normal,
+            ThemeRole::TextPrimary => self.text_primary,
+            ThemeRole::TextSecondary => self.text_secondary,
+            ThemeRole::SliderTrack => self.slider_track,
+            ThemeRole::SliderFill => self.slider_fill,
+            ThemeRole::SliderHandle => self.slider_handle,
+            ThemeRole::SliderMarker => self.slider_marker,
+            ThemeRole::Toggle => self.toggle_off,
+        }
+    }
+
+    /// Green when on, gray when off
+    pub fn toggle_background(&self, is_on: bool) -> BackgroundColor {
+        BackgroundColor(if is_on { self.toggle_on } else { self.toggle_off })
+    }
+
+    pub fn button_config(&self) -> ButtonConfig {
+        ButtonConfig {
+            normal_color: self.button_normal,
+            hover_color: self.button_hover,
+            pressed_color: self.button_pressed,
+        }
+    }
+
+    /// The egui look of the theme, based on egui's own dark or light visuals
+    pub fn egui_visuals(&self) -> egui::Visuals {
+        let mut visuals = match self.preset {
+            ThemePreset::Dark => egui::Visuals::dark(),
+            ThemePreset::Light => egui::Visuals::light(),
+        };
+        visuals.panel_fill = color32(self.panel_background);
+        visuals.window_fill = color32(self.panel_background);
+        visuals.selection.bg_fill = color32(self.accent);
+        visuals.slider_trailing_fill = true;
+        visuals.widgets.noninteractive.fg_stroke.color = color32(self.text_primary);
+        visuals.widgets.inactive.bg_fill = color32(self.slider_track);
+        visuals.widgets.inactive.weak_bg_fill = color32(self.button_normal);
+        visuals.widgets.hovered.weak_bg_fill = color32(self.button_hover);
+        visuals.widgets.active.weak_bg_fill = color32(self.button_pressed);
+        visuals
+    }
+
+    fn egui_theme(&self) -> egui::Theme {
+        match self.preset {
+            ThemePreset::Dark => egui::Theme::Dark,
+            ThemePreset::Light => egui::Theme::Light,
+        }
+    }
+}
+
+/// Which color of the [`UiTheme`] a spawned widget part takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeRole {
+    PanelBackground,
+    Accent,
+    /// Background and hover colors of a button
+    Button,
+    TextPrimary,
+    TextSecondary,
+    SliderTrack,
+    SliderFill,
+    SliderHandle,
+    SliderMarker,
+    /// Background of a toggle, on or off
+    Toggle,
+}
+
+/// Colored by the theme, its background or text color is rewritten when the theme changes
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Themed(pub ThemeRole);
+
+/// Every part of a themed entity a theme can color
+type ThemedParts<'a> = (
+    &'a Themed,
+    Option<&'a mut BackgroundColor>,
+    Option<&'a mut TextColor>,
+    Option<&'a mut ButtonConfig>,
+    Option<&'a ToggleState>,
+);
+
+/// Give the themed entities the colors of a changed theme
+pub fn restyle_themed(theme: Res<UiTheme>, mut themed: Query<ThemedParts>) {
+    if !theme.is_changed() {
+        return;
+    }
+    for (themed, background, text, button, toggle) in themed.iter_mut() {
+        let color = match toggle {
+            Some(toggle) => theme.toggle_background(toggle.is_on).0,
+            None => theme.color(themed.0),
+        };
+        if let Some(mut background) = background {
+            background.0 = color;
+        }
+        if let Some(mut text) = text {
+            text.0 = color;
+        }
+        if let Some(mut button) = button {
+            *button = theme.button_config();
+        }
+    }
+}
+
+/// Put the theme into egui, only when it differs so the style isn't rebuilt every frame
+pub fn configure_theme(ctx: &egui::Context, theme: &UiTheme) {
+    let visuals = theme.egui_visuals();
+    if ctx.theme() != theme.egui_theme() || ctx.style().visuals != visuals {
+        ctx.set_theme(theme.egui_theme());
+        ctx.set_visuals_of(theme.egui_theme(), visuals);
+    }
+}
+
+pub fn color32(color: Color) -> egui::Color32 {
+    let [r, g, b, a] = color.to_srgba().to_u8_array();
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
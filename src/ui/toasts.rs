@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+/// How long a toast stays up before it goes away on its own
+pub const TOAST_DURATION_SECS: f32 = 10.0;
+
+/// Older toasts are dropped when more than this many are stacked
+const MAX_TOASTS: usize = 5;
+
+const TOAST_WIDTH: f32 = 320.0;
+const TOAST_MARGIN: f32 = 12.0;
+
+/// A short message in the corner of the main area, closed with its button or after a while
+#[derive(Debug, Clone)]
+pub struct Toast {
+    id: u64,
+    pub message: String,
+    pub remaining_secs: f32,
+}
+
+/// Stack of toasts, newest last
+#[derive(Resource, Default)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+    next_id: u64,
+}
+
+impl Toasts {
+    pub fn push(&mut self, message: impl Into<String>) {
+        self.toasts.push(Toast {
+            id: self.next_id,
+            message: message.into(),
+            remaining_secs: TOAST_DURATION_SECS,
+        });
+        self.next_id += 1;
+
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+    }
+
+    pub fn dismiss(&mut self, id: u64) {
+        self.toasts.retain(|toast| toast.id != id);
+    }
+
+    /// Count the timers down and drop the toasts that ran out
+    pub fn tick(&mut self, delta_secs: f32) {
+        for toast in self.toasts.iter_mut() {
+            toast.remaining_secs -= delta_secs;
+        }
+        self.toasts.retain(|toast| toast.remaining_secs > 0.0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.toasts.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+pub fn tick_toasts(time: Res<Time>, mut toasts: ResMut<Toasts>) {
+    if !toasts.is_empty() {
+        toasts.tick(time.delta_secs());
+    }
+}
+
+/// Toasts stack down from the top left corner, away from the settings panel on the right
+pub fn render_toasts(mut contexts: EguiContexts, mut toasts: ResMut<Toasts>) {
+    if toasts.is_empty() {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else {
+        return;
+    };
+
+    let mut dismissed = Vec::new();
+    egui::Area::new(egui::Id::new("toasts"))
+        .anchor(egui::Align2::LEFT_TOP, egui::vec2(TOAST_MARGIN, TOAST_MARGIN))
+        .order(egui::Order::Foreground)
+        .interactable(true)
+        .show(ctx, |ui| {
+            ui.set_max_width(TOAST_WIDTH);
+            for toast in toasts.iter() {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(TOAST_WIDTH);
+                    ui.horizontal(|ui| {
+                        if ui.small_button("✕").clicked() {
+                            dismissed.push(toast.id);
+                        }
+                        ui.colored_label(ui.visuals().warn_fg_color, &toast.message);
+                    });
+                });
+                ui.add_space(4.0);
+            }
+        });
+
+    for id in dismissed {
+        toasts.dismiss(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts_expire_and_can_be_dismissed() {
+        let mut toasts = Toasts::default();
+        toasts.push("first");
+        toasts.tick(TOAST_DURATION_SECS * 0.5);
+        toasts.push("second");
+        toasts.push("third");
+
+        toasts.tick(TOAST_DURATION_SECS * 0.6);
+        let messages: Vec<_> = toasts.iter().map(|toast| toast.message.as_str()).collect();
+        assert_eq!(messages, ["second", "third"]);
+
+        let second = toasts.iter().next().unwrap().id;
+        toasts.dismiss(second);
+        let messages: Vec<_> = toasts.iter().map(|toast| toast.message.as_str()).collect();
+        assert_eq!(messages, ["third"]);
+    }
+
+    #[test]
+    fn test_oldest_toast_is_dropped_when_the_stack_is_full() {
+        let mut toasts = Toasts::default();
+        for i in 0..MAX_TOASTS + 2 {
+            toasts.push(i.to_string());
+        }
+        let messages: Vec<_> = toasts.iter().map(|toast| toast.message.clone()).collect();
+        assert_eq!(messages.len(), MAX_TOASTS);
+        assert_eq!(messages[0], "2");
+    }
+}
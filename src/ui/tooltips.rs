@@ -0,0 +1,270 @@
+use crate::ui::locale::Strings;
+use bevy_egui::egui;
+
+/// How long the pointer has to rest on a setting label before its tooltip shows up
+pub const TOOLTIP_DELAY_SECS: f32 = 0.5;
+
+const TOOLTIP_MAX_WIDTH: f32 = 280.0;
+
+/// Every slider in the settings panel. Adding a variant without a `key` is a compile error,
+/// and a key without its texts in the locale tables fails the tests, so a new setting can't
+/// ship without a tooltip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SettingId {
+    PlanetRadius,
+    Oblateness,
+    WorldType,
+    MasterVolume,
+    MusicVolume,
+    AmbientVolume,
+    DistortionFrequency,
+    DistortionAmplitude,
+    ContinentThreshold,
+    DetailFrequency,
+    DetailAmplitude,
+    SnowThreshold,
+    MountainHeight,
+    MountainWidth,
+    OceanFoamWidth,
+    HillshadeAzimuth,
+    HillshadeElevation,
+    HillshadeStrength,
+    ColorVariation,
+    Palette,
+    UiTheme,
+    Language,
+    CameraOffset,
+    LookAtOffset,
+    AxialTilt,
+    MoonCount,
+    MoonRadius,
+    MoonOrbitRadius,
+    MoonOrbitPeriod,
+    MoonInclination,
+    NumPlates,
+    NumMicroPlates,
+    FlowWarpFrequency,
+    FlowWarpSteps,
+    FlowWarpStepAngle,
+    WindZonalSpeed,
+    WindParticleLifespan,
+    WindGlyphSpacing,
+    WindDeflectionHeightThreshold,
+    WindDeflectionHeightScale,
+    WindDeflectionSpreadRadius,
+    WindDeflectionSpreadDecay,
+    WindDeflectionStrength,
+    WindDeflectionIterations,
+    EquatorTemperature,
+    PoleTemperature,
+    LatitudeFalloff,
+    MaxTemperature,
+    MinTemperature,
+    LandTemperatureBonus,
+    LapseRate,
+    PrecipitationTemperatureWeight,
+    PrecipitationOceanWeight,
+    BiomeIceTemperature,
+    BiomeTundraTemperature,
+    BiomeBorealTemperature,
+    BiomeTemperateTemperature,
+    BiomeHotTemperature,
+    BiomeDesertPrecipitation,
+    BiomeSavannaPrecipitation,
+    BiomeJunglePrecipitation,
+    BiomeTemperatePrecipitation,
+}
+
+pub struct SettingInfo {
+    pub label: String,
+    pub description: String,
+    pub sensible_range: String,
+}
+
+impl SettingId {
+    pub const ALL: [SettingId; 62] = [
+        SettingId::PlanetRadius,
+        SettingId::Oblateness,
+        SettingId::WorldType,
+        SettingId::MasterVolume,
+        SettingId::MusicVolume,
+        SettingId::AmbientVolume,
+        SettingId::DistortionFrequency,
+        SettingId::DistortionAmplitude,
+        SettingId::ContinentThreshold,
+        SettingId::DetailFrequency,
+        SettingId::DetailAmplitude,
+        SettingId::SnowThreshold,
+        SettingId::MountainHeight,
+        SettingId::MountainWidth,
+        SettingId::OceanFoamWidth,
+        SettingId::HillshadeAzimuth,
+        SettingId::HillshadeElevation,
+        SettingId::HillshadeStrength,
+        SettingId::ColorVariation,
+        SettingId::Palette,
+        SettingId::UiTheme,
+        SettingId::Language,
+        SettingId::CameraOffset,
+        SettingId::LookAtOffset,
+        SettingId::AxialTilt,
+        SettingId::MoonCount,
+        SettingId::MoonRadius,
+        SettingId::MoonOrbitRadius,
+        SettingId::MoonOrbitPeriod,
+        SettingId::MoonInclination,
+        SettingId::NumPlates,
+        SettingId::NumMicroPlates,
+        SettingId::FlowWarpFrequency,
+        SettingId::FlowWarpSteps,
+        SettingId::FlowWarpStepAngle,
+        SettingId::WindZonalSpeed,
+        SettingId::WindParticleLifespan,
+        SettingId::WindGlyphSpacing,
+        SettingId::WindDeflectionHeightThreshold,
+        SettingId::WindDeflectionHeightScale,
+        SettingId::WindDeflectionSpreadRadius,
+        SettingId::WindDeflectionSpreadDecay,
+        SettingId::WindDeflectionStrength,
+        SettingId::WindDeflectionIterations,
+        SettingId::EquatorTemperature,
+        SettingId::PoleTemperature,
+        SettingId::LatitudeFalloff,
+        SettingId::MaxTemperature,
+        SettingId::MinTemperature,
+        SettingId::LandTemperatureBonus,
+        SettingId::LapseRate,
+        SettingId::PrecipitationTemperatureWeight,
+        SettingId::PrecipitationOceanWeight,
+        SettingId::BiomeIceTemperature,
+        SettingId::BiomeTundraTemperature,
+        SettingId::BiomeBorealTemperature,
+        SettingId::BiomeTemperateTemperature,
+        SettingId::BiomeHotTemperature,
+        SettingId::BiomeDesertPrecipitation,
+        SettingId::BiomeSavannaPrecipitation,
+        SettingId::BiomeJunglePrecipitation,
+        SettingId::BiomeTemperatePrecipitation,
+    ];
+
+    /// Name of the setting in the locale tables, its texts are `setting.<key>.label`,
+    /// `.description` and `.range`
+    pub fn key(self) -> &'static str {
+        match self {
+            SettingId::PlanetRadius => "planet_radius",
+            SettingId::Oblateness => "oblateness",
+            SettingId::WorldType => "world_type",
+            SettingId::MasterVolume => "master_volume",
+            SettingId::MusicVolume => "music_volume",
+            SettingId::AmbientVolume => "ambient_volume",
+            SettingId::DistortionFrequency => "distortion_frequency",
+            SettingId::DistortionAmplitude => "distortion_amplitude",
+            SettingId::ContinentThreshold => "continent_threshold",
+            SettingId::DetailFrequency => "detail_frequency",
+            SettingId::DetailAmplitude => "detail_amplitude",
+            SettingId::SnowThreshold => "snow_threshold",
+            SettingId::MountainHeight => "mountain_height",
+            SettingId::MountainWidth => "mountain_width",
+            SettingId::OceanFoamWidth => "ocean_foam_width",
+            SettingId::HillshadeAzimuth => "hillshade_azimuth",
+            SettingId::HillshadeElevation => "hillshade_elevation",
+            SettingId::HillshadeStrength => "hillshade_strength",
+            SettingId::ColorVariation => "color_variation",
+            SettingId::Palette => "palette",
+            SettingId::UiTheme => "ui_theme",
+            SettingId::Language => "language",
+            SettingId::CameraOffset => "camera_offset",
+            SettingId::LookAtOffset => "look_at_offset",
+            SettingId::AxialTilt => "axial_tilt",
+            SettingId::MoonCount => "moon_count",
+            SettingId::MoonRadius => "moon_radius",
+            SettingId::MoonOrbitRadius => "moon_orbit_radius",
+            SettingId::MoonOrbitPeriod => "moon_orbit_period",
+            SettingId::MoonInclination => "moon_inclination",
+            SettingId::NumPlates => "num_plates",
+            SettingId::NumMicroPlates => "num_micro_plates",
+            SettingId::FlowWarpFrequency => "flow_warp_frequency",
+            SettingId::FlowWarpSteps => "flow_warp_steps",
+            SettingId::FlowWarpStepAngle => "flow_warp_step_angle",
+            SettingId::WindZonalSpeed => "wind_zonal_speed",
+            SettingId::WindParticleLifespan => "wind_particle_lifespan",
+            SettingId::WindGlyphSpacing => "wind_glyph_spacing",
+            SettingId::WindDeflectionHeightThreshold => "wind_deflection_height_threshold",
+            SettingId::WindDeflectionHeightScale => "wind_deflection_height_scale",
+            SettingId::WindDeflectionSpreadRadius => "wind_deflection_spread_radius",
+            SettingId::WindDeflectionSpreadDecay => "wind_deflection_spread_decay",
+            SettingId::WindDeflectionStrength => "wind_deflection_strength",
+            SettingId::WindDeflectionIterations => "wind_deflection_iterations",
+            SettingId::EquatorTemperature => "equator_temperature",
+            SettingId::PoleTemperature => "pole_temperature",
+            SettingId::LatitudeFalloff => "latitude_falloff",
+            SettingId::MaxTemperature => "max_temperature",
+            SettingId::MinTemperature => "min_temperature",
+            SettingId::LandTemperatureBonus => "land_temperature_bonus",
+            SettingId::LapseRate => "lapse_rate",
+            SettingId::PrecipitationTemperatureWeight => "precipitation_temperature_weight",
+            SettingId::PrecipitationOceanWeight => "precipitation_ocean_weight",
+            SettingId::BiomeIceTemperature => "biome_ice_temperature",
+            SettingId::BiomeTundraTemperature => "biome_tundra_temperature",
+            SettingId::BiomeBorealTemperature => "biome_boreal_temperature",
+            SettingId::BiomeTemperateTemperature => "biome_temperate_temperature",
+            SettingId::BiomeHotTemperature => "biome_hot_temperature",
+            SettingId::BiomeDesertPrecipitation => "biome_desert_precipitation",
+            SettingId::BiomeSavannaPrecipitation => "biome_savanna_precipitation",
+            SettingId::BiomeJunglePrecipitation => "biome_jungle_precipitation",
+            SettingId::BiomeTemperatePrecipitation => "biome_temperate_precipitation",
+        }
+    }
+
+    pub fn info(self, strings: &Strings) -> SettingInfo {
+        let text = |part: &str| strings.get(&format!("setting.{}.{part}", self.key())).to_string();
+        SettingInfo {
+            label: text("label"),
+            description: text("description"),
+            sensible_range: text("range"),
+        }
+    }
+}
+
+/// Label for a settings slider that explains the setting when hovered
+pub fn setting_label(ui: &mut egui::Ui, strings: &Strings, id: SettingId) -> egui::Response {
+    let info = id.info(strings);
+    ui.label(info.label).on_hover_ui(|ui| {
+        ui.set_max_width(TOOLTIP_MAX_WIDTH);
+        ui.label(info.description);
+        ui.add_space(4.0);
+        ui.weak(strings.format("tooltip.sensible_range", &[("range", &info.sensible_range)]));
+    })
+}
+
+/// Tooltips are shown after a short rest and are placed by egui inside the screen,
+/// on its topmost layer, so they never end up under other UI
+pub fn configure_tooltips(ctx: &egui::Context) {
+    if ctx.style().interaction.tooltip_delay != TOOLTIP_DELAY_SECS {
+        ctx.style_mut(|style| style.interaction.tooltip_delay = TOOLTIP_DELAY_SECS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::locale::Language;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_every_setting_has_its_texts() {
+        let strings = Strings::load(Language::English);
+        for id in SettingId::ALL {
+            for part in ["label", "description", "range"] {
+                let key = format!("setting.{}.{part}", id.key());
+                assert_ne!(strings.get(&key), key, "no text for {id:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_setting_keys_are_unique() {
+        let keys: HashSet<&str> = SettingId::ALL.iter().map(|id| id.key()).collect();
+        assert_eq!(keys.len(), SettingId::ALL.len());
+    }
+}
@@ -1,37 +1,28 @@
 use crate::ui::bundles::*;
 use crate::ui::components::*;
+use crate::ui::locale::{LocalizedText, StringId, Strings};
+use crate::ui::theme::{ThemeRole, Themed, UiTheme};
 use bevy::ecs::relationship::RelatedSpawnerCommands;
 use bevy::prelude::*;
 use bevy::ui::RelativeCursorPosition;
+use bevy_egui::egui;
 
 pub fn spawn_default_button_with_marker<T: Component>(
     parent: &mut RelatedSpawnerCommands<ChildOf>,
-    text: &str,
-    normal_color: Color,
-    hover_color: Color,
-    pressed_color: Color,
+    text: StringId,
+    (theme, strings): (&UiTheme, &Strings),
     marker: T,
 ) -> Entity {
-    parent
-        .spawn((
-            ButtonBundle::new(200.0, 50.0, normal_color, hover_color, pressed_color, 8.0),
-            marker,
-        ))
-        .with_children(|parent| {
-            parent.spawn(LabelBundle::new(text, 18.0, Color::WHITE));
-        })
-        .id()
+    spawn_button_with_marker(parent, text, 200.0, 50.0, 8.0, (theme, strings), marker)
 }
 
 pub fn spawn_button_with_marker<T: Component>(
     parent: &mut RelatedSpawnerCommands<ChildOf>,
-    text: &str,
+    text: StringId,
     width: f32,
     height: f32,
-    normal_color: Color,
-    hover_color: Color,
-    pressed_color: Color,
     border_radius: f32,
+    (theme, strings): (&UiTheme, &Strings),
     marker: T,
 ) -> Entity {
     parent
@@ -39,23 +30,30 @@ pub fn spawn_button_with_marker<T: Component>(
             ButtonBundle::new(
                 width,
                 height,
-                normal_color,
-                hover_color,
-                pressed_color,
+                theme.button_normal,
+                theme.button_hover,
+                theme.button_pressed,
                 border_radius,
             ),
+            Themed(ThemeRole::Button),
             marker,
         ))
         .with_children(|parent| {
-            parent.spawn(LabelBundle::new(text, 18.0, Color::WHITE));
+            parent.spawn((
+                LabelBundle::new(strings.get(text), 18.0, theme.text_primary),
+                Themed(ThemeRole::TextPrimary),
+                LocalizedText(text),
+            ));
         })
         .id()
 }
 
+/// Returns the toggle button itself, not its row, so it can be set with `SetWidgetValue`
 pub fn spawn_toggle_with_marker<T: Component>(
     parent: &mut RelatedSpawnerCommands<ChildOf>,
-    label: &str,
+    label: StringId,
     initial_state: bool,
+    (theme, strings): (&UiTheme, &Strings),
     marker: T,
 ) -> Entity {
     let container_node = Node {
@@ -66,32 +64,44 @@ pub fn spawn_toggle_with_marker<T: Component>(
         ..default()
     };
 
-    parent
-        .spawn(container_node)
-        .with_children(|parent| {
-            // Label
-            parent.spawn(LabelBundle::new(label, 16.0, Color::WHITE));
-
-            // Toggle button using the new bundle
-            parent
-                .spawn((ToggleBundle::new(60.0, 30.0, initial_state, 15.0), marker))
-                .with_children(|parent| {
-                    let toggle_text = if initial_state { "ON" } else { "OFF" };
-
-                    parent.spawn(LabelBundle::new(toggle_text, 14.0, Color::WHITE));
-                });
-        })
-        .id()
+    let mut toggle = Entity::PLACEHOLDER;
+    parent.spawn(container_node).with_children(|parent| {
+        // Label
+        parent.spawn((
+            LabelBundle::new(strings.get(label), 16.0, theme.text_primary),
+            Themed(ThemeRole::TextPrimary),
+            LocalizedText(label),
+        ));
+
+        // Toggle button using the new bundle
+        toggle = parent
+            .spawn((
+                ToggleBundle::new(60.0, 30.0, initial_state, 15.0, theme),
+                Themed(ThemeRole::Toggle),
+                marker,
+            ))
+            .with_children(|parent| {
+                let toggle_text = strings.get(if initial_state { "widget.on" } else { "widget.off" });
+
+                parent.spawn((
+                    LabelBundle::new(toggle_text, 14.0, theme.text_primary),
+                    Themed(ThemeRole::TextPrimary),
+                ));
+            })
+            .id();
+    });
+    toggle
 }
 
 pub fn spawn_slider_with_marker<T: Component>(
     parent: &mut RelatedSpawnerCommands<ChildOf>,
-    label: &str,
+    label: StringId,
     initial_value: f32,
     min_value: f32,
     max_value: f32,
     is_integer: bool,
     width: f32,
+    (theme, strings): (&UiTheme, &Strings),
     marker: T,
 ) -> Entity {
     parent
@@ -107,7 +117,11 @@ pub fn spawn_slider_with_marker<T: Component>(
                 .spawn(SliderTitleRowBundle::new())
                 .with_children(|parent| {
                     // Title on the left
-                    parent.spawn(LabelBundle::new(label, 16.0, Color::WHITE));
+                    parent.spawn((
+                        LabelBundle::new(strings.get(label), 16.0, theme.text_primary),
+                        Themed(ThemeRole::TextPrimary),
+                        LocalizedText(label),
+                    ));
 
                     // Current value on the right
                     let display_value = if is_integer {
@@ -117,7 +131,8 @@ pub fn spawn_slider_with_marker<T: Component>(
                     };
 
                     parent.spawn((
-                        LabelBundle::new(&display_value, 12.0, Color::srgb(0.8, 0.8, 1.0)),
+                        LabelBundle::new(&display_value, 12.0, theme.text_secondary),
+                        Themed(ThemeRole::TextSecondary),
                         SliderValueDisplay,
                         SliderTarget(slider_entity),
                     ));
@@ -137,17 +152,15 @@ pub fn spawn_slider_with_marker<T: Component>(
                             height: Val::Px(16.0),
                             ..default()
                         },
-                        BackgroundColor(Color::srgb(0.6, 0.6, 0.6)),
+                        BackgroundColor(theme.slider_marker),
+                        Themed(ThemeRole::SliderMarker),
                     ));
 
                     // Main track with visual improvements
                     parent
                         .spawn((
-                            SliderTrackBundle::new(
-                                track_width,
-                                track_height,
-                                Color::srgb(0.2, 0.2, 0.2),
-                            ),
+                            SliderTrackBundle::new(track_width, track_height, theme.slider_track),
+                            Themed(ThemeRole::SliderTrack),
                             SliderTrack,
                             SliderTarget(slider_entity),
                             RelativeCursorPosition::default(),
@@ -163,7 +176,8 @@ pub fn spawn_slider_with_marker<T: Component>(
                                     left: Val::Px(0.0),
                                     ..default()
                                 },
-                                BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+                                BackgroundColor(theme.slider_fill),
+                                Themed(ThemeRole::SliderFill),
                             ));
 
                             // Calculate handle position based on initial value
@@ -172,8 +186,9 @@ pub fn spawn_slider_with_marker<T: Component>(
                             let handle_position = value_ratio * (track_width - handle_size);
 
                             parent.spawn((
-                                SliderHandleBundle::new(handle_size, Color::srgb(0.8, 0.8, 1.0))
+                                SliderHandleBundle::new(handle_size, theme.slider_handle)
                                     .with_position(handle_position),
+                                Themed(ThemeRole::SliderHandle),
                                 SliderHandle,
                                 SliderTarget(slider_entity),
                             ));
@@ -186,9 +201,37 @@ pub fn spawn_slider_with_marker<T: Component>(
                             height: Val::Px(16.0),
                             ..default()
                         },
-                        BackgroundColor(Color::srgb(0.6, 0.6, 0.6)),
+                        BackgroundColor(theme.slider_marker),
+                        Themed(ThemeRole::SliderMarker),
                     ));
                 });
         })
         .id()
 }
+
+/// Egui table of `rows` rows in a scroll area no taller than `max_height`, with `header` as
+/// the first row. `add_row` fills row `index`, one widget per column.
+pub fn scrollable_list(
+    ui: &mut egui::Ui,
+    id: &str,
+    header: &[&str],
+    rows: usize,
+    max_height: f32,
+    mut add_row: impl FnMut(&mut egui::Ui, usize),
+) {
+    egui::ScrollArea::vertical()
+        .id_salt(id)
+        .max_height(max_height)
+        .show(ui, |ui| {
+            egui::Grid::new(id).striped(true).show(ui, |ui| {
+                for title in header {
+                    ui.strong(*title);
+                }
+                ui.end_row();
+                for index in 0..rows {
+                    add_row(ui, index);
+                    ui.end_row();
+                }
+            });
+        });
+}
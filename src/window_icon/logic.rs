@@ -0,0 +1,155 @@
+use image::{ImageError, ImageFormat};
+use std::io::Cursor;
+
+/// Size of the ICO file header, a directory entry follows it for every image
+const ICO_HEADER_LEN: usize = 6;
+const ICO_ENTRY_LEN: usize = 16;
+
+/// A decoded icon, ready to be handed to the window
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconImage {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub fn decode_png(bytes: &[u8]) -> Result<IconImage, ImageError> {
+    decode(bytes, ImageFormat::Png)
+}
+
+/// One image in an ICO file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IcoEntry {
+    /// The raw directory entry, the image data offset in it is rewritten when decoding
+    directory_entry: [u8; ICO_ENTRY_LEN],
+    data_offset: usize,
+    data_len: usize,
+}
+
+/// The images listed in an ICO file. Entries pointing outside the file are skipped, a file
+/// that isn't an icon at all has none.
+pub fn ico_entries(bytes: &[u8]) -> Vec<IcoEntry> {
+    let Some(header) = bytes.get(..ICO_HEADER_LEN) else {
+        return Vec::new();
+    };
+    // Reserved 0, type 1 = icon (2 would be a cursor)
+    if header[..4] != [0, 0, 1, 0] {
+        return Vec::new();
+    }
+    let count = u16::from_le_bytes([header[4], header[5]]) as usize;
+
+    (0..count)
+        .filter_map(|index| {
+            let start = ICO_HEADER_LEN + index * ICO_ENTRY_LEN;
+            let directory_entry: [u8; ICO_ENTRY_LEN] = bytes.get(start..start + ICO_ENTRY_LEN)?.try_into().ok()?;
+            let data_len = u32::from_le_bytes(directory_entry[8..12].try_into().ok()?) as usize;
+            let data_offset = u32::from_le_bytes(directory_entry[12..16].try_into().ok()?) as usize;
+            bytes.get(data_offset..data_offset.checked_add(data_len)?)?;
+            Some(IcoEntry {
+                directory_entry,
+                data_offset,
+                data_len,
+            })
+        })
+        .collect()
+}
+
+/// Decode every image of an ICO file, images that fail to decode are left out
+pub fn decode_ico(bytes: &[u8]) -> Vec<IconImage> {
+    ico_entries(bytes)
+        .iter()
+        .filter_map(|entry| decode_ico_entry(bytes, entry).ok())
+        .collect()
+}
+
+/// The icon to show at `target` pixels: the smallest one that is at least that big so it
+/// only gets scaled down, or the biggest one if they are all smaller
+pub fn closest_icon(icons: &[IconImage], target: u32) -> Option<&IconImage> {
+    icons
+        .iter()
+        .filter(|icon| icon.width >= target)
+        .min_by_key(|icon| icon.width)
+        .or_else(|| icons.iter().max_by_key(|icon| icon.width))
+}
+
+/// Decode a single image of an ICO file. The ICO decoder only reads the biggest image, so the
+/// entry is wrapped into an ICO file of its own first.
+pub fn decode_ico_entry(bytes: &[u8], entry: &IcoEntry) -> Result<IconImage, ImageError> {
+    let data_offset = (ICO_HEADER_LEN + ICO_ENTRY_LEN) as u32;
+    let mut single = Vec::with_capacity(data_offset as usize + entry.data_len);
+    single.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    single.extend_from_slice(&entry.directory_entry[..12]);
+    single.extend_from_slice(&data_offset.to_le_bytes());
+    single.extend_from_slice(&bytes[entry.data_offset..entry.data_offset + entry.data_len]);
+    decode(&single, ImageFormat::Ico)
+}
+
+fn decode(bytes: &[u8], format: ImageFormat) -> Result<IconImage, ImageError> {
+    let image = image::load(Cursor::new(bytes), format)?.into_rgba8();
+    let (width, height) = image.dimensions();
+    Ok(IconImage {
+        rgba: image.into_raw(),
+        width,
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn png(size: u32, color: [u8; 4]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        RgbaImage::from_pixel(size, size, Rgba(color))
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    /// An ICO file with PNG images of the given sizes, the way most icon tools write them
+    fn ico(images: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, 1, 0, images.len() as u8, 0];
+        let mut offset = ICO_HEADER_LEN + images.len() * ICO_ENTRY_LEN;
+        for (size, data) in images {
+            let dimension = if *size >= 256 { 0 } else { *size as u8 };
+            bytes.extend_from_slice(&[dimension, dimension, 0, 0, 1, 0, 32, 0]);
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += data.len();
+        }
+        for (_, data) in images {
+            bytes.extend_from_slice(data);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_each_size_of_an_ico_decodes_on_its_own() {
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let bytes = ico(&[(16, png(16, red)), (32, png(32, blue)), (256, png(256, red))]);
+
+        let icons = decode_ico(&bytes);
+        assert_eq!(icons.iter().map(|icon| icon.width).collect::<Vec<_>>(), [16, 32, 256]);
+
+        let taskbar = closest_icon(&icons, 24).unwrap();
+        assert_eq!((taskbar.width, taskbar.height), (32, 32));
+        assert_eq!(taskbar.rgba[..4], blue);
+
+        let huge = closest_icon(&icons, 512).unwrap();
+        assert_eq!(huge.width, 256);
+    }
+
+    #[test]
+    fn test_broken_icons_are_errors_instead_of_panics() {
+        assert!(decode_png(&[]).is_err());
+        assert!(ico_entries(&png(16, [0; 4])).is_empty());
+        assert!(closest_icon(&[], 16).is_none());
+
+        // The directory claims more data than the file has
+        let mut truncated = ico(&[(16, png(16, [0; 4]))]);
+        truncated.truncate(truncated.len() - 1);
+        assert!(ico_entries(&truncated).is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+mod logic;
+mod systems;
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use logic::IconImage;
+
+pub struct WindowIconPlugin;
+
+// Sets the icon of every window once winit has created it, and again whenever it is recreated
+impl Plugin for WindowIconPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(WindowIcon::load())
+            .add_systems(Update, systems::apply_window_icon);
+    }
+}
+
+/// Icons decoded once at startup, every window gets a copy of them
+#[derive(Resource, Default)]
+pub struct WindowIcon {
+    /// All sizes of the icon, a single one unless it came from a multi-size .ico
+    pub images: Vec<IconImage>,
+}
+
+/// Marks windows that got their icon, so it isn't set again every frame
+#[derive(Component)]
+pub struct WindowIconApplied;
+
+const ICON_PNG: &[u8] = include_bytes!("../../assets/icons/icon.png");
+/// Optional multi-size icon, so Windows doesn't scale the taskbar icon down from the PNG.
+/// Looked up in the asset folder at runtime so builds don't depend on it.
+const ICON_ICO_PATH: &str = "icons/icon.ico";
+
+impl WindowIcon {
+    fn load() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = FileAssetReader::get_base_path().join("assets").join(ICON_ICO_PATH);
+            if let Ok(bytes) = std::fs::read(&path) {
+                let images = logic::decode_ico(&bytes);
+                if !images.is_empty() {
+                    return Self { images };
+                }
+                warn!("Could not decode {}, falling back to the PNG icon", path.display());
+            }
+        }
+
+        match logic::decode_png(ICON_PNG) {
+            Ok(image) => Self { images: vec![image] },
+            Err(error) => {
+                warn!("Could not decode the window icon: {error}");
+                Self::default()
+            }
+        }
+    }
+}
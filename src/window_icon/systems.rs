@@ -0,0 +1,67 @@
+use super::logic::{self, IconImage};
+use super::{WindowIcon, WindowIconApplied};
+use bevy::ecs::message::MessageReader;
+use bevy::prelude::*;
+use bevy::window::WindowCreated;
+use bevy::winit::WinitWindows;
+use winit::window::Icon;
+
+/// Logical size of the title bar icon, the taskbar one is twice as big
+const SMALL_ICON_SIZE: f64 = 16.0;
+
+/// Sets the icon of windows that don't have it yet and of windows winit has just recreated.
+/// A window winit hasn't created yet is retried next frame.
+pub fn apply_window_icon(
+    mut commands: Commands,
+    mut created_events: MessageReader<WindowCreated>,
+    windows: Query<(Entity, Has<WindowIconApplied>), With<Window>>,
+    winit_windows: Option<NonSend<WinitWindows>>,
+    icon: Res<WindowIcon>,
+    mut warned: Local<bool>,
+) {
+    let created: Vec<Entity> = created_events.read().map(|event| event.window).collect();
+    let mut pending = windows
+        .iter()
+        .filter(|(entity, applied)| !applied || created.contains(entity))
+        .map(|(entity, _)| entity)
+        .peekable();
+    if pending.peek().is_none() || icon.images.is_empty() {
+        return;
+    }
+
+    // Headless runs and CI have windows without winit behind them
+    let Some(winit_windows) = winit_windows else {
+        if !*warned {
+            warn!("WinitWindows is unavailable, the window icon is not set");
+            *warned = true;
+        }
+        return;
+    };
+
+    for entity in pending {
+        let Some(window) = winit_windows.get_window(entity) else {
+            continue;
+        };
+        let small_size = (SMALL_ICON_SIZE * window.scale_factor()).round() as u32;
+        window.set_window_icon(closest_winit_icon(&icon.images, small_size));
+
+        #[cfg(windows)]
+        {
+            use winit::platform::windows::WindowExtWindows;
+            window.set_taskbar_icon(closest_winit_icon(&icon.images, small_size * 2));
+        }
+
+        commands.entity(entity).insert(WindowIconApplied);
+    }
+}
+
+fn closest_winit_icon(images: &[IconImage], size: u32) -> Option<Icon> {
+    let image = logic::closest_icon(images, size)?;
+    match Icon::from_rgba(image.rgba.clone(), image.width, image.height) {
+        Ok(icon) => Some(icon),
+        Err(error) => {
+            warn!("Invalid window icon: {error}");
+            None
+        }
+    }
+}
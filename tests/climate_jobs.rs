@@ -0,0 +1,61 @@
+//! Runs the climate rebuilds through the job queue headless: the first planet gets its wind and
+//! precipitation from finished jobs, and a new planet cancels the jobs queued for the old one.
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::events::{GeneratePlanetEvent, PrecipitationRebuilt};
+use inhabitants::planet::jobs::logic::{ClimateJobKind, PrecipitationPhase};
+use inhabitants::planet::jobs::{ClimateJobOutput, ClimateJobQueue};
+use inhabitants::planet::precipitation::systems::PrecipitationCubeMap;
+use inhabitants::planet::wind::systems::{MountainInfluence, VerticalAirCubeMap, WindCubeMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+fn headless_app() -> App {
+    // A small planet, generated in one go. The climate jobs still run in the background.
+    let mut app = base_app(10.0, false, |_| {});
+
+    run_frames(&mut app);
+    app
+}
+
+#[test]
+fn test_first_planet_gets_its_climate_from_finished_jobs() {
+    let app = headless_app();
+    let world = app.world();
+
+    assert!(world.contains_resource::<WindCubeMap>());
+    assert!(world.contains_resource::<MountainInfluence>());
+    assert!(world.contains_resource::<VerticalAirCubeMap>());
+    assert!(world.contains_resource::<PrecipitationCubeMap>());
+
+    let queue = world.resource::<ClimateJobQueue>();
+    assert!(queue.is_idle());
+    // The wind and the annual precipitation at least
+    assert!(queue.finished >= 2, "{} jobs finished", queue.finished);
+}
+
+#[test]
+fn test_new_planet_cancels_the_queued_jobs() {
+    let mut app = headless_app();
+    let finished = app.world().resource::<ClimateJobQueue>().finished;
+    let ran = Arc::new(AtomicBool::new(false));
+    let job_ran = ran.clone();
+    let kind = ClimateJobKind::RebuildPrecipitation { phase: PrecipitationPhase::Seasons };
+    app.world_mut().resource_mut::<ClimateJobQueue>().push(kind, move || {
+        job_ran.store(true, Ordering::SeqCst);
+        ClimateJobOutput::Precipitation(PrecipitationRebuilt::Seasons(Vec::new()))
+    });
+
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    assert!(!ran.load(Ordering::SeqCst), "the job of the old planet ran");
+    let queue = app.world().resource::<ClimateJobQueue>();
+    assert!(queue.cancelled >= 1);
+    assert!(queue.is_idle());
+    // The new planet's own climate was built
+    assert!(queue.finished > finished);
+}
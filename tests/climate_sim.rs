@@ -0,0 +1,54 @@
+//! Runs the climate simulation headless at different frame rates and checks that the fixed
+//! steps give exactly the same temperatures.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::{base_app, run_frames};
+use inhabitants::planet::temperature::ClimateSimState;
+use inhabitants::planet::temperature::systems::TemperatureCubeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+const SEED: u32 = 12345678;
+const STEPS: u32 = 100;
+
+fn headless_app(frame_duration: Duration) -> App {
+    // A small planet with a fixed seed, generated in one go
+    let mut app = base_app(10.0, false, |settings| {
+        settings.terrain.set_seed(SEED);
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(frame_duration));
+
+    // The first frames generate the planet and the wind deflected by it
+    run_frames(&mut app);
+    app
+}
+
+/// Request `STEPS` single steps and run frames until they are all taken
+fn simulate(frame_duration: Duration) -> u64 {
+    let mut app = headless_app(frame_duration);
+    app.world_mut().resource_mut::<ClimateSimState>().pending_steps = STEPS;
+    while app.world().resource::<ClimateSimState>().pending_steps > 0 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<ClimateSimState>().step, STEPS as u64);
+
+    let mut hasher = DefaultHasher::new();
+    let cubemap = app.world().resource::<TemperatureCubeMap>();
+    for face in &cubemap.inner.faces {
+        for temperature in face.temperatures.iter() {
+            temperature.to_bits().hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+#[test]
+fn test_temperatures_after_fixed_steps_dont_depend_on_the_frame_rate() {
+    let at_60_fps = simulate(Duration::from_micros(16_667));
+    assert_eq!(at_60_fps, simulate(Duration::from_micros(16_667)));
+    assert_eq!(at_60_fps, simulate(Duration::from_millis(250)));
+}
@@ -0,0 +1,89 @@
+//! The planet generation screen run headless, shared by the integration tests. Every test file
+//! compiles its own copy of this module and uses only part of it.
+#![allow(dead_code)]
+
+use bevy::asset::AssetPlugin;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use inhabitants::PlanetGenerationPlugin;
+use inhabitants::planet::components::CameraLerp;
+use inhabitants::planet::jobs::ClimateJobQueue;
+use inhabitants::planet::resources::{
+    ClimateSettings, DisplaySettings, PlanetSettingsSnapshot, TerrainSettings, WindSettings,
+};
+use std::time::{Duration, Instant};
+
+/// Enough for events to travel through every system that reacts to them
+pub const FRAMES: usize = 5;
+/// Longest the climate jobs of a test planet may take
+const CLIMATE_JOBS_TIMEOUT: Duration = Duration::from_secs(120);
+/// Wait between frames while a climate job is running
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The planet generation screen without a window, before its first frame. The first planet is
+/// generated at `radius`, in preview steps if `preview` is set, with whatever else `settings`
+/// changes.
+pub fn base_app(radius: f32, preview: bool, settings: impl FnOnce(&mut PlanetSettingsSnapshot)) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        StatesPlugin,
+        InputPlugin,
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<StandardMaterial>()
+    .init_asset::<Image>()
+    .add_plugins(PlanetGenerationPlugin);
+
+    let world = app.world_mut();
+    let mut planet_settings = PlanetSettingsSnapshot {
+        terrain: world.resource::<TerrainSettings>().clone(),
+        climate: world.resource::<ClimateSettings>().clone(),
+        wind: world.resource::<WindSettings>().clone(),
+        display: world.resource::<DisplaySettings>().clone(),
+    };
+    planet_settings.terrain.radius = radius;
+    planet_settings.terrain.preview_generation = preview;
+    settings(&mut planet_settings);
+    world.insert_resource(planet_settings.terrain);
+    world.insert_resource(planet_settings.climate);
+    world.insert_resource(planet_settings.wind);
+    world.insert_resource(planet_settings.display);
+    app
+}
+
+/// Run `FRAMES` frames, then more until the climate jobs were idle for `FRAMES` frames in a row.
+/// The jobs run on the async compute pool, so what they build reaches the world by then.
+pub fn run_frames(app: &mut App) {
+    let deadline = Instant::now() + CLIMATE_JOBS_TIMEOUT;
+    let mut idle_frames = 0;
+    while idle_frames < FRAMES {
+        app.update();
+        if app.world().get_resource::<ClimateJobQueue>().is_none_or(ClimateJobQueue::is_idle) {
+            idle_frames += 1;
+        } else {
+            idle_frames = 0;
+            assert!(Instant::now() < deadline, "the climate jobs didn't finish in {CLIMATE_JOBS_TIMEOUT:?}");
+            std::thread::sleep(JOB_POLL_INTERVAL);
+        }
+    }
+}
+
+/// What the camera plugin spawns, the planet is turned in front of it
+pub fn spawn_camera(app: &mut App) {
+    app.world_mut().spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 60.0).looking_at(Vec3::ZERO, Vec3::Y),
+        CameraLerp {
+            target_position: Vec3::new(0.0, 0.0, 60.0),
+            target_look_at: Vec3::ZERO,
+            current_look_at: Vec3::ZERO,
+            pivot: Vec3::ZERO,
+            dir: Vec3::Z,
+            lerp_speed: 3.0,
+            is_lerping: false,
+        },
+    ));
+}
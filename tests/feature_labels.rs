@@ -0,0 +1,77 @@
+//! Names the features of a headless planet and checks the names follow the seed and the
+//! labels follow the Display toggle
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::PlanetEntity;
+use inhabitants::planet::events::GeneratePlanetEvent;
+use inhabitants::planet::labels::FeatureLabel;
+use inhabitants::planet::resources::{DisplaySettings, TerrainSettings};
+
+fn headless_app(seed: u32) -> App {
+    // A small planet, generated in one go
+    let mut app = base_app(10.0, false, |settings| {
+        settings.terrain.set_seed(seed);
+    });
+
+    run_frames(&mut app);
+    app
+}
+
+/// Names of the labels, each checked to sit on the current planet
+fn label_names(app: &mut App) -> Vec<String> {
+    let world = app.world_mut();
+    let planet = world
+        .query_filtered::<Entity, With<PlanetEntity>>()
+        .single(world)
+        .expect("exactly one planet");
+    let mut labels = world.query::<(&FeatureLabel, &ChildOf)>();
+    let mut names: Vec<String> = labels
+        .iter(world)
+        .map(|(label, parent)| {
+            assert_eq!(parent.parent(), planet);
+            label.name.clone()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_the_same_seed_names_the_planet_the_same() {
+    let mut first = headless_app(11);
+    let mut second = headless_app(11);
+
+    let names = label_names(&mut first);
+    assert!(!names.is_empty());
+    assert_eq!(names, label_names(&mut second));
+}
+
+#[test]
+fn test_a_new_planet_is_named_again() {
+    let mut app = headless_app(11);
+    let names = label_names(&mut app);
+
+    app.world_mut().resource_mut::<TerrainSettings>().set_seed(12);
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    let renamed = label_names(&mut app);
+    assert!(!renamed.is_empty());
+    assert_ne!(names, renamed);
+}
+
+#[test]
+fn test_labels_follow_the_toggle() {
+    let mut app = headless_app(11);
+
+    app.world_mut().resource_mut::<DisplaySettings>().show_feature_labels = false;
+    run_frames(&mut app);
+    assert!(label_names(&mut app).is_empty());
+
+    app.world_mut().resource_mut::<DisplaySettings>().show_feature_labels = true;
+    run_frames(&mut app);
+    assert!(!label_names(&mut app).is_empty());
+}
@@ -0,0 +1,83 @@
+//! Plays a gamepad on the headless planet generation screen: the shoulder buttons switch tabs
+//! like the tab bar, Start generates like the Generate button and the right stick turns the
+//! planet.
+
+mod common;
+
+use bevy::input::gamepad::{RawGamepadAxisChangedEvent, RawGamepadButtonChangedEvent, RawGamepadEvent};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::{base_app, run_frames, spawn_camera};
+use inhabitants::planet::components::{PlanetControls, PlanetEntity};
+use inhabitants::planet::events::ViewKind;
+use inhabitants::planet::resources::OverlayState;
+use std::time::Duration;
+
+fn headless_app() -> (App, Entity) {
+    let mut app = base_app(10.0, false, |_| {});
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)));
+
+    spawn_camera(&mut app);
+    let gamepad = app.world_mut().spawn(Gamepad::default()).id();
+    run_frames(&mut app);
+    (app, gamepad)
+}
+
+/// Press and release `button`, a frame each
+fn tap(app: &mut App, gamepad: Entity, button: GamepadButton) {
+    for value in [1.0, 0.0] {
+        app.world_mut()
+            .write_message(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(gamepad, button, value)));
+        app.update();
+    }
+    run_frames(app);
+}
+
+fn active_view(app: &App) -> ViewKind {
+    app.world().resource::<OverlayState>().active
+}
+
+fn planet(app: &mut App) -> (Entity, Quat) {
+    let world = app.world_mut();
+    let mut planets = world.query_filtered::<(Entity, &PlanetControls), With<PlanetEntity>>();
+    let (entity, controls) = planets.single(world).expect("exactly one planet");
+    (entity, controls.rotation)
+}
+
+#[test]
+fn test_shoulder_buttons_cycle_the_tabs_and_start_generates() {
+    let (mut app, gamepad) = headless_app();
+    assert_eq!(active_view(&app), ViewKind::Continent);
+
+    tap(&mut app, gamepad, GamepadButton::RightTrigger);
+    assert_eq!(active_view(&app), ViewKind::Tectonic);
+    tap(&mut app, gamepad, GamepadButton::LeftTrigger);
+    tap(&mut app, gamepad, GamepadButton::LeftTrigger);
+    // Past the first tab is the last one
+    assert_eq!(active_view(&app), ViewKind::CrustAge);
+
+    let (before, _) = planet(&mut app);
+    tap(&mut app, gamepad, GamepadButton::Start);
+    let (after, _) = planet(&mut app);
+    assert_ne!(before, after, "Start didn't generate a new planet");
+}
+
+#[test]
+fn test_right_stick_turns_the_planet_while_held() {
+    let (mut app, gamepad) = headless_app();
+    let (_, resting) = planet(&mut app);
+
+    let stick = |value| RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(gamepad, GamepadAxis::RightStickX, value));
+    app.world_mut().write_message(stick(1.0));
+    run_frames(&mut app);
+    let (_, turned) = planet(&mut app);
+    assert!(turned.angle_between(resting) > 0.01, "the stick didn't turn the planet");
+
+    // Let go, no spin is left over
+    app.world_mut().write_message(stick(0.0));
+    app.update();
+    let (_, released) = planet(&mut app);
+    run_frames(&mut app);
+    let (_, later) = planet(&mut app);
+    assert!(later.angle_between(released) < 1e-6);
+}
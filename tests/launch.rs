@@ -0,0 +1,112 @@
+//! Launches headless with command line options and checks that the planet is generated from
+//! them and its overlays end up as PNGs before the app asks to quit, or that it comes from the
+//! generation record to reproduce.
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::launch::{EXPORT_HEIGHT, LaunchOptions, LaunchPlugin};
+use inhabitants::planet::components::PlanetEntity;
+use inhabitants::planet::history::logic::{DerivedValues, GAME_VERSION, GenerationRecord, record_to_ron};
+use inhabitants::planet::resources::{PlanetSettingsSnapshot, TerrainSettings};
+
+/// Generating and building the climate cubemaps takes a few frames, quitting must not take more
+const MAX_FRAMES: usize = 30;
+
+fn headless_app(options: LaunchOptions) -> App {
+    let mut app = base_app(TerrainSettings::default().radius, false, |_| {});
+    app.add_plugins(LaunchPlugin { options });
+    app
+}
+
+#[test]
+fn test_export_writes_the_overlays_and_quits() {
+    let export_dir = std::env::temp_dir().join(format!("inhabitants-launch-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&export_dir);
+    let options = LaunchOptions::parse(
+        [
+            "--seed", "12345", "--radius", "10", "--plates", "5", "--export-dir",
+            export_dir.to_str().unwrap(), "--exit-after-export",
+        ]
+        .map(String::from),
+    )
+    .unwrap();
+    let mut app = headless_app(options);
+
+    let mut exit = None;
+    for _ in 0..MAX_FRAMES {
+        app.update();
+        exit = app.should_exit();
+        if exit.is_some() {
+            break;
+        }
+    }
+
+    assert_eq!(exit, Some(AppExit::Success));
+    let settings = app.world().resource::<TerrainSettings>();
+    assert_eq!(settings.user_seed, 12345);
+    assert_eq!(settings.radius, 10.0);
+    assert_eq!(settings.num_plates, 5);
+    for overlay in ["temperature", "precipitation", "vertical_air", "wind_influence"] {
+        let path = export_dir.join(format!("{overlay}.png"));
+        let dimensions = image::image_dimensions(&path).unwrap_or_else(|error| panic!("{overlay}: {error}"));
+        assert_eq!(dimensions, (EXPORT_HEIGHT * 2, EXPORT_HEIGHT));
+    }
+    std::fs::remove_dir_all(&export_dir).unwrap();
+}
+
+#[test]
+fn test_autogenerate_replaces_the_first_planet() {
+    let options = LaunchOptions::parse(["--radius", "10", "--autogenerate"].map(String::from)).unwrap();
+    let mut app = headless_app(options);
+    run_frames(&mut app);
+
+    let world = app.world_mut();
+    assert_eq!(world.query_filtered::<(), With<PlanetEntity>>().iter(world).count(), 1);
+    assert_eq!(world.resource::<TerrainSettings>().radius, 10.0);
+}
+
+#[test]
+fn test_reproduce_takes_the_settings_of_the_record() {
+    let mut recorded = PlanetSettingsSnapshot::default();
+    recorded.terrain.set_seed(4242);
+    recorded.terrain.radius = 10.0;
+    recorded.terrain.num_plates = 4;
+    recorded.terrain.continent_threshold += 0.1;
+    recorded.terrain.preview_generation = false;
+    let record = GenerationRecord {
+        version: GAME_VERSION.to_string(),
+        settings: recorded.clone(),
+        config: planetgen::config::get_config(),
+        derived: DerivedValues::default(),
+        stats: Default::default(),
+    };
+    let path = std::env::temp_dir().join(format!("inhabitants-reproduce-{}.ron", std::process::id()));
+    std::fs::write(&path, record_to_ron(&record).unwrap()).unwrap();
+    let options =
+        LaunchOptions::parse(["--reproduce", path.to_str().unwrap(), "--plates", "6"].map(String::from)).unwrap();
+    let mut app = headless_app(options);
+    run_frames(&mut app);
+    std::fs::remove_file(&path).unwrap();
+
+    let world = app.world_mut();
+    assert_eq!(world.query_filtered::<(), With<PlanetEntity>>().iter(world).count(), 1);
+    let settings = world.resource::<TerrainSettings>();
+    assert_eq!(settings.user_seed, 4242);
+    assert_eq!(settings.radius, 10.0);
+    assert_eq!(settings.continent_threshold, recorded.terrain.continent_threshold);
+    // The options on the command line go on top of the record
+    assert_eq!(settings.num_plates, 6);
+}
+
+#[test]
+fn test_unreadable_record_quits() {
+    let path = std::env::temp_dir().join("inhabitants-no-such-record.ron");
+    let options = LaunchOptions::parse(["--reproduce", path.to_str().unwrap()].map(String::from)).unwrap();
+    let mut app = headless_app(options);
+
+    app.update();
+
+    assert_eq!(app.should_exit(), Some(AppExit::error()));
+}
@@ -0,0 +1,73 @@
+//! Spawns moons next to a planet generated headless and checks that they orbit it on their
+//! own, outside the planet hierarchy the overlays work on.
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::{OverlayMesh, PlanetEntity};
+use inhabitants::planet::moons::Moon;
+use inhabitants::planet::resources::{DisplaySettings, MoonSettings};
+
+/// Enough for a settings change to travel through every system that reacts to it
+const RADIUS: f32 = 10.0;
+
+fn headless_app(moons: Vec<MoonSettings>) -> App {
+    let mut app = base_app(RADIUS, false, |settings| {
+        settings.display.moons = moons;
+    });
+
+    run_frames(&mut app);
+    app
+}
+
+fn moon_transforms(app: &mut App) -> Vec<(usize, Transform)> {
+    let world = app.world_mut();
+    let mut moons: Vec<(usize, Transform)> = world
+        .query::<(&Moon, &Transform)>()
+        .iter(world)
+        .map(|(moon, transform)| (moon.index, *transform))
+        .collect();
+    moons.sort_by_key(|(index, _)| *index);
+    moons
+}
+
+#[test]
+fn test_moons_orbit_the_planet_at_their_distance() {
+    let moons = vec![MoonSettings::nth(0), MoonSettings::nth(1)];
+    let mut app = headless_app(moons.clone());
+
+    let transforms = moon_transforms(&mut app);
+    assert_eq!(transforms.len(), 2);
+    for ((index, transform), settings) in transforms.iter().zip(&moons) {
+        let distance = transform.translation.length();
+        assert!(
+            (distance - settings.orbit_radius * RADIUS).abs() < 1e-2,
+            "moon {index} is {distance} from the center"
+        );
+        assert_eq!(transform.scale, Vec3::splat(RADIUS));
+    }
+
+    // Free of the planet, and of everything the overlays and the tab visibility look for
+    let world = app.world_mut();
+    let stray = world
+        .query_filtered::<(), (With<Moon>, Or<(With<ChildOf>, With<PlanetEntity>, With<OverlayMesh>)>)>()
+        .iter(world)
+        .count();
+    assert_eq!(stray, 0);
+}
+
+#[test]
+fn test_moon_count_follows_the_settings() {
+    let mut app = headless_app(vec![MoonSettings::nth(0)]);
+    assert_eq!(moon_transforms(&mut app).len(), 1);
+
+    let mut settings = app.world_mut().resource_mut::<DisplaySettings>();
+    settings.moons = (0..3).map(MoonSettings::nth).collect();
+    run_frames(&mut app);
+    assert_eq!(moon_transforms(&mut app).len(), 3);
+
+    app.world_mut().resource_mut::<DisplaySettings>().moons.clear();
+    run_frames(&mut app);
+    assert!(moon_transforms(&mut app).is_empty());
+}
@@ -0,0 +1,142 @@
+//! Opens every overlay on a full size planet headless and counts the vertex and index bytes
+//! the overlay meshes add on top of the continent and ocean meshes they are copied from.
+
+mod common;
+
+use bevy::mesh::Indices;
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::{ContinentViewMesh, OceanEntity, OverlayMesh};
+use inhabitants::planet::crust_age::systems::CrustAgeMesh;
+use inhabitants::planet::events::*;
+use inhabitants::planet::precipitation::systems::PrecipitationMesh;
+use inhabitants::planet::resources::DisplaySettings;
+use inhabitants::planet::temperature::systems::TemperatureMesh;
+use inhabitants::planet::wind::systems::{VerticalAirMesh, WindInfluenceMesh};
+use std::collections::HashSet;
+
+fn headless_app(overlay_textures: bool) -> App {
+    let mut app = base_app(50.0, false, |settings| {
+        settings.display.overlay_textures = overlay_textures;
+    });
+
+    run_frames(&mut app);
+    app
+}
+
+fn switch_tab(app: &mut App, view: ViewKind) {
+    app.world_mut().write_message(SetActiveView(view));
+    run_frames(app);
+}
+
+/// Open every overlay the way a user clicking through the tabs would
+fn open_all_overlays(app: &mut App) {
+    switch_tab(app, ViewKind::Temperature);
+    switch_tab(app, ViewKind::Precipitations);
+    switch_tab(app, ViewKind::CrustAge);
+    switch_tab(app, ViewKind::Wind);
+    let mut settings = app.world_mut().resource_mut::<DisplaySettings>();
+    settings.show_vertical_air = true;
+    settings.show_wind_influence = true;
+    run_frames(app);
+}
+
+fn count<M: Component>(app: &mut App) -> usize {
+    let world = app.world_mut();
+    world.query_filtered::<(), With<M>>().iter(world).count()
+}
+
+/// Bytes of vertex and index data a mesh holds
+fn mesh_byte_size(mesh: &Mesh) -> usize {
+    let vertices: usize = mesh.attributes().map(|(_, values)| values.get_bytes().len()).sum();
+    let indices = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.len() * 2,
+        Some(Indices::U32(indices)) => indices.len() * 4,
+        None => 0,
+    };
+    vertices + indices
+}
+
+/// Bytes of every distinct mesh asset on entities matching the filter `F`
+fn distinct_mesh_bytes<F: bevy::ecs::query::QueryFilter>(app: &mut App) -> usize {
+    let world = app.world_mut();
+    let handles: Vec<Handle<Mesh>> = world
+        .query_filtered::<&Mesh3d, F>()
+        .iter(world)
+        .map(|mesh| mesh.0.clone())
+        .collect();
+    let meshes = world.resource::<Assets<Mesh>>();
+    let mut seen = HashSet::new();
+    handles
+        .iter()
+        .filter(|handle| seen.insert(handle.id()))
+        .map(|handle| mesh_byte_size(meshes.get(handle).expect("mesh asset")))
+        .sum()
+}
+
+fn assert_overlays_stay_within_budget(overlay_textures: bool) {
+    let mut app = headless_app(overlay_textures);
+    open_all_overlays(&mut app);
+
+    for (name, entities) in [
+        ("temperature", count::<TemperatureMesh>(&mut app)),
+        ("precipitation", count::<PrecipitationMesh>(&mut app)),
+        ("crust age", count::<CrustAgeMesh>(&mut app)),
+        ("vertical air", count::<VerticalAirMesh>(&mut app)),
+        ("wind influence", count::<WindInfluenceMesh>(&mut app)),
+    ] {
+        assert!(entities > 0, "no {name} overlay");
+    }
+
+    let base = distinct_mesh_bytes::<Or<(With<ContinentViewMesh>, With<OceanEntity>)>>(&mut app);
+    let overlays = distinct_mesh_bytes::<With<OverlayMesh>>(&mut app);
+    assert!(base > 0);
+    assert!(
+        overlays as f32 <= base as f32 * 1.2,
+        "overlays add {overlays} bytes to the {base} bytes of the planet meshes"
+    );
+}
+
+#[test]
+fn test_all_overlays_add_at_most_a_fifth_more_than_the_planet_with_textures() {
+    assert_overlays_stay_within_budget(true);
+}
+
+#[test]
+fn test_all_overlays_add_at_most_a_fifth_more_than_the_planet_with_vertex_colors() {
+    assert_overlays_stay_within_budget(false);
+}
+
+#[test]
+fn test_shown_overlay_brings_its_colors_to_the_shared_copy() {
+    let mut app = headless_app(false);
+    open_all_overlays(&mut app);
+    switch_tab(&mut app, ViewKind::Precipitations);
+
+    let world = app.world_mut();
+    let copies: Vec<Handle<Mesh>> = world
+        .query_filtered::<&Mesh3d, With<PrecipitationMesh>>()
+        .iter(world)
+        .map(|mesh| mesh.0.clone())
+        .collect();
+    let meshes = world.resource::<Assets<Mesh>>();
+    for handle in copies {
+        let mesh = meshes.get(&handle).expect("mesh asset");
+        let colors = mesh.attribute(Mesh::ATTRIBUTE_COLOR).expect("vertex colors");
+        assert_eq!(colors.len(), mesh.count_vertices());
+    }
+
+    // The textured temperature overlay has no use for them
+    let mut app = headless_app(true);
+    switch_tab(&mut app, ViewKind::Temperature);
+    let world = app.world_mut();
+    let copies: Vec<Handle<Mesh>> = world
+        .query_filtered::<&Mesh3d, With<TemperatureMesh>>()
+        .iter(world)
+        .map(|mesh| mesh.0.clone())
+        .collect();
+    let meshes = world.resource::<Assets<Mesh>>();
+    for handle in copies {
+        assert!(meshes.get(&handle).expect("mesh asset").attribute(Mesh::ATTRIBUTE_COLOR).is_none());
+    }
+}
@@ -0,0 +1,68 @@
+//! Generates planet after planet headless and checks the meshes, materials and images of the
+//! replaced planets don't pile up in the asset stores
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::events::*;
+use inhabitants::planet::resources::{AssetCounts, PlanetAssets, TerrainSettings};
+
+const PLANETS: u32 = 5;
+
+fn headless_app() -> App {
+    // A small planet, generated in one go, with everything that makes assets for it shown
+    let mut app = base_app(10.0, false, |settings| {
+        settings.display.show_arrows = true;
+        settings.display.show_axis = true;
+    });
+
+    run_frames(&mut app);
+    app.world_mut().write_message(SetActiveView(ViewKind::Wind));
+    run_frames(&mut app);
+    app
+}
+
+fn asset_counts(app: &App) -> AssetCounts {
+    let world = app.world();
+    AssetCounts {
+        meshes: world.resource::<Assets<Mesh>>().len(),
+        materials: world.resource::<Assets<StandardMaterial>>().len(),
+        images: world.resource::<Assets<Image>>().len(),
+    }
+}
+
+fn regenerate(app: &mut App, seed: u32) {
+    app.world_mut().resource_mut::<TerrainSettings>().set_seed(seed);
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(app);
+}
+
+#[test]
+fn test_regenerating_keeps_the_asset_count_of_one_planet() {
+    let mut app = headless_app();
+    let one_planet = asset_counts(&app);
+    assert!(one_planet.meshes > 0 && one_planet.materials > 0);
+
+    for seed in 1..PLANETS {
+        regenerate(&mut app, seed);
+        assert_eq!(asset_counts(&app), one_planet, "after planet {}", seed + 1);
+    }
+}
+
+#[test]
+fn test_the_current_planet_owns_its_assets() {
+    let mut app = headless_app();
+    regenerate(&mut app, 1);
+
+    let world = app.world();
+    let planet = world.resource::<PlanetAssets>().current_counts(
+        world.resource::<Assets<Mesh>>(),
+        world.resource::<Assets<StandardMaterial>>(),
+        world.resource::<Assets<Image>>(),
+    );
+    // Terrain, ocean, arrows, axis and particles, the moons are no part of the planet
+    assert!(planet.meshes > 0);
+    assert!(planet.materials > 1000, "the wind particles have a material each");
+    assert!(planet.meshes <= asset_counts(&app).meshes);
+}
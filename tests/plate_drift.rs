@@ -0,0 +1,58 @@
+//! Steps the plate drift headless and checks that the terrain meshes are updated in place.
+
+mod common;
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::{ContinentViewMesh, TerrainLods};
+use inhabitants::planet::drift::PlateDriftState;
+use std::time::Duration;
+
+const SEED: u32 = 12345678;
+const STEPS: u32 = 20;
+
+fn headless_app() -> App {
+    let mut app = base_app(10.0, false, |settings| {
+        settings.terrain.set_seed(SEED);
+    });
+    // Every frame is long enough for a drift tick
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(250)));
+
+    run_frames(&mut app);
+    app
+}
+
+/// Handle and vertex positions of the finest continent mesh
+fn continent_positions(app: &mut App) -> (AssetId<Mesh>, Vec<[f32; 3]>) {
+    let world = app.world_mut();
+    let handle = world
+        .query_filtered::<&TerrainLods, With<ContinentViewMesh>>()
+        .single(world)
+        .unwrap()
+        .meshes[0]
+        .clone();
+    let mesh = world.resource::<Assets<Mesh>>().get(&handle).unwrap();
+    let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+        panic!("terrain mesh without positions");
+    };
+    (handle.id(), positions.clone())
+}
+
+#[test]
+fn test_drift_steps_move_the_terrain_of_the_same_meshes() {
+    let mut app = headless_app();
+    let (mesh_before, positions_before) = continent_positions(&mut app);
+
+    app.world_mut().resource_mut::<PlateDriftState>().pending_steps = STEPS;
+    while app.world().resource::<PlateDriftState>().pending_steps > 0 {
+        app.update();
+    }
+
+    assert_eq!(app.world().resource::<PlateDriftState>().step, STEPS as u64);
+    let (mesh_after, positions_after) = continent_positions(&mut app);
+    assert_eq!(mesh_after, mesh_before, "the meshes were replaced instead of updated");
+    assert_eq!(positions_after.len(), positions_before.len());
+    assert_ne!(positions_after, positions_before, "the terrain didn't move");
+}
@@ -0,0 +1,105 @@
+//! Drags the mouse over the headless planet generation screen. Whether the pointer is over the
+//! UI comes from egui's hit test of the panels and windows, set here the way the UI pass sets it,
+//! so the planet turns wherever the panels leave room, however wide or short they are.
+
+mod common;
+
+use bevy::input::ButtonState;
+use bevy::input::mouse::{MouseButtonInput, MouseMotion};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::{FRAMES, base_app, run_frames, spawn_camera};
+use inhabitants::planet::components::{PlanetControls, PlanetEntity};
+use inhabitants::planet::ui::systems::PointerOverUi;
+use std::time::Duration;
+
+fn headless_app() -> App {
+    let mut app = base_app(10.0, false, |settings| {
+        // A released drag would keep turning the planet between the checks
+        settings.display.rotation_inertia = false;
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)));
+
+    spawn_camera(&mut app);
+    run_frames(&mut app);
+    app
+}
+
+fn set_pointer_over_ui(app: &mut App, over_ui: bool) {
+    app.world_mut().resource_mut::<PointerOverUi>().0 = over_ui;
+}
+
+fn mouse_button(app: &mut App, state: ButtonState) {
+    app.world_mut().write_message(MouseButtonInput {
+        button: MouseButton::Left,
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+}
+
+/// Move the held mouse sideways for a few frames
+fn move_mouse(app: &mut App) {
+    for _ in 0..FRAMES {
+        app.world_mut().write_message(MouseMotion {
+            delta: Vec2::new(40.0, 0.0),
+        });
+        app.update();
+    }
+}
+
+fn planet_rotation(app: &mut App) -> Quat {
+    let world = app.world_mut();
+    let mut planets = world.query_filtered::<&PlanetControls, With<PlanetEntity>>();
+    planets.single(world).expect("exactly one planet").rotation
+}
+
+/// Press over the view or the UI, drag, then release, returns how far the planet turned
+fn drag(app: &mut App, press_over_ui: bool, move_over_ui: bool) -> f32 {
+    let before = planet_rotation(app);
+    set_pointer_over_ui(app, press_over_ui);
+    mouse_button(app, ButtonState::Pressed);
+    set_pointer_over_ui(app, move_over_ui);
+    move_mouse(app);
+    mouse_button(app, ButtonState::Released);
+    set_pointer_over_ui(app, false);
+    run_frames(app);
+    // `Quat::angle_between` goes through an approximate acos, off by about 1e-3 for an unchanged
+    // rotation. The sine of the half angle is exact for small turns.
+    let turn = before.inverse() * planet_rotation(app);
+    2.0 * turn.xyz().length().min(1.0).asin()
+}
+
+#[test]
+fn test_dragging_the_view_turns_the_planet() {
+    let mut app = headless_app();
+
+    assert!(drag(&mut app, false, false) > 0.01, "the drag didn't turn the planet");
+}
+
+#[test]
+fn test_dragging_over_the_panel_leaves_the_planet_alone() {
+    let mut app = headless_app();
+
+    assert!(drag(&mut app, true, true) < 1e-6, "a drag on the panel turned the planet");
+}
+
+#[test]
+fn test_area_a_collapsed_panel_gives_up_turns_the_planet() {
+    let mut app = headless_app();
+    // Under the expanded panel
+    assert!(drag(&mut app, true, true) < 1e-6);
+
+    // All sections collapsed, the same spot is the 3D view now and egui doesn't claim it
+    assert!(drag(&mut app, false, false) > 0.01, "the exposed area didn't turn the planet");
+}
+
+#[test]
+fn test_drag_belongs_to_where_it_started() {
+    let mut app = headless_app();
+
+    // Started on the view and carried over the panel, the planet keeps following
+    assert!(drag(&mut app, false, true) > 0.01, "the drag stopped over the panel");
+    // Started on a slider and carried over the view, the planet stays put
+    assert!(drag(&mut app, true, false) < 1e-6, "a slider drag turned the planet");
+}
@@ -0,0 +1,111 @@
+//! Plays the precipitation year headless on the precipitation tab and checks the overlay colors
+//! follow the phase, are written at most `MAX_SEASON_COLOR_UPDATES_PER_SECOND` times a second and
+//! go back to the annual colors when the year is hidden again.
+
+mod common;
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::OverlayMesh;
+use inhabitants::planet::events::*;
+use inhabitants::planet::precipitation::systems::{PrecipitationMesh, PrecipitationSeasons};
+use inhabitants::planet::precipitation::{MAX_SEASON_COLOR_UPDATES_PER_SECOND, SeasonPlayback};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_millis(10);
+
+fn headless_app() -> App {
+    let mut app = base_app(10.0, false, |_| {});
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+
+    run_frames(&mut app);
+    app.world_mut().write_message(SetActiveView(ViewKind::Precipitations));
+    run_frames(&mut app);
+    app
+}
+
+/// Vertex colors of every precipitation overlay copy
+fn overlay_colors(app: &mut App) -> Vec<[f32; 4]> {
+    let world = app.world_mut();
+    let handles: Vec<Handle<Mesh>> = world
+        .query_filtered::<&Mesh3d, (With<PrecipitationMesh>, With<OverlayMesh>)>()
+        .iter(world)
+        .map(|mesh| mesh.0.clone())
+        .collect();
+    let meshes = world.resource::<Assets<Mesh>>();
+    handles
+        .iter()
+        .filter_map(|handle| match meshes.get(handle)?.attribute(Mesh::ATTRIBUTE_COLOR)? {
+            VertexAttributeValues::Float32x4(colors) => Some(colors),
+            _ => None,
+        })
+        .flat_map(|colors| colors.iter().copied())
+        .collect()
+}
+
+/// Move to `phase` and run the frames of two color updates, the first one may be throttled
+fn set_phase(app: &mut App, phase: f32) {
+    app.world_mut().resource_mut::<SeasonPlayback>().phase = phase;
+    let frames = (2.0 / MAX_SEASON_COLOR_UPDATES_PER_SECOND / FRAME.as_secs_f32()).ceil() as usize;
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+#[test]
+fn test_overlay_follows_the_phase_and_returns_to_the_annual_colors() {
+    let mut app = headless_app();
+    let annual = overlay_colors(&mut app);
+    assert!(!annual.is_empty(), "no precipitation overlay");
+
+    app.world_mut().resource_mut::<SeasonPlayback>().enabled = true;
+    // The maps of the year are built by a climate job in the background
+    run_frames(&mut app);
+    set_phase(&mut app, 0.25);
+    assert!(app.world().resource::<PrecipitationSeasons>().is_built());
+    let northern_summer = overlay_colors(&mut app);
+    set_phase(&mut app, 0.75);
+    let southern_summer = overlay_colors(&mut app);
+
+    assert_eq!(northern_summer.len(), annual.len());
+    assert_ne!(northern_summer, annual);
+    assert_ne!(northern_summer, southern_summer);
+
+    app.world_mut().resource_mut::<SeasonPlayback>().enabled = false;
+    run_frames(&mut app);
+    assert_eq!(overlay_colors(&mut app), annual);
+}
+
+#[test]
+fn test_playing_recolors_at_most_ten_times_a_second() {
+    let mut app = headless_app();
+    // One second of frames
+    let frames = (1.0 / FRAME.as_secs_f32()).round() as usize;
+    // Rebuilt overlays are recolored right away, wait for the debounced climate rebuilds
+    app.world_mut().resource_mut::<SeasonPlayback>().enabled = true;
+    run_frames(&mut app);
+    for _ in 0..frames {
+        app.update();
+    }
+
+    let mut playback = app.world_mut().resource_mut::<SeasonPlayback>();
+    playback.playing = true;
+    playback.years_per_minute = 30.0;
+    let mut shown = HashSet::new();
+    for _ in 0..frames {
+        app.update();
+        let phase = app.world().resource::<PrecipitationSeasons>().shown_phase();
+        shown.insert(phase.map(f32::to_bits));
+    }
+
+    let updates = shown.len();
+    assert!(updates > 1, "the overlay never changed");
+    assert!(
+        updates <= MAX_SEASON_COLOR_UPDATES_PER_SECOND as usize + 1,
+        "{updates} different phases shown in a second"
+    );
+}
+
@@ -0,0 +1,108 @@
+//! Pins probe points on a headless planet and checks they survive a regeneration only while
+//! the view is kept, markers included
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::PlanetEntity;
+use inhabitants::planet::events::GeneratePlanetEvent;
+use inhabitants::planet::picking::SurfacePick;
+use inhabitants::planet::probe::{ProbeMarker, ProbePin, SurfaceProbe};
+use inhabitants::planet::resources::{DisplaySettings, TerrainSettings};
+use planetgen::prelude::SurfaceHit;
+
+fn headless_app() -> App {
+    // A small planet, generated in one go
+    let mut app = base_app(10.0, false, |_| {});
+
+    run_frames(&mut app);
+    app
+}
+
+/// Click the planet in `direction` with `key` held
+fn click(app: &mut App, direction: Vec3, key: KeyCode) {
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.world_mut().resource_mut::<SurfacePick>().clicked = Some(SurfaceHit {
+        position: direction * 10.0,
+        direction,
+        face: 0,
+        cell: (0, 0),
+    });
+    app.update();
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().release(key);
+    run_frames(app);
+}
+
+/// Pins of the markers, each checked to sit on the current planet
+fn markers(app: &mut App) -> Vec<ProbePin> {
+    let world = app.world_mut();
+    let planet = world
+        .query_filtered::<Entity, With<PlanetEntity>>()
+        .single(world)
+        .expect("exactly one planet");
+    let mut markers = world.query::<(&ProbeMarker, &ChildOf)>();
+    let mut pins: Vec<ProbePin> = markers
+        .iter(world)
+        .map(|(marker, parent)| {
+            assert_eq!(parent.parent(), planet);
+            marker.0
+        })
+        .collect();
+    pins.sort_by_key(|pin| *pin as usize);
+    pins
+}
+
+fn pin_both(app: &mut App) {
+    click(app, Vec3::X, KeyCode::ShiftLeft);
+    click(app, Vec3::new(0.0, 0.6, 0.8), KeyCode::ControlLeft);
+}
+
+#[test]
+fn test_modifier_clicks_pin_a_and_b() {
+    let mut app = headless_app();
+    pin_both(&mut app);
+
+    let probe = app.world().resource::<SurfaceProbe>();
+    assert_eq!(probe.a.map(|reading| reading.direction), Some(Vec3::X));
+    assert_eq!(probe.b.map(|reading| reading.direction), Some(Vec3::new(0.0, 0.6, 0.8)));
+    assert!(probe.a.unwrap().temperature.is_some(), "the climate maps are read");
+    assert_eq!(markers(&mut app), vec![ProbePin::A, ProbePin::B]);
+
+    // A plain click only probes
+    click(&mut app, Vec3::Z, KeyCode::KeyQ);
+    let probe = app.world().resource::<SurfaceProbe>();
+    assert_eq!(probe.current.map(|reading| reading.direction), Some(Vec3::Z));
+    assert_eq!(probe.a.map(|reading| reading.direction), Some(Vec3::X));
+}
+
+#[test]
+fn test_pins_stay_on_a_new_planet_with_the_view_kept() {
+    let mut app = headless_app();
+    pin_both(&mut app);
+
+    app.world_mut().resource_mut::<TerrainSettings>().set_seed(7);
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    let probe = app.world().resource::<SurfaceProbe>();
+    assert_eq!(probe.a.map(|reading| reading.direction), Some(Vec3::X));
+    assert!(probe.b.is_some());
+    assert_eq!(markers(&mut app), vec![ProbePin::A, ProbePin::B]);
+}
+
+#[test]
+fn test_pins_are_cleared_on_a_new_planet_without_keeping_the_view() {
+    let mut app = headless_app();
+    app.world_mut().resource_mut::<DisplaySettings>().keep_view = false;
+    pin_both(&mut app);
+
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    assert_eq!(*app.world().resource::<SurfaceProbe>(), SurfaceProbe {
+        sampled_radius: Some(10.0),
+        ..default()
+    });
+    assert!(markers(&mut app).is_empty());
+}
@@ -0,0 +1,169 @@
+//! Regenerates the planet headless in ways that could leave more than one planet alive, and
+//! checks that exactly one planet with its children is left each time. Re-rolling the terrain
+//! or the plates alone keeps the other.
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::{
+    ContinentViewMesh, OceanEntity, PlanetControls, PlanetEntity, PlateViewMesh,
+};
+use inhabitants::planet::events::{GeneratePlanetEvent, RerollPlanetEvent, SetCameraPositionEvent};
+use inhabitants::planet::resources::{CurrentPlanetData, DisplaySettings, TerrainSettings};
+use planetgen::prelude::PlateLayout;
+
+fn headless_app() -> App {
+    // A small planet, generated in one go
+    let mut app = base_app(10.0, false, |_| {});
+
+    run_frames(&mut app);
+    app
+}
+
+fn count<F: bevy::ecs::query::QueryFilter>(app: &mut App) -> usize {
+    let world = app.world_mut();
+    world.query_filtered::<Entity, F>().iter(world).count()
+}
+
+fn assert_one_planet(app: &mut App) {
+    assert_eq!(count::<With<PlanetEntity>>(app), 1, "planets");
+    assert_eq!(count::<With<ContinentViewMesh>>(app), 1, "continent meshes");
+    assert_eq!(count::<With<PlateViewMesh>>(app), 1, "plate meshes");
+    assert_eq!(count::<With<OceanEntity>>(app), 1, "oceans");
+}
+
+#[test]
+fn test_two_generate_requests_in_one_frame_make_one_planet() {
+    let mut app = headless_app();
+    assert_one_planet(&mut app);
+
+    app.world_mut().write_message(GeneratePlanetEvent);
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    assert_one_planet(&mut app);
+}
+
+#[test]
+fn test_a_leftover_planet_makes_way_for_the_newest() {
+    let mut app = headless_app();
+    let old_planet = {
+        let world = app.world_mut();
+        let mut planets = world.query_filtered::<Entity, With<PlanetEntity>>();
+        planets.single(world).expect("exactly one planet")
+    };
+
+    let newest = app
+        .world_mut()
+        .spawn((Transform::default(), PlanetEntity))
+        .id();
+    run_frames(&mut app);
+
+    let world = app.world_mut();
+    let mut planets = world.query_filtered::<Entity, With<PlanetEntity>>();
+    assert_eq!(planets.single(world).ok(), Some(newest));
+    assert!(app.world().get_entity(old_planet).is_err());
+    // The children went with the old planet
+    assert_eq!(count::<With<OceanEntity>>(&mut app), 0);
+}
+
+/// Camera moves requested since the test started counting
+#[derive(Resource, Default)]
+struct CameraMoves(usize);
+
+fn count_camera_moves(mut events: MessageReader<SetCameraPositionEvent>, mut moves: ResMut<CameraMoves>) {
+    moves.0 += events.read().count();
+}
+
+/// Zoom and rotation of the planet
+fn view(app: &mut App) -> (f32, f32, Quat) {
+    let world = app.world_mut();
+    let mut controls = world.query::<&PlanetControls>();
+    let controls = controls.single(world).expect("exactly one planet");
+    (controls.zoom, controls.max_zoom, controls.rotation)
+}
+
+/// Zoom in and turn the planet the way the user would, and count the camera moves from here on
+fn change_view(app: &mut App) {
+    app.init_resource::<CameraMoves>().add_systems(Update, count_camera_moves);
+    // Moves of the first planet may still be queued
+    app.update();
+    app.world_mut().resource_mut::<CameraMoves>().0 = 0;
+    let world = app.world_mut();
+    let mut controls = world.query::<&mut PlanetControls>();
+    for mut controls in controls.iter_mut(world) {
+        controls.zoom = 17.0;
+        controls.rotation = Quat::from_rotation_y(1.0);
+    }
+}
+
+#[test]
+fn test_a_new_planet_keeps_the_view() {
+    let mut app = headless_app();
+    change_view(&mut app);
+
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    let (zoom, _, rotation) = view(&mut app);
+    assert_eq!(zoom, 17.0);
+    assert!(rotation.abs_diff_eq(Quat::from_rotation_y(1.0), 1e-6));
+    assert_eq!(app.world().resource::<CameraMoves>().0, 0);
+}
+
+#[test]
+fn test_a_new_planet_is_framed_again_without_keeping_the_view() {
+    let mut app = headless_app();
+    app.world_mut().resource_mut::<DisplaySettings>().keep_view = false;
+    change_view(&mut app);
+
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    let (zoom, max_zoom, rotation) = view(&mut app);
+    assert_eq!(zoom, max_zoom);
+    // The rotation is kept either way
+    assert!(rotation.abs_diff_eq(Quat::from_rotation_y(1.0), 1e-6));
+    assert_eq!(app.world().resource::<CameraMoves>().0, 1);
+}
+
+/// Plate layout and a few surface heights of the current planet
+fn plates_and_terrain(app: &App) -> (PlateLayout, Vec<f32>) {
+    let planet = app.world().resource::<CurrentPlanetData>().planet_data.as_ref().expect("no planet");
+    let heights = [Vec3::X, Vec3::Y, Vec3::Z, Vec3::NEG_X, Vec3::NEG_Y, Vec3::new(1.0, 1.0, 1.0).normalize()]
+        .map(|direction| planet.sample_height(direction))
+        .to_vec();
+    (planet.extract_plate_layout(), heights)
+}
+
+#[test]
+fn test_a_terrain_reroll_keeps_the_plates() {
+    let mut app = headless_app();
+    let (plates_before, terrain_before) = plates_and_terrain(&app);
+    let plate_seed = app.world().resource::<TerrainSettings>().plate_seed;
+
+    app.world_mut().write_message(RerollPlanetEvent::Terrain);
+    run_frames(&mut app);
+
+    assert_one_planet(&mut app);
+    let (plates_after, terrain_after) = plates_and_terrain(&app);
+    assert_eq!(plates_after, plates_before);
+    assert_ne!(terrain_after, terrain_before);
+    assert_eq!(app.world().resource::<TerrainSettings>().plate_seed, plate_seed);
+}
+
+#[test]
+fn test_a_plate_reroll_keeps_the_terrain_seed() {
+    let mut app = headless_app();
+    let (plates_before, _) = plates_and_terrain(&app);
+    let terrain_seed = app.world().resource::<TerrainSettings>().terrain_seed;
+
+    app.world_mut().write_message(RerollPlanetEvent::Plates);
+    run_frames(&mut app);
+
+    assert_one_planet(&mut app);
+    let (plates_after, _) = plates_and_terrain(&app);
+    assert_ne!(plates_after, plates_before);
+    assert_eq!(app.world().resource::<TerrainSettings>().terrain_seed, terrain_seed);
+}
@@ -0,0 +1,104 @@
+//! Generates a planet headless and checks that everything drawing water agrees on where the
+//! sea level of the planet is.
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::OceanEntity;
+use inhabitants::planet::resources::{CurrentPlanetData, DisplaySettings, TerrainSettings, WaterLevel, WindSettings};
+use inhabitants::planet::systems::ocean_config;
+use planetgen::prelude::PlanetData;
+
+const CONTINENT_THRESHOLD: f32 = 0.15;
+
+fn headless_app() -> App {
+    // A small round planet, generated in one go
+    let mut app = base_app(10.0, false, |settings| {
+        settings.terrain.oblateness = 0.0;
+        settings.terrain.continent_threshold = CONTINENT_THRESHOLD;
+    });
+
+    run_frames(&mut app);
+    run_frames(&mut app);
+    app
+}
+
+fn planet(app: &App) -> &PlanetData {
+    let current_planet_data = app.world().resource::<CurrentPlanetData>();
+    current_planet_data
+        .planet_data
+        .as_ref()
+        .expect("a generated planet")
+}
+
+fn ocean_mesh(app: &mut App) -> Handle<Mesh> {
+    let world = app.world_mut();
+    let mut oceans = world.query_filtered::<&Mesh3d, With<OceanEntity>>();
+    oceans.single(world).expect("exactly one ocean").0.clone()
+}
+
+/// `count` directions spread evenly over the sphere
+fn fibonacci_directions(count: usize) -> impl Iterator<Item = Vec3> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+    (0..count).map(move |i| {
+        let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+        let ring = (1.0 - y * y).sqrt();
+        let angle = golden_angle * i as f32;
+        Vec3::new(ring * angle.cos(), y, ring * angle.sin())
+    })
+}
+
+#[test]
+fn test_generated_planet_remembers_its_threshold() {
+    let mut app = headless_app();
+    assert_eq!(planet(&app).sea_level(), CONTINENT_THRESHOLD);
+    assert_eq!(
+        *app.world().resource::<WaterLevel>(),
+        WaterLevel(Some(CONTINENT_THRESHOLD))
+    );
+    let ocean = ocean_mesh(&mut app);
+
+    // The slider shapes the next planet, the water of this one stays where it is
+    app.world_mut()
+        .resource_mut::<TerrainSettings>()
+        .continent_threshold = 0.4;
+    run_frames(&mut app);
+
+    assert_eq!(planet(&app).sea_level(), CONTINENT_THRESHOLD);
+    assert_eq!(
+        *app.world().resource::<WaterLevel>(),
+        WaterLevel(Some(CONTINENT_THRESHOLD))
+    );
+    assert_eq!(ocean_mesh(&mut app), ocean);
+}
+
+/// Precipitation counts a texel as ocean by `PlanetData::is_ocean`, the ocean mesh covers the
+/// terrain below its calm surface. Both have to draw the same coastline.
+#[test]
+fn test_precipitation_ocean_mask_matches_the_ocean_extent() {
+    let app = headless_app();
+    let planet = planet(&app);
+    let world = app.world();
+    let config = ocean_config((world.resource::<DisplaySettings>(), world.resource::<WindSettings>()), planet);
+
+    let (mut ocean, mut land) = (0, 0);
+    for direction in fibonacci_directions(4000) {
+        let terrain_radius = planet.surface_radius(direction) + planet.sample_height(direction);
+        let under_water = ocean::sea_radius(&config, direction) > terrain_radius;
+        assert_eq!(
+            planet.is_ocean(direction),
+            under_water,
+            "towards {direction}"
+        );
+        if under_water {
+            ocean += 1;
+        } else {
+            land += 1;
+        }
+    }
+    assert!(
+        ocean > 0 && land > 0,
+        "{ocean} ocean and {land} land samples"
+    );
+}
@@ -0,0 +1,103 @@
+//! Runs the planet generation screen headless, moves single sliders and checks that only the
+//! maps and meshes depending on them are rebuilt.
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::coastline::CoastlineGizmo;
+use inhabitants::planet::components::OceanEntity;
+use inhabitants::planet::resources::{ClimateSettings, DisplaySettings, TerrainSettings, WindSettings};
+use inhabitants::planet::temperature::systems::TemperatureCubeMap;
+use inhabitants::planet::wind::systems::WindCubeMap;
+use planetgen::prelude::Palette;
+
+fn headless_app() -> App {
+    // A small planet, generated in one go
+    let mut app = base_app(10.0, false, |_| {});
+
+    // Generate the planet, then let the wind and climate maps built from it settle
+    run_frames(&mut app);
+    run_frames(&mut app);
+    app
+}
+
+fn ocean_mesh(app: &mut App) -> Handle<Mesh> {
+    let world = app.world_mut();
+    let mut oceans = world.query_filtered::<&Mesh3d, With<OceanEntity>>();
+    oceans.single(world).expect("exactly one ocean").0.clone()
+}
+
+fn coastline_lines(app: &mut App) -> Vec<Entity> {
+    let world = app.world_mut();
+    let mut lines = world.query_filtered::<Entity, With<CoastlineGizmo>>();
+    lines.iter(world).collect()
+}
+
+/// When the settings resources a climate slider doesn't belong to last changed
+fn untouched_settings_changes(app: &App) -> [bevy::ecs::change_detection::Tick; 3] {
+    let world = app.world();
+    [
+        world.resource_ref::<TerrainSettings>().last_changed(),
+        world.resource_ref::<WindSettings>().last_changed(),
+        world.resource_ref::<DisplaySettings>().last_changed(),
+    ]
+}
+
+#[test]
+fn test_temperature_slider_leaves_the_wind_and_the_ocean_alone() {
+    let mut app = headless_app();
+    let wind_built = app.world().resource_ref::<WindCubeMap>().last_changed();
+    let temperature_built = app.world().resource_ref::<TemperatureCubeMap>().last_changed();
+    let ocean = ocean_mesh(&mut app);
+    let untouched_settings = untouched_settings_changes(&app);
+
+    app.world_mut()
+        .resource_mut::<ClimateSettings>()
+        .temperature_equator_temp += 5.0;
+    run_frames(&mut app);
+
+    assert_ne!(
+        app.world().resource_ref::<TemperatureCubeMap>().last_changed(),
+        temperature_built,
+        "the temperature map follows the slider"
+    );
+    assert_eq!(app.world().resource_ref::<WindCubeMap>().last_changed(), wind_built);
+    assert_eq!(ocean_mesh(&mut app), ocean);
+    assert_eq!(untouched_settings_changes(&app), untouched_settings, "only the climate settings changed");
+}
+
+#[test]
+fn test_palette_recolors_the_temperature_map_without_rebuilding_it() {
+    let mut app = headless_app();
+    let wind_built = app.world().resource_ref::<WindCubeMap>().last_changed();
+    let colors_before = app.world().resource::<TemperatureCubeMap>().sample_color(Vec3::X);
+    let temperature = app.world().resource::<TemperatureCubeMap>().sample_temperature(Vec3::X);
+
+    app.world_mut().resource_mut::<DisplaySettings>().palette = Palette::Viridis;
+    run_frames(&mut app);
+
+    let cubemap = app.world().resource::<TemperatureCubeMap>();
+    assert_ne!(cubemap.sample_color(Vec3::X), colors_before, "the colors follow the palette");
+    assert_eq!(cubemap.sample_temperature(Vec3::X), temperature);
+    assert_eq!(app.world().resource_ref::<WindCubeMap>().last_changed(), wind_built);
+}
+
+#[test]
+fn test_coastline_is_only_redrawn_when_it_is_toggled() {
+    let mut app = headless_app();
+    app.world_mut().resource_mut::<DisplaySettings>().show_coastline = true;
+    run_frames(&mut app);
+    let lines = coastline_lines(&mut app);
+    assert!(!lines.is_empty(), "the toggle draws the coastline");
+
+    let mut display = app.world_mut().resource_mut::<DisplaySettings>();
+    display.show_axis = !display.show_axis;
+    display.camera_offset += 1.0;
+    run_frames(&mut app);
+    assert_eq!(coastline_lines(&mut app), lines);
+
+    app.world_mut().resource_mut::<DisplaySettings>().show_coastline = false;
+    run_frames(&mut app);
+    assert!(coastline_lines(&mut app).is_empty());
+}
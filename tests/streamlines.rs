@@ -0,0 +1,72 @@
+//! Traces the wind of a headless planet and checks the lines stay until cleared or a new
+//! wind comes
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::planet::components::PlanetEntity;
+use inhabitants::planet::events::GeneratePlanetEvent;
+use inhabitants::planet::resources::{TerrainSettings, WindSettings};
+use inhabitants::planet::streamlines::{StreamlineMesh, Streamlines};
+
+fn headless_app() -> App {
+    // A small planet, generated in one go
+    let mut app = base_app(10.0, false, |settings| {
+        settings.terrain.set_seed(5);
+    });
+
+    run_frames(&mut app);
+    app
+}
+
+/// Lines on the planet, each checked to be a child of it
+fn line_count(app: &mut App) -> usize {
+    let world = app.world_mut();
+    let planet = world
+        .query_filtered::<Entity, With<PlanetEntity>>()
+        .single(world)
+        .expect("exactly one planet");
+    let mut lines = world.query_filtered::<&ChildOf, With<StreamlineMesh>>();
+    lines.iter(world).inspect(|parent| assert_eq!(parent.parent(), planet)).count()
+}
+
+fn trace(app: &mut App, seeds: &[Vec3]) {
+    let mut streamlines = app.world_mut().resource_mut::<Streamlines>();
+    for &seed in seeds {
+        streamlines.add(seed);
+    }
+    run_frames(app);
+}
+
+#[test]
+fn test_streamlines_stay_until_cleared() {
+    let mut app = headless_app();
+    assert_eq!(line_count(&mut app), 0);
+
+    trace(&mut app, &[Vec3::new(1.0, 0.4, 0.0), Vec3::new(-0.3, -0.5, 0.8)]);
+    assert_eq!(line_count(&mut app), 2);
+
+    // A longer arc rebuilds the same lines
+    app.world_mut().resource_mut::<WindSettings>().streamline_arc_length += 20.0;
+    run_frames(&mut app);
+    assert_eq!(line_count(&mut app), 2);
+
+    app.world_mut().resource_mut::<Streamlines>().clear();
+    run_frames(&mut app);
+    assert_eq!(line_count(&mut app), 0);
+}
+
+#[test]
+fn test_a_new_wind_clears_the_streamlines() {
+    let mut app = headless_app();
+    trace(&mut app, &[Vec3::new(0.2, 0.3, 1.0)]);
+    assert_eq!(line_count(&mut app), 1);
+
+    app.world_mut().resource_mut::<TerrainSettings>().set_seed(6);
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    assert!(app.world().resource::<Streamlines>().seeds.is_empty());
+    assert_eq!(line_count(&mut app), 0);
+}
@@ -0,0 +1,341 @@
+//! Runs the planet generation screen headless and switches through the view tabs the way the
+//! tab bar does, checking which overlay meshes exist and what is visible after each switch.
+
+mod common;
+
+use bevy::prelude::*;
+use common::{base_app, run_frames};
+use inhabitants::launch::LaunchOptions;
+use inhabitants::planet::components::{
+    ContinentViewMesh, OceanEntity, OverlayMesh, PlanetControls, PlanetEntity,
+};
+use inhabitants::planet::crust_age::systems::CrustAgeMesh;
+use inhabitants::planet::events::*;
+use inhabitants::planet::precipitation::systems::PrecipitationMesh;
+use inhabitants::planet::resources::{DisplaySettings, OverlayHint, OverlayState, WindSettings};
+use inhabitants::planet::temperature::systems::{TemperatureCubeMap, TemperatureMesh};
+use inhabitants::planet::view::logic::NO_PLANET_HINT;
+use inhabitants::planet::wind::WindDisplay;
+use inhabitants::planet::wind::systems::{VerticalAirMesh, WindParticle};
+use inhabitants::planet::wind_glyphs::WindGlyphMesh;
+
+fn headless_app() -> App {
+    // A small planet, generated in one go
+    let mut app = base_app(10.0, false, |_| {});
+    // The first frame enters the planet generation state, which generates the initial planet
+    run_frames(&mut app);
+    app
+}
+
+/// The planet generation screen before anything generated the first planet. Launching with
+/// `--autogenerate` skips the usual one, and without the launch plugin nothing generates it.
+fn app_without_planet() -> App {
+    let mut app = base_app(10.0, false, |_| {});
+    app.insert_resource(LaunchOptions {
+        autogenerate: true,
+        ..default()
+    });
+    run_frames(&mut app);
+    app
+}
+
+/// Same message the tab bar sends when the user clicks a tab
+fn switch_tab(app: &mut App, view: ViewKind) {
+    app.world_mut().write_message(SetActiveView(view));
+    run_frames(app);
+}
+
+fn planet(app: &mut App) -> Entity {
+    let world = app.world_mut();
+    let mut planets = world.query_filtered::<Entity, With<PlanetEntity>>();
+    planets.single(world).expect("exactly one planet")
+}
+
+/// Parent of every entity with the marker `M`
+fn parents<M: Component>(app: &mut App) -> Vec<Entity> {
+    let world = app.world_mut();
+    let mut query = world.query_filtered::<&ChildOf, With<M>>();
+    query.iter(world).map(ChildOf::parent).collect()
+}
+
+/// Parent and visibility of every entity with the marker `M`
+fn marked<M: Component>(app: &mut App) -> Vec<(Entity, Visibility)> {
+    let world = app.world_mut();
+    let mut query = world.query_filtered::<(&ChildOf, &Visibility), With<M>>();
+    query.iter(world).map(|(child_of, visibility)| (child_of.parent(), *visibility)).collect()
+}
+
+/// Every entity with the marker `M` hangs off the planet and has the expected visibility
+fn assert_children_of_planet<M: Component>(app: &mut App, expected: Visibility) {
+    let planet = planet(app);
+    let entities = marked::<M>(app);
+    assert!(!entities.is_empty(), "no {} entities", std::any::type_name::<M>());
+    for (parent, visibility) in entities {
+        assert_eq!(parent, planet, "{} is not a child of the planet", std::any::type_name::<M>());
+        assert_eq!(visibility, expected, "visibility of {}", std::any::type_name::<M>());
+    }
+}
+
+#[test]
+fn test_planet_is_generated_on_start_and_on_event() {
+    let mut app = headless_app();
+    let first = planet(&mut app);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Visible);
+
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    // The old planet is replaced, not kept next to the new one
+    assert_ne!(planet(&mut app), first);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+}
+
+#[test]
+fn test_temperature_tab_waits_for_the_first_planet_with_a_hint() {
+    let mut app = app_without_planet();
+
+    switch_tab(&mut app, ViewKind::Temperature);
+    assert!(app.world().get_resource::<TemperatureCubeMap>().is_none());
+    assert!(marked::<TemperatureMesh>(&mut app).is_empty());
+    assert_eq!(app.world().resource::<OverlayHint>().0, Some(NO_PLANET_HINT));
+
+    // The climate maps are built for the first planet and the overlay shows up on it
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+    assert!(app.world().get_resource::<TemperatureCubeMap>().is_some());
+    assert_children_of_planet::<TemperatureMesh>(&mut app, Visibility::Visible);
+    assert_eq!(app.world().resource::<OverlayHint>().0, None);
+}
+
+#[test]
+fn test_temperature_tab_shows_overlay_and_hides_it_again() {
+    let mut app = headless_app();
+
+    switch_tab(&mut app, ViewKind::Temperature);
+    assert_children_of_planet::<TemperatureMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Hidden);
+
+    switch_tab(&mut app, ViewKind::Continent);
+    assert_children_of_planet::<TemperatureMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Visible);
+}
+
+#[test]
+fn test_two_switches_in_one_frame_leave_only_the_last_view_active() {
+    let mut app = headless_app();
+    switch_tab(&mut app, ViewKind::Temperature);
+
+    let world = app.world_mut();
+    world.write_message(SetActiveView(ViewKind::Precipitations));
+    world.write_message(SetActiveView(ViewKind::Wind));
+    run_frames(&mut app);
+
+    assert_eq!(app.world().resource::<OverlayState>().active, ViewKind::Wind);
+    let settings = app.world().resource::<DisplaySettings>();
+    assert!(settings.show_wind);
+    assert!(!settings.show_temperature);
+    assert!(!settings.show_precipitation);
+    assert_children_of_planet::<TemperatureMesh>(&mut app, Visibility::Hidden);
+}
+
+#[test]
+fn test_precipitation_tab_shows_overlay_and_hides_it_again() {
+    let mut app = headless_app();
+
+    switch_tab(&mut app, ViewKind::Precipitations);
+    assert_children_of_planet::<PrecipitationMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Hidden);
+
+    switch_tab(&mut app, ViewKind::Continent);
+    assert_children_of_planet::<PrecipitationMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+}
+
+#[test]
+fn test_crust_age_tab_shows_overlay_and_rebuilds_it_for_a_new_planet() {
+    let mut app = headless_app();
+
+    switch_tab(&mut app, ViewKind::CrustAge);
+    assert_children_of_planet::<CrustAgeMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Hidden);
+
+    // The overlay of the old planet went with it, the new one gets its own
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+    assert_children_of_planet::<CrustAgeMesh>(&mut app, Visibility::Visible);
+
+    switch_tab(&mut app, ViewKind::Continent);
+    assert_children_of_planet::<CrustAgeMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+}
+
+/// The overlay with the marker `M` of the open tab `view` comes back on a new planet
+fn assert_rebuilt_for_a_new_planet<M: Component>(view: ViewKind) {
+    let mut app = headless_app();
+    switch_tab(&mut app, view);
+
+    app.world_mut().write_message(GeneratePlanetEvent);
+    run_frames(&mut app);
+
+    // Still on the tab, with the overlay on the new planet only
+    assert_eq!(app.world().resource::<OverlayState>().active, view);
+    assert_children_of_planet::<M>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Hidden);
+}
+
+#[test]
+fn test_open_climate_tab_is_rebuilt_for_a_new_planet() {
+    assert_rebuilt_for_a_new_planet::<TemperatureMesh>(ViewKind::Temperature);
+    assert_rebuilt_for_a_new_planet::<PrecipitationMesh>(ViewKind::Precipitations);
+}
+
+#[test]
+fn test_wind_tab_spawns_particles_and_removes_them_again() {
+    let mut app = headless_app();
+    assert!(parents::<WindParticle>(&mut app).is_empty(), "particles outside the wind tab");
+
+    switch_tab(&mut app, ViewKind::Wind);
+    let planet = planet(&mut app);
+    let particles = parents::<WindParticle>(&mut app);
+    assert!(!particles.is_empty(), "no wind particles");
+    assert!(particles.iter().all(|&parent| parent == planet));
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Visible);
+
+    switch_tab(&mut app, ViewKind::Continent);
+    assert!(parents::<WindParticle>(&mut app).is_empty());
+}
+
+#[test]
+fn test_wind_glyphs_replace_the_particles_on_the_wind_tab() {
+    let mut app = headless_app();
+    switch_tab(&mut app, ViewKind::Wind);
+    assert!(!parents::<WindParticle>(&mut app).is_empty());
+
+    app.world_mut().resource_mut::<WindSettings>().wind_display = WindDisplay::Glyphs;
+    run_frames(&mut app);
+    assert!(parents::<WindParticle>(&mut app).is_empty(), "particles next to the glyphs");
+    assert_children_of_planet::<WindGlyphMesh>(&mut app, Visibility::Visible);
+
+    // Kept for the next visit, only hidden
+    switch_tab(&mut app, ViewKind::Continent);
+    assert_children_of_planet::<WindGlyphMesh>(&mut app, Visibility::Hidden);
+    switch_tab(&mut app, ViewKind::Wind);
+    assert_children_of_planet::<WindGlyphMesh>(&mut app, Visibility::Visible);
+    assert!(parents::<WindParticle>(&mut app).is_empty());
+
+    app.world_mut().resource_mut::<WindSettings>().wind_display = WindDisplay::Particles;
+    run_frames(&mut app);
+    assert!(parents::<WindGlyphMesh>(&mut app).is_empty());
+    assert!(!parents::<WindParticle>(&mut app).is_empty());
+}
+
+#[test]
+fn test_vertical_air_overlay_replaces_planet_on_wind_tab() {
+    let mut app = headless_app();
+    switch_tab(&mut app, ViewKind::Wind);
+
+    app.world_mut().resource_mut::<DisplaySettings>().show_vertical_air = true;
+    run_frames(&mut app);
+    assert_children_of_planet::<VerticalAirMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Hidden);
+
+    // Unchecking the box on the wind tab brings the planet back
+    app.world_mut().resource_mut::<DisplaySettings>().show_vertical_air = false;
+    run_frames(&mut app);
+    assert!(parents::<VerticalAirMesh>(&mut app).is_empty());
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Visible);
+
+    // Leaving the wind tab with the box checked removes the overlay too
+    app.world_mut().resource_mut::<DisplaySettings>().show_vertical_air = true;
+    run_frames(&mut app);
+    assert_children_of_planet::<VerticalAirMesh>(&mut app, Visibility::Visible);
+    switch_tab(&mut app, ViewKind::Continent);
+    assert!(parents::<VerticalAirMesh>(&mut app).is_empty());
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+}
+
+#[test]
+fn test_originals_come_back_after_vertical_air_then_temperature() {
+    let mut app = headless_app();
+    switch_tab(&mut app, ViewKind::Wind);
+    app.world_mut().resource_mut::<DisplaySettings>().show_vertical_air = true;
+    run_frames(&mut app);
+    assert_children_of_planet::<VerticalAirMesh>(&mut app, Visibility::Visible);
+
+    switch_tab(&mut app, ViewKind::Temperature);
+    assert_children_of_planet::<TemperatureMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Hidden);
+
+    // Back on the wind tab with the box still checked the overlay is there again
+    switch_tab(&mut app, ViewKind::Wind);
+    assert_children_of_planet::<VerticalAirMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<TemperatureMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Hidden);
+
+    // Unchecking the box brings the planet back
+    app.world_mut().resource_mut::<DisplaySettings>().show_vertical_air = false;
+    run_frames(&mut app);
+    assert!(parents::<VerticalAirMesh>(&mut app).is_empty());
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Visible);
+
+    switch_tab(&mut app, ViewKind::Continent);
+    assert_children_of_planet::<TemperatureMesh>(&mut app, Visibility::Hidden);
+    assert_children_of_planet::<ContinentViewMesh>(&mut app, Visibility::Visible);
+    assert_children_of_planet::<OceanEntity>(&mut app, Visibility::Visible);
+}
+
+/// Vertex count of the mesh of the first entity with the marker `M`
+fn vertex_count<M: Component>(app: &mut App) -> usize {
+    let world = app.world_mut();
+    let mut query = world.query_filtered::<&Mesh3d, With<M>>();
+    let handle = query.iter(world).next().expect("marked entity").0.clone();
+    world.resource::<Assets<Mesh>>().get(&handle).expect("mesh asset").count_vertices()
+}
+
+/// Mesh asset of the first entity with the marker `M`
+fn mesh_id<M: Component>(app: &mut App) -> AssetId<Mesh> {
+    let world = app.world_mut();
+    let mut query = world.query_filtered::<&Mesh3d, With<M>>();
+    query.iter(world).next().expect("marked entity").0.id()
+}
+
+/// Source meshes the overlay entities with the marker `M` were copied from
+fn overlay_sources<M: Component>(app: &mut App) -> Vec<AssetId<Mesh>> {
+    let world = app.world_mut();
+    let mut query = world.query_filtered::<&OverlayMesh, With<M>>();
+    query.iter(world).map(|overlay| overlay.source).collect()
+}
+
+#[test]
+fn test_overlay_follows_terrain_lod() {
+    let mut app = headless_app();
+    switch_tab(&mut app, ViewKind::Temperature);
+    let coarse = vertex_count::<ContinentViewMesh>(&mut app);
+    let coarse_mesh = mesh_id::<ContinentViewMesh>(&mut app);
+    assert!(overlay_sources::<TemperatureMesh>(&mut app).contains(&coarse_mesh));
+
+    // Zooming all the way in shows the finest terrain, the overlay is rebuilt from it
+    let world = app.world_mut();
+    let mut controls = world.query::<&mut PlanetControls>();
+    for mut controls in controls.iter_mut(world) {
+        controls.zoom = controls.min_zoom;
+    }
+    run_frames(&mut app);
+
+    let fine = vertex_count::<ContinentViewMesh>(&mut app);
+    assert!(fine > coarse, "{fine} vertices when zoomed in, {coarse} when zoomed out");
+    let sources = overlay_sources::<TemperatureMesh>(&mut app);
+    assert!(sources.contains(&mesh_id::<ContinentViewMesh>(&mut app)));
+    assert!(!sources.contains(&coarse_mesh));
+    assert_children_of_planet::<TemperatureMesh>(&mut app, Visibility::Visible);
+}